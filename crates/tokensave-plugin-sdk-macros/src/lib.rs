@@ -0,0 +1,107 @@
+//! Procedural macros for `tokensave-plugin-sdk`. Use them through the SDK,
+//! which re-exports them.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields};
+
+/// Exports a `LanguageExtractor` implementation through the tokensave plugin
+/// C ABI.
+///
+/// Put it on the extractor type of a `cdylib` crate. Unit structs are
+/// constructed directly; other types must implement `Default`. A library
+/// holds a single plugin.
+#[proc_macro_attribute]
+pub fn tokensave_plugin(args: TokenStream, item: TokenStream) -> TokenStream {
+    if !args.is_empty() {
+        return syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "#[tokensave_plugin] takes no arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+    let input = parse_macro_input!(item as DeriveInput);
+    let ident = &input.ident;
+    if !input.generics.params.is_empty() {
+        return syn::Error::new_spanned(&input.generics, "plugin extractors cannot be generic")
+            .to_compile_error()
+            .into();
+    }
+    let constructor = match &input.data {
+        Data::Struct(data) if matches!(data.fields, Fields::Unit) => quote!(#ident),
+        _ => quote!(<#ident as ::core::default::Default>::default()),
+    };
+
+    quote! {
+        #input
+
+        const _: () = {
+            use ::std::ffi::c_char;
+            use ::std::sync::OnceLock;
+            use ::tokensave_plugin_sdk::__private;
+            use ::tokensave_plugin_sdk::LanguageExtractor as _;
+
+            fn extractor() -> &'static #ident {
+                static EXTRACTOR: OnceLock<#ident> = OnceLock::new();
+                EXTRACTOR.get_or_init(|| #constructor)
+            }
+
+            /// Returns the plugin API version this library was built for.
+            #[unsafe(no_mangle)]
+            pub extern "C" fn tokensave_plugin_api_version() -> u32 {
+                ::tokensave_plugin_sdk::PLUGIN_API_VERSION
+            }
+
+            /// Returns the extractor's file extensions as a null-terminated
+            /// array of C strings owned by the library.
+            #[unsafe(no_mangle)]
+            pub extern "C" fn tokensave_extensions() -> *const *const c_char {
+                static EXTENSIONS: OnceLock<__private::CStringList> = OnceLock::new();
+                EXTENSIONS
+                    .get_or_init(|| __private::CStringList::new(extractor().extensions()))
+                    .as_ptr()
+            }
+
+            /// Returns the extractor's language name as a C string owned by
+            /// the library.
+            #[unsafe(no_mangle)]
+            pub extern "C" fn tokensave_language_name() -> *const c_char {
+                static NAME: OnceLock<__private::CStringList> = OnceLock::new();
+                NAME.get_or_init(|| __private::CStringList::new(&[extractor().language_name()]))
+                    .first()
+            }
+
+            /// Extracts a file, returning its `ExtractionResult` as JSON, or
+            /// null when the extractor panicked.
+            ///
+            /// # Safety
+            ///
+            /// `file_path` must be a NUL-terminated string and `source` must
+            /// point to `source_len` readable bytes. The result must be
+            /// released with `tokensave_free`.
+            #[unsafe(no_mangle)]
+            pub unsafe extern "C" fn tokensave_extract(
+                file_path: *const c_char,
+                source: *const c_char,
+                source_len: usize,
+            ) -> *const c_char {
+                // SAFETY: forwarded from this function's contract.
+                unsafe { __private::extract(extractor(), file_path, source, source_len) }
+            }
+
+            /// Releases a string returned by `tokensave_extract`.
+            ///
+            /// # Safety
+            ///
+            /// `ptr` must be null or a result of `tokensave_extract` that was
+            /// not released yet.
+            #[unsafe(no_mangle)]
+            pub unsafe extern "C" fn tokensave_free(ptr: *const c_char) {
+                // SAFETY: forwarded from this function's contract.
+                unsafe { __private::free(ptr) }
+            }
+        };
+    }
+    .into()
+}
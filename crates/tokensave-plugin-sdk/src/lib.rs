@@ -0,0 +1,131 @@
+//! SDK for tokensave extractor plugins.
+//!
+//! A plugin is a `cdylib` crate with one [`LanguageExtractor`]
+//! implementation marked with [`tokensave_plugin`], shipped next to a
+//! `plugin.toml` manifest:
+//!
+//! ```toml
+//! [plugin]
+//! name        = "tokensave-elixir"
+//! version     = "1.2.0"
+//! api_version = 1
+//!
+//! [language]
+//! name       = "Elixir"
+//! extensions = [".ex", ".exs"]
+//! ```
+//!
+//! ```ignore
+//! use tokensave_plugin_sdk::types::ExtractionResult;
+//! use tokensave_plugin_sdk::{tokensave_plugin, LanguageExtractor};
+//!
+//! #[tokensave_plugin]
+//! pub struct ElixirExtractor;
+//!
+//! impl LanguageExtractor for ElixirExtractor {
+//!     fn extensions(&self) -> &[&str] { &["ex", "exs"] }
+//!     fn language_name(&self) -> &str { "Elixir" }
+//!     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult { todo!() }
+//! }
+//! ```
+//!
+//! `tokensave plugin install <dir>` copies the manifest and the built library
+//! into `~/.tokensave/plugins`. tokensave loads plugins in its extraction
+//! worker subprocesses only, so a crashing plugin loses one file, not the
+//! index.
+
+pub use tokensave::extraction::ts_provider;
+pub use tokensave::extraction::LanguageExtractor;
+pub use tokensave::plugins::PLUGIN_API_VERSION;
+pub use tokensave::types;
+pub use tokensave_plugin_sdk_macros::tokensave_plugin;
+pub use tree_sitter;
+
+/// Support code for the items `#[tokensave_plugin]` generates.
+#[doc(hidden)]
+pub mod __private {
+    use std::ffi::{c_char, CStr, CString};
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    use crate::LanguageExtractor;
+
+    /// C strings plus the null-terminated array of pointers to them.
+    pub struct CStringList {
+        _strings: Vec<CString>,
+        ptrs: Vec<*const c_char>,
+    }
+
+    // SAFETY: the pointers point into `_strings`, which is never mutated.
+    unsafe impl Send for CStringList {}
+    // SAFETY: as above.
+    unsafe impl Sync for CStringList {}
+
+    impl CStringList {
+        /// Copies `items`, dropping any that contain a NUL byte.
+        pub fn new(items: &[&str]) -> Self {
+            let strings: Vec<CString> =
+                items.iter().filter_map(|s| CString::new(*s).ok()).collect();
+            let ptrs = strings
+                .iter()
+                .map(|s| s.as_ptr())
+                .chain(std::iter::once(std::ptr::null()))
+                .collect();
+            Self {
+                _strings: strings,
+                ptrs,
+            }
+        }
+
+        pub fn as_ptr(&self) -> *const *const c_char {
+            self.ptrs.as_ptr()
+        }
+
+        /// The first string, or null when the list is empty.
+        pub fn first(&self) -> *const c_char {
+            self.ptrs[0]
+        }
+    }
+
+    /// Runs `extractor` and returns its result as an owned JSON C string,
+    /// or null when the arguments are null or the extractor panicked.
+    ///
+    /// # Safety
+    ///
+    /// `file_path` must be null or NUL-terminated; `source` must be null or
+    /// point to `source_len` readable bytes.
+    pub unsafe fn extract(
+        extractor: &dyn LanguageExtractor,
+        file_path: *const c_char,
+        source: *const c_char,
+        source_len: usize,
+    ) -> *const c_char {
+        if file_path.is_null() || source.is_null() {
+            return std::ptr::null();
+        }
+        // SAFETY: guaranteed by the caller.
+        let file_path = unsafe { CStr::from_ptr(file_path) }.to_string_lossy();
+        // SAFETY: guaranteed by the caller.
+        let bytes = unsafe { std::slice::from_raw_parts(source.cast::<u8>(), source_len) };
+        let source = String::from_utf8_lossy(bytes);
+        // A panic must not unwind into the host across the C ABI.
+        let json = catch_unwind(AssertUnwindSafe(|| {
+            serde_json::to_string(&extractor.extract(&file_path, &source))
+        }));
+        match json {
+            Ok(Ok(json)) => CString::new(json).map_or(std::ptr::null(), |s| s.into_raw()),
+            _ => std::ptr::null(),
+        }
+    }
+
+    /// Releases a string returned by [`extract`].
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be null or a not yet released result of [`extract`].
+    pub unsafe fn free(ptr: *const c_char) {
+        if !ptr.is_null() {
+            // SAFETY: `ptr` came from `CString::into_raw` in `extract`.
+            drop(unsafe { CString::from_raw(ptr.cast_mut()) });
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use std::ffi::{c_char, CStr, CString};
+
+use tokensave_plugin_sdk::types::ExtractionResult;
+use tokensave_plugin_sdk::{tokensave_plugin, LanguageExtractor, PLUGIN_API_VERSION};
+
+/// Reports how many lines a file has, or panics on files named `panic.cnt`.
+#[tokensave_plugin]
+struct LineCounter;
+
+impl LanguageExtractor for LineCounter {
+    fn extensions(&self) -> &[&str] {
+        &["cnt", "count"]
+    }
+
+    fn language_name(&self) -> &str {
+        "Counted"
+    }
+
+    fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
+        assert!(file_path != "panic.cnt", "extractor panicked");
+        ExtractionResult {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            unresolved_refs: Vec::new(),
+            signatures: Vec::new(),
+            errors: vec![format!("{file_path}: {} lines", source.lines().count())],
+            duration_ms: 0,
+        }
+    }
+}
+
+// The macro exports these from the test binary; declare them to call them
+// the way the host does.
+extern "C" {
+    fn tokensave_plugin_api_version() -> u32;
+    fn tokensave_extensions() -> *const *const c_char;
+    fn tokensave_language_name() -> *const c_char;
+    fn tokensave_extract(
+        file_path: *const c_char,
+        source: *const c_char,
+        source_len: usize,
+    ) -> *const c_char;
+    fn tokensave_free(ptr: *const c_char);
+}
+
+fn extract(file_path: &str, source: &str) -> Option<ExtractionResult> {
+    let path = CString::new(file_path).unwrap();
+    unsafe {
+        let json = tokensave_extract(path.as_ptr(), source.as_ptr().cast(), source.len());
+        if json.is_null() {
+            return None;
+        }
+        let result = serde_json::from_slice(CStr::from_ptr(json).to_bytes()).unwrap();
+        tokensave_free(json);
+        Some(result)
+    }
+}
+
+#[test]
+fn test_exports_metadata() {
+    unsafe {
+        assert_eq!(tokensave_plugin_api_version(), PLUGIN_API_VERSION);
+        assert_eq!(
+            CStr::from_ptr(tokensave_language_name()).to_str().unwrap(),
+            "Counted"
+        );
+        let mut extensions = Vec::new();
+        let mut ptr = tokensave_extensions();
+        while !(*ptr).is_null() {
+            extensions.push(CStr::from_ptr(*ptr).to_str().unwrap());
+            ptr = ptr.add(1);
+        }
+        assert_eq!(extensions, vec!["cnt", "count"]);
+    }
+}
+
+#[test]
+fn test_extract_returns_json_result() {
+    let result = extract("src/a.cnt", "one\ntwo\nthree").unwrap();
+    assert_eq!(result.errors, vec!["src/a.cnt: 3 lines"]);
+}
+
+#[test]
+fn test_extract_contains_panics() {
+    assert!(extract("panic.cnt", "").is_none());
+}
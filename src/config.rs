@@ -40,6 +40,46 @@ pub struct TokenSaveConfig {
     /// Whether to respect `.gitignore` rules when scanning files.
     #[serde(default)]
     pub git_ignore: bool,
+    /// Extra languages indexed by the generic tags extractor from a
+    /// user-supplied grammar and `tags.scm` query.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<TagsLanguageConfig>,
+    /// Naming conventions linking GraphQL schema fields to the functions
+    /// resolving them, such as `{Type}.resolve_{field}`; see
+    /// [`resolver_edges`](crate::resolution::resolver_edges).
+    #[serde(default = "default_graphql_resolvers")]
+    pub graphql_resolvers: Vec<String>,
+}
+
+fn default_graphql_resolvers() -> Vec<String> {
+    crate::resolution::DEFAULT_RESOLVER_CONVENTIONS
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// A language indexed by the generic tags extractor: a tree-sitter grammar
+/// plus a `tags.scm`-style query whose `@definition.*` and `@reference.*`
+/// captures become nodes and references.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagsLanguageConfig {
+    /// Language name, shown in statistics.
+    pub name: String,
+    /// File extensions of the language, with or without a leading dot.
+    pub extensions: Vec<String>,
+    /// Path of the compiled grammar (a shared library), relative to the
+    /// project root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub library: Option<String>,
+    /// Function the library exports the grammar as. Defaults to
+    /// `tree_sitter_<name>`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    /// Key of a bundled grammar, used instead of `library`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grammar: Option<String>,
+    /// Path of the tags query, relative to the project root.
+    pub tags: String,
 }
 
 impl Default for TokenSaveConfig {
@@ -64,6 +104,8 @@ impl Default for TokenSaveConfig {
             extract_docstrings: true,
             track_call_sites: true,
             git_ignore: false,
+            languages: Vec::new(),
+            graphql_resolvers: default_graphql_resolvers(),
         }
     }
 }
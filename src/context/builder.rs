@@ -7,6 +7,7 @@ use crate::context::ranking::{apply_connectivity_boost, rerank_candidates};
 use crate::db::Database;
 use crate::errors::Result;
 use crate::graph::GraphTraverser;
+use crate::tokensave::TestScopes;
 use crate::types::*;
 
 /// Builds AI-ready context by combining search, graph traversal, and source code extraction.
@@ -249,8 +250,9 @@ impl<'a> ContextBuilder<'a> {
             });
         }
 
-        // --- Re-rank with structural signals (kind, visibility, path) ---
-        rerank_candidates(&mut candidates);
+        // --- Re-rank with structural signals (kind, visibility, path, test code) ---
+        let test_cases = self.db.get_nodes_by_kind(NodeKind::TestCase).await?;
+        rerank_candidates(&mut candidates, &TestScopes::new(&test_cases));
 
         // --- Connectivity boost (batch edge-count query) ---
         let node_ids: Vec<String> = candidates.iter().map(|c| c.node.id.clone()).collect();
@@ -1,3 +1,4 @@
+use crate::tokensave::TestScopes;
 use crate::types::{NodeKind, SearchResult, Visibility};
 
 /// Boost factor based on node kind.
@@ -47,8 +48,9 @@ pub fn visibility_boost(visibility: &Visibility) -> f64 {
     }
 }
 
-/// Boost factor based on file path.
-pub fn path_boost(file_path: &str) -> f64 {
+/// Boost factor based on file path, and on whether the node is test code
+/// (see [`TestScopes`]).
+pub fn path_boost(file_path: &str, is_test: bool) -> f64 {
     if file_path.contains("tests/fixtures/")
         || file_path.contains("test/fixtures/")
         || file_path.contains("testdata/")
@@ -56,13 +58,7 @@ pub fn path_boost(file_path: &str) -> f64 {
     {
         return 0.1;
     }
-    if file_path.starts_with("tests/")
-        || file_path.starts_with("test/")
-        || file_path.contains("_test.")
-        || file_path.contains(".test.")
-        || file_path.contains("_spec.")
-        || file_path.contains(".spec.")
-    {
+    if is_test {
         return 0.4;
     }
     1.0
@@ -88,11 +84,11 @@ pub fn apply_connectivity_boost<S: std::hash::BuildHasher>(
 }
 
 /// Re-ranks search result candidates using structural signals.
-pub fn rerank_candidates(candidates: &mut [SearchResult]) {
+pub fn rerank_candidates(candidates: &mut [SearchResult], tests: &TestScopes) {
     for candidate in candidates.iter_mut() {
         let boost = kind_boost(&candidate.node.kind)
             * visibility_boost(&candidate.node.visibility)
-            * path_boost(&candidate.node.file_path);
+            * path_boost(&candidate.node.file_path, tests.contains(&candidate.node));
         candidate.score *= boost;
     }
     candidates.sort_by(|a, b| {
@@ -143,7 +139,7 @@ mod tests {
             make_result(NodeKind::Field, Visibility::Pub, "src/lib.rs", 10.0),
             make_result(NodeKind::Function, Visibility::Pub, "src/lib.rs", 10.0),
         ];
-        rerank_candidates(&mut candidates);
+        rerank_candidates(&mut candidates, &TestScopes::default());
         assert_eq!(candidates[0].node.kind, NodeKind::Function);
     }
 
@@ -153,7 +149,7 @@ mod tests {
             make_result(NodeKind::Function, Visibility::Private, "src/lib.rs", 10.0),
             make_result(NodeKind::Function, Visibility::Pub, "src/lib.rs", 10.0),
         ];
-        rerank_candidates(&mut candidates);
+        rerank_candidates(&mut candidates, &TestScopes::default());
         assert_eq!(candidates[0].node.visibility, Visibility::Pub);
     }
 
@@ -168,12 +164,12 @@ mod tests {
             ),
             make_result(NodeKind::Function, Visibility::Pub, "src/logging.rs", 5.0),
         ];
-        rerank_candidates(&mut candidates);
+        rerank_candidates(&mut candidates, &TestScopes::default());
         assert_eq!(candidates[0].node.file_path, "src/logging.rs");
     }
 
     #[test]
-    fn test_test_files_penalized_vs_source() {
+    fn test_test_code_penalized_vs_source() {
         let mut candidates = vec![
             make_result(
                 NodeKind::Function,
@@ -183,7 +179,10 @@ mod tests {
             ),
             make_result(NodeKind::Function, Visibility::Pub, "src/sync.rs", 10.0),
         ];
-        rerank_candidates(&mut candidates);
+        let mut suite = candidates[0].node.clone();
+        suite.kind = NodeKind::TestCase;
+        suite.end_line = 50;
+        rerank_candidates(&mut candidates, &TestScopes::new(&[suite]));
         assert_eq!(candidates[0].node.file_path, "src/sync.rs");
     }
 
@@ -193,7 +192,7 @@ mod tests {
             make_result(NodeKind::Function, Visibility::Pub, "src/a.rs", 10.0),
             make_result(NodeKind::Function, Visibility::Pub, "src/b.rs", 5.0),
         ];
-        rerank_candidates(&mut candidates);
+        rerank_candidates(&mut candidates, &TestScopes::default());
         assert_eq!(candidates[0].node.file_path, "src/a.rs");
         assert_eq!(candidates[1].node.file_path, "src/b.rs");
     }
@@ -222,11 +221,11 @@ mod tests {
 
     #[test]
     fn test_path_boost_values() {
-        assert_eq!(path_boost("src/lib.rs"), 1.0);
-        assert_eq!(path_boost("tests/fixtures/sample.m"), 0.1);
-        assert_eq!(path_boost("tests/sync_test.rs"), 0.4);
-        assert_eq!(path_boost("test/fixtures/foo.js"), 0.1);
-        assert_eq!(path_boost("src/components/Button.test.tsx"), 0.4);
+        assert_eq!(path_boost("src/lib.rs", false), 1.0);
+        assert_eq!(path_boost("tests/fixtures/sample.m", false), 0.1);
+        assert_eq!(path_boost("tests/sync_test.rs", true), 0.4);
+        assert_eq!(path_boost("test/fixtures/foo.js", true), 0.1);
+        assert_eq!(path_boost("src/lib.rs", true), 0.4);
     }
 
     #[test]
@@ -235,7 +234,7 @@ mod tests {
             make_result(NodeKind::Function, Visibility::Pub, "src/a.rs", 10.0),
             make_result(NodeKind::Function, Visibility::Pub, "src/b.rs", 10.0),
         ];
-        rerank_candidates(&mut candidates);
+        rerank_candidates(&mut candidates, &TestScopes::default());
         let base_score = candidates[0].score;
         assert_eq!(candidates[1].score, base_score, "same base score");
 
@@ -252,6 +252,10 @@ impl Database {
              DROP INDEX IF EXISTS idx_unresolved_refs_from_node_id;
              DROP INDEX IF EXISTS idx_unresolved_refs_reference_name;
              DROP INDEX IF EXISTS idx_unresolved_refs_file_path;
+             DROP INDEX IF EXISTS idx_unresolved_refs_name_key;
+             DROP INDEX IF EXISTS idx_unresolved_refs_target_node_id;
+             DROP INDEX IF EXISTS idx_params_type_name;
+             DROP INDEX IF EXISTS idx_return_types_type_name;
              DROP TRIGGER IF EXISTS nodes_fts_insert;
              DROP TRIGGER IF EXISTS nodes_fts_delete;
              DROP TRIGGER IF EXISTS nodes_fts_update;
@@ -281,6 +285,10 @@ impl Database {
              CREATE INDEX IF NOT EXISTS idx_unresolved_refs_from_node_id ON unresolved_refs(from_node_id);
              CREATE INDEX IF NOT EXISTS idx_unresolved_refs_reference_name ON unresolved_refs(reference_name);
              CREATE INDEX IF NOT EXISTS idx_unresolved_refs_file_path ON unresolved_refs(file_path);
+             CREATE INDEX IF NOT EXISTS idx_unresolved_refs_name_key ON unresolved_refs(name_key);
+             CREATE INDEX IF NOT EXISTS idx_unresolved_refs_target_node_id ON unresolved_refs(target_node_id);
+             CREATE INDEX IF NOT EXISTS idx_params_type_name ON params(type_name);
+             CREATE INDEX IF NOT EXISTS idx_return_types_type_name ON return_types(type_name);
              CREATE TRIGGER IF NOT EXISTS nodes_fts_insert AFTER INSERT ON nodes BEGIN
                  INSERT INTO nodes_fts(rowid, name, qualified_name, docstring, signature)
                  VALUES (NEW.rowid, NEW.name, NEW.qualified_name, NEW.docstring, NEW.signature);
@@ -15,7 +15,7 @@ use crate::errors::{Result, TokenSaveError};
 
 /// The highest migration version defined in this file. Bump this and add a
 /// new entry to `run_migration` whenever the schema changes.
-const LATEST_VERSION: u32 = 6;
+const LATEST_VERSION: u32 = 14;
 
 /// Reads the current schema version from `PRAGMA user_version`.
 async fn get_version(conn: &Connection) -> Result<u32> {
@@ -57,7 +57,7 @@ async fn set_version(conn: &Connection, version: u32) -> Result<()> {
 }
 
 /// Creates the complete latest schema from scratch for a brand-new database.
-/// This avoids running v0→v1→…→v6 migrations sequentially.
+/// This avoids running v0→v1→…→v7 migrations sequentially.
 pub async fn create_schema(conn: &Connection) -> Result<()> {
     conn.execute_batch(
         "CREATE TABLE IF NOT EXISTS nodes (
@@ -90,6 +90,9 @@ pub async fn create_schema(conn: &Connection) -> Result<()> {
             target TEXT NOT NULL,
             kind TEXT NOT NULL,
             line INTEGER,
+            strategy TEXT,
+            confidence REAL,
+            candidates INTEGER,
             FOREIGN KEY (source) REFERENCES nodes(id) ON DELETE CASCADE,
             FOREIGN KEY (target) REFERENCES nodes(id) ON DELETE CASCADE
         );
@@ -111,6 +114,12 @@ pub async fn create_schema(conn: &Connection) -> Result<()> {
             line INTEGER NOT NULL,
             col INTEGER NOT NULL,
             file_path TEXT NOT NULL,
+            name_key TEXT NOT NULL DEFAULT '',
+            target_node_id TEXT,
+            receiver_type TEXT,
+            abi TEXT,
+            http_method TEXT,
+            sql INTEGER NOT NULL DEFAULT 0,
             FOREIGN KEY (from_node_id) REFERENCES nodes(id) ON DELETE CASCADE
         );
 
@@ -127,6 +136,25 @@ pub async fn create_schema(conn: &Connection) -> Result<()> {
             value TEXT NOT NULL
         );
 
+        CREATE TABLE IF NOT EXISTS params (
+            node_id TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            type_text TEXT,
+            type_name TEXT,
+            default_value TEXT,
+            optional INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (node_id, position),
+            FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS return_types (
+            node_id TEXT PRIMARY KEY,
+            type_text TEXT NOT NULL,
+            type_name TEXT NOT NULL,
+            FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+        );
+
         CREATE VIRTUAL TABLE IF NOT EXISTS nodes_fts USING fts5(
             name, qualified_name, docstring, signature,
             content='nodes', content_rowid='rowid'
@@ -164,8 +192,13 @@ pub async fn create_schema(conn: &Connection) -> Result<()> {
         CREATE INDEX IF NOT EXISTS idx_unresolved_refs_from_node_id ON unresolved_refs(from_node_id);
         CREATE INDEX IF NOT EXISTS idx_unresolved_refs_reference_name ON unresolved_refs(reference_name);
         CREATE INDEX IF NOT EXISTS idx_unresolved_refs_file_path ON unresolved_refs(file_path);
+        CREATE INDEX IF NOT EXISTS idx_unresolved_refs_name_key ON unresolved_refs(name_key);
+        CREATE INDEX IF NOT EXISTS idx_unresolved_refs_target_node_id ON unresolved_refs(target_node_id);
+
+        CREATE INDEX IF NOT EXISTS idx_nodes_lower_name ON nodes(lower(name));
 
-        CREATE INDEX IF NOT EXISTS idx_nodes_lower_name ON nodes(lower(name));",
+        CREATE INDEX IF NOT EXISTS idx_params_type_name ON params(type_name);
+        CREATE INDEX IF NOT EXISTS idx_return_types_type_name ON return_types(type_name);",
     )
     .await
     .map_err(|e| TokenSaveError::Database {
@@ -250,6 +283,14 @@ async fn run_migration(conn: &Connection, version: u32) -> Result<()> {
         4 => migrate_v4(conn).await,
         5 => migrate_v5(conn).await,
         6 => migrate_v6(conn).await,
+        7 => migrate_v7(conn).await,
+        8 => migrate_v8(conn).await,
+        9 => migrate_v9(conn).await,
+        10 => migrate_v10(conn).await,
+        11 => migrate_v11(conn).await,
+        12 => migrate_v12(conn).await,
+        13 => migrate_v13(conn).await,
+        14 => migrate_v14(conn).await,
         _ => Err(TokenSaveError::Database {
             message: format!("unknown migration version: {version}"),
             operation: "run_migration".to_string(),
@@ -522,3 +563,194 @@ async fn migrate_v6(conn: &Connection) -> Result<()> {
 
     Ok(())
 }
+
+// ---------------------------------------------------------------------------
+// Migration V7: persisted reference targets and name keys
+// ---------------------------------------------------------------------------
+
+/// Adds `name_key` and `target_node_id` columns to `unresolved_refs` so that
+/// incremental syncs can find the references affected by a change without
+/// re-resolving every reference in the graph. Existing rows are repopulated
+/// by the full re-index that follows any migration.
+async fn migrate_v7(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE unresolved_refs ADD COLUMN name_key TEXT NOT NULL DEFAULT '';
+         ALTER TABLE unresolved_refs ADD COLUMN target_node_id TEXT;
+         CREATE INDEX IF NOT EXISTS idx_unresolved_refs_name_key ON unresolved_refs(name_key);
+         CREATE INDEX IF NOT EXISTS idx_unresolved_refs_target_node_id ON unresolved_refs(target_node_id);",
+    )
+    .await
+    .map_err(|e| TokenSaveError::Database {
+        message: format!("v7: failed to add reference resolution columns: {e}"),
+        operation: "migrate_v7".to_string(),
+    })?;
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Migration V8: receiver types on references
+// ---------------------------------------------------------------------------
+
+/// Adds the `receiver_type` column to `unresolved_refs`, so method calls
+/// re-resolved by an incremental sync keep the declared type of their
+/// receiver.
+async fn migrate_v8(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "ALTER TABLE unresolved_refs ADD COLUMN receiver_type TEXT",
+        (),
+    )
+    .await
+    .map_err(|e| TokenSaveError::Database {
+        message: format!("v8: failed to add receiver_type column: {e}"),
+        operation: "migrate_v8".to_string(),
+    })?;
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Migration V9: edge provenance
+// ---------------------------------------------------------------------------
+
+/// Adds the provenance columns to `edges`: the strategy that resolved the
+/// edge, its confidence, and for ambiguous matches the number of candidates.
+/// Existing rows keep NULLs, which read back as exact edges.
+async fn migrate_v9(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE edges ADD COLUMN strategy TEXT;
+         ALTER TABLE edges ADD COLUMN confidence REAL;
+         ALTER TABLE edges ADD COLUMN candidates INTEGER;",
+    )
+    .await
+    .map_err(|e| TokenSaveError::Database {
+        message: format!("v9: failed to add edge provenance columns: {e}"),
+        operation: "migrate_v9".to_string(),
+    })?;
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Migration V10: stable node IDs
+// ---------------------------------------------------------------------------
+
+/// Node IDs are now derived from qualified names instead of start lines.
+/// Forgets the indexed files so every node is re-extracted under its new
+/// ID: the full re-index that follows any migration rebuilds the graph, and
+/// should it be interrupted, the next sync treats every file as new and
+/// replaces its line-keyed nodes.
+async fn migrate_v10(conn: &Connection) -> Result<()> {
+    conn.execute("DELETE FROM files", ())
+        .await
+        .map_err(|e| TokenSaveError::Database {
+            message: format!("v10: failed to clear indexed files: {e}"),
+            operation: "migrate_v10".to_string(),
+        })?;
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Migration V11: structured signatures
+// ---------------------------------------------------------------------------
+
+/// Adds the `params` and `return_types` tables, which hold the declared
+/// parameters and return type of each callable with their types normalized
+/// for `tokensave_signature_search`. The full re-index that follows the
+/// migration fills them.
+async fn migrate_v11(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS params (
+            node_id TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            type_text TEXT,
+            type_name TEXT,
+            default_value TEXT,
+            optional INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (node_id, position),
+            FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS return_types (
+            node_id TEXT PRIMARY KEY,
+            type_text TEXT NOT NULL,
+            type_name TEXT NOT NULL,
+            FOREIGN KEY (node_id) REFERENCES nodes(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_params_type_name ON params(type_name);
+        CREATE INDEX IF NOT EXISTS idx_return_types_type_name ON return_types(type_name);",
+    )
+    .await
+    .map_err(|e| TokenSaveError::Database {
+        message: format!("v11: failed to create signature tables: {e}"),
+        operation: "migrate_v11".to_string(),
+    })?;
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Migration V12: ABIs on references
+// ---------------------------------------------------------------------------
+
+/// Adds the `abi` column to `unresolved_refs`, which marks references to C
+/// symbols and exports to other languages. Such references used to encode
+/// the ABI in their name (`extern "C"::compress`); the full re-index that
+/// follows the migration stores them anew.
+async fn migrate_v12(conn: &Connection) -> Result<()> {
+    conn.execute("ALTER TABLE unresolved_refs ADD COLUMN abi TEXT", ())
+        .await
+        .map_err(|e| TokenSaveError::Database {
+            message: format!("v12: failed to add abi column: {e}"),
+            operation: "migrate_v12".to_string(),
+        })?;
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Migration V13: HTTP methods on references
+// ---------------------------------------------------------------------------
+
+/// Adds the `http_method` column to `unresolved_refs`, which holds the
+/// method of an HTTP request whose name is the path it requests. Requests
+/// used to be named after both (`GET /users/{}`); the full re-index that
+/// follows the migration stores them anew.
+async fn migrate_v13(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "ALTER TABLE unresolved_refs ADD COLUMN http_method TEXT",
+        (),
+    )
+    .await
+    .map_err(|e| TokenSaveError::Database {
+        message: format!("v13: failed to add http_method column: {e}"),
+        operation: "migrate_v13".to_string(),
+    })?;
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Migration V14: SQL references
+// ---------------------------------------------------------------------------
+
+/// Adds the `sql` column to `unresolved_refs`, which marks references from
+/// SQL statements to the tables, views and columns they name. Such
+/// references used to carry a `SQL ` prefix in their name (`SQL users.id`);
+/// the full re-index that follows the migration stores them anew.
+async fn migrate_v14(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "ALTER TABLE unresolved_refs ADD COLUMN sql INTEGER NOT NULL DEFAULT 0",
+        (),
+    )
+    .await
+    .map_err(|e| TokenSaveError::Database {
+        message: format!("v14: failed to add sql column: {e}"),
+        operation: "migrate_v14".to_string(),
+    })?;
+
+    Ok(())
+}
@@ -82,16 +82,29 @@ fn get_opt_string_lossy(
 
 /// Maps a row from the `edges` table to an `Edge`.
 ///
-/// Expected column order: source(0), target(1), kind(2), line(3).
+/// Expected column order: source(0), target(1), kind(2), line(3),
+/// strategy(4), confidence(5), candidates(6).
 fn row_to_edge(row: &libsql::Row) -> std::result::Result<Edge, libsql::Error> {
     let kind_str = row.get::<String>(2)?;
     let line = row.get::<Option<u32>>(3)?;
+    let strategy = row.get::<Option<String>>(4)?;
+    let confidence = row.get::<Option<f64>>(5)?;
+    let candidates = row.get::<Option<u32>>(6)?;
+
+    let provenance = strategy
+        .as_deref()
+        .and_then(|s| ResolutionStrategy::parse(s, candidates.unwrap_or(1)))
+        .map(|strategy| EdgeProvenance {
+            strategy,
+            confidence: confidence.unwrap_or(1.0),
+        });
 
     Ok(Edge {
         source: row.get::<String>(0)?,
         target: row.get::<String>(1)?,
         kind: EdgeKind::from_str(&kind_str).unwrap_or(EdgeKind::Uses),
         line,
+        provenance,
     })
 }
 
@@ -113,7 +126,8 @@ fn row_to_file(row: &libsql::Row) -> std::result::Result<FileRecord, libsql::Err
 /// Maps a row from the `unresolved_refs` table to an `UnresolvedRef`.
 ///
 /// Expected column order: `from_node_id(0)`, `reference_name(1)`,
-/// `reference_kind(2)`, line(3), col(4), `file_path(5)`.
+/// `reference_kind(2)`, line(3), col(4), `file_path(5)`, `receiver_type(6)`,
+/// abi(7), `http_method(8)`, sql(9).
 fn row_to_unresolved_ref(row: &libsql::Row) -> std::result::Result<UnresolvedRef, libsql::Error> {
     let kind_str = row.get::<String>(2)?;
 
@@ -124,9 +138,77 @@ fn row_to_unresolved_ref(row: &libsql::Row) -> std::result::Result<UnresolvedRef
         line: row.get::<u32>(3)?,
         column: row.get::<u32>(4)?,
         file_path: row.get::<String>(5)?,
+        receiver_type: row.get::<Option<String>>(6)?,
+        abi: row.get::<Option<String>>(7)?,
+        http_method: row.get::<Option<String>>(8)?,
+        sql: row.get::<i64>(9)? != 0,
     })
 }
 
+/// Maps a row from the `unresolved_refs` table to a reference paired with
+/// its currently resolved target.
+///
+/// Expected column order: the ten columns of `row_to_unresolved_ref`
+/// followed by `target_node_id(10)`.
+fn row_to_ref_with_target(
+    row: &libsql::Row,
+) -> std::result::Result<(UnresolvedRef, Option<String>), libsql::Error> {
+    Ok((row_to_unresolved_ref(row)?, row.get::<Option<String>>(10)?))
+}
+
+/// Maps a row from the `params` table to its node ID and `Param`.
+///
+/// Expected column order: `node_id(0)`, position(1), name(2), `type_text(3)`,
+/// `type_name(4)`, `default_value(5)`, optional(6).
+fn row_to_param(row: &libsql::Row) -> std::result::Result<(String, Param), libsql::Error> {
+    let declared_type = match (get_opt_string_lossy(row, 3)?, get_opt_string_lossy(row, 4)?) {
+        (Some(text), Some(name)) => Some(TypeSpec { text, name }),
+        _ => None,
+    };
+    Ok((
+        row.get::<String>(0)?,
+        Param {
+            name: get_string_lossy(row, 2)?,
+            declared_type,
+            default_value: get_opt_string_lossy(row, 5)?,
+            optional: row.get::<i64>(6)? != 0,
+        },
+    ))
+}
+
+/// Maps a row from the `return_types` table to its node ID and `TypeSpec`.
+///
+/// Expected column order: `node_id(0)`, `type_text(1)`, `type_name(2)`.
+fn row_to_return_type(row: &libsql::Row) -> std::result::Result<(String, TypeSpec), libsql::Error> {
+    Ok((
+        row.get::<String>(0)?,
+        TypeSpec {
+            text: get_string_lossy(row, 1)?,
+            name: get_string_lossy(row, 2)?,
+        },
+    ))
+}
+
+/// A signature for `node_id` with nothing recorded yet.
+fn empty_signature(node_id: String) -> Signature {
+    Signature {
+        node_id,
+        params: Vec::new(),
+        return_type: None,
+    }
+}
+
+/// Maximum number of bound parameters per `IN (...)` batch query.
+const IN_BATCH: usize = 500;
+
+/// Builds `?1, ?2, …, ?n` for an `IN (...)` clause.
+fn in_placeholders(n: usize) -> String {
+    (1..=n)
+        .map(|i| format!("?{i}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 // ---------------------------------------------------------------------------
 // Node operations
 // ---------------------------------------------------------------------------
@@ -250,7 +332,10 @@ impl Database {
 
         // Edges
         for chunk in edges.chunks(500) {
-            sql.push_str("INSERT OR IGNORE INTO edges (source,target,kind,line) VALUES ");
+            sql.push_str(
+                "INSERT OR IGNORE INTO edges \
+                 (source,target,kind,line,strategy,confidence,candidates) VALUES ",
+            );
             for (i, edge) in chunk.iter().enumerate() {
                 if i > 0 {
                     sql.push(',');
@@ -266,6 +351,17 @@ impl Database {
                     Some(l) => push_int(&mut sql, i64::from(l)),
                     None => sql.push_str("NULL"),
                 }
+                sql.push(',');
+                match &edge.provenance {
+                    Some(p) => {
+                        push_quoted(&mut sql, p.strategy.as_str());
+                        sql.push(',');
+                        sql.push_str(&p.confidence.to_string());
+                        sql.push(',');
+                        push_int(&mut sql, i64::from(p.strategy.candidates()));
+                    }
+                    None => sql.push_str("NULL,NULL,NULL"),
+                }
                 sql.push(')');
             }
             sql.push_str(";\n");
@@ -444,6 +540,62 @@ impl Database {
         collect_rows(&mut rows, row_to_node, "get_nodes_by_ids").await
     }
 
+    /// Returns all nodes whose `name` is one of `names`, using the name index.
+    pub async fn get_nodes_by_names(&self, names: &[String]) -> Result<Vec<Node>> {
+        let mut nodes = Vec::new();
+        for chunk in names.chunks(IN_BATCH) {
+            let sql = format!(
+                "SELECT id, kind, name, qualified_name, file_path,
+                        start_line, end_line, start_column, end_column,
+                        docstring, signature, visibility, is_async, branches, loops, returns, max_nesting, unsafe_blocks, unchecked_calls, assertions, updated_at
+                 FROM nodes WHERE name IN ({})",
+                in_placeholders(chunk.len())
+            );
+            let param_values: Vec<libsql::Value> = chunk
+                .iter()
+                .map(|n| libsql::Value::Text(n.clone()))
+                .collect();
+            let mut rows = self
+                .conn()
+                .query(&sql, libsql::params_from_iter(param_values))
+                .await
+                .map_err(|e| TokenSaveError::Database {
+                    message: format!("failed to query nodes by name: {e}"),
+                    operation: "get_nodes_by_names".to_string(),
+                })?;
+            nodes.extend(collect_rows(&mut rows, row_to_node, "get_nodes_by_names").await?);
+        }
+        Ok(nodes)
+    }
+
+    /// Returns the `use` / `include` nodes declared in any of `file_paths`.
+    pub async fn get_import_nodes_by_files(&self, file_paths: &[String]) -> Result<Vec<Node>> {
+        let mut nodes = Vec::new();
+        for chunk in file_paths.chunks(IN_BATCH) {
+            let sql = format!(
+                "SELECT id, kind, name, qualified_name, file_path,
+                        start_line, end_line, start_column, end_column,
+                        docstring, signature, visibility, is_async, branches, loops, returns, max_nesting, unsafe_blocks, unchecked_calls, assertions, updated_at
+                 FROM nodes WHERE kind IN ('use', 'include') AND file_path IN ({})",
+                in_placeholders(chunk.len())
+            );
+            let param_values: Vec<libsql::Value> = chunk
+                .iter()
+                .map(|p| libsql::Value::Text(p.clone()))
+                .collect();
+            let mut rows = self
+                .conn()
+                .query(&sql, libsql::params_from_iter(param_values))
+                .await
+                .map_err(|e| TokenSaveError::Database {
+                    message: format!("failed to query import nodes: {e}"),
+                    operation: "get_import_nodes_by_files".to_string(),
+                })?;
+            nodes.extend(collect_rows(&mut rows, row_to_node, "get_import_nodes_by_files").await?);
+        }
+        Ok(nodes)
+    }
+
     /// Returns all nodes for a given file, ordered by start line.
     pub async fn get_nodes_by_file(&self, file_path: &str) -> Result<Vec<Node>> {
         let mut rows = self
@@ -504,7 +656,8 @@ impl Database {
         collect_rows(&mut rows, row_to_node, "get_all_nodes").await
     }
 
-    /// Deletes all nodes (and cascading edges, unresolved refs, vectors) for a file.
+    /// Deletes all nodes (and cascading edges, unresolved refs, vectors and
+    /// signatures) for a file.
     pub async fn delete_nodes_by_file(&self, file_path: &str) -> Result<()> {
         debug_assert!(
             !file_path.is_empty(),
@@ -584,6 +737,26 @@ impl Database {
                 message: format!("failed to delete vectors: {e}"),
                 operation: "delete_nodes_by_file".to_string(),
             })?;
+
+            tx.execute(
+                "DELETE FROM params WHERE node_id = ?1",
+                params![id.as_str()],
+            )
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to delete params: {e}"),
+                operation: "delete_nodes_by_file".to_string(),
+            })?;
+
+            tx.execute(
+                "DELETE FROM return_types WHERE node_id = ?1",
+                params![id.as_str()],
+            )
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to delete return types: {e}"),
+                operation: "delete_nodes_by_file".to_string(),
+            })?;
         }
 
         tx.execute("DELETE FROM nodes WHERE file_path = ?1", params![file_path])
@@ -598,6 +771,114 @@ impl Database {
             operation: "delete_nodes_by_file".to_string(),
         })
     }
+
+    /// Deletes all nodes of `kind`, with their edges, unresolved refs,
+    /// vectors and signatures.
+    pub async fn delete_nodes_by_kind(&self, kind: NodeKind) -> Result<()> {
+        let op = "delete_nodes_by_kind";
+        let tx = self
+            .conn()
+            .transaction()
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to begin transaction: {e}"),
+                operation: op.to_string(),
+            })?;
+
+        for (sql, what) in [
+            (
+                "DELETE FROM edges WHERE source IN (SELECT id FROM nodes WHERE kind = ?1)
+                    OR target IN (SELECT id FROM nodes WHERE kind = ?1)",
+                "edges",
+            ),
+            (
+                "DELETE FROM unresolved_refs WHERE from_node_id IN (SELECT id FROM nodes WHERE kind = ?1)",
+                "unresolved refs",
+            ),
+            (
+                "DELETE FROM vectors WHERE node_id IN (SELECT id FROM nodes WHERE kind = ?1)",
+                "vectors",
+            ),
+            (
+                "DELETE FROM params WHERE node_id IN (SELECT id FROM nodes WHERE kind = ?1)",
+                "params",
+            ),
+            (
+                "DELETE FROM return_types WHERE node_id IN (SELECT id FROM nodes WHERE kind = ?1)",
+                "return types",
+            ),
+            ("DELETE FROM nodes WHERE kind = ?1", "nodes"),
+        ] {
+            tx.execute(sql, params![kind.as_str()])
+                .await
+                .map_err(|e| TokenSaveError::Database {
+                    message: format!("failed to delete {what}: {e}"),
+                    operation: op.to_string(),
+                })?;
+        }
+
+        tx.commit().await.map_err(|e| TokenSaveError::Database {
+            message: format!("failed to commit transaction: {e}"),
+            operation: op.to_string(),
+        })
+    }
+
+    /// Deletes the external symbols no reference points at any more, then
+    /// the external packages left without symbols.
+    pub async fn delete_unreferenced_external_nodes(&self) -> Result<()> {
+        let op = "delete_unreferenced_external_nodes";
+        let tx = self
+            .conn()
+            .transaction()
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to begin transaction: {e}"),
+                operation: op.to_string(),
+            })?;
+
+        for (sql, what) in [
+            (
+                "DELETE FROM edges WHERE kind = 'contains' AND target IN (
+                    SELECT n.id FROM nodes n WHERE n.kind = 'external_symbol'
+                    AND NOT EXISTS (SELECT 1 FROM edges e WHERE e.target = n.id AND e.kind != 'contains'))",
+                "package edges",
+            ),
+            (
+                "DELETE FROM vectors WHERE node_id IN (
+                    SELECT n.id FROM nodes n WHERE n.kind = 'external_symbol'
+                    AND NOT EXISTS (SELECT 1 FROM edges e WHERE e.target = n.id))",
+                "symbol vectors",
+            ),
+            (
+                "DELETE FROM nodes WHERE kind = 'external_symbol'
+                    AND NOT EXISTS (SELECT 1 FROM edges e WHERE e.target = nodes.id)",
+                "symbols",
+            ),
+            (
+                "DELETE FROM vectors WHERE node_id IN (
+                    SELECT n.id FROM nodes n WHERE n.kind = 'external_package'
+                    AND NOT EXISTS (SELECT 1 FROM edges e WHERE e.source = n.id))",
+                "package vectors",
+            ),
+            (
+                "DELETE FROM nodes WHERE kind = 'external_package'
+                    AND NOT EXISTS (SELECT 1 FROM edges e WHERE e.source = nodes.id)",
+                "packages",
+            ),
+        ] {
+            tx.execute(sql, ())
+                .await
+                .map_err(|e| TokenSaveError::Database {
+                    message: format!("failed to delete unreferenced external {what}: {e}"),
+                    operation: op.to_string(),
+                })?;
+        }
+
+        tx.commit().await.map_err(|e| TokenSaveError::Database {
+            message: format!("failed to commit transaction: {e}"),
+            operation: op.to_string(),
+        })
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -609,12 +890,19 @@ impl Database {
     pub async fn insert_edge(&self, edge: &Edge) -> Result<()> {
         self.conn()
             .execute(
-                "INSERT OR IGNORE INTO edges (source, target, kind, line) VALUES (?1, ?2, ?3, ?4)",
+                "INSERT OR IGNORE INTO edges
+                 (source, target, kind, line, strategy, confidence, candidates)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
                 params![
                     edge.source.as_str(),
                     edge.target.as_str(),
                     edge.kind.as_str(),
-                    edge.line.map(i64::from)
+                    edge.line.map(i64::from),
+                    edge.provenance.as_ref().map(|p| p.strategy.as_str()),
+                    edge.provenance.as_ref().map(|p| p.confidence),
+                    edge.provenance
+                        .as_ref()
+                        .map(|p| i64::from(p.strategy.candidates()))
                 ],
             )
             .await
@@ -641,7 +929,11 @@ impl Database {
 
         let stmt = self
             .conn()
-            .prepare("INSERT OR IGNORE INTO edges (source,target,kind,line) VALUES (?1,?2,?3,?4)")
+            .prepare(
+                "INSERT OR IGNORE INTO edges \
+                 (source,target,kind,line,strategy,confidence,candidates) \
+                 VALUES (?1,?2,?3,?4,?5,?6,?7)",
+            )
             .await
             .map_err(|e| TokenSaveError::Database {
                 message: format!("failed to prepare: {e}"),
@@ -654,6 +946,11 @@ impl Database {
                 edge.target.as_str(),
                 edge.kind.as_str(),
                 edge.line.map(i64::from),
+                edge.provenance.as_ref().map(|p| p.strategy.as_str()),
+                edge.provenance.as_ref().map(|p| p.confidence),
+                edge.provenance
+                    .as_ref()
+                    .map(|p| i64::from(p.strategy.candidates())),
             ])
             .await
             .map_err(|e| TokenSaveError::Database {
@@ -685,7 +982,7 @@ impl Database {
             let mut rows = self
                 .conn()
                 .query(
-                    "SELECT source, target, kind, line FROM edges WHERE source = ?1",
+                    "SELECT source, target, kind, line, strategy, confidence, candidates FROM edges WHERE source = ?1",
                     params![source_id],
                 )
                 .await
@@ -702,7 +999,7 @@ impl Database {
                 .map(|(i, _)| format!("?{}", i + 2))
                 .collect();
             let sql = format!(
-                "SELECT source, target, kind, line FROM edges WHERE source = ?1 AND kind IN ({})",
+                "SELECT source, target, kind, line, strategy, confidence, candidates FROM edges WHERE source = ?1 AND kind IN ({})",
                 placeholders.join(", ")
             );
 
@@ -737,7 +1034,7 @@ impl Database {
             let mut rows = self
                 .conn()
                 .query(
-                    "SELECT source, target, kind, line FROM edges WHERE target = ?1",
+                    "SELECT source, target, kind, line, strategy, confidence, candidates FROM edges WHERE target = ?1",
                     params![target_id],
                 )
                 .await
@@ -754,7 +1051,7 @@ impl Database {
                 .map(|(i, _)| format!("?{}", i + 2))
                 .collect();
             let sql = format!(
-                "SELECT source, target, kind, line FROM edges WHERE target = ?1 AND kind IN ({})",
+                "SELECT source, target, kind, line, strategy, confidence, candidates FROM edges WHERE target = ?1 AND kind IN ({})",
                 placeholders.join(", ")
             );
 
@@ -943,6 +1240,7 @@ impl Database {
     /// file; `fan_out` counts how many files each file depends on.
     ///
     /// Only `calls`, `uses`, `implements`, and `extends` edges are considered.
+    /// Edges into external dependencies are not.
     pub async fn get_file_coupling(
         &self,
         fan_in: bool,
@@ -967,6 +1265,7 @@ impl Database {
              JOIN nodes n_tgt ON e.target = n_tgt.id
              WHERE e.kind IN ('calls', 'uses', 'implements', 'extends')
                AND n_src.file_path != n_tgt.file_path
+               AND n_tgt.kind != 'external_symbol'
                {path_filter}
              GROUP BY {group_alias}.file_path
              ORDER BY coupling DESC
@@ -1069,6 +1368,75 @@ impl Database {
         Ok(items)
     }
 
+    /// Returns every reference from project code into an external dependency
+    /// as (package name, symbol qualified name, referencing node, line),
+    /// ordered by package, symbol and location.
+    ///
+    /// If `path_prefix` is provided, only references from files under that
+    /// path are included.
+    pub async fn get_external_usages(
+        &self,
+        path_prefix: Option<&str>,
+    ) -> Result<Vec<(String, String, Node, Option<u32>)>> {
+        let path_filter = match path_prefix {
+            Some(prefix) => format!("AND n.file_path LIKE '{prefix}%'"),
+            None => String::new(),
+        };
+
+        let sql = format!(
+            "SELECT n.id, n.kind, n.name, n.qualified_name, n.file_path,
+                    n.start_line, n.end_line, n.start_column, n.end_column,
+                    n.docstring, n.signature, n.visibility, n.is_async, n.branches, n.loops, n.returns, n.max_nesting, n.unsafe_blocks, n.unchecked_calls, n.assertions, n.updated_at,
+                    p.name, s.qualified_name, e.line
+             FROM nodes p
+             JOIN edges c ON c.source = p.id AND c.kind = 'contains'
+             JOIN nodes s ON s.id = c.target AND s.kind = 'external_symbol'
+             JOIN edges e ON e.target = s.id AND e.kind != 'contains'
+             JOIN nodes n ON n.id = e.source
+             WHERE p.kind = 'external_package'
+               {path_filter}
+             ORDER BY p.name, s.qualified_name, n.file_path, e.line"
+        );
+
+        let op = "get_external_usages";
+        let mut rows = self
+            .conn()
+            .query(&sql, ())
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to query external usages: {e}"),
+                operation: op.to_string(),
+            })?;
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.next().await.map_err(|e| TokenSaveError::Database {
+            message: format!("failed to read row: {e}"),
+            operation: op.to_string(),
+        })? {
+            let node = row_to_node(&row).map_err(|e| TokenSaveError::Database {
+                message: format!("failed to map row: {e}"),
+                operation: op.to_string(),
+            })?;
+            let package = get_string_lossy(&row, 21).map_err(|e| TokenSaveError::Database {
+                message: format!("failed to read package name: {e}"),
+                operation: op.to_string(),
+            })?;
+            let symbol = get_string_lossy(&row, 22).map_err(|e| TokenSaveError::Database {
+                message: format!("failed to read symbol name: {e}"),
+                operation: op.to_string(),
+            })?;
+            let line = row
+                .get::<Option<u32>>(23)
+                .map_err(|e| TokenSaveError::Database {
+                    message: format!("failed to read line: {e}"),
+                    operation: op.to_string(),
+                })?;
+            items.push((package, symbol, node, line));
+        }
+
+        Ok(items)
+    }
+
     /// Returns node kind counts grouped by file or directory prefix.
     ///
     /// If `path_prefix` is provided, only files under that path are included.
@@ -1392,7 +1760,10 @@ impl Database {
     pub async fn get_all_edges(&self) -> Result<Vec<Edge>> {
         let mut rows = self
             .conn()
-            .query("SELECT source, target, kind, line FROM edges", ())
+            .query(
+                "SELECT source, target, kind, line, strategy, confidence, candidates FROM edges",
+                (),
+            )
             .await
             .map_err(|e| TokenSaveError::Database {
                 message: format!("failed to query all edges: {e}"),
@@ -1402,6 +1773,58 @@ impl Database {
         collect_rows(&mut rows, row_to_edge, "get_all_edges").await
     }
 
+    /// Deletes the given edges (matched on source, target, kind and line)
+    /// inside a single transaction.
+    pub async fn delete_edges(&self, edges: &[Edge]) -> Result<()> {
+        if edges.is_empty() {
+            return Ok(());
+        }
+
+        self.conn()
+            .execute("BEGIN", ())
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to begin: {e}"),
+                operation: "delete_edges".to_string(),
+            })?;
+
+        let stmt = self
+            .conn()
+            .prepare(
+                "DELETE FROM edges WHERE source = ?1 AND target = ?2 AND kind = ?3
+                 AND COALESCE(line, -1) = COALESCE(?4, -1)",
+            )
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to prepare: {e}"),
+                operation: "delete_edges".to_string(),
+            })?;
+
+        for edge in edges {
+            stmt.execute(params![
+                edge.source.as_str(),
+                edge.target.as_str(),
+                edge.kind.as_str(),
+                edge.line.map(i64::from),
+            ])
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to delete edge: {e}"),
+                operation: "delete_edges".to_string(),
+            })?;
+            stmt.reset();
+        }
+
+        self.conn()
+            .execute("COMMIT", ())
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to commit: {e}"),
+                operation: "delete_edges".to_string(),
+            })?;
+        Ok(())
+    }
+
     /// Deletes all edges originating from a given source node.
     pub async fn delete_edges_by_source(&self, source_id: &str) -> Result<()> {
         self.conn()
@@ -1540,6 +1963,35 @@ impl Database {
         collect_rows(&mut rows, row_to_file, "get_all_files").await
     }
 
+    /// Returns the paths of the files whose path, lowercased and with `-`
+    /// read as `_`, contains any of `needles`.
+    pub async fn get_file_paths_containing(&self, needles: &[String]) -> Result<Vec<String>> {
+        let op = "get_file_paths_containing";
+        let mut paths = Vec::new();
+        for chunk in needles.chunks(IN_BATCH) {
+            let clauses: Vec<String> = (1..=chunk.len())
+                .map(|i| format!("instr(replace(lower(path), '-', '_'), ?{i}) > 0"))
+                .collect();
+            let sql = format!("SELECT path FROM files WHERE {}", clauses.join(" OR "));
+            let param_values: Vec<libsql::Value> = chunk
+                .iter()
+                .map(|n| libsql::Value::Text(n.clone()))
+                .collect();
+            let mut rows = self
+                .conn()
+                .query(&sql, libsql::params_from_iter(param_values))
+                .await
+                .map_err(|e| TokenSaveError::Database {
+                    message: format!("failed to query file paths: {e}"),
+                    operation: op.to_string(),
+                })?;
+            paths.extend(
+                collect_rows(&mut rows, |row: &libsql::Row| row.get::<String>(0), op).await?,
+            );
+        }
+        Ok(paths)
+    }
+
     /// Deletes a file record and cascades to delete its nodes first.
     pub async fn delete_file(&self, path: &str) -> Result<()> {
         self.delete_nodes_by_file(path).await?;
@@ -1564,8 +2016,9 @@ impl Database {
         self.conn()
             .execute(
                 "INSERT INTO unresolved_refs
-                (from_node_id, reference_name, reference_kind, line, col, file_path)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                (from_node_id, reference_name, reference_kind, line, col, file_path, name_key,
+                 receiver_type, abi, http_method, sql)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
                 params![
                     uref.from_node_id.as_str(),
                     uref.reference_name.as_str(),
@@ -1573,6 +2026,11 @@ impl Database {
                     i64::from(uref.line),
                     i64::from(uref.column),
                     uref.file_path.as_str(),
+                    uref.name_key(),
+                    uref.receiver_type.as_deref(),
+                    uref.abi.as_deref(),
+                    uref.http_method.as_deref(),
+                    i64::from(uref.sql),
                 ],
             )
             .await
@@ -1598,7 +2056,7 @@ impl Database {
             })?;
 
         let stmt = self.conn()
-            .prepare("INSERT INTO unresolved_refs (from_node_id,reference_name,reference_kind,line,col,file_path) VALUES (?1,?2,?3,?4,?5,?6)")
+            .prepare("INSERT INTO unresolved_refs (from_node_id,reference_name,reference_kind,line,col,file_path,name_key,receiver_type,abi,http_method,sql) VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10,?11)")
             .await
             .map_err(|e| TokenSaveError::Database {
                 message: format!("failed to prepare: {e}"),
@@ -1613,6 +2071,11 @@ impl Database {
                 i64::from(uref.line),
                 i64::from(uref.column),
                 uref.file_path.as_str(),
+                uref.name_key(),
+                uref.receiver_type.as_deref(),
+                uref.abi.as_deref(),
+                uref.http_method.as_deref(),
+                i64::from(uref.sql),
             ])
             .await
             .map_err(|e| TokenSaveError::Database {
@@ -1632,13 +2095,192 @@ impl Database {
         Ok(())
     }
 
-    /// Returns all unresolved references.
+    /// Inserts every reference of a resolution pass, recording the resolved
+    /// target (or NULL) alongside each one. Used by full indexing, where the
+    /// references are not in the table yet.
+    pub async fn insert_resolution(&self, resolution: &ResolutionResult) -> Result<()> {
+        if resolution.total == 0 {
+            return Ok(());
+        }
+
+        self.conn()
+            .execute("BEGIN", ())
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to begin: {e}"),
+                operation: "insert_resolution".to_string(),
+            })?;
+
+        let stmt = self.conn()
+            .prepare("INSERT INTO unresolved_refs (from_node_id,reference_name,reference_kind,line,col,file_path,name_key,receiver_type,abi,http_method,sql,target_node_id) VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10,?11,?12)")
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to prepare: {e}"),
+                operation: "insert_resolution".to_string(),
+            })?;
+
+        let rows = resolution
+            .resolved
+            .iter()
+            .map(|r| (&r.original, Some(r.target_node_id.as_str())))
+            .chain(resolution.unresolved.iter().map(|u| (u, None)));
+        for (uref, target) in rows {
+            stmt.execute(params![
+                uref.from_node_id.as_str(),
+                uref.reference_name.as_str(),
+                uref.reference_kind.as_str(),
+                i64::from(uref.line),
+                i64::from(uref.column),
+                uref.file_path.as_str(),
+                uref.name_key(),
+                uref.receiver_type.as_deref(),
+                uref.abi.as_deref(),
+                uref.http_method.as_deref(),
+                i64::from(uref.sql),
+                target,
+            ])
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to insert reference: {e}"),
+                operation: "insert_resolution".to_string(),
+            })?;
+            stmt.reset();
+        }
+
+        self.conn()
+            .execute("COMMIT", ())
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to commit: {e}"),
+                operation: "insert_resolution".to_string(),
+            })?;
+        Ok(())
+    }
+
+    /// Records the outcome of re-resolving references that are already stored:
+    /// resolved ones get their target, unresolved ones have it cleared.
+    pub async fn update_ref_targets(&self, resolution: &ResolutionResult) -> Result<()> {
+        if resolution.total == 0 {
+            return Ok(());
+        }
+
+        self.conn()
+            .execute("BEGIN", ())
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to begin: {e}"),
+                operation: "update_ref_targets".to_string(),
+            })?;
+
+        let stmt = self
+            .conn()
+            .prepare(
+                "UPDATE unresolved_refs SET target_node_id = ?1
+                 WHERE from_node_id = ?2 AND reference_name = ?3 AND reference_kind = ?4
+                   AND line = ?5 AND col = ?6",
+            )
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to prepare: {e}"),
+                operation: "update_ref_targets".to_string(),
+            })?;
+
+        let rows = resolution
+            .resolved
+            .iter()
+            .map(|r| (&r.original, Some(r.target_node_id.as_str())))
+            .chain(resolution.unresolved.iter().map(|u| (u, None)));
+        for (uref, target) in rows {
+            stmt.execute(params![
+                target,
+                uref.from_node_id.as_str(),
+                uref.reference_name.as_str(),
+                uref.reference_kind.as_str(),
+                i64::from(uref.line),
+                i64::from(uref.column),
+            ])
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to update reference target: {e}"),
+                operation: "update_ref_targets".to_string(),
+            })?;
+            stmt.reset();
+        }
+
+        self.conn()
+            .execute("COMMIT", ())
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to commit: {e}"),
+                operation: "update_ref_targets".to_string(),
+            })?;
+        Ok(())
+    }
+
+    /// Returns references from other files whose resolved target is a node
+    /// declared in `file_path`. Call before the file's nodes are deleted: the
+    /// edges these references produced disappear with those nodes.
+    pub async fn get_refs_targeting_file(&self, file_path: &str) -> Result<Vec<UnresolvedRef>> {
+        let mut rows = self
+            .conn()
+            .query(
+                "SELECT from_node_id, reference_name, reference_kind, line, col, file_path,
+                        receiver_type, abi, http_method, sql
+                 FROM unresolved_refs
+                 WHERE target_node_id IN (SELECT id FROM nodes WHERE file_path = ?1)
+                   AND file_path <> ?1",
+                params![file_path],
+            )
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to query refs targeting file: {e}"),
+                operation: "get_refs_targeting_file".to_string(),
+            })?;
+
+        collect_rows(&mut rows, row_to_unresolved_ref, "get_refs_targeting_file").await
+    }
+
+    /// Returns stored references whose name key is one of `keys`, each paired
+    /// with its current target (if resolved).
+    pub async fn get_refs_by_name_keys(
+        &self,
+        keys: &[String],
+    ) -> Result<Vec<(UnresolvedRef, Option<String>)>> {
+        let mut refs = Vec::new();
+        for chunk in keys.chunks(IN_BATCH) {
+            let sql = format!(
+                "SELECT from_node_id, reference_name, reference_kind, line, col, file_path,
+                        receiver_type, abi, http_method, sql, target_node_id
+                 FROM unresolved_refs WHERE name_key IN ({})",
+                in_placeholders(chunk.len())
+            );
+            let param_values: Vec<libsql::Value> = chunk
+                .iter()
+                .map(|k| libsql::Value::Text(k.clone()))
+                .collect();
+            let mut rows = self
+                .conn()
+                .query(&sql, libsql::params_from_iter(param_values))
+                .await
+                .map_err(|e| TokenSaveError::Database {
+                    message: format!("failed to query refs by name key: {e}"),
+                    operation: "get_refs_by_name_keys".to_string(),
+                })?;
+            refs.extend(
+                collect_rows(&mut rows, row_to_ref_with_target, "get_refs_by_name_keys").await?,
+            );
+        }
+        Ok(refs)
+    }
+
+    /// Returns all references that have not been resolved to a node.
     pub async fn get_unresolved_refs(&self) -> Result<Vec<UnresolvedRef>> {
         let mut rows = self
             .conn()
             .query(
-                "SELECT from_node_id, reference_name, reference_kind, line, col, file_path
-                 FROM unresolved_refs",
+                "SELECT from_node_id, reference_name, reference_kind, line, col, file_path,
+                        receiver_type, abi, http_method, sql
+                 FROM unresolved_refs WHERE target_node_id IS NULL",
                 (),
             )
             .await
@@ -1650,6 +2292,75 @@ impl Database {
         collect_rows(&mut rows, row_to_unresolved_ref, "get_unresolved_refs").await
     }
 
+    /// Returns the references whose edge was picked among several
+    /// same-named candidates (strategy `ambiguous`).
+    pub async fn get_ambiguous_refs(&self) -> Result<Vec<UnresolvedRef>> {
+        let mut rows = self
+            .conn()
+            .query(
+                "SELECT u.from_node_id, u.reference_name, u.reference_kind, u.line, u.col,
+                        u.file_path, u.receiver_type, u.abi, u.http_method, u.sql
+                 FROM unresolved_refs u
+                 JOIN edges e ON e.source = u.from_node_id AND e.target = u.target_node_id
+                     AND e.kind = u.reference_kind AND e.line = u.line
+                 WHERE e.strategy = 'ambiguous'",
+                (),
+            )
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to query ambiguous refs: {e}"),
+                operation: "get_ambiguous_refs".to_string(),
+            })?;
+
+        collect_rows(&mut rows, row_to_unresolved_ref, "get_ambiguous_refs").await
+    }
+
+    /// Returns `(type name, supertype reference)` pairs for every stored
+    /// `extends` / `implements` reference, resolved or not.
+    pub async fn get_supertype_refs(&self) -> Result<Vec<(String, String)>> {
+        let mut rows = self
+            .conn()
+            .query(
+                "SELECT n.name, u.reference_name
+                 FROM unresolved_refs u JOIN nodes n ON n.id = u.from_node_id
+                 WHERE u.reference_kind IN ('extends', 'implements')",
+                (),
+            )
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to query supertype refs: {e}"),
+                operation: "get_supertype_refs".to_string(),
+            })?;
+
+        collect_rows(
+            &mut rows,
+            |row| Ok((row.get::<String>(0)?, row.get::<String>(1)?)),
+            "get_supertype_refs",
+        )
+        .await
+    }
+
+    /// Returns every stored reference recording that a function is exported
+    /// to another language (see [`UnresolvedRef::abi`]).
+    pub async fn get_export_refs(&self) -> Result<Vec<UnresolvedRef>> {
+        let mut rows = self
+            .conn()
+            .query(
+                "SELECT from_node_id, reference_name, reference_kind, line, col, file_path,
+                        receiver_type, abi, http_method, sql
+                 FROM unresolved_refs
+                 WHERE reference_kind = 'implements' AND abi IS NOT NULL",
+                (),
+            )
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to query export refs: {e}"),
+                operation: "get_export_refs".to_string(),
+            })?;
+
+        collect_rows(&mut rows, row_to_unresolved_ref, "get_export_refs").await
+    }
+
     /// Removes all unresolved references.
     pub async fn clear_unresolved_refs(&self) -> Result<()> {
         self.conn()
@@ -1663,6 +2374,216 @@ impl Database {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Signature operations
+// ---------------------------------------------------------------------------
+
+impl Database {
+    /// Inserts the parameters and return types of a batch of signatures
+    /// using prepared statements.
+    pub async fn insert_signatures(&self, signatures: &[Signature]) -> Result<()> {
+        let op = "insert_signatures";
+        if signatures.is_empty() {
+            return Ok(());
+        }
+
+        self.conn()
+            .execute("BEGIN", ())
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to begin: {e}"),
+                operation: op.to_string(),
+            })?;
+
+        let param_stmt = self.conn()
+            .prepare("INSERT OR REPLACE INTO params (node_id,position,name,type_text,type_name,default_value,optional) VALUES (?1,?2,?3,?4,?5,?6,?7)")
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to prepare: {e}"),
+                operation: op.to_string(),
+            })?;
+        let return_stmt = self.conn()
+            .prepare("INSERT OR REPLACE INTO return_types (node_id,type_text,type_name) VALUES (?1,?2,?3)")
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to prepare: {e}"),
+                operation: op.to_string(),
+            })?;
+
+        for signature in signatures {
+            for (position, param) in signature.params.iter().enumerate() {
+                let declared = param.declared_type.as_ref();
+                param_stmt
+                    .execute(params![
+                        signature.node_id.as_str(),
+                        position as i64,
+                        param.name.as_str(),
+                        declared.map(|t| t.text.as_str()),
+                        declared.map(|t| t.name.as_str()),
+                        param.default_value.as_deref(),
+                        i64::from(param.optional),
+                    ])
+                    .await
+                    .map_err(|e| TokenSaveError::Database {
+                        message: format!("failed to insert param: {e}"),
+                        operation: op.to_string(),
+                    })?;
+                param_stmt.reset();
+            }
+            if let Some(ret) = &signature.return_type {
+                return_stmt
+                    .execute(params![
+                        signature.node_id.as_str(),
+                        ret.text.as_str(),
+                        ret.name.as_str(),
+                    ])
+                    .await
+                    .map_err(|e| TokenSaveError::Database {
+                        message: format!("failed to insert return type: {e}"),
+                        operation: op.to_string(),
+                    })?;
+                return_stmt.reset();
+            }
+        }
+
+        self.conn()
+            .execute("COMMIT", ())
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to commit: {e}"),
+                operation: op.to_string(),
+            })?;
+        Ok(())
+    }
+
+    /// Returns the signatures of the nodes in `node_ids` that declare any
+    /// parameter or return type, in no particular order.
+    pub async fn get_signatures(&self, node_ids: &[String]) -> Result<Vec<Signature>> {
+        let op = "get_signatures";
+        let mut signatures: HashMap<String, Signature> = HashMap::new();
+        for chunk in node_ids.chunks(IN_BATCH) {
+            let param_values: Vec<libsql::Value> = chunk
+                .iter()
+                .map(|id| libsql::Value::Text(id.clone()))
+                .collect();
+
+            let sql = format!(
+                "SELECT node_id, position, name, type_text, type_name, default_value, optional
+                 FROM params WHERE node_id IN ({}) ORDER BY node_id, position",
+                in_placeholders(chunk.len())
+            );
+            let mut rows = self
+                .conn()
+                .query(&sql, libsql::params_from_iter(param_values.clone()))
+                .await
+                .map_err(|e| TokenSaveError::Database {
+                    message: format!("failed to query params: {e}"),
+                    operation: op.to_string(),
+                })?;
+            for (node_id, param) in collect_rows(&mut rows, row_to_param, op).await? {
+                signatures
+                    .entry(node_id.clone())
+                    .or_insert_with(|| empty_signature(node_id))
+                    .params
+                    .push(param);
+            }
+
+            let sql = format!(
+                "SELECT node_id, type_text, type_name FROM return_types WHERE node_id IN ({})",
+                in_placeholders(chunk.len())
+            );
+            let mut rows = self
+                .conn()
+                .query(&sql, libsql::params_from_iter(param_values))
+                .await
+                .map_err(|e| TokenSaveError::Database {
+                    message: format!("failed to query return types: {e}"),
+                    operation: op.to_string(),
+                })?;
+            for (node_id, ret) in collect_rows(&mut rows, row_to_return_type, op).await? {
+                signatures
+                    .entry(node_id.clone())
+                    .or_insert_with(|| empty_signature(node_id))
+                    .return_type = Some(ret);
+            }
+        }
+        Ok(signatures.into_values().collect())
+    }
+
+    /// Returns the IDs of callables that may match a signature search.
+    ///
+    /// `param_key` keeps callables with a parameter whose normalized type
+    /// contains the text, `return_key` those whose normalized return type
+    /// does; `None` leaves that side unconstrained and an empty key requires
+    /// only that the type is declared. This is a coarse prefilter: callers
+    /// match the returned signatures against the full pattern.
+    pub async fn get_signature_candidates(
+        &self,
+        param_key: Option<&str>,
+        return_key: Option<&str>,
+        path_prefix: Option<&str>,
+    ) -> Result<Vec<String>> {
+        let op = "get_signature_candidates";
+        let mut clauses = Vec::new();
+        let mut param_values = Vec::new();
+        if let Some(key) = param_key {
+            param_values.push(libsql::Value::Text(key.to_string()));
+            clauses.push(format!(
+                "EXISTS (SELECT 1 FROM params p WHERE p.node_id = n.id
+                    AND instr(p.type_name, ?{}) > 0)",
+                param_values.len()
+            ));
+        }
+        if let Some(key) = return_key {
+            param_values.push(libsql::Value::Text(key.to_string()));
+            clauses.push(format!(
+                "EXISTS (SELECT 1 FROM return_types r WHERE r.node_id = n.id
+                    AND instr(r.type_name, ?{}) > 0)",
+                param_values.len()
+            ));
+        }
+        if clauses.is_empty() {
+            return Ok(Vec::new());
+        }
+        if let Some(prefix) = path_prefix {
+            param_values.push(libsql::Value::Text(format!("{prefix}%")));
+            clauses.push(format!("n.file_path LIKE ?{}", param_values.len()));
+        }
+        let sql = format!(
+            "SELECT n.id FROM nodes n WHERE {} ORDER BY n.file_path, n.start_line",
+            clauses.join(" AND ")
+        );
+        let mut rows = self
+            .conn()
+            .query(&sql, libsql::params_from_iter(param_values))
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to query signature candidates: {e}"),
+                operation: op.to_string(),
+            })?;
+        collect_rows(&mut rows, |row: &libsql::Row| row.get::<String>(0), op).await
+    }
+
+    /// Returns the files under `path_prefix` that declare a function or
+    /// method.
+    pub async fn get_callable_files(&self, path_prefix: Option<&str>) -> Result<Vec<String>> {
+        let op = "get_callable_files";
+        let mut rows = self
+            .conn()
+            .query(
+                "SELECT DISTINCT file_path FROM nodes
+                 WHERE kind IN ('function', 'method') AND file_path LIKE ?1",
+                params![format!("{}%", path_prefix.unwrap_or_default())],
+            )
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to query callable files: {e}"),
+                operation: op.to_string(),
+            })?;
+        collect_rows(&mut rows, |row: &libsql::Row| row.get::<String>(0), op).await
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Search
 // ---------------------------------------------------------------------------
@@ -2007,6 +2928,8 @@ impl Database {
         self.conn()
             .execute_batch(
                 "DELETE FROM vectors;
+                 DELETE FROM params;
+                 DELETE FROM return_types;
                  DELETE FROM unresolved_refs;
                  DELETE FROM edges;
                  DELETE FROM nodes;
@@ -2135,7 +3058,7 @@ impl Database {
                 .map(|(i, _)| format!("?{}", i + 1))
                 .collect();
             let sql = format!(
-                "SELECT source, target, kind, line FROM edges WHERE source IN ({})",
+                "SELECT source, target, kind, line, strategy, confidence, candidates FROM edges WHERE source IN ({})",
                 placeholders.join(", ")
             );
 
@@ -72,6 +72,12 @@ impl BashExtractor {
     /// `file_path` is used for qualified names and node IDs (not for I/O).
     /// `source` is the Bash source code to parse.
     pub fn extract_bash(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -79,7 +85,7 @@ impl BashExtractor {
             Ok(tree) => tree,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -117,7 +123,7 @@ impl BashExtractor {
 
         state.node_stack.pop();
 
-        Self::build_result(state, start)
+        (Self::build_result(state, start), Some(tree))
     }
 
     /// Parse source code into a tree-sitter AST.
@@ -208,6 +214,7 @@ impl BashExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -269,6 +276,7 @@ impl BashExtractor {
                         target: id,
                         kind: EdgeKind::Contains,
                         line: Some(start_line),
+                        provenance: None,
                     });
                 }
             }
@@ -327,6 +335,7 @@ impl BashExtractor {
                         target: id,
                         kind: EdgeKind::Contains,
                         line: Some(start_line),
+                        provenance: None,
                     });
                 }
             }
@@ -390,6 +399,10 @@ impl BashExtractor {
                                 line: child.start_position().row as u32,
                                 column: child.start_position().column as u32,
                                 file_path: state.file_path.clone(),
+                                receiver_type: None,
+                                abi: None,
+                                http_method: None,
+                                sql: false,
                             });
                         }
                         // Recurse into command for nested command substitutions.
@@ -450,6 +463,7 @@ impl BashExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: Vec::new(),
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -468,4 +482,8 @@ impl crate::extraction::LanguageExtractor for BashExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         Self::extract_bash(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
 }
@@ -72,6 +72,12 @@ impl BatchExtractor {
     /// `file_path` is used for qualified names and node IDs (not for I/O).
     /// `source` is the Batch source code to parse.
     pub fn extract_batch(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -79,7 +85,7 @@ impl BatchExtractor {
             Ok(tree) => tree,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -117,7 +123,7 @@ impl BatchExtractor {
 
         state.node_stack.pop();
 
-        Self::build_result(state, start)
+        (Self::build_result(state, start), Some(tree))
     }
 
     /// Parse source code into a tree-sitter AST.
@@ -236,6 +242,7 @@ impl BatchExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -312,6 +319,7 @@ impl BatchExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -400,6 +408,10 @@ impl BatchExtractor {
                     line: node.start_position().row as u32,
                     column: node.start_position().column as u32,
                     file_path: state.file_path.clone(),
+                    receiver_type: None,
+                    abi: None,
+                    http_method: None,
+                    sql: false,
                 });
             }
         }
@@ -443,6 +455,7 @@ impl BatchExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: Vec::new(),
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -461,4 +474,8 @@ impl crate::extraction::LanguageExtractor for BatchExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         Self::extract_batch(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
 }
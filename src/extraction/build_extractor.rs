@@ -0,0 +1,1509 @@
+//! Extractors for build files: Makefiles, `CMakeLists.txt` and the Starlark
+//! `BUILD` / `BUCK` files of Bazel and Buck.
+//!
+//! Every build target becomes a `BuildTarget` node: Make rule targets by
+//! their name (`app`, `build/main.o`), `CMake` targets by the name given to
+//! `add_executable` / `add_library` / `add_custom_target`, and Bazel and Buck
+//! rules by their label (`//src/net:http`).
+//!
+//! A target's dependencies on other targets become `DependsOn` refs named
+//! like the target, and its source files `Contains` refs named by their
+//! project-relative path, which the resolver binds to the file's `File`
+//! node. Make prerequisites without a rule of their own are mapped through
+//! the Makefile's pattern rules (`%.o: %.c`) to the sources they are built
+//! from.
+
+use std::collections::HashMap;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::extraction::LanguageExtractor;
+use crate::types::{
+    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, UnresolvedRef, Visibility,
+};
+
+/// Extracts rule targets from Makefiles.
+pub struct MakeExtractor;
+
+/// Extracts targets from `CMakeLists.txt` and `.cmake` files.
+pub struct CMakeExtractor;
+
+/// Extracts rule targets from Bazel `BUILD` and Buck `BUCK` files.
+pub struct StarlarkBuildExtractor;
+
+impl LanguageExtractor for MakeExtractor {
+    fn extensions(&self) -> &[&str] {
+        &["mk"]
+    }
+
+    fn file_names(&self) -> &[&str] {
+        &["Makefile", "makefile", "GNUmakefile"]
+    }
+
+    fn language_name(&self) -> &'static str {
+        "Make"
+    }
+
+    fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
+        let start = Instant::now();
+        let mut emitter = Emitter::new(file_path, source);
+        Makefile::default().extract(&mut emitter);
+        emitter.finish(Vec::new(), start)
+    }
+}
+
+impl LanguageExtractor for CMakeExtractor {
+    fn extensions(&self) -> &[&str] {
+        &["cmake"]
+    }
+
+    fn file_names(&self) -> &[&str] {
+        &["CMakeLists.txt"]
+    }
+
+    fn language_name(&self) -> &'static str {
+        "CMake"
+    }
+
+    fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
+        let start = Instant::now();
+        let mut emitter = Emitter::new(file_path, source);
+        let mut errors = Vec::new();
+        match cmake_commands(source) {
+            Ok(commands) => CMakeLists::new(&emitter.dir).extract(&mut emitter, &commands),
+            Err(offset) => {
+                let (line, _) = emitter.position(offset);
+                errors.push(format!("{file_path}:{}: unclosed command", line + 1));
+            }
+        }
+        emitter.finish(errors, start)
+    }
+}
+
+impl LanguageExtractor for StarlarkBuildExtractor {
+    fn extensions(&self) -> &[&str] {
+        &[]
+    }
+
+    fn file_names(&self) -> &[&str] {
+        &["BUILD", "BUILD.bazel", "BUCK"]
+    }
+
+    fn language_name(&self) -> &'static str {
+        "Starlark"
+    }
+
+    fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
+        let start = Instant::now();
+        let mut emitter = Emitter::new(file_path, source);
+        let tokens = starlark_tokens(source);
+        StarlarkFile::default().extract(&mut emitter, &tokens);
+        emitter.finish(Vec::new(), start)
+    }
+}
+
+/// Joins `path`, relative to the directory `dir`, into a project-relative
+/// path. `None` for absolute paths and paths leaving the project.
+fn join_path(dir: &str, path: &str) -> Option<String> {
+    if path.is_empty() || path.starts_with('/') {
+        return None;
+    }
+    let mut parts: Vec<&str> = dir.split('/').filter(|s| !s.is_empty()).collect();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            _ => parts.push(segment),
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+// ---------------------------------------------------------------------------
+// Make
+// ---------------------------------------------------------------------------
+
+/// A logical Makefile line: physical lines joined at their trailing
+/// backslashes.
+struct Line {
+    text: String,
+    /// (offset in `text`, offset in the file) of each joined physical line.
+    segments: Vec<(usize, usize)>,
+    /// File offset of the end of the last physical line.
+    end: usize,
+}
+
+impl Line {
+    /// File offset of a byte offset in `text`.
+    fn offset(&self, at: usize) -> usize {
+        let index = self.segments.partition_point(|&(start, _)| start <= at) - 1;
+        let (start, file_offset) = self.segments[index];
+        file_offset + at - start
+    }
+}
+
+fn make_lines(source: &str) -> Vec<Line> {
+    let mut lines: Vec<Line> = Vec::new();
+    let mut continued = false;
+    let mut offset = 0;
+    for physical in source.split_inclusive('\n') {
+        let start = offset;
+        offset += physical.len();
+        let content = physical.trim_end_matches(['\n', '\r']);
+        let (content, continues) = match content.strip_suffix('\\') {
+            Some(content) => (content, true),
+            None => (content, false),
+        };
+        match lines.last_mut() {
+            Some(line) if continued => {
+                line.text.push(' ');
+                let content = content.trim_start();
+                let skipped = physical.len() - physical.trim_start().len();
+                line.segments.push((line.text.len(), start + skipped));
+                line.text.push_str(content);
+                line.end = start + content.len() + skipped;
+            }
+            _ => lines.push(Line {
+                text: content.to_string(),
+                segments: vec![(0, start)],
+                end: start + content.len(),
+            }),
+        }
+        continued = continues;
+    }
+    lines
+}
+
+/// Byte index of the first character of `text` matching `pred` outside
+/// `$(...)` and `${...}` references.
+fn find_top(text: &str, pred: impl Fn(u8) -> bool) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut depth = 0usize;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'$' if matches!(bytes.get(i + 1), Some(b'(' | b'{')) => {
+                depth += 1;
+                i += 1;
+            }
+            b'(' | b'{' if depth > 0 => depth += 1,
+            b')' | b'}' if depth > 0 => depth -= 1,
+            c if depth == 0 && pred(c) => return Some(i),
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Splits `text` at the top-level occurrences of `separator`.
+fn split_top(text: &str, separator: u8) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut rest = text;
+    while let Some(i) = find_top(rest, |c| c == separator) {
+        parts.push(&rest[..i]);
+        rest = &rest[i + 1..];
+    }
+    parts.push(rest);
+    parts
+}
+
+/// The whitespace-separated words of `text` outside references, with their
+/// byte offsets.
+fn make_words(text: &str) -> Vec<(&str, usize)> {
+    let mut words = Vec::new();
+    let mut rest = text;
+    let mut base = 0;
+    loop {
+        let trimmed = rest.trim_start();
+        base += rest.len() - trimmed.len();
+        if trimmed.is_empty() {
+            return words;
+        }
+        let end = find_top(trimmed, |c| c.is_ascii_whitespace()).unwrap_or(trimmed.len());
+        words.push((&trimmed[..end], base));
+        base += end;
+        rest = &trimmed[end..];
+    }
+}
+
+/// The stem `%` matches when `word` matches `pattern`.
+fn pattern_stem<'w>(pattern: &str, word: &'w str) -> Option<&'w str> {
+    match pattern.split_once('%') {
+        Some((prefix, suffix)) => word
+            .strip_prefix(prefix)?
+            .strip_suffix(suffix)
+            .filter(|stem| !stem.is_empty()),
+        None => (pattern == word).then_some(""),
+    }
+}
+
+fn patsubst(pattern: &str, replacement: &str, words: &str) -> String {
+    words
+        .split_whitespace()
+        .map(|word| match pattern_stem(pattern, word) {
+            Some(stem) if pattern.contains('%') => replacement.replacen('%', stem, 1),
+            Some(_) => replacement.to_string(),
+            None => word.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Make functions expanded when naming targets and prerequisites; the others
+/// expand to nothing.
+const MAKE_FUNCTIONS: &[&str] = &[
+    "patsubst",
+    "subst",
+    "addprefix",
+    "addsuffix",
+    "strip",
+    "wildcard",
+    "shell",
+    "foreach",
+    "filter",
+    "filter-out",
+    "notdir",
+    "dir",
+    "basename",
+    "suffix",
+    "sort",
+    "if",
+    "call",
+    "eval",
+    "abspath",
+    "realpath",
+    "join",
+    "word",
+    "words",
+    "firstword",
+    "lastword",
+    "origin",
+    "info",
+    "warning",
+    "error",
+];
+
+/// A rule target of the Makefile, with the prerequisites of all its rules.
+struct MakeTarget {
+    id: String,
+    prerequisites: Vec<(String, usize)>,
+}
+
+#[derive(Default)]
+struct Makefile {
+    variables: HashMap<String, String>,
+    targets: Vec<(String, MakeTarget)>,
+    /// Pattern rules: target pattern and prerequisite patterns.
+    patterns: Vec<(String, Vec<String>)>,
+}
+
+impl Makefile {
+    fn extract(mut self, emitter: &mut Emitter<'_>) {
+        let lines = make_lines(emitter.source);
+        let mut in_define = false;
+        // The target nodes of the rule whose recipe lines follow.
+        let mut open: Vec<usize> = Vec::new();
+        for line in &lines {
+            if line.text.starts_with('\t') {
+                for &index in &open {
+                    emitter.extend(&self.targets[index].1.id, line.end);
+                }
+                continue;
+            }
+            let text = match find_top(&line.text, |c| c == b'#') {
+                Some(comment) => &line.text[..comment],
+                None => &line.text,
+            };
+            let first = text.split_whitespace().next().unwrap_or("");
+            if in_define {
+                in_define = first != "endef";
+                continue;
+            }
+            if text.trim().is_empty() {
+                continue;
+            }
+            open.clear();
+            match first {
+                "define" => in_define = true,
+                "ifeq" | "ifneq" | "ifdef" | "ifndef" | "else" | "endif" | "include"
+                | "-include" | "sinclude" | "vpath" | "unexport" => {}
+                _ => self.statement(emitter, line, text, &mut open),
+            }
+        }
+        self.link(emitter);
+    }
+
+    fn statement(
+        &mut self,
+        emitter: &mut Emitter<'_>,
+        line: &Line,
+        text: &str,
+        open: &mut Vec<usize>,
+    ) {
+        let Some(at) = find_top(text, |c| c == b':' || c == b'=') else {
+            return;
+        };
+        let after = &text[at + 1..];
+        if text.as_bytes()[at] == b'=' || after.starts_with('=') || after.starts_with(":=") {
+            self.assignment(text, at);
+            return;
+        }
+        // A double-colon rule lists its prerequisites after `::`.
+        let rest_start = at + 1 + usize::from(after.starts_with(':'));
+        let mut rest = &text[rest_start..];
+        if let Some(recipe) = find_top(rest, |c| c == b';') {
+            rest = &rest[..recipe];
+        }
+        // Target-specific variable values are not prerequisites.
+        if find_top(rest, |c| c == b'=').is_some() {
+            return;
+        }
+        // A static pattern rule (`objs: %.o: %.c`) maps each target through
+        // its target pattern.
+        let (static_pattern, prerequisites, prerequisites_start) =
+            match find_top(rest, |c| c == b':') {
+                Some(colon) => (
+                    Some(self.expand(&rest[..colon], 0)),
+                    &rest[colon + 1..],
+                    rest_start + colon + 1,
+                ),
+                None => (None, rest, rest_start),
+            };
+        let mut words: Vec<(String, usize)> = Vec::new();
+        for (word, offset) in make_words(prerequisites) {
+            if word == "|" {
+                continue;
+            }
+            for expanded in self.expand(word, 0).split_whitespace() {
+                if expanded != "|" {
+                    words.push((
+                        expanded.to_string(),
+                        line.offset(prerequisites_start + offset),
+                    ));
+                }
+            }
+        }
+
+        for target in self.expand(&text[..at], 0).split_whitespace() {
+            if target.contains('%') {
+                let patterns = words.iter().map(|(w, _)| w.clone()).collect();
+                self.patterns.push((target.to_string(), patterns));
+                continue;
+            }
+            // Special targets (`.PHONY`, `.SUFFIXES`) configure make.
+            if target.strip_prefix('.').is_some_and(|name| {
+                !name.is_empty() && name.bytes().all(|c| c.is_ascii_uppercase() || c == b'_')
+            }) {
+                continue;
+            }
+            let prerequisites: Vec<(String, usize)> = match &static_pattern {
+                Some(pattern) => {
+                    let Some(stem) = pattern_stem(pattern.trim(), target) else {
+                        continue;
+                    };
+                    words
+                        .iter()
+                        .map(|(w, offset)| (w.replacen('%', stem, 1), *offset))
+                        .collect()
+                }
+                None => words.clone(),
+            };
+            let index =
+                if let Some(index) = self.targets.iter().position(|(name, _)| name == target) {
+                    index
+                } else {
+                    let id = emitter.add(target.to_string(), (line.offset(0), line.end), None);
+                    self.targets.push((
+                        target.to_string(),
+                        MakeTarget {
+                            id,
+                            prerequisites: Vec::new(),
+                        },
+                    ));
+                    self.targets.len() - 1
+                };
+            self.targets[index].1.prerequisites.extend(prerequisites);
+            open.push(index);
+        }
+    }
+
+    fn assignment(&mut self, text: &str, at: usize) {
+        let bytes = text.as_bytes();
+        let (name_end, value_start, operator) = if bytes[at] == b':' {
+            // `:=` or `::=`
+            let equals = at + text[at..].find('=').unwrap_or(0);
+            (at, equals + 1, b':')
+        } else {
+            match at.checked_sub(1).map(|i| bytes[i]) {
+                Some(op @ (b'?' | b'+' | b'!')) => (at - 1, at + 1, op),
+                _ => (at, at + 1, b'='),
+            }
+        };
+        let name = text[..name_end].trim();
+        let name = name
+            .strip_prefix("override ")
+            .or_else(|| name.strip_prefix("export "))
+            .unwrap_or(name)
+            .trim();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return;
+        }
+        let value = text[value_start..].trim();
+        match operator {
+            b':' => {
+                let expanded = self.expand(value, 0);
+                self.variables.insert(name.to_string(), expanded);
+            }
+            b'?' => {
+                self.variables
+                    .entry(name.to_string())
+                    .or_insert_with(|| value.to_string());
+            }
+            b'+' => {
+                let current = self.variables.entry(name.to_string()).or_default();
+                if !current.is_empty() {
+                    current.push(' ');
+                }
+                current.push_str(value);
+            }
+            b'!' => {
+                self.variables.insert(name.to_string(), String::new());
+            }
+            _ => {
+                self.variables.insert(name.to_string(), value.to_string());
+            }
+        }
+    }
+
+    /// Expands the variable references and supported functions of `text`.
+    fn expand(&self, text: &str, depth: usize) -> String {
+        if depth > 16 || !text.contains('$') {
+            return text.to_string();
+        }
+        let mut out = String::new();
+        let mut rest = text;
+        while let Some(dollar) = rest.find('$') {
+            out.push_str(&rest[..dollar]);
+            let after = &rest[dollar + 1..];
+            let Some(open) = after.chars().next() else {
+                rest = after;
+                break;
+            };
+            let close = match open {
+                '(' => ')',
+                '{' => '}',
+                '$' => {
+                    out.push('$');
+                    rest = &after[1..];
+                    continue;
+                }
+                // `$@`, `$<` and other single-character variables.
+                _ => {
+                    let name = &after[..open.len_utf8()];
+                    out.push_str(&self.variable(name, depth));
+                    rest = &after[open.len_utf8()..];
+                    continue;
+                }
+            };
+            let mut level = 0usize;
+            let mut end = None;
+            for (i, c) in after.char_indices() {
+                if c == open {
+                    level += 1;
+                } else if c == close {
+                    level -= 1;
+                    if level == 0 {
+                        end = Some(i);
+                        break;
+                    }
+                }
+            }
+            let Some(end) = end else {
+                rest = "";
+                break;
+            };
+            out.push_str(&self.reference(&after[1..end], depth));
+            rest = &after[end + 1..];
+        }
+        out.push_str(rest);
+        out
+    }
+
+    /// Expands the contents of one `$(...)` reference.
+    fn reference(&self, inner: &str, depth: usize) -> String {
+        if let Some((function, arguments)) = inner.split_once(|c: char| c.is_ascii_whitespace()) {
+            if MAKE_FUNCTIONS.contains(&function) {
+                return self.function(function, arguments, depth);
+            }
+        }
+        if let Some(colon) = find_top(inner, |c| c == b':') {
+            if let Some((from, to)) = inner[colon + 1..].split_once('=') {
+                let words = self.variable(inner[..colon].trim(), depth);
+                let (from, to) = if from.contains('%') {
+                    (from.to_string(), to.to_string())
+                } else {
+                    (format!("%{from}"), format!("%{to}"))
+                };
+                return patsubst(&from, &to, &words);
+            }
+        }
+        let name = self.expand(inner, depth + 1);
+        self.variable(name.trim(), depth)
+    }
+
+    fn function(&self, function: &str, arguments: &str, depth: usize) -> String {
+        let arguments: Vec<String> = split_top(arguments, b',')
+            .into_iter()
+            .map(|a| self.expand(a, depth + 1))
+            .collect();
+        let argument = |i: usize| arguments.get(i).map_or("", |a| a.trim());
+        match function {
+            "patsubst" => patsubst(argument(0), argument(1), argument(2)),
+            "subst" if !argument(0).is_empty() => {
+                arguments.get(2).map_or_else(String::new, |text| {
+                    text.replace(argument(0), arguments.get(1).map_or("", String::as_str))
+                })
+            }
+            "addprefix" => argument(1)
+                .split_whitespace()
+                .map(|w| format!("{}{w}", argument(0)))
+                .collect::<Vec<_>>()
+                .join(" "),
+            "addsuffix" => argument(1)
+                .split_whitespace()
+                .map(|w| format!("{w}{}", argument(0)))
+                .collect::<Vec<_>>()
+                .join(" "),
+            "strip" | "sort" => argument(0).to_string(),
+            _ => String::new(),
+        }
+    }
+
+    fn variable(&self, name: &str, depth: usize) -> String {
+        self.variables
+            .get(name)
+            .map(|value| self.expand(value, depth + 1))
+            .unwrap_or_default()
+    }
+
+    /// Records each target's prerequisites: other targets as `DependsOn`
+    /// refs, files as `Contains` refs.
+    fn link(&self, emitter: &mut Emitter<'_>) {
+        let dir = emitter.dir.clone();
+        for (_, target) in &self.targets {
+            for (prerequisite, offset) in &target.prerequisites {
+                if prerequisite.contains(['$', '%']) {
+                    continue;
+                }
+                if self.targets.iter().any(|(name, _)| name == prerequisite) {
+                    emitter.reference(&target.id, prerequisite, EdgeKind::DependsOn, *offset);
+                    continue;
+                }
+                let sources = self
+                    .patterns
+                    .iter()
+                    .find_map(|(pattern, sources)| {
+                        let stem = pattern_stem(pattern, prerequisite)?;
+                        Some(sources.iter().map(move |s| s.replacen('%', stem, 1)))
+                    })
+                    .map_or_else(|| vec![prerequisite.clone()], Iterator::collect);
+                for source in sources {
+                    if self.targets.iter().any(|(name, _)| *name == source) {
+                        emitter.reference(&target.id, &source, EdgeKind::DependsOn, *offset);
+                    } else if let Some(path) = join_path(&dir, &source) {
+                        emitter.reference(&target.id, &path, EdgeKind::Contains, *offset);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CMake
+// ---------------------------------------------------------------------------
+
+struct Command {
+    /// Lowercased command name.
+    name: String,
+    start: usize,
+    end: usize,
+    args: Vec<Argument>,
+}
+
+struct Argument {
+    text: String,
+    offset: usize,
+    quoted: bool,
+}
+
+/// Length of the `[==[` opening of a bracket argument or comment at the
+/// start of `text`, with the number of `=`.
+fn bracket_open(text: &str) -> Option<(usize, usize)> {
+    let rest = text.strip_prefix('[')?;
+    let equals = rest.bytes().take_while(|&c| c == b'=').count();
+    rest[equals..]
+        .starts_with('[')
+        .then_some((equals + 2, equals))
+}
+
+/// Parses the commands of a `CMake` file. Fails with the offset of a command
+/// that is never closed.
+fn cmake_commands(source: &str) -> Result<Vec<Command>, usize> {
+    let bytes = source.as_bytes();
+    let mut commands = Vec::new();
+    let mut pos = 0;
+    // Skips white space and comments, returning false at the end of input.
+    let skip = |pos: &mut usize| -> bool {
+        loop {
+            while bytes.get(*pos).is_some_and(u8::is_ascii_whitespace) {
+                *pos += 1;
+            }
+            if bytes.get(*pos) != Some(&b'#') {
+                return *pos < bytes.len();
+            }
+            *pos += 1;
+            if let Some((open, equals)) = bracket_open(&source[*pos..]) {
+                let close = format!("]{}]", "=".repeat(equals));
+                *pos = source[*pos + open..]
+                    .find(&close)
+                    .map_or(bytes.len(), |i| *pos + open + i + close.len());
+            } else {
+                *pos = source[*pos..].find('\n').map_or(bytes.len(), |i| *pos + i);
+            }
+        }
+    };
+    while skip(&mut pos) {
+        let start = pos;
+        while bytes
+            .get(pos)
+            .is_some_and(|c| c.is_ascii_alphanumeric() || *c == b'_')
+        {
+            pos += 1;
+        }
+        let name = source[start..pos].to_ascii_lowercase();
+        while bytes.get(pos).is_some_and(|c| *c == b' ' || *c == b'\t') {
+            pos += 1;
+        }
+        if name.is_empty() || bytes.get(pos) != Some(&b'(') {
+            pos = start + source[start..].chars().next().map_or(1, char::len_utf8);
+            continue;
+        }
+        pos += 1;
+        let mut args = Vec::new();
+        let mut depth = 0usize;
+        loop {
+            if !skip(&mut pos) {
+                return Err(start);
+            }
+            let at = pos;
+            match bytes[pos] {
+                b')' if depth == 0 => {
+                    pos += 1;
+                    break;
+                }
+                b')' => {
+                    depth -= 1;
+                    pos += 1;
+                }
+                b'(' => {
+                    depth += 1;
+                    pos += 1;
+                }
+                b'"' => {
+                    pos += 1;
+                    let mut text = String::new();
+                    while let Some(&c) = bytes.get(pos) {
+                        if c == b'"' {
+                            break;
+                        }
+                        if c == b'\\' && pos + 1 < bytes.len() {
+                            pos += 1;
+                        }
+                        let ch = source[pos..].chars().next().unwrap_or(' ');
+                        text.push(ch);
+                        pos += ch.len_utf8();
+                    }
+                    if pos >= bytes.len() {
+                        return Err(start);
+                    }
+                    pos += 1;
+                    args.push(Argument {
+                        text,
+                        offset: at + 1,
+                        quoted: true,
+                    });
+                }
+                _ => {
+                    if let Some((open, equals)) = bracket_open(&source[pos..]) {
+                        let close = format!("]{}]", "=".repeat(equals));
+                        let Some(end) = source[pos + open..].find(&close) else {
+                            return Err(start);
+                        };
+                        args.push(Argument {
+                            text: source[pos + open..pos + open + end].to_string(),
+                            offset: pos + open,
+                            quoted: true,
+                        });
+                        pos += open + end + close.len();
+                        continue;
+                    }
+                    let mut level = 0usize;
+                    while let Some(&c) = bytes.get(pos) {
+                        match c {
+                            b'$' if matches!(bytes.get(pos + 1), Some(b'{' | b'<')) => {
+                                level += 1;
+                                pos += 1;
+                            }
+                            b'}' | b'>' if level > 0 => level -= 1,
+                            b'(' | b')' | b'"' | b'#' if level == 0 => break,
+                            c if c.is_ascii_whitespace() && level == 0 => break,
+                            _ => {}
+                        }
+                        pos += 1;
+                    }
+                    args.push(Argument {
+                        text: source[at..pos].to_string(),
+                        offset: at,
+                        quoted: false,
+                    });
+                }
+            }
+        }
+        commands.push(Command {
+            name,
+            start,
+            end: pos,
+            args,
+        });
+    }
+    Ok(commands)
+}
+
+/// Keywords of `add_library` / `add_executable` that are not sources.
+const CMAKE_TARGET_KEYWORDS: &[&str] = &[
+    "STATIC",
+    "SHARED",
+    "MODULE",
+    "OBJECT",
+    "INTERFACE",
+    "UNKNOWN",
+    "EXCLUDE_FROM_ALL",
+    "WIN32",
+    "MACOSX_BUNDLE",
+    "GLOBAL",
+];
+
+/// Keywords of `target_sources` / `target_link_libraries`.
+const CMAKE_SCOPE_KEYWORDS: &[&str] = &[
+    "PRIVATE",
+    "PUBLIC",
+    "INTERFACE",
+    "LINK_PRIVATE",
+    "LINK_PUBLIC",
+    "LINK_INTERFACE_LIBRARIES",
+    "debug",
+    "optimized",
+    "general",
+];
+
+struct CMakeLists {
+    variables: HashMap<String, String>,
+    /// Target names defined in this file, with their node ids.
+    targets: HashMap<String, String>,
+}
+
+impl CMakeLists {
+    fn new(dir: &str) -> Self {
+        let depth = dir.split('/').filter(|s| !s.is_empty()).count();
+        let root = if depth == 0 {
+            ".".to_string()
+        } else {
+            vec![".."; depth].join("/")
+        };
+        let mut variables = HashMap::new();
+        for name in ["CMAKE_CURRENT_SOURCE_DIR", "CMAKE_CURRENT_LIST_DIR"] {
+            variables.insert(name.to_string(), ".".to_string());
+        }
+        for name in ["CMAKE_SOURCE_DIR", "PROJECT_SOURCE_DIR"] {
+            variables.insert(name.to_string(), root.clone());
+        }
+        Self {
+            variables,
+            targets: HashMap::new(),
+        }
+    }
+
+    fn extract(mut self, emitter: &mut Emitter<'_>, commands: &[Command]) {
+        for command in commands {
+            let args = self.arguments(command);
+            let Some((first, _)) = args.first() else {
+                continue;
+            };
+            let first = first.clone();
+            match command.name.as_str() {
+                "set" => {
+                    let values: Vec<&str> = args[1..]
+                        .iter()
+                        .map(|(a, _)| a.as_str())
+                        .take_while(|a| *a != "CACHE" && *a != "PARENT_SCOPE")
+                        .collect();
+                    self.variables.insert(first, values.join(";"));
+                }
+                "list" if first == "APPEND" && args.len() > 1 => {
+                    let values: Vec<&str> = args[2..].iter().map(|(a, _)| a.as_str()).collect();
+                    let current = self.variables.entry(args[1].0.clone()).or_default();
+                    for value in values {
+                        if !current.is_empty() {
+                            current.push(';');
+                        }
+                        current.push_str(value);
+                    }
+                }
+                "project" => {
+                    self.variables.insert("PROJECT_NAME".to_string(), first);
+                }
+                "add_executable" | "add_library" => {
+                    let id = self.target(emitter, command, &first);
+                    let mut items = args[1..].iter();
+                    let mut sources = Vec::new();
+                    while let Some((item, offset)) = items.next() {
+                        match item.as_str() {
+                            "ALIAS" => {
+                                if let Some((aliased, offset)) = items.next() {
+                                    emitter.reference(&id, aliased, EdgeKind::DependsOn, *offset);
+                                }
+                            }
+                            "IMPORTED" => break,
+                            _ if CMAKE_TARGET_KEYWORDS.contains(&item.as_str()) => {}
+                            _ => sources.push((item.as_str(), *offset)),
+                        }
+                    }
+                    Self::sources(emitter, &id, sources);
+                }
+                "add_custom_target" => {
+                    let id = self.target(emitter, command, &first);
+                    let mut section = "";
+                    for (item, offset) in &args[1..] {
+                        if item.bytes().all(|c| c.is_ascii_uppercase() || c == b'_') {
+                            section = item;
+                        } else if section == "DEPENDS" {
+                            emitter.reference(&id, item, EdgeKind::DependsOn, *offset);
+                        } else if section == "SOURCES" {
+                            Self::sources(emitter, &id, vec![(item.as_str(), *offset)]);
+                        }
+                    }
+                }
+                "target_sources" => {
+                    let id = self.owner(emitter, &first);
+                    let sources = args[1..]
+                        .iter()
+                        .filter(|(a, _)| !CMAKE_SCOPE_KEYWORDS.contains(&a.as_str()))
+                        .take_while(|(a, _)| a != "FILE_SET")
+                        .map(|(a, offset)| (a.as_str(), *offset))
+                        .collect();
+                    Self::sources(emitter, &id, sources);
+                }
+                "target_link_libraries" | "add_dependencies" => {
+                    let id = self.owner(emitter, &first);
+                    for (library, offset) in &args[1..] {
+                        let is_target = !CMAKE_SCOPE_KEYWORDS.contains(&library.as_str())
+                            && !library.starts_with('-')
+                            && !library.contains(['$', '/', '<']);
+                        if is_target {
+                            emitter.reference(&id, library, EdgeKind::DependsOn, *offset);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// The expanded arguments of a command: unquoted arguments split into
+    /// their `;`-separated list items.
+    fn arguments(&self, command: &Command) -> Vec<(String, usize)> {
+        let mut args = Vec::new();
+        for argument in &command.args {
+            let text = self.expand(&argument.text);
+            if argument.quoted {
+                args.push((text, argument.offset));
+            } else {
+                args.extend(
+                    text.split(';')
+                        .filter(|item| !item.is_empty())
+                        .map(|item| (item.to_string(), argument.offset)),
+                );
+            }
+        }
+        args
+    }
+
+    /// Replaces the `${VAR}` references of `text`, innermost first. Unknown
+    /// variables are left in place.
+    fn expand(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        let mut from = text.len();
+        while let Some(start) = text[..from].rfind("${") {
+            let Some(length) = text[start..].find('}') else {
+                break;
+            };
+            let name = &text[start + 2..start + length];
+            match self.variables.get(name) {
+                Some(value) => text.replace_range(start..=start + length, &value.clone()),
+                None => from = start,
+            }
+            from = from.min(text.len());
+        }
+        text
+    }
+
+    fn target(&mut self, emitter: &mut Emitter<'_>, command: &Command, name: &str) -> String {
+        let id = emitter.add(name.to_string(), (command.start, command.end), None);
+        self.targets.insert(name.to_string(), id.clone());
+        id
+    }
+
+    /// The node a command on the target `name` attaches its refs to: the
+    /// target's when this file defines it, else the file's.
+    fn owner(&self, emitter: &Emitter<'_>, name: &str) -> String {
+        self.targets.get(name).unwrap_or(&emitter.file_id).clone()
+    }
+
+    fn sources(emitter: &mut Emitter<'_>, id: &str, sources: Vec<(&str, usize)>) {
+        let dir = emitter.dir.clone();
+        for (source, offset) in sources {
+            if source.contains(['$', '<', '*']) {
+                continue;
+            }
+            if let Some(path) = join_path(&dir, source) {
+                emitter.reference(id, &path, EdgeKind::Contains, offset);
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Starlark (Bazel / Buck)
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum StarTok<'a> {
+    Name(&'a str),
+    Str(String),
+    Punct(u8),
+    Other,
+}
+
+struct StarToken<'a> {
+    tok: StarTok<'a>,
+    start: usize,
+}
+
+fn starlark_tokens(source: &str) -> Vec<StarToken<'_>> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let c = bytes[pos];
+        if c.is_ascii_whitespace() || c == b'\\' {
+            pos += 1;
+            continue;
+        }
+        if c == b'#' {
+            pos = source[pos..].find('\n').map_or(bytes.len(), |i| pos + i);
+            continue;
+        }
+        let start = pos;
+        // String prefixes (`r"..."`, `b'...'`).
+        let prefix = bytes[pos..]
+            .iter()
+            .take_while(|c| matches!(c, b'r' | b'R' | b'b' | b'B' | b'u' | b'U'))
+            .count();
+        if prefix <= 2 && matches!(bytes.get(pos + prefix), Some(b'"' | b'\'')) {
+            let raw = bytes[pos..pos + prefix]
+                .iter()
+                .any(|c| c.eq_ignore_ascii_case(&b'r'));
+            pos += prefix;
+            let quote = bytes[pos];
+            let triple = bytes[pos..].starts_with(&[quote; 3]);
+            let delimiter = if triple { 3 } else { 1 };
+            pos += delimiter;
+            let content = pos;
+            while pos < bytes.len() && !bytes[pos..].starts_with(&vec![quote; delimiter]) {
+                if bytes[pos] == b'\\' && !raw {
+                    pos += 1;
+                } else if bytes[pos] == b'\n' && !triple {
+                    break;
+                }
+                pos += 1;
+            }
+            let text = source.get(content..pos.min(bytes.len())).unwrap_or("");
+            pos = (pos + delimiter).min(bytes.len());
+            tokens.push(StarToken {
+                tok: StarTok::Str(text.to_string()),
+                start: content,
+            });
+            continue;
+        }
+        if c.is_ascii_alphabetic() || c == b'_' {
+            while bytes
+                .get(pos)
+                .is_some_and(|c| c.is_ascii_alphanumeric() || *c == b'_')
+            {
+                pos += 1;
+            }
+            tokens.push(StarToken {
+                tok: StarTok::Name(&source[start..pos]),
+                start,
+            });
+            continue;
+        }
+        pos += source[pos..].chars().next().map_or(1, char::len_utf8);
+        let tok = if b"()[]{}=,+:".contains(&c) {
+            // `==` is a comparison, not an assignment.
+            if c == b'=' && bytes.get(pos) == Some(&b'=') {
+                pos += 1;
+                StarTok::Other
+            } else {
+                StarTok::Punct(c)
+            }
+        } else {
+            StarTok::Other
+        };
+        tokens.push(StarToken { tok, start });
+    }
+    tokens
+}
+
+/// Rule attributes listing the target's sources.
+const SOURCE_ATTRIBUTES: &[&str] = &[
+    "srcs",
+    "hdrs",
+    "textual_hdrs",
+    "data",
+    "resources",
+    "headers",
+    "exported_headers",
+];
+
+/// Rule attributes listing the targets it depends on.
+const DEPENDENCY_ATTRIBUTES: &[&str] = &[
+    "deps",
+    "runtime_deps",
+    "implementation_deps",
+    "exports",
+    "exported_deps",
+    "plugins",
+];
+
+/// A rule call with a `name`.
+struct Rule {
+    label: String,
+    id: String,
+    attributes: Vec<(String, Vec<(String, usize)>)>,
+}
+
+#[derive(Default)]
+struct StarlarkFile {
+    /// Top-level list and string variables.
+    variables: HashMap<String, Vec<(String, usize)>>,
+    rules: Vec<Rule>,
+}
+
+impl StarlarkFile {
+    fn extract(mut self, emitter: &mut Emitter<'_>, tokens: &[StarToken<'_>]) {
+        let package = emitter.dir.clone();
+        let mut pos = 0;
+        while pos < tokens.len() {
+            let next = tokens.get(pos + 1).map(|t| &t.tok);
+            match (&tokens[pos].tok, next) {
+                (StarTok::Name(rule), Some(StarTok::Punct(b'('))) => {
+                    let start = tokens[pos].start;
+                    let rule = (*rule).to_string();
+                    pos += 2;
+                    let attributes = self.arguments(tokens, &mut pos);
+                    let end = tokens
+                        .get(pos.saturating_sub(1))
+                        .map_or(emitter.source.len(), |t| t.start + 1)
+                        .min(emitter.source.len());
+                    self.rule(emitter, &package, &rule, attributes, (start, end));
+                }
+                (StarTok::Name(name), Some(StarTok::Punct(b'='))) => {
+                    let name = (*name).to_string();
+                    pos += 2;
+                    let value = self.expression(tokens, &mut pos);
+                    self.variables.insert(name, value);
+                }
+                _ => pos += 1,
+            }
+        }
+        self.link(emitter, &package);
+    }
+
+    fn rule(
+        &mut self,
+        emitter: &mut Emitter<'_>,
+        package: &str,
+        rule: &str,
+        attributes: Vec<(String, Vec<(String, usize)>)>,
+        span: (usize, usize),
+    ) {
+        let name = attributes
+            .iter()
+            .find(|(key, _)| key == "name")
+            .and_then(|(_, values)| values.first());
+        let Some((name, _)) = name else {
+            return;
+        };
+        let label = format!("//{package}:{name}");
+        let signature = format!("{rule}(name = \"{name}\")");
+        let id = emitter.add(label.clone(), span, Some(signature));
+        self.rules.push(Rule {
+            label,
+            id,
+            attributes,
+        });
+    }
+
+    /// Parses call arguments after the `(`, returning the keyword arguments
+    /// with the strings their values list.
+    fn arguments(
+        &self,
+        tokens: &[StarToken<'_>],
+        pos: &mut usize,
+    ) -> Vec<(String, Vec<(String, usize)>)> {
+        let mut attributes = Vec::new();
+        while let Some(token) = tokens.get(*pos) {
+            match (&token.tok, tokens.get(*pos + 1).map(|t| &t.tok)) {
+                (StarTok::Punct(b')'), _) => {
+                    *pos += 1;
+                    break;
+                }
+                (StarTok::Punct(b','), _) => *pos += 1,
+                (StarTok::Name(key), Some(StarTok::Punct(b'='))) => {
+                    let key = (*key).to_string();
+                    *pos += 2;
+                    let values = self.expression(tokens, pos);
+                    attributes.push((key, values));
+                }
+                _ => {
+                    let before = *pos;
+                    self.expression(tokens, pos);
+                    if *pos == before {
+                        *pos += 1;
+                    }
+                }
+            }
+        }
+        attributes
+    }
+
+    /// Parses an expression, returning the strings it lists: a string, a
+    /// list of strings, a variable or a concatenation of those. Calls
+    /// (`glob`, `select`) list nothing.
+    fn expression(&self, tokens: &[StarToken<'_>], pos: &mut usize) -> Vec<(String, usize)> {
+        let mut values = Vec::new();
+        loop {
+            let Some(token) = tokens.get(*pos) else {
+                return values;
+            };
+            match &token.tok {
+                StarTok::Str(text) => {
+                    values.push((text.clone(), token.start));
+                    *pos += 1;
+                }
+                StarTok::Punct(b'[') => {
+                    *pos += 1;
+                    loop {
+                        match tokens.get(*pos).map(|t| &t.tok) {
+                            None => break,
+                            Some(StarTok::Punct(b']')) => {
+                                *pos += 1;
+                                break;
+                            }
+                            Some(StarTok::Punct(b',')) => *pos += 1,
+                            // A comprehension lists nothing usable.
+                            Some(StarTok::Name("for")) => {
+                                skip_group(tokens, pos, 1);
+                                values.clear();
+                                break;
+                            }
+                            Some(_) => {
+                                let before = *pos;
+                                values.extend(self.expression(tokens, pos));
+                                if *pos == before {
+                                    *pos += 1;
+                                }
+                            }
+                        }
+                    }
+                }
+                StarTok::Name(name) => {
+                    *pos += 1;
+                    if tokens.get(*pos).map(|t| &t.tok) == Some(&StarTok::Punct(b'(')) {
+                        *pos += 1;
+                        skip_group(tokens, pos, 1);
+                    } else if let Some(variable) = self.variables.get(*name) {
+                        values.extend(variable.iter().cloned());
+                    }
+                }
+                StarTok::Punct(b'(' | b'{') => {
+                    *pos += 1;
+                    skip_group(tokens, pos, 1);
+                }
+                _ => return values,
+            }
+            if tokens.get(*pos).map(|t| &t.tok) != Some(&StarTok::Punct(b'+')) {
+                return values;
+            }
+            *pos += 1;
+        }
+    }
+
+    /// Records each rule's sources as `Contains` refs and the targets it
+    /// depends on as `DependsOn` refs.
+    fn link(&self, emitter: &mut Emitter<'_>, package: &str) {
+        for rule in &self.rules {
+            for (attribute, values) in &rule.attributes {
+                let sources = SOURCE_ATTRIBUTES.contains(&attribute.as_str());
+                if !sources && !DEPENDENCY_ATTRIBUTES.contains(&attribute.as_str()) {
+                    continue;
+                }
+                for (value, offset) in values {
+                    let is_label = value.starts_with([':', '/', '@']);
+                    if sources && !is_label {
+                        let own = format!("//{package}:{value}");
+                        if self.rules.iter().any(|r| r.label == own) {
+                            emitter.reference(&rule.id, &own, EdgeKind::DependsOn, *offset);
+                        } else if let Some(path) = join_path(package, value) {
+                            emitter.reference(&rule.id, &path, EdgeKind::Contains, *offset);
+                        }
+                        continue;
+                    }
+                    let Some(label) = label(package, value) else {
+                        continue;
+                    };
+                    // A label of a file in another package (`//lib:util.h`).
+                    let (target_package, target) = label[2..].split_once(':').unwrap_or(("", ""));
+                    let is_file = sources
+                        && target.contains('.')
+                        && !self.rules.iter().any(|r| r.label == label);
+                    match join_path(target_package, target) {
+                        Some(path) if is_file => {
+                            emitter.reference(&rule.id, &path, EdgeKind::Contains, *offset);
+                        }
+                        _ => emitter.reference(&rule.id, &label, EdgeKind::DependsOn, *offset),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Skips tokens until the group opened `depth` levels up is closed.
+fn skip_group(tokens: &[StarToken<'_>], pos: &mut usize, mut depth: usize) {
+    while let Some(token) = tokens.get(*pos) {
+        *pos += 1;
+        match token.tok {
+            StarTok::Punct(b'(' | b'[' | b'{') => depth += 1,
+            StarTok::Punct(b')' | b']' | b'}') => {
+                depth -= 1;
+                if depth == 0 {
+                    return;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Normalizes a target label relative to `package` to `//package:name`.
+/// `None` for targets of external repositories and Buck cells.
+fn label(package: &str, value: &str) -> Option<String> {
+    // `@//pkg:name` names the main repository.
+    let value = match value.strip_prefix('@') {
+        Some(repository) => {
+            Some(repository.trim_start_matches('@')).filter(|v| v.starts_with("//"))?
+        }
+        None => value,
+    };
+    if let Some(path) = value.strip_prefix("//") {
+        return Some(match path.split_once(':') {
+            Some(_) => format!("//{path}"),
+            None => format!("//{path}:{}", path.rsplit('/').next().unwrap_or(path)),
+        });
+    }
+    if value.contains("//") {
+        return None;
+    }
+    let name = value.strip_prefix(':').unwrap_or(value);
+    Some(format!("//{package}:{name}"))
+}
+
+// ---------------------------------------------------------------------------
+// Emitter
+// ---------------------------------------------------------------------------
+
+struct Emitter<'a> {
+    file_path: &'a str,
+    source: &'a str,
+    /// Directory of the build file, empty at the project root.
+    dir: String,
+    file_id: String,
+    timestamp: u64,
+    /// Byte offset of the start of each line.
+    line_starts: Vec<usize>,
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+    unresolved_refs: Vec<UnresolvedRef>,
+}
+
+impl<'a> Emitter<'a> {
+    fn new(file_path: &'a str, source: &'a str) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        let mut emitter = Self {
+            file_path,
+            source,
+            dir: file_path
+                .rsplit_once('/')
+                .map_or("", |(dir, _)| dir)
+                .to_string(),
+            file_id: generate_node_id(file_path, &NodeKind::File, file_path, 0),
+            timestamp,
+            line_starts,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            unresolved_refs: Vec::new(),
+        };
+        let file_node = emitter.node(
+            emitter.file_id.clone(),
+            NodeKind::File,
+            file_path.to_string(),
+            file_path.to_string(),
+            (0, source.len()),
+        );
+        emitter.nodes.push(file_node);
+        emitter
+    }
+
+    fn finish(self, errors: Vec<String>, start: Instant) -> ExtractionResult {
+        ExtractionResult {
+            nodes: self.nodes,
+            edges: self.edges,
+            unresolved_refs: self.unresolved_refs,
+            signatures: Vec::new(),
+            errors,
+            duration_ms: start.elapsed().as_millis() as u64,
+        }
+    }
+
+    /// 0-based (line, column) of a byte offset.
+    fn position(&self, offset: usize) -> (u32, u32) {
+        let line = self.line_starts.partition_point(|&s| s <= offset) - 1;
+        (line as u32, (offset - self.line_starts[line]) as u32)
+    }
+
+    fn node(
+        &self,
+        id: String,
+        kind: NodeKind,
+        name: String,
+        qualified_name: String,
+        span: (usize, usize),
+    ) -> Node {
+        let (start_line, start_column) = self.position(span.0);
+        let (end_line, end_column) = self.position(span.1);
+        let signature = (kind != NodeKind::File).then(|| {
+            let line = self
+                .source
+                .get(span.0..span.1)
+                .unwrap_or("")
+                .lines()
+                .next()
+                .unwrap_or("")
+                .trim();
+            match line.char_indices().nth(120) {
+                Some((cut, _)) => format!("{}...", &line[..cut]),
+                None => line.to_string(),
+            }
+        });
+        Node {
+            id,
+            kind,
+            name,
+            qualified_name,
+            file_path: self.file_path.to_string(),
+            start_line,
+            end_line,
+            start_column,
+            end_column,
+            signature,
+            docstring: None,
+            visibility: Visibility::Pub,
+            is_async: false,
+            branches: 0,
+            loops: 0,
+            returns: 0,
+            max_nesting: 0,
+            unsafe_blocks: 0,
+            unchecked_calls: 0,
+            assertions: 0,
+            updated_at: self.timestamp,
+        }
+    }
+
+    /// Adds a build target contained in the file and returns its id.
+    fn add(&mut self, name: String, span: (usize, usize), signature: Option<String>) -> String {
+        let (line, _) = self.position(span.0);
+        let qualified_name = format!("{}::{name}", self.file_path);
+        let kind = NodeKind::BuildTarget;
+        let id = generate_node_id(self.file_path, &kind, &qualified_name, line);
+        let mut node = self.node(id.clone(), kind, name, qualified_name, span);
+        if signature.is_some() {
+            node.signature = signature;
+        }
+        self.nodes.push(node);
+        self.edges.push(Edge {
+            source: self.file_id.clone(),
+            target: id.clone(),
+            kind: EdgeKind::Contains,
+            line: Some(line),
+            provenance: None,
+        });
+        id
+    }
+
+    /// Extends the node `id` to end at the byte offset `end`.
+    fn extend(&mut self, id: &str, end: usize) {
+        let (end_line, end_column) = self.position(end);
+        if let Some(node) = self.nodes.iter_mut().rev().find(|n| n.id == id) {
+            node.end_line = end_line;
+            node.end_column = end_column;
+        }
+    }
+
+    fn reference(&mut self, from: &str, name: &str, kind: EdgeKind, offset: usize) {
+        let (line, column) = self.position(offset);
+        self.unresolved_refs.push(UnresolvedRef {
+            from_node_id: from.to_string(),
+            reference_name: name.to_string(),
+            reference_kind: kind,
+            line,
+            column,
+            file_path: self.file_path.to_string(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
+        });
+    }
+}
@@ -7,8 +7,11 @@ use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tree_sitter::{Node as TsNode, Parser, Tree};
 
 use crate::extraction::complexity::{count_complexity, C_COMPLEXITY};
+use crate::extraction::signatures::{param, signature};
+use crate::extraction::type_refs::{push_type_refs, C_TYPE_REFS};
 use crate::types::{
-    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, UnresolvedRef, Visibility,
+    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, Signature, UnresolvedRef,
+    Visibility,
 };
 
 /// Extracts code graph nodes and edges from C source files using tree-sitter.
@@ -19,6 +22,7 @@ struct ExtractionState {
     nodes: Vec<Node>,
     edges: Vec<Edge>,
     unresolved_refs: Vec<UnresolvedRef>,
+    signatures: Vec<Signature>,
     errors: Vec<String>,
     /// Stack of (name, `node_id`) for building qualified names and parent edges.
     node_stack: Vec<(String, String)>,
@@ -37,6 +41,7 @@ impl ExtractionState {
             nodes: Vec::new(),
             edges: Vec::new(),
             unresolved_refs: Vec::new(),
+            signatures: Vec::new(),
             errors: Vec::new(),
             node_stack: Vec::new(),
             file_path: file_path.to_string(),
@@ -73,6 +78,12 @@ impl CExtractor {
     /// `file_path` is used for qualified names and node IDs (not for I/O).
     /// `source` is the C source code to parse.
     pub fn extract_source(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -80,7 +91,7 @@ impl CExtractor {
             Ok(tree) => tree,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -118,7 +129,7 @@ impl CExtractor {
 
         state.node_stack.pop();
 
-        Self::build_result(state, start)
+        (Self::build_result(state, start), Some(tree))
     }
 
     /// Parse source code into a tree-sitter AST.
@@ -221,9 +232,14 @@ impl CExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
+        // Types named by the parameters and the return type.
+        Self::extract_type_refs(state, node, &id);
+        Self::push_signature(state, node, &id);
+
         // Extract call sites from the function body.
         if let Some(body) = Self::find_child_by_kind(node, "compound_statement") {
             Self::extract_call_sites(state, body, &id);
@@ -340,11 +356,15 @@ impl CExtractor {
         if let Some(parent_id) = state.parent_node_id() {
             state.edges.push(Edge {
                 source: parent_id.to_string(),
-                target: id,
+                target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
+
+        Self::extract_type_refs(state, node, &id);
+        Self::push_signature(state, node, &id);
     }
 
     /// Extract a global variable declaration.
@@ -403,6 +423,7 @@ impl CExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -525,6 +546,7 @@ impl CExtractor {
                 target: typedef_id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -592,6 +614,7 @@ impl CExtractor {
                 target: typedef_id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -659,6 +682,7 @@ impl CExtractor {
                 target: typedef_id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -720,6 +744,7 @@ impl CExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -792,6 +817,7 @@ impl CExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -922,6 +948,7 @@ impl CExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -979,6 +1006,7 @@ impl CExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -1036,6 +1064,7 @@ impl CExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -1099,6 +1128,7 @@ impl CExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -1158,6 +1188,7 @@ impl CExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -1228,11 +1259,24 @@ impl CExtractor {
         if let Some(parent_id) = state.parent_node_id() {
             state.edges.push(Edge {
                 source: parent_id.to_string(),
-                target: id,
+                target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
+
+        if let Some(field_type) = node.child_by_field_name("type") {
+            push_type_refs(
+                &mut state.unresolved_refs,
+                field_type,
+                &state.source,
+                &C_TYPE_REFS,
+                &id,
+                &EdgeKind::TypeOf,
+                &state.file_path,
+            );
+        }
     }
 
     /// Extract enum variants from an `enum_specifier` node.
@@ -1298,10 +1342,116 @@ impl CExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
 
+    // -------------------------------------------------------
+    // Type reference extraction
+    // -------------------------------------------------------
+
+    /// Extract type references from parameter types and return type.
+    ///
+    /// The `parameters` of the `function_declarator` become `Receives` refs and
+    /// the definition's `type` field becomes `Returns` refs. Pointer and array
+    /// modifiers live in the declarators, so only the base type is reported.
+    fn extract_type_refs(state: &mut ExtractionState, node: TsNode<'_>, fn_node_id: &str) {
+        if let Some(declarator) = Self::find_descendant_by_kind(node, "function_declarator") {
+            if let Some(params) = declarator.child_by_field_name("parameters") {
+                push_type_refs(
+                    &mut state.unresolved_refs,
+                    params,
+                    &state.source,
+                    &C_TYPE_REFS,
+                    fn_node_id,
+                    &EdgeKind::Receives,
+                    &state.file_path,
+                );
+            }
+        }
+        if let Some(return_type) = node.child_by_field_name("type") {
+            push_type_refs(
+                &mut state.unresolved_refs,
+                return_type,
+                &state.source,
+                &C_TYPE_REFS,
+                fn_node_id,
+                &EdgeKind::Returns,
+                &state.file_path,
+            );
+        }
+    }
+
+    /// Records the declared parameters and return type of a function
+    /// definition or prototype. Pointer and array modifiers are read from
+    /// the declarators (`char *argv[]` is a `char*[]`), a `(void)` list
+    /// declares no parameters, and `...` is an unnamed optional parameter.
+    fn push_signature(state: &mut ExtractionState, node: TsNode<'_>, fn_node_id: &str) {
+        let Some(declarator) = Self::find_descendant_by_kind(node, "function_declarator") else {
+            return;
+        };
+        let mut params = Vec::new();
+        if let Some(list) = declarator.child_by_field_name("parameters") {
+            let mut cursor = list.walk();
+            for child in list.named_children(&mut cursor) {
+                match child.kind() {
+                    "parameter_declaration" => {
+                        let (name, modifiers) =
+                            Self::split_declarator(state, child.child_by_field_name("declarator"));
+                        let Some(base) = child.child_by_field_name("type") else {
+                            continue;
+                        };
+                        let base = state.node_text(base);
+                        if base == "void" && modifiers.is_empty() {
+                            continue;
+                        }
+                        let ty = format!("{base}{modifiers}");
+                        params.push(param(&name, Some(&ty), None, false));
+                    }
+                    "variadic_parameter" => params.push(param("", None, None, true)),
+                    _ => {}
+                }
+            }
+        }
+        let return_type = node.child_by_field_name("type").map(|t| {
+            let (_, modifiers) =
+                Self::split_declarator(state, node.child_by_field_name("declarator"));
+            format!("{}{modifiers}", state.node_text(t))
+        });
+        state
+            .signatures
+            .extend(signature(fn_node_id, params, return_type.as_deref()));
+    }
+
+    /// Splits a declarator into the name it declares and the pointer and
+    /// array modifiers it applies to the base type, as `*` and `[]`.
+    fn split_declarator(
+        state: &ExtractionState,
+        declarator: Option<TsNode<'_>>,
+    ) -> (String, String) {
+        let (mut pointers, mut arrays) = (String::new(), String::new());
+        let mut current = declarator;
+        while let Some(d) = current {
+            current = match d.kind() {
+                "pointer_declarator" | "abstract_pointer_declarator" => {
+                    pointers.push('*');
+                    d.child_by_field_name("declarator")
+                }
+                "array_declarator" | "abstract_array_declarator" => {
+                    arrays.push_str("[]");
+                    d.child_by_field_name("declarator")
+                }
+                "parenthesized_declarator" | "abstract_parenthesized_declarator" => {
+                    d.named_child(0)
+                }
+                "identifier" => return (state.node_text(d), format!("{pointers}{arrays}")),
+                _ => None,
+            };
+        }
+        (String::new(), format!("{pointers}{arrays}"))
+    }
+
     // -------------------------------------------------------
     // Call site extraction
     // -------------------------------------------------------
@@ -1323,6 +1473,10 @@ impl CExtractor {
                             line: child.start_position().row as u32,
                             column: child.start_position().column as u32,
                             file_path: state.file_path.clone(),
+                            receiver_type: None,
+                            abi: None,
+                            http_method: None,
+                            sql: false,
                         });
                     }
                     // Also recurse into the call expression for nested calls.
@@ -1465,6 +1619,7 @@ impl CExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: state.signatures,
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -1483,4 +1638,12 @@ impl crate::extraction::LanguageExtractor for CExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         CExtractor::extract_source(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
+
+    fn extracts_signatures(&self) -> bool {
+        true
+    }
 }
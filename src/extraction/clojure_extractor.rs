@@ -58,6 +58,12 @@ impl ExtractionState {
 
 impl ClojureExtractor {
     pub fn extract_clojure(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -65,7 +71,7 @@ impl ClojureExtractor {
             Ok(t) => t,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -100,7 +106,7 @@ impl ClojureExtractor {
         Self::visit_children(&mut state, root);
 
         state.node_stack.pop();
-        Self::build_result(state, start)
+        (Self::build_result(state, start), Some(tree))
     }
 
     fn parse_source(source: &str) -> Result<Tree, String> {
@@ -189,6 +195,7 @@ impl ClojureExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -244,6 +251,7 @@ impl ClojureExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -292,6 +300,7 @@ impl ClojureExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -336,6 +345,7 @@ impl ClojureExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -375,6 +385,7 @@ impl ClojureExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -454,6 +465,10 @@ impl ClojureExtractor {
                                 line: child.start_position().row as u32,
                                 column: child.start_position().column as u32,
                                 file_path: state.file_path.clone(),
+                                receiver_type: None,
+                                abi: None,
+                                http_method: None,
+                                sql: false,
                             });
                         }
                         Self::extract_calls(state, child, fn_id, 1);
@@ -476,6 +491,7 @@ impl ClojureExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: Vec::new(),
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -494,4 +510,8 @@ impl crate::extraction::LanguageExtractor for ClojureExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         Self::extract_clojure(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
 }
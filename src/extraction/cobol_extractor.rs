@@ -90,6 +90,12 @@ impl CobolExtractor {
     /// `file_path` is used for qualified names and node IDs (not for I/O).
     /// `source` is the COBOL source code to parse.
     pub fn extract_cobol(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -97,7 +103,7 @@ impl CobolExtractor {
             Ok(tree) => tree,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -135,7 +141,7 @@ impl CobolExtractor {
 
         state.node_stack.pop();
 
-        Self::build_result(state, start)
+        (Self::build_result(state, start), Some(tree))
     }
 
     /// Parse source code into a tree-sitter AST.
@@ -241,6 +247,7 @@ impl CobolExtractor {
                     target: id.clone(),
                     kind: EdgeKind::Contains,
                     line: Some(start_line),
+                    provenance: None,
                 });
             }
 
@@ -372,6 +379,7 @@ impl CobolExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -532,6 +540,7 @@ impl CobolExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -560,6 +569,10 @@ impl CobolExtractor {
                             line: node.start_position().row as u32,
                             column: node.start_position().column as u32,
                             file_path: state.file_path.clone(),
+                            receiver_type: None,
+                            abi: None,
+                            http_method: None,
+                            sql: false,
                         });
                     }
                 }
@@ -586,6 +599,10 @@ impl CobolExtractor {
                                     line: node.start_position().row as u32,
                                     column: node.start_position().column as u32,
                                     file_path: state.file_path.clone(),
+                                    receiver_type: None,
+                                    abi: None,
+                                    http_method: None,
+                                    sql: false,
                                 });
                             }
                         }
@@ -647,6 +664,7 @@ impl CobolExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: Vec::new(),
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -665,4 +683,8 @@ impl crate::extraction::LanguageExtractor for CobolExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         Self::extract_cobol(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
 }
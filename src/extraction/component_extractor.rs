@@ -0,0 +1,658 @@
+/// Single-file component extractor for Vue, Svelte and Astro.
+///
+/// A component file is markup with embedded script sections: `<script>`
+/// blocks, plus the `---` frontmatter of Astro files. The script sections are
+/// handed to [`TypeScriptExtractor`] in a copy of the file with everything
+/// else blanked out, so its nodes keep their real lines and columns. The
+/// file gets a `Component` node that contains the script's top-level
+/// symbols, and the markup is scanned for child components (`Uses`) and
+/// bound event handlers (`Calls`).
+use std::ops::Range;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tree_sitter::{Node as TsNode, Parser};
+
+use crate::extraction::TypeScriptExtractor;
+use crate::types::{
+    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, UnresolvedRef, Visibility,
+};
+
+/// Extracts Vue single-file components (`.vue`).
+pub struct VueExtractor;
+
+/// Extracts Svelte components (`.svelte`).
+pub struct SvelteExtractor;
+
+/// Extracts Astro components (`.astro`).
+pub struct AstroExtractor;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Flavor {
+    Vue,
+    Svelte,
+    Astro,
+}
+
+/// The sections of a component file, as byte ranges.
+struct Sections {
+    scripts: Vec<Range<usize>>,
+    markup: Vec<Range<usize>>,
+}
+
+/// Returns `source` with the bytes of HTML comments replaced by spaces,
+/// keeping newlines so offsets still map to the same lines.
+fn blank_comments(source: &str) -> Vec<u8> {
+    let mut doc = source.as_bytes().to_vec();
+    let mut from = 0;
+    while let Some(start) = find(&doc, b"<!--", from) {
+        let end = find(&doc, b"-->", start + 4).map_or(doc.len(), |e| e + 3);
+        blank(&mut doc[start..end]);
+        from = end;
+    }
+    doc
+}
+
+fn blank(bytes: &mut [u8]) {
+    for b in bytes.iter_mut().filter(|b| **b != b'\n') {
+        *b = b' ';
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack
+        .get(from..)?
+        .windows(needle.len())
+        .position(|w| w.eq_ignore_ascii_case(needle))
+        .map(|i| i + from)
+}
+
+/// Finds the blocks of top-level element `tag` as (whole block, open tag,
+/// content) ranges.
+fn find_blocks(doc: &[u8], tag: &str) -> Vec<(Range<usize>, Range<usize>, Range<usize>)> {
+    let open = format!("<{tag}");
+    let close = format!("</{tag}");
+    let mut blocks = Vec::new();
+    let mut from = 0;
+    while let Some(start) = find(doc, open.as_bytes(), from) {
+        let after = start + open.len();
+        if !doc
+            .get(after)
+            .is_some_and(|b| b.is_ascii_whitespace() || *b == b'>' || *b == b'/')
+        {
+            from = after;
+            continue;
+        }
+        let Some(gt) = tag_end(doc, after) else {
+            break;
+        };
+        let content_start = gt + 1;
+        if doc[gt - 1] == b'/' {
+            blocks.push((start..content_start, start..content_start, gt..gt));
+            from = content_start;
+            continue;
+        }
+        let content_end = find(doc, close.as_bytes(), content_start).unwrap_or(doc.len());
+        let end = find(doc, b">", content_end).map_or(doc.len(), |e| e + 1);
+        blocks.push((start..end, start..content_start, content_start..content_end));
+        from = end;
+    }
+    blocks
+}
+
+/// Returns the position of the `>` closing the tag whose attributes start at
+/// `from`, skipping quoted values and `{...}` expressions.
+fn tag_end(doc: &[u8], from: usize) -> Option<usize> {
+    let mut quote = None;
+    let mut depth = 0usize;
+    for (i, &b) in doc.iter().enumerate().skip(from) {
+        match (quote, b) {
+            (Some(q), _) if b == q => quote = None,
+            (None, b'"' | b'\'' | b'`') => quote = Some(b),
+            (None, b'{') => depth += 1,
+            (None, b'}') => depth = depth.saturating_sub(1),
+            (None, b'>') if depth == 0 => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns the value of attribute `name` in an open tag, if present.
+fn attribute<'a>(open_tag: &'a str, name: &str) -> Option<&'a str> {
+    attributes(open_tag)
+        .into_iter()
+        .find(|a| a.name.eq_ignore_ascii_case(name))
+        .and_then(|a| a.value)
+        .map(|(_, value)| value)
+}
+
+/// True for `<script>` blocks that hold JavaScript or TypeScript, rather
+/// than JSON, templates or other data.
+fn is_code_script(open_tag: &str) -> bool {
+    attribute(open_tag, "type").is_none_or(|t| {
+        matches!(
+            t.to_ascii_lowercase().as_str(),
+            "" | "module" | "text/javascript" | "application/javascript" | "text/typescript"
+        )
+    })
+}
+
+fn sections(doc: &[u8], flavor: Flavor) -> Sections {
+    let mut scripts = Vec::new();
+    let mut skipped = Vec::new();
+
+    // Astro frontmatter: the script between the leading `---` fences.
+    if flavor == Flavor::Astro {
+        let start = doc.iter().position(|b| !b.is_ascii_whitespace());
+        if let Some(start) = start.filter(|&s| doc[s..].starts_with(b"---")) {
+            let content_start = find(doc, b"\n", start).map_or(doc.len(), |e| e + 1);
+            let mut line_start = content_start;
+            let mut fence = None;
+            while line_start < doc.len() {
+                let line_end = find(doc, b"\n", line_start).unwrap_or(doc.len());
+                if doc[line_start..line_end].trim_ascii() == b"---" {
+                    fence = Some((line_start, line_end));
+                    break;
+                }
+                line_start = line_end + 1;
+            }
+            let (content_end, end) = fence.unwrap_or((doc.len(), doc.len()));
+            scripts.push(content_start..content_end);
+            skipped.push(start..end);
+        }
+    }
+
+    for (block, open, content) in find_blocks(doc, "script") {
+        if is_code_script(&String::from_utf8_lossy(&doc[open])) {
+            scripts.push(content);
+        }
+        skipped.push(block);
+    }
+    skipped.extend(
+        find_blocks(doc, "style")
+            .into_iter()
+            .map(|(block, _, _)| block),
+    );
+
+    let markup = if flavor == Flavor::Vue {
+        // The first top-level `<template>` up to the last closing tag, which
+        // also covers nested `<template v-if>` elements.
+        find_blocks(doc, "template")
+            .first()
+            .map(|(_, open, _)| {
+                let end = doc
+                    .windows(b"</template".len())
+                    .rposition(|w| w.eq_ignore_ascii_case(b"</template"))
+                    .filter(|&e| e >= open.end)
+                    .unwrap_or(doc.len());
+                std::iter::once(open.end..end).collect()
+            })
+            .unwrap_or_default()
+    } else {
+        skipped.sort_by_key(|r| r.start);
+        let mut markup = Vec::new();
+        let mut at = 0;
+        for range in skipped {
+            if range.start > at {
+                markup.push(at..range.start);
+            }
+            at = at.max(range.end);
+        }
+        if at < doc.len() {
+            markup.push(at..doc.len());
+        }
+        markup
+    };
+    Sections { scripts, markup }
+}
+
+/// An attribute of a tag: name, and value with the offset it starts at.
+struct Attribute<'a> {
+    name: &'a str,
+    value: Option<(usize, &'a str)>,
+}
+
+/// Parses the attributes of the open tag `tag`, from `<name` up to its
+/// closing `>`. Offsets are relative to `tag`.
+fn attributes(tag: &str) -> Vec<Attribute<'_>> {
+    let bytes = tag.as_bytes();
+    let mut attrs = Vec::new();
+    // Skip the tag name.
+    let mut i = 1;
+    while i < bytes.len() && !bytes[i].is_ascii_whitespace() && !b"/>".contains(&bytes[i]) {
+        i += 1;
+    }
+    loop {
+        while i < bytes.len() && (bytes[i].is_ascii_whitespace() || bytes[i] == b'/') {
+            i += 1;
+        }
+        if i >= bytes.len() || bytes[i] == b'>' {
+            return attrs;
+        }
+        if bytes[i] == b'{' {
+            // A Svelte `{...spread}` or `{shorthand}` attribute.
+            let end = matching_brace(bytes, i).unwrap_or(bytes.len());
+            i = end + 1;
+            continue;
+        }
+        let name_start = i;
+        while i < bytes.len() && !bytes[i].is_ascii_whitespace() && !b"=>/".contains(&bytes[i]) {
+            i += 1;
+        }
+        let name = &tag[name_start..i];
+        let mut value = None;
+        if bytes.get(i) == Some(&b'=') {
+            i += 1;
+            match bytes.get(i) {
+                Some(&q @ (b'"' | b'\'')) => {
+                    let end = tag[i + 1..]
+                        .find(q as char)
+                        .map_or(bytes.len(), |e| e + i + 1);
+                    value = Some((i + 1, &tag[i + 1..end]));
+                    i = end + 1;
+                }
+                Some(b'{') => {
+                    let end = matching_brace(bytes, i).unwrap_or(bytes.len());
+                    value = Some((i + 1, &tag[i + 1..end]));
+                    i = end + 1;
+                }
+                _ => {
+                    let start = i;
+                    while i < bytes.len() && !bytes[i].is_ascii_whitespace() && bytes[i] != b'>' {
+                        i += 1;
+                    }
+                    value = Some((start, &tag[start..i]));
+                }
+            }
+        }
+        if name.is_empty() {
+            i += 1;
+            continue;
+        }
+        attrs.push(Attribute { name, value });
+    }
+}
+
+fn matching_brace(bytes: &[u8], open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, &b) in bytes.iter().enumerate().skip(open) {
+        match b {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// True when `name` binds an event handler: Vue `@click` / `v-on:click`,
+/// Svelte `on:click`, or `onclick={...}` / `onClick={...}` expressions.
+fn is_handler_attribute(name: &str, braced: bool) -> bool {
+    name.starts_with('@')
+        || name.starts_with("v-on:")
+        || name.starts_with("on:")
+        || (braced
+            && name
+                .strip_prefix("on")
+                .is_some_and(|event| event.starts_with(|c: char| c.is_ascii_alphabetic())))
+}
+
+/// Returns the handler an attribute value names: a bare method (`save`,
+/// `store.save`) or a call of one (`save(item)`). Inline statements and
+/// arrow functions name none.
+fn handler_name(value: &str) -> Option<&str> {
+    let value = value.trim();
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    let end = value
+        .char_indices()
+        .find(|&(_, c)| !is_ident(c) && c != '.')
+        .map_or(value.len(), |(i, _)| i);
+    let name = &value[..end];
+    let rest = value[end..].trim_start();
+    let valid = !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+        && !name.ends_with('.')
+        && !name.contains("..");
+    (valid && (rest.is_empty() || (rest.starts_with('(') && rest.ends_with(')')))).then_some(name)
+}
+
+/// Returns the component a tag name refers to: `PascalCase` tags anywhere,
+/// and `kebab-case` tags in Vue, which resolves them to `KebabCase`.
+fn component_name(tag: &str, flavor: Flavor) -> Option<String> {
+    if tag.contains(':') {
+        return None;
+    }
+    if tag.starts_with(|c: char| c.is_ascii_uppercase()) {
+        return Some(tag.to_string());
+    }
+    if flavor == Flavor::Vue && tag.contains('-') {
+        let pascal: String = tag
+            .split('-')
+            .filter(|part| !part.is_empty())
+            .map(|part| {
+                let mut chars = part.chars();
+                chars.next().map_or_else(String::new, |first| {
+                    first.to_ascii_uppercase().to_string() + chars.as_str()
+                })
+            })
+            .collect();
+        return Some(pascal);
+    }
+    None
+}
+
+/// Byte offset to (line, column) conversion.
+struct Lines(Vec<usize>);
+
+impl Lines {
+    fn new(doc: &[u8]) -> Self {
+        let starts = std::iter::once(0)
+            .chain(
+                doc.iter()
+                    .enumerate()
+                    .filter(|(_, b)| **b == b'\n')
+                    .map(|(i, _)| i + 1),
+            )
+            .collect();
+        Self(starts)
+    }
+
+    fn position(&self, offset: usize) -> (u32, u32) {
+        let line = self.0.partition_point(|&start| start <= offset) - 1;
+        (line as u32, (offset - self.0[line]) as u32)
+    }
+}
+
+/// Vue Options API methods: the members of the `methods` object of the
+/// default export, `export default { methods: { save() {} } }`, optionally
+/// wrapped in `defineComponent(...)`. Returns (name, definition node).
+fn options_api_methods<'t>(root: TsNode<'t>, source: &[u8]) -> Vec<(String, TsNode<'t>)> {
+    let mut methods = Vec::new();
+    let mut cursor = root.walk();
+    for statement in root.children(&mut cursor) {
+        if statement.kind() != "export_statement" {
+            continue;
+        }
+        let Some(mut value) = statement.child_by_field_name("value") else {
+            continue;
+        };
+        if value.kind() == "call_expression" {
+            let Some(object) = value
+                .child_by_field_name("arguments")
+                .and_then(|args| args.named_child(0))
+            else {
+                continue;
+            };
+            value = object;
+        }
+        if value.kind() != "object" {
+            continue;
+        }
+        let mut pairs = value.walk();
+        for pair in value.named_children(&mut pairs) {
+            let is_methods = pair.kind() == "pair"
+                && pair
+                    .child_by_field_name("key")
+                    .and_then(|k| k.utf8_text(source).ok())
+                    == Some("methods");
+            let Some(object) = pair
+                .child_by_field_name("value")
+                .filter(|v| is_methods && v.kind() == "object")
+            else {
+                continue;
+            };
+            let mut members = object.walk();
+            for member in object.named_children(&mut members) {
+                let name = match member.kind() {
+                    "method_definition" => member.child_by_field_name("name"),
+                    "pair"
+                        if member.child_by_field_name("value").is_some_and(|v| {
+                            matches!(v.kind(), "function_expression" | "arrow_function")
+                        }) =>
+                    {
+                        member.child_by_field_name("key")
+                    }
+                    _ => None,
+                };
+                if let Some(name) = name.and_then(|n| n.utf8_text(source).ok()) {
+                    methods.push((name.to_string(), member));
+                }
+            }
+        }
+    }
+    methods
+}
+
+fn extract_component(file_path: &str, source: &str, flavor: Flavor) -> ExtractionResult {
+    let doc = blank_comments(source);
+    let sections = sections(&doc, flavor);
+
+    // The scripts in place, with everything around them blanked out.
+    let mut script = vec![b' '; doc.len()];
+    for (i, b) in doc.iter().enumerate() {
+        if *b == b'\n' {
+            script[i] = b'\n';
+        }
+    }
+    for range in &sections.scripts {
+        script[range.clone()].copy_from_slice(&doc[range.clone()]);
+    }
+    // Only whole UTF-8 sequences were copied or blanked.
+    let script = String::from_utf8(script).unwrap_or_default();
+    let mut result = TypeScriptExtractor::extract_typescript(file_path, &script);
+    super::graphql_extractor::extract_embedded(file_path, &script, &mut result);
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let file_id = generate_node_id(file_path, &NodeKind::File, file_path, 0);
+    let file_name = file_path.rsplit('/').next().unwrap_or(file_path);
+    let name = file_name.split('.').next().unwrap_or(file_name).to_string();
+    let name = if name.is_empty() {
+        file_name.to_string()
+    } else {
+        name
+    };
+    let component_id = generate_node_id(file_path, &NodeKind::Component, &name, 0);
+    let component_qn = format!("{file_path}::{name}");
+    let node = |id: String,
+                kind: NodeKind,
+                name: String,
+                qualified_name: String,
+                start: (u32, u32),
+                end: (u32, u32),
+                signature: Option<String>| Node {
+        id,
+        kind,
+        name,
+        qualified_name,
+        file_path: file_path.to_string(),
+        start_line: start.0,
+        end_line: end.0,
+        start_column: start.1,
+        end_column: end.1,
+        signature,
+        docstring: None,
+        visibility: Visibility::Pub,
+        is_async: false,
+        branches: 0,
+        loops: 0,
+        returns: 0,
+        max_nesting: 0,
+        unsafe_blocks: 0,
+        unchecked_calls: 0,
+        assertions: 0,
+        updated_at: timestamp,
+    };
+
+    // The component owns what the scripts declare and reference at the top
+    // level.
+    for edge in &mut result.edges {
+        if edge.kind == EdgeKind::Contains && edge.source == file_id {
+            edge.source.clone_from(&component_id);
+        }
+    }
+    for uref in &mut result.unresolved_refs {
+        if uref.from_node_id == file_id {
+            uref.from_node_id.clone_from(&component_id);
+        }
+    }
+    let last_line = source.lines().count().saturating_sub(1) as u32;
+    result.nodes.insert(
+        1.min(result.nodes.len()),
+        node(
+            component_id.clone(),
+            NodeKind::Component,
+            name.clone(),
+            component_qn.clone(),
+            (0, 0),
+            (last_line, 0),
+            None,
+        ),
+    );
+    result.edges.push(Edge {
+        source: file_id,
+        target: component_id.clone(),
+        kind: EdgeKind::Contains,
+        line: Some(0),
+        provenance: None,
+    });
+
+    if flavor == Flavor::Vue && !sections.scripts.is_empty() {
+        let mut parser = Parser::new();
+        let language = crate::extraction::ts_provider::language("typescript");
+        if parser.set_language(&language).is_ok() {
+            if let Some(tree) = parser.parse(&script, None) {
+                for (method, ts_node) in options_api_methods(tree.root_node(), script.as_bytes()) {
+                    let start = ts_node.start_position();
+                    let end = ts_node.end_position();
+                    let id =
+                        generate_node_id(file_path, &NodeKind::Method, &method, start.row as u32);
+                    let signature = ts_node
+                        .utf8_text(script.as_bytes())
+                        .ok()
+                        .and_then(|t| t.lines().next())
+                        .map(|l| l.trim().to_string());
+                    result.edges.push(Edge {
+                        source: component_id.clone(),
+                        target: id.clone(),
+                        kind: EdgeKind::Contains,
+                        line: Some(start.row as u32),
+                        provenance: None,
+                    });
+                    result.nodes.push(node(
+                        id,
+                        NodeKind::Method,
+                        method.clone(),
+                        format!("{component_qn}::{method}"),
+                        (start.row as u32, start.column as u32),
+                        (end.row as u32, end.column as u32),
+                        signature,
+                    ));
+                }
+            }
+        }
+    }
+
+    let lines = Lines::new(&doc);
+    let text = String::from_utf8_lossy(&doc);
+    let mut reference = |name: &str, kind: EdgeKind, offset: usize| {
+        let (line, column) = lines.position(offset);
+        result.unresolved_refs.push(UnresolvedRef {
+            from_node_id: component_id.clone(),
+            reference_name: name.to_string(),
+            reference_kind: kind,
+            line,
+            column,
+            file_path: file_path.to_string(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
+        });
+    };
+    for range in &sections.markup {
+        let mut at = range.start;
+        while let Some(lt) = find(&doc[..range.end], b"<", at) {
+            at = lt + 1;
+            let name_len = doc[at..range.end]
+                .iter()
+                .position(|b| !(b.is_ascii_alphanumeric() || b"-_.:".contains(b)))
+                .unwrap_or(range.end - at);
+            if name_len == 0 || !doc[at].is_ascii_alphabetic() {
+                continue;
+            }
+            let tag_name = &text[at..at + name_len];
+            if let Some(component) = component_name(tag_name, flavor) {
+                reference(&component, EdgeKind::Uses, at);
+            }
+            let Some(gt) = tag_end(&doc[..range.end], at + name_len) else {
+                break;
+            };
+            let open_tag = &text[lt..=gt];
+            for attr in attributes(open_tag) {
+                let Some((offset, value)) = attr.value else {
+                    continue;
+                };
+                let braced = open_tag.as_bytes().get(offset.wrapping_sub(1)) == Some(&b'{');
+                if !is_handler_attribute(attr.name, braced) {
+                    continue;
+                }
+                if let Some(handler) = handler_name(value) {
+                    let lead = value.len() - value.trim_start().len();
+                    reference(handler, EdgeKind::Calls, lt + offset + lead);
+                }
+            }
+            at = gt + 1;
+        }
+    }
+    result
+}
+
+impl crate::extraction::LanguageExtractor for VueExtractor {
+    fn extensions(&self) -> &[&str] {
+        &["vue"]
+    }
+
+    fn language_name(&self) -> &'static str {
+        "Vue"
+    }
+
+    fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
+        extract_component(file_path, source, Flavor::Vue)
+    }
+}
+
+impl crate::extraction::LanguageExtractor for SvelteExtractor {
+    fn extensions(&self) -> &[&str] {
+        &["svelte"]
+    }
+
+    fn language_name(&self) -> &'static str {
+        "Svelte"
+    }
+
+    fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
+        extract_component(file_path, source, Flavor::Svelte)
+    }
+}
+
+impl crate::extraction::LanguageExtractor for AstroExtractor {
+    fn extensions(&self) -> &[&str] {
+        &["astro"]
+    }
+
+    fn language_name(&self) -> &'static str {
+        "Astro"
+    }
+
+    fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
+        extract_component(file_path, source, Flavor::Astro)
+    }
+}
@@ -0,0 +1,558 @@
+//! Extractors for YAML, TOML and JSON configuration files.
+//!
+//! Every key becomes a `ConfigKey` node named by its key path
+//! (`dependencies.tokio`, `jobs.build.steps[2]`) and nested under its parent
+//! key. Lists of scalars stay on their key; list items holding collections
+//! get a node of their own.
+//!
+//! Well-known schemas get first-class nodes instead: GitHub Actions workflow
+//! jobs and steps, docker-compose services and Kubernetes resources. Jobs and
+//! services are linked to the ones they need with `DependsOn` edges.
+//! Kubernetes resources reference the resources they mount or target through
+//! unresolved refs named `Kind/name` (`ConfigMap/app-config`), so those links
+//! cross files.
+
+use std::collections::HashMap;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use super::config_parser::{parse_json, parse_toml, parse_yaml, ConfigEntry, ConfigValue};
+use crate::extraction::LanguageExtractor;
+use crate::types::{
+    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, UnresolvedRef, Visibility,
+};
+
+/// Generic key nodes emitted per file at most; data files can hold far more
+/// keys than are worth indexing. Schema nodes are always emitted.
+const MAX_KEY_NODES: usize = 2000;
+
+/// Files that are generated listings rather than configuration: only their
+/// file node is emitted.
+const LOCK_FILES: &[&str] = &["package-lock.json", "npm-shrinkwrap.json", "pnpm-lock.yaml"];
+
+/// Extracts key paths and schema nodes from YAML files.
+pub struct YamlExtractor;
+
+/// Extracts key paths from TOML files.
+pub struct TomlExtractor;
+
+/// Extracts key paths and schema nodes from JSON files.
+pub struct JsonExtractor;
+
+impl LanguageExtractor for YamlExtractor {
+    fn extensions(&self) -> &[&str] {
+        &["yaml", "yml"]
+    }
+
+    fn language_name(&self) -> &'static str {
+        "YAML"
+    }
+
+    fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
+        extract_config(file_path, source, parse_yaml)
+    }
+}
+
+impl LanguageExtractor for TomlExtractor {
+    fn extensions(&self) -> &[&str] {
+        &["toml"]
+    }
+
+    fn language_name(&self) -> &'static str {
+        "TOML"
+    }
+
+    fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
+        extract_config(file_path, source, parse_toml)
+    }
+}
+
+impl LanguageExtractor for JsonExtractor {
+    fn extensions(&self) -> &[&str] {
+        &["json"]
+    }
+
+    fn language_name(&self) -> &'static str {
+        "JSON"
+    }
+
+    fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
+        extract_config(file_path, source, parse_json)
+    }
+}
+
+/// The well-known schema a file follows.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Schema {
+    Plain,
+    /// A GitHub Actions workflow (`.github/workflows/*.yml`).
+    Workflow,
+    /// A docker-compose file.
+    Compose,
+}
+
+impl Schema {
+    fn detect(file_path: &str, root: &ConfigValue) -> Self {
+        let is_map = |key: &str| {
+            root.get(key)
+                .is_some_and(|e| matches!(e.value, ConfigValue::Map(_)))
+        };
+        let file_name = file_path.rsplit('/').next().unwrap_or(file_path);
+        if file_path.contains(".github/workflows/") && is_map("jobs") {
+            return Schema::Workflow;
+        }
+        let services = root.get("services").map(|e| &e.value);
+        let has_containers = matches!(services, Some(ConfigValue::Map(entries))
+            if entries.iter().any(|s| s.value.get("image").is_some() || s.value.get("build").is_some()));
+        if is_map("services")
+            && (file_name.starts_with("docker-compose")
+                || file_name.starts_with("compose.")
+                || has_containers)
+        {
+            return Schema::Compose;
+        }
+        Schema::Plain
+    }
+}
+
+fn extract_config(
+    file_path: &str,
+    source: &str,
+    parse: fn(&str) -> Result<ConfigValue, String>,
+) -> ExtractionResult {
+    let start = Instant::now();
+    let mut emitter = Emitter::new(file_path, source);
+    let file_name = file_path.rsplit('/').next().unwrap_or(file_path);
+    let mut errors = Vec::new();
+    if !LOCK_FILES.contains(&file_name) {
+        match parse(source) {
+            Ok(root) => emitter.emit_root(&root),
+            Err(e) => errors.push(format!("{file_path}: {e}")),
+        }
+    }
+    ExtractionResult {
+        nodes: emitter.nodes,
+        edges: emitter.edges,
+        unresolved_refs: emitter.unresolved_refs,
+        signatures: Vec::new(),
+        errors,
+        duration_ms: start.elapsed().as_millis() as u64,
+    }
+}
+
+/// The parent of the entries being emitted.
+struct Parent<'p> {
+    id: &'p str,
+    /// Key path of the parent, empty at the root.
+    path: &'p str,
+}
+
+struct Emitter<'a> {
+    file_path: &'a str,
+    source: &'a str,
+    file_id: String,
+    schema: Schema,
+    timestamp: u64,
+    /// Byte offset of the start of each line.
+    line_starts: Vec<usize>,
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+    unresolved_refs: Vec<UnresolvedRef>,
+    key_nodes: usize,
+    /// Jobs or services by name.
+    named: HashMap<String, String>,
+    /// `needs`/`depends_on` links as (from node id, target name, line).
+    needs: Vec<(String, String, u32)>,
+}
+
+impl<'a> Emitter<'a> {
+    fn new(file_path: &'a str, source: &'a str) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        let mut emitter = Self {
+            file_path,
+            source,
+            file_id: generate_node_id(file_path, &NodeKind::File, file_path, 0),
+            schema: Schema::Plain,
+            timestamp,
+            line_starts,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            unresolved_refs: Vec::new(),
+            key_nodes: 0,
+            named: HashMap::new(),
+            needs: Vec::new(),
+        };
+        let file_node = emitter.node(
+            emitter.file_id.clone(),
+            NodeKind::File,
+            file_path.to_string(),
+            file_path.to_string(),
+            0,
+            source.len(),
+        );
+        emitter.nodes.push(file_node);
+        emitter
+    }
+
+    /// 0-based (line, column) of a byte offset.
+    fn position(&self, offset: usize) -> (u32, u32) {
+        let line = self.line_starts.partition_point(|&s| s <= offset) - 1;
+        (line as u32, (offset - self.line_starts[line]) as u32)
+    }
+
+    fn node(
+        &self,
+        id: String,
+        kind: NodeKind,
+        name: String,
+        qualified_name: String,
+        start: usize,
+        end: usize,
+    ) -> Node {
+        let (start_line, start_column) = self.position(start);
+        let (end_line, end_column) = self.position(end);
+        let signature = (kind != NodeKind::File).then(|| {
+            let line = self.source[start..end].lines().next().unwrap_or("").trim();
+            match line.char_indices().nth(120) {
+                Some((cut, _)) => format!("{}...", &line[..cut]),
+                None => line.to_string(),
+            }
+        });
+        Node {
+            id,
+            kind,
+            name,
+            qualified_name,
+            file_path: self.file_path.to_string(),
+            start_line,
+            end_line,
+            start_column,
+            end_column,
+            signature,
+            docstring: None,
+            visibility: Visibility::Pub,
+            is_async: false,
+            branches: 0,
+            loops: 0,
+            returns: 0,
+            max_nesting: 0,
+            unsafe_blocks: 0,
+            unchecked_calls: 0,
+            assertions: 0,
+            updated_at: self.timestamp,
+        }
+    }
+
+    /// Adds a node contained in `parent_id` and returns its id.
+    fn add(
+        &mut self,
+        parent_id: &str,
+        kind: NodeKind,
+        name: String,
+        qualified_name: String,
+        entry: (usize, usize),
+    ) -> String {
+        let (line, _) = self.position(entry.0);
+        let id = generate_node_id(self.file_path, &kind, &qualified_name, line);
+        let node = self.node(id.clone(), kind, name, qualified_name, entry.0, entry.1);
+        self.nodes.push(node);
+        self.edges.push(Edge {
+            source: parent_id.to_string(),
+            target: id.clone(),
+            kind: EdgeKind::Contains,
+            line: Some(line),
+            provenance: None,
+        });
+        id
+    }
+
+    fn emit_root(&mut self, root: &ConfigValue) {
+        self.schema = Schema::detect(self.file_path, root);
+        let file_id = self.file_id.clone();
+        match root {
+            ConfigValue::Map(entries) => {
+                let parent_id = match k8s_resource_name(root) {
+                    Some(name) => {
+                        let qualified_name = format!("{}::{name}", self.file_path);
+                        let id = self.add(
+                            &file_id,
+                            NodeKind::K8sResource,
+                            name,
+                            qualified_name,
+                            (0, self.source.trim_end().len()),
+                        );
+                        self.k8s_refs(&id, root);
+                        id
+                    }
+                    None => file_id,
+                };
+                let parent = Parent {
+                    id: &parent_id,
+                    path: "",
+                };
+                for entry in entries {
+                    self.walk(entry, 0, &parent);
+                }
+            }
+            ConfigValue::List(items) => {
+                let parent = Parent {
+                    id: &file_id,
+                    path: "",
+                };
+                for (index, item) in items.iter().enumerate() {
+                    self.walk(item, index, &parent);
+                }
+            }
+            ConfigValue::Scalar(_) => {}
+        }
+
+        for (from, name, line) in std::mem::take(&mut self.needs) {
+            if let Some(target) = self.named.get(&name) {
+                self.edges.push(Edge {
+                    source: from,
+                    target: target.clone(),
+                    kind: EdgeKind::DependsOn,
+                    line: Some(line),
+                    provenance: None,
+                });
+            }
+        }
+    }
+
+    /// Emits the node for `entry`, the `index`th entry of `parent`, and its
+    /// children.
+    fn walk(&mut self, entry: &ConfigEntry, index: usize, parent: &Parent<'_>) {
+        let path = match (&entry.key, parent.path.is_empty()) {
+            (Some(key), true) => key.clone(),
+            (Some(key), false) => format!("{}.{key}", parent.path),
+            (None, _) => format!("{}[{index}]", parent.path),
+        };
+        let children: &[ConfigEntry] = match &entry.value {
+            ConfigValue::Map(children) | ConfigValue::List(children) => children,
+            ConfigValue::Scalar(_) if entry.key.is_none() => return,
+            ConfigValue::Scalar(_) => &[],
+        };
+        let qualified_name = format!("{}::{path}", self.file_path);
+        let span = (entry.start, entry.end);
+        let (line, _) = self.position(entry.start);
+        let parent_path = parent.path;
+
+        let id = if let Some(job) = self.schema_key(Schema::Workflow, "jobs", parent_path, entry) {
+            let id = self.add(
+                parent.id,
+                NodeKind::WorkflowJob,
+                job.clone(),
+                qualified_name,
+                span,
+            );
+            self.link_needs(&id, &entry.value, "needs", line);
+            if let Some(workflow) = entry.value.get_str("uses") {
+                self.link_workflow(&id, workflow, line);
+            }
+            self.named.insert(job, id.clone());
+            id
+        } else if self.schema == Schema::Workflow && is_steps_of_job(parent_path) {
+            let name = step_name(&entry.value).unwrap_or_else(|| format!("steps[{index}]"));
+            self.add(
+                parent.id,
+                NodeKind::WorkflowStep,
+                name,
+                qualified_name,
+                span,
+            )
+        } else if let Some(service) =
+            self.schema_key(Schema::Compose, "services", parent_path, entry)
+        {
+            let id = self.add(
+                parent.id,
+                NodeKind::ComposeService,
+                service.clone(),
+                qualified_name,
+                span,
+            );
+            self.link_needs(&id, &entry.value, "depends_on", line);
+            self.link_needs(&id, &entry.value, "links", line);
+            self.named.insert(service, id.clone());
+            id
+        } else if let Some(name) =
+            k8s_resource_name(&entry.value).filter(|_| parent_path.is_empty())
+        {
+            let qualified_name = format!("{}::{name}", self.file_path);
+            let id = self.add(parent.id, NodeKind::K8sResource, name, qualified_name, span);
+            self.k8s_refs(&id, &entry.value);
+            id
+        } else {
+            if self.key_nodes >= MAX_KEY_NODES {
+                return;
+            }
+            self.key_nodes += 1;
+            self.add(
+                parent.id,
+                NodeKind::ConfigKey,
+                path.clone(),
+                qualified_name,
+                span,
+            )
+        };
+
+        let parent = Parent {
+            id: &id,
+            path: &path,
+        };
+        for (index, child) in children.iter().enumerate() {
+            self.walk(child, index, &parent);
+        }
+    }
+
+    /// Returns the key of `entry` when it is a member of the top-level
+    /// `section` mapping of a `schema` file.
+    fn schema_key(
+        &self,
+        schema: Schema,
+        section: &str,
+        parent_path: &str,
+        entry: &ConfigEntry,
+    ) -> Option<String> {
+        (self.schema == schema
+            && parent_path == section
+            && matches!(entry.value, ConfigValue::Map(_)))
+        .then(|| entry.key.clone())
+        .flatten()
+    }
+
+    /// Records the names listed under `key` of `value` (a name, a list of
+    /// names or a mapping keyed by name) as dependencies of `from`.
+    fn link_needs(&mut self, from: &str, value: &ConfigValue, key: &str, line: u32) {
+        let Some(entry) = value.get(key) else {
+            return;
+        };
+        let names: Vec<&str> = match &entry.value {
+            ConfigValue::Scalar(name) => vec![name.as_str()],
+            ConfigValue::List(items) => items
+                .iter()
+                .filter_map(|item| match &item.value {
+                    ConfigValue::Scalar(name) => Some(name.as_str()),
+                    _ => None,
+                })
+                .collect(),
+            ConfigValue::Map(entries) => entries.iter().filter_map(|e| e.key.as_deref()).collect(),
+        };
+        for name in names {
+            // Compose `links` may alias the service: `db:database`.
+            let name = name.split(':').next().unwrap_or(name).trim();
+            if !name.is_empty() {
+                self.needs.push((from.to_string(), name.to_string(), line));
+            }
+        }
+    }
+
+    /// Links a job to the local reusable workflow it calls
+    /// (`uses: ./.github/workflows/deploy.yml`).
+    fn link_workflow(&mut self, from: &str, uses: &str, line: u32) {
+        let Some(path) = uses.strip_prefix("./") else {
+            return;
+        };
+        let path = path.split('@').next().unwrap_or(path);
+        self.edges.push(Edge {
+            source: from.to_string(),
+            target: generate_node_id(path, &NodeKind::File, path, 0),
+            kind: EdgeKind::Uses,
+            line: Some(line),
+            provenance: None,
+        });
+    }
+
+    /// Adds a reference from the resource `from` to each resource its
+    /// manifest names.
+    fn k8s_refs(&mut self, from: &str, manifest: &ConfigValue) {
+        let mut refs = Vec::new();
+        collect_k8s_refs(manifest, &mut refs);
+        for (target, offset) in refs {
+            let (line, column) = self.position(offset);
+            self.unresolved_refs.push(UnresolvedRef {
+                from_node_id: from.to_string(),
+                reference_name: target,
+                reference_kind: EdgeKind::Uses,
+                line,
+                column,
+                file_path: self.file_path.to_string(),
+                receiver_type: None,
+                abi: None,
+                http_method: None,
+                sql: false,
+            });
+        }
+    }
+}
+
+fn is_steps_of_job(parent_path: &str) -> bool {
+    parent_path
+        .strip_prefix("jobs.")
+        .and_then(|rest| rest.strip_suffix(".steps"))
+        .is_some_and(|job| !job.contains(['.', '[']))
+}
+
+/// Names a workflow step by its `name`, `id` or the action it `uses`.
+fn step_name(step: &ConfigValue) -> Option<String> {
+    ["name", "id", "uses"]
+        .iter()
+        .find_map(|key| step.get_str(key))
+        .map(str::to_string)
+}
+
+/// Returns `Kind/name` when `manifest` is a Kubernetes resource.
+fn k8s_resource_name(manifest: &ConfigValue) -> Option<String> {
+    manifest.get_str("apiVersion")?;
+    let kind = manifest.get_str("kind")?;
+    let name = manifest.get("metadata")?.value.get_str("name")?;
+    Some(format!("{kind}/{name}"))
+}
+
+/// Collects the `Kind/name` of the resources a manifest refers to, with the
+/// offset of each reference.
+fn collect_k8s_refs(value: &ConfigValue, refs: &mut Vec<(String, usize)>) {
+    let entries = match value {
+        ConfigValue::Map(entries) | ConfigValue::List(entries) => entries,
+        ConfigValue::Scalar(_) => return,
+    };
+    for entry in entries {
+        let name_of = |key: &str| entry.value.get_scalar(key);
+        let target = match entry.key.as_deref() {
+            Some("configMapRef" | "configMapKeyRef" | "configMap") => {
+                name_of("name").map(|(n, at)| (format!("ConfigMap/{n}"), at))
+            }
+            Some("secretRef" | "secretKeyRef") => {
+                name_of("name").map(|(n, at)| (format!("Secret/{n}"), at))
+            }
+            Some("secret") => name_of("secretName").map(|(n, at)| (format!("Secret/{n}"), at)),
+            Some("persistentVolumeClaim") => {
+                name_of("claimName").map(|(n, at)| (format!("PersistentVolumeClaim/{n}"), at))
+            }
+            Some("serviceAccountName") => match &entry.value {
+                ConfigValue::Scalar(n) if !n.is_empty() => {
+                    Some((format!("ServiceAccount/{n}"), entry.start))
+                }
+                _ => None,
+            },
+            Some("scaleTargetRef" | "roleRef") => entry
+                .value
+                .get_str("kind")
+                .zip(name_of("name"))
+                .map(|(kind, (n, at))| (format!("{kind}/{n}"), at)),
+            // Ingress backends, `networking.k8s.io/v1` style.
+            Some("backend") => entry
+                .value
+                .get("service")
+                .and_then(|service| service.value.get_scalar("name"))
+                .map(|(n, at)| (format!("Service/{n}"), at)),
+            _ => None,
+        };
+        refs.extend(target);
+        collect_k8s_refs(&entry.value, refs);
+    }
+}
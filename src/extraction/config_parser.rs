@@ -0,0 +1,630 @@
+//! Span-keeping parsers for YAML, TOML and JSON.
+//!
+//! The config extractors need the byte range of every key, which the
+//! serde-based parsers drop. TOML's span-aware `DeTable` keeps them; YAML
+//! and JSON are read by the small parsers below into the same
+//! [`ConfigValue`] tree.
+//!
+//! The YAML parser covers what configuration files use: block mappings and
+//! sequences, flow collections, quoted, plain and block scalars, comments,
+//! anchors and tags, and multi-document streams. The JSON parser also
+//! accepts comments and trailing commas (JSONC, as in `tsconfig.json`).
+
+use toml::de::{DeTable, DeValue};
+use toml::Spanned;
+
+/// A parsed configuration value.
+#[derive(Debug)]
+pub(crate) enum ConfigValue {
+    Scalar(String),
+    Map(Vec<ConfigEntry>),
+    List(Vec<ConfigEntry>),
+}
+
+/// A key of a mapping with its value, or an item of a list (`key: None`).
+#[derive(Debug)]
+pub(crate) struct ConfigEntry {
+    pub key: Option<String>,
+    /// Byte range from the key (or item) to the end of the value.
+    pub start: usize,
+    pub end: usize,
+    pub value: ConfigValue,
+}
+
+impl ConfigValue {
+    /// Returns the value of `key` when this is a mapping.
+    pub fn get(&self, key: &str) -> Option<&ConfigEntry> {
+        match self {
+            ConfigValue::Map(entries) => entries.iter().find(|e| e.key.as_deref() == Some(key)),
+            _ => None,
+        }
+    }
+
+    /// Returns the text of `key` when this is a mapping and it holds a
+    /// non-empty scalar.
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get_scalar(key).map(|(text, _)| text)
+    }
+
+    /// Like [`ConfigValue::get_str`], with the offset of the key.
+    pub fn get_scalar(&self, key: &str) -> Option<(&str, usize)> {
+        let entry = self.get(key)?;
+        match &entry.value {
+            ConfigValue::Scalar(s) if !s.is_empty() => Some((s, entry.start)),
+            _ => None,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// TOML
+// ---------------------------------------------------------------------------
+
+/// Parses a TOML document.
+pub(crate) fn parse_toml(source: &str) -> Result<ConfigValue, String> {
+    let table = DeTable::parse(source).map_err(|e| e.to_string())?;
+    Ok(ConfigValue::Map(toml_entries(source, table.get_ref())))
+}
+
+fn toml_entries(source: &str, table: &DeTable<'_>) -> Vec<ConfigEntry> {
+    let mut entries: Vec<ConfigEntry> = table
+        .iter()
+        .map(|(key, value)| {
+            toml_entry(
+                source,
+                Some(key.get_ref().to_string()),
+                key.span().start,
+                value,
+            )
+        })
+        .collect();
+    entries.sort_by_key(|e| e.start);
+    entries
+}
+
+fn toml_entry(
+    source: &str,
+    key: Option<String>,
+    start: usize,
+    value: &Spanned<DeValue<'_>>,
+) -> ConfigEntry {
+    let span = value.span();
+    let value = match value.get_ref() {
+        DeValue::Table(table) => ConfigValue::Map(toml_entries(source, table)),
+        DeValue::Array(items) => ConfigValue::List(
+            items
+                .iter()
+                .map(|item| toml_entry(source, None, item.span().start, item))
+                .collect(),
+        ),
+        DeValue::String(s) => ConfigValue::Scalar(s.to_string()),
+        _ => ConfigValue::Scalar(source.get(span.clone()).unwrap_or("").to_string()),
+    };
+    // Tables opened by `[header]`s extend past the span of their first key.
+    let end = match &value {
+        ConfigValue::Map(children) | ConfigValue::List(children) => {
+            children.iter().map(|c| c.end).fold(span.end, usize::max)
+        }
+        ConfigValue::Scalar(_) => span.end,
+    };
+    ConfigEntry {
+        key,
+        start: start.min(span.start),
+        end,
+        value,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// JSON and YAML flow collections
+// ---------------------------------------------------------------------------
+
+/// Parses a JSON document.
+pub(crate) fn parse_json(source: &str) -> Result<ConfigValue, String> {
+    let mut parser = FlowParser { source, pos: 0 };
+    parser.skip_whitespace();
+    if parser.pos == source.len() {
+        return Ok(ConfigValue::Map(Vec::new()));
+    }
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.pos < source.len() {
+        return Err(parser.error("unexpected trailing characters"));
+    }
+    Ok(value)
+}
+
+/// Recursive-descent parser for JSON and for YAML flow collections, which
+/// are JSON with optional quotes.
+struct FlowParser<'a> {
+    source: &'a str,
+    pos: usize,
+}
+
+impl FlowParser<'_> {
+    fn error(&self, message: &str) -> String {
+        let line = self.source[..self.pos].matches('\n').count() + 1;
+        format!("{message} at line {line}")
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.source.as_bytes().get(self.pos).copied()
+    }
+
+    /// Skips whitespace and `//`, `/* */` and `#` comments.
+    fn skip_whitespace(&mut self) {
+        loop {
+            let rest = &self.source[self.pos..];
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") || trimmed.starts_with('#') {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if let Some(comment) = trimmed.strip_prefix("/*") {
+                self.pos += comment.find("*/").map_or(trimmed.len(), |i| i + 4);
+            } else {
+                return;
+            }
+        }
+    }
+
+    /// Parses a value and returns it with its end offset in `pos`.
+    fn value(&mut self) -> Result<ConfigValue, String> {
+        match self.peek() {
+            Some(b'{') => self.map(),
+            Some(b'[') => self.list(),
+            Some(b'"' | b'\'') => self.quoted().map(ConfigValue::Scalar),
+            Some(_) => Ok(ConfigValue::Scalar(self.bare())),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn map(&mut self) -> Result<ConfigValue, String> {
+        self.pos += 1;
+        let mut entries = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(ConfigValue::Map(entries));
+                }
+                None => return Err(self.error("unclosed object")),
+                Some(_) => {}
+            }
+            let start = self.pos;
+            let key = match self.peek() {
+                Some(b'"' | b'\'') => self.quoted()?,
+                _ => self.bare(),
+            };
+            self.skip_whitespace();
+            let value = if self.peek() == Some(b':') {
+                self.pos += 1;
+                self.skip_whitespace();
+                self.value()?
+            } else {
+                // A YAML flow mapping may list bare keys: `{ a, b }`.
+                ConfigValue::Scalar(String::new())
+            };
+            entries.push(ConfigEntry {
+                key: Some(key),
+                start,
+                end: self.pos,
+                value,
+            });
+            self.separator(b'}')?;
+        }
+    }
+
+    fn list(&mut self) -> Result<ConfigValue, String> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(ConfigValue::List(items));
+                }
+                None => return Err(self.error("unclosed array")),
+                Some(_) => {}
+            }
+            let start = self.pos;
+            let value = self.value()?;
+            items.push(ConfigEntry {
+                key: None,
+                start,
+                end: self.pos,
+                value,
+            });
+            self.separator(b']')?;
+        }
+    }
+
+    /// Consumes the `,` after an element, or leaves `close` in place.
+    fn separator(&mut self, close: u8) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b',') => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(c) if c == close => Ok(()),
+            _ => Err(self.error(&format!("expected ',' or '{}'", close as char))),
+        }
+    }
+
+    /// Parses a double-quoted (JSON escapes) or single-quoted (YAML `''`)
+    /// string.
+    fn quoted(&mut self) -> Result<String, String> {
+        let quote = self.peek().unwrap_or(b'"');
+        let start = self.pos;
+        self.pos += 1;
+        let mut text = String::new();
+        let mut chars = self.source[self.pos..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' if quote == b'"' => {
+                    let Some((_, escaped)) = chars.next() else {
+                        break;
+                    };
+                    match escaped {
+                        'n' => text.push('\n'),
+                        't' => text.push('\t'),
+                        'r' => text.push('\r'),
+                        'u' => {
+                            let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                            text.extend(
+                                u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32),
+                            );
+                        }
+                        other => text.push(other),
+                    }
+                }
+                '\'' if quote == b'\'' => {
+                    if self.source[self.pos + i + 1..].starts_with('\'') {
+                        chars.next();
+                        text.push('\'');
+                    } else {
+                        self.pos += i + 1;
+                        return Ok(text);
+                    }
+                }
+                '"' if quote == b'"' => {
+                    self.pos += i + 1;
+                    return Ok(text);
+                }
+                c => text.push(c),
+            }
+        }
+        self.pos = start;
+        Err(self.error("unterminated string"))
+    }
+
+    /// Parses an unquoted scalar: a JSON number or literal, or a YAML plain
+    /// scalar inside a flow collection.
+    fn bare(&mut self) -> String {
+        let rest = &self.source[self.pos..];
+        let bytes = rest.as_bytes();
+        let mut end = 0;
+        while end < bytes.len() {
+            match bytes[end] {
+                b',' | b']' | b'}' | b'\n' => break,
+                b':' if bytes.get(end + 1).is_none_or(u8::is_ascii_whitespace) => break,
+                _ => end += 1,
+            }
+        }
+        // Never stall on a stray character.
+        let end = end.max(rest.chars().next().map_or(0, char::len_utf8));
+        let text = rest[..end].trim_end();
+        self.pos += text.len().max(1).min(rest.len());
+        text.to_string()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// YAML
+// ---------------------------------------------------------------------------
+
+/// A non-blank YAML line with its comment removed.
+#[derive(Clone, Copy)]
+struct Line<'a> {
+    /// Byte offset of `text` in the source.
+    start: usize,
+    indent: usize,
+    text: &'a str,
+}
+
+impl Line<'_> {
+    fn end(&self) -> usize {
+        self.start + self.text.len()
+    }
+}
+
+/// Parses a YAML stream. A stream of several documents parses as a list
+/// with one item per document.
+pub(crate) fn parse_yaml(source: &str) -> Result<ConfigValue, String> {
+    let mut documents: Vec<Vec<Line<'_>>> = vec![Vec::new()];
+    let mut offset = 0;
+    for raw in source.split_inclusive('\n') {
+        let start = offset;
+        offset += raw.len();
+        let content = raw.trim_end_matches(['\n', '\r']);
+        if content == "---" || content.starts_with("--- ") || content == "..." {
+            if documents.last().is_some_and(|d| !d.is_empty()) {
+                documents.push(Vec::new());
+            }
+            continue;
+        }
+        if content.starts_with('%') {
+            continue; // %YAML and %TAG directives
+        }
+        let indent = content.len() - content.trim_start_matches(' ').len();
+        let text = strip_comment(&content[indent..]).trim_end();
+        if text.is_empty() {
+            continue;
+        }
+        if let Some(document) = documents.last_mut() {
+            document.push(Line {
+                start: start + indent,
+                indent,
+                text,
+            });
+        }
+    }
+    documents.retain(|d| !d.is_empty());
+
+    let mut parsed = Vec::new();
+    for mut lines in documents {
+        let mut pos = 0;
+        let indent = lines[0].indent;
+        let start = lines[0].start;
+        let (value, end) = yaml_block(source, &mut lines, &mut pos, indent)?;
+        if let Some(extra) = lines.get(pos) {
+            let line = source[..extra.start].matches('\n').count() + 1;
+            return Err(format!("unexpected content at line {line}"));
+        }
+        parsed.push(ConfigEntry {
+            key: None,
+            start,
+            end,
+            value,
+        });
+    }
+    Ok(match parsed.len() {
+        0 => ConfigValue::Map(Vec::new()),
+        1 => parsed.remove(0).value,
+        _ => ConfigValue::List(parsed),
+    })
+}
+
+/// Cuts a `#` comment off a line, outside quotes.
+fn strip_comment(text: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    let mut previous = ' ';
+    for (i, c) in text.char_indices() {
+        match quote {
+            Some('"') if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            None if c == '#' && previous.is_whitespace() => return &text[..i],
+            None if matches!(c, '"' | '\'')
+                && (previous.is_whitespace() || "[{,:".contains(previous)) =>
+            {
+                quote = Some(c);
+            }
+            Some(_) | None => {}
+        }
+        previous = c;
+    }
+    text
+}
+
+fn is_sequence_item(text: &str) -> bool {
+    text == "-" || text.starts_with("- ")
+}
+
+/// Splits `key: value` into the key and the byte offset of the value, or
+/// `None` when `text` is not a mapping entry.
+fn split_key(text: &str) -> Option<(String, usize)> {
+    if text.starts_with(['"', '\'']) {
+        let mut parser = FlowParser {
+            source: text,
+            pos: 0,
+        };
+        let key = parser.quoted().ok()?;
+        let rest = &text[parser.pos..];
+        let after = rest.trim_start();
+        let colon = parser.pos + rest.len() - after.len();
+        let value = after.strip_prefix(':')?;
+        if !(value.is_empty() || value.starts_with(' ')) {
+            return None;
+        }
+        return Some((key, colon + 1));
+    }
+    // Flow collections, block indicators, aliases, anchors and tags start
+    // values, not keys.
+    if text.starts_with(['[', '{', '?', '|', '>', '*', '&', '!']) || is_sequence_item(text) {
+        return None;
+    }
+    let bytes = text.as_bytes();
+    let colon = (0..bytes.len())
+        .find(|&i| bytes[i] == b':' && bytes.get(i + 1).is_none_or(|&b| b == b' ' || b == b'\t'))?;
+    let key = text[..colon].trim_end();
+    (!key.is_empty()).then(|| (key.to_string(), colon + 1))
+}
+
+/// Parses the block node starting at `lines[*pos]`, whose lines are
+/// indented by `indent`. Returns the value and its end offset.
+fn yaml_block<'a>(
+    source: &'a str,
+    lines: &mut Vec<Line<'a>>,
+    pos: &mut usize,
+    indent: usize,
+) -> Result<(ConfigValue, usize), String> {
+    let line = lines[*pos];
+    if is_sequence_item(line.text) {
+        return yaml_sequence(source, lines, pos, indent);
+    }
+    if split_key(line.text).is_some() {
+        return yaml_mapping(source, lines, pos, indent);
+    }
+    // A plain scalar on a line of its own may continue on lines at the
+    // same indentation.
+    *pos += 1;
+    Ok(yaml_inline(
+        source,
+        lines,
+        pos,
+        indent.saturating_sub(1),
+        line.start,
+    ))
+}
+
+fn yaml_mapping<'a>(
+    source: &'a str,
+    lines: &mut Vec<Line<'a>>,
+    pos: &mut usize,
+    indent: usize,
+) -> Result<(ConfigValue, usize), String> {
+    let mut entries = Vec::new();
+    let mut end = lines[*pos].end();
+    while let Some(line) = lines.get(*pos).copied() {
+        if line.indent < indent {
+            break;
+        }
+        // Lines that are no entry of this mapping, such as the `{{ if }}`
+        // lines of a Helm template, are skipped.
+        let Some((key, value_offset)) = split_key(line.text).filter(|_| line.indent == indent)
+        else {
+            *pos += 1;
+            continue;
+        };
+        *pos += 1;
+        let rest = line.text[value_offset..].trim_start();
+        let (value, value_end) = if rest.is_empty() {
+            match lines.get(*pos).copied() {
+                Some(next) if next.indent > indent => yaml_block(source, lines, pos, next.indent)?,
+                // A sequence may sit at its key's own indentation.
+                Some(next) if next.indent == indent && is_sequence_item(next.text) => {
+                    yaml_sequence(source, lines, pos, indent)?
+                }
+                _ => (ConfigValue::Scalar(String::new()), line.end()),
+            }
+        } else {
+            let value_start = line.end() - rest.len();
+            yaml_inline(source, lines, pos, indent, value_start)
+        };
+        end = value_end;
+        entries.push(ConfigEntry {
+            key: Some(key),
+            start: line.start,
+            end: value_end,
+            value,
+        });
+    }
+    Ok((ConfigValue::Map(entries), end))
+}
+
+fn yaml_sequence<'a>(
+    source: &'a str,
+    lines: &mut Vec<Line<'a>>,
+    pos: &mut usize,
+    indent: usize,
+) -> Result<(ConfigValue, usize), String> {
+    let mut items = Vec::new();
+    let mut end = lines[*pos].end();
+    while let Some(line) = lines.get(*pos).copied() {
+        if line.indent < indent || (line.indent == indent && !is_sequence_item(line.text)) {
+            break;
+        }
+        if line.indent > indent {
+            *pos += 1;
+            continue;
+        }
+        let rest = line.text[1..].trim_start();
+        let (value, item_end) = if rest.is_empty() {
+            *pos += 1;
+            match lines.get(*pos).copied() {
+                Some(next) if next.indent > indent => yaml_block(source, lines, pos, next.indent)?,
+                _ => (ConfigValue::Scalar(String::new()), line.end()),
+            }
+        } else {
+            // The item's content starts a node of its own, indented to where
+            // it begins: `- name: x` opens a mapping whose keys align with
+            // `name`.
+            let column = indent + line.text.len() - rest.len();
+            lines[*pos] = Line {
+                start: line.end() - rest.len(),
+                indent: column,
+                text: rest,
+            };
+            yaml_block(source, lines, pos, column)?
+        };
+        end = item_end;
+        items.push(ConfigEntry {
+            key: None,
+            start: line.start,
+            end: item_end,
+            value,
+        });
+    }
+    Ok((ConfigValue::List(items), end))
+}
+
+/// Parses a value that starts inside a line, at byte `start`: a flow
+/// collection, a quoted, block or plain scalar. Lines after `lines[*pos -
+/// 1]` that the value spans are consumed.
+fn yaml_inline(
+    source: &str,
+    lines: &[Line<'_>],
+    pos: &mut usize,
+    indent: usize,
+    start: usize,
+) -> (ConfigValue, usize) {
+    let line_end = lines[*pos - 1].end();
+    let text = strip_value_properties(&source[start..line_end]);
+    if text.starts_with(['[', '{', '"', '\'']) {
+        let mut flow = FlowParser {
+            source,
+            pos: line_end - text.len(),
+        };
+        // A template placeholder (`{{ .Values.name }}`) is no flow mapping.
+        if let Ok(value) = flow.value() {
+            // Skip the lines a multi-line flow value or quoted scalar covered.
+            while lines.get(*pos).is_some_and(|l| l.start < flow.pos) {
+                *pos += 1;
+            }
+            return (value, flow.pos);
+        }
+    }
+    // Block scalars and multi-line plain scalars continue on the more
+    // indented lines that follow.
+    let block = text.starts_with(['|', '>']);
+    let mut scalar = if block {
+        String::new()
+    } else {
+        text.to_string()
+    };
+    let mut end = line_end;
+    while let Some(next) = lines.get(*pos).filter(|l| l.indent > indent) {
+        if !scalar.is_empty() {
+            scalar.push(if block { '\n' } else { ' ' });
+        }
+        scalar.push_str(next.text);
+        end = next.end();
+        *pos += 1;
+    }
+    (ConfigValue::Scalar(scalar), end)
+}
+
+/// Drops the `&anchor` and `!tag` properties in front of a value.
+fn strip_value_properties(mut text: &str) -> &str {
+    while text.starts_with(['&', '!']) {
+        text = text
+            .split_once(' ')
+            .map_or("", |(_, rest)| rest)
+            .trim_start();
+    }
+    text
+}
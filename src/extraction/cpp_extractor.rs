@@ -7,8 +7,11 @@ use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tree_sitter::{Node as TsNode, Parser, Tree};
 
 use crate::extraction::complexity::{count_complexity, CPP_COMPLEXITY};
+use crate::extraction::signatures::{param, signature};
+use crate::extraction::type_refs::{push_type_refs, CPP_TYPE_REFS};
 use crate::types::{
-    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, UnresolvedRef, Visibility,
+    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, Signature, UnresolvedRef,
+    Visibility,
 };
 
 /// Extracts code graph nodes and edges from C++ source files using tree-sitter.
@@ -19,6 +22,7 @@ struct ExtractionState {
     nodes: Vec<Node>,
     edges: Vec<Edge>,
     unresolved_refs: Vec<UnresolvedRef>,
+    signatures: Vec<Signature>,
     errors: Vec<String>,
     /// Stack of (name, `node_id`) for building qualified names and parent edges.
     node_stack: Vec<(String, String)>,
@@ -44,6 +48,7 @@ impl ExtractionState {
             nodes: Vec::new(),
             edges: Vec::new(),
             unresolved_refs: Vec::new(),
+            signatures: Vec::new(),
             errors: Vec::new(),
             node_stack: Vec::new(),
             file_path: file_path.to_string(),
@@ -80,6 +85,12 @@ impl ExtractionState {
 impl CppExtractor {
     /// Extract code graph nodes and edges from a C++ source file.
     pub fn extract_source(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -87,7 +98,7 @@ impl CppExtractor {
             Ok(tree) => tree,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -125,7 +136,7 @@ impl CppExtractor {
 
         state.node_stack.pop();
 
-        Self::build_result(state, start)
+        (Self::build_result(state, start), Some(tree))
     }
 
     /// Parse source code into a tree-sitter AST.
@@ -262,10 +273,13 @@ impl CppExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
         Self::extract_annotations(state, node, &id);
+        Self::extract_type_refs(state, node, &id);
+        Self::push_signature(state, node, &id);
 
         // Extract call sites from the function body.
         if let Some(body) = Self::find_child_by_kind(node, "compound_statement") {
@@ -342,9 +356,13 @@ impl CppExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
+        Self::extract_type_refs(state, node, &id);
+        Self::push_signature(state, node, &id);
+
         if let Some(body) = Self::find_child_by_kind(node, "compound_statement") {
             Self::extract_call_sites(state, body, &id);
         }
@@ -395,6 +413,7 @@ impl CppExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -547,11 +566,15 @@ impl CppExtractor {
                 if let Some(parent_id) = state.parent_node_id() {
                     state.edges.push(Edge {
                         source: parent_id.to_string(),
-                        target: id,
+                        target: id.clone(),
                         kind: EdgeKind::Contains,
                         line: Some(start_line),
+                        provenance: None,
                     });
                 }
+
+                Self::extract_type_refs(state, node, &id);
+                Self::push_signature(state, node, &id);
                 return;
             }
         }
@@ -605,10 +628,13 @@ impl CppExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
         Self::extract_annotations(state, node, &id);
+        Self::extract_type_refs(state, node, &id);
+        Self::push_signature(state, node, &id);
     }
 
     /// Visit a field-like declaration inside a class body (not a function).
@@ -652,11 +678,24 @@ impl CppExtractor {
         if let Some(parent_id) = state.parent_node_id() {
             state.edges.push(Edge {
                 source: parent_id.to_string(),
-                target: id,
+                target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
+
+        if let Some(field_type) = node.child_by_field_name("type") {
+            push_type_refs(
+                &mut state.unresolved_refs,
+                field_type,
+                &state.source,
+                &CPP_TYPE_REFS,
+                &id,
+                &EdgeKind::TypeOf,
+                &state.file_path,
+            );
+        }
     }
 
     /// Extract a function prototype (declaration without body).
@@ -711,10 +750,13 @@ impl CppExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
         Self::extract_annotations(state, node, &id);
+        Self::extract_type_refs(state, node, &id);
+        Self::push_signature(state, node, &id);
     }
 
     /// Extract a global variable declaration.
@@ -770,6 +812,7 @@ impl CppExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -881,6 +924,7 @@ impl CppExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -961,6 +1005,7 @@ impl CppExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -1065,11 +1110,24 @@ impl CppExtractor {
         if let Some(parent_id) = state.parent_node_id() {
             state.edges.push(Edge {
                 source: parent_id.to_string(),
-                target: id,
+                target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
+
+        if let Some(field_type) = node.child_by_field_name("type") {
+            push_type_refs(
+                &mut state.unresolved_refs,
+                field_type,
+                &state.source,
+                &CPP_TYPE_REFS,
+                &id,
+                &EdgeKind::TypeOf,
+                &state.file_path,
+            );
+        }
     }
 
     // visit_field_method_declaration was identical to visit_class_method_declaration
@@ -1146,6 +1204,7 @@ impl CppExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -1210,11 +1269,13 @@ impl CppExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
-        // If the template wraps a function, extract call sites
+        // If the template wraps a function, extract its signature and call sites
         if let Some(func_def) = Self::find_child_by_kind(node, "function_definition") {
+            Self::push_signature(state, func_def, &id);
             if let Some(body) = Self::find_child_by_kind(func_def, "compound_statement") {
                 Self::extract_call_sites(state, body, &id);
             }
@@ -1319,6 +1380,7 @@ impl CppExtractor {
                 target: typedef_id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -1383,6 +1445,7 @@ impl CppExtractor {
                 target: typedef_id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -1447,6 +1510,7 @@ impl CppExtractor {
                 target: typedef_id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -1503,6 +1567,7 @@ impl CppExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -1572,6 +1637,7 @@ impl CppExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -1685,6 +1751,7 @@ impl CppExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -1740,6 +1807,7 @@ impl CppExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -1791,6 +1859,7 @@ impl CppExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -1852,6 +1921,7 @@ impl CppExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -1908,6 +1978,7 @@ impl CppExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -1978,6 +2049,7 @@ impl CppExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -2002,6 +2074,10 @@ impl CppExtractor {
                             line: child.start_position().row as u32,
                             column: child.start_position().column as u32,
                             file_path: state.file_path.clone(),
+                            receiver_type: None,
+                            abi: None,
+                            http_method: None,
+                            sql: false,
                         });
                     }
                     if child.kind() == "qualified_identifier" {
@@ -2013,6 +2089,10 @@ impl CppExtractor {
                             line: child.start_position().row as u32,
                             column: child.start_position().column as u32,
                             file_path: state.file_path.clone(),
+                            receiver_type: None,
+                            abi: None,
+                            http_method: None,
+                            sql: false,
                         });
                     }
                     if !cursor.goto_next_sibling() {
@@ -2023,6 +2103,138 @@ impl CppExtractor {
         }
     }
 
+    // -------------------------------------------------------
+    // Type reference extraction
+    // -------------------------------------------------------
+
+    /// Extract type references from parameter types and return type.
+    ///
+    /// The `parameters` of the `function_declarator` become `Receives` refs and
+    /// the declaration's `type` field (absent on constructors) becomes
+    /// `Returns` refs. Namespace qualifiers are dropped, so `ns::Account`
+    /// reports `Account`.
+    fn extract_type_refs(state: &mut ExtractionState, node: TsNode<'_>, fn_node_id: &str) {
+        if let Some(declarator) = Self::find_descendant_by_kind(node, "function_declarator") {
+            if let Some(params) = declarator.child_by_field_name("parameters") {
+                push_type_refs(
+                    &mut state.unresolved_refs,
+                    params,
+                    &state.source,
+                    &CPP_TYPE_REFS,
+                    fn_node_id,
+                    &EdgeKind::Receives,
+                    &state.file_path,
+                );
+            }
+        }
+        if let Some(return_type) = node.child_by_field_name("type") {
+            push_type_refs(
+                &mut state.unresolved_refs,
+                return_type,
+                &state.source,
+                &CPP_TYPE_REFS,
+                fn_node_id,
+                &EdgeKind::Returns,
+                &state.file_path,
+            );
+        }
+    }
+
+    /// Records the declared parameters and return type of a function or
+    /// method. Pointer, reference and array modifiers are read from the
+    /// declarators (`const std::string &name` is a `std::string&`), a
+    /// trailing return type (`auto f() -> int`) replaces `auto`, and
+    /// parameters with a default value or a `...` pack are optional.
+    fn push_signature(state: &mut ExtractionState, node: TsNode<'_>, fn_node_id: &str) {
+        let Some(declarator) = Self::find_descendant_by_kind(node, "function_declarator") else {
+            return;
+        };
+        let mut params = Vec::new();
+        if let Some(list) = declarator.child_by_field_name("parameters") {
+            let mut cursor = list.walk();
+            for child in list.named_children(&mut cursor) {
+                let variadic = matches!(
+                    child.kind(),
+                    "variadic_parameter_declaration" | "variadic_parameter"
+                );
+                if !variadic
+                    && !matches!(
+                        child.kind(),
+                        "parameter_declaration" | "optional_parameter_declaration"
+                    )
+                {
+                    continue;
+                }
+                let (name, modifiers) =
+                    Self::split_declarator(state, child.child_by_field_name("declarator"));
+                let base = child
+                    .child_by_field_name("type")
+                    .map(|t| state.node_text(t));
+                if base.as_deref() == Some("void") && modifiers.is_empty() {
+                    continue;
+                }
+                let ty = base.map(|base| {
+                    let ty = format!("{base}{modifiers}");
+                    if variadic {
+                        format!("{ty}...")
+                    } else {
+                        ty
+                    }
+                });
+                let default = child
+                    .child_by_field_name("default_value")
+                    .map(|v| state.node_text(v));
+                params.push(param(&name, ty.as_deref(), default.as_deref(), variadic));
+            }
+        }
+        let trailing = Self::find_child_by_kind(declarator, "trailing_return_type")
+            .and_then(|t| t.named_child(0))
+            .map(|t| state.node_text(t));
+        let return_type = trailing.or_else(|| {
+            node.child_by_field_name("type").map(|t| {
+                let (_, modifiers) =
+                    Self::split_declarator(state, node.child_by_field_name("declarator"));
+                format!("{}{modifiers}", state.node_text(t))
+            })
+        });
+        state
+            .signatures
+            .extend(signature(fn_node_id, params, return_type.as_deref()));
+    }
+
+    /// Splits a declarator into the name it declares and the pointer,
+    /// reference and array modifiers it applies to the base type, as `*`,
+    /// `&` and `[]`.
+    fn split_declarator(
+        state: &ExtractionState,
+        declarator: Option<TsNode<'_>>,
+    ) -> (String, String) {
+        let (mut prefix, mut arrays) = (String::new(), String::new());
+        let mut current = declarator;
+        while let Some(d) = current {
+            current = match d.kind() {
+                "pointer_declarator" | "abstract_pointer_declarator" => {
+                    prefix.push('*');
+                    d.child_by_field_name("declarator")
+                }
+                "reference_declarator" | "abstract_reference_declarator" => {
+                    prefix.push('&');
+                    d.named_child(0)
+                }
+                "array_declarator" | "abstract_array_declarator" => {
+                    arrays.push_str("[]");
+                    d.child_by_field_name("declarator")
+                }
+                "parenthesized_declarator"
+                | "abstract_parenthesized_declarator"
+                | "variadic_declarator" => d.named_child(0),
+                "identifier" => return (state.node_text(d), format!("{prefix}{arrays}")),
+                _ => None,
+            };
+        }
+        (String::new(), format!("{prefix}{arrays}"))
+    }
+
     // -------------------------------------------------------
     // Call site extraction
     // -------------------------------------------------------
@@ -2043,6 +2255,10 @@ impl CppExtractor {
                             line: child.start_position().row as u32,
                             column: child.start_position().column as u32,
                             file_path: state.file_path.clone(),
+                            receiver_type: None,
+                            abi: None,
+                            http_method: None,
+                            sql: false,
                         });
                     }
                     Self::extract_call_sites(state, child, fn_node_id);
@@ -2266,6 +2482,10 @@ impl CppExtractor {
                         line: start_line,
                         column: start_column,
                         file_path: state.file_path.clone(),
+                        receiver_type: None,
+                        abi: None,
+                        http_method: None,
+                        sql: false,
                     });
 
                     // Direct Annotates edge from annotation to target.
@@ -2274,6 +2494,7 @@ impl CppExtractor {
                         target: target_id.to_string(),
                         kind: EdgeKind::Annotates,
                         line: Some(start_line),
+                        provenance: None,
                     });
                 }
                 if !cursor.goto_next_sibling() {
@@ -2299,6 +2520,7 @@ impl CppExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: state.signatures,
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -2317,4 +2539,12 @@ impl crate::extraction::LanguageExtractor for CppExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         CppExtractor::extract_source(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
+
+    fn extracts_signatures(&self) -> bool {
+        true
+    }
 }
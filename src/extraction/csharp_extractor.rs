@@ -6,8 +6,12 @@ use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tree_sitter::{Node as TsNode, Parser, Tree};
 
 use crate::extraction::complexity::{count_complexity, CSHARP_COMPLEXITY};
+use crate::extraction::receivers::{receiver_type, TypeDecls, CSHARP_RECEIVERS};
+use crate::extraction::signatures::{param, signature};
+use crate::extraction::type_refs::{push_type_refs, CSHARP_TYPE_REFS};
 use crate::types::{
-    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, UnresolvedRef, Visibility,
+    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, Signature, UnresolvedRef,
+    Visibility,
 };
 
 /// Extracts code graph nodes and edges from C# source files using tree-sitter.
@@ -18,11 +22,14 @@ struct ExtractionState {
     nodes: Vec<Node>,
     edges: Vec<Edge>,
     unresolved_refs: Vec<UnresolvedRef>,
+    signatures: Vec<Signature>,
     errors: Vec<String>,
     /// Stack of (name, `node_id`) for building qualified names and parent edges.
     node_stack: Vec<(String, String)>,
     file_path: String,
     source: Vec<u8>,
+    /// Type declarations of the file, for receiver inference.
+    type_decls: TypeDecls,
     timestamp: u64,
     /// Track nesting depth to distinguish inner classes from top-level classes.
     class_depth: usize,
@@ -38,10 +45,12 @@ impl ExtractionState {
             nodes: Vec::new(),
             edges: Vec::new(),
             unresolved_refs: Vec::new(),
+            signatures: Vec::new(),
             errors: Vec::new(),
             node_stack: Vec::new(),
             file_path: file_path.to_string(),
             source: source.as_bytes().to_vec(),
+            type_decls: TypeDecls::default(),
             timestamp,
             class_depth: 0,
         }
@@ -75,6 +84,12 @@ impl CSharpExtractor {
     /// `file_path` is used for qualified names and node IDs (not for I/O).
     /// `source` is the C# source code to parse.
     pub fn extract_csharp(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -82,7 +97,7 @@ impl CSharpExtractor {
             Ok(tree) => tree,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -116,11 +131,12 @@ impl CSharpExtractor {
 
         // Walk the AST.
         let root = tree.root_node();
+        state.type_decls = TypeDecls::new(root, &state.source, &CSHARP_RECEIVERS);
         Self::visit_children(&mut state, root);
 
         state.node_stack.pop();
 
-        Self::build_result(state, start)
+        (Self::build_result(state, start), Some(tree))
     }
 
     /// Parse source code into a tree-sitter AST.
@@ -221,6 +237,7 @@ impl CSharpExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -290,6 +307,7 @@ impl CSharpExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -301,6 +319,10 @@ impl CSharpExtractor {
             line: start_line,
             column: start_column,
             file_path: state.file_path.clone(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
         });
     }
 
@@ -356,6 +378,7 @@ impl CSharpExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -420,6 +443,7 @@ impl CSharpExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -481,6 +505,7 @@ impl CSharpExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -542,6 +567,7 @@ impl CSharpExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -626,6 +652,7 @@ impl CSharpExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -686,12 +713,17 @@ impl CSharpExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
         // Extract attributes on this method.
         Self::extract_attributes_from_declaration(state, node, &id);
 
+        // Types named by the parameters and the return type.
+        Self::extract_type_refs(state, node, &id);
+        Self::push_signature(state, node, &id);
+
         // Extract call sites from the method body.
         if let Some(body) = node.child_by_field_name("body") {
             Self::extract_call_sites(state, body, &id);
@@ -744,12 +776,17 @@ impl CSharpExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
         // Extract attributes on this constructor.
         Self::extract_attributes_from_declaration(state, node, &id);
 
+        // Types named by the parameters.
+        Self::extract_type_refs(state, node, &id);
+        Self::push_signature(state, node, &id);
+
         // Extract call sites from the constructor body.
         if let Some(body) = node.child_by_field_name("body") {
             Self::extract_call_sites(state, body, &id);
@@ -774,10 +811,8 @@ impl CSharpExtractor {
         );
 
         // Extract the type from the type field
-        let type_str = node
-            .child_by_field_name("type")
-            .map(|n| state.node_text(n))
-            .unwrap_or_default();
+        let type_node = node.child_by_field_name("type");
+        let type_str = type_node.map(|n| state.node_text(n)).unwrap_or_default();
         let sig = format!("{type_str} {name}");
 
         let graph_node = Node {
@@ -809,11 +844,24 @@ impl CSharpExtractor {
         if let Some(parent_id) = state.parent_node_id() {
             state.edges.push(Edge {
                 source: parent_id.to_string(),
-                target: id,
+                target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
+
+        if let Some(type_node) = type_node {
+            push_type_refs(
+                &mut state.unresolved_refs,
+                type_node,
+                &state.source,
+                &CSHARP_TYPE_REFS,
+                &id,
+                &EdgeKind::TypeOf,
+                &state.file_path,
+            );
+        }
     }
 
     /// Extract field declarations.
@@ -848,6 +896,7 @@ impl CSharpExtractor {
         let start_column = field_decl.start_position().column as u32;
         let end_column = field_decl.end_position().column as u32;
         let signature_text = state.node_text(field_decl).trim().to_string();
+        let field_type = node.child_by_field_name("type");
         let mut cursor = node.walk();
         if cursor.goto_first_child() {
             loop {
@@ -910,11 +959,24 @@ impl CSharpExtractor {
                     if let Some(parent_id) = state.parent_node_id() {
                         state.edges.push(Edge {
                             source: parent_id.to_string(),
-                            target: id,
+                            target: id.clone(),
                             kind: EdgeKind::Contains,
                             line: Some(start_line),
+                            provenance: None,
                         });
                     }
+
+                    if let Some(field_type) = field_type {
+                        push_type_refs(
+                            &mut state.unresolved_refs,
+                            field_type,
+                            &state.source,
+                            &CSHARP_TYPE_REFS,
+                            &id,
+                            &EdgeKind::TypeOf,
+                            &state.file_path,
+                        );
+                    }
                 }
                 if !cursor.goto_next_sibling() {
                     break;
@@ -968,6 +1030,7 @@ impl CSharpExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -1030,6 +1093,7 @@ impl CSharpExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -1086,6 +1150,7 @@ impl CSharpExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -1146,6 +1211,10 @@ impl CSharpExtractor {
                         line: start_line,
                         column: start_column,
                         file_path: state.file_path.clone(),
+                        receiver_type: None,
+                        abi: None,
+                        http_method: None,
+                        sql: false,
                     });
 
                     // If we found the target, create a direct Annotates edge.
@@ -1155,6 +1224,7 @@ impl CSharpExtractor {
                             target: tid.clone(),
                             kind: EdgeKind::Annotates,
                             line: Some(start_line),
+                            provenance: None,
                         });
                     }
                 }
@@ -1409,6 +1479,10 @@ impl CSharpExtractor {
                         line: child.start_position().row as u32,
                         column: child.start_position().column as u32,
                         file_path: state.file_path.clone(),
+                        receiver_type: None,
+                        abi: None,
+                        http_method: None,
+                        sql: false,
                     });
                 }
                 if !cursor.goto_next_sibling() {
@@ -1494,6 +1568,7 @@ impl CSharpExtractor {
                         target: target_id.to_string(),
                         kind: EdgeKind::Annotates,
                         line: Some(start_line),
+                        provenance: None,
                     });
 
                     // Contains edge from parent.
@@ -1503,6 +1578,7 @@ impl CSharpExtractor {
                             target: id,
                             kind: EdgeKind::Contains,
                             line: Some(start_line),
+                            provenance: None,
                         });
                     }
                 }
@@ -1591,6 +1667,82 @@ impl CSharpExtractor {
         None
     }
 
+    /// Extract type references from parameter types and return type.
+    ///
+    /// Each parameter's `type` field becomes `Receives` refs and the method's
+    /// `returns` field (absent on constructors) becomes `Returns` refs. Only the
+    /// `type` fields are walked because parameter names are `identifier`s too.
+    fn extract_type_refs(state: &mut ExtractionState, node: TsNode<'_>, fn_node_id: &str) {
+        if let Some(params) = node.child_by_field_name("parameters") {
+            let mut cursor = params.walk();
+            for param in params.named_children(&mut cursor) {
+                if let Some(param_type) = param.child_by_field_name("type") {
+                    push_type_refs(
+                        &mut state.unresolved_refs,
+                        param_type,
+                        &state.source,
+                        &CSHARP_TYPE_REFS,
+                        fn_node_id,
+                        &EdgeKind::Receives,
+                        &state.file_path,
+                    );
+                }
+            }
+        }
+        if let Some(return_type) = node.child_by_field_name("returns") {
+            push_type_refs(
+                &mut state.unresolved_refs,
+                return_type,
+                &state.source,
+                &CSHARP_TYPE_REFS,
+                fn_node_id,
+                &EdgeKind::Returns,
+                &state.file_path,
+            );
+        }
+    }
+
+    /// Records the declared parameters and return type of a method or
+    /// constructor. Parameters with a default value and the `params` array
+    /// are optional.
+    fn push_signature(state: &mut ExtractionState, node: TsNode<'_>, fn_node_id: &str) {
+        let mut params = Vec::new();
+        if let Some(list) = node.child_by_field_name("parameters") {
+            let mut cursor = list.walk();
+            for child in list.named_children(&mut cursor) {
+                if child.kind() != "parameter" {
+                    continue;
+                }
+                let name = child
+                    .child_by_field_name("name")
+                    .map(|n| state.node_text(n))
+                    .unwrap_or_default();
+                let ty = child
+                    .child_by_field_name("type")
+                    .map(|t| state.node_text(t));
+                let mut inner = child.walk();
+                let parts: Vec<TsNode<'_>> = child.children(&mut inner).collect();
+                let default = parts
+                    .iter()
+                    .position(|c| c.kind() == "=")
+                    .and_then(|i| parts.get(i + 1))
+                    .map(|v| state.node_text(*v));
+                params.push(param(&name, ty.as_deref(), default.as_deref(), false));
+            }
+            // A `params` array is declared by the list itself.
+            if let Some(name) = list.child_by_field_name("name") {
+                let ty = list.child_by_field_name("type").map(|t| state.node_text(t));
+                params.push(param(&state.node_text(name), ty.as_deref(), None, true));
+            }
+        }
+        let return_type = node
+            .child_by_field_name("returns")
+            .map(|t| state.node_text(t));
+        state
+            .signatures
+            .extend(signature(fn_node_id, params, return_type.as_deref()));
+    }
+
     /// Recursively find `invocation_expression` nodes and create unresolved Calls references.
     fn extract_call_sites(state: &mut ExtractionState, node: TsNode<'_>, fn_node_id: &str) {
         let mut cursor = node.walk();
@@ -1600,6 +1752,13 @@ impl CSharpExtractor {
                 match child.kind() {
                     "invocation_expression" => {
                         let callee_name = Self::extract_invocation_name(state, child);
+                        let receiver = receiver_type(
+                            child,
+                            &callee_name,
+                            &state.source,
+                            &state.type_decls,
+                            &CSHARP_RECEIVERS,
+                        );
                         state.unresolved_refs.push(UnresolvedRef {
                             from_node_id: fn_node_id.to_string(),
                             reference_name: callee_name,
@@ -1607,6 +1766,10 @@ impl CSharpExtractor {
                             line: child.start_position().row as u32,
                             column: child.start_position().column as u32,
                             file_path: state.file_path.clone(),
+                            receiver_type: receiver,
+                            abi: None,
+                            http_method: None,
+                            sql: false,
                         });
                         // Recurse for nested calls inside arguments.
                         Self::extract_call_sites(state, child, fn_node_id);
@@ -1620,6 +1783,10 @@ impl CSharpExtractor {
                             line: child.start_position().row as u32,
                             column: child.start_position().column as u32,
                             file_path: state.file_path.clone(),
+                            receiver_type: None,
+                            abi: None,
+                            http_method: None,
+                            sql: false,
                         });
                         Self::extract_call_sites(state, child, fn_node_id);
                     }
@@ -1682,6 +1849,7 @@ impl CSharpExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: state.signatures,
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -1700,4 +1868,12 @@ impl crate::extraction::LanguageExtractor for CSharpExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         CSharpExtractor::extract_csharp(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
+
+    fn extracts_signatures(&self) -> bool {
+        true
+    }
 }
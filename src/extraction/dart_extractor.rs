@@ -75,6 +75,12 @@ impl DartExtractor {
     /// `file_path` is used for qualified names and node IDs (not for I/O).
     /// `source` is the Dart source code to parse.
     pub fn extract_dart(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -82,7 +88,7 @@ impl DartExtractor {
             Ok(tree) => tree,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -120,7 +126,7 @@ impl DartExtractor {
 
         state.node_stack.pop();
 
-        Self::build_result(state, start)
+        (Self::build_result(state, start), Some(tree))
     }
 
     /// Parse source code into a tree-sitter AST.
@@ -217,6 +223,7 @@ impl DartExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -266,6 +273,7 @@ impl DartExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -276,6 +284,10 @@ impl DartExtractor {
             line: start_line,
             column: start_column,
             file_path: state.file_path.clone(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
         });
     }
 
@@ -368,6 +380,7 @@ impl DartExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -438,6 +451,7 @@ impl DartExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -452,6 +466,10 @@ impl DartExtractor {
                     line: superclass.start_position().row as u32,
                     column: superclass.start_position().column as u32,
                     file_path: state.file_path.clone(),
+                    receiver_type: None,
+                    abi: None,
+                    http_method: None,
+                    sql: false,
                 });
             }
         }
@@ -518,6 +536,7 @@ impl DartExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -583,6 +602,7 @@ impl DartExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -646,6 +666,7 @@ impl DartExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -723,6 +744,7 @@ impl DartExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -776,6 +798,7 @@ impl DartExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -985,6 +1008,7 @@ impl DartExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -1052,6 +1076,7 @@ impl DartExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -1141,6 +1166,7 @@ impl DartExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -1211,6 +1237,7 @@ impl DartExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -1328,6 +1355,7 @@ impl DartExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -1408,6 +1436,10 @@ impl DartExtractor {
                                 line: child.start_position().row as u32,
                                 column: child.start_position().column as u32,
                                 file_path: state.file_path.clone(),
+                                receiver_type: None,
+                                abi: None,
+                                http_method: None,
+                                sql: false,
                             });
                         }
                     }
@@ -1430,6 +1462,10 @@ impl DartExtractor {
                                     line: child.start_position().row as u32,
                                     column: child.start_position().column as u32,
                                     file_path: state.file_path.clone(),
+                                    receiver_type: None,
+                                    abi: None,
+                                    http_method: None,
+                                    sql: false,
                                 });
                             }
                         }
@@ -1658,6 +1694,10 @@ impl DartExtractor {
             line: start_line,
             column: start_column,
             file_path: state.file_path.clone(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
         });
 
         // Direct Annotates edge from the annotation to the target.
@@ -1666,6 +1706,7 @@ impl DartExtractor {
             target: target_id.to_string(),
             kind: EdgeKind::Annotates,
             line: Some(start_line),
+            provenance: None,
         });
     }
 
@@ -1694,6 +1735,7 @@ impl DartExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: Vec::new(),
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -1712,4 +1754,8 @@ impl crate::extraction::LanguageExtractor for DartExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         DartExtractor::extract_dart(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
 }
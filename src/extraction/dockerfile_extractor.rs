@@ -69,6 +69,12 @@ impl DockerfileExtractor {
     /// `file_path` is used for qualified names and node IDs (not for I/O).
     /// `source` is the Dockerfile source code to parse.
     pub fn extract_dockerfile(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -76,7 +82,7 @@ impl DockerfileExtractor {
             Ok(tree) => tree,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -114,7 +120,7 @@ impl DockerfileExtractor {
 
         state.node_stack.pop();
 
-        Self::build_result(state, start)
+        (Self::build_result(state, start), Some(tree))
     }
 
     /// Parse source code into a tree-sitter AST.
@@ -223,6 +229,7 @@ impl DockerfileExtractor {
                     target: id.clone(),
                     kind: EdgeKind::Contains,
                     line: Some(start_line),
+                    provenance: None,
                 });
             }
 
@@ -266,6 +273,7 @@ impl DockerfileExtractor {
                     target: id,
                     kind: EdgeKind::Contains,
                     line: Some(start_line),
+                    provenance: None,
                 });
             }
         }
@@ -336,6 +344,7 @@ impl DockerfileExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -390,6 +399,7 @@ impl DockerfileExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -446,6 +456,7 @@ impl DockerfileExtractor {
                             target: id,
                             kind: EdgeKind::Contains,
                             line: Some(start_line),
+                            provenance: None,
                         });
                     }
                 }
@@ -512,6 +523,7 @@ impl DockerfileExtractor {
                             target: id,
                             kind: EdgeKind::Contains,
                             line: Some(start_line),
+                            provenance: None,
                         });
                     }
                 }
@@ -561,6 +573,7 @@ impl DockerfileExtractor {
                                 target,
                                 kind: EdgeKind::Uses,
                                 line: Some(start_line),
+                                provenance: None,
                             });
                         }
                     }
@@ -599,6 +612,7 @@ impl DockerfileExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: Vec::new(),
+            signatures: Vec::new(),
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -617,4 +631,8 @@ impl crate::extraction::LanguageExtractor for DockerfileExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         Self::extract_dockerfile(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
 }
@@ -0,0 +1,508 @@
+//! Domain symbol extraction.
+//!
+//! A second pass over a file's [`ExtractionResult`] that promotes the names
+//! a program registers as data — HTTP routes, CLI commands, interpreter
+//! primitives, event names — into [`NodeKind::DomainSymbol`] nodes. Rules
+//! live in `.tokensave/domain-symbols.toml` (see `docs/DOMAIN-EXTRACTORS.md`):
+//! each one is a tree-sitter query whose `name_arg` capture is a string
+//! literal naming a symbol, plus the edges to emit for every match.
+//!
+//! Domain symbols are named after the literal and qualified as
+//! `{layer}::{name}`, so registrations of one symbol in different files
+//! share a qualified name.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use glob::Pattern;
+use serde::Deserialize;
+use tree_sitter::{Language, Query, QueryCursor, StreamingIterator, Tree};
+
+use crate::config::get_tokensave_dir;
+use crate::errors::{Result, TokenSaveError};
+use crate::extraction::{ts_provider, Callables};
+use crate::types::*;
+
+/// Name of the rules file inside the `.tokensave` directory.
+pub const DOMAIN_RULES_FILENAME: &str = "domain-symbols.toml";
+
+/// Longest registration line kept as a domain symbol's signature.
+const MAX_SIGNATURE_LEN: usize = 200;
+
+#[derive(Deserialize)]
+struct RulesFile {
+    layer: LayerConfig,
+    #[serde(default, rename = "rule")]
+    rules: Vec<RuleConfig>,
+}
+
+#[derive(Deserialize)]
+struct LayerConfig {
+    name: String,
+    #[serde(default)]
+    files: Vec<String>,
+    language: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RuleConfig {
+    name: String,
+    pattern: String,
+    #[serde(default = "default_pattern_lang")]
+    pattern_lang: String,
+    language: Option<String>,
+    name_arg: String,
+    target_arg: Option<String>,
+    #[serde(default = "default_emit_node")]
+    emit_node: String,
+    emit_edges: Option<Vec<EdgeConfig>>,
+    #[serde(default)]
+    var_filter: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct EdgeConfig {
+    kind: String,
+    from: String,
+    to: String,
+}
+
+fn default_pattern_lang() -> String {
+    "treesitter".to_string()
+}
+
+fn default_emit_node() -> String {
+    NodeKind::DomainSymbol.as_str().to_string()
+}
+
+/// One end of an edge a rule emits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Endpoint {
+    /// The innermost function or method around the match, else the file.
+    EnclosingFunction,
+    /// The file node.
+    EnclosingFile,
+    /// The domain symbol the match names.
+    Symbol,
+    /// Another capture of the query, resolved by name like a reference.
+    Capture(u32),
+}
+
+/// A rule compiled against its language's grammar.
+struct DomainRule {
+    grammar: Language,
+    query: Query,
+    name_capture: u32,
+    var_capture: Option<u32>,
+    var_filter: Vec<String>,
+    edges: Vec<(EdgeKind, Endpoint, Endpoint)>,
+}
+
+/// The domain rules of a project: one layer of rules applied to the files
+/// matching the layer's globs.
+pub struct DomainRules {
+    /// The TOML document the rules were compiled from.
+    source: String,
+    layer: String,
+    files: Vec<Pattern>,
+    rules: Vec<DomainRule>,
+}
+
+impl DomainRules {
+    /// Loads the rules of the project at `project_root`, returning `None`
+    /// when it has no rules file.
+    pub fn load(project_root: &Path) -> Result<Option<Self>> {
+        let path = get_tokensave_dir(project_root).join(DOMAIN_RULES_FILENAME);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let text = std::fs::read_to_string(&path).map_err(|e| TokenSaveError::Config {
+            message: format!("failed to read {}: {e}", path.display()),
+        })?;
+        Self::parse(&text).map(Some)
+    }
+
+    /// Parses and compiles the rules in the TOML document `text`.
+    pub fn parse(text: &str) -> Result<Self> {
+        let file: RulesFile = toml::from_str(text).map_err(|e| TokenSaveError::Config {
+            message: format!("invalid {DOMAIN_RULES_FILENAME}: {e}"),
+        })?;
+        let layer = file.layer.name.trim().to_string();
+        if layer.is_empty() {
+            return Err(config_error("the [layer] name must not be empty"));
+        }
+        let files = file
+            .layer
+            .files
+            .iter()
+            .map(|glob| {
+                Pattern::new(glob)
+                    .map_err(|e| config_error(&format!("invalid layer glob '{glob}': {e}")))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let rules = file
+            .rules
+            .iter()
+            .map(|rule| {
+                compile_rule(rule, file.layer.language.as_deref())
+                    .map_err(|message| config_error(&format!("rule '{}': {message}", rule.name)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            source: text.to_string(),
+            layer,
+            files,
+            rules,
+        })
+    }
+
+    /// Returns the TOML document the rules were compiled from, for handing
+    /// them to extraction workers.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns true when the layer's globs select `file_path`.
+    pub fn applies_to(&self, file_path: &str) -> bool {
+        let match_opts = glob::MatchOptions {
+            case_sensitive: true,
+            require_literal_separator: false,
+            require_literal_leading_dot: false,
+        };
+        self.files.is_empty()
+            || self
+                .files
+                .iter()
+                .any(|pattern| pattern.matches_with(file_path, match_opts))
+    }
+
+    /// Runs the rules written for `tree`'s grammar over it and adds the
+    /// domain symbols, edges and references they find to `result`, the
+    /// primary extraction of `file_path`. `tree` is the one the primary
+    /// extractor parsed `source` into; without it no rule runs.
+    pub fn apply(
+        &self,
+        file_path: &str,
+        source: &str,
+        tree: Option<&Tree>,
+        result: &mut ExtractionResult,
+    ) {
+        let Some(tree) = tree else {
+            return;
+        };
+        let language = tree.language();
+        let mut rules = self
+            .rules
+            .iter()
+            .filter(|rule| rule.grammar == *language)
+            .peekable();
+        if rules.peek().is_none() || !self.applies_to(file_path) {
+            return;
+        }
+        let mut pass = DomainPass::new(&self.layer, file_path, source, result);
+        for rule in rules {
+            pass.run(rule, tree);
+        }
+    }
+}
+
+fn config_error(message: &str) -> TokenSaveError {
+    TokenSaveError::Config {
+        message: format!("{DOMAIN_RULES_FILENAME}: {message}"),
+    }
+}
+
+/// Strips the `$` or `@` a rule puts in front of a capture name.
+fn capture_name(arg: &str) -> &str {
+    arg.trim().trim_start_matches(['$', '@'])
+}
+
+fn compile_rule(
+    rule: &RuleConfig,
+    layer_language: Option<&str>,
+) -> std::result::Result<DomainRule, String> {
+    if rule.pattern_lang != "treesitter" {
+        return Err(format!(
+            "unsupported pattern_lang '{}'; only treesitter queries are supported",
+            rule.pattern_lang
+        ));
+    }
+    if NodeKind::from_str(&rule.emit_node) != Some(NodeKind::DomainSymbol) {
+        return Err(format!(
+            "unsupported emit_node '{}'; rules emit domain_symbol nodes",
+            rule.emit_node
+        ));
+    }
+    let language = rule
+        .language
+        .as_deref()
+        .or(layer_language)
+        .ok_or("no language; set `language` on the [layer] or the rule")?;
+    let grammar = ts_provider::find_language(language)
+        .ok_or_else(|| format!("unknown language '{language}'"))?;
+    let query = Query::new(&grammar, &rule.pattern).map_err(|e| format!("invalid pattern: {e}"))?;
+    let capture = |arg: &str| {
+        query
+            .capture_index_for_name(capture_name(arg))
+            .ok_or_else(|| format!("pattern has no capture @{}", capture_name(arg)))
+    };
+
+    let name_capture = capture(&rule.name_arg)?;
+    let var_capture = if rule.var_filter.is_empty() {
+        None
+    } else {
+        Some(capture("VAR")?)
+    };
+    let endpoint = |arg: &str| -> std::result::Result<Endpoint, String> {
+        Ok(match arg.trim() {
+            "enclosing_function" => Endpoint::EnclosingFunction,
+            "enclosing_file" => Endpoint::EnclosingFile,
+            _ => match capture(arg)? {
+                index if index == name_capture => Endpoint::Symbol,
+                index => Endpoint::Capture(index),
+            },
+        })
+    };
+
+    let edges = if let Some(edges) = &rule.emit_edges {
+        edges
+            .iter()
+            .map(|edge| {
+                let kind = EdgeKind::from_str(&edge.kind)
+                    .filter(|kind| *kind != EdgeKind::Virtual)
+                    .ok_or_else(|| format!("unknown edge kind '{}'", edge.kind))?;
+                let from = endpoint(&edge.from)?;
+                if matches!(from, Endpoint::Capture(_)) {
+                    return Err(format!(
+                        "edge from '{}': edges start at the symbol or an enclosing node",
+                        edge.from
+                    ));
+                }
+                Ok((kind, from, endpoint(&edge.to)?))
+            })
+            .collect::<std::result::Result<Vec<_>, String>>()?
+    } else {
+        // By default the enclosing function registers the symbol, which
+        // implements the target when the rule names one.
+        let mut edges = vec![(
+            EdgeKind::Registers,
+            Endpoint::EnclosingFunction,
+            Endpoint::Symbol,
+        )];
+        if let Some(target) = &rule.target_arg {
+            edges.push((EdgeKind::Implements, Endpoint::Symbol, endpoint(target)?));
+        }
+        edges
+    };
+
+    Ok(DomainRule {
+        grammar,
+        query,
+        name_capture,
+        var_capture,
+        var_filter: rule.var_filter.clone(),
+        edges,
+    })
+}
+
+/// The text of a string literal without its quotes, prefixes (`r#`, `b`,
+/// `f`, `@`) and raw-string hashes.
+pub(crate) fn literal_text(raw: &str) -> &str {
+    let text = raw
+        .trim()
+        .trim_start_matches(|c: char| c.is_ascii_alphabetic() || c == '@' || c == '#')
+        .trim_end_matches('#');
+    match text.chars().next() {
+        Some(quote @ ('"' | '\'' | '`')) => text.trim_matches(quote),
+        _ => raw.trim(),
+    }
+}
+
+/// Returns the name a target capture refers to, or `None` when the capture
+/// is an expression rather than a (possibly qualified) identifier.
+pub(crate) fn target_name(raw: &str) -> Option<&str> {
+    let text = literal_text(raw);
+    let is_path = !text.is_empty()
+        && text
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '$' | ':' | '.'));
+    is_path.then_some(text)
+}
+
+/// The state of applying one layer's rules to one file.
+struct DomainPass<'a> {
+    layer: &'a str,
+    file_path: &'a str,
+    source: &'a str,
+    result: &'a mut ExtractionResult,
+    file_id: Option<String>,
+    functions: Callables,
+    /// Indexes into `result.nodes` of the domain symbols already emitted for
+    /// this file, by qualified name.
+    symbols: HashMap<String, usize>,
+    edges: HashSet<(String, String, &'static str, Option<u32>)>,
+}
+
+impl<'a> DomainPass<'a> {
+    fn new(
+        layer: &'a str,
+        file_path: &'a str,
+        source: &'a str,
+        result: &'a mut ExtractionResult,
+    ) -> Self {
+        let file_id = result
+            .nodes
+            .iter()
+            .find(|n| n.kind == NodeKind::File)
+            .map(|n| n.id.clone());
+        let functions = Callables::new(&result.nodes);
+        Self {
+            layer,
+            file_path,
+            source,
+            result,
+            file_id,
+            functions,
+            symbols: HashMap::new(),
+            edges: HashSet::new(),
+        }
+    }
+
+    fn run(&mut self, rule: &DomainRule, tree: &Tree) {
+        let source = self.source;
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(&rule.query, tree.root_node(), source.as_bytes());
+        while let Some(found) = matches.next() {
+            let text_of = |index: u32| {
+                found
+                    .captures
+                    .iter()
+                    .filter(move |c| c.index == index)
+                    .map(|c| (c.node, c.node.utf8_text(source.as_bytes()).unwrap_or("")))
+            };
+            if let Some(var) = rule.var_capture {
+                let allowed = text_of(var)
+                    .any(|(_, text)| rule.var_filter.iter().any(|v| v == literal_text(text)));
+                if !allowed {
+                    continue;
+                }
+            }
+            for (node, raw) in text_of(rule.name_capture) {
+                let name = literal_text(raw);
+                if name.is_empty() {
+                    continue;
+                }
+                let line = node.start_position().row as u32;
+                let symbol = self.symbol(name, node);
+                for (kind, from, to) in &rule.edges {
+                    let Some(source_id) = self.endpoint_id(*from, &symbol, line) else {
+                        continue;
+                    };
+                    if let Endpoint::Capture(index) = to {
+                        for (target, raw) in text_of(*index) {
+                            let Some(target_name) = target_name(raw) else {
+                                continue;
+                            };
+                            self.result.unresolved_refs.push(UnresolvedRef {
+                                from_node_id: source_id.clone(),
+                                reference_name: target_name.to_string(),
+                                reference_kind: kind.clone(),
+                                line: target.start_position().row as u32,
+                                column: target.start_position().column as u32,
+                                file_path: self.file_path.to_string(),
+                                receiver_type: None,
+                                abi: None,
+                                http_method: None,
+                                sql: false,
+                            });
+                        }
+                    } else if let Some(target_id) = self.endpoint_id(*to, &symbol, line) {
+                        self.edge(source_id, target_id, kind.clone(), Some(line));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the ID of the domain symbol `name`, emitting its node on its
+    /// first match in the file. The node sits at the earliest literal that
+    /// names the symbol, whichever rule matched it.
+    fn symbol(&mut self, name: &str, literal: tree_sitter::Node<'_>) -> String {
+        let qualified_name = format!("{}::{name}", self.layer);
+        let start = literal.start_position();
+        let end = literal.end_position();
+        let signature = self
+            .source
+            .lines()
+            .nth(start.row)
+            .map(|line| line.trim().chars().take(MAX_SIGNATURE_LEN).collect());
+        if let Some(&index) = self.symbols.get(&qualified_name) {
+            let node = &mut self.result.nodes[index];
+            if (start.row as u32, start.column as u32) < (node.start_line, node.start_column) {
+                node.start_line = start.row as u32;
+                node.end_line = end.row as u32;
+                node.start_column = start.column as u32;
+                node.end_column = end.column as u32;
+                node.signature = signature;
+            }
+            return node.id.clone();
+        }
+        let id = generate_node_id(self.file_path, &NodeKind::DomainSymbol, &qualified_name, 0);
+        self.symbols
+            .insert(qualified_name.clone(), self.result.nodes.len());
+        self.result.nodes.push(Node {
+            id: id.clone(),
+            kind: NodeKind::DomainSymbol,
+            name: name.to_string(),
+            qualified_name: qualified_name.clone(),
+            file_path: self.file_path.to_string(),
+            start_line: start.row as u32,
+            end_line: end.row as u32,
+            start_column: start.column as u32,
+            end_column: end.column as u32,
+            signature,
+            docstring: None,
+            visibility: Visibility::Pub,
+            is_async: false,
+            branches: 0,
+            loops: 0,
+            returns: 0,
+            max_nesting: 0,
+            unsafe_blocks: 0,
+            unchecked_calls: 0,
+            assertions: 0,
+            updated_at: crate::tokensave::current_timestamp() as u64,
+        });
+        if let Some(file_id) = self.file_id.clone() {
+            self.edge(file_id, id.clone(), EdgeKind::Contains, None);
+        }
+        id
+    }
+
+    fn endpoint_id(&self, endpoint: Endpoint, symbol: &str, line: u32) -> Option<String> {
+        match endpoint {
+            Endpoint::Symbol => Some(symbol.to_string()),
+            Endpoint::EnclosingFile => self.file_id.clone(),
+            Endpoint::EnclosingFunction => self
+                .functions
+                .enclosing(line)
+                .or_else(|| self.file_id.clone()),
+            Endpoint::Capture(_) => None,
+        }
+    }
+
+    fn edge(&mut self, source: String, target: String, kind: EdgeKind, line: Option<u32>) {
+        if self
+            .edges
+            .insert((source.clone(), target.clone(), kind.as_str(), line))
+        {
+            self.result.edges.push(Edge {
+                source,
+                target,
+                kind,
+                line,
+                provenance: None,
+            });
+        }
+    }
+}
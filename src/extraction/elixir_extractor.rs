@@ -58,6 +58,12 @@ impl ExtractionState {
 
 impl ElixirExtractor {
     pub fn extract_elixir(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -65,7 +71,7 @@ impl ElixirExtractor {
             Ok(t) => t,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -100,7 +106,7 @@ impl ElixirExtractor {
         Self::visit_children(&mut state, root);
 
         state.node_stack.pop();
-        Self::build_result(state, start)
+        (Self::build_result(state, start), Some(tree))
     }
 
     fn parse_source(source: &str) -> Result<Tree, String> {
@@ -187,6 +193,7 @@ impl ElixirExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -245,6 +252,7 @@ impl ElixirExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -291,6 +299,7 @@ impl ElixirExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -339,6 +348,7 @@ impl ElixirExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -379,6 +389,7 @@ impl ElixirExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -501,6 +512,10 @@ impl ElixirExtractor {
                                 line: child.start_position().row as u32,
                                 column: child.start_position().column as u32,
                                 file_path: state.file_path.clone(),
+                                receiver_type: None,
+                                abi: None,
+                                http_method: None,
+                                sql: false,
                             });
                         }
                     }
@@ -525,6 +540,7 @@ impl ElixirExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: Vec::new(),
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -543,4 +559,8 @@ impl crate::extraction::LanguageExtractor for ElixirExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         Self::extract_elixir(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
 }
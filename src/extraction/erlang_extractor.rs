@@ -47,6 +47,12 @@ impl ExtractionState {
 
 impl ErlangExtractor {
     pub fn extract_erlang(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -54,7 +60,7 @@ impl ErlangExtractor {
             Ok(t) => t,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -86,7 +92,7 @@ impl ErlangExtractor {
         let root = tree.root_node();
         Self::visit_children(&mut state, root);
 
-        Self::build_result(state, start)
+        (Self::build_result(state, start), Some(tree))
     }
 
     fn parse_source(source: &str) -> Result<Tree, String> {
@@ -173,6 +179,7 @@ impl ErlangExtractor {
             target: id.clone(),
             kind: EdgeKind::Contains,
             line: Some(start_line),
+            provenance: None,
         });
 
         // Collect call sites from all clauses.
@@ -231,6 +238,7 @@ impl ErlangExtractor {
             target: id,
             kind: EdgeKind::Contains,
             line: Some(start_line),
+            provenance: None,
         });
     }
 
@@ -269,6 +277,7 @@ impl ErlangExtractor {
             target: id,
             kind: EdgeKind::Contains,
             line: Some(start_line),
+            provenance: None,
         });
     }
 
@@ -285,6 +294,10 @@ impl ErlangExtractor {
                 line: start_line,
                 column: 0,
                 file_path: state.file_path.clone(),
+                receiver_type: None,
+                abi: None,
+                http_method: None,
+                sql: false,
             });
         }
         let _ = text;
@@ -391,6 +404,10 @@ impl ErlangExtractor {
                             line: child.start_position().row as u32,
                             column: child.start_position().column as u32,
                             file_path: state.file_path.clone(),
+                            receiver_type: None,
+                            abi: None,
+                            http_method: None,
+                            sql: false,
                         });
                     }
                     Self::extract_calls(state, child, fn_id);
@@ -414,6 +431,7 @@ impl ErlangExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: Vec::new(),
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -432,4 +450,8 @@ impl crate::extraction::LanguageExtractor for ErlangExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         Self::extract_erlang(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
 }
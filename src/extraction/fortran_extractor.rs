@@ -69,6 +69,12 @@ impl ExtractionState {
 impl FortranExtractor {
     /// Extract code graph nodes and edges from a Fortran source file.
     pub fn extract_fortran(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -76,7 +82,7 @@ impl FortranExtractor {
             Ok(tree) => tree,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -114,7 +120,7 @@ impl FortranExtractor {
 
         state.node_stack.pop();
 
-        Self::build_result(state, start)
+        (Self::build_result(state, start), Some(tree))
     }
 
     /// Parse source code into a tree-sitter AST.
@@ -209,6 +215,7 @@ impl FortranExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -270,6 +277,7 @@ impl FortranExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -327,6 +335,7 @@ impl FortranExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -380,6 +389,7 @@ impl FortranExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -437,6 +447,7 @@ impl FortranExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -449,6 +460,10 @@ impl FortranExtractor {
                 line: start_line,
                 column: start_column,
                 file_path: state.file_path.clone(),
+                receiver_type: None,
+                abi: None,
+                http_method: None,
+                sql: false,
             });
         }
 
@@ -521,6 +536,7 @@ impl FortranExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -575,6 +591,7 @@ impl FortranExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -637,6 +654,7 @@ impl FortranExtractor {
                         target: id,
                         kind: EdgeKind::Contains,
                         line: Some(start_line),
+                        provenance: None,
                     });
                 }
             }
@@ -687,6 +705,7 @@ impl FortranExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -832,6 +851,10 @@ impl FortranExtractor {
                                 line: child.start_position().row as u32,
                                 column: child.start_position().column as u32,
                                 file_path: state.file_path.clone(),
+                                receiver_type: None,
+                                abi: None,
+                                http_method: None,
+                                sql: false,
                             });
                         }
                         // Recurse into arguments for nested calls.
@@ -849,6 +872,10 @@ impl FortranExtractor {
                                     line: child.start_position().row as u32,
                                     column: child.start_position().column as u32,
                                     file_path: state.file_path.clone(),
+                                    receiver_type: None,
+                                    abi: None,
+                                    http_method: None,
+                                    sql: false,
                                 });
                             }
                         }
@@ -891,6 +918,7 @@ impl FortranExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: Vec::new(),
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -909,4 +937,8 @@ impl crate::extraction::LanguageExtractor for FortranExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         Self::extract_fortran(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
 }
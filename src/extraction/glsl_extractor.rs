@@ -66,6 +66,12 @@ impl ExtractionState {
 
 impl GlslExtractor {
     pub fn extract_source(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -73,7 +79,7 @@ impl GlslExtractor {
             Ok(tree) => tree,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -110,7 +116,7 @@ impl GlslExtractor {
 
         state.node_stack.pop();
 
-        Self::build_result(state, start)
+        (Self::build_result(state, start), Some(tree))
     }
 
     fn parse_source(source: &str) -> Result<Tree, String> {
@@ -196,6 +202,7 @@ impl GlslExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -288,6 +295,7 @@ impl GlslExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -355,6 +363,7 @@ impl GlslExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -437,6 +446,7 @@ impl GlslExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -506,6 +516,7 @@ impl GlslExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -577,6 +588,7 @@ impl GlslExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -597,6 +609,10 @@ impl GlslExtractor {
                 line,
                 column,
                 file_path: state.file_path.clone(),
+                receiver_type: None,
+                abi: None,
+                http_method: None,
+                sql: false,
             });
         }
     }
@@ -620,6 +636,10 @@ impl GlslExtractor {
                             line: child.start_position().row as u32,
                             column: child.start_position().column as u32,
                             file_path: state.file_path.clone(),
+                            receiver_type: None,
+                            abi: None,
+                            http_method: None,
+                            sql: false,
                         });
                     }
                     Self::extract_call_sites(state, child, fn_node_id);
@@ -763,6 +783,7 @@ impl GlslExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: Vec::new(),
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -781,4 +802,8 @@ impl crate::extraction::LanguageExtractor for GlslExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         GlslExtractor::extract_source(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
 }
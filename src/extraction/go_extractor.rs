@@ -6,8 +6,11 @@ use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tree_sitter::{Node as TsNode, Parser, Tree};
 
 use crate::extraction::complexity::{count_complexity, GO_COMPLEXITY};
+use crate::extraction::receivers::{receiver_type, TypeDecls, GO_RECEIVERS};
+use crate::extraction::signatures::{param, signature};
 use crate::types::{
-    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, UnresolvedRef, Visibility,
+    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, Signature, UnresolvedRef,
+    Visibility,
 };
 
 /// Extracts code graph nodes and edges from Go source files using tree-sitter.
@@ -18,12 +21,18 @@ struct ExtractionState {
     nodes: Vec<Node>,
     edges: Vec<Edge>,
     unresolved_refs: Vec<UnresolvedRef>,
+    signatures: Vec<Signature>,
     errors: Vec<String>,
     /// Stack of (name, `node_id`) for building qualified names and parent edges.
     node_stack: Vec<(String, String)>,
     file_path: String,
     source: Vec<u8>,
+    /// Type declarations of the file, for receiver inference.
+    type_decls: TypeDecls,
     timestamp: u64,
+    /// Whether the file imports cgo's `"C"` pseudo-package, making
+    /// `C.name()` a call to a C function.
+    cgo: bool,
 }
 
 impl ExtractionState {
@@ -36,11 +45,16 @@ impl ExtractionState {
             nodes: Vec::new(),
             edges: Vec::new(),
             unresolved_refs: Vec::new(),
+            signatures: Vec::new(),
             errors: Vec::new(),
             node_stack: Vec::new(),
             file_path: file_path.to_string(),
             source: source.as_bytes().to_vec(),
+            type_decls: TypeDecls::default(),
             timestamp,
+            cgo: source
+                .lines()
+                .any(|line| matches!(line.trim(), "import \"C\"" | "\"C\"")),
         }
     }
 
@@ -72,6 +86,12 @@ impl GoExtractor {
     /// `file_path` is used for qualified names and node IDs (not for I/O).
     /// `source` is the Go source code to parse.
     pub fn extract_source(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -79,7 +99,7 @@ impl GoExtractor {
             Ok(tree) => tree,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -113,11 +133,15 @@ impl GoExtractor {
 
         // Walk the AST.
         let root = tree.root_node();
+        state.type_decls = TypeDecls::new(root, &state.source, &GO_RECEIVERS);
         Self::visit_children(&mut state, root);
 
         state.node_stack.pop();
 
-        Self::build_result(state, start)
+        let mut result = Self::build_result(state, start);
+        super::routes::extract_routes(file_path, source, root, &mut result);
+        super::sql::extract_embedded_sql(file_path, source, root, &mut result);
+        (result, Some(tree))
     }
 
     /// Parse source code into a tree-sitter AST.
@@ -206,6 +230,7 @@ impl GoExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -289,6 +314,7 @@ impl GoExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -300,6 +326,10 @@ impl GoExtractor {
             line: start_line,
             column: start_column,
             file_path: state.file_path.clone(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
         });
     }
 
@@ -316,12 +346,17 @@ impl GoExtractor {
         let start_column = node.start_position().column as u32;
         let end_column = node.end_position().column as u32;
         let qualified_name = format!("{}::{}", state.qualified_prefix(), name);
-        let id = generate_node_id(&state.file_path, &NodeKind::Function, &name, start_line);
+        let kind = if Self::is_test_function(state, node, &name) {
+            NodeKind::TestCase
+        } else {
+            NodeKind::Function
+        };
+        let id = generate_node_id(&state.file_path, &kind, &name, start_line);
         let metrics = count_complexity(node, &GO_COMPLEXITY, &state.source);
 
         let graph_node = Node {
             id: id.clone(),
-            kind: NodeKind::Function,
+            kind,
             name: name.clone(),
             qualified_name,
             file_path: state.file_path.clone(),
@@ -351,11 +386,13 @@ impl GoExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
         // Extract generic type parameters.
         Self::extract_type_params(state, node, &id);
+        Self::push_signature(state, node, &id);
 
         // Extract call sites from the function body.
         if let Some(body) = Self::find_child_by_kind(node, "block") {
@@ -363,6 +400,90 @@ impl GoExtractor {
         }
     }
 
+    /// True for a function `go test` runs: `TestXxx(t *testing.T)`,
+    /// `BenchmarkXxx(b *testing.B)` or `FuzzXxx(f *testing.F)`, where `Xxx`
+    /// does not start with a lowercase letter.
+    fn is_test_function(state: &ExtractionState, node: TsNode<'_>, name: &str) -> bool {
+        let Some(params) = node.child_by_field_name("parameters") else {
+            return false;
+        };
+        let params: String = state
+            .node_text(params)
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        [("Test", 'T'), ("Benchmark", 'B'), ("Fuzz", 'F')]
+            .iter()
+            .any(|(prefix, param)| {
+                name.strip_prefix(prefix)
+                    .is_some_and(|rest| !rest.starts_with(|c: char| c.is_lowercase()))
+                    && params.ends_with(&format!("*testing.{param})"))
+            })
+    }
+
+    /// Records the declared parameters and results of a function or method.
+    /// Parameters sharing a type (`a, b int`) are listed one by one, and
+    /// several results read as a tuple of their types, `(int, error)`.
+    fn push_signature(state: &mut ExtractionState, node: TsNode<'_>, id: &str) {
+        let mut params = Vec::new();
+        if let Some(list) = node.child_by_field_name("parameters") {
+            for (name, ty, variadic) in Self::parameter_declarations(state, list) {
+                params.push(param(&name, ty.as_deref(), None, variadic));
+            }
+        }
+        let result = node.child_by_field_name("result").map(|r| {
+            if r.kind() == "parameter_list" {
+                let types: Vec<String> = Self::parameter_declarations(state, r)
+                    .into_iter()
+                    .filter_map(|(_, ty, _)| ty)
+                    .collect();
+                format!("({})", types.join(", "))
+            } else {
+                state.node_text(r)
+            }
+        });
+        state
+            .signatures
+            .extend(signature(id, params, result.as_deref()));
+    }
+
+    /// The (name, type, variadic) of each parameter in a parameter list,
+    /// with `...T` for a variadic one. Unnamed parameters have empty names.
+    fn parameter_declarations(
+        state: &ExtractionState,
+        list: TsNode<'_>,
+    ) -> Vec<(String, Option<String>, bool)> {
+        let mut params = Vec::new();
+        let mut cursor = list.walk();
+        for decl in list.named_children(&mut cursor) {
+            let variadic = decl.kind() == "variadic_parameter_declaration";
+            if !variadic && decl.kind() != "parameter_declaration" {
+                continue;
+            }
+            let ty = decl.child_by_field_name("type").map(|t| {
+                let text = state.node_text(t);
+                if variadic {
+                    format!("...{text}")
+                } else {
+                    text
+                }
+            });
+            let mut names_cursor = decl.walk();
+            let names: Vec<String> = decl
+                .children_by_field_name("name", &mut names_cursor)
+                .map(|n| state.node_text(n))
+                .collect();
+            if names.is_empty() {
+                params.push((String::new(), ty, variadic));
+            } else {
+                for name in names {
+                    params.push((name, ty.clone(), variadic));
+                }
+            }
+        }
+        params
+    }
+
     /// Extract a method declaration node (function with receiver).
     fn visit_method(state: &mut ExtractionState, node: TsNode<'_>) {
         // In Go, method name is a `field_identifier` child.
@@ -411,11 +532,13 @@ impl GoExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
         // Extract receiver type and create a Receives edge.
         Self::extract_receiver(state, node, &id);
+        Self::push_signature(state, node, &id);
 
         // Extract call sites from the method body.
         if let Some(body) = Self::find_child_by_kind(node, "block") {
@@ -509,6 +632,7 @@ impl GoExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -581,6 +705,7 @@ impl GoExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -642,6 +767,7 @@ impl GoExtractor {
             target: id,
             kind: EdgeKind::Contains,
             line: Some(start_line),
+            provenance: None,
         });
     }
 
@@ -695,6 +821,7 @@ impl GoExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -725,6 +852,10 @@ impl GoExtractor {
                             line,
                             column,
                             file_path: state.file_path.clone(),
+                            receiver_type: None,
+                            abi: None,
+                            http_method: None,
+                            sql: false,
                         });
                     }
                 }
@@ -785,6 +916,7 @@ impl GoExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -833,6 +965,7 @@ impl GoExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -898,6 +1031,7 @@ impl GoExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -963,6 +1097,7 @@ impl GoExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -994,6 +1129,10 @@ impl GoExtractor {
                                 line,
                                 column,
                                 file_path: state.file_path.clone(),
+                                receiver_type: None,
+                                abi: None,
+                                http_method: None,
+                                sql: false,
                             });
                             // Also try to create a direct Receives edge if we can find
                             // the struct node. We look for it by matching name.
@@ -1008,6 +1147,7 @@ impl GoExtractor {
                                     target: struct_id,
                                     kind: EdgeKind::Receives,
                                     line: Some(line),
+                                    provenance: None,
                                 });
                             }
                         }
@@ -1091,6 +1231,7 @@ impl GoExtractor {
                                 target: id,
                                 kind: EdgeKind::Contains,
                                 line: Some(start_line),
+                                provenance: None,
                             });
                         }
                     }
@@ -1114,7 +1255,21 @@ impl GoExtractor {
                         // Get the callee: either an identifier or a selector_expression.
                         let callee = child.named_child(0);
                         if let Some(callee) = callee {
-                            let callee_name = state.node_text(callee);
+                            let mut callee_name = state.node_text(callee);
+                            let receiver = receiver_type(
+                                child,
+                                &callee_name,
+                                &state.source,
+                                &state.type_decls,
+                                &GO_RECEIVERS,
+                            );
+                            let mut abi = None;
+                            if let Some(symbol) =
+                                callee_name.strip_prefix("C.").filter(|_| state.cgo)
+                            {
+                                callee_name = symbol.to_string();
+                                abi = Some("C".to_string());
+                            }
                             state.unresolved_refs.push(UnresolvedRef {
                                 from_node_id: fn_node_id.to_string(),
                                 reference_name: callee_name,
@@ -1122,6 +1277,10 @@ impl GoExtractor {
                                 line: child.start_position().row as u32,
                                 column: child.start_position().column as u32,
                                 file_path: state.file_path.clone(),
+                                receiver_type: receiver,
+                                abi,
+                                http_method: None,
+                                sql: false,
                             });
                         }
                         // Also recurse into the call expression for nested calls.
@@ -1234,6 +1393,7 @@ impl GoExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: state.signatures,
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -1252,4 +1412,12 @@ impl crate::extraction::LanguageExtractor for GoExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         GoExtractor::extract_source(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
+
+    fn extracts_signatures(&self) -> bool {
+        true
+    }
 }
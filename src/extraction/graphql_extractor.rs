@@ -0,0 +1,865 @@
+//! Extractor for GraphQL schemas and operations.
+//!
+//! Type definitions (object, interface, input, enum, union and scalar types)
+//! become `GqlType` nodes and their fields `GqlField` nodes named
+//! `Type.field`, whose signature ends with the field's type. Named queries,
+//! mutations and subscriptions become `GqlOperation` nodes, fragments
+//! `GqlFragment` nodes.
+//!
+//! Every field an operation or fragment selects becomes a `Uses` ref named
+//! by its path from the root type or type condition (`Query.user.posts`);
+//! the resolver follows the field types along the path to the selected
+//! `GqlField`. Fragment spreads reference the fragment by name.
+//!
+//! Documents embedded in TypeScript and JavaScript as `gql` or `graphql`
+//! tagged templates are extracted by [`extract_embedded`].
+
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::extraction::LanguageExtractor;
+use crate::types::{
+    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, UnresolvedRef, Visibility,
+};
+
+/// Extracts schema types, fields, operations and fragments from GraphQL
+/// documents.
+pub struct GraphqlExtractor;
+
+impl LanguageExtractor for GraphqlExtractor {
+    fn extensions(&self) -> &[&str] {
+        &["graphql", "gql"]
+    }
+
+    fn language_name(&self) -> &'static str {
+        "GraphQL"
+    }
+
+    fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
+        let start = Instant::now();
+        let file_id = generate_node_id(file_path, &NodeKind::File, file_path, 0);
+        let mut emitter = Emitter::new(file_path, source, Some(file_id.clone()));
+        let file_node = emitter.node(
+            file_id,
+            NodeKind::File,
+            file_path.to_string(),
+            file_path.to_string(),
+            (0, source.len()),
+        );
+        emitter.nodes.push(file_node);
+        Parser::new(source, 0, &mut emitter).document();
+        ExtractionResult {
+            nodes: emitter.nodes,
+            edges: emitter.edges,
+            unresolved_refs: emitter.unresolved_refs,
+            signatures: Vec::new(),
+            errors: Vec::new(),
+            duration_ms: start.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+/// Tags whose template literals hold GraphQL documents.
+const TEMPLATE_TAGS: &[&str] = &["gql", "graphql"];
+
+/// Extracts the GraphQL documents of `gql` and `graphql` tagged templates
+/// (`` gql`...` `` or `` graphql(`...`) ``) in a script into `result`.
+/// Definitions are contained in the innermost script node around them,
+/// usually the constant the document is assigned to.
+pub(crate) fn extract_embedded(file_path: &str, source: &str, result: &mut ExtractionResult) {
+    let templates = tagged_templates(source);
+    if templates.is_empty() {
+        return;
+    }
+    let mut emitter = Emitter::new(file_path, source, None);
+    for (start, end) in templates {
+        let (line, _) = emitter.position(start);
+        emitter.container = result
+            .nodes
+            .iter()
+            .filter(|n| n.kind != NodeKind::File && n.start_line <= line && line <= n.end_line)
+            .min_by_key(|n| n.end_line - n.start_line)
+            .map(|n| n.id.clone());
+        // Interpolated fragments (`${USER_FIELDS}`) become blanks, which
+        // keeps every offset in place.
+        let mut text = source[start..end].to_string();
+        for (from, to) in interpolations(&text) {
+            text.replace_range(from..to, &" ".repeat(to - from));
+        }
+        Parser::new(&text, start, &mut emitter).document();
+    }
+    result.nodes.extend(emitter.nodes);
+    result.edges.extend(emitter.edges);
+    result.unresolved_refs.extend(emitter.unresolved_refs);
+}
+
+/// Byte ranges of the contents of the GraphQL tagged templates in `source`.
+fn tagged_templates(source: &str) -> Vec<(usize, usize)> {
+    let bytes = source.as_bytes();
+    let mut templates = Vec::new();
+    for tag in TEMPLATE_TAGS {
+        for (at, _) in source.match_indices(tag) {
+            let is_word = |c: &u8| c.is_ascii_alphanumeric() || *c == b'_' || *c == b'$';
+            if at > 0 && (is_word(&bytes[at - 1]) || bytes[at - 1] == b'.') {
+                continue;
+            }
+            let mut pos = at + tag.len();
+            if bytes.get(pos) == Some(&b'(') {
+                pos += 1;
+            }
+            if bytes.get(pos) != Some(&b'`') {
+                continue;
+            }
+            if let Some(end) = template_end(bytes, pos + 1) {
+                templates.push((pos + 1, end));
+            }
+        }
+    }
+    templates.sort_unstable();
+    templates
+}
+
+/// The offset of the backtick closing a template literal whose content
+/// starts at `pos`.
+fn template_end(bytes: &[u8], mut pos: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    while let Some(&c) = bytes.get(pos) {
+        match c {
+            b'\\' => pos += 1,
+            b'`' if depth == 0 => return Some(pos),
+            b'{' if depth > 0 => depth += 1,
+            b'}' if depth > 0 => depth -= 1,
+            b'$' if depth == 0 && bytes.get(pos + 1) == Some(&b'{') => {
+                depth = 1;
+                pos += 1;
+            }
+            _ => {}
+        }
+        pos += 1;
+    }
+    None
+}
+
+/// Byte ranges of the `${...}` interpolations of a template's content,
+/// including the `...` of a spread whose fragment name is interpolated.
+fn interpolations(text: &str) -> Vec<(usize, usize)> {
+    let bytes = text.as_bytes();
+    let mut ranges = Vec::new();
+    let mut pos = 0;
+    while let Some(found) = text[pos..].find("${") {
+        let start = pos + found;
+        let mut depth = 0usize;
+        let mut end = bytes.len();
+        for (i, &c) in bytes.iter().enumerate().skip(start + 1) {
+            match c {
+                b'{' => depth += 1,
+                b'}' if depth == 1 => {
+                    end = i + 1;
+                    break;
+                }
+                b'}' => depth -= 1,
+                _ => {}
+            }
+        }
+        let spread = if text[..start].ends_with("...") { 3 } else { 0 };
+        ranges.push((start - spread, end));
+        pos = end;
+    }
+    ranges
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Tok<'a> {
+    Name(&'a str),
+    Punct(u8),
+    Spread,
+    /// A string or block string, which may be a description.
+    Str,
+    Other,
+}
+
+#[derive(Clone, Copy)]
+struct Token<'a> {
+    tok: Tok<'a>,
+    start: usize,
+    end: usize,
+}
+
+struct Parser<'t, 'e, 's> {
+    text: &'t str,
+    pos: usize,
+    /// Offset of `text` in the file.
+    base: usize,
+    peeked: Option<Token<'t>>,
+    /// End of the last consumed token.
+    last_end: usize,
+    emitter: &'e mut Emitter<'s>,
+}
+
+impl<'t, 'e, 's> Parser<'t, 'e, 's> {
+    fn new(text: &'t str, base: usize, emitter: &'e mut Emitter<'s>) -> Self {
+        Self {
+            text,
+            pos: 0,
+            base,
+            peeked: None,
+            last_end: 0,
+            emitter,
+        }
+    }
+
+    fn lex(&mut self) -> Option<Token<'t>> {
+        let bytes = self.text.as_bytes();
+        // Commas are insignificant, like white space.
+        loop {
+            match bytes.get(self.pos)? {
+                b' ' | b'\t' | b'\r' | b'\n' | b',' => self.pos += 1,
+                b'#' => {
+                    self.pos = self.text[self.pos..]
+                        .find('\n')
+                        .map_or(bytes.len(), |i| self.pos + i);
+                }
+                _ => break,
+            }
+        }
+        let start = self.pos;
+        let c = bytes[start];
+        let tok = if self.text[start..].starts_with("\"\"\"") {
+            self.pos = self.text[start + 3..]
+                .find("\"\"\"")
+                .map_or(bytes.len(), |i| start + 3 + i + 3);
+            Tok::Str
+        } else if c == b'"' {
+            self.pos += 1;
+            while let Some(&c) = bytes.get(self.pos) {
+                self.pos += if c == b'\\' { 2 } else { 1 };
+                if c == b'"' || c == b'\n' {
+                    break;
+                }
+            }
+            Tok::Str
+        } else if self.text[start..].starts_with("...") {
+            self.pos += 3;
+            Tok::Spread
+        } else if c.is_ascii_alphabetic() || c == b'_' {
+            while bytes
+                .get(self.pos)
+                .is_some_and(|c| c.is_ascii_alphanumeric() || *c == b'_')
+            {
+                self.pos += 1;
+            }
+            Tok::Name(&self.text[start..self.pos])
+        } else if c.is_ascii_digit() || c == b'-' {
+            self.pos += 1;
+            while bytes
+                .get(self.pos)
+                .is_some_and(|c| c.is_ascii_alphanumeric() || matches!(c, b'.' | b'+' | b'-'))
+            {
+                self.pos += 1;
+            }
+            Tok::Other
+        } else if b"!$&()=:@[]{}|".contains(&c) {
+            self.pos += 1;
+            Tok::Punct(c)
+        } else {
+            self.pos += self.text[start..].chars().next().map_or(1, char::len_utf8);
+            Tok::Other
+        };
+        self.pos = self.pos.min(bytes.len());
+        Some(Token {
+            tok,
+            start,
+            end: self.pos,
+        })
+    }
+
+    fn peek(&mut self) -> Option<Tok<'t>> {
+        if self.peeked.is_none() {
+            self.peeked = self.lex();
+        }
+        self.peeked.map(|t| t.tok)
+    }
+
+    fn bump(&mut self) -> Option<Token<'t>> {
+        let token = self.peeked.take().or_else(|| self.lex());
+        if let Some(token) = token {
+            self.last_end = token.end;
+        }
+        token
+    }
+
+    fn eat(&mut self, tok: Tok<'t>) -> bool {
+        let matches = self.peek() == Some(tok);
+        if matches {
+            self.bump();
+        }
+        matches
+    }
+
+    fn name(&mut self) -> Option<Token<'t>> {
+        match self.peek()? {
+            Tok::Name(_) => self.bump(),
+            _ => None,
+        }
+    }
+
+    /// Skips a balanced `open ... close` group when one comes next.
+    fn skip_group(&mut self, open: u8, close: u8) {
+        if !self.eat(Tok::Punct(open)) {
+            return;
+        }
+        let mut depth = 1;
+        while let Some(token) = self.bump() {
+            match token.tok {
+                Tok::Punct(c) if c == open => depth += 1,
+                Tok::Punct(c) if c == close => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn directives(&mut self) {
+        while self.eat(Tok::Punct(b'@')) {
+            self.name();
+            self.skip_group(b'(', b')');
+        }
+    }
+
+    /// The absolute offset of a position in `text`.
+    fn at(&self, offset: usize) -> usize {
+        self.base + offset
+    }
+
+    fn document(&mut self) {
+        let mut description = None;
+        while let Some(token) = self.bump() {
+            let Tok::Name(keyword) = token.tok else {
+                match token.tok {
+                    Tok::Str => description = Some(token),
+                    // An anonymous query.
+                    Tok::Punct(b'{') => {
+                        self.peeked = Some(token);
+                        let from = self.emitter.container.clone();
+                        self.selection_set("Query", from.as_deref());
+                    }
+                    _ => {}
+                }
+                continue;
+            };
+            let start = token.start;
+            let docstring = description.take().map(|d| self.description(d.start, d.end));
+            let keyword = if keyword == "extend" {
+                match self.name().map(|t| t.tok) {
+                    Some(Tok::Name(keyword)) => keyword,
+                    _ => continue,
+                }
+            } else {
+                keyword
+            };
+            match keyword {
+                "type" | "interface" | "input" => self.object_type(start, docstring),
+                "enum" | "scalar" | "union" => self.simple_type(keyword, start, docstring),
+                "query" | "mutation" | "subscription" => self.operation(keyword, start),
+                "fragment" => self.fragment(start),
+                "schema" => {
+                    self.directives();
+                    self.skip_group(b'{', b'}');
+                }
+                "directive" => {
+                    self.bump();
+                    self.name();
+                    self.skip_group(b'(', b')');
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// The text of the description string between `start` and `end`.
+    fn description(&self, start: usize, end: usize) -> String {
+        self.text[start..end].trim_matches('"').trim().to_string()
+    }
+
+    fn object_type(&mut self, start: usize, docstring: Option<String>) {
+        let Some(name) = self.name() else { return };
+        let Tok::Name(type_name) = name.tok else {
+            return;
+        };
+        let mut refs = Vec::new();
+        if self.eat(Tok::Name("implements")) {
+            self.eat(Tok::Punct(b'&'));
+            while let Some(interface) = self.name() {
+                if let Tok::Name(interface_name) = interface.tok {
+                    refs.push((
+                        interface_name.to_string(),
+                        interface.start,
+                        EdgeKind::Implements,
+                    ));
+                }
+                if !self.eat(Tok::Punct(b'&')) {
+                    break;
+                }
+            }
+        }
+        self.directives();
+        let mut fields = Vec::new();
+        if self.eat(Tok::Punct(b'{')) {
+            let mut description = None;
+            loop {
+                match self.peek() {
+                    None => break,
+                    Some(Tok::Punct(b'}')) => {
+                        self.bump();
+                        break;
+                    }
+                    Some(Tok::Str) => description = self.bump(),
+                    Some(Tok::Name(field_name)) => {
+                        let field = self.bump().map_or(0, |t| t.start);
+                        let docstring =
+                            description.take().map(|d| self.description(d.start, d.end));
+                        fields.push(self.field(field_name, field, docstring));
+                    }
+                    Some(_) => {
+                        self.bump();
+                    }
+                }
+            }
+        }
+        let span = (self.at(start), self.at(self.last_end));
+        let type_id =
+            self.emitter
+                .add_top_level(NodeKind::GqlType, type_name.to_string(), span, docstring);
+        self.emitter.refs(&type_id, refs, self.base);
+        for field in fields {
+            let id = self.emitter.add(
+                &type_id,
+                NodeKind::GqlField,
+                format!("{type_name}.{}", field.name),
+                (self.at(field.start), self.at(field.end)),
+                field.docstring,
+            );
+            if let Some(node) = self.emitter.nodes.last_mut() {
+                node.signature = Some(field.signature);
+            }
+            self.emitter.refs(&id, field.refs, self.base);
+        }
+    }
+
+    /// Parses a field definition after its name.
+    fn field(&mut self, name: &str, start: usize, docstring: Option<String>) -> FieldDef {
+        let mut refs = Vec::new();
+        if self.peek() == Some(Tok::Punct(b'(')) {
+            self.typed_list(&mut refs);
+        }
+        if self.eat(Tok::Punct(b':')) {
+            if let Some(named) = self.type_ref() {
+                refs.push(named);
+            }
+        }
+        let end = self.last_end;
+        if self.eat(Tok::Punct(b'=')) {
+            self.value();
+        }
+        self.directives();
+        let signature = self.text[start..end]
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        FieldDef {
+            name: name.to_string(),
+            start,
+            end,
+            signature,
+            docstring,
+            refs,
+        }
+    }
+
+    /// Skips a default value.
+    fn value(&mut self) {
+        match self.peek() {
+            Some(Tok::Punct(b'{')) => self.skip_group(b'{', b'}'),
+            Some(Tok::Punct(b'[')) => self.skip_group(b'[', b']'),
+            _ => {
+                self.bump();
+            }
+        }
+    }
+
+    /// Parses a type reference (`[Post!]!`) and returns the named type.
+    fn type_ref(&mut self) -> Option<(String, usize, EdgeKind)> {
+        while self.eat(Tok::Punct(b'[')) {}
+        let named = self.name()?;
+        while matches!(self.peek(), Some(Tok::Punct(b'!' | b']'))) {
+            self.bump();
+        }
+        let Tok::Name(type_name) = named.tok else {
+            return None;
+        };
+        Some((type_name.to_string(), named.start, EdgeKind::Uses))
+    }
+
+    /// Parses an argument or variable definition list, recording the types
+    /// it names.
+    fn typed_list(&mut self, refs: &mut Vec<(String, usize, EdgeKind)>) {
+        self.bump();
+        let mut depth = 0usize;
+        while let Some(tok) = self.peek() {
+            match tok {
+                Tok::Punct(b')') if depth == 0 => {
+                    self.bump();
+                    return;
+                }
+                Tok::Punct(b':') if depth == 0 => {
+                    self.bump();
+                    refs.extend(self.type_ref());
+                }
+                Tok::Punct(b'(' | b'[' | b'{') => {
+                    depth += 1;
+                    self.bump();
+                }
+                Tok::Punct(b')' | b']' | b'}') => {
+                    depth = depth.saturating_sub(1);
+                    self.bump();
+                }
+                _ => {
+                    self.bump();
+                }
+            }
+        }
+    }
+
+    fn simple_type(&mut self, keyword: &str, start: usize, docstring: Option<String>) {
+        let Some(Token {
+            tok: Tok::Name(type_name),
+            ..
+        }) = self.name()
+        else {
+            return;
+        };
+        self.directives();
+        let mut refs = Vec::new();
+        match keyword {
+            "enum" => self.skip_group(b'{', b'}'),
+            "union" if self.eat(Tok::Punct(b'=')) => {
+                self.eat(Tok::Punct(b'|'));
+                while let Some((member, offset, kind)) = self.type_ref() {
+                    refs.push((member, offset, kind));
+                    if !self.eat(Tok::Punct(b'|')) {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+        let span = (self.at(start), self.at(self.last_end));
+        let id =
+            self.emitter
+                .add_top_level(NodeKind::GqlType, type_name.to_string(), span, docstring);
+        self.emitter.refs(&id, refs, self.base);
+    }
+
+    fn operation(&mut self, keyword: &str, start: usize) {
+        let root = match keyword {
+            "mutation" => "Mutation",
+            "subscription" => "Subscription",
+            _ => "Query",
+        };
+        let name = self.name();
+        let mut refs = Vec::new();
+        if self.peek() == Some(Tok::Punct(b'(')) {
+            self.typed_list(&mut refs);
+        }
+        self.directives();
+        let id = match name.map(|t| t.tok) {
+            Some(Tok::Name(name)) => Some(self.emitter.add_top_level(
+                NodeKind::GqlOperation,
+                name.to_string(),
+                (self.at(start), self.at(start)),
+                None,
+            )),
+            _ => self.emitter.container.clone(),
+        };
+        self.finish_definition(id, refs, root, start);
+    }
+
+    fn fragment(&mut self, start: usize) {
+        let name = self.name();
+        let mut type_condition = "";
+        if self.eat(Tok::Name("on")) {
+            if let Some(Tok::Name(condition)) = self.name().map(|t| t.tok) {
+                type_condition = condition;
+            }
+        }
+        self.directives();
+        let id = match name.map(|t| t.tok) {
+            Some(Tok::Name(name)) => Some(self.emitter.add_top_level(
+                NodeKind::GqlFragment,
+                name.to_string(),
+                (self.at(start), self.at(start)),
+                None,
+            )),
+            _ => None,
+        };
+        self.finish_definition(id, Vec::new(), type_condition, start);
+    }
+
+    /// Records the selections of an operation or fragment as refs from
+    /// `id`, and extends its node over its selection set.
+    fn finish_definition(
+        &mut self,
+        id: Option<String>,
+        refs: Vec<(String, usize, EdgeKind)>,
+        root: &str,
+        start: usize,
+    ) {
+        if let Some(id) = &id {
+            self.emitter.refs(id, refs, self.base);
+        }
+        self.selection_set(root, id.as_deref());
+        let Some(id) = id else { return };
+        if let Some(index) = self.emitter.nodes.iter().rposition(|n| n.id == id) {
+            if self.emitter.nodes[index].kind != NodeKind::File {
+                let span = (self.at(start), self.at(self.last_end));
+                let (end_line, end_column) = self.emitter.position(span.1);
+                let node = &mut self.emitter.nodes[index];
+                node.end_line = end_line;
+                node.end_column = end_column;
+            }
+        }
+    }
+
+    /// Parses a selection set of fields of `parent`, recording the fields,
+    /// inline fragment fields and fragment spreads it selects as refs from
+    /// `from`.
+    fn selection_set(&mut self, parent: &str, from: Option<&str>) {
+        if !self.eat(Tok::Punct(b'{')) {
+            return;
+        }
+        let mut refs = Vec::new();
+        while let Some(token) = self.bump() {
+            match token.tok {
+                Tok::Punct(b'}') => break,
+                Tok::Spread => {
+                    if self.eat(Tok::Name("on")) {
+                        let condition = match self.name().map(|t| t.tok) {
+                            Some(Tok::Name(condition)) => condition.to_string(),
+                            _ => parent.to_string(),
+                        };
+                        self.directives();
+                        self.selection_set(&condition, from);
+                    } else if let Some(spread) = self.name() {
+                        if let Tok::Name(fragment) = spread.tok {
+                            refs.push((fragment.to_string(), spread.start, EdgeKind::Uses));
+                        }
+                        self.directives();
+                    } else {
+                        self.directives();
+                        self.selection_set(parent, from);
+                    }
+                }
+                Tok::Name(mut field) => {
+                    let mut field_start = token.start;
+                    if self.eat(Tok::Punct(b':')) {
+                        if let Some(aliased) = self.name() {
+                            if let Tok::Name(aliased_name) = aliased.tok {
+                                field = aliased_name;
+                                field_start = aliased.start;
+                            }
+                        }
+                    }
+                    self.skip_group(b'(', b')');
+                    self.directives();
+                    let path = format!("{parent}.{field}");
+                    // Meta fields (`__typename`) are not in the schema, nor
+                    // is anything selected under a fragment without a type.
+                    if !field.starts_with("__") && !parent.is_empty() {
+                        refs.push((path.clone(), field_start, EdgeKind::Uses));
+                    }
+                    if self.peek() == Some(Tok::Punct(b'{')) {
+                        let nested = if parent.is_empty() { "" } else { &path };
+                        self.selection_set(nested, from);
+                    }
+                }
+                _ => {}
+            }
+        }
+        if let Some(from) = from {
+            self.emitter.refs(from, refs, self.base);
+        }
+    }
+}
+
+struct FieldDef {
+    name: String,
+    start: usize,
+    end: usize,
+    signature: String,
+    docstring: Option<String>,
+    refs: Vec<(String, usize, EdgeKind)>,
+}
+
+struct Emitter<'a> {
+    file_path: &'a str,
+    source: &'a str,
+    /// The node that contains top-level definitions: the file node of a
+    /// GraphQL file, or the script node around an embedded document.
+    container: Option<String>,
+    timestamp: u64,
+    /// Byte offset of the start of each line.
+    line_starts: Vec<usize>,
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+    unresolved_refs: Vec<UnresolvedRef>,
+}
+
+impl<'a> Emitter<'a> {
+    fn new(file_path: &'a str, source: &'a str, container: Option<String>) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self {
+            file_path,
+            source,
+            container,
+            timestamp,
+            line_starts,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            unresolved_refs: Vec::new(),
+        }
+    }
+
+    /// 0-based (line, column) of a byte offset.
+    fn position(&self, offset: usize) -> (u32, u32) {
+        let line = self.line_starts.partition_point(|&s| s <= offset) - 1;
+        (line as u32, (offset - self.line_starts[line]) as u32)
+    }
+
+    fn node(
+        &self,
+        id: String,
+        kind: NodeKind,
+        name: String,
+        qualified_name: String,
+        span: (usize, usize),
+    ) -> Node {
+        let (start_line, start_column) = self.position(span.0);
+        let (end_line, end_column) = self.position(span.1);
+        let signature = (kind != NodeKind::File).then(|| {
+            let line = self.source[span.0..]
+                .lines()
+                .next()
+                .unwrap_or("")
+                .trim()
+                .trim_end_matches('{')
+                .trim_end();
+            match line.char_indices().nth(120) {
+                Some((cut, _)) => format!("{}...", &line[..cut]),
+                None => line.to_string(),
+            }
+        });
+        Node {
+            id,
+            kind,
+            name,
+            qualified_name,
+            file_path: self.file_path.to_string(),
+            start_line,
+            end_line,
+            start_column,
+            end_column,
+            signature,
+            docstring: None,
+            visibility: Visibility::Pub,
+            is_async: false,
+            branches: 0,
+            loops: 0,
+            returns: 0,
+            max_nesting: 0,
+            unsafe_blocks: 0,
+            unchecked_calls: 0,
+            assertions: 0,
+            updated_at: self.timestamp,
+        }
+    }
+
+    /// Adds a node contained in `parent_id` and returns its id.
+    fn add(
+        &mut self,
+        parent_id: &str,
+        kind: NodeKind,
+        name: String,
+        span: (usize, usize),
+        docstring: Option<String>,
+    ) -> String {
+        let id = self.add_node(kind, name, span, docstring);
+        let (line, _) = self.position(span.0);
+        self.edges.push(Edge {
+            source: parent_id.to_string(),
+            target: id.clone(),
+            kind: EdgeKind::Contains,
+            line: Some(line),
+            provenance: None,
+        });
+        id
+    }
+
+    fn add_node(
+        &mut self,
+        kind: NodeKind,
+        name: String,
+        span: (usize, usize),
+        docstring: Option<String>,
+    ) -> String {
+        let (line, _) = self.position(span.0);
+        let qualified_name = format!("{}::{name}", self.file_path);
+        let id = generate_node_id(self.file_path, &kind, &qualified_name, line);
+        let mut node = self.node(id.clone(), kind, name, qualified_name, span);
+        node.docstring = docstring.filter(|d| !d.is_empty());
+        self.nodes.push(node);
+        id
+    }
+
+    /// Adds a top-level definition, contained in the container if there is
+    /// one.
+    fn add_top_level(
+        &mut self,
+        kind: NodeKind,
+        name: String,
+        span: (usize, usize),
+        docstring: Option<String>,
+    ) -> String {
+        match self.container.clone() {
+            Some(container) => self.add(&container, kind, name, span, docstring),
+            None => self.add_node(kind, name, span, docstring),
+        }
+    }
+
+    /// Records refs from `from`; offsets are relative to `base`.
+    fn refs(&mut self, from: &str, refs: Vec<(String, usize, EdgeKind)>, base: usize) {
+        for (name, offset, kind) in refs {
+            let (line, column) = self.position(base + offset);
+            self.unresolved_refs.push(UnresolvedRef {
+                from_node_id: from.to_string(),
+                reference_name: name,
+                reference_kind: kind,
+                line,
+                column,
+                file_path: self.file_path.to_string(),
+                receiver_type: None,
+                abi: None,
+                http_method: None,
+                sql: false,
+            });
+        }
+    }
+}
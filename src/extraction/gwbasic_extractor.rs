@@ -87,6 +87,12 @@ impl GwBasicExtractor {
     /// `file_path` is used for qualified names and node IDs (not for I/O).
     /// `source` is the BASIC source code to parse.
     pub fn extract_gwbasic(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -94,7 +100,7 @@ impl GwBasicExtractor {
             Ok(tree) => tree,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -144,7 +150,7 @@ impl GwBasicExtractor {
 
         state.node_stack.pop();
 
-        Self::build_result(state, start)
+        (Self::build_result(state, start), Some(tree))
     }
 
     /// Parse source code into a tree-sitter AST.
@@ -281,6 +287,7 @@ impl GwBasicExtractor {
                     target: id,
                     kind: EdgeKind::Contains,
                     line: Some(start_line),
+                    provenance: None,
                 });
             }
         }
@@ -368,6 +375,7 @@ impl GwBasicExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -495,6 +503,7 @@ impl GwBasicExtractor {
                             target: fn_id.clone(),
                             kind: EdgeKind::Contains,
                             line: Some(start_line),
+                            provenance: None,
                         });
                     }
 
@@ -642,6 +651,10 @@ impl GwBasicExtractor {
                         line: node.start_position().row as u32,
                         column: node.start_position().column as u32,
                         file_path: state.file_path.clone(),
+                        receiver_type: None,
+                        abi: None,
+                        http_method: None,
+                        sql: false,
                     });
                 }
             }
@@ -725,6 +738,7 @@ impl GwBasicExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: Vec::new(),
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -743,4 +757,8 @@ impl crate::extraction::LanguageExtractor for GwBasicExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         Self::extract_gwbasic(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
 }
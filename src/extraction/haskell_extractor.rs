@@ -47,6 +47,12 @@ impl ExtractionState {
 
 impl HaskellExtractor {
     pub fn extract_haskell(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -54,7 +60,7 @@ impl HaskellExtractor {
             Ok(t) => t,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -86,7 +92,7 @@ impl HaskellExtractor {
         let root = tree.root_node();
         Self::visit_children(&mut state, root);
 
-        Self::build_result(state, start)
+        (Self::build_result(state, start), Some(tree))
     }
 
     fn parse_source(source: &str) -> Result<Tree, String> {
@@ -287,6 +293,7 @@ impl HaskellExtractor {
             target: id,
             kind: EdgeKind::Contains,
             line: Some(start_line),
+            provenance: None,
         });
     }
 
@@ -371,6 +378,7 @@ impl HaskellExtractor {
             target: id,
             kind: EdgeKind::Contains,
             line: Some(node.start_position().row as u32),
+            provenance: None,
         });
     }
 
@@ -384,6 +392,7 @@ impl HaskellExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: Vec::new(),
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -402,4 +411,8 @@ impl crate::extraction::LanguageExtractor for HaskellExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         Self::extract_haskell(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
 }
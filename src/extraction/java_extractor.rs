@@ -6,8 +6,12 @@ use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tree_sitter::{Node as TsNode, Parser, Tree};
 
 use crate::extraction::complexity::{count_complexity, JAVA_COMPLEXITY};
+use crate::extraction::receivers::{receiver_type, TypeDecls, JAVA_RECEIVERS};
+use crate::extraction::signatures::{param, signature};
+use crate::extraction::type_refs::{push_type_refs, JAVA_TYPE_REFS};
 use crate::types::{
-    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, UnresolvedRef, Visibility,
+    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, Signature, UnresolvedRef,
+    Visibility,
 };
 
 /// Extracts code graph nodes and edges from Java source files using tree-sitter.
@@ -18,16 +22,21 @@ struct ExtractionState {
     nodes: Vec<Node>,
     edges: Vec<Edge>,
     unresolved_refs: Vec<UnresolvedRef>,
+    signatures: Vec<Signature>,
     errors: Vec<String>,
     /// Stack of (name, `node_id`) for building qualified names and parent edges.
     node_stack: Vec<(String, String)>,
     file_path: String,
     source: Vec<u8>,
+    /// Type declarations of the file, for receiver inference.
+    type_decls: TypeDecls,
     timestamp: u64,
     /// Track nesting depth to distinguish inner classes from top-level classes.
     class_depth: usize,
     /// Track whether we are inside an interface (for abstract method detection).
     inside_interface: bool,
+    /// The declared package, for the JNI symbols of `native` methods.
+    package: Option<String>,
 }
 
 impl ExtractionState {
@@ -40,13 +49,16 @@ impl ExtractionState {
             nodes: Vec::new(),
             edges: Vec::new(),
             unresolved_refs: Vec::new(),
+            signatures: Vec::new(),
             errors: Vec::new(),
             node_stack: Vec::new(),
             file_path: file_path.to_string(),
             source: source.as_bytes().to_vec(),
+            type_decls: TypeDecls::default(),
             timestamp,
             class_depth: 0,
             inside_interface: false,
+            package: None,
         }
     }
 
@@ -78,6 +90,12 @@ impl JavaExtractor {
     /// `file_path` is used for qualified names and node IDs (not for I/O).
     /// `source` is the Java source code to parse.
     pub fn extract_java(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -85,7 +103,7 @@ impl JavaExtractor {
             Ok(tree) => tree,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -119,11 +137,15 @@ impl JavaExtractor {
 
         // Walk the AST.
         let root = tree.root_node();
+        state.type_decls = TypeDecls::new(root, &state.source, &JAVA_RECEIVERS);
         Self::visit_children(&mut state, root);
 
         state.node_stack.pop();
 
-        Self::build_result(state, start)
+        let mut result = Self::build_result(state, start);
+        super::routes::extract_routes(file_path, source, root, &mut result);
+        super::sql::extract_embedded_sql(file_path, source, root, &mut result);
+        (result, Some(tree))
     }
 
     /// Parse source code into a tree-sitter AST.
@@ -195,6 +217,7 @@ impl JavaExtractor {
         let end_column = node.end_position().column as u32;
         let qualified_name = format!("{}::{}", state.qualified_prefix(), pkg_name);
         let id = generate_node_id(&state.file_path, &NodeKind::Package, &pkg_name, start_line);
+        state.package = Some(pkg_name.clone());
 
         let graph_node = Node {
             id: id.clone(),
@@ -228,6 +251,7 @@ impl JavaExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -286,6 +310,7 @@ impl JavaExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -297,6 +322,10 @@ impl JavaExtractor {
             line: start_line,
             column: start_column,
             file_path: state.file_path.clone(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
         });
     }
 
@@ -353,6 +382,7 @@ impl JavaExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -418,6 +448,7 @@ impl JavaExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -482,6 +513,7 @@ impl JavaExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -551,6 +583,7 @@ impl JavaExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -600,6 +633,7 @@ impl JavaExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -627,12 +661,30 @@ impl JavaExtractor {
 
         let kind = if is_abstract {
             NodeKind::AbstractMethod
+        } else if Self::is_test_method(state, node) {
+            NodeKind::TestCase
         } else {
             NodeKind::Method
         };
 
         let id = generate_node_id(&state.file_path, &kind, &name, start_line);
         let metrics = count_complexity(node, &JAVA_COMPLEXITY, &state.source);
+        // A `native` method calls the C function JNI names after it.
+        if Self::has_modifier(node, state, "native") {
+            let symbol = Self::jni_symbol(state, &name);
+            state.unresolved_refs.push(UnresolvedRef {
+                from_node_id: id.clone(),
+                reference_name: symbol,
+                reference_kind: EdgeKind::Calls,
+                line: start_line,
+                column: start_column,
+                file_path: state.file_path.clone(),
+                receiver_type: None,
+                abi: Some("C".to_string()),
+                http_method: None,
+                sql: false,
+            });
+        }
 
         let graph_node = Node {
             id: id.clone(),
@@ -666,6 +718,7 @@ impl JavaExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -674,6 +727,7 @@ impl JavaExtractor {
 
         // Extract type references from parameter and return type.
         Self::extract_type_refs(state, node, &id);
+        Self::push_signature(state, node, &id);
 
         // Extract call sites from the method body.
         if has_body {
@@ -727,11 +781,13 @@ impl JavaExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
         // Extract type references from parameter types.
         Self::extract_type_refs(state, node, &id);
+        Self::push_signature(state, node, &id);
 
         // Extract call sites from the constructor body.
         Self::extract_call_sites(state, node, &id);
@@ -745,6 +801,7 @@ impl JavaExtractor {
         let start_column = node.start_position().column as u32;
         let end_column = node.end_position().column as u32;
         let signature_text = state.node_text(node).trim().to_string();
+        let field_type = node.child_by_field_name("type");
 
         // Iterate over variable_declarator children to extract each field name.
         let mut cursor = node.walk();
@@ -798,11 +855,24 @@ impl JavaExtractor {
                     if let Some(parent_id) = state.parent_node_id() {
                         state.edges.push(Edge {
                             source: parent_id.to_string(),
-                            target: id,
+                            target: id.clone(),
                             kind: EdgeKind::Contains,
                             line: Some(start_line),
+                            provenance: None,
                         });
                     }
+
+                    if let Some(field_type) = field_type {
+                        push_type_refs(
+                            &mut state.unresolved_refs,
+                            field_type,
+                            &state.source,
+                            &JAVA_TYPE_REFS,
+                            &id,
+                            &EdgeKind::TypeOf,
+                            &state.file_path,
+                        );
+                    }
                 }
                 if !cursor.goto_next_sibling() {
                     break;
@@ -853,6 +923,7 @@ impl JavaExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -911,6 +982,62 @@ impl JavaExtractor {
         false
     }
 
+    /// True for a method annotated as a `JUnit` test: `@Test`, and `JUnit` 5's
+    /// `@ParameterizedTest`, `@RepeatedTest`, `@TestFactory` and
+    /// `@TestTemplate`.
+    fn is_test_method(state: &ExtractionState, node: TsNode<'_>) -> bool {
+        let mut cursor = node.walk();
+        let Some(modifiers) = node
+            .children(&mut cursor)
+            .find(|child| child.kind() == "modifiers")
+        else {
+            return false;
+        };
+        let mut cursor = modifiers.walk();
+        for child in modifiers.named_children(&mut cursor) {
+            if child.kind() == "marker_annotation" || child.kind() == "annotation" {
+                let name = Self::extract_annotation_name(state, child);
+                let name = name.rsplit('.').next().unwrap_or(&name);
+                if matches!(
+                    name,
+                    "Test" | "ParameterizedTest" | "RepeatedTest" | "TestFactory" | "TestTemplate"
+                ) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// The C symbol JNI binds a `native` method of the enclosing classes to:
+    /// `Java_com_example_Codec_compress` for `com.example.Codec.compress`,
+    /// with `_` escaped as `_1` and nested classes joined by `$`.
+    fn jni_symbol(state: &ExtractionState, method: &str) -> String {
+        let classes: Vec<&str> = state
+            .node_stack
+            .iter()
+            .skip(1)
+            .map(|(name, _)| name.as_str())
+            .collect();
+        let class = classes.join("$");
+        let path = match &state.package {
+            Some(package) => format!("{package}.{class}"),
+            None => class,
+        };
+        let mangle = |name: &str| -> String {
+            name.chars()
+                .map(|c| match c {
+                    '.' => "_".to_string(),
+                    '_' => "_1".to_string(),
+                    '$' => "_00024".to_string(),
+                    c if c.is_ascii_alphanumeric() => c.to_string(),
+                    c => format!("_0{:04x}", u32::from(c)),
+                })
+                .collect()
+        };
+        format!("Java_{}_{}", mangle(&path), mangle(method))
+    }
+
     /// Check if a node has a direct child of a given kind.
     fn has_child_of_kind(node: TsNode<'_>, kind: &str) -> bool {
         let mut cursor = node.walk();
@@ -1013,6 +1140,10 @@ impl JavaExtractor {
                                     line: inner_child.start_position().row as u32,
                                     column: inner_child.start_position().column as u32,
                                     file_path: state.file_path.clone(),
+                                    receiver_type: None,
+                                    abi: None,
+                                    http_method: None,
+                                    sql: false,
                                 });
                                 break;
                             }
@@ -1067,6 +1198,10 @@ impl JavaExtractor {
                         line: child.start_position().row as u32,
                         column: child.start_position().column as u32,
                         file_path: state.file_path.clone(),
+                        receiver_type: None,
+                        abi: None,
+                        http_method: None,
+                        sql: false,
                     });
                 } else if child.kind() == "type_list" {
                     // Recurse into nested type_list.
@@ -1152,6 +1287,7 @@ impl JavaExtractor {
                         target: id,
                         kind: EdgeKind::Contains,
                         line: Some(start_line),
+                        provenance: None,
                     });
                 }
                 if !cursor.goto_next_sibling() {
@@ -1239,6 +1375,10 @@ impl JavaExtractor {
                         line: start_line,
                         column: start_column,
                         file_path: state.file_path.clone(),
+                        receiver_type: None,
+                        abi: None,
+                        http_method: None,
+                        sql: false,
                     });
 
                     // Also create a direct Annotates edge from the annotation to the target.
@@ -1247,6 +1387,7 @@ impl JavaExtractor {
                         target: target_id.to_string(),
                         kind: EdgeKind::Annotates,
                         line: Some(start_line),
+                        provenance: None,
                     });
                 }
                 if !cursor.goto_next_sibling() {
@@ -1280,84 +1421,79 @@ impl JavaExtractor {
 
     /// Extract type references from parameter types and return type.
     ///
-    /// In Java, `formal_parameter` children contain a `type_identifier` (or
-    /// `generic_type` wrapping one). The method's return type also appears as a
-    /// `type_identifier` direct child.
+    /// Types named in the `formal_parameters` become `Receives` refs; the
+    /// method's `type` field (absent on constructors) becomes `Returns` refs.
     fn extract_type_refs(state: &mut ExtractionState, node: TsNode<'_>, fn_node_id: &str) {
-        let java_builtins: &[&str] = &[
-            "void",
-            "int",
-            "long",
-            "short",
-            "byte",
-            "char",
-            "float",
-            "double",
-            "boolean",
-            "String",
-            "Object",
-            "Integer",
-            "Long",
-            "Short",
-            "Byte",
-            "Character",
-            "Float",
-            "Double",
-            "Boolean",
-            "Void",
-        ];
-
-        let mut cursor = node.walk();
-        if !cursor.goto_first_child() {
-            return;
+        if let Some(params) = node.child_by_field_name("parameters") {
+            push_type_refs(
+                &mut state.unresolved_refs,
+                params,
+                &state.source,
+                &JAVA_TYPE_REFS,
+                fn_node_id,
+                &EdgeKind::Receives,
+                &state.file_path,
+            );
         }
-        loop {
-            let child = cursor.node();
-            match child.kind() {
-                "formal_parameters" => {
-                    Self::extract_type_refs(state, child, fn_node_id);
-                }
-                "formal_parameter" | "spread_parameter" | "type_identifier" | "generic_type" => {
-                    Self::collect_java_type_ids(state, child, fn_node_id, java_builtins);
-                }
-                _ => {}
-            }
-            if !cursor.goto_next_sibling() {
-                break;
-            }
+        if let Some(return_type) = node.child_by_field_name("type") {
+            push_type_refs(
+                &mut state.unresolved_refs,
+                return_type,
+                &state.source,
+                &JAVA_TYPE_REFS,
+                fn_node_id,
+                &EdgeKind::Returns,
+                &state.file_path,
+            );
         }
     }
 
-    /// Recursively collect `type_identifier` nodes and emit "uses" refs.
-    fn collect_java_type_ids(
-        state: &mut ExtractionState,
-        node: TsNode<'_>,
-        fn_node_id: &str,
-        builtins: &[&str],
-    ) {
-        if node.kind() == "type_identifier" {
-            let type_name = state.node_text(node);
-            if !builtins.contains(&type_name.as_str()) {
-                state.unresolved_refs.push(UnresolvedRef {
-                    from_node_id: fn_node_id.to_string(),
-                    reference_name: type_name,
-                    reference_kind: EdgeKind::Uses,
-                    line: node.start_position().row as u32,
-                    column: node.start_position().column as u32,
-                    file_path: state.file_path.clone(),
-                });
-            }
-            return;
-        }
-        let mut cursor = node.walk();
-        if cursor.goto_first_child() {
-            loop {
-                Self::collect_java_type_ids(state, cursor.node(), fn_node_id, builtins);
-                if !cursor.goto_next_sibling() {
-                    break;
+    /// Records the declared parameters and return type of a method or
+    /// constructor. A varargs parameter is an optional array of its type.
+    fn push_signature(state: &mut ExtractionState, node: TsNode<'_>, id: &str) {
+        let mut params = Vec::new();
+        if let Some(list) = node.child_by_field_name("parameters") {
+            let mut cursor = list.walk();
+            for child in list.named_children(&mut cursor) {
+                match child.kind() {
+                    "formal_parameter" => {
+                        let name = child
+                            .child_by_field_name("name")
+                            .map(|n| state.node_text(n))
+                            .unwrap_or_default();
+                        // `int values[]` puts the dimensions after the name.
+                        let ty = child.child_by_field_name("type").map(|t| {
+                            let dims = child
+                                .child_by_field_name("dimensions")
+                                .map(|d| state.node_text(d))
+                                .unwrap_or_default();
+                            format!("{}{dims}", state.node_text(t))
+                        });
+                        params.push(param(&name, ty.as_deref(), None, false));
+                    }
+                    "spread_parameter" => {
+                        let mut inner = child.walk();
+                        let parts: Vec<TsNode<'_>> = child
+                            .named_children(&mut inner)
+                            .filter(|c| c.kind() != "modifiers")
+                            .collect();
+                        let ty = parts.first().map(|t| format!("{}...", state.node_text(*t)));
+                        let name = parts
+                            .iter()
+                            .find(|c| c.kind() == "variable_declarator")
+                            .and_then(|d| d.child_by_field_name("name"))
+                            .map(|n| state.node_text(n))
+                            .unwrap_or_default();
+                        params.push(param(&name, ty.as_deref(), None, true));
+                    }
+                    _ => {}
                 }
             }
         }
+        let return_type = node.child_by_field_name("type").map(|t| state.node_text(t));
+        state
+            .signatures
+            .extend(signature(id, params, return_type.as_deref()));
     }
 
     /// Recursively find `method_invocation` and `object_creation_expression` nodes inside a
@@ -1370,6 +1506,13 @@ impl JavaExtractor {
                 match child.kind() {
                     "method_invocation" => {
                         let callee_name = Self::extract_method_invocation_name(state, child);
+                        let receiver = receiver_type(
+                            child,
+                            &callee_name,
+                            &state.source,
+                            &state.type_decls,
+                            &JAVA_RECEIVERS,
+                        );
                         state.unresolved_refs.push(UnresolvedRef {
                             from_node_id: fn_node_id.to_string(),
                             reference_name: callee_name,
@@ -1377,6 +1520,10 @@ impl JavaExtractor {
                             line: child.start_position().row as u32,
                             column: child.start_position().column as u32,
                             file_path: state.file_path.clone(),
+                            receiver_type: receiver,
+                            abi: None,
+                            http_method: None,
+                            sql: false,
                         });
                         // Recurse for nested calls inside arguments, etc.
                         Self::extract_call_sites(state, child, fn_node_id);
@@ -1390,6 +1537,10 @@ impl JavaExtractor {
                             line: child.start_position().row as u32,
                             column: child.start_position().column as u32,
                             file_path: state.file_path.clone(),
+                            receiver_type: None,
+                            abi: None,
+                            http_method: None,
+                            sql: false,
                         });
                         // Recurse for nested calls.
                         Self::extract_call_sites(state, child, fn_node_id);
@@ -1460,6 +1611,7 @@ impl JavaExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: state.signatures,
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -1478,4 +1630,12 @@ impl crate::extraction::LanguageExtractor for JavaExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         JavaExtractor::extract_java(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
+
+    fn extracts_signatures(&self) -> bool {
+        true
+    }
 }
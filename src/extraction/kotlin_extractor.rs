@@ -8,8 +8,12 @@ use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tree_sitter::{Node as TsNode, Parser, Tree};
 
 use crate::extraction::complexity::{count_complexity, KOTLIN_COMPLEXITY};
+use crate::extraction::receivers::{receiver_type, TypeDecls, KOTLIN_RECEIVERS};
+use crate::extraction::signatures::{param, signature};
+use crate::extraction::type_refs::{push_type_refs, KOTLIN_TYPE_REFS};
 use crate::types::{
-    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, UnresolvedRef, Visibility,
+    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, Param, Signature,
+    UnresolvedRef, Visibility,
 };
 
 /// Extracts code graph nodes and edges from Kotlin source files using tree-sitter.
@@ -20,11 +24,14 @@ struct ExtractionState {
     nodes: Vec<Node>,
     edges: Vec<Edge>,
     unresolved_refs: Vec<UnresolvedRef>,
+    signatures: Vec<Signature>,
     errors: Vec<String>,
     /// Stack of (name, `node_id`) for building qualified names and parent edges.
     node_stack: Vec<(String, String)>,
     file_path: String,
     source: Vec<u8>,
+    /// Type declarations of the file, for receiver inference.
+    type_decls: TypeDecls,
     timestamp: u64,
     /// Track nesting depth to distinguish methods from top-level functions.
     class_depth: usize,
@@ -42,10 +49,12 @@ impl ExtractionState {
             nodes: Vec::new(),
             edges: Vec::new(),
             unresolved_refs: Vec::new(),
+            signatures: Vec::new(),
             errors: Vec::new(),
             node_stack: Vec::new(),
             file_path: file_path.to_string(),
             source: source.as_bytes().to_vec(),
+            type_decls: TypeDecls::default(),
             timestamp,
             class_depth: 0,
             inside_trait: false,
@@ -80,6 +89,12 @@ impl KotlinExtractor {
     /// `file_path` is used for qualified names and node IDs (not for I/O).
     /// `source` is the Kotlin source code to parse.
     pub fn extract_kotlin(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -87,7 +102,7 @@ impl KotlinExtractor {
             Ok(tree) => tree,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -121,11 +136,12 @@ impl KotlinExtractor {
 
         // Walk the AST.
         let root = tree.root_node();
+        state.type_decls = TypeDecls::new(root, &state.source, &KOTLIN_RECEIVERS);
         Self::visit_children(&mut state, root);
 
         state.node_stack.pop();
 
-        Self::build_result(state, start)
+        (Self::build_result(state, start), Some(tree))
     }
 
     /// Parse source code into a tree-sitter AST.
@@ -232,6 +248,7 @@ impl KotlinExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -308,6 +325,7 @@ impl KotlinExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -318,6 +336,10 @@ impl KotlinExtractor {
             line: start_line,
             column: start_column,
             file_path: state.file_path.clone(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
         });
     }
 
@@ -398,6 +420,7 @@ impl KotlinExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -457,6 +480,7 @@ impl KotlinExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -516,6 +540,7 @@ impl KotlinExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -574,6 +599,7 @@ impl KotlinExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -635,6 +661,7 @@ impl KotlinExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -714,6 +741,7 @@ impl KotlinExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -767,6 +795,7 @@ impl KotlinExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -841,6 +870,7 @@ impl KotlinExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -929,6 +959,7 @@ impl KotlinExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -936,6 +967,7 @@ impl KotlinExtractor {
 
         // Extract type references from parameter and return type annotations.
         Self::extract_type_refs(state, node, &id);
+        Self::push_signature(state, node, &id);
 
         // Extract call sites from the body.
         if let Some(body) = Self::find_child_by_kind(node, "function_body") {
@@ -1016,11 +1048,30 @@ impl KotlinExtractor {
         if let Some(parent_id) = state.parent_node_id() {
             state.edges.push(Edge {
                 source: parent_id.to_string(),
-                target: id,
+                target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
+
+        // The declared type lives inside the `variable_declaration`.
+        if let Some(decl) = Self::find_child_by_kind(node, "variable_declaration") {
+            let mut cursor = decl.walk();
+            for child in decl.named_children(&mut cursor) {
+                if child.kind() != "simple_identifier" {
+                    push_type_refs(
+                        &mut state.unresolved_refs,
+                        child,
+                        &state.source,
+                        &KOTLIN_TYPE_REFS,
+                        &id,
+                        &EdgeKind::TypeOf,
+                        &state.file_path,
+                    );
+                }
+            }
+        }
     }
 
     // -----------------------------------------------------------------------
@@ -1074,11 +1125,15 @@ impl KotlinExtractor {
         if let Some(parent_id) = state.parent_node_id() {
             state.edges.push(Edge {
                 source: parent_id.to_string(),
-                target: id,
+                target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
+
+        Self::extract_type_refs(state, node, &id);
+        Self::push_signature(state, node, &id);
     }
 
     // -----------------------------------------------------------------------
@@ -1345,6 +1400,10 @@ impl KotlinExtractor {
                 line: node.start_position().row as u32,
                 column: node.start_position().column as u32,
                 file_path: state.file_path.clone(),
+                receiver_type: None,
+                abi: None,
+                http_method: None,
+                sql: false,
             });
         }
     }
@@ -1427,6 +1486,10 @@ impl KotlinExtractor {
                         line: start_line,
                         column: start_column,
                         file_path: state.file_path.clone(),
+                        receiver_type: None,
+                        abi: None,
+                        http_method: None,
+                        sql: false,
                     });
 
                     // Direct Annotates edge from the annotation to the target.
@@ -1435,6 +1498,7 @@ impl KotlinExtractor {
                         target: target_id.to_string(),
                         kind: EdgeKind::Annotates,
                         line: Some(start_line),
+                        provenance: None,
                     });
                 }
                 if !cursor.goto_next_sibling() {
@@ -1475,82 +1539,122 @@ impl KotlinExtractor {
 
     /// Extract type references from Kotlin function parameters and return type.
     ///
-    /// Kotlin uses `user_type` containing `type_identifier` for type references.
-    /// Parameters are `parameter` nodes with `: Type` syntax.
+    /// Kotlin's grammar has no field names here, so the position decides: a
+    /// type before the function name is an extension receiver and the types in
+    /// `function_value_parameters` are parameters (both `Receives`); a type
+    /// after the parameters is the return type (`Returns`).
     fn extract_type_refs(state: &mut ExtractionState, node: TsNode<'_>, fn_node_id: &str) {
-        let kotlin_builtins: &[&str] = &[
-            "Unit",
-            "Int",
-            "Long",
-            "Short",
-            "Byte",
-            "Char",
-            "Float",
-            "Double",
-            "Boolean",
-            "String",
-            "Any",
-            "Nothing",
-            "Array",
-            "List",
-            "Map",
-            "Set",
-            "MutableList",
-            "MutableMap",
-            "MutableSet",
-        ];
-
+        let mut after_params = false;
         let mut cursor = node.walk();
-        if !cursor.goto_first_child() {
-            return;
-        }
-        loop {
-            let child = cursor.node();
+        for child in node.named_children(&mut cursor) {
             match child.kind() {
                 "function_value_parameters" => {
-                    Self::extract_type_refs(state, child, fn_node_id);
+                    push_type_refs(
+                        &mut state.unresolved_refs,
+                        child,
+                        &state.source,
+                        &KOTLIN_TYPE_REFS,
+                        fn_node_id,
+                        &EdgeKind::Receives,
+                        &state.file_path,
+                    );
+                    after_params = true;
                 }
-                "parameter" | "user_type" | "nullable_type" => {
-                    Self::collect_kotlin_type_ids(state, child, fn_node_id, kotlin_builtins);
+                "user_type" | "nullable_type" | "function_type" | "parenthesized_type" => {
+                    let kind = if after_params {
+                        EdgeKind::Returns
+                    } else {
+                        EdgeKind::Receives
+                    };
+                    push_type_refs(
+                        &mut state.unresolved_refs,
+                        child,
+                        &state.source,
+                        &KOTLIN_TYPE_REFS,
+                        fn_node_id,
+                        &kind,
+                        &state.file_path,
+                    );
                 }
                 _ => {}
             }
-            if !cursor.goto_next_sibling() {
-                break;
-            }
         }
     }
 
-    /// Recursively collect `type_identifier` inside Kotlin type nodes.
-    fn collect_kotlin_type_ids(
-        state: &mut ExtractionState,
-        node: TsNode<'_>,
-        fn_node_id: &str,
-        builtins: &[&str],
-    ) {
-        if node.kind() == "type_identifier" {
-            let type_name = state.node_text(node);
-            if !builtins.contains(&type_name.as_str()) {
-                state.unresolved_refs.push(UnresolvedRef {
-                    from_node_id: fn_node_id.to_string(),
-                    reference_name: type_name,
-                    reference_kind: EdgeKind::Uses,
-                    line: node.start_position().row as u32,
-                    column: node.start_position().column as u32,
-                    file_path: state.file_path.clone(),
-                });
+    /// Records the declared parameters and return type of a function or
+    /// constructor. As for type references, the position decides: the type
+    /// after `function_value_parameters` is the return type, and an
+    /// extension receiver is not a parameter. Parameters with a default
+    /// value and `vararg` parameters are optional.
+    fn push_signature(state: &mut ExtractionState, node: TsNode<'_>, fn_node_id: &str) {
+        let mut params = Vec::new();
+        let mut return_type = None;
+        let mut after_params = false;
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            match child.kind() {
+                "function_value_parameters" => {
+                    params = Self::value_parameters(state, child);
+                    after_params = true;
+                }
+                "user_type" | "nullable_type" | "function_type" | "parenthesized_type"
+                    if after_params =>
+                {
+                    return_type = Some(state.node_text(child));
+                }
+                _ => {}
             }
-            return;
         }
-        let mut cursor = node.walk();
-        if cursor.goto_first_child() {
-            loop {
-                Self::collect_kotlin_type_ids(state, cursor.node(), fn_node_id, builtins);
-                if !cursor.goto_next_sibling() {
-                    break;
+        state
+            .signatures
+            .extend(signature(fn_node_id, params, return_type.as_deref()));
+    }
+
+    /// The parameters of a `function_value_parameters` list. Modifiers and
+    /// default values are siblings of the `parameter` they belong to.
+    fn value_parameters(state: &ExtractionState, list: TsNode<'_>) -> Vec<Param> {
+        let mut params: Vec<Param> = Vec::new();
+        let mut vararg = false;
+        let mut default_follows = false;
+        let mut cursor = list.walk();
+        for child in list.children(&mut cursor) {
+            match child.kind() {
+                "parameter_modifiers" => vararg = state.node_text(child).contains("vararg"),
+                "parameter" => {
+                    let mut inner = child.walk();
+                    let parts: Vec<TsNode<'_>> = child.named_children(&mut inner).collect();
+                    let name = parts
+                        .iter()
+                        .find(|p| p.kind() == "simple_identifier")
+                        .map(|n| state.node_text(*n))
+                        .unwrap_or_default();
+                    // A `vararg` parameter collects an array, like Java's `T...`.
+                    let ty = parts
+                        .iter()
+                        .find(|p| p.kind() != "simple_identifier")
+                        .map(|t| {
+                            let text = state.node_text(*t);
+                            if vararg {
+                                format!("{text}...")
+                            } else {
+                                text
+                            }
+                        });
+                    params.push(param(&name, ty.as_deref(), None, vararg));
+                    vararg = false;
+                }
+                "=" => default_follows = true,
+                _ if default_follows && child.is_named() => {
+                    if let Some(last) = params.last_mut() {
+                        last.default_value = Some(state.node_text(child));
+                        last.optional = true;
+                    }
+                    default_follows = false;
                 }
+                _ => {}
             }
         }
+        params
     }
 
     /// Recursively find `call_expression` nodes and create unresolved Calls references.
@@ -1562,6 +1666,13 @@ impl KotlinExtractor {
                 match child.kind() {
                     "call_expression" => {
                         let callee_name = Self::extract_call_name(state, child);
+                        let receiver = receiver_type(
+                            child,
+                            &callee_name,
+                            &state.source,
+                            &state.type_decls,
+                            &KOTLIN_RECEIVERS,
+                        );
                         state.unresolved_refs.push(UnresolvedRef {
                             from_node_id: fn_node_id.to_string(),
                             reference_name: callee_name,
@@ -1569,6 +1680,10 @@ impl KotlinExtractor {
                             line: child.start_position().row as u32,
                             column: child.start_position().column as u32,
                             file_path: state.file_path.clone(),
+                            receiver_type: receiver,
+                            abi: None,
+                            http_method: None,
+                            sql: false,
                         });
                         Self::extract_call_sites(state, child, fn_node_id);
                     }
@@ -1623,6 +1738,7 @@ impl KotlinExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: state.signatures,
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -1641,4 +1757,12 @@ impl crate::extraction::LanguageExtractor for KotlinExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         KotlinExtractor::extract_kotlin(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
+
+    fn extracts_signatures(&self) -> bool {
+        true
+    }
 }
@@ -1,12 +1,16 @@
 /// Tree-sitter based Markdown source code extractor.
 ///
 /// Parses Markdown source files and emits nodes and edges for the code graph.
+/// Code spans and fenced code blocks that mention symbols become `Uses`
+/// references from the enclosing section, linking docs to the code they
+/// describe.
+use std::collections::HashSet;
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 use tree_sitter::{Node as TsNode, Parser, Tree};
 
 use crate::types::{
-    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, Visibility,
+    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, UnresolvedRef, Visibility,
 };
 
 pub struct MarkdownExtractor;
@@ -14,6 +18,7 @@ pub struct MarkdownExtractor;
 struct ExtractionState {
     nodes: Vec<Node>,
     edges: Vec<Edge>,
+    unresolved_refs: Vec<UnresolvedRef>,
     file_path: String,
     source: Vec<u8>,
     timestamp: u64,
@@ -29,6 +34,7 @@ impl ExtractionState {
         Self {
             nodes: Vec::new(),
             edges: Vec::new(),
+            unresolved_refs: Vec::new(),
             file_path: file_path.to_string(),
             source: source.as_bytes().to_vec(),
             timestamp,
@@ -92,7 +98,8 @@ impl MarkdownExtractor {
         ExtractionResult {
             nodes: state.nodes,
             edges: state.edges,
-            unresolved_refs: Vec::new(),
+            unresolved_refs: state.unresolved_refs,
+            signatures: Vec::new(),
             errors: Vec::new(),
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -131,6 +138,12 @@ impl MarkdownExtractor {
             "link" => {
                 Self::visit_link(state, node);
             }
+            "code_span" => {
+                Self::visit_code_span(state, node);
+            }
+            "fenced_code_block" => {
+                Self::visit_code_block(state, node);
+            }
             _ => {
                 Self::visit_children(state, node);
             }
@@ -219,11 +232,85 @@ impl MarkdownExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(node.start_position().row as u32),
+                provenance: None,
             });
         }
 
         state.nodes.push(node_obj);
         state.node_stack.push((title_node, id, level));
+
+        // Code spans in the title belong to the new section.
+        if let Some(content) = node
+            .children(&mut node.walk())
+            .find(|n| n.kind() == "heading_content")
+        {
+            Self::visit_children(state, content);
+        }
+    }
+
+    /// Records a `Uses` reference from the current section.
+    fn push_reference(state: &mut ExtractionState, name: &str, line: u32, column: u32) {
+        let Some((_, section_id, _)) = state.node_stack.last() else {
+            return;
+        };
+        state.unresolved_refs.push(UnresolvedRef {
+            from_node_id: section_id.clone(),
+            reference_name: name.to_string(),
+            reference_kind: EdgeKind::Uses,
+            line,
+            column,
+            file_path: state.file_path.clone(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
+        });
+    }
+
+    /// Links an inline code span such as `` `ReferenceResolver::resolve_all()` ``
+    /// to the symbol it names.
+    fn visit_code_span(state: &mut ExtractionState, node: TsNode<'_>) {
+        let text = state.node_text(node);
+        let text = text.trim_matches('`');
+        if let Some(name) = span_symbol(text) {
+            let start = node.start_position();
+            Self::push_reference(state, name, start.row as u32, start.column as u32);
+        }
+    }
+
+    /// Links a fenced code block to the symbols it mentions: qualified paths
+    /// and identifiers that look like code rather than prose. Blocks of
+    /// shell sessions and data formats are skipped.
+    fn visit_code_block(state: &mut ExtractionState, node: TsNode<'_>) {
+        let info = node
+            .children(&mut node.walk())
+            .find(|n| n.kind() == "info_string")
+            .map(|n| state.node_text(n))
+            .unwrap_or_default();
+        let language = info.split_whitespace().next().unwrap_or("");
+        if is_non_code_block(language) {
+            return;
+        }
+        let Some(content) = node
+            .children(&mut node.walk())
+            .find(|n| n.kind() == "code_fence_content")
+        else {
+            return;
+        };
+        let text = state.node_text(content);
+        let start = content.start_position();
+        let mut seen = HashSet::new();
+        for (offset, name) in block_symbols(&text) {
+            if !seen.insert(name) {
+                continue;
+            }
+            let before = &text[..offset];
+            let row = before.matches('\n').count();
+            let column = before
+                .rfind('\n')
+                .map_or(start.column + offset, |nl| offset - nl - 1);
+            Self::push_reference(state, name, (start.row + row) as u32, column as u32);
+        }
     }
 
     fn visit_link(state: &mut ExtractionState, node: TsNode<'_>) {
@@ -261,11 +348,162 @@ impl MarkdownExtractor {
                 target: target_id,
                 kind: EdgeKind::Uses,
                 line: Some(node.start_position().row as u32),
+                provenance: None,
             });
         }
     }
 }
 
+/// Splits `text` into an identifier path (`a::b`, `a.b`) and whatever
+/// follows it. Returns `None` unless `text` starts with an identifier.
+fn identifier_path(text: &str) -> Option<(&str, &str)> {
+    let bytes = text.as_bytes();
+    let is_start = |b: u8| b.is_ascii_alphabetic() || b == b'_';
+    let is_part = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+    if !bytes.first().copied().is_some_and(is_start) {
+        return None;
+    }
+    let mut end = 0;
+    let mut i = 0;
+    while i < bytes.len() && is_part(bytes[i]) {
+        i += 1;
+        end = i;
+        let separator = ["::", "."]
+            .into_iter()
+            .find(|sep| text[i..].starts_with(sep))
+            .map_or(0, str::len);
+        if separator > 0 && bytes.get(i + separator).copied().is_some_and(is_start) {
+            i += separator;
+        }
+    }
+    Some((&text[..end], &text[end..]))
+}
+
+/// True when an identifier path reads as a code symbol rather than a word:
+/// it is qualified with `::`, or contains an underscore, or mixes upper and
+/// lower case.
+fn is_symbol_like(path: &str) -> bool {
+    path.contains("::")
+        || path.contains('_')
+        || (path.chars().any(|c| c.is_ascii_uppercase())
+            && path.chars().any(|c| c.is_ascii_lowercase()))
+}
+
+/// Returns the symbol an inline code span names, if it names exactly one:
+/// `resolve_all`, `ReferenceResolver::resolve_all()` or
+/// `self.db.get_node(id)`, but not `cargo test` or `Cargo.toml`.
+fn span_symbol(text: &str) -> Option<&str> {
+    let (path, rest) = identifier_path(text.trim())?;
+    let called = rest.starts_with('(') && rest.ends_with(')');
+    if !(rest.is_empty() || called) {
+        return None;
+    }
+    if !path.contains("::") {
+        if let Some((_, ext)) = path.rsplit_once('.') {
+            if !called && (is_code_extension(ext) || is_data_extension(ext)) {
+                return None;
+            }
+        }
+    }
+    (called || is_symbol_like(path)).then_some(path)
+}
+
+/// Returns the symbols mentioned in a code block, with their byte offsets:
+/// identifier paths that are symbol-like, skipping string literals and
+/// comments only roughly (by ignoring paths right after a quote). Names the
+/// block binds itself (`let` bindings, parameters) and paths through a
+/// lowercase receiver (`graph.load_nodes`) are locals, not symbols.
+fn block_symbols(text: &str) -> Vec<(usize, &str)> {
+    let mut paths = Vec::new();
+    let mut i = 0;
+    while i < text.len() {
+        let previous = text[..i].chars().next_back();
+        let at_boundary = previous.is_none_or(|c| !(c.is_alphanumeric() || c == '_'));
+        if at_boundary {
+            if let Some((path, _)) = identifier_path(&text[i..]) {
+                if !matches!(previous, Some('"' | '\'')) {
+                    paths.push((i, path));
+                }
+                i += path.len();
+                continue;
+            }
+        }
+        i += text[i..].chars().next().map_or(1, char::len_utf8);
+    }
+    let bindings = block_bindings(text, &paths);
+    paths.retain(|&(_, path)| {
+        let receiver = path.split_once('.').map(|(head, _)| head);
+        let head = path.split(['.', ':']).next().unwrap_or(path);
+        is_symbol_like(path)
+            && !bindings.contains(head)
+            && receiver.is_none_or(|r| r.starts_with(|c: char| c.is_ascii_uppercase()))
+    });
+    paths
+}
+
+/// Keywords introducing a local binding in the languages code blocks are
+/// usually written in.
+const BINDING_KEYWORDS: &[&str] = &["let", "mut", "var", "val", "const", "for", "auto"];
+
+/// Returns the names a code block binds locally: those following a binding
+/// keyword, parameters (`(name: Type`, `, name: Type`) and assignments at
+/// the start of a line (`name = ...`, `name := ...`).
+fn block_bindings<'a>(text: &str, paths: &[(usize, &'a str)]) -> HashSet<&'a str> {
+    let mut bindings = HashSet::new();
+    let mut keyword = false;
+    for &(offset, path) in paths {
+        let before = text[..offset].trim_end_matches([' ', '\t']);
+        let after = text[offset + path.len()..].trim_start_matches([' ', '\t']);
+        let at_line_start = before.is_empty() || before.ends_with('\n');
+        let parameter =
+            before.ends_with(['(', ',']) && after.starts_with(':') && !after.starts_with("::");
+        let assigned = at_line_start
+            && (after.starts_with(":=") || (after.starts_with('=') && !after.starts_with("==")));
+        if keyword || parameter || assigned {
+            bindings.insert(path);
+        }
+        keyword = BINDING_KEYWORDS.contains(&path);
+    }
+    bindings
+}
+
+/// Info strings of fenced blocks that hold commands, output or data rather
+/// than code.
+fn is_non_code_block(language: &str) -> bool {
+    matches!(
+        language.to_ascii_lowercase().as_str(),
+        "text"
+            | "txt"
+            | "plain"
+            | "console"
+            | "shell"
+            | "sh"
+            | "bash"
+            | "zsh"
+            | "powershell"
+            | "output"
+            | "log"
+            | "diff"
+            | "json"
+            | "jsonc"
+            | "toml"
+            | "yaml"
+            | "yml"
+            | "ini"
+            | "xml"
+            | "html"
+            | "csv"
+            | "mermaid"
+    )
+}
+
+fn is_data_extension(ext: &str) -> bool {
+    matches!(
+        ext,
+        "md" | "toml" | "json" | "yaml" | "yml" | "lock" | "txt" | "html" | "css" | "scm"
+    )
+}
+
 fn is_code_extension(ext: &str) -> bool {
     // Only include actual programming-language source files.
     // Config (yaml, toml, json), markup (html, css, markdown), and
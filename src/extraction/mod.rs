@@ -1,18 +1,31 @@
 // Lite — always available (no cfg needed)
+mod build_extractor;
 mod c_extractor;
+mod component_extractor;
+mod config_extractor;
+mod config_parser;
 mod cpp_extractor;
 mod csharp_extractor;
 mod go_extractor;
+mod graphql_extractor;
 mod java_extractor;
 mod kotlin_extractor;
 mod python_extractor;
+mod routes;
 mod rust_extractor;
 mod scala_extractor;
+mod sql;
 mod swift_extractor;
+mod tags_extractor;
+mod terraform_extractor;
 mod typescript_extractor;
 
 pub mod complexity;
+pub mod domain;
+pub mod receivers;
+pub mod signatures;
 pub mod ts_provider;
+pub mod type_refs;
 
 #[cfg(feature = "lang-bash")]
 mod bash_extractor;
@@ -84,16 +97,22 @@ mod sql_extractor;
 mod zig_extractor;
 
 // Lite — always available (no cfg needed)
+pub use build_extractor::{CMakeExtractor, MakeExtractor, StarlarkBuildExtractor};
 pub use c_extractor::CExtractor;
+pub use component_extractor::{AstroExtractor, SvelteExtractor, VueExtractor};
+pub use config_extractor::{JsonExtractor, TomlExtractor, YamlExtractor};
 pub use cpp_extractor::CppExtractor;
 pub use csharp_extractor::CSharpExtractor;
 pub use go_extractor::GoExtractor;
+pub use graphql_extractor::GraphqlExtractor;
 pub use java_extractor::JavaExtractor;
 pub use kotlin_extractor::KotlinExtractor;
 pub use python_extractor::PythonExtractor;
 pub use rust_extractor::RustExtractor;
 pub use scala_extractor::ScalaExtractor;
 pub use swift_extractor::SwiftExtractor;
+pub use tags_extractor::TagsExtractor;
+pub use terraform_extractor::TerraformExtractor;
 pub use typescript_extractor::TypeScriptExtractor;
 
 // Medium
@@ -164,6 +183,11 @@ pub use sql_extractor::SqlExtractor;
 #[cfg(feature = "lang-zig")]
 pub use zig_extractor::ZigExtractor;
 
+use std::collections::HashSet;
+use std::sync::{LazyLock, Mutex, PoisonError};
+
+use tree_sitter::Tree;
+
 use crate::types::ExtractionResult;
 
 /// Trait for language-specific source code extractors.
@@ -174,6 +198,13 @@ pub trait LanguageExtractor: Send + Sync {
     /// File extensions this extractor handles (without leading dot).
     fn extensions(&self) -> &[&str];
 
+    /// Whole file names this extractor handles whatever their extension,
+    /// such as `Makefile` or `CMakeLists.txt`. These take precedence over
+    /// extensions.
+    fn file_names(&self) -> &[&str] {
+        &[]
+    }
+
     /// Human-readable language name.
     fn language_name(&self) -> &str;
 
@@ -182,6 +213,107 @@ pub trait LanguageExtractor: Send + Sync {
     /// `file_path` is the relative path used for qualified names and node IDs.
     /// `source` is the source code to parse.
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult;
+
+    /// Like [`extract`](Self::extract), but also returns the tree-sitter tree
+    /// the result was built from, so passes that run after extraction (the
+    /// domain rules) can query it instead of parsing the file again.
+    /// Extractors that keep no tree return `None`.
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        (self.extract(file_path, source), None)
+    }
+
+    /// Whether the extractor records the parameter and return types of
+    /// callables in [`ExtractionResult::signatures`], which
+    /// `tokensave_signature_search` matches against.
+    fn extracts_signatures(&self) -> bool {
+        false
+    }
+}
+
+/// Returns `'static` copies of `extensions`, without leading dots, for the
+/// [`LanguageExtractor::extensions`] of extractors built at run time from a
+/// plugin manifest or a project config. Each distinct extension is leaked
+/// once per process, however often such extractors are rebuilt.
+pub(crate) fn intern_extensions<'a>(
+    extensions: impl IntoIterator<Item = &'a str>,
+) -> Vec<&'static str> {
+    static INTERNED: LazyLock<Mutex<HashSet<&'static str>>> = LazyLock::new(Mutex::default);
+    let mut interned = INTERNED.lock().unwrap_or_else(PoisonError::into_inner);
+    extensions
+        .into_iter()
+        .map(|ext| ext.trim_start_matches('.'))
+        .filter(|ext| !ext.is_empty())
+        .map(|ext| {
+            if let Some(ext) = interned.get(ext) {
+                return *ext;
+            }
+            let ext: &'static str = Box::leak(ext.to_string().into_boxed_str());
+            interned.insert(ext);
+            ext
+        })
+        .collect()
+}
+
+/// Finishes the primary extraction of `file_path`, as every extraction path
+/// does before storing it: sanitizes `result`, adds what the project's
+/// domain `rules` find in `tree`, then assigns the stable node IDs.
+pub(crate) fn finish_extraction(
+    result: &mut ExtractionResult,
+    file_path: &str,
+    source: &str,
+    tree: Option<&Tree>,
+    rules: Option<&domain::DomainRules>,
+) {
+    result.sanitize();
+    if let Some(rules) = rules {
+        rules.apply(file_path, source, tree, result);
+    }
+    result.assign_stable_ids();
+}
+
+/// True for the kinds of nodes with a body that code runs in: functions,
+/// methods, constructors, procedures and test cases.
+pub(crate) fn is_callable(node: &crate::types::Node) -> bool {
+    use crate::types::NodeKind;
+    matches!(
+        node.kind,
+        NodeKind::Function
+            | NodeKind::Method
+            | NodeKind::StructMethod
+            | NodeKind::Constructor
+            | NodeKind::AbstractMethod
+            | NodeKind::ArrowFunction
+            | NodeKind::Procedure
+            | NodeKind::TestCase
+    )
+}
+
+/// The callable nodes of one file, for attributing what a later pass finds
+/// in it to the function around it.
+pub(crate) struct Callables {
+    /// (start line, end line, id) of each callable.
+    spans: Vec<(u32, u32, String)>,
+}
+
+impl Callables {
+    /// Collects the callable nodes among `nodes`.
+    pub(crate) fn new(nodes: &[crate::types::Node]) -> Self {
+        let spans = nodes
+            .iter()
+            .filter(|n| is_callable(n))
+            .map(|n| (n.start_line, n.end_line, n.id.clone()))
+            .collect();
+        Self { spans }
+    }
+
+    /// The ID of the innermost callable around `line`.
+    pub(crate) fn enclosing(&self, line: u32) -> Option<String> {
+        self.spans
+            .iter()
+            .filter(|(start, end, _)| *start <= line && line <= *end)
+            .max_by_key(|(start, end, _)| (*start, std::cmp::Reverse(*end)))
+            .map(|(.., id)| id.clone())
+    }
 }
 
 /// Registry of all available language extractors.
@@ -189,6 +321,10 @@ pub trait LanguageExtractor: Send + Sync {
 /// Dispatches to the correct extractor based on file extension.
 pub struct LanguageRegistry {
     extractors: Vec<Box<dyn LanguageExtractor>>,
+    /// Extensions claimed by plugins and grammar libraries that this process
+    /// does not load. Files with these extensions are indexed, but only the
+    /// `extract-worker` subprocess can extract them.
+    worker_extensions: Vec<String>,
 }
 
 impl LanguageRegistry {
@@ -202,12 +338,23 @@ impl LanguageRegistry {
             Box::new(JavaExtractor),
             Box::new(ScalaExtractor),
             Box::new(TypeScriptExtractor),
+            Box::new(VueExtractor),
+            Box::new(SvelteExtractor),
+            Box::new(AstroExtractor),
             Box::new(PythonExtractor),
             Box::new(CExtractor),
             Box::new(CppExtractor),
             Box::new(CSharpExtractor),
             Box::new(KotlinExtractor),
             Box::new(SwiftExtractor),
+            Box::new(YamlExtractor),
+            Box::new(TomlExtractor),
+            Box::new(JsonExtractor),
+            Box::new(TerraformExtractor),
+            Box::new(GraphqlExtractor),
+            Box::new(MakeExtractor),
+            Box::new(CMakeExtractor),
+            Box::new(StarlarkBuildExtractor),
         ];
 
         // Medium
@@ -278,23 +425,151 @@ impl LanguageRegistry {
         #[cfg(feature = "lang-fsharp")]
         extractors.push(Box::new(FSharpExtractor));
 
-        Self { extractors }
+        Self {
+            extractors,
+            worker_extensions: Vec::new(),
+        }
+    }
+
+    /// Creates a registry with the built-in extractors that also indexes the
+    /// files of the installed plugins, without loading them. Plugin files are
+    /// left to the `extract-worker` subprocess: [`Self::extractor_for_file`]
+    /// returns `None` for them, even when a built-in extractor handles the
+    /// same extension.
+    pub fn with_installed_plugins() -> Self {
+        let mut registry = Self::new();
+        for plugin in crate::plugins::discover_plugins() {
+            registry
+                .worker_extensions
+                .extend(plugin.manifest.extensions().map(str::to_string));
+        }
+        registry
+    }
+
+    /// Creates a registry with the built-in extractors and the installed
+    /// plugins loaded in front of them, so plugins take precedence for the
+    /// extensions they claim. Plugins that fail to load are reported on
+    /// stderr and skipped.
+    ///
+    /// Loading runs plugin code in this process; only the `extract-worker`
+    /// subprocess should call this.
+    pub fn with_loaded_plugins() -> Self {
+        let mut registry = Self::new();
+        let mut plugins: Vec<Box<dyn LanguageExtractor>> = Vec::new();
+        for plugin in crate::plugins::discover_plugins() {
+            match crate::plugins::PluginExtractor::load(&plugin) {
+                Ok(extractor) => plugins.push(Box::new(extractor)),
+                Err(e) => eprintln!("[tokensave] skipping plugin: {e}"),
+            }
+        }
+        registry.extractors.splice(0..0, plugins);
+        registry
+    }
+
+    /// Adds the languages a project declares in its config, in front of the
+    /// built-in extractors and plugins. Languages that fail to load are
+    /// skipped and their errors returned.
+    ///
+    /// Loading a grammar library runs its code in this process; only the
+    /// `extract-worker` subprocess should call this. Others call
+    /// [`Self::declare_tags_languages`].
+    pub fn add_tags_languages(
+        &mut self,
+        project_root: &std::path::Path,
+        languages: &[crate::config::TagsLanguageConfig],
+    ) -> Vec<crate::errors::TokenSaveError> {
+        let mut extractors: Vec<Box<dyn LanguageExtractor>> = Vec::new();
+        let mut errors = Vec::new();
+        for language in languages {
+            match TagsExtractor::from_config(project_root, language) {
+                Ok(extractor) => extractors.push(Box::new(extractor)),
+                Err(e) => errors.push(e),
+            }
+        }
+        self.worker_extensions.retain(|ext| {
+            !extractors
+                .iter()
+                .any(|e| e.extensions().contains(&ext.as_str()))
+        });
+        self.extractors.splice(0..0, extractors);
+        errors
+    }
+
+    /// Adds the languages a project declares in its config without loading
+    /// any grammar library: languages with a bundled grammar are added as by
+    /// [`Self::add_tags_languages`], and the files of those with a grammar
+    /// library are left to the `extract-worker` subprocess, like plugin
+    /// files. Bundled languages that fail to load are skipped and their
+    /// errors returned.
+    pub fn declare_tags_languages(
+        &mut self,
+        project_root: &std::path::Path,
+        languages: &[crate::config::TagsLanguageConfig],
+    ) -> Vec<crate::errors::TokenSaveError> {
+        let (libraries, bundled): (Vec<_>, Vec<_>) = languages
+            .iter()
+            .cloned()
+            .partition(|language| language.grammar.is_none());
+        let errors = self.add_tags_languages(project_root, &bundled);
+        for language in &libraries {
+            self.worker_extensions.extend(
+                language
+                    .extensions
+                    .iter()
+                    .map(|ext| ext.trim_start_matches('.'))
+                    .filter(|ext| !ext.is_empty())
+                    .map(str::to_string),
+            );
+        }
+        errors
     }
 
-    /// Returns the extractor for a file path based on its extension.
+    /// Returns the extractor for a file path based on its file name or
+    /// extension.
     pub fn extractor_for_file(&self, path: &str) -> Option<&dyn LanguageExtractor> {
+        if let Some(extractor) = self.extractor_for_file_name(path) {
+            return Some(extractor);
+        }
         let ext = path.rsplit('.').next()?;
+        if self.worker_extensions.iter().any(|e| e == ext) {
+            return None;
+        }
         self.extractors
             .iter()
             .find(|e| e.extensions().contains(&ext))
             .map(std::convert::AsRef::as_ref)
     }
 
-    /// Returns all supported file extensions across all extractors.
+    /// Returns the extractor claiming the file name of `path`, if any.
+    fn extractor_for_file_name(&self, path: &str) -> Option<&dyn LanguageExtractor> {
+        let file_name = path.rsplit(['/', '\\']).next()?;
+        self.extractors
+            .iter()
+            .find(|e| e.file_names().contains(&file_name))
+            .map(std::convert::AsRef::as_ref)
+    }
+
+    /// Returns true when a built-in extractor claims the file name of
+    /// `path`, whatever its extension.
+    pub fn handles_file_name(&self, path: &str) -> bool {
+        self.extractor_for_file_name(path).is_some()
+    }
+
+    /// Returns true when `path` is extracted by a plugin or grammar library
+    /// that only the `extract-worker` subprocess loads.
+    pub fn is_worker_file(&self, path: &str) -> bool {
+        path.rsplit('.')
+            .next()
+            .is_some_and(|ext| self.worker_extensions.iter().any(|e| e == ext))
+    }
+
+    /// Returns all supported file extensions across all extractors and
+    /// installed plugins.
     pub fn supported_extensions(&self) -> Vec<&str> {
         self.extractors
             .iter()
             .flat_map(|e| e.extensions().iter().copied())
+            .chain(self.worker_extensions.iter().map(String::as_str))
             .collect()
     }
 }
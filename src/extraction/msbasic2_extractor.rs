@@ -88,6 +88,12 @@ impl MsBasic2Extractor {
     /// `file_path` is used for qualified names and node IDs (not for I/O).
     /// `source` is the BASIC source code to parse.
     pub fn extract_msbasic2(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -95,7 +101,7 @@ impl MsBasic2Extractor {
             Ok(tree) => tree,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -142,7 +148,7 @@ impl MsBasic2Extractor {
 
         state.node_stack.pop();
 
-        Self::build_result(state, start)
+        (Self::build_result(state, start), Some(tree))
     }
 
     /// Parse source code into a tree-sitter AST.
@@ -296,6 +302,7 @@ impl MsBasic2Extractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -429,6 +436,7 @@ impl MsBasic2Extractor {
                             target: fn_id.clone(),
                             kind: EdgeKind::Contains,
                             line: Some(start_line),
+                            provenance: None,
                         });
                     }
 
@@ -529,6 +537,10 @@ impl MsBasic2Extractor {
                         line: node.start_position().row as u32,
                         column: node.start_position().column as u32,
                         file_path: state.file_path.clone(),
+                        receiver_type: None,
+                        abi: None,
+                        http_method: None,
+                        sql: false,
                     });
                 }
             }
@@ -612,6 +624,7 @@ impl MsBasic2Extractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: Vec::new(),
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -630,4 +643,8 @@ impl crate::extraction::LanguageExtractor for MsBasic2Extractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         Self::extract_msbasic2(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
 }
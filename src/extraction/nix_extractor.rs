@@ -72,6 +72,12 @@ impl NixExtractor {
     /// `file_path` is used for qualified names and node IDs (not for I/O).
     /// `source` is the Nix source code to parse.
     pub fn extract_nix(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -79,7 +85,7 @@ impl NixExtractor {
             Ok(tree) => tree,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -117,7 +123,7 @@ impl NixExtractor {
 
         state.node_stack.pop();
 
-        Self::build_result(state, start)
+        (Self::build_result(state, start), Some(tree))
     }
 
     /// Parse source code into a tree-sitter AST.
@@ -278,6 +284,7 @@ impl NixExtractor {
                         target: id.clone(),
                         kind: EdgeKind::Contains,
                         line: Some(start_line),
+                        provenance: None,
                     });
                 }
 
@@ -339,6 +346,7 @@ impl NixExtractor {
                         target: id.clone(),
                         kind: EdgeKind::Contains,
                         line: Some(start_line),
+                        provenance: None,
                     });
                 }
 
@@ -399,6 +407,7 @@ impl NixExtractor {
                         target: id.clone(),
                         kind: EdgeKind::Contains,
                         line: Some(start_line),
+                        provenance: None,
                     });
                 }
 
@@ -519,6 +528,7 @@ impl NixExtractor {
                                         target: id.clone(),
                                         kind: EdgeKind::Contains,
                                         line: Some(attr_line),
+                                        provenance: None,
                                     });
                                 }
 
@@ -530,6 +540,10 @@ impl NixExtractor {
                                     line: start_line,
                                     column: start_column,
                                     file_path: state.file_path.clone(),
+                                    receiver_type: None,
+                                    abi: None,
+                                    http_method: None,
+                                    sql: false,
                                 });
                             }
                             if !attr_cursor.goto_next_sibling() {
@@ -738,6 +752,7 @@ impl NixExtractor {
                                         target: id,
                                         kind: EdgeKind::Contains,
                                         line: Some(start_line),
+                                        provenance: None,
                                     });
                                 }
                             }
@@ -851,6 +866,10 @@ impl NixExtractor {
                                 line: child.start_position().row as u32,
                                 column: child.start_position().column as u32,
                                 file_path: state.file_path.clone(),
+                                receiver_type: None,
+                                abi: None,
+                                http_method: None,
+                                sql: false,
                             });
                         }
 
@@ -950,6 +969,7 @@ impl NixExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -960,6 +980,10 @@ impl NixExtractor {
             line: start_line,
             column: start_column,
             file_path: state.file_path.clone(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
         });
     }
 
@@ -990,6 +1014,7 @@ impl NixExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: Vec::new(),
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -1038,4 +1063,8 @@ impl crate::extraction::LanguageExtractor for NixExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         Self::extract_nix(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
 }
@@ -76,6 +76,12 @@ impl ObjcExtractor {
     /// `file_path` is used for qualified names and node IDs (not for I/O).
     /// `source` is the Objective-C source code to parse.
     pub fn extract_objc(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -83,7 +89,7 @@ impl ObjcExtractor {
             Ok(tree) => tree,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -121,7 +127,7 @@ impl ObjcExtractor {
 
         state.node_stack.pop();
 
-        Self::build_result(state, start)
+        (Self::build_result(state, start), Some(tree))
     }
 
     /// Parse source code into a tree-sitter AST.
@@ -223,6 +229,7 @@ impl ObjcExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -280,6 +287,7 @@ impl ObjcExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -369,6 +377,7 @@ impl ObjcExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -453,6 +462,7 @@ impl ObjcExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -502,6 +512,7 @@ impl ObjcExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -576,6 +587,7 @@ impl ObjcExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -628,6 +640,10 @@ impl ObjcExtractor {
                         line,
                         column: child.start_position().column as u32,
                         file_path: state.file_path.clone(),
+                        receiver_type: None,
+                        abi: None,
+                        http_method: None,
+                        sql: false,
                     });
                 }
                 if !cursor.goto_next_sibling() {
@@ -689,6 +705,7 @@ impl ObjcExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -702,6 +719,10 @@ impl ObjcExtractor {
                 line: start_line,
                 column: superclass.start_position().column as u32,
                 file_path: state.file_path.clone(),
+                receiver_type: None,
+                abi: None,
+                http_method: None,
+                sql: false,
             });
         }
 
@@ -739,6 +760,10 @@ impl ObjcExtractor {
                             line,
                             column: type_id.start_position().column as u32,
                             file_path: state.file_path.clone(),
+                            receiver_type: None,
+                            abi: None,
+                            http_method: None,
+                            sql: false,
                         });
                     }
                 }
@@ -819,6 +844,7 @@ impl ObjcExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -900,6 +926,7 @@ impl ObjcExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -956,6 +983,7 @@ impl ObjcExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -1075,6 +1103,7 @@ impl ObjcExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -1133,6 +1162,7 @@ impl ObjcExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -1195,6 +1225,7 @@ impl ObjcExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -1222,6 +1253,10 @@ impl ObjcExtractor {
                                 line: child.start_position().row as u32,
                                 column: child.start_position().column as u32,
                                 file_path: state.file_path.clone(),
+                                receiver_type: None,
+                                abi: None,
+                                http_method: None,
+                                sql: false,
                             });
                         }
                         Self::extract_call_sites(state, child, fn_node_id);
@@ -1267,6 +1302,10 @@ impl ObjcExtractor {
                 line: node.start_position().row as u32,
                 column: node.start_position().column as u32,
                 file_path: state.file_path.clone(),
+                receiver_type: None,
+                abi: None,
+                http_method: None,
+                sql: false,
             });
         }
     }
@@ -1431,6 +1470,7 @@ impl ObjcExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: Vec::new(),
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -1449,4 +1489,8 @@ impl crate::extraction::LanguageExtractor for ObjcExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         ObjcExtractor::extract_objc(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
 }
@@ -84,6 +84,12 @@ impl PascalExtractor {
     /// `file_path` is used for qualified names and node IDs (not for I/O).
     /// `source` is the Pascal source code to parse.
     pub fn extract_pascal(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -91,7 +97,7 @@ impl PascalExtractor {
             Ok(tree) => tree,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -129,7 +135,7 @@ impl PascalExtractor {
 
         state.node_stack.pop();
 
-        Self::build_result(state, start)
+        (Self::build_result(state, start), Some(tree))
     }
 
     /// Parse source code into a tree-sitter AST.
@@ -224,6 +230,7 @@ impl PascalExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -275,6 +282,7 @@ impl PascalExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -357,6 +365,7 @@ impl PascalExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -368,6 +377,10 @@ impl PascalExtractor {
             line: start_line,
             column: start_column,
             file_path: state.file_path.clone(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
         });
     }
 
@@ -469,6 +482,7 @@ impl PascalExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -482,6 +496,10 @@ impl PascalExtractor {
                 line: parent_ref.start_position().row as u32,
                 column: parent_ref.start_position().column as u32,
                 file_path: state.file_path.clone(),
+                receiver_type: None,
+                abi: None,
+                http_method: None,
+                sql: false,
             });
         }
 
@@ -549,6 +567,7 @@ impl PascalExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -610,6 +629,7 @@ impl PascalExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -667,6 +687,7 @@ impl PascalExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -790,6 +811,7 @@ impl PascalExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -840,6 +862,7 @@ impl PascalExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -889,6 +912,7 @@ impl PascalExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -937,6 +961,7 @@ impl PascalExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -1007,6 +1032,7 @@ impl PascalExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -1077,6 +1103,7 @@ impl PascalExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -1157,6 +1184,7 @@ impl PascalExtractor {
                     target: id.clone(),
                     kind: EdgeKind::Contains,
                     line: Some(start_line),
+                    provenance: None,
                 });
             }
 
@@ -1169,6 +1197,10 @@ impl PascalExtractor {
                     line: start_line,
                     column: start_column,
                     file_path: state.file_path.clone(),
+                    receiver_type: None,
+                    abi: None,
+                    http_method: None,
+                    sql: false,
                 });
             }
 
@@ -1267,6 +1299,10 @@ impl PascalExtractor {
                                 line: child.start_position().row as u32,
                                 column: child.start_position().column as u32,
                                 file_path: state.file_path.clone(),
+                                receiver_type: None,
+                                abi: None,
+                                http_method: None,
+                                sql: false,
                             });
                         }
                         // Recurse into the call expression for nested calls.
@@ -1297,6 +1333,10 @@ impl PascalExtractor {
                                             line: fc.start_position().row as u32,
                                             column: fc.start_position().column as u32,
                                             file_path: state.file_path.clone(),
+                                            receiver_type: None,
+                                            abi: None,
+                                            http_method: None,
+                                            sql: false,
                                         });
                                     }
                                 }
@@ -1390,6 +1430,7 @@ impl PascalExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: Vec::new(),
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -1408,4 +1449,8 @@ impl crate::extraction::LanguageExtractor for PascalExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         PascalExtractor::extract_pascal(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
 }
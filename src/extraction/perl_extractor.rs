@@ -75,6 +75,12 @@ impl PerlExtractor {
     /// `file_path` is used for qualified names and node IDs (not for I/O).
     /// `source` is the Perl source code to parse.
     pub fn extract_perl(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -82,7 +88,7 @@ impl PerlExtractor {
             Ok(tree) => tree,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -120,7 +126,7 @@ impl PerlExtractor {
 
         state.node_stack.pop();
 
-        Self::build_result(state, start)
+        (Self::build_result(state, start), Some(tree))
     }
 
     /// Parse source code into a tree-sitter AST.
@@ -216,6 +222,7 @@ impl PerlExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -303,6 +310,7 @@ impl PerlExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -377,6 +385,7 @@ impl PerlExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -450,6 +459,7 @@ impl PerlExtractor {
                                 target: id,
                                 kind: EdgeKind::Contains,
                                 line: Some(start_line),
+                                provenance: None,
                             });
                         }
                     }
@@ -529,6 +539,10 @@ impl PerlExtractor {
                                     line: child.start_position().row as u32,
                                     column: child.start_position().column as u32,
                                     file_path: state.file_path.clone(),
+                                    receiver_type: None,
+                                    abi: None,
+                                    http_method: None,
+                                    sql: false,
                                 });
                             }
                         }
@@ -548,6 +562,10 @@ impl PerlExtractor {
                                         line: child.start_position().row as u32,
                                         column: child.start_position().column as u32,
                                         file_path: state.file_path.clone(),
+                                        receiver_type: None,
+                                        abi: None,
+                                        http_method: None,
+                                        sql: false,
                                     });
                                 }
                             }
@@ -574,6 +592,10 @@ impl PerlExtractor {
                                     line: child.start_position().row as u32,
                                     column: child.start_position().column as u32,
                                     file_path: state.file_path.clone(),
+                                    receiver_type: None,
+                                    abi: None,
+                                    http_method: None,
+                                    sql: false,
                                 });
                             } else {
                                 state.unresolved_refs.push(UnresolvedRef {
@@ -583,6 +605,10 @@ impl PerlExtractor {
                                     line: child.start_position().row as u32,
                                     column: child.start_position().column as u32,
                                     file_path: state.file_path.clone(),
+                                    receiver_type: None,
+                                    abi: None,
+                                    http_method: None,
+                                    sql: false,
                                 });
                             }
                         }
@@ -679,6 +705,7 @@ impl PerlExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: Vec::new(),
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -697,4 +724,8 @@ impl crate::extraction::LanguageExtractor for PerlExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         Self::extract_perl(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
 }
@@ -75,6 +75,12 @@ impl PhpExtractor {
     /// `file_path` is used for qualified names and node IDs (not for I/O).
     /// `source` is the PHP source code to parse.
     pub fn extract_php(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -82,7 +88,7 @@ impl PhpExtractor {
             Ok(tree) => tree,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -120,7 +126,7 @@ impl PhpExtractor {
 
         state.node_stack.pop();
 
-        Self::build_result(state, start)
+        (Self::build_result(state, start), Some(tree))
     }
 
     /// Parse source code into a tree-sitter AST.
@@ -215,6 +221,7 @@ impl PhpExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -274,6 +281,7 @@ impl PhpExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -332,6 +340,7 @@ impl PhpExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -397,6 +406,7 @@ impl PhpExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -456,6 +466,7 @@ impl PhpExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -515,6 +526,7 @@ impl PhpExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -590,6 +602,7 @@ impl PhpExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -639,6 +652,7 @@ impl PhpExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -755,6 +769,7 @@ impl PhpExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -766,6 +781,10 @@ impl PhpExtractor {
             line: start_line,
             column: start_column,
             file_path: state.file_path.clone(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
         });
     }
 
@@ -829,6 +848,7 @@ impl PhpExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -891,6 +911,7 @@ impl PhpExtractor {
                             target: id.clone(),
                             kind: EdgeKind::Contains,
                             line: Some(start_line),
+                            provenance: None,
                         });
                     }
 
@@ -959,6 +980,10 @@ impl PhpExtractor {
                     line,
                     column,
                     file_path: state.file_path.clone(),
+                    receiver_type: None,
+                    abi: None,
+                    http_method: None,
+                    sql: false,
                 });
             }
         }
@@ -982,6 +1007,10 @@ impl PhpExtractor {
                             line,
                             column,
                             file_path: state.file_path.clone(),
+                            receiver_type: None,
+                            abi: None,
+                            http_method: None,
+                            sql: false,
                         });
                     }
                     if !cursor.goto_next_sibling() {
@@ -1091,6 +1120,10 @@ impl PhpExtractor {
                                 line: child.start_position().row as u32,
                                 column: child.start_position().column as u32,
                                 file_path: state.file_path.clone(),
+                                receiver_type: None,
+                                abi: None,
+                                http_method: None,
+                                sql: false,
                             });
                         }
                         // Recurse for nested calls.
@@ -1110,6 +1143,10 @@ impl PhpExtractor {
                                 line: child.start_position().row as u32,
                                 column: child.start_position().column as u32,
                                 file_path: state.file_path.clone(),
+                                receiver_type: None,
+                                abi: None,
+                                http_method: None,
+                                sql: false,
                             });
                         }
                         Self::extract_call_sites(state, child, fn_node_id);
@@ -1127,6 +1164,10 @@ impl PhpExtractor {
                                 line: child.start_position().row as u32,
                                 column: child.start_position().column as u32,
                                 file_path: state.file_path.clone(),
+                                receiver_type: None,
+                                abi: None,
+                                http_method: None,
+                                sql: false,
                             });
                         }
                         Self::extract_call_sites(state, child, fn_node_id);
@@ -1235,6 +1276,10 @@ impl PhpExtractor {
                                     line: start_line,
                                     column: start_column,
                                     file_path: state.file_path.clone(),
+                                    receiver_type: None,
+                                    abi: None,
+                                    http_method: None,
+                                    sql: false,
                                 });
 
                                 // Direct Annotates edge from annotation to target.
@@ -1243,6 +1288,7 @@ impl PhpExtractor {
                                     target: target_id.to_string(),
                                     kind: EdgeKind::Annotates,
                                     line: Some(start_line),
+                                    provenance: None,
                                 });
                             }
                             if !inner.goto_next_sibling() {
@@ -1295,6 +1341,7 @@ impl PhpExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: Vec::new(),
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -1313,4 +1360,8 @@ impl crate::extraction::LanguageExtractor for PhpExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         Self::extract_php(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
 }
@@ -6,8 +6,12 @@ use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tree_sitter::{Node as TsNode, Parser, Tree};
 
 use crate::extraction::complexity::{count_complexity, PYTHON_COMPLEXITY};
+use crate::extraction::receivers::{receiver_type, TypeDecls, PYTHON_RECEIVERS};
+use crate::extraction::signatures::{param, signature};
+use crate::extraction::type_refs::{push_type_refs, PYTHON_TYPE_REFS};
 use crate::types::{
-    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, UnresolvedRef, Visibility,
+    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, Signature, UnresolvedRef,
+    Visibility,
 };
 
 /// Extracts code graph nodes and edges from Python source files using tree-sitter.
@@ -18,14 +22,22 @@ struct ExtractionState {
     nodes: Vec<Node>,
     edges: Vec<Edge>,
     unresolved_refs: Vec<UnresolvedRef>,
+    signatures: Vec<Signature>,
     errors: Vec<String>,
     /// Stack of (name, `node_id`) for building qualified names and parent edges.
     node_stack: Vec<(String, String)>,
     file_path: String,
     source: Vec<u8>,
+    /// Type declarations of the file, for receiver inference.
+    type_decls: TypeDecls,
     timestamp: u64,
     /// Depth of class nesting. > 0 means we are inside a class.
     class_depth: usize,
+    /// True inside a class pytest or unittest collects tests from.
+    in_test_class: bool,
+    /// Names bound to C libraries loaded through ctypes or cffi
+    /// (`lib = ctypes.CDLL("libz.so")`), whose methods are C functions.
+    c_libraries: Vec<String>,
 }
 
 impl ExtractionState {
@@ -38,12 +50,16 @@ impl ExtractionState {
             nodes: Vec::new(),
             edges: Vec::new(),
             unresolved_refs: Vec::new(),
+            signatures: Vec::new(),
             errors: Vec::new(),
             node_stack: Vec::new(),
             file_path: file_path.to_string(),
             source: source.as_bytes().to_vec(),
+            type_decls: TypeDecls::default(),
             timestamp,
             class_depth: 0,
+            in_test_class: false,
+            c_libraries: c_libraries(source),
         }
     }
 
@@ -69,12 +85,44 @@ impl ExtractionState {
     }
 }
 
+/// Calls loading a C library as an object whose attributes are its
+/// functions: ctypes' library classes and loaders, and cffi's `dlopen`.
+const C_LIBRARY_LOADERS: &[&str] = &["CDLL(", "PyDLL(", "WinDLL(", "LoadLibrary(", "dlopen("];
+
+/// The names assigned a C library loaded through ctypes or cffi anywhere in
+/// `source`: `lib` for `lib = ctypes.CDLL("libz.so")`, `self._lib` for
+/// `self._lib = ffi.dlopen(path)`.
+fn c_libraries(source: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    for line in source.lines() {
+        let Some((target, value)) = line.split_once('=') else {
+            continue;
+        };
+        // `lib: ctypes.CDLL = ...` annotates the name.
+        let target = target.split(':').next().unwrap_or(target).trim();
+        let is_name = !target.is_empty()
+            && target
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '.');
+        if is_name && C_LIBRARY_LOADERS.iter().any(|l| value.contains(l)) {
+            names.push(target.to_string());
+        }
+    }
+    names
+}
+
 impl PythonExtractor {
     /// Extract code graph nodes and edges from a Python source file.
     ///
     /// `file_path` is used for qualified names and node IDs (not for I/O).
     /// `source` is the Python source code to parse.
     pub fn extract_python(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -82,7 +130,7 @@ impl PythonExtractor {
             Ok(tree) => tree,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -116,11 +164,15 @@ impl PythonExtractor {
 
         // Walk the AST.
         let root = tree.root_node();
+        state.type_decls = TypeDecls::new(root, &state.source, &PYTHON_RECEIVERS);
         Self::visit_children(&mut state, root);
 
         state.node_stack.pop();
 
-        Self::build_result(state, start)
+        let mut result = Self::build_result(state, start);
+        super::routes::extract_routes(file_path, source, root, &mut result);
+        super::sql::extract_embedded_sql(file_path, source, root, &mut result);
+        (result, Some(tree))
     }
 
     /// Parse source code into a tree-sitter AST.
@@ -184,12 +236,7 @@ impl PythonExtractor {
         let name = Self::find_child_by_kind(node, "identifier")
             .map_or_else(|| "<anonymous>".to_string(), |n| state.node_text(n));
 
-        let in_class = state.class_depth > 0;
-        let kind = if in_class {
-            NodeKind::Method
-        } else {
-            NodeKind::Function
-        };
+        let kind = Self::function_kind(state, &name);
         let visibility = Self::python_visibility(&name);
         let signature = Some(Self::extract_function_signature(state, node));
         let docstring = Self::extract_docstring(state, node);
@@ -233,9 +280,14 @@ impl PythonExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
+        // Types named by the parameter and return annotations.
+        Self::extract_type_refs(state, node, &id);
+        Self::push_signature(state, node, &id);
+
         // Extract call sites from the function body.
         if let Some(body) = Self::find_child_by_kind(node, "block") {
             Self::extract_call_sites(state, body, &id);
@@ -289,6 +341,7 @@ impl PythonExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -296,6 +349,8 @@ impl PythonExtractor {
         Self::extract_base_classes(state, node, &id);
 
         // Visit class body.
+        let is_test_class = Self::is_test_class(state, node, &name);
+        let in_test_class = std::mem::replace(&mut state.in_test_class, is_test_class);
         state.node_stack.push((name.clone(), id));
         state.class_depth += 1;
         if let Some(body) = Self::find_child_by_kind(node, "block") {
@@ -303,6 +358,42 @@ impl PythonExtractor {
         }
         state.class_depth -= 1;
         state.node_stack.pop();
+        state.in_test_class = in_test_class;
+    }
+
+    /// The kind of the function `name` defined at the current nesting:
+    /// pytest runs module-level `test_*` functions, and both pytest and
+    /// unittest run the `test*` methods of test classes.
+    fn function_kind(state: &ExtractionState, name: &str) -> NodeKind {
+        if state.class_depth == 0 {
+            if name.starts_with("test_") {
+                NodeKind::TestCase
+            } else {
+                NodeKind::Function
+            }
+        } else if state.in_test_class && name.starts_with("test") {
+            NodeKind::TestCase
+        } else {
+            NodeKind::Method
+        }
+    }
+
+    /// True for a class pytest collects tests from (`Test*`) or a
+    /// `unittest.TestCase` subclass.
+    fn is_test_class(state: &ExtractionState, node: TsNode<'_>, name: &str) -> bool {
+        if name.starts_with("Test") {
+            return true;
+        }
+        let Some(bases) = Self::find_child_by_kind(node, "argument_list") else {
+            return false;
+        };
+        let mut cursor = bases.walk();
+        for base in bases.named_children(&mut cursor) {
+            if state.node_text(base).ends_with("TestCase") {
+                return true;
+            }
+        }
+        false
     }
 
     /// Extract a decorated definition (decorator + function or class).
@@ -321,13 +412,7 @@ impl PythonExtractor {
                 .map_or_else(|| "<anonymous>".to_string(), |n| state.node_text(n));
             let kind = match inner.kind() {
                 "class_definition" => NodeKind::Class,
-                _ => {
-                    if state.class_depth > 0 {
-                        NodeKind::Method
-                    } else {
-                        NodeKind::Function
-                    }
-                }
+                _ => Self::function_kind(state, &name),
             };
             let start_line = inner.start_position().row as u32;
             Some((kind, name, start_line))
@@ -387,6 +472,7 @@ impl PythonExtractor {
                             target: target_id,
                             kind: EdgeKind::Annotates,
                             line: Some(start_line),
+                            provenance: None,
                         });
                     }
                 }
@@ -563,6 +649,7 @@ impl PythonExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -574,6 +661,10 @@ impl PythonExtractor {
             line: start_line,
             column: start_column,
             file_path: state.file_path.clone(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
         });
     }
 
@@ -621,11 +712,24 @@ impl PythonExtractor {
                 if let Some(parent_id) = state.parent_node_id() {
                     state.edges.push(Edge {
                         source: parent_id.to_string(),
-                        target: id,
+                        target: id.clone(),
                         kind: EdgeKind::Contains,
                         line: Some(start_line),
+                        provenance: None,
                     });
                 }
+
+                if let Some(annotation) = node.child_by_field_name("type") {
+                    push_type_refs(
+                        &mut state.unresolved_refs,
+                        annotation,
+                        &state.source,
+                        &PYTHON_TYPE_REFS,
+                        &id,
+                        &EdgeKind::TypeOf,
+                        &state.file_path,
+                    );
+                }
             }
         }
     }
@@ -634,6 +738,86 @@ impl PythonExtractor {
     // Helper extraction methods
     // ----------------------------
 
+    /// Extract type references from parameter annotations and return type.
+    ///
+    /// Each annotated parameter's `type` field becomes `Receives` refs and the
+    /// `-> T` annotation becomes `Returns` refs. Unannotated parameters are
+    /// plain `identifier`s and are skipped.
+    fn extract_type_refs(state: &mut ExtractionState, node: TsNode<'_>, fn_node_id: &str) {
+        if let Some(params) = node.child_by_field_name("parameters") {
+            let mut cursor = params.walk();
+            for param in params.named_children(&mut cursor) {
+                if let Some(annotation) = param.child_by_field_name("type") {
+                    push_type_refs(
+                        &mut state.unresolved_refs,
+                        annotation,
+                        &state.source,
+                        &PYTHON_TYPE_REFS,
+                        fn_node_id,
+                        &EdgeKind::Receives,
+                        &state.file_path,
+                    );
+                }
+            }
+        }
+        if let Some(return_type) = node.child_by_field_name("return_type") {
+            push_type_refs(
+                &mut state.unresolved_refs,
+                return_type,
+                &state.source,
+                &PYTHON_TYPE_REFS,
+                fn_node_id,
+                &EdgeKind::Returns,
+                &state.file_path,
+            );
+        }
+    }
+
+    /// Records the declared parameters and return type of a function. The
+    /// leading `self` or `cls` of a method is a receiver, not a parameter.
+    fn push_signature(state: &mut ExtractionState, node: TsNode<'_>, id: &str) {
+        let mut params = Vec::new();
+        if let Some(list) = node.child_by_field_name("parameters") {
+            let mut cursor = list.walk();
+            for child in list.named_children(&mut cursor) {
+                let text =
+                    |field: &str| child.child_by_field_name(field).map(|n| state.node_text(n));
+                let (name, ty, default) = match child.kind() {
+                    "identifier" | "list_splat_pattern" | "dictionary_splat_pattern" => {
+                        (state.node_text(child), None, None)
+                    }
+                    // The name of a typed parameter is its first child, which
+                    // may itself be a `*args` or `**kwargs` pattern.
+                    "typed_parameter" => (
+                        child
+                            .named_child(0)
+                            .map(|n| state.node_text(n))
+                            .unwrap_or_default(),
+                        text("type"),
+                        None,
+                    ),
+                    "default_parameter" | "typed_default_parameter" => (
+                        text("name").unwrap_or_default(),
+                        text("type"),
+                        text("value"),
+                    ),
+                    _ => continue,
+                };
+                if params.is_empty() && state.class_depth > 0 && (name == "self" || name == "cls") {
+                    continue;
+                }
+                let optional = name.starts_with('*');
+                params.push(param(&name, ty.as_deref(), default.as_deref(), optional));
+            }
+        }
+        let return_type = node
+            .child_by_field_name("return_type")
+            .map(|t| state.node_text(t));
+        state
+            .signatures
+            .extend(signature(id, params, return_type.as_deref()));
+    }
+
     /// Extract base classes from a class definition's `argument_list`.
     fn extract_base_classes(state: &mut ExtractionState, node: TsNode<'_>, class_id: &str) {
         if let Some(arg_list) = Self::find_child_by_kind(node, "argument_list") {
@@ -653,6 +837,10 @@ impl PythonExtractor {
                                 line,
                                 column,
                                 file_path: state.file_path.clone(),
+                                receiver_type: None,
+                                abi: None,
+                                http_method: None,
+                                sql: false,
                             });
                         }
                         "attribute" => {
@@ -667,6 +855,10 @@ impl PythonExtractor {
                                 line,
                                 column,
                                 file_path: state.file_path.clone(),
+                                receiver_type: None,
+                                abi: None,
+                                http_method: None,
+                                sql: false,
                             });
                         }
                         _ => {}
@@ -787,7 +979,21 @@ impl PythonExtractor {
                         // Get the callee: the first named child (function being called).
                         let callee = child.named_child(0);
                         if let Some(callee) = callee {
-                            let callee_name = state.node_text(callee);
+                            let mut callee_name = state.node_text(callee);
+                            let receiver = receiver_type(
+                                child,
+                                &callee_name,
+                                &state.source,
+                                &state.type_decls,
+                                &PYTHON_RECEIVERS,
+                            );
+                            let mut abi = None;
+                            if let Some((library, function)) = callee_name.rsplit_once('.') {
+                                if state.c_libraries.iter().any(|l| l == library) {
+                                    callee_name = function.to_string();
+                                    abi = Some("C".to_string());
+                                }
+                            }
                             state.unresolved_refs.push(UnresolvedRef {
                                 from_node_id: fn_node_id.to_string(),
                                 reference_name: callee_name,
@@ -795,6 +1001,10 @@ impl PythonExtractor {
                                 line: child.start_position().row as u32,
                                 column: child.start_position().column as u32,
                                 file_path: state.file_path.clone(),
+                                receiver_type: receiver,
+                                abi,
+                                http_method: None,
+                                sql: false,
                             });
                         }
                         // Recurse into the call for nested calls.
@@ -867,6 +1077,7 @@ impl PythonExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: state.signatures,
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -885,4 +1096,12 @@ impl crate::extraction::LanguageExtractor for PythonExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         PythonExtractor::extract_python(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
+
+    fn extracts_signatures(&self) -> bool {
+        true
+    }
 }
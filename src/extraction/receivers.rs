@@ -0,0 +1,572 @@
+// Rust guideline compliant 2025-10-17
+//! Receiver type inference for method calls.
+//!
+//! For a call such as `self.db.get_node(id)` or `client.send(req)`, works
+//! out the declared type of the receiver expression (`self.db`, `client`)
+//! from the parameters, typed locals and fields visible at the call site,
+//! so the resolver can look for the method on that type instead of
+//! matching every method that shares its name. As with type-reference
+//! collection, each language supplies the node type names that declare
+//! bindings in its grammar.
+
+use std::collections::HashMap;
+
+use tree_sitter::Node as TsNode;
+
+use crate::extraction::type_refs::{
+    collect_type_mentions, TypeRefConfig, CSHARP_TYPE_REFS, GO_TYPE_REFS, JAVA_TYPE_REFS,
+    KOTLIN_TYPE_REFS, PYTHON_TYPE_REFS, RUST_TYPE_REFS, SCALA_TYPE_REFS, TYPESCRIPT_TYPE_REFS,
+};
+
+/// A grammar node that binds a name to a declared type.
+pub struct ReceiverBinding {
+    /// Node type of the declaration (e.g. `parameter`, `field_declaration`).
+    pub kind: &'static str,
+    /// Steps leading from the declaration to the bound name. Each step is a
+    /// field name or, failing that, the node type of a named child.
+    pub name: &'static [&'static str],
+    /// Field holding the declared type; `None` searches the whole node.
+    pub type_field: Option<&'static str>,
+}
+
+/// Configuration mapping tree-sitter node type names to receiver bindings.
+pub struct ReceiverConfig {
+    /// Receiver names that refer to the enclosing type (`self`, `this`).
+    pub self_names: &'static [&'static str],
+    /// Nodes whose body makes `self` refer to a type, with the steps to
+    /// that type's name (e.g. `impl_item` → `type`).
+    pub owner_kinds: &'static [(&'static str, &'static [&'static str])],
+    /// Nodes that declare a type's fields, with the steps to its name
+    /// (e.g. `struct_item` → `name`).
+    pub type_kinds: &'static [(&'static str, &'static [&'static str])],
+    /// Function-like nodes whose parameters and locals are visible inside.
+    pub scope_kinds: &'static [&'static str],
+    /// Parameter, local and field declarations.
+    pub bindings: &'static [ReceiverBinding],
+    /// Whether a bare field name refers to a field of the enclosing type
+    /// (Java, C#, Kotlin, Scala), not only `self.field`.
+    pub implicit_self: bool,
+    /// Wrapper types that stand for the type they wrap (`Arc<Database>`).
+    pub transparent_types: &'static [&'static str],
+    /// How declared types spell type names.
+    pub type_refs: &'static TypeRefConfig,
+}
+
+/// The type declarations of one file by type name, built once per file so
+/// that following a receiver chain does not search the whole tree for each
+/// field.
+#[derive(Default)]
+pub struct TypeDecls {
+    /// Node type and byte range of each declaration of a type.
+    by_name: HashMap<String, Vec<(&'static str, usize, usize)>>,
+}
+
+impl TypeDecls {
+    /// Indexes the declarations of `config.type_kinds` under `root`.
+    ///
+    /// Uses an explicit stack instead of recursion (NASA Power of 10, Rule 1).
+    pub fn new(root: TsNode<'_>, source: &[u8], config: &ReceiverConfig) -> Self {
+        let mut by_name: HashMap<String, Vec<(&'static str, usize, usize)>> = HashMap::new();
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            let declared = config
+                .type_kinds
+                .iter()
+                .find(|(kind, _)| *kind == node.kind())
+                .and_then(|(kind, steps)| Some((*kind, follow(node, steps)?)));
+            if let Some((kind, name)) = declared {
+                by_name
+                    .entry(simple_type_name(&text(name, source)))
+                    .or_default()
+                    .push((kind, node.start_byte(), node.end_byte()));
+            }
+            let mut cursor = node.walk();
+            stack.extend(node.named_children(&mut cursor));
+        }
+        Self { by_name }
+    }
+
+    /// Returns the declarations of `type_name` in the tree under `root`.
+    fn declarations<'t>(
+        &'t self,
+        root: TsNode<'t>,
+        type_name: &str,
+    ) -> impl Iterator<Item = TsNode<'t>> + 't {
+        self.by_name
+            .get(type_name)
+            .into_iter()
+            .flatten()
+            .filter_map(move |&(kind, start, end)| {
+                let mut node = root.descendant_for_byte_range(start, end)?;
+                while node.kind() != kind || node.byte_range() != (start..end) {
+                    node = node.parent()?;
+                }
+                Some(node)
+            })
+    }
+}
+
+/// Returns the declared type of the receiver of the method call `call`,
+/// whose callee text is `callee`, or `None` when the call has no receiver
+/// or its type is not declared anywhere visible. `decls` are the type
+/// declarations of the file.
+///
+/// Only chains of plain names are followed (`a.b.c()`, `this->a->b()`);
+/// receivers that contain calls or indexing are left alone.
+pub fn receiver_type(
+    call: TsNode<'_>,
+    callee: &str,
+    source: &[u8],
+    decls: &TypeDecls,
+    config: &ReceiverConfig,
+) -> Option<String> {
+    let path = receiver_path(callee)?;
+    let (head, rest) = path.split_first()?;
+
+    let root = root_of(call);
+    let mut current = head_type(call, head, root, source, decls, config)?;
+    for segment in rest {
+        current = field_type(root, &current, segment, source, decls, config)?;
+    }
+    Some(current)
+}
+
+/// Splits a callee into the names of its receiver chain: `self.db.get` →
+/// `["self", "db"]`. Returns `None` for unqualified callees and receivers
+/// that are not a plain chain of names.
+fn receiver_path(callee: &str) -> Option<Vec<String>> {
+    let normalized = callee
+        .replace("?.", ".")
+        .replace("!!.", ".")
+        .replace("->", ".");
+    let (receiver, _) = normalized.rsplit_once('.')?;
+    let path: Vec<String> = receiver.split('.').map(|s| s.trim().to_string()).collect();
+    let plain = path.iter().all(|segment| {
+        !segment.is_empty()
+            && segment
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+    });
+    plain.then_some(path)
+}
+
+/// Resolves the first name of a receiver chain: a parameter or local of an
+/// enclosing scope, `self`, or a field of the enclosing type.
+fn head_type(
+    call: TsNode<'_>,
+    head: &str,
+    root: TsNode<'_>,
+    source: &[u8],
+    decls: &TypeDecls,
+    config: &ReceiverConfig,
+) -> Option<String> {
+    let mut owner: Option<TsNode<'_>> = None;
+    let mut current = call.parent();
+    while let Some(node) = current {
+        let kind = node.kind();
+        if config.scope_kinds.contains(&kind) {
+            if let Some(declared) = find_binding(node, head, source, config) {
+                return Some(declared);
+            }
+        }
+        if config.owner_kinds.iter().any(|(k, _)| *k == kind) {
+            owner = Some(node);
+            break;
+        }
+        current = node.parent();
+    }
+
+    let owner = owner?;
+    let (_, steps) = config
+        .owner_kinds
+        .iter()
+        .find(|(k, _)| *k == owner.kind())?;
+    let owner_type = simple_type_name(&text(follow(owner, steps)?, source));
+    if config.self_names.contains(&head) {
+        return Some(owner_type);
+    }
+    if config.implicit_self {
+        return field_type(root, &owner_type, head, source, decls, config);
+    }
+    None
+}
+
+/// Returns the declared type of the field `field` of the type named
+/// `type_name`, looking in the type's declarations under `root`.
+fn field_type(
+    root: TsNode<'_>,
+    type_name: &str,
+    field: &str,
+    source: &[u8],
+    decls: &TypeDecls,
+    config: &ReceiverConfig,
+) -> Option<String> {
+    decls
+        .declarations(root, type_name)
+        .find_map(|decl| find_binding(decl, field, source, config))
+}
+
+/// Searches `scope` for a binding of `name` and returns its declared type.
+/// Nested scopes are not entered: their bindings are not visible here.
+///
+/// Uses an explicit stack instead of recursion (NASA Power of 10, Rule 1).
+fn find_binding(
+    scope: TsNode<'_>,
+    name: &str,
+    source: &[u8],
+    config: &ReceiverConfig,
+) -> Option<String> {
+    let mut stack = vec![scope];
+    while let Some(node) = stack.pop() {
+        let kind = node.kind();
+        if node != scope && config.scope_kinds.contains(&kind) {
+            continue;
+        }
+        if let Some(binding) = config.bindings.iter().find(|b| b.kind == kind) {
+            let bound = follow(node, binding.name).map(|n| text(n, source));
+            if bound.as_deref() == Some(name) {
+                let type_node = match binding.type_field {
+                    Some(field) => node.child_by_field_name(field)?,
+                    None => node,
+                };
+                return declared_type(type_node, source, config);
+            }
+        }
+        let count = node.named_child_count();
+        for i in (0..count).rev() {
+            if let Some(child) = node.named_child(i as u32) {
+                stack.push(child);
+            }
+        }
+    }
+    None
+}
+
+/// Picks the type a declared type expression stands for: the first type it
+/// names, looking through transparent wrappers.
+fn declared_type(type_node: TsNode<'_>, source: &[u8], config: &ReceiverConfig) -> Option<String> {
+    collect_type_mentions(type_node, source, config.type_refs)
+        .into_iter()
+        .map(|mention| simple_type_name(&mention.name))
+        .find(|name| !config.transparent_types.contains(&name.as_str()))
+}
+
+/// Follows `steps` from `node`: each step is a field name or the node type
+/// of a named child.
+fn follow<'t>(node: TsNode<'t>, steps: &[&str]) -> Option<TsNode<'t>> {
+    let mut current = node;
+    for step in steps {
+        let next = current.child_by_field_name(step).or_else(|| {
+            let mut cursor = current.walk();
+            let found = current
+                .named_children(&mut cursor)
+                .find(|c| c.kind() == *step);
+            found
+        });
+        current = next?;
+    }
+    Some(current)
+}
+
+/// Strips generic arguments and qualifiers: `crate::db::Database<T>` →
+/// `Database`.
+fn simple_type_name(name: &str) -> String {
+    let name = name.split(['<', '[']).next().unwrap_or(name);
+    let name = name.rsplit("::").next().unwrap_or(name);
+    name.rsplit('.').next().unwrap_or(name).trim().to_string()
+}
+
+fn text(node: TsNode<'_>, source: &[u8]) -> String {
+    node.utf8_text(source).unwrap_or("").trim().to_string()
+}
+
+fn root_of(node: TsNode<'_>) -> TsNode<'_> {
+    let mut root = node;
+    while let Some(parent) = root.parent() {
+        root = parent;
+    }
+    root
+}
+
+// ---------------------------------------------------------------------------
+// Per-language configurations
+// ---------------------------------------------------------------------------
+
+pub static RUST_RECEIVERS: ReceiverConfig = ReceiverConfig {
+    self_names: &["self"],
+    owner_kinds: &[("impl_item", &["type"])],
+    type_kinds: &[("struct_item", &["name"])],
+    scope_kinds: &["function_item", "closure_expression"],
+    bindings: &[
+        ReceiverBinding {
+            kind: "parameter",
+            name: &["pattern"],
+            type_field: Some("type"),
+        },
+        ReceiverBinding {
+            kind: "let_declaration",
+            name: &["pattern"],
+            type_field: Some("type"),
+        },
+        ReceiverBinding {
+            kind: "field_declaration",
+            name: &["name"],
+            type_field: Some("type"),
+        },
+    ],
+    implicit_self: false,
+    transparent_types: &[
+        "Box", "Rc", "Arc", "Option", "RefCell", "Cell", "Mutex", "RwLock",
+    ],
+    type_refs: &RUST_TYPE_REFS,
+};
+
+const TYPESCRIPT_OWNERS: &[(&str, &[&str])] = &[
+    ("class_declaration", &["name"]),
+    ("abstract_class_declaration", &["name"]),
+    ("class", &["name"]),
+];
+
+pub static TYPESCRIPT_RECEIVERS: ReceiverConfig = ReceiverConfig {
+    self_names: &["this"],
+    owner_kinds: TYPESCRIPT_OWNERS,
+    type_kinds: TYPESCRIPT_OWNERS,
+    scope_kinds: &[
+        "method_definition",
+        "function_declaration",
+        "generator_function_declaration",
+        "function_expression",
+        "arrow_function",
+    ],
+    bindings: &[
+        ReceiverBinding {
+            kind: "required_parameter",
+            name: &["pattern"],
+            type_field: Some("type"),
+        },
+        ReceiverBinding {
+            kind: "optional_parameter",
+            name: &["pattern"],
+            type_field: Some("type"),
+        },
+        ReceiverBinding {
+            kind: "variable_declarator",
+            name: &["name"],
+            type_field: Some("type"),
+        },
+        ReceiverBinding {
+            kind: "public_field_definition",
+            name: &["name"],
+            type_field: Some("type"),
+        },
+    ],
+    implicit_self: false,
+    transparent_types: &[],
+    type_refs: &TYPESCRIPT_TYPE_REFS,
+};
+
+const JAVA_OWNERS: &[(&str, &[&str])] = &[
+    ("class_declaration", &["name"]),
+    ("interface_declaration", &["name"]),
+    ("enum_declaration", &["name"]),
+    ("record_declaration", &["name"]),
+];
+
+pub static JAVA_RECEIVERS: ReceiverConfig = ReceiverConfig {
+    self_names: &["this"],
+    owner_kinds: JAVA_OWNERS,
+    type_kinds: JAVA_OWNERS,
+    scope_kinds: &[
+        "method_declaration",
+        "constructor_declaration",
+        "lambda_expression",
+    ],
+    bindings: &[
+        ReceiverBinding {
+            kind: "formal_parameter",
+            name: &["name"],
+            type_field: Some("type"),
+        },
+        ReceiverBinding {
+            kind: "local_variable_declaration",
+            name: &["declarator", "name"],
+            type_field: Some("type"),
+        },
+        ReceiverBinding {
+            kind: "field_declaration",
+            name: &["declarator", "name"],
+            type_field: Some("type"),
+        },
+    ],
+    implicit_self: true,
+    transparent_types: &[],
+    type_refs: &JAVA_TYPE_REFS,
+};
+
+const CSHARP_OWNERS: &[(&str, &[&str])] = &[
+    ("class_declaration", &["name"]),
+    ("struct_declaration", &["name"]),
+    ("record_declaration", &["name"]),
+    ("interface_declaration", &["name"]),
+];
+
+pub static CSHARP_RECEIVERS: ReceiverConfig = ReceiverConfig {
+    self_names: &["this"],
+    owner_kinds: CSHARP_OWNERS,
+    type_kinds: CSHARP_OWNERS,
+    scope_kinds: &[
+        "method_declaration",
+        "constructor_declaration",
+        "local_function_statement",
+        "lambda_expression",
+    ],
+    bindings: &[
+        ReceiverBinding {
+            kind: "parameter",
+            name: &["name"],
+            type_field: Some("type"),
+        },
+        ReceiverBinding {
+            kind: "variable_declaration",
+            name: &["variable_declarator", "name"],
+            type_field: Some("type"),
+        },
+        ReceiverBinding {
+            kind: "property_declaration",
+            name: &["name"],
+            type_field: Some("type"),
+        },
+    ],
+    implicit_self: true,
+    transparent_types: &[],
+    type_refs: &CSHARP_TYPE_REFS,
+};
+
+pub static PYTHON_RECEIVERS: ReceiverConfig = ReceiverConfig {
+    self_names: &["self", "cls"],
+    owner_kinds: &[("class_definition", &["name"])],
+    type_kinds: &[("class_definition", &["name"])],
+    scope_kinds: &["function_definition", "lambda"],
+    bindings: &[
+        ReceiverBinding {
+            kind: "typed_parameter",
+            name: &["identifier"],
+            type_field: Some("type"),
+        },
+        ReceiverBinding {
+            kind: "typed_default_parameter",
+            name: &["name"],
+            type_field: Some("type"),
+        },
+        ReceiverBinding {
+            kind: "assignment",
+            name: &["left"],
+            type_field: Some("type"),
+        },
+    ],
+    implicit_self: false,
+    transparent_types: &[],
+    type_refs: &PYTHON_TYPE_REFS,
+};
+
+pub static GO_RECEIVERS: ReceiverConfig = ReceiverConfig {
+    self_names: &[],
+    owner_kinds: &[],
+    type_kinds: &[("type_spec", &["name"])],
+    scope_kinds: &["function_declaration", "method_declaration", "func_literal"],
+    bindings: &[
+        ReceiverBinding {
+            kind: "parameter_declaration",
+            name: &["name"],
+            type_field: Some("type"),
+        },
+        ReceiverBinding {
+            kind: "var_spec",
+            name: &["name"],
+            type_field: Some("type"),
+        },
+        ReceiverBinding {
+            kind: "field_declaration",
+            name: &["name"],
+            type_field: Some("type"),
+        },
+    ],
+    implicit_self: false,
+    transparent_types: &[],
+    type_refs: &GO_TYPE_REFS,
+};
+
+const KOTLIN_OWNERS: &[(&str, &[&str])] = &[
+    ("class_declaration", &["type_identifier"]),
+    ("object_declaration", &["type_identifier"]),
+];
+
+pub static KOTLIN_RECEIVERS: ReceiverConfig = ReceiverConfig {
+    self_names: &["this"],
+    owner_kinds: KOTLIN_OWNERS,
+    type_kinds: KOTLIN_OWNERS,
+    scope_kinds: &[
+        "function_declaration",
+        "secondary_constructor",
+        "anonymous_function",
+        "lambda_literal",
+    ],
+    bindings: &[
+        ReceiverBinding {
+            kind: "class_parameter",
+            name: &["simple_identifier"],
+            type_field: None,
+        },
+        ReceiverBinding {
+            kind: "parameter",
+            name: &["simple_identifier"],
+            type_field: None,
+        },
+        ReceiverBinding {
+            kind: "variable_declaration",
+            name: &["simple_identifier"],
+            type_field: None,
+        },
+    ],
+    implicit_self: true,
+    transparent_types: &[],
+    type_refs: &KOTLIN_TYPE_REFS,
+};
+
+const SCALA_OWNERS: &[(&str, &[&str])] = &[
+    ("class_definition", &["name"]),
+    ("object_definition", &["name"]),
+    ("trait_definition", &["name"]),
+];
+
+pub static SCALA_RECEIVERS: ReceiverConfig = ReceiverConfig {
+    self_names: &["this"],
+    owner_kinds: SCALA_OWNERS,
+    type_kinds: SCALA_OWNERS,
+    scope_kinds: &["function_definition", "lambda_expression"],
+    bindings: &[
+        ReceiverBinding {
+            kind: "class_parameter",
+            name: &["name"],
+            type_field: Some("type"),
+        },
+        ReceiverBinding {
+            kind: "parameter",
+            name: &["name"],
+            type_field: Some("type"),
+        },
+        ReceiverBinding {
+            kind: "val_definition",
+            name: &["pattern"],
+            type_field: Some("type"),
+        },
+        ReceiverBinding {
+            kind: "var_definition",
+            name: &["pattern"],
+            type_field: Some("type"),
+        },
+    ],
+    implicit_self: true,
+    transparent_types: &[],
+    type_refs: &SCALA_TYPE_REFS,
+};
@@ -0,0 +1,1106 @@
+//! HTTP routes and the requests calling them.
+//!
+//! A second pass over the syntax tree of a Rust, TypeScript / JavaScript,
+//! Python, Java or Go file. It finds the handlers registered with a web
+//! framework — axum and actix `route`, actix and Rocket `#[get]`, Express
+//! `app.get`, Flask `@app.route`, `FastAPI` `@app.get`, Spring `@GetMapping`,
+//! Go `http.HandleFunc` and the gin, echo and chi routers — and the client
+//! requests to literal URLs: `fetch`, axios, `requests`, `httpx`, `reqwest`
+//! and Go's `http.Get` / `http.NewRequest`.
+//!
+//! Every registration becomes a [`NodeKind::Route`] named after its method
+//! and normalized path (see [`UnresolvedRef::route_name`]). The function
+//! registering it `Registers` the route, which `Implements` its handler. A
+//! request becomes a `Calls` reference to the normalized path, carrying its
+//! method (see [`UnresolvedRef::http_method`]), from the function making it,
+//! which the resolver binds to the route, or to a route of the same path
+//! registered for any method.
+//!
+//! Paths are joined with the prefix of a Spring controller's class-level
+//! `@RequestMapping`, but not with the prefixes routers are mounted at
+//! (`app.use("/api", router)`, axum `nest`, Flask blueprints).
+
+use std::collections::HashMap;
+
+use tree_sitter::Node as TsNode;
+
+use super::domain::{literal_text, target_name};
+use super::{is_callable, Callables};
+use crate::types::*;
+
+/// The languages routes are extracted from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lang {
+    Rust,
+    JavaScript,
+    Python,
+    Java,
+    Go,
+}
+
+/// The functions and decorators web frameworks register handlers with,
+/// lowercased, and the HTTP method each one serves.
+const METHODS: &[(&str, &str)] = &[
+    ("get", "GET"),
+    ("post", "POST"),
+    ("put", "PUT"),
+    ("patch", "PATCH"),
+    ("delete", "DELETE"),
+    ("head", "HEAD"),
+    ("options", "OPTIONS"),
+    ("any", "ANY"),
+    ("all", "ANY"),
+];
+
+/// Spring's mapping annotations and the method each one maps;
+/// `@RequestMapping` maps the methods it lists, or all of them.
+const SPRING_MAPPINGS: &[(&str, &str)] = &[
+    ("GetMapping", "GET"),
+    ("PostMapping", "POST"),
+    ("PutMapping", "PUT"),
+    ("PatchMapping", "PATCH"),
+    ("DeleteMapping", "DELETE"),
+    ("RequestMapping", "ANY"),
+];
+
+/// Receivers of JavaScript calls that send requests rather than register
+/// handlers (`axios.get(url)`, `this.http.post(url, body)`), lowercased.
+const JS_CLIENTS: &[&str] = &[
+    "axios",
+    "http",
+    "httpclient",
+    "$http",
+    "ky",
+    "api",
+    "client",
+];
+
+/// Longest registration line kept as a route's signature.
+const MAX_SIGNATURE_LEN: usize = 200;
+
+/// The HTTP method a framework function, decorator or client method named
+/// `name` serves (`get` → `GET`, `all` → `ANY`).
+fn http_method(name: &str) -> Option<&'static str> {
+    let name = name.to_ascii_lowercase();
+    METHODS.iter().find(|(n, _)| *n == name).map(|(_, m)| *m)
+}
+
+/// Adds the routes `file_path` registers and the requests it sends to
+/// `result`, its primary extraction, from the syntax tree at `root`.
+pub(crate) fn extract_routes(
+    file_path: &str,
+    source: &str,
+    root: TsNode<'_>,
+    result: &mut ExtractionResult,
+) {
+    let lang = match file_path.rsplit('.').next().unwrap_or("") {
+        "rs" => Lang::Rust,
+        "ts" | "tsx" | "js" | "jsx" => Lang::JavaScript,
+        "py" => Lang::Python,
+        "java" => Lang::Java,
+        "go" => Lang::Go,
+        _ => return,
+    };
+    let mut pass = RoutePass::new(lang, file_path, source, result);
+    pass.visit(root, "");
+}
+
+/// Who handles a route.
+enum Handler<'t> {
+    /// The function the route is declared on, by a decorator, attribute or
+    /// annotation.
+    Declared(String),
+    /// A function passed by name, resolved like a reference.
+    Named(TsNode<'t>),
+    /// An inline closure, or nothing the pass recognizes.
+    Unknown,
+}
+
+/// The state of extracting the routes of one file.
+struct RoutePass<'a> {
+    lang: Lang,
+    file_path: &'a str,
+    source: &'a str,
+    result: &'a mut ExtractionResult,
+    file_id: Option<String>,
+    updated_at: u64,
+    functions: Callables,
+    /// IDs of the routes already emitted for this file, by name.
+    routes: HashMap<String, String>,
+}
+
+impl<'a> RoutePass<'a> {
+    fn new(
+        lang: Lang,
+        file_path: &'a str,
+        source: &'a str,
+        result: &'a mut ExtractionResult,
+    ) -> Self {
+        let file = result.nodes.iter().find(|n| n.kind == NodeKind::File);
+        let file_id = file.map(|n| n.id.clone());
+        let updated_at = file.map_or(0, |n| n.updated_at);
+        let functions = Callables::new(&result.nodes);
+        Self {
+            lang,
+            file_path,
+            source,
+            result,
+            file_id,
+            updated_at,
+            functions,
+            routes: HashMap::new(),
+        }
+    }
+
+    fn text(&self, node: TsNode<'_>) -> &'a str {
+        node.utf8_text(self.source.as_bytes()).unwrap_or("")
+    }
+
+    /// Visits the descendants of `node`, then `node`, so the calls of a
+    /// chain (`.route(a).route(b)`) are seen in source order. `prefix` is
+    /// the path of the enclosing Spring controller.
+    fn visit(&mut self, node: TsNode<'_>, prefix: &str) {
+        let controller;
+        let prefix = if self.lang == Lang::Java && node.kind() == "class_declaration" {
+            controller = self
+                .spring_mappings(node)
+                .into_iter()
+                .find_map(|(_, paths)| paths.into_iter().next())
+                .map(|(path, _)| path)
+                .unwrap_or_default();
+            controller.as_str()
+        } else {
+            prefix
+        };
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.visit(child, prefix);
+        }
+        match (self.lang, node.kind()) {
+            (Lang::Rust, "call_expression") => self.rust_call(node),
+            (Lang::Rust, "function_item") => self.rust_attributes(node),
+            (Lang::JavaScript, "call_expression") => self.js_call(node),
+            (Lang::Python, "decorated_definition") => self.python_decorators(node),
+            (Lang::Python, "call") => self.python_call(node),
+            (Lang::Java, "method_declaration") => self.java_method(node, prefix),
+            (Lang::Go, "call_expression") => self.go_call(node),
+            _ => {}
+        }
+    }
+
+    // ---- Rust ----
+
+    /// `.route("/users", get(list).post(create))` (axum, actix),
+    /// `web::resource("/users").route(web::get().to(list))` (actix), and
+    /// `client.get(url)` / `reqwest::get(url)` requests.
+    fn rust_call(&mut self, call: TsNode<'_>) {
+        let Some(function) = call.child_by_field_name("function") else {
+            return;
+        };
+        let args = arguments(call);
+        let name = last_segment(self.text(function));
+        if function.kind() == "field_expression" && name == "route" {
+            let registration = match args.as_slice() {
+                [path, methods] => Some((*path, *methods)),
+                [methods] => function
+                    .child_by_field_name("value")
+                    .and_then(|value| self.rust_resource(value))
+                    .map(|path| (path, *methods)),
+                _ => None,
+            };
+            if let Some((path, methods)) = registration {
+                if let Some(pattern) = self.string(path) {
+                    for (method, handler) in self.rust_method_routers(methods) {
+                        self.route(method, &pattern, path, Some(call), handler);
+                    }
+                }
+            }
+            return;
+        }
+        let method = match (function.kind(), args.as_slice()) {
+            ("field_expression", [url]) if name != "to" => {
+                http_method(name).filter(|m| *m != "ANY").map(|m| (m, *url))
+            }
+            ("field_expression", [method, url]) if name == "request" => {
+                let method = last_segment(self.text(*method));
+                http_method(method).map(|m| (m, *url))
+            }
+            ("scoped_identifier", [url]) if self.text(function).starts_with("reqwest::") => {
+                http_method(name).map(|m| (m, *url))
+            }
+            _ => None,
+        };
+        if let Some((method, url)) = method {
+            // reqwest takes absolute URLs, so a path alone is a map lookup.
+            if let Some(text) = self.url(url).filter(|u| !u.starts_with('/')) {
+                self.request(method, &text, url);
+            }
+        }
+    }
+
+    /// The path literal of the `web::resource("/path")` an actix
+    /// `.route(...)` chain starts at.
+    fn rust_resource<'t>(&self, mut node: TsNode<'t>) -> Option<TsNode<'t>> {
+        loop {
+            let function = (node.kind() == "call_expression")
+                .then(|| node.child_by_field_name("function"))??;
+            if last_segment(self.text(function)) == "resource" {
+                return arguments(node).into_iter().next();
+            }
+            node = function.child_by_field_name("value")?;
+        }
+    }
+
+    /// The methods and handlers of an axum method router (`get(list)
+    /// .post(create)`) or an actix route (`web::get().to(list)`).
+    fn rust_method_routers<'t>(&self, node: TsNode<'t>) -> Vec<(&'static str, Handler<'t>)> {
+        let mut routers = Vec::new();
+        let mut calls = vec![node];
+        while let Some(call) = calls.pop() {
+            let mut cursor = call.walk();
+            calls.extend(call.named_children(&mut cursor));
+            if call.kind() != "call_expression" {
+                continue;
+            }
+            let Some(function) = call.child_by_field_name("function") else {
+                continue;
+            };
+            let [argument] = arguments(call)[..] else {
+                continue;
+            };
+            let name = last_segment(self.text(function));
+            let method = if name == "to" {
+                // `web::get().to(handler)`: the method is the receiver.
+                function
+                    .child_by_field_name("value")
+                    .filter(|value| value.kind() == "call_expression")
+                    .and_then(|value| value.child_by_field_name("function"))
+                    .and_then(|f| http_method(last_segment(self.text(f))))
+            } else {
+                http_method(name)
+            };
+            if let Some(method) = method {
+                routers.push((method, self.handler(argument)));
+            }
+        }
+        routers.sort_by_key(|(_, handler)| match handler {
+            Handler::Named(node) => node.start_byte(),
+            _ => 0,
+        });
+        routers
+    }
+
+    /// `#[get("/users/{id}")]` (actix, Rocket) and `#[route("/", method =
+    /// "GET")]` on a function.
+    fn rust_attributes(&mut self, function: TsNode<'_>) {
+        let Some(name) = function.child_by_field_name("name") else {
+            return;
+        };
+        let mut current = function.prev_named_sibling();
+        while let Some(sibling) = current {
+            match sibling.kind() {
+                "attribute_item" => {
+                    let attribute = self.text(sibling);
+                    let inner = attribute.trim_start_matches("#[").trim_end_matches(']');
+                    if let Some((path, methods)) = attribute_route(inner) {
+                        let handler = self.declared(self.text(name), function);
+                        for method in methods {
+                            let handler =
+                                handler.clone().map_or(Handler::Unknown, Handler::Declared);
+                            self.route(method, &path, sibling, None, handler);
+                        }
+                    }
+                }
+                "line_comment" | "block_comment" => {}
+                _ => break,
+            }
+            current = sibling.prev_named_sibling();
+        }
+    }
+
+    // ---- TypeScript / JavaScript ----
+
+    /// `app.get("/users/:id", show)`, `router.route("/users").post(create)`
+    /// (Express) and `fetch(url, { method })`, `axios.get(url)` and
+    /// `request(app).get(url)` requests.
+    fn js_call(&mut self, call: TsNode<'_>) {
+        let Some(function) = call.child_by_field_name("function") else {
+            return;
+        };
+        let args = arguments(call);
+        match function.kind() {
+            "identifier" if self.text(function) == "fetch" => {
+                if let Some(url) = args.first() {
+                    let method = args.get(1).and_then(|init| self.js_fetch_method(*init));
+                    if let Some(text) = self.url(*url) {
+                        self.request(method.unwrap_or("GET"), &text, *url);
+                    }
+                }
+            }
+            "member_expression" => {
+                let (Some(object), Some(property)) = (
+                    function.child_by_field_name("object"),
+                    function.child_by_field_name("property"),
+                ) else {
+                    return;
+                };
+                let Some(method) = http_method(self.text(property)) else {
+                    return;
+                };
+                let receiver = last_segment(self.text(object)).to_ascii_lowercase();
+                let is_client = JS_CLIENTS.contains(&receiver.as_str())
+                    || (object.kind() == "call_expression"
+                        && object
+                            .child_by_field_name("function")
+                            .is_some_and(|f| self.text(f) == "request"));
+                if is_client {
+                    if let Some(url) = args.first() {
+                        if let Some(text) = self.url(*url) {
+                            self.request(method, &text, *url);
+                        }
+                    }
+                } else if let Some(path) = self.js_route_path(object) {
+                    // `router.route("/users").get(list).post(create)`
+                    if let Some(pattern) = self.string(path) {
+                        let handler = args.last().map_or(Handler::Unknown, |h| self.handler(*h));
+                        self.route(method, &pattern, path, Some(call), handler);
+                    }
+                } else if let [path, .., handler] = args[..] {
+                    if let Some(pattern) = self.string(path).filter(|p| p.starts_with('/')) {
+                        let handler = self.handler(handler);
+                        self.route(method, &pattern, path, Some(call), handler);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The path literal of the `.route("/path")` call a chain of Express
+    /// method registrations starts at.
+    fn js_route_path<'t>(&self, mut node: TsNode<'t>) -> Option<TsNode<'t>> {
+        loop {
+            let function = (node.kind() == "call_expression")
+                .then(|| node.child_by_field_name("function"))??;
+            let property = function.child_by_field_name("property")?;
+            if self.text(property) == "route" {
+                return arguments(node).into_iter().next();
+            }
+            http_method(self.text(property))?;
+            node = function.child_by_field_name("object")?;
+        }
+    }
+
+    /// The `method` of a `fetch` options object.
+    fn js_fetch_method(&self, init: TsNode<'_>) -> Option<&'static str> {
+        let mut cursor = init.walk();
+        let pairs: Vec<TsNode<'_>> = init.named_children(&mut cursor).collect();
+        pairs.into_iter().find_map(|pair| {
+            let key = pair.child_by_field_name("key")?;
+            if literal_text(self.text(key)) != "method" {
+                return None;
+            }
+            http_method(literal_text(self.text(pair.child_by_field_name("value")?)))
+        })
+    }
+
+    // ---- Python ----
+
+    /// `@app.route("/users", methods=["GET", "POST"])` (Flask) and
+    /// `@router.get("/users/{id}")` (`FastAPI`, Flask 2) on a function.
+    fn python_decorators(&mut self, decorated: TsNode<'_>) {
+        let Some(function) = decorated.child_by_field_name("definition") else {
+            return;
+        };
+        let Some(name) = function.child_by_field_name("name") else {
+            return;
+        };
+        let handler = self.declared(self.text(name), function);
+        let mut cursor = decorated.walk();
+        let decorators: Vec<TsNode<'_>> = decorated
+            .named_children(&mut cursor)
+            .filter(|c| c.kind() == "decorator")
+            .collect();
+        for decorator in decorators {
+            let Some(call) = decorator.named_child(0).filter(|c| c.kind() == "call") else {
+                continue;
+            };
+            let Some(function) = call
+                .child_by_field_name("function")
+                .filter(|f| f.kind() == "attribute")
+            else {
+                continue;
+            };
+            let Some(attribute) = function.child_by_field_name("attribute") else {
+                continue;
+            };
+            let args = arguments(call);
+            let Some(path) = args.iter().find(|a| a.kind() != "keyword_argument") else {
+                continue;
+            };
+            let Some(pattern) = self.string(*path).filter(|p| p.starts_with('/')) else {
+                continue;
+            };
+            let methods = match self.text(attribute) {
+                "route" | "api_route" => {
+                    let listed: Vec<&'static str> = self
+                        .keyword(&args, "methods")
+                        .map(|list| {
+                            let mut cursor = list.walk();
+                            list.named_children(&mut cursor)
+                                .filter_map(|m| http_method(literal_text(self.text(m))))
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    if listed.is_empty() {
+                        vec!["GET"]
+                    } else {
+                        listed
+                    }
+                }
+                name => http_method(name).into_iter().collect(),
+            };
+            for method in methods {
+                let handler = handler.clone().map_or(Handler::Unknown, Handler::Declared);
+                self.route(method, &pattern, *path, None, handler);
+            }
+        }
+    }
+
+    /// `requests.get(url)`, `httpx.post(url)`, `session.request("GET", url)`
+    /// and test clients' `client.get(url)`.
+    fn python_call(&mut self, call: TsNode<'_>) {
+        let Some(function) = call
+            .child_by_field_name("function")
+            .filter(|f| f.kind() == "attribute")
+        else {
+            return;
+        };
+        let (Some(object), Some(attribute)) = (
+            function.child_by_field_name("object"),
+            function.child_by_field_name("attribute"),
+        ) else {
+            return;
+        };
+        let receiver = self.text(object);
+        let last = last_segment(receiver).to_ascii_lowercase();
+        if !(matches!(receiver, "requests" | "httpx" | "aiohttp")
+            || last.contains("client")
+            || last.contains("session"))
+        {
+            return;
+        }
+        let args = arguments(call);
+        let mut positional = args.iter().filter(|a| a.kind() != "keyword_argument");
+        let (method, url) = match self.text(attribute) {
+            "request" => {
+                let Some(method) = positional
+                    .next()
+                    .and_then(|m| http_method(literal_text(self.text(*m))))
+                else {
+                    return;
+                };
+                (method, positional.next().copied())
+            }
+            name => match http_method(name).filter(|m| *m != "ANY") {
+                Some(method) => (method, positional.next().copied()),
+                None => return,
+            },
+        };
+        let Some(url) = url.or_else(|| self.keyword(&args, "url")) else {
+            return;
+        };
+        if let Some(text) = self.url(url) {
+            self.request(method, &text, url);
+        }
+    }
+
+    /// The value of the keyword argument `name` among `args`.
+    fn keyword<'t>(&self, args: &[TsNode<'t>], name: &str) -> Option<TsNode<'t>> {
+        args.iter()
+            .filter(|a| a.kind() == "keyword_argument")
+            .find(|a| {
+                a.child_by_field_name("name")
+                    .is_some_and(|n| self.text(n) == name)
+            })
+            .and_then(|a| a.child_by_field_name("value"))
+    }
+
+    // ---- Java ----
+
+    /// `@GetMapping("/{id}")` and `@RequestMapping(value = "/", method =
+    /// RequestMethod.POST)` on a method of a Spring controller whose path is
+    /// `prefix`.
+    fn java_method(&mut self, method: TsNode<'_>, prefix: &str) {
+        let Some(name) = method.child_by_field_name("name") else {
+            return;
+        };
+        let mappings = self.spring_mappings(method);
+        if mappings.is_empty() {
+            return;
+        }
+        let handler = self.declared(self.text(name), method);
+        for (methods, paths) in mappings {
+            for (path, at) in paths {
+                let pattern = join_paths(prefix, &path);
+                for method in &methods {
+                    let handler = handler.clone().map_or(Handler::Unknown, Handler::Declared);
+                    self.route(method, &pattern, at, None, handler);
+                }
+            }
+        }
+    }
+
+    /// The Spring mapping annotations of a class or method: for each one,
+    /// the methods it maps and its paths with the node naming each.
+    #[allow(clippy::type_complexity)]
+    fn spring_mappings<'t>(
+        &self,
+        declaration: TsNode<'t>,
+    ) -> Vec<(Vec<&'static str>, Vec<(String, TsNode<'t>)>)> {
+        let mut cursor = declaration.walk();
+        let Some(modifiers) = declaration
+            .named_children(&mut cursor)
+            .find(|c| c.kind() == "modifiers")
+        else {
+            return Vec::new();
+        };
+        let mut cursor = modifiers.walk();
+        let annotations: Vec<TsNode<'t>> = modifiers
+            .named_children(&mut cursor)
+            .filter(|c| matches!(c.kind(), "annotation" | "marker_annotation"))
+            .collect();
+        let mut mappings = Vec::new();
+        for annotation in annotations {
+            let Some(name) = annotation.child_by_field_name("name") else {
+                continue;
+            };
+            let Some(&(_, mapped)) = SPRING_MAPPINGS
+                .iter()
+                .find(|(n, _)| *n == last_segment(self.text(name)))
+            else {
+                continue;
+            };
+            let mut methods = vec![mapped];
+            let mut paths = Vec::new();
+            let arguments = annotation
+                .child_by_field_name("arguments")
+                .map(|list| {
+                    let mut cursor = list.walk();
+                    list.named_children(&mut cursor).collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            for argument in arguments {
+                if argument.kind() != "element_value_pair" {
+                    paths.extend(self.java_strings(argument));
+                    continue;
+                }
+                let (Some(key), Some(value)) = (
+                    argument.child_by_field_name("key"),
+                    argument.child_by_field_name("value"),
+                ) else {
+                    continue;
+                };
+                match self.text(key) {
+                    "value" | "path" => paths.extend(self.java_strings(value)),
+                    "method" => {
+                        let listed: Vec<&'static str> = self
+                            .text(value)
+                            .split(|c: char| !c.is_ascii_alphabetic())
+                            .filter(|word| word.chars().all(|c| c.is_ascii_uppercase()))
+                            .filter_map(http_method)
+                            .collect();
+                        if !listed.is_empty() {
+                            methods = listed;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            if paths.is_empty() {
+                paths.push((String::new(), annotation));
+            }
+            mappings.push((methods, paths));
+        }
+        mappings
+    }
+
+    /// The string literals of an annotation value: the literal itself, or
+    /// those of an array (`{"/a", "/b"}`).
+    fn java_strings<'t>(&self, value: TsNode<'t>) -> Vec<(String, TsNode<'t>)> {
+        if value.kind() == "string_literal" {
+            return vec![(literal_text(self.text(value)).to_string(), value)];
+        }
+        let mut cursor = value.walk();
+        value
+            .named_children(&mut cursor)
+            .filter(|c| c.kind() == "string_literal")
+            .map(|c| (literal_text(self.text(c)).to_string(), c))
+            .collect()
+    }
+
+    // ---- Go ----
+
+    /// `http.HandleFunc("GET /users/{id}", show)` (net/http),
+    /// `r.HandleFunc("/users", list).Methods("GET")` (gorilla),
+    /// `r.GET("/users", list)` (gin, echo), `r.Get("/users", list)` (chi),
+    /// and `http.Get(url)` / `http.NewRequest("POST", url, body)` requests.
+    fn go_call(&mut self, call: TsNode<'_>) {
+        let Some(function) = call
+            .child_by_field_name("function")
+            .filter(|f| f.kind() == "selector_expression")
+        else {
+            return;
+        };
+        let (Some(operand), Some(field)) = (
+            function.child_by_field_name("operand"),
+            function.child_by_field_name("field"),
+        ) else {
+            return;
+        };
+        let operand = self.text(operand);
+        let field = self.text(field);
+        let args = arguments(call);
+        let is_client = operand == "http" || operand.to_ascii_lowercase().contains("client");
+        match (field, args.as_slice()) {
+            ("NewRequest", [method, url, ..]) | ("NewRequestWithContext", [_, method, url, ..]) => {
+                let method = self.text(*method);
+                let method = literal_text(method.rsplit('.').next().unwrap_or(method));
+                if let Some(method) = http_method(method.trim_start_matches("Method")) {
+                    if let Some(text) = self.url(*url) {
+                        self.request(method, &text, *url);
+                    }
+                }
+            }
+            ("Get" | "Head" | "Post" | "PostForm", [url, ..]) if is_client => {
+                let method = http_method(field.trim_end_matches("Form")).unwrap_or("GET");
+                if let Some(text) = self.url(*url) {
+                    self.request(method, &text, *url);
+                }
+            }
+            ("HandleFunc" | "Handle", [path, handler]) => {
+                let Some(pattern) = self.string(*path) else {
+                    return;
+                };
+                let (method, pattern) = match pattern.split_once(' ') {
+                    Some((method, rest)) if http_method(method).is_some() => {
+                        (http_method(method), rest.trim().to_string())
+                    }
+                    _ => (None, pattern),
+                };
+                // `example.com/users` serves `/users` on one host.
+                let Some(pattern) = pattern.find('/').map(|i| pattern[i..].to_string()) else {
+                    return;
+                };
+                let methods = match method {
+                    Some(method) => vec![method],
+                    None => self.gorilla_methods(call),
+                };
+                for method in methods {
+                    let handler = self.handler(*handler);
+                    self.route(method, &pattern, *path, Some(call), handler);
+                }
+            }
+            (_, [path, .., handler]) if !is_client => {
+                let Some(method) = http_method(field) else {
+                    return;
+                };
+                if let Some(pattern) = self.string(*path).filter(|p| p.starts_with('/')) {
+                    let handler = self.handler(*handler);
+                    self.route(method, &pattern, *path, Some(call), handler);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The methods a gorilla route is restricted to by a chained
+    /// `.Methods("GET", "POST")`, or `ANY`.
+    fn gorilla_methods(&self, call: TsNode<'_>) -> Vec<&'static str> {
+        let chained = call
+            .parent()
+            .filter(|p| p.kind() == "selector_expression")
+            .filter(|p| {
+                p.child_by_field_name("field")
+                    .is_some_and(|f| self.text(f) == "Methods")
+            })
+            .and_then(|p| p.parent())
+            .filter(|p| p.kind() == "call_expression");
+        let methods: Vec<&'static str> = chained
+            .map(|c| {
+                arguments(c)
+                    .into_iter()
+                    .filter_map(|m| http_method(literal_text(self.text(m))))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if methods.is_empty() {
+            vec!["ANY"]
+        } else {
+            methods
+        }
+    }
+
+    // ---- Shared ----
+
+    /// The text of a string literal, or `None` for any other expression.
+    fn string(&self, node: TsNode<'_>) -> Option<String> {
+        matches!(
+            node.kind(),
+            "string_literal" | "raw_string_literal" | "interpreted_string_literal" | "string"
+        )
+        .then(|| literal_text(self.text(node)).to_string())
+    }
+
+    /// The URL an expression builds, with each interpolated or concatenated
+    /// value as `{}`: string literals, template strings (`` `${api}/users` ``),
+    /// f-strings, `format!` and `fmt.Sprintf` calls and `+` concatenations.
+    fn url(&self, node: TsNode<'_>) -> Option<String> {
+        match node.kind() {
+            "template_string" => {
+                let text = self.text(node).trim_matches('`');
+                Some(replace_interpolations(text))
+            }
+            "binary_expression" | "binary_operator" => {
+                let operator = node.child_by_field_name("operator")?;
+                if self.text(operator) != "+" {
+                    return None;
+                }
+                let part = |side| {
+                    node.child_by_field_name(side)
+                        .and_then(|n| self.url(n))
+                        .unwrap_or_else(|| "{}".to_string())
+                };
+                Some(part("left") + &part("right"))
+            }
+            "parenthesized_expression" | "reference_expression" => {
+                let mut cursor = node.walk();
+                let inner = node.named_children(&mut cursor).last()?;
+                self.url(inner)
+            }
+            "macro_invocation" => {
+                let name = node.child_by_field_name("macro")?;
+                if self.text(name) != "format" {
+                    return None;
+                }
+                let mut cursor = node.walk();
+                let tokens = node
+                    .named_children(&mut cursor)
+                    .find(|c| c.kind() == "token_tree")?;
+                let mut cursor = tokens.walk();
+                let format = tokens
+                    .named_children(&mut cursor)
+                    .find(|c| matches!(c.kind(), "string_literal" | "raw_string_literal"))?;
+                self.string(format)
+            }
+            "call_expression" => {
+                let function = node.child_by_field_name("function")?;
+                if self.text(function) != "fmt.Sprintf" {
+                    return None;
+                }
+                self.url(*arguments(node).first()?)
+            }
+            _ => self.string(node),
+        }
+    }
+
+    /// A handler passed by name (`list_users`, `users.show`, `h.List`).
+    fn handler<'t>(&self, node: TsNode<'t>) -> Handler<'t> {
+        if target_name(self.text(node)).is_some() && !self.text(node).starts_with(['"', '\'']) {
+            Handler::Named(node)
+        } else {
+            Handler::Unknown
+        }
+    }
+
+    /// The ID of the function `name` declared by the syntax node
+    /// `declaration`.
+    fn declared(&self, name: &str, declaration: TsNode<'_>) -> Option<String> {
+        let row = declaration.start_position().row as u32;
+        let end = declaration.end_position().row as u32;
+        self.result
+            .nodes
+            .iter()
+            .filter(|n| is_callable(n) && n.name == name)
+            .find(|n| n.start_line <= end && row <= n.end_line)
+            .map(|n| n.id.clone())
+    }
+
+    /// Emits the route `method pattern` declared at `at` (its path literal,
+    /// or the annotation naming it) with its handler. `registration` is the
+    /// call registering it, whose enclosing function `Registers` the route.
+    fn route(
+        &mut self,
+        method: &str,
+        pattern: &str,
+        at: TsNode<'_>,
+        registration: Option<TsNode<'_>>,
+        handler: Handler<'_>,
+    ) {
+        let Some(path) = route_path(pattern) else {
+            return;
+        };
+        let name = UnresolvedRef::route_name(method, &path);
+        let line = at.start_position().row as u32;
+        let id = match self.routes.get(&name) {
+            Some(id) => id.clone(),
+            None => self.route_node(&name, method, pattern, at),
+        };
+        if let Some(registrar) =
+            registration.and_then(|call| self.functions.enclosing(call.start_position().row as u32))
+        {
+            self.edge(registrar, id.clone(), EdgeKind::Registers, Some(line));
+        }
+        match handler {
+            Handler::Declared(target) => {
+                self.edge(id, target, EdgeKind::Implements, Some(line));
+            }
+            Handler::Named(node) => {
+                let Some(name) = target_name(self.text(node)) else {
+                    return;
+                };
+                self.result.unresolved_refs.push(UnresolvedRef {
+                    from_node_id: id,
+                    reference_name: name.to_string(),
+                    reference_kind: EdgeKind::Implements,
+                    line: node.start_position().row as u32,
+                    column: node.start_position().column as u32,
+                    file_path: self.file_path.to_string(),
+                    receiver_type: None,
+                    abi: None,
+                    http_method: None,
+                    sql: false,
+                });
+            }
+            Handler::Unknown => {}
+        }
+    }
+
+    fn route_node(&mut self, name: &str, method: &str, pattern: &str, at: TsNode<'_>) -> String {
+        let id = generate_node_id(self.file_path, &NodeKind::Route, name, 0);
+        let start = at.start_position();
+        let end = at.end_position();
+        let signature = format!("{method} {pattern}")
+            .chars()
+            .take(MAX_SIGNATURE_LEN)
+            .collect();
+        self.result.nodes.push(Node {
+            id: id.clone(),
+            kind: NodeKind::Route,
+            name: name.to_string(),
+            qualified_name: name.to_string(),
+            file_path: self.file_path.to_string(),
+            start_line: start.row as u32,
+            end_line: end.row as u32,
+            start_column: start.column as u32,
+            end_column: end.column as u32,
+            signature: Some(signature),
+            docstring: None,
+            visibility: Visibility::Pub,
+            is_async: false,
+            branches: 0,
+            loops: 0,
+            returns: 0,
+            max_nesting: 0,
+            unsafe_blocks: 0,
+            unchecked_calls: 0,
+            assertions: 0,
+            updated_at: self.updated_at,
+        });
+        self.routes.insert(name.to_string(), id.clone());
+        if let Some(file_id) = self.file_id.clone() {
+            self.edge(file_id, id.clone(), EdgeKind::Contains, None);
+        }
+        id
+    }
+
+    /// Emits a request for `method url` from the function around `at`.
+    fn request(&mut self, method: &str, url: &str, at: TsNode<'_>) {
+        let Some(path) = client_path(url) else {
+            return;
+        };
+        let line = at.start_position().row as u32;
+        let Some(from) = self
+            .functions
+            .enclosing(line)
+            .or_else(|| self.file_id.clone())
+        else {
+            return;
+        };
+        self.result.unresolved_refs.push(UnresolvedRef {
+            from_node_id: from,
+            reference_name: path,
+            reference_kind: EdgeKind::Calls,
+            line,
+            column: at.start_position().column as u32,
+            file_path: self.file_path.to_string(),
+            receiver_type: None,
+            abi: None,
+            http_method: Some(method.to_string()),
+            sql: false,
+        });
+    }
+
+    fn edge(&mut self, source: String, target: String, kind: EdgeKind, line: Option<u32>) {
+        let duplicate = self
+            .result
+            .edges
+            .iter()
+            .any(|e| e.source == source && e.target == target && e.kind == kind);
+        if !duplicate {
+            self.result.edges.push(Edge {
+                source,
+                target,
+                kind,
+                line,
+                provenance: None,
+            });
+        }
+    }
+}
+
+/// The named arguments of a call.
+fn arguments(call: TsNode<'_>) -> Vec<TsNode<'_>> {
+    let Some(list) = call.child_by_field_name("arguments") else {
+        return Vec::new();
+    };
+    let mut cursor = list.walk();
+    list.named_children(&mut cursor)
+        .filter(|c| !matches!(c.kind(), "comment" | "line_comment" | "block_comment"))
+        .collect()
+}
+
+/// The last segment of a path or member expression
+/// (`actix_web::web::get` → `get`, `this.http` → `http`).
+fn last_segment(text: &str) -> &str {
+    let text = text.rsplit("::").next().unwrap_or(text);
+    text.rsplit('.').next().unwrap_or(text)
+}
+
+/// The path and methods of a route attribute's contents: `get("/users")`
+/// or `route("/", method = "GET", method = "HEAD")`.
+fn attribute_route(inner: &str) -> Option<(String, Vec<&'static str>)> {
+    let (name, args) = inner.split_once('(')?;
+    let name = last_segment(name.trim());
+    let mut strings = args.split('"').skip(1).step_by(2);
+    let path = strings.next().filter(|p| p.starts_with('/'))?;
+    let methods = if name == "route" {
+        let listed: Vec<&'static str> = args
+            .split(|c: char| !c.is_ascii_alphabetic())
+            .filter(|word| word.chars().all(|c| c.is_ascii_uppercase()))
+            .filter_map(http_method)
+            .collect();
+        if listed.is_empty() {
+            vec!["ANY"]
+        } else {
+            listed
+        }
+    } else {
+        vec![http_method(name)?]
+    };
+    Some((path.to_string(), methods))
+}
+
+/// Replaces each `${...}` of a template string with `{}`.
+fn replace_interpolations(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        out.push_str("{}");
+        let mut depth = 0;
+        let mut end = rest.len();
+        for (i, c) in rest[start + 1..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = start + 1 + i + 1;
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Joins a controller's path and a method's (`/api` + `users/{id}`).
+fn join_paths(prefix: &str, path: &str) -> String {
+    let prefix = prefix.trim_end_matches('/');
+    let path = path.trim_start_matches('/');
+    match (prefix.is_empty(), path.is_empty()) {
+        (true, true) => "/".to_string(),
+        (_, true) => prefix.to_string(),
+        _ => format!("{prefix}/{path}"),
+    }
+}
+
+/// True for a path segment that is, or embeds, a route parameter: `:id`,
+/// `{id}`, `{id:[0-9]+}`, `<int:id>`, `*` and `*rest`.
+fn is_parameter(segment: &str) -> bool {
+    segment.starts_with([':', '*']) || segment.contains(['{', '<'])
+}
+
+/// Normalizes a route pattern for matching requests: a leading `/`, no
+/// trailing `/` or query, and `{}` for every parameter.
+fn route_path(pattern: &str) -> Option<String> {
+    let pattern = pattern.trim();
+    let pattern = if pattern.starts_with('/') {
+        pattern
+    } else if !pattern.is_empty() && !pattern.contains(' ') {
+        // Flask and Spring paths may omit the leading slash.
+        return route_path(&format!("/{pattern}"));
+    } else {
+        return None;
+    };
+    let pattern = pattern.split('?').next().unwrap_or(pattern);
+    Some(normalize(
+        pattern.split('/').filter(|s| *s != "{$}"),
+        is_parameter,
+    ))
+}
+
+/// The path a request to `url` is routed by, normalized like
+/// [`route_path`]: numeric segments and interpolated values become `{}`.
+/// The scheme, host and query are dropped, as is an interpolated base URL
+/// (`{}/users`). Returns `None` for anything that is not an absolute URL or
+/// path.
+fn client_path(url: &str) -> Option<String> {
+    let url = url.trim();
+    let path = if let Some((scheme, rest)) = url.split_once("://") {
+        if scheme.contains('/') {
+            return None;
+        }
+        rest.find('/').map_or("/", |i| &rest[i..])
+    } else if url.starts_with('/') {
+        url
+    } else {
+        let slash = url.find('/')?;
+        if !url[..slash].starts_with(['{', '%']) {
+            return None;
+        }
+        &url[slash..]
+    };
+    let path = path.split(['?', '#']).next().unwrap_or(path);
+    Some(normalize(path.split('/'), |segment| {
+        is_parameter(segment)
+            || segment.contains('%')
+            || segment.bytes().all(|b| b.is_ascii_digit())
+    }))
+}
+
+fn normalize<'s>(
+    segments: impl Iterator<Item = &'s str>,
+    is_parameter: impl Fn(&str) -> bool,
+) -> String {
+    let segments: Vec<&str> = segments
+        .filter(|s| !s.is_empty())
+        .map(|s| if is_parameter(s) { "{}" } else { s })
+        .collect();
+    format!("/{}", segments.join("/"))
+}
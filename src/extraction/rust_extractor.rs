@@ -6,8 +6,12 @@ use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tree_sitter::{Node as TsNode, Parser, Tree};
 
 use crate::extraction::complexity::{count_complexity, RUST_COMPLEXITY};
+use crate::extraction::receivers::{receiver_type, TypeDecls, RUST_RECEIVERS};
+use crate::extraction::signatures::{param, signature};
+use crate::extraction::type_refs::{push_type_refs, RUST_TYPE_REFS};
 use crate::types::{
-    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, UnresolvedRef, Visibility,
+    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, Signature, UnresolvedRef,
+    Visibility,
 };
 
 /// Extracts code graph nodes and edges from Rust source files using tree-sitter.
@@ -18,11 +22,14 @@ struct ExtractionState {
     nodes: Vec<Node>,
     edges: Vec<Edge>,
     unresolved_refs: Vec<UnresolvedRef>,
+    signatures: Vec<Signature>,
     errors: Vec<String>,
     /// Stack of (name, `node_id`) for building qualified names and parent edges.
     node_stack: Vec<(String, String)>,
     file_path: String,
     source: Vec<u8>,
+    /// Type declarations of the file, for receiver inference.
+    type_decls: TypeDecls,
     timestamp: u64,
 }
 
@@ -36,10 +43,12 @@ impl ExtractionState {
             nodes: Vec::new(),
             edges: Vec::new(),
             unresolved_refs: Vec::new(),
+            signatures: Vec::new(),
             errors: Vec::new(),
             node_stack: Vec::new(),
             file_path: file_path.to_string(),
             source: source.as_bytes().to_vec(),
+            type_decls: TypeDecls::default(),
             timestamp,
         }
     }
@@ -72,6 +81,12 @@ impl RustExtractor {
     /// `file_path` is used for qualified names and node IDs (not for I/O).
     /// `source` is the Rust source code to parse.
     pub fn extract(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -79,7 +94,7 @@ impl RustExtractor {
             Ok(tree) => tree,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -113,11 +128,15 @@ impl RustExtractor {
 
         // Walk the AST.
         let root = tree.root_node();
+        state.type_decls = TypeDecls::new(root, &state.source, &RUST_RECEIVERS);
         Self::visit_children(&mut state, root);
 
         state.node_stack.pop();
 
-        Self::build_result(state, start)
+        let mut result = Self::build_result(state, start);
+        super::routes::extract_routes(file_path, source, root, &mut result);
+        super::sql::extract_embedded_sql(file_path, source, root, &mut result);
+        (result, Some(tree))
     }
 
     /// Parse source code into a tree-sitter AST.
@@ -178,12 +197,34 @@ impl RustExtractor {
             .node_stack
             .iter()
             .any(|(_, id)| id.starts_with("trait:"));
-        let kind = if is_inside_impl || is_inside_trait {
+        let attributes = Self::preceding_attributes(state, node);
+        let kind = if attributes.iter().any(|a| Self::is_test_attribute(a)) {
+            NodeKind::TestCase
+        } else if is_inside_impl || is_inside_trait {
             NodeKind::Method
         } else {
             NodeKind::Function
         };
         let visibility = Self::extract_visibility(node, state);
+        // A function declared in an `extern "C"` block calls the C symbol,
+        // and one an attribute exports to another language records the
+        // export, which for `#[no_mangle]` implements the C prototypes
+        // declaring it.
+        let foreign_refs: Vec<(String, EdgeKind, &str)> = if Self::is_foreign_item(node) {
+            let symbol = attributes
+                .iter()
+                .find_map(|a| Self::link_name(a))
+                .unwrap_or(&name);
+            vec![(symbol.to_string(), EdgeKind::Calls, "C")]
+        } else if node.kind() == "function_item" {
+            attributes
+                .iter()
+                .filter_map(|a| Self::export_language(a))
+                .map(|language| (name.clone(), EdgeKind::Implements, language))
+                .collect()
+        } else {
+            Vec::new()
+        };
         let signature = Some(Self::extract_function_signature(state, node));
         let docstring = Self::extract_docstring(state, node);
         let is_async = Self::detect_async(state, node);
@@ -227,6 +268,47 @@ impl RustExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
+            });
+        }
+
+        // Types named by the parameters and the return type.
+        if let Some(params) = node.child_by_field_name("parameters") {
+            push_type_refs(
+                &mut state.unresolved_refs,
+                params,
+                &state.source,
+                &RUST_TYPE_REFS,
+                &id,
+                &EdgeKind::Receives,
+                &state.file_path,
+            );
+        }
+        if let Some(return_type) = node.child_by_field_name("return_type") {
+            push_type_refs(
+                &mut state.unresolved_refs,
+                return_type,
+                &state.source,
+                &RUST_TYPE_REFS,
+                &id,
+                &EdgeKind::Returns,
+                &state.file_path,
+            );
+        }
+        Self::push_signature(state, node, &id);
+
+        for (reference_name, reference_kind, abi) in foreign_refs {
+            state.unresolved_refs.push(UnresolvedRef {
+                from_node_id: id.clone(),
+                reference_name,
+                reference_kind,
+                line: start_line,
+                column: start_column,
+                file_path: state.file_path.clone(),
+                receiver_type: None,
+                abi: Some(abi.to_string()),
+                http_method: None,
+                sql: false,
             });
         }
 
@@ -237,6 +319,81 @@ impl RustExtractor {
         Self::extract_annotations_from_modifiers(state, node, &id);
     }
 
+    /// True for a function declared in an `extern` block.
+    fn is_foreign_item(node: TsNode<'_>) -> bool {
+        node.kind() == "function_signature_item"
+            && node
+                .parent()
+                .and_then(|list| list.parent())
+                .is_some_and(|block| block.kind() == "foreign_mod_item")
+    }
+
+    /// The texts of the attributes preceding an item, nearest first.
+    fn preceding_attributes(state: &ExtractionState, node: TsNode<'_>) -> Vec<String> {
+        let mut attributes = Vec::new();
+        let mut current = node.prev_named_sibling();
+        while let Some(sibling) = current {
+            match sibling.kind() {
+                "attribute_item" => attributes.push(state.node_text(sibling).trim().to_string()),
+                "line_comment" | "block_comment" => {}
+                _ => break,
+            }
+            current = sibling.prev_named_sibling();
+        }
+        attributes
+    }
+
+    /// The language an attribute exports a function to: `#[no_mangle]` to
+    /// C, `#[pyfunction]` to Python and `#[wasm_bindgen]` to JavaScript.
+    fn export_language(attribute: &str) -> Option<&'static str> {
+        let inner = attribute.trim_start_matches("#[").trim_end_matches(']');
+        if inner.replace(' ', "") == "unsafe(no_mangle)" {
+            return Some("C");
+        }
+        match inner.split('(').next().unwrap_or(inner).trim() {
+            "no_mangle" => Some("C"),
+            "pyfunction" => Some("Python"),
+            "wasm_bindgen" => Some("JavaScript"),
+            _ => None,
+        }
+    }
+
+    /// True for the attributes marking a function as a test the harness
+    /// runs: `#[test]` and the async runtimes' `#[tokio::test]` and the
+    /// like, `#[rstest]`, `#[test_case(...)]` and `#[bench]`.
+    fn is_test_attribute(attribute: &str) -> bool {
+        let inner = attribute.trim_start_matches("#[").trim_end_matches(']');
+        let path = inner.split('(').next().unwrap_or(inner).trim();
+        let name = path.rsplit("::").next().unwrap_or(path);
+        matches!(name, "test" | "rstest" | "test_case" | "bench")
+    }
+
+    /// True for `#[cfg(test)]`, and for `#[cfg(all(test, ...))]`, which
+    /// compile an item only into the test harness.
+    fn is_cfg_test_attribute(attribute: &str) -> bool {
+        let inner: String = attribute
+            .trim_start_matches("#[")
+            .trim_end_matches(']')
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        let Some(predicate) = inner.strip_prefix("cfg(").and_then(|p| p.strip_suffix(')')) else {
+            return false;
+        };
+        predicate == "test"
+            || predicate
+                .strip_prefix("all(")
+                .and_then(|p| p.strip_suffix(')'))
+                .is_some_and(|all| all.split(',').any(|p| p == "test"))
+    }
+
+    /// The symbol named by a `#[link_name = "..."]` attribute.
+    fn link_name(attribute: &str) -> Option<&str> {
+        let inner = attribute.strip_prefix("#[")?.strip_suffix(']')?;
+        let (key, value) = inner.split_once('=')?;
+        (key.trim() == "link_name").then(|| value.trim().trim_matches('"'))
+    }
+
     /// Extract a struct node and its fields.
     fn visit_struct(state: &mut ExtractionState, node: TsNode<'_>) {
         let name = Self::extract_name(state, node).unwrap_or_else(|| "<anonymous>".to_string());
@@ -282,6 +439,7 @@ impl RustExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -343,6 +501,7 @@ impl RustExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -403,6 +562,7 @@ impl RustExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -467,6 +627,7 @@ impl RustExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -479,6 +640,10 @@ impl RustExtractor {
                 line: start_line,
                 column: start_column,
                 file_path: state.file_path.clone(),
+                receiver_type: None,
+                abi: None,
+                http_method: None,
+                sql: false,
             });
         }
 
@@ -544,6 +709,7 @@ impl RustExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -555,6 +721,10 @@ impl RustExtractor {
             line: start_line,
             column: start_column,
             file_path: state.file_path.clone(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
         });
     }
 
@@ -604,6 +774,7 @@ impl RustExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -654,6 +825,7 @@ impl RustExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -704,6 +876,7 @@ impl RustExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -718,11 +891,20 @@ impl RustExtractor {
         let start_column = node.start_position().column as u32;
         let end_column = node.end_position().column as u32;
         let qualified_name = format!("{}::{}", state.qualified_prefix(), name);
-        let id = generate_node_id(&state.file_path, &NodeKind::Module, &name, start_line);
+        // A `#[cfg(test)]` module is a test suite.
+        let kind = if Self::preceding_attributes(state, node)
+            .iter()
+            .any(|a| Self::is_cfg_test_attribute(a))
+        {
+            NodeKind::TestCase
+        } else {
+            NodeKind::Module
+        };
+        let id = generate_node_id(&state.file_path, &kind, &name, start_line);
 
         let graph_node = Node {
             id: id.clone(),
-            kind: NodeKind::Module,
+            kind,
             name: name.clone(),
             qualified_name,
             file_path: state.file_path.clone(),
@@ -752,6 +934,7 @@ impl RustExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -784,6 +967,10 @@ impl RustExtractor {
                 line: start_line,
                 column: start_column,
                 file_path: state.file_path.clone(),
+                receiver_type: None,
+                abi: None,
+                http_method: None,
+                sql: false,
             });
         }
     }
@@ -987,11 +1174,56 @@ impl RustExtractor {
         if let Some(parent_id) = state.parent_node_id() {
             state.edges.push(Edge {
                 source: parent_id.to_string(),
-                target: id,
+                target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
+
+        if let Some(field_type) = node.child_by_field_name("type") {
+            push_type_refs(
+                &mut state.unresolved_refs,
+                field_type,
+                &state.source,
+                &RUST_TYPE_REFS,
+                &id,
+                &EdgeKind::TypeOf,
+                &state.file_path,
+            );
+        }
+    }
+
+    /// Records the declared parameters and return type of a function.
+    /// `self` is a receiver, not a parameter.
+    fn push_signature(state: &mut ExtractionState, node: TsNode<'_>, id: &str) {
+        let mut params = Vec::new();
+        if let Some(list) = node.child_by_field_name("parameters") {
+            let mut cursor = list.walk();
+            for child in list.named_children(&mut cursor) {
+                match child.kind() {
+                    "parameter" => {
+                        let name = child
+                            .child_by_field_name("pattern")
+                            .map(|p| state.node_text(p))
+                            .unwrap_or_default();
+                        let name = name.strip_prefix("mut ").unwrap_or(&name);
+                        let ty = child
+                            .child_by_field_name("type")
+                            .map(|t| state.node_text(t));
+                        params.push(param(name, ty.as_deref(), None, false));
+                    }
+                    "variadic_parameter" => params.push(param("...", None, None, true)),
+                    _ => {}
+                }
+            }
+        }
+        let return_type = node
+            .child_by_field_name("return_type")
+            .map(|t| state.node_text(t));
+        state
+            .signatures
+            .extend(signature(id, params, return_type.as_deref()));
     }
 
     /// Extract enum variants from the enum body.
@@ -1055,6 +1287,7 @@ impl RustExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -1070,6 +1303,13 @@ impl RustExtractor {
                     "call_expression" => {
                         if let Some(callee) = child.child_by_field_name("function") {
                             let callee_name = state.node_text(callee);
+                            let receiver = receiver_type(
+                                child,
+                                &callee_name,
+                                &state.source,
+                                &state.type_decls,
+                                &RUST_RECEIVERS,
+                            );
                             state.unresolved_refs.push(UnresolvedRef {
                                 from_node_id: fn_node_id.to_string(),
                                 reference_name: callee_name,
@@ -1077,6 +1317,10 @@ impl RustExtractor {
                                 line: child.start_position().row as u32,
                                 column: child.start_position().column as u32,
                                 file_path: state.file_path.clone(),
+                                receiver_type: receiver,
+                                abi: None,
+                                http_method: None,
+                                sql: false,
                             });
                         }
                         // Also recurse into the call expression for nested calls.
@@ -1097,6 +1341,10 @@ impl RustExtractor {
                             line: child.start_position().row as u32,
                             column: child.start_position().column as u32,
                             file_path: state.file_path.clone(),
+                            receiver_type: None,
+                            abi: None,
+                            http_method: None,
+                            sql: false,
                         });
                     }
                     // Skip nested function definitions — they are handled separately.
@@ -1155,6 +1403,10 @@ impl RustExtractor {
                             line,
                             column: attr_node.start_position().column as u32,
                             file_path: state.file_path.clone(),
+                            receiver_type: None,
+                            abi: None,
+                            http_method: None,
+                            sql: false,
                         });
                     }
                 }
@@ -1239,6 +1491,10 @@ impl RustExtractor {
             line: start_line,
             column: start_column,
             file_path: state.file_path.clone(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
         });
 
         // Direct Annotates edge from the annotation to the target.
@@ -1247,6 +1503,7 @@ impl RustExtractor {
             target: target_id.to_string(),
             kind: EdgeKind::Annotates,
             line: Some(start_line),
+            provenance: None,
         });
     }
 
@@ -1271,6 +1528,7 @@ impl RustExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: state.signatures,
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -1289,4 +1547,12 @@ impl crate::extraction::LanguageExtractor for RustExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         RustExtractor::extract(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
+
+    fn extracts_signatures(&self) -> bool {
+        true
+    }
 }
@@ -9,8 +9,12 @@ use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tree_sitter::{Node as TsNode, Parser, Tree};
 
 use crate::extraction::complexity::{count_complexity, SCALA_COMPLEXITY};
+use crate::extraction::receivers::{receiver_type, TypeDecls, SCALA_RECEIVERS};
+use crate::extraction::signatures::{param, signature};
+use crate::extraction::type_refs::{push_type_refs, SCALA_TYPE_REFS};
 use crate::types::{
-    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, UnresolvedRef, Visibility,
+    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, Signature, UnresolvedRef,
+    Visibility,
 };
 
 /// Extracts code graph nodes and edges from Scala source files using tree-sitter.
@@ -21,11 +25,14 @@ struct ExtractionState {
     nodes: Vec<Node>,
     edges: Vec<Edge>,
     unresolved_refs: Vec<UnresolvedRef>,
+    signatures: Vec<Signature>,
     errors: Vec<String>,
     /// Stack of (name, `node_id`) for building qualified names and parent edges.
     node_stack: Vec<(String, String)>,
     file_path: String,
     source: Vec<u8>,
+    /// Type declarations of the file, for receiver inference.
+    type_decls: TypeDecls,
     timestamp: u64,
     /// Track nesting depth to distinguish inner classes from top-level classes.
     class_depth: usize,
@@ -43,10 +50,12 @@ impl ExtractionState {
             nodes: Vec::new(),
             edges: Vec::new(),
             unresolved_refs: Vec::new(),
+            signatures: Vec::new(),
             errors: Vec::new(),
             node_stack: Vec::new(),
             file_path: file_path.to_string(),
             source: source.as_bytes().to_vec(),
+            type_decls: TypeDecls::default(),
             timestamp,
             class_depth: 0,
             inside_trait: false,
@@ -81,6 +90,12 @@ impl ScalaExtractor {
     /// `file_path` is used for qualified names and node IDs (not for I/O).
     /// `source` is the Scala source code to parse.
     pub fn extract_scala(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -88,7 +103,7 @@ impl ScalaExtractor {
             Ok(tree) => tree,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -122,11 +137,12 @@ impl ScalaExtractor {
 
         // Walk the AST.
         let root = tree.root_node();
+        state.type_decls = TypeDecls::new(root, &state.source, &SCALA_RECEIVERS);
         Self::visit_children(&mut state, root);
 
         state.node_stack.pop();
 
-        Self::build_result(state, start)
+        (Self::build_result(state, start), Some(tree))
     }
 
     /// Parse source code into a tree-sitter AST.
@@ -231,6 +247,7 @@ impl ScalaExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -292,6 +309,7 @@ impl ScalaExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -302,6 +320,10 @@ impl ScalaExtractor {
             line: start_line,
             column: start_column,
             file_path: state.file_path.clone(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
         });
     }
 
@@ -363,6 +385,7 @@ impl ScalaExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -428,6 +451,7 @@ impl ScalaExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -495,6 +519,7 @@ impl ScalaExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -558,6 +583,7 @@ impl ScalaExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -646,6 +672,7 @@ impl ScalaExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -706,10 +733,13 @@ impl ScalaExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
         Self::extract_annotations(state, node, &id);
+        Self::extract_type_refs(state, node, &id);
+        Self::push_signature(state, node, &id);
 
         // Extract call sites from the body.
         if let Some(body) = node.child_by_field_name("body") {
@@ -768,10 +798,13 @@ impl ScalaExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
         Self::extract_annotations(state, node, &id);
+        Self::extract_type_refs(state, node, &id);
+        Self::push_signature(state, node, &id);
     }
 
     // -----------------------------------------------------------------------
@@ -828,11 +861,24 @@ impl ScalaExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
         Self::extract_annotations(state, node, &id);
 
+        if let Some(declared_type) = node.child_by_field_name("type") {
+            push_type_refs(
+                &mut state.unresolved_refs,
+                declared_type,
+                &state.source,
+                &SCALA_TYPE_REFS,
+                &id,
+                &EdgeKind::TypeOf,
+                &state.file_path,
+            );
+        }
+
         // Extract call sites from the value expression.
         if let Some(value) = node.child_by_field_name("value") {
             Self::extract_call_sites(state, value, &id);
@@ -889,11 +935,24 @@ impl ScalaExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
         Self::extract_annotations(state, node, &id);
 
+        if let Some(declared_type) = node.child_by_field_name("type") {
+            push_type_refs(
+                &mut state.unresolved_refs,
+                declared_type,
+                &state.source,
+                &SCALA_TYPE_REFS,
+                &id,
+                &EdgeKind::TypeOf,
+                &state.file_path,
+            );
+        }
+
         if let Some(value) = node.child_by_field_name("value") {
             Self::extract_call_sites(state, value, &id);
         }
@@ -947,6 +1006,7 @@ impl ScalaExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -1131,6 +1191,10 @@ impl ScalaExtractor {
                                 line: child.start_position().row as u32,
                                 column: child.start_position().column as u32,
                                 file_path: state.file_path.clone(),
+                                receiver_type: None,
+                                abi: None,
+                                http_method: None,
+                                sql: false,
                             });
                         }
                     }
@@ -1191,6 +1255,7 @@ impl ScalaExtractor {
                             target: id,
                             kind: EdgeKind::Contains,
                             line: Some(start_line),
+                            provenance: None,
                         });
                     }
                     if !cursor.goto_next_sibling() {
@@ -1263,10 +1328,23 @@ impl ScalaExtractor {
                         });
                         state.edges.push(Edge {
                             source: owner_id.to_string(),
-                            target: id,
+                            target: id.clone(),
                             kind: EdgeKind::Contains,
                             line: Some(start_line),
+                            provenance: None,
                         });
+
+                        if let Some(param_type) = child.child_by_field_name("type") {
+                            push_type_refs(
+                                &mut state.unresolved_refs,
+                                param_type,
+                                &state.source,
+                                &SCALA_TYPE_REFS,
+                                &id,
+                                &EdgeKind::TypeOf,
+                                &state.file_path,
+                            );
+                        }
                     }
                     if !cursor.goto_next_sibling() {
                         break;
@@ -1276,6 +1354,80 @@ impl ScalaExtractor {
         }
     }
 
+    /// Extract type references from parameter types and return type.
+    ///
+    /// Types named in every `parameters` clause (curried functions have
+    /// several) become `Receives` refs and the `return_type` field becomes
+    /// `Returns` refs.
+    fn extract_type_refs(state: &mut ExtractionState, node: TsNode<'_>, fn_node_id: &str) {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            if child.kind() == "parameters" {
+                push_type_refs(
+                    &mut state.unresolved_refs,
+                    child,
+                    &state.source,
+                    &SCALA_TYPE_REFS,
+                    fn_node_id,
+                    &EdgeKind::Receives,
+                    &state.file_path,
+                );
+            }
+        }
+        if let Some(return_type) = node.child_by_field_name("return_type") {
+            push_type_refs(
+                &mut state.unresolved_refs,
+                return_type,
+                &state.source,
+                &SCALA_TYPE_REFS,
+                fn_node_id,
+                &EdgeKind::Returns,
+                &state.file_path,
+            );
+        }
+    }
+
+    /// Records the declared parameters and return type of a function. The
+    /// parameters of every clause of a curried function are listed in
+    /// order. Parameters with a default value and repeated parameters
+    /// (`tags: String*`) are optional.
+    fn push_signature(state: &mut ExtractionState, node: TsNode<'_>, fn_node_id: &str) {
+        let mut params = Vec::new();
+        let mut cursor = node.walk();
+        for clause in node.named_children(&mut cursor) {
+            if clause.kind() != "parameters" {
+                continue;
+            }
+            let mut inner = clause.walk();
+            for child in clause.named_children(&mut inner) {
+                if child.kind() != "parameter" {
+                    continue;
+                }
+                let name = child
+                    .child_by_field_name("name")
+                    .map(|n| state.node_text(n))
+                    .unwrap_or_default();
+                let declared = child.child_by_field_name("type");
+                let repeated = declared.is_some_and(|t| t.kind() == "repeated_parameter_type");
+                // A repeated parameter collects a sequence, like Java's `T...`.
+                let ty = declared.map(|t| match t.child_by_field_name("type") {
+                    Some(inner) if repeated => format!("{}...", state.node_text(inner)),
+                    _ => state.node_text(t),
+                });
+                let default = child
+                    .child_by_field_name("default_value")
+                    .map(|v| state.node_text(v));
+                params.push(param(&name, ty.as_deref(), default.as_deref(), repeated));
+            }
+        }
+        let return_type = node
+            .child_by_field_name("return_type")
+            .map(|t| state.node_text(t));
+        state
+            .signatures
+            .extend(signature(fn_node_id, params, return_type.as_deref()));
+    }
+
     /// Recursively find `call_expression` nodes and create unresolved Calls references.
     fn extract_call_sites(state: &mut ExtractionState, node: TsNode<'_>, fn_node_id: &str) {
         let mut cursor = node.walk();
@@ -1285,6 +1437,13 @@ impl ScalaExtractor {
                 match child.kind() {
                     "call_expression" => {
                         let callee_name = Self::extract_call_name(state, child);
+                        let receiver = receiver_type(
+                            child,
+                            &callee_name,
+                            &state.source,
+                            &state.type_decls,
+                            &SCALA_RECEIVERS,
+                        );
                         state.unresolved_refs.push(UnresolvedRef {
                             from_node_id: fn_node_id.to_string(),
                             reference_name: callee_name,
@@ -1292,6 +1451,10 @@ impl ScalaExtractor {
                             line: child.start_position().row as u32,
                             column: child.start_position().column as u32,
                             file_path: state.file_path.clone(),
+                            receiver_type: receiver,
+                            abi: None,
+                            http_method: None,
+                            sql: false,
                         });
                         Self::extract_call_sites(state, child, fn_node_id);
                     }
@@ -1304,6 +1467,10 @@ impl ScalaExtractor {
                             line: child.start_position().row as u32,
                             column: child.start_position().column as u32,
                             file_path: state.file_path.clone(),
+                            receiver_type: None,
+                            abi: None,
+                            http_method: None,
+                            sql: false,
                         });
                         Self::extract_call_sites(state, child, fn_node_id);
                     }
@@ -1431,6 +1598,10 @@ impl ScalaExtractor {
                         line: start_line,
                         column: start_column,
                         file_path: state.file_path.clone(),
+                        receiver_type: None,
+                        abi: None,
+                        http_method: None,
+                        sql: false,
                     });
 
                     // Direct Annotates edge from the annotation to the target.
@@ -1439,6 +1610,7 @@ impl ScalaExtractor {
                         target: target_id.to_string(),
                         kind: EdgeKind::Annotates,
                         line: Some(start_line),
+                        provenance: None,
                     });
                 }
                 if !cursor.goto_next_sibling() {
@@ -1491,6 +1663,7 @@ impl ScalaExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: state.signatures,
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -1509,4 +1682,12 @@ impl crate::extraction::LanguageExtractor for ScalaExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         ScalaExtractor::extract_scala(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
+
+    fn extracts_signatures(&self) -> bool {
+        true
+    }
 }
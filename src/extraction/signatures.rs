@@ -0,0 +1,700 @@
+// Rust guideline compliant 2025-10-17
+//! Normalized parameter and return types.
+//!
+//! Extractors record the declared parameters and return type of each
+//! callable as a [`Signature`]. A type is kept as written and as a
+//! normalized name, which drops what does not change the type meant:
+//! references, pointers, lifetimes, qualifiers such as `mut`, `const`,
+//! `dyn` and `impl`, nullability (`T?`, `T | None`), module paths and
+//! whitespace. `&'a mut crate::db::Database` and Go's `*db.Database` both
+//! normalize to `Database`, Python's `List[int]` to `List<int>`, and Go's
+//! `[]byte` and Java's `byte[]` to `byte[]`.
+//!
+//! [`TypePattern`] matches normalized names against a query, comparing
+//! generic arguments structurally: `Result<Subgraph>` matches
+//! `Result<Subgraph, TokenSaveError>`, a bare `Result` matches any
+//! `Result`, and `_` or a single capital letter stands for any type.
+
+use std::fmt;
+
+use crate::types::{Param, Signature, TypeSpec};
+
+/// Name of tuple types, such as Go's multiple results.
+const TUPLE: &str = "()";
+/// Name of array and slice types.
+const ARRAY: &str = "[]";
+/// Name of union types.
+const UNION: &str = "|";
+/// Name of object literal types.
+const OBJECT: &str = "{}";
+/// Name of function types.
+const FUNCTION: &str = "fn";
+
+/// Nesting beyond which a type is not parsed further.
+const MAX_DEPTH: usize = 32;
+
+/// Words qualifying a type without changing which type it is.
+const QUALIFIERS: &[&str] = &[
+    "mut", "const", "dyn", "impl", "final", "readonly", "volatile", "restrict", "struct", "enum",
+    "union", "typename", "in", "out", "ref", "unique", "keyof", "extends", "super",
+];
+
+/// Union members that only make the type nullable.
+const NULLS: &[&str] = &["null", "undefined", "None", "nil", "void"];
+
+/// C type specifiers that combine with the following word.
+const C_SPECIFIERS: &[&str] = &["unsigned", "signed", "long", "short"];
+
+/// Names of function types across languages.
+const FUNCTION_NAMES: &[&str] = &["fn", "func", "function", "Fn", "FnMut", "FnOnce"];
+
+/// Builds the spec of a type as written in a signature.
+pub fn type_spec(text: &str) -> TypeSpec {
+    let text = text.trim();
+    TypeSpec {
+        text: text.to_string(),
+        name: normalize_type(text),
+    }
+}
+
+/// Builds a parameter, normalizing its declared type.
+pub fn param(
+    name: &str,
+    declared_type: Option<&str>,
+    default_value: Option<&str>,
+    optional: bool,
+) -> Param {
+    Param {
+        name: name.trim().to_string(),
+        declared_type: declared_type
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(type_spec),
+        default_value: default_value.map(|v| v.trim().to_string()),
+        optional: optional || default_value.is_some(),
+    }
+}
+
+/// Builds the signature of `node_id`, or `None` when it declares neither
+/// parameters nor a return type.
+pub fn signature(
+    node_id: &str,
+    params: Vec<Param>,
+    return_type: Option<&str>,
+) -> Option<Signature> {
+    let return_type = return_type
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(type_spec);
+    if params.is_empty() && return_type.is_none() {
+        return None;
+    }
+    Some(Signature {
+        node_id: node_id.to_string(),
+        params,
+        return_type,
+    })
+}
+
+/// Normalizes a type as written to the name it is stored and matched
+/// under; see the module documentation.
+pub fn normalize_type(text: &str) -> String {
+    let tokens = tokenize(text);
+    let mut parser = Parser {
+        tokens: &tokens,
+        pos: 0,
+    };
+    match parser.parse_union(0) {
+        Some(expr) => expr.to_string(),
+        None => text.split_whitespace().collect(),
+    }
+}
+
+/// A type to look for in signatures; see the module documentation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypePattern {
+    expr: TypeExpr,
+}
+
+impl TypePattern {
+    /// Parses a pattern, normalizing it like a declared type. Returns
+    /// `None` when `text` names no type.
+    pub fn parse(text: &str) -> Option<Self> {
+        let tokens = tokenize(text);
+        let mut parser = Parser {
+            tokens: &tokens,
+            pos: 0,
+        };
+        parser.parse_union(0).map(|expr| Self { expr })
+    }
+
+    /// A name every matching type contains, for narrowing candidates
+    /// before matching them; `None` when the pattern is a wildcard.
+    pub fn key(&self) -> Option<&str> {
+        let mut stack = vec![&self.expr];
+        while let Some(expr) = stack.pop() {
+            if !is_wildcard(&expr.name) && ![TUPLE, ARRAY, UNION, OBJECT].contains(&&*expr.name) {
+                return Some(&expr.name);
+            }
+            stack.extend(expr.args.iter().rev());
+        }
+        None
+    }
+
+    /// Whether the normalized type `name` is an instance of the pattern.
+    pub fn matches(&self, name: &str) -> bool {
+        parse_name(name).is_some_and(|ty| expr_matches(&self.expr, &ty))
+    }
+
+    /// Whether the pattern matches the normalized type `name` or any type
+    /// nested in its generic arguments (`Database` within
+    /// `Arc<Database>`).
+    pub fn matches_within(&self, name: &str) -> bool {
+        let Some(ty) = parse_name(name) else {
+            return false;
+        };
+        let mut stack = vec![&ty];
+        while let Some(current) = stack.pop() {
+            if expr_matches(&self.expr, current) {
+                return true;
+            }
+            stack.extend(current.args.iter());
+        }
+        false
+    }
+}
+
+impl fmt::Display for TypePattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.expr.fmt(f)
+    }
+}
+
+/// Parses a normalized type name back into its structure.
+fn parse_name(name: &str) -> Option<TypeExpr> {
+    let tokens = tokenize(name);
+    let mut parser = Parser {
+        tokens: &tokens,
+        pos: 0,
+    };
+    parser.parse_union(0)
+}
+
+/// True for the pattern names standing for any type.
+fn is_wildcard(name: &str) -> bool {
+    name == "_" || (name.len() == 1 && name.chars().all(|c| c.is_ascii_uppercase()))
+}
+
+/// Whether `ty` is an instance of `pattern`. A pattern without generic
+/// arguments matches every instantiation, and one with arguments matches
+/// types whose leading arguments match them, so `Result<T>` aliases match
+/// `Result<T, E>`. Union members match in any order.
+fn expr_matches(pattern: &TypeExpr, ty: &TypeExpr) -> bool {
+    if is_wildcard(&pattern.name) {
+        return true;
+    }
+    if pattern.name != ty.name {
+        return false;
+    }
+    if pattern.args.is_empty() {
+        return true;
+    }
+    match pattern.name.as_str() {
+        UNION => pattern
+            .args
+            .iter()
+            .all(|p| ty.args.iter().any(|t| expr_matches(p, t))),
+        TUPLE => {
+            pattern.args.len() == ty.args.len()
+                && pattern
+                    .args
+                    .iter()
+                    .zip(&ty.args)
+                    .all(|(p, t)| expr_matches(p, t))
+        }
+        _ => {
+            pattern.args.len() <= ty.args.len()
+                && pattern
+                    .args
+                    .iter()
+                    .zip(&ty.args)
+                    .all(|(p, t)| expr_matches(p, t))
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Parsing
+// ---------------------------------------------------------------------------
+
+/// A parsed type: a name and its generic arguments. Tuples, arrays,
+/// unions, object literals and function types use the names [`TUPLE`],
+/// [`ARRAY`], [`UNION`], [`OBJECT`] and [`FUNCTION`]; a function type's
+/// arguments are its parameter types followed by its return type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TypeExpr {
+    name: String,
+    args: Vec<TypeExpr>,
+}
+
+impl TypeExpr {
+    fn named(name: &str, args: Vec<TypeExpr>) -> Self {
+        Self {
+            name: name.to_string(),
+            args,
+        }
+    }
+}
+
+impl fmt::Display for TypeExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |f: &mut fmt::Formatter<'_>, sep: &str| -> fmt::Result {
+            for (i, arg) in self.args.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(sep)?;
+                }
+                arg.fmt(f)?;
+            }
+            Ok(())
+        };
+        match self.name.as_str() {
+            TUPLE => {
+                f.write_str("(")?;
+                join(f, ",")?;
+                f.write_str(")")
+            }
+            ARRAY => {
+                match self.args.first() {
+                    Some(elem) if elem.name == UNION => write!(f, "({elem})")?,
+                    Some(elem) => elem.fmt(f)?,
+                    None => {}
+                }
+                f.write_str("[]")
+            }
+            UNION => join(f, "|"),
+            OBJECT => f.write_str(OBJECT),
+            name => {
+                f.write_str(name)?;
+                if !self.args.is_empty() {
+                    f.write_str("<")?;
+                    join(f, ",")?;
+                    f.write_str(">")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Splits a type into identifiers and punctuation. Quoted names (Python
+/// forward references) become identifiers; lifetimes are dropped.
+fn tokenize(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() || c == '"' || c == '`' {
+            i += 1;
+        } else if is_ident(c) {
+            let start = i;
+            while i < chars.len() && is_ident(chars[i]) {
+                i += 1;
+            }
+            tokens.push(chars[start..i].iter().collect());
+        } else if c == '\'' {
+            // `'a` is a lifetime, `'Node'` a quoted name.
+            let start = i + 1;
+            i = start;
+            while i < chars.len() && is_ident(chars[i]) {
+                i += 1;
+            }
+            if chars.get(i) == Some(&'\'') {
+                tokens.push(chars[start..i].iter().collect());
+                i += 1;
+            }
+        } else {
+            let pair: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let triple: String = chars[i..chars.len().min(i + 3)].iter().collect();
+            let token = if triple == "..." {
+                triple
+            } else if ["::", "->", "=>"].contains(&pair.as_str()) {
+                pair
+            } else {
+                c.to_string()
+            };
+            i += token.chars().count();
+            tokens.push(token);
+        }
+    }
+    tokens
+}
+
+/// A recursive-descent parser over the tokens of a type, bounded by
+/// [`MAX_DEPTH`]. Each step consumes at least one token.
+struct Parser<'a> {
+    tokens: &'a [String],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&str> {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&str> {
+        self.tokens.get(self.pos + offset).map(String::as_str)
+    }
+
+    fn bump(&mut self) -> Option<&str> {
+        let token = self.tokens.get(self.pos).map(String::as_str);
+        self.pos += 1;
+        token
+    }
+
+    fn peek_ident(&self) -> bool {
+        self.peek()
+            .and_then(|t| t.chars().next())
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '$')
+    }
+
+    /// Parses `A | B | ...`, dropping the members that only make the type
+    /// nullable.
+    fn parse_union(&mut self, depth: usize) -> Option<TypeExpr> {
+        let mut members = Vec::new();
+        // TypeScript allows a leading `|`.
+        if self.peek() == Some("|") {
+            self.bump();
+        }
+        members.extend(self.parse_type(depth));
+        while self.peek() == Some("|") {
+            self.bump();
+            members.extend(self.parse_type(depth));
+        }
+        let (nulls, mut types): (Vec<_>, Vec<_>) = members
+            .into_iter()
+            .partition(|m| m.args.is_empty() && NULLS.contains(&m.name.as_str()));
+        match types.len() {
+            // A type that is only null, such as Python's `None` result.
+            0 => nulls.into_iter().next(),
+            1 => types.pop(),
+            _ => Some(TypeExpr::named(UNION, types)),
+        }
+    }
+
+    fn parse_type(&mut self, depth: usize) -> Option<TypeExpr> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        let mut variadic = false;
+        let mut wildcard = false;
+        loop {
+            match self.peek() {
+                Some("&" | "*" | "^" | "~" | "@") => {}
+                Some("...") => variadic = true,
+                // Java's `?`, `? extends T` and `? super T`.
+                Some("?") => wildcard = true,
+                Some(word) if QUALIFIERS.contains(&word) => {}
+                _ => break,
+            }
+            self.bump();
+        }
+        let Some(first) = self.peek() else {
+            return wildcard.then(|| TypeExpr::named("_", Vec::new()));
+        };
+        let mut ty = match first {
+            "(" => {
+                self.bump();
+                let mut items = self.parse_list(")", depth);
+                if matches!(self.peek(), Some("=>" | "->")) {
+                    self.bump();
+                    items.extend(self.parse_union(depth + 1));
+                    TypeExpr::named(FUNCTION, items)
+                } else if items.len() == 1 {
+                    items.pop()?
+                } else {
+                    TypeExpr::named(TUPLE, items)
+                }
+            }
+            "[" => {
+                self.bump();
+                let sized = self.peek() == Some("]")
+                    || self.peek() == Some("...")
+                    || self.peek_at(1) == Some("]")
+                        && self
+                            .peek()
+                            .is_some_and(|t| t.chars().all(|c| c.is_ascii_digit()));
+                if sized {
+                    // Go's `[]T`, `[N]T` and `[...]T`.
+                    while !matches!(self.bump(), Some("]") | None) {}
+                    TypeExpr::named(ARRAY, self.parse_type(depth + 1).into_iter().collect())
+                } else {
+                    let elem = self.parse_union(depth + 1);
+                    if self.peek() == Some(",") {
+                        // Python's `[A, B]` inside `Callable[[A, B], R]`.
+                        let mut items: Vec<TypeExpr> = elem.into_iter().collect();
+                        items.extend(self.parse_list("]", depth));
+                        TypeExpr::named(TUPLE, items)
+                    } else {
+                        // Rust's `[T]` and `[T; N]`.
+                        while !matches!(self.bump(), Some("]") | None) {}
+                        TypeExpr::named(ARRAY, elem.into_iter().collect())
+                    }
+                }
+            }
+            "{" => {
+                let mut open = 0usize;
+                while let Some(token) = self.bump() {
+                    match token {
+                        "{" => open += 1,
+                        "}" if open <= 1 => break,
+                        "}" => open -= 1,
+                        _ => {}
+                    }
+                }
+                TypeExpr::named(OBJECT, Vec::new())
+            }
+            _ if self.peek_ident() => self.parse_named(depth)?,
+            _ if wildcard => TypeExpr::named("_", Vec::new()),
+            // Leave closing delimiters to the list they close.
+            ")" | "]" | ">" | "}" | "," | "|" => return None,
+            _ => {
+                self.bump();
+                return None;
+            }
+        };
+        loop {
+            match self.peek() {
+                Some("[") if self.peek_at(1) == Some("]") => {
+                    self.pos += 2;
+                    ty = TypeExpr::named(ARRAY, vec![ty]);
+                }
+                Some("...") => {
+                    self.bump();
+                    ty = TypeExpr::named(ARRAY, vec![ty]);
+                }
+                Some("?" | "*" | "&" | "!") => {
+                    self.bump();
+                }
+                _ => break,
+            }
+        }
+        if variadic {
+            ty = TypeExpr::named(ARRAY, vec![ty]);
+        }
+        Some(ty)
+    }
+
+    /// Parses a possibly qualified name and its generic arguments.
+    fn parse_named(&mut self, depth: usize) -> Option<TypeExpr> {
+        let mut name = self.bump()?.to_string();
+        loop {
+            match (self.peek(), self.peek_at(1)) {
+                (Some("::" | "."), Some("<")) => {
+                    // Turbofish: `Vec::<T>`.
+                    self.bump();
+                }
+                (Some("::" | "."), Some(_)) => {
+                    self.bump();
+                    if !self.peek_ident() {
+                        break;
+                    }
+                    name = self.bump()?.to_string();
+                }
+                _ => break,
+            }
+        }
+        while C_SPECIFIERS.contains(&name.split(' ').next_back().unwrap_or("")) && self.peek_ident()
+        {
+            name = format!("{name} {}", self.bump()?);
+        }
+
+        if FUNCTION_NAMES.contains(&name.as_str()) && self.peek() == Some("(") {
+            self.bump();
+            let mut items = self.parse_list(")", depth);
+            if self.peek() == Some("->") {
+                self.bump();
+                items.extend(self.parse_union(depth + 1));
+            } else if name == "func"
+                && (self.peek_ident() || matches!(self.peek(), Some("(" | "[" | "*")))
+            {
+                // Go writes the results straight after the parameters.
+                items.extend(self.parse_type(depth + 1));
+            }
+            return Some(TypeExpr::named(FUNCTION, items));
+        }
+        if name == "map" && self.peek() == Some("[") {
+            // Go's `map[K]V`.
+            self.bump();
+            let key = self.parse_list("]", depth);
+            let value = self.parse_type(depth + 1);
+            return Some(TypeExpr::named(
+                "map",
+                key.into_iter().chain(value).collect(),
+            ));
+        }
+        if name == "chan" && self.peek() != Some("<") {
+            let elem = self.parse_type(depth + 1);
+            return Some(TypeExpr::named("chan", elem.into_iter().collect()));
+        }
+
+        let args = match self.peek() {
+            Some("<") => {
+                self.bump();
+                self.parse_list(">", depth)
+            }
+            Some("[") if self.peek_at(1) != Some("]") => {
+                self.bump();
+                self.parse_list("]", depth)
+            }
+            _ => Vec::new(),
+        };
+        Some(TypeExpr { name, args })
+    }
+
+    /// Parses comma-separated types up to and including `close`, skipping
+    /// parameter names (`a: A`), associated type names (`Item = T`) and
+    /// lifetimes.
+    fn parse_list(&mut self, close: &str, depth: usize) -> Vec<TypeExpr> {
+        let mut items = Vec::new();
+        loop {
+            match self.peek() {
+                None => break,
+                Some(token) if token == close => {
+                    self.bump();
+                    break;
+                }
+                Some(",") => {
+                    self.bump();
+                    continue;
+                }
+                _ => {}
+            }
+            if self.peek_ident() {
+                match (self.peek_at(1), self.peek_at(2)) {
+                    (Some(":" | "="), _) => self.pos += 2,
+                    (Some("?"), Some(":")) => self.pos += 3,
+                    _ => {}
+                }
+            }
+            let before = self.pos;
+            items.extend(self.parse_union(depth + 1));
+            if self.pos == before {
+                self.bump();
+            }
+        }
+        items
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_drops_references_paths_and_qualifiers() {
+        assert_eq!(normalize_type("&'a mut crate::db::Database"), "Database");
+        assert_eq!(normalize_type("*db.Database"), "Database");
+        assert_eq!(normalize_type("const char *"), "char");
+        assert_eq!(normalize_type("unsigned long int"), "unsigned long int");
+        assert_eq!(
+            normalize_type("impl Iterator<Item = &Node>"),
+            "Iterator<Node>"
+        );
+        assert_eq!(
+            normalize_type("Box<dyn Fn(&str) -> bool>"),
+            "Box<fn<str,bool>>"
+        );
+    }
+
+    #[test]
+    fn normalize_unifies_generic_array_and_nullable_syntax() {
+        assert_eq!(normalize_type("List[int]"), "List<int>");
+        assert_eq!(normalize_type("Dict[str, 'Node']"), "Dict<str,Node>");
+        assert_eq!(normalize_type("[]byte"), "byte[]");
+        assert_eq!(normalize_type("byte[]"), "byte[]");
+        assert_eq!(normalize_type("String..."), "String[]");
+        assert_eq!(normalize_type("map[string][]int"), "map<string,int[]>");
+        assert_eq!(normalize_type("Node | None"), "Node");
+        assert_eq!(
+            normalize_type("string | number | undefined"),
+            "string|number"
+        );
+        assert_eq!(normalize_type("Node?"), "Node");
+        assert_eq!(normalize_type("(Node, error)"), "(Node,error)");
+        assert_eq!(
+            normalize_type("Promise<Map<string, User[]>>"),
+            "Promise<Map<string,User[]>>"
+        );
+        assert_eq!(normalize_type("&[u8]"), "u8[]");
+        assert_eq!(normalize_type("List<? extends Node>"), "List<Node>");
+        assert_eq!(normalize_type("Map<String, ?>"), "Map<String,_>");
+        assert_eq!(normalize_type("None"), "None");
+        assert_eq!(
+            normalize_type("Callable[[int, str], bool]"),
+            "Callable<(int,str),bool>"
+        );
+    }
+
+    #[test]
+    fn normalize_survives_malformed_types() {
+        assert_eq!(normalize_type(""), "");
+        assert_eq!(normalize_type("Vec<"), "Vec");
+        assert_eq!(normalize_type(")))"), ")))");
+        assert_eq!(normalize_type("{ a: string }"), "{}");
+    }
+
+    #[test]
+    fn normalized_names_parse_back_to_themselves() {
+        for text in [
+            "Result<Subgraph,TokenSaveError>",
+            "(Node,error)",
+            "(string|number)[]",
+            "map<string,int[]>",
+            "fn<str,bool>",
+            "unsigned int",
+        ] {
+            assert_eq!(normalize_type(text), text);
+        }
+    }
+
+    #[test]
+    fn pattern_matches_generics_structurally() {
+        let pattern = TypePattern::parse("Result<Subgraph>").unwrap();
+        assert!(pattern.matches("Result<Subgraph>"));
+        assert!(pattern.matches("Result<Subgraph,TokenSaveError>"));
+        assert!(!pattern.matches("Result<Vec<Subgraph>>"));
+        assert!(!pattern.matches("Option<Subgraph>"));
+        assert!(pattern.matches_within("Option<Result<Subgraph>>"));
+
+        let bare = TypePattern::parse("Result").unwrap();
+        assert!(bare.matches("Result<()>"));
+        assert!(bare.matches("Result"));
+
+        let wildcard = TypePattern::parse("HashMap<_, Vec<T>>").unwrap();
+        assert!(wildcard.matches("HashMap<String,Vec<Node>>"));
+        assert!(!wildcard.matches("HashMap<String,Node>"));
+        assert_eq!(wildcard.key(), Some("HashMap"));
+        assert_eq!(TypePattern::parse("_").unwrap().key(), None);
+    }
+
+    #[test]
+    fn pattern_normalizes_like_declared_types() {
+        let pattern = TypePattern::parse("&Database").unwrap();
+        assert!(pattern.matches(&normalize_type("&mut crate::db::Database")));
+        assert!(!pattern.matches("Arc<Database>"));
+        assert!(pattern.matches_within("Arc<Database>"));
+        assert_eq!(pattern.to_string(), "Database");
+    }
+
+    #[test]
+    fn signature_skips_callables_declaring_nothing() {
+        assert!(signature("f", Vec::new(), None).is_none());
+        assert!(signature("f", Vec::new(), Some("  ")).is_none());
+        let sig = signature("f", vec![param("x", None, Some("1"), false)], None).unwrap();
+        assert!(sig.params[0].optional);
+        assert!(sig.params[0].declared_type.is_none());
+    }
+}
@@ -0,0 +1,1264 @@
+//! SQL schema objects and the statements touching them.
+//!
+//! Shared by the SQL extractor, which passes it the whole text of a `.sql`
+//! file, and by a second pass over the syntax tree of a Rust, Python, Go,
+//! Java or TypeScript / JavaScript file, which passes it the string
+//! literals that parse as SQL (`conn.execute("SELECT ... FROM nodes")`).
+//!
+//! `CREATE TABLE` and `ALTER TABLE ... ADD COLUMN` produce a
+//! [`NodeKind::SqlTable`] containing a [`NodeKind::SqlColumn`] per column,
+//! named `table.column`. `CREATE VIEW` and `CREATE INDEX` produce a
+//! [`NodeKind::SqlView`] and a [`NodeKind::SqlIndex`]. A foreign key column
+//! `DependsOn` the column, or the table, it references. Queries make
+//! references from the function around them, or the view they define, to
+//! the tables and columns they read (`Uses`) and to those they insert into,
+//! update or delete from (`Writes`). The resolver binds these only to SQL
+//! schema objects (see [`UnresolvedRef::sql`]).
+//!
+//! The analysis is lexical. Identifiers are case-insensitive and stored
+//! lowercase, schema qualifiers are dropped, and an unqualified column is
+//! attributed only when its statement names a single table. Queries
+//! assembled from several literals are not joined back together.
+
+use std::collections::{HashMap, HashSet};
+
+use tree_sitter::Node as TsNode;
+
+use super::domain::literal_text;
+use super::Callables;
+use crate::types::*;
+
+/// Longest statement text kept as a schema object's signature.
+const MAX_SIGNATURE_LEN: usize = 200;
+
+/// Words that never name a table, an alias or a column in a query, lowercase.
+const KEYWORDS: &[&str] = &[
+    "abort",
+    "after",
+    "all",
+    "alter",
+    "and",
+    "any",
+    "as",
+    "asc",
+    "before",
+    "begin",
+    "between",
+    "by",
+    "cascade",
+    "case",
+    "cast",
+    "check",
+    "collate",
+    "commit",
+    "conflict",
+    "constraint",
+    "create",
+    "cross",
+    "current_date",
+    "current_time",
+    "current_timestamp",
+    "default",
+    "delete",
+    "desc",
+    "distinct",
+    "do",
+    "drop",
+    "else",
+    "end",
+    "escape",
+    "except",
+    "exists",
+    "fail",
+    "false",
+    "filter",
+    "for",
+    "foreign",
+    "from",
+    "full",
+    "glob",
+    "group",
+    "having",
+    "if",
+    "ignore",
+    "ilike",
+    "in",
+    "indexed",
+    "inner",
+    "insert",
+    "instead",
+    "intersect",
+    "into",
+    "is",
+    "isnull",
+    "join",
+    "lateral",
+    "left",
+    "like",
+    "limit",
+    "match",
+    "materialized",
+    "natural",
+    "not",
+    "nothing",
+    "notnull",
+    "null",
+    "nulls",
+    "offset",
+    "on",
+    "only",
+    "or",
+    "order",
+    "outer",
+    "over",
+    "partition",
+    "primary",
+    "recursive",
+    "references",
+    "regexp",
+    "replace",
+    "restrict",
+    "returning",
+    "right",
+    "rollback",
+    "select",
+    "set",
+    "then",
+    "true",
+    "union",
+    "unique",
+    "update",
+    "using",
+    "values",
+    "when",
+    "where",
+    "window",
+    "with",
+];
+
+/// The words a `CREATE` may carry before the kind of object it creates.
+const CREATE_MODIFIERS: &[&str] = &[
+    "or",
+    "replace",
+    "temp",
+    "temporary",
+    "unique",
+    "virtual",
+    "materialized",
+    "unlogged",
+];
+
+/// The words after which a table definition lists a constraint rather than
+/// a column.
+const TABLE_CONSTRAINTS: &[&str] = &[
+    "constraint",
+    "primary",
+    "foreign",
+    "unique",
+    "check",
+    "exclude",
+];
+
+/// Adds the schema objects the `.sql` file at `file_path` defines, and the
+/// references its queries make, to `result`, its primary extraction.
+/// Queries inside the functions and procedures of `result` are attributed
+/// to them.
+pub(crate) fn extract_sql_file(file_path: &str, source: &str, result: &mut ExtractionResult) {
+    let mut pass = SqlPass::new(file_path, result);
+    let text = Text {
+        sql: source,
+        line: 0,
+        column: 0,
+    };
+    pass.statements(&text, &tokenize(source));
+}
+
+/// Adds the schema objects and query references of the string literals of
+/// `file_path` that parse as SQL to `result`, its primary extraction, from
+/// the syntax tree at `root`.
+pub(crate) fn extract_embedded_sql(
+    file_path: &str,
+    source: &str,
+    root: TsNode<'_>,
+    result: &mut ExtractionResult,
+) {
+    let kinds: &[&str] = match file_path.rsplit('.').next().unwrap_or("") {
+        "rs" => &["string_literal", "raw_string_literal"],
+        "py" => &["string"],
+        "go" => &["interpreted_string_literal", "raw_string_literal"],
+        "java" => &["string_literal", "text_block"],
+        "ts" | "tsx" | "js" | "jsx" => &["string", "template_string"],
+        _ => return,
+    };
+    let mut found = Vec::new();
+    literals(root, kinds, &mut found);
+    if found.is_empty() {
+        return;
+    }
+    let mut pass = SqlPass::new(file_path, result);
+    for literal in found {
+        let raw = literal.utf8_text(source.as_bytes()).unwrap_or("");
+        pass.literal(raw, literal);
+    }
+}
+
+/// Collects the nodes of the given kinds below `node`, without descending
+/// into them.
+fn literals<'t>(node: TsNode<'t>, kinds: &[&str], found: &mut Vec<TsNode<'t>>) {
+    if kinds.contains(&node.kind()) {
+        found.push(node);
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        literals(child, kinds, found);
+    }
+}
+
+/// SQL text and the file position it starts at.
+struct Text<'s> {
+    sql: &'s str,
+    line: u32,
+    column: u32,
+}
+
+impl Text<'_> {
+    /// The file position of byte `offset` of the text.
+    fn position(&self, offset: usize) -> (u32, u32) {
+        let before = &self.sql[..offset.min(self.sql.len())];
+        match before.rfind('\n') {
+            Some(newline) => (
+                self.line + before.matches('\n').count() as u32,
+                (before.len() - newline - 1) as u32,
+            ),
+            None => (self.line, self.column + before.len() as u32),
+        }
+    }
+
+    /// The text of `tokens` with its whitespace collapsed, shortened to a
+    /// signature.
+    fn signature(&self, tokens: &[Token<'_>]) -> Option<String> {
+        let (first, last) = (tokens.first()?, tokens.last()?);
+        let end = (last.offset + last.text.len()).min(self.sql.len());
+        let text = self.sql.get(first.offset..end)?;
+        Some(
+            text.split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .chars()
+                .take(MAX_SIGNATURE_LEN)
+                .collect(),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    /// A keyword or an unquoted identifier.
+    Word,
+    /// A quoted identifier (`"name"`, `` `name` ``, `[name]`).
+    Ident,
+    /// A string or number literal, a bind parameter (`?1`, `$1`, `:id`), or
+    /// a host-language interpolation (`{}`, `${id}`).
+    Value,
+    /// A dollar-quoted body (`$$ ... $$`), as in `PostgreSQL` functions.
+    Body,
+    Punct,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token<'s> {
+    kind: TokenKind,
+    /// The text, without the quotes of identifiers and bodies.
+    text: &'s str,
+    /// Byte offset of `text` in the SQL.
+    offset: usize,
+}
+
+impl Token<'_> {
+    fn is(&self, keyword: &str) -> bool {
+        self.kind == TokenKind::Word && self.text.eq_ignore_ascii_case(keyword)
+    }
+
+    fn is_any(&self, keywords: &[&str]) -> bool {
+        keywords.iter().any(|k| self.is(k))
+    }
+
+    fn is_punct(&self, c: char) -> bool {
+        self.kind == TokenKind::Punct && self.text.starts_with(c)
+    }
+
+    /// The identifier this token is, lowercased, when it is not a keyword.
+    fn name(&self) -> Option<String> {
+        let name = self.text.to_ascii_lowercase();
+        match self.kind {
+            TokenKind::Word if !KEYWORDS.contains(&name.as_str()) => Some(name),
+            TokenKind::Ident if !name.is_empty() => Some(name),
+            _ => None,
+        }
+    }
+
+    /// The identifier this token is, lowercased, keyword or not: column
+    /// definitions may name a column `key` or `order`.
+    fn any_name(&self) -> Option<String> {
+        matches!(self.kind, TokenKind::Word | TokenKind::Ident)
+            .then(|| self.text.to_ascii_lowercase())
+            .filter(|name| !name.is_empty())
+    }
+}
+
+/// Splits SQL into tokens, dropping whitespace and comments.
+fn tokenize(sql: &str) -> Vec<Token<'_>> {
+    let bytes = sql.as_bytes();
+    let mut tokens = Vec::new();
+    let mut push = |kind, start: usize, end: usize| {
+        tokens.push(Token {
+            kind,
+            text: &sql[start..end],
+            offset: start,
+        });
+    };
+    let word_byte = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || !b.is_ascii();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let next = bytes.get(i + 1).copied();
+        match bytes[i] {
+            b if b.is_ascii_whitespace() => i += 1,
+            b'-' if next == Some(b'-') => {
+                i = sql[i..].find('\n').map_or(bytes.len(), |end| i + end);
+            }
+            b'/' if next == Some(b'*') => {
+                i = sql[i + 2..]
+                    .find("*/")
+                    .map_or(bytes.len(), |end| i + end + 4);
+            }
+            b'\'' => {
+                i = closing(bytes, i, b'\'');
+                push(TokenKind::Value, start, i);
+            }
+            quote @ (b'"' | b'`' | b'[') => {
+                let close = if quote == b'[' { b']' } else { quote };
+                i = closing(bytes, i, close);
+                let end = if i > start + 1 && bytes[i - 1] == close {
+                    i - 1
+                } else {
+                    i
+                };
+                push(TokenKind::Ident, start + 1, end);
+            }
+            b'{' => {
+                i = matching_brace(bytes, i);
+                push(TokenKind::Value, start, i);
+            }
+            b'$' if next == Some(b'{') => {
+                i = matching_brace(bytes, i + 1);
+                push(TokenKind::Value, start, i);
+            }
+            b'$' if next.is_some_and(|b| b.is_ascii_digit()) => {
+                i += 1;
+                while i < bytes.len() && bytes[i].is_ascii_digit() {
+                    i += 1;
+                }
+                push(TokenKind::Value, start, i);
+            }
+            b'$' => {
+                let mut tag_end = i + 1;
+                while tag_end < bytes.len()
+                    && (bytes[tag_end].is_ascii_alphanumeric() || bytes[tag_end] == b'_')
+                {
+                    tag_end += 1;
+                }
+                if bytes.get(tag_end) == Some(&b'$') {
+                    let tag = &sql[i..=tag_end];
+                    let body = tag_end + 1;
+                    let end = sql[body..].find(tag).map_or(bytes.len(), |e| body + e);
+                    push(TokenKind::Body, body, end);
+                    i = (end + tag.len()).min(bytes.len());
+                } else {
+                    i += 1;
+                    push(TokenKind::Punct, start, i);
+                }
+            }
+            b'?' | b':' | b'@' if next.is_some_and(word_byte) => {
+                i += 1;
+                while i < bytes.len() && word_byte(bytes[i]) {
+                    i += 1;
+                }
+                push(TokenKind::Value, start, i);
+            }
+            b'?' => {
+                i += 1;
+                push(TokenKind::Value, start, i);
+            }
+            b if b.is_ascii_digit() => {
+                while i < bytes.len() && (word_byte(bytes[i]) || bytes[i] == b'.') {
+                    i += 1;
+                }
+                push(TokenKind::Value, start, i);
+            }
+            b if word_byte(b) => {
+                while i < bytes.len() && (word_byte(bytes[i]) || bytes[i] == b'$') {
+                    i += 1;
+                }
+                push(TokenKind::Word, start, i);
+            }
+            _ => {
+                i += 1;
+                push(TokenKind::Punct, start, i);
+            }
+        }
+    }
+    tokens
+}
+
+/// The index after the quote closing the one at `open`; doubled quotes
+/// escape it.
+fn closing(bytes: &[u8], open: usize, close: u8) -> usize {
+    let mut i = open + 1;
+    while i < bytes.len() {
+        if bytes[i] == close {
+            if close != b']' && bytes.get(i + 1) == Some(&close) {
+                i += 2;
+                continue;
+            }
+            return i + 1;
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+/// The index after the brace closing the one at `open`.
+fn matching_brace(bytes: &[u8], open: usize) -> usize {
+    let mut depth = 0usize;
+    for (i, b) in bytes.iter().enumerate().skip(open) {
+        match b {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    bytes.len()
+}
+
+/// Replaces the escape sequences of a string literal with as many spaces,
+/// keeping escaped quotes, so byte offsets still match the source.
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        unescaped.push(' ');
+        match chars.peek() {
+            Some('"' | '\'' | '\n') | None => {}
+            Some(next) if next.is_ascii() => {
+                chars.next();
+                unescaped.push(' ');
+            }
+            Some(_) => {}
+        }
+    }
+    unescaped
+}
+
+/// The index of the parenthesis closing the one at `tokens[open]`, or the
+/// number of tokens when it is not closed.
+fn matching(tokens: &[Token<'_>], open: usize) -> usize {
+    let mut depth = 0usize;
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        if token.is_punct('(') {
+            depth += 1;
+        } else if token.is_punct(')') {
+            depth = depth.saturating_sub(1);
+            if depth == 0 {
+                return i;
+            }
+        }
+    }
+    tokens.len()
+}
+
+/// Splits tokens at the commas outside parentheses.
+fn elements<'t, 's>(tokens: &'t [Token<'s>]) -> Vec<&'t [Token<'s>]> {
+    let mut elements = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        if token.is_punct('(') {
+            depth += 1;
+        } else if token.is_punct(')') {
+            depth = depth.saturating_sub(1);
+        } else if token.is_punct(',') && depth == 0 {
+            elements.push(&tokens[start..i]);
+            start = i + 1;
+        }
+    }
+    elements.push(&tokens[start..]);
+    elements
+}
+
+/// Skips an `IF [NOT] EXISTS` at `tokens[i]`.
+fn skip_if_exists(tokens: &[Token<'_>], mut i: usize) -> usize {
+    if tokens.get(i).is_some_and(|t| t.is("if")) {
+        while tokens
+            .get(i)
+            .is_some_and(|t| t.is_any(&["if", "not", "exists"]))
+        {
+            i += 1;
+        }
+    }
+    i
+}
+
+/// The possibly schema-qualified name at `tokens[i]` (`main.nodes`), without
+/// its qualifiers, and the index after it.
+fn qualified_name(tokens: &[Token<'_>], i: usize) -> Option<(String, usize)> {
+    let mut name = tokens.get(i)?.name()?;
+    let mut i = i + 1;
+    while tokens.get(i).is_some_and(|t| t.is_punct('.')) {
+        let Some(part) = tokens.get(i + 1).and_then(Token::any_name) else {
+            break;
+        };
+        name = part;
+        i += 2;
+    }
+    Some((name, i))
+}
+
+/// The table reference at `tokens[i]` (`main.nodes AS n`): its name, its
+/// alias and the index after it.
+fn table_ref(tokens: &[Token<'_>], i: usize) -> Option<(String, Option<String>, usize)> {
+    let (name, mut i) = qualified_name(tokens, i)?;
+    let mut alias = None;
+    if tokens.get(i).is_some_and(|t| t.is("as")) {
+        alias = tokens.get(i + 1).and_then(Token::name);
+        i += 2;
+    } else if let Some(name) = tokens.get(i).and_then(Token::name) {
+        alias = Some(name);
+        i += 1;
+    }
+    Some((name, alias, i))
+}
+
+/// The names of the common table expressions a `WITH` statement defines.
+fn cte_names(tokens: &[Token<'_>]) -> HashSet<String> {
+    let mut names = HashSet::new();
+    if !tokens.first().is_some_and(|t| t.is("with")) {
+        return names;
+    }
+    let mut i = 1;
+    if tokens.get(i).is_some_and(|t| t.is("recursive")) {
+        i += 1;
+    }
+    while let Some(name) = tokens.get(i).and_then(Token::name) {
+        names.insert(name);
+        i += 1;
+        if tokens.get(i).is_some_and(|t| t.is_punct('(')) {
+            i = matching(tokens, i) + 1;
+        }
+        while tokens
+            .get(i)
+            .is_some_and(|t| t.is_any(&["as", "not", "materialized"]))
+        {
+            i += 1;
+        }
+        if !tokens.get(i).is_some_and(|t| t.is_punct('(')) {
+            break;
+        }
+        i = matching(tokens, i) + 1;
+        if !tokens.get(i).is_some_and(|t| t.is_punct(',')) {
+            break;
+        }
+        i += 1;
+    }
+    names
+}
+
+/// The indexes of the columns assigned by the `SET` at `tokens[set]`
+/// (`SET a = 1, (b, c) = (2, 3)`).
+fn assignments(tokens: &[Token<'_>], set: usize) -> Vec<usize> {
+    let mut columns = Vec::new();
+    let mut depth = 0usize;
+    let mut expecting = true;
+    // Inside a parenthesized list of assigned columns.
+    let mut tuple = false;
+    for (i, token) in tokens.iter().enumerate().skip(set + 1) {
+        if token.is_punct('(') {
+            if depth == 0 {
+                tuple = expecting;
+            }
+            depth += 1;
+        } else if token.is_punct(')') {
+            depth = depth.saturating_sub(1);
+            if depth == 0 && tuple {
+                tuple = false;
+                expecting = false;
+            }
+        } else if depth == 0 && token.is_punct(',') {
+            expecting = true;
+        } else if depth == 0 && token.is_any(&["where", "from", "returning", "on"]) {
+            break;
+        } else if ((depth == 0 && expecting) || (depth == 1 && tuple)) && token.name().is_some() {
+            columns.push(i);
+            expecting = tuple;
+        }
+    }
+    columns
+}
+
+/// True when the first statement of a string literal reads as SQL rather
+/// than prose: its leading keyword is in one case and is followed by the
+/// clause that statement needs.
+fn is_statement(tokens: &[Token<'_>]) -> bool {
+    let Some(first) = tokens.first() else {
+        return false;
+    };
+    let same_case = first.text == first.text.to_ascii_uppercase()
+        || first.text == first.text.to_ascii_lowercase();
+    let has = |keyword: &str| tokens.iter().any(|t| t.is(keyword));
+    let next_is = |keywords: &[&str]| tokens.get(1).is_some_and(|t| t.is_any(keywords));
+    same_case
+        && first.kind == TokenKind::Word
+        && match first.text.to_ascii_lowercase().as_str() {
+            "select" => has("from"),
+            "with" => {
+                has("as")
+                    && ["select", "insert", "update", "delete"]
+                        .iter()
+                        .any(|k| has(k))
+            }
+            "insert" | "replace" => next_is(&["into", "or"]),
+            "update" => has("set"),
+            "delete" => next_is(&["from"]),
+            "create" | "alter" => tokens
+                .iter()
+                .take(5)
+                .any(|t| t.is_any(&["table", "view", "index", "trigger", "function", "procedure"])),
+            _ => false,
+        }
+}
+
+/// A table or column a query touches, and the token naming it.
+struct Access {
+    object: String,
+    kind: EdgeKind,
+    token: usize,
+}
+
+/// The tables a query names and the accesses found so far.
+struct Scope {
+    /// The common table expressions the query defines.
+    ctes: HashSet<String>,
+    /// Aliases, mapped to their table, or to `None` for a common table
+    /// expression.
+    aliases: HashMap<String, Option<String>>,
+    tables: Vec<String>,
+    accesses: Vec<Access>,
+}
+
+impl Scope {
+    fn new(ctes: HashSet<String>) -> Self {
+        Self {
+            ctes,
+            aliases: HashMap::new(),
+            tables: Vec::new(),
+            accesses: Vec::new(),
+        }
+    }
+
+    /// Records the table reference `name [alias]` at `token`, returning the
+    /// table unless it names a common table expression.
+    fn table(
+        &mut self,
+        name: String,
+        alias: Option<String>,
+        kind: EdgeKind,
+        token: usize,
+    ) -> Option<String> {
+        let table = (!self.ctes.contains(&name)).then_some(name);
+        if let Some(alias) = alias {
+            self.aliases.insert(alias, table.clone());
+        }
+        let table = table?;
+        if !self.tables.contains(&table) {
+            self.tables.push(table.clone());
+        }
+        self.accesses.push(Access {
+            object: table.clone(),
+            kind,
+            token,
+        });
+        Some(table)
+    }
+
+    fn column(&mut self, table: &str, column: &str, kind: EdgeKind, token: usize) {
+        self.accesses.push(Access {
+            object: format!("{table}.{column}"),
+            kind,
+            token,
+        });
+    }
+
+    /// The table a qualifier (`n` in `n.kind`) stands for.
+    fn resolve(&self, qualifier: &str) -> Option<String> {
+        match self.aliases.get(qualifier) {
+            Some(table) => table.clone(),
+            None => self.tables.iter().find(|t| *t == qualifier).cloned(),
+        }
+    }
+
+    fn is_table_or_alias(&self, name: &str) -> bool {
+        self.ctes.contains(name)
+            || self.aliases.contains_key(name)
+            || self.tables.iter().any(|t| t == name)
+    }
+}
+
+/// The state of extracting the SQL of one file.
+struct SqlPass<'a> {
+    file_path: &'a str,
+    result: &'a mut ExtractionResult,
+    file_id: Option<String>,
+    updated_at: u64,
+    functions: Callables,
+    /// IDs of the schema objects already emitted for this file, by kind and
+    /// name.
+    objects: HashMap<(&'static str, String), String>,
+    /// References already made, as (source, name, kind).
+    refs: HashSet<(String, String, &'static str)>,
+}
+
+impl<'a> SqlPass<'a> {
+    fn new(file_path: &'a str, result: &'a mut ExtractionResult) -> Self {
+        let file = result.nodes.iter().find(|n| n.kind == NodeKind::File);
+        let file_id = file.map(|n| n.id.clone());
+        let updated_at = file.map_or(0, |n| n.updated_at);
+        let functions = Callables::new(&result.nodes);
+        Self {
+            file_path,
+            result,
+            file_id,
+            updated_at,
+            functions,
+            objects: HashMap::new(),
+            refs: HashSet::new(),
+        }
+    }
+
+    /// Analyzes the string literal `raw` at `at` when it reads as SQL.
+    fn literal(&mut self, raw: &str, at: TsNode<'_>) {
+        let body = literal_text(raw);
+        let offset = raw.find(body).unwrap_or(0);
+        let sql = unescape(body);
+        let tokens = tokenize(&sql);
+        if !tokens
+            .split(|t| t.is_punct(';'))
+            .next()
+            .is_some_and(is_statement)
+        {
+            return;
+        }
+        let start = at.start_position();
+        let text = Text {
+            sql: &sql,
+            line: start.row as u32,
+            column: (start.column + offset) as u32,
+        };
+        self.statements(&text, &tokens);
+    }
+
+    fn statements(&mut self, text: &Text<'_>, tokens: &[Token<'_>]) {
+        for statement in tokens.split(|t| t.is_punct(';')) {
+            self.statement(text, statement);
+        }
+    }
+
+    fn statement(&mut self, text: &Text<'_>, tokens: &[Token<'_>]) {
+        let Some(first) = tokens.first() else {
+            return;
+        };
+        if first.is("create") {
+            self.create(text, tokens);
+        } else if first.is("alter") {
+            self.alter(text, tokens);
+        } else if first.is_any(&["select", "with", "insert", "replace", "update", "delete"]) {
+            let (line, _) = text.position(first.offset);
+            if let Some(from) = self
+                .functions
+                .enclosing(line)
+                .or_else(|| self.file_id.clone())
+            {
+                self.query(text, tokens, &from);
+            }
+        }
+    }
+
+    // ---- Schema ----
+
+    fn create(&mut self, text: &Text<'_>, tokens: &[Token<'_>]) {
+        let mut i = 1;
+        while tokens.get(i).is_some_and(|t| t.is_any(CREATE_MODIFIERS)) {
+            i += 1;
+        }
+        let Some(object) = tokens.get(i) else {
+            return;
+        };
+        if object.is("table") {
+            self.create_table(text, tokens, i + 1);
+        } else if object.is("view") {
+            self.create_view(text, tokens, i + 1);
+        } else if object.is("index") {
+            self.create_index(text, tokens, i + 1);
+        } else if object.is_any(&["function", "procedure", "trigger"]) {
+            self.routine(text, tokens);
+        }
+    }
+
+    /// `CREATE TABLE [IF NOT EXISTS] name (columns and constraints)`, or
+    /// `CREATE TABLE name AS SELECT ...`.
+    fn create_table(&mut self, text: &Text<'_>, tokens: &[Token<'_>], i: usize) {
+        let i = skip_if_exists(tokens, i);
+        let Some((table, i)) = qualified_name(tokens, i) else {
+            return;
+        };
+        let close = if tokens.get(i).is_some_and(|t| t.is_punct('(')) {
+            matching(tokens, i)
+        } else {
+            i
+        };
+        let span = &tokens[..(close + 1).min(tokens.len())];
+        let signature = text.signature(&tokens[..i]);
+        let id = self.object(NodeKind::SqlTable, &table, text, span, signature, None);
+        if close > i {
+            for element in elements(&tokens[i + 1..close]) {
+                self.table_element(text, &table, Some(&id), element);
+            }
+        } else if tokens.get(i).is_some_and(|t| t.is("as")) {
+            self.query(text, &tokens[i + 1..], &id);
+        }
+    }
+
+    /// A column definition or a table constraint of table `table`, whose
+    /// node is `table_id` when this file defines it.
+    fn table_element(
+        &mut self,
+        text: &Text<'_>,
+        table: &str,
+        table_id: Option<&str>,
+        element: &[Token<'_>],
+    ) {
+        let Some(head) = element.first() else {
+            return;
+        };
+        // MySQL's `KEY name (columns)`, unlike a column named `key`.
+        let keyed_index = head.is_any(&["key", "index", "fulltext"])
+            && match (element.get(1), element.get(2), element.get(3)) {
+                (Some(open), ..) if open.is_punct('(') => true,
+                (Some(_), Some(open), Some(column)) => {
+                    open.is_punct('(') && column.kind != TokenKind::Value
+                }
+                _ => false,
+            };
+        if head.is_any(TABLE_CONSTRAINTS) || keyed_index {
+            self.foreign_key(text, table, table_id, element);
+            return;
+        }
+        let Some(column) = head.any_name() else {
+            return;
+        };
+        let name = format!("{table}.{column}");
+        let signature = text.signature(element);
+        let id = self.object(
+            NodeKind::SqlColumn,
+            &name,
+            text,
+            element,
+            signature,
+            table_id,
+        );
+        if let Some(references) = element.iter().position(|t| t.is("references")) {
+            self.references(text, &id, &element[references + 1..], 0);
+        }
+    }
+
+    /// `[CONSTRAINT name] FOREIGN KEY (columns) REFERENCES table (columns)`:
+    /// each local column depends on the column it references.
+    fn foreign_key(
+        &mut self,
+        text: &Text<'_>,
+        table: &str,
+        table_id: Option<&str>,
+        element: &[Token<'_>],
+    ) {
+        let Some(foreign) = element.iter().position(|t| t.is("foreign")) else {
+            return;
+        };
+        let Some(open) = (foreign..element.len()).find(|&i| element[i].is_punct('(')) else {
+            return;
+        };
+        let close = matching(element, open);
+        let Some(references) = element.iter().position(|t| t.is("references")) else {
+            return;
+        };
+        let columns: Vec<String> = element[open + 1..close.min(element.len())]
+            .iter()
+            .filter_map(Token::any_name)
+            .collect();
+        for (position, column) in columns.iter().enumerate() {
+            let key = (NodeKind::SqlColumn.as_str(), format!("{table}.{column}"));
+            let from = self
+                .objects
+                .get(&key)
+                .cloned()
+                .or_else(|| table_id.map(str::to_string))
+                .or_else(|| self.file_id.clone());
+            if let Some(from) = from {
+                self.references(text, &from, &element[references + 1..], position);
+            }
+        }
+    }
+
+    /// `REFERENCES table [(columns)]`, starting after `REFERENCES`: `from`
+    /// depends on the `position`th referenced column, or on the table when
+    /// no columns are listed.
+    fn references(&mut self, text: &Text<'_>, from: &str, tokens: &[Token<'_>], position: usize) {
+        let Some((table, i)) = qualified_name(tokens, 0) else {
+            return;
+        };
+        let column = if tokens.get(i).is_some_and(|t| t.is_punct('(')) {
+            let close = matching(tokens, i);
+            tokens[i + 1..close.min(tokens.len())]
+                .iter()
+                .filter_map(Token::any_name)
+                .nth(position)
+        } else {
+            None
+        };
+        let object = match column {
+            Some(column) => format!("{table}.{column}"),
+            None => table,
+        };
+        let at = text.position(tokens[0].offset);
+        self.reference(from, &object, EdgeKind::DependsOn, at);
+    }
+
+    /// `CREATE VIEW [IF NOT EXISTS] name [(columns)] AS SELECT ...`.
+    fn create_view(&mut self, text: &Text<'_>, tokens: &[Token<'_>], i: usize) {
+        let i = skip_if_exists(tokens, i);
+        let Some((view, i)) = qualified_name(tokens, i) else {
+            return;
+        };
+        let signature = text.signature(&tokens[..i]);
+        let id = self.object(NodeKind::SqlView, &view, text, tokens, signature, None);
+        if let Some(as_) = (i..tokens.len()).find(|&j| tokens[j].is("as")) {
+            self.query(text, &tokens[as_ + 1..], &id);
+        }
+    }
+
+    /// `CREATE [UNIQUE] INDEX [IF NOT EXISTS] name ON table (columns)`: the
+    /// index uses its table and the columns it lists by name.
+    fn create_index(&mut self, text: &Text<'_>, tokens: &[Token<'_>], i: usize) {
+        let i = skip_if_exists(tokens, i);
+        let Some((index, i)) = qualified_name(tokens, i) else {
+            return;
+        };
+        if !tokens.get(i).is_some_and(|t| t.is("on")) {
+            return;
+        }
+        let Some((table, mut i)) = qualified_name(tokens, i + 1) else {
+            return;
+        };
+        let signature = text.signature(tokens);
+        let id = self.object(NodeKind::SqlIndex, &index, text, tokens, signature, None);
+        self.reference(
+            &id,
+            &table,
+            EdgeKind::Uses,
+            text.position(tokens[i - 1].offset),
+        );
+        if tokens.get(i).is_some_and(|t| t.is("using")) {
+            i += 2;
+        }
+        if !tokens.get(i).is_some_and(|t| t.is_punct('(')) {
+            return;
+        }
+        let close = matching(tokens, i);
+        for element in elements(&tokens[i + 1..close.min(tokens.len())]) {
+            let plain = element.len() == 1
+                || element
+                    .get(1)
+                    .is_some_and(|t| t.is_any(&["asc", "desc", "collate"]));
+            if let Some(column) = element.first().and_then(Token::any_name).filter(|_| plain) {
+                let at = text.position(element[0].offset);
+                self.reference(&id, &format!("{table}.{column}"), EdgeKind::Uses, at);
+            }
+        }
+    }
+
+    /// `ALTER TABLE name ADD [COLUMN] definition` and `ALTER TABLE name ADD
+    /// [CONSTRAINT name] FOREIGN KEY ...`. Other alterations are ignored.
+    fn alter(&mut self, text: &Text<'_>, tokens: &[Token<'_>]) {
+        if !tokens.get(1).is_some_and(|t| t.is("table")) {
+            return;
+        }
+        let mut i = skip_if_exists(tokens, 2);
+        if tokens.get(i).is_some_and(|t| t.is("only")) {
+            i += 1;
+        }
+        let Some((table, i)) = qualified_name(tokens, i) else {
+            return;
+        };
+        let table_id = self
+            .objects
+            .get(&(NodeKind::SqlTable.as_str(), table.clone()))
+            .cloned();
+        for action in elements(&tokens[i..]) {
+            if !action.first().is_some_and(|t| t.is("add")) {
+                continue;
+            }
+            let mut definition = &action[1..];
+            if definition.first().is_some_and(|t| t.is("column")) {
+                definition = &definition[skip_if_exists(definition, 1)..];
+            }
+            self.table_element(text, &table, table_id.as_deref(), definition);
+        }
+    }
+
+    /// The body of a function, procedure or trigger: a dollar-quoted body,
+    /// or the statements after its `BEGIN` (or `AS`). Its statements after
+    /// the first are split off by the caller.
+    fn routine(&mut self, text: &Text<'_>, tokens: &[Token<'_>]) {
+        for body in tokens.iter().filter(|t| t.kind == TokenKind::Body) {
+            let (line, column) = text.position(body.offset);
+            let inner = Text {
+                sql: body.text,
+                line,
+                column,
+            };
+            self.statements(&inner, &tokenize(body.text));
+        }
+        let start = tokens
+            .iter()
+            .position(|t| t.is("begin"))
+            .or_else(|| tokens.iter().position(|t| t.is("as")));
+        if let Some(start) = start {
+            self.statement(text, &tokens[start + 1..]);
+        }
+    }
+
+    /// Emits the schema object `name` spanning `span`, contained by
+    /// `container` or the file, unless this file already defines it.
+    fn object(
+        &mut self,
+        kind: NodeKind,
+        name: &str,
+        text: &Text<'_>,
+        span: &[Token<'_>],
+        signature: Option<String>,
+        container: Option<&str>,
+    ) -> String {
+        let key = (kind.as_str(), name.to_string());
+        if let Some(id) = self.objects.get(&key) {
+            return id.clone();
+        }
+        let id = generate_node_id(self.file_path, &kind, name, 0);
+        let (start_line, start_column) = span.first().map_or((0, 0), |t| text.position(t.offset));
+        let (end_line, end_column) = span
+            .last()
+            .map_or((0, 0), |t| text.position(t.offset + t.text.len()));
+        self.result.nodes.push(Node {
+            id: id.clone(),
+            kind,
+            name: name.to_string(),
+            qualified_name: name.to_string(),
+            file_path: self.file_path.to_string(),
+            start_line,
+            end_line,
+            start_column,
+            end_column,
+            signature,
+            docstring: None,
+            visibility: Visibility::Pub,
+            is_async: false,
+            branches: 0,
+            loops: 0,
+            returns: 0,
+            max_nesting: 0,
+            unsafe_blocks: 0,
+            unchecked_calls: 0,
+            assertions: 0,
+            updated_at: self.updated_at,
+        });
+        self.objects.insert(key, id.clone());
+        if let Some(container) = container
+            .map(str::to_string)
+            .or_else(|| self.file_id.clone())
+        {
+            self.result.edges.push(Edge {
+                source: container,
+                target: id.clone(),
+                kind: EdgeKind::Contains,
+                line: Some(start_line),
+                provenance: None,
+            });
+        }
+        id
+    }
+
+    // ---- Queries ----
+
+    /// Makes references from `from` to the tables and columns the query in
+    /// `tokens` reads and writes.
+    fn query(&mut self, text: &Text<'_>, tokens: &[Token<'_>], from: &str) {
+        let mut scope = Scope::new(cte_names(tokens));
+        // Tokens naming tables, aliases and written columns.
+        let mut consumed = vec![false; tokens.len()];
+        // The table inserted into, updated or deleted from.
+        let mut target: Option<String> = None;
+
+        let mut i = 0;
+        while i < tokens.len() {
+            let token = &tokens[i];
+            let previous = i.checked_sub(1).map(|p| &tokens[p]);
+            if token.is_any(&["from", "join"]) {
+                let kind = if token.is("from") && previous.is_some_and(|p| p.is("delete")) {
+                    EdgeKind::Writes
+                } else {
+                    EdgeKind::Uses
+                };
+                let mut j = i + 1;
+                while let Some((name, alias, next)) = table_ref(tokens, j) {
+                    if tokens.get(next).is_some_and(|t| t.is_punct('(')) {
+                        // A table-valued function (`json_each(...)`).
+                        break;
+                    }
+                    consumed[j..next].iter_mut().for_each(|c| *c = true);
+                    let table = scope.table(name, alias, kind.clone(), j);
+                    if kind == EdgeKind::Writes && target.is_none() {
+                        target = table;
+                    }
+                    j = next;
+                    if !tokens.get(j).is_some_and(|t| t.is_punct(',')) {
+                        break;
+                    }
+                    j += 1;
+                }
+                i = j.max(i + 1);
+                continue;
+            }
+            let inserts = token.is("into")
+                && (previous.is_some_and(|p| p.is_any(&["insert", "replace"]))
+                    || i >= 2 && tokens[i - 2].is("or"));
+            let updates = token.is("update")
+                && !previous.is_some_and(|p| p.is_any(&["do", "on", "for", "of"]));
+            if inserts || updates {
+                let mut j = i + 1;
+                if updates && tokens.get(j).is_some_and(|t| t.is("or")) {
+                    j += 2;
+                }
+                if let Some((name, alias, next)) = table_ref(tokens, j) {
+                    consumed[j..next].iter_mut().for_each(|c| *c = true);
+                    let table = scope.table(name, alias, EdgeKind::Writes, j);
+                    i = next;
+                    if inserts && tokens.get(next).is_some_and(|t| t.is_punct('(')) {
+                        let close = matching(tokens, next);
+                        for k in next + 1..close {
+                            if let (Some(table), Some(column)) = (&table, tokens[k].name()) {
+                                consumed[k] = true;
+                                scope.column(table, &column, EdgeKind::Writes, k);
+                            }
+                        }
+                        i = close;
+                    }
+                    if target.is_none() {
+                        target = table;
+                    }
+                    continue;
+                }
+            }
+            if token.is("set") {
+                for k in assignments(tokens, i) {
+                    consumed[k] = true;
+                    if let (Some(table), Some(column)) = (&target, tokens[k].name()) {
+                        scope.column(table, &column, EdgeKind::Writes, k);
+                    }
+                }
+            }
+            i += 1;
+        }
+        if scope.tables.is_empty() {
+            return;
+        }
+
+        // Columns qualified by an alias or a table in scope, and unqualified
+        // ones when the statement names a single table.
+        let single = (scope.tables.len() == 1).then(|| scope.tables[0].clone());
+        let mut k = 0;
+        while k < tokens.len() {
+            let after_dot = k > 0 && tokens[k - 1].is_punct('.');
+            let Some(name) = tokens[k].name().filter(|_| !consumed[k] && !after_dot) else {
+                k += 1;
+                continue;
+            };
+            let called = |at: usize| tokens.get(at).is_some_and(|t| t.is_punct('('));
+            if tokens.get(k + 1).is_some_and(|t| t.is_punct('.')) {
+                let column = tokens.get(k + 2).and_then(Token::any_name);
+                if let (Some(table), Some(column), false) =
+                    (scope.resolve(&name), column, called(k + 3))
+                {
+                    scope.column(&table, &column, EdgeKind::Uses, k + 2);
+                }
+                k += 3;
+                continue;
+            }
+            // A name right after another name, a value or a parenthesis is
+            // an output alias (`count(*) total`), as is one after `AS`.
+            let aliased = k > 0
+                && (tokens[k - 1].is("as")
+                    || tokens[k - 1].name().is_some()
+                    || tokens[k - 1].kind == TokenKind::Value
+                    || tokens[k - 1].is_punct(')'));
+            if let Some(table) = &single {
+                if !called(k + 1) && !aliased && !scope.is_table_or_alias(&name) {
+                    scope.column(table, &name, EdgeKind::Uses, k);
+                }
+            }
+            k += 1;
+        }
+
+        for access in scope.accesses {
+            let at = text.position(tokens[access.token].offset);
+            self.reference(from, &access.object, access.kind, at);
+        }
+    }
+
+    fn reference(&mut self, from: &str, object: &str, kind: EdgeKind, (line, column): (u32, u32)) {
+        if !self
+            .refs
+            .insert((from.to_string(), object.to_string(), kind.as_str()))
+        {
+            return;
+        }
+        self.result.unresolved_refs.push(UnresolvedRef {
+            from_node_id: from.to_string(),
+            reference_name: object.to_string(),
+            reference_kind: kind,
+            line,
+            column,
+            file_path: self.file_path.to_string(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: true,
+        });
+    }
+}
@@ -47,6 +47,12 @@ impl ExtractionState {
 
 impl SqlExtractor {
     pub fn extract_sql(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -54,7 +60,7 @@ impl SqlExtractor {
             Ok(t) => t,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -86,7 +92,9 @@ impl SqlExtractor {
         let root = tree.root_node();
         Self::visit_children(&mut state, root);
 
-        Self::build_result(state, start)
+        let mut result = Self::build_result(state, start);
+        super::sql::extract_sql_file(file_path, source, &mut result);
+        (result, Some(tree))
     }
 
     fn parse_source(source: &str) -> Result<Tree, String> {
@@ -114,8 +122,6 @@ impl SqlExtractor {
 
     fn visit_node(state: &mut ExtractionState, node: TsNode<'_>) {
         match node.kind() {
-            "create_table" => Self::emit_named(state, node, NodeKind::Class),
-            "create_view" => Self::emit_named(state, node, NodeKind::Class),
             "create_function" => Self::emit_named(state, node, NodeKind::Function),
             "create_procedure" => Self::emit_named(state, node, NodeKind::Function),
             _ => Self::visit_children(state, node),
@@ -164,6 +170,7 @@ impl SqlExtractor {
             target: id,
             kind: EdgeKind::Contains,
             line: Some(start_line),
+            provenance: None,
         });
     }
 
@@ -192,6 +199,7 @@ impl SqlExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: Vec::new(),
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -210,4 +218,8 @@ impl crate::extraction::LanguageExtractor for SqlExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         Self::extract_sql(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
 }
@@ -6,8 +6,11 @@ use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tree_sitter::{Node as TsNode, Parser, Tree};
 
 use crate::extraction::complexity::{count_complexity, SWIFT_COMPLEXITY};
+use crate::extraction::signatures::{param, signature};
+use crate::extraction::type_refs::{push_type_refs, SWIFT_TYPE_REFS};
 use crate::types::{
-    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, UnresolvedRef, Visibility,
+    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, Param, Signature,
+    UnresolvedRef, Visibility,
 };
 
 /// Extracts code graph nodes and edges from Swift source files using tree-sitter.
@@ -18,6 +21,7 @@ struct ExtractionState {
     nodes: Vec<Node>,
     edges: Vec<Edge>,
     unresolved_refs: Vec<UnresolvedRef>,
+    signatures: Vec<Signature>,
     errors: Vec<String>,
     /// Stack of (name, `node_id`) for building qualified names and parent edges.
     node_stack: Vec<(String, String)>,
@@ -38,6 +42,7 @@ impl ExtractionState {
             nodes: Vec::new(),
             edges: Vec::new(),
             unresolved_refs: Vec::new(),
+            signatures: Vec::new(),
             errors: Vec::new(),
             node_stack: Vec::new(),
             file_path: file_path.to_string(),
@@ -75,6 +80,12 @@ impl SwiftExtractor {
     /// `file_path` is used for qualified names and node IDs (not for I/O).
     /// `source` is the Swift source code to parse.
     pub fn extract_swift(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -82,7 +93,7 @@ impl SwiftExtractor {
             Ok(tree) => tree,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -120,7 +131,7 @@ impl SwiftExtractor {
 
         state.node_stack.pop();
 
-        Self::build_result(state, start)
+        (Self::build_result(state, start), Some(tree))
     }
 
     /// Parse source code into a tree-sitter AST.
@@ -220,6 +231,7 @@ impl SwiftExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -308,6 +320,7 @@ impl SwiftExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -371,6 +384,7 @@ impl SwiftExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -431,6 +445,7 @@ impl SwiftExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -520,6 +535,7 @@ impl SwiftExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -577,6 +593,7 @@ impl SwiftExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -641,6 +658,7 @@ impl SwiftExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -724,11 +742,15 @@ impl SwiftExtractor {
         if let Some(parent_id) = state.parent_node_id() {
             state.edges.push(Edge {
                 source: parent_id.to_string(),
-                target: id,
+                target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
+
+        Self::extract_type_refs(state, node, &id);
+        Self::push_signature(state, node, &id);
     }
 
     // ----------------------------------
@@ -797,9 +819,14 @@ impl SwiftExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
+        // Types named by the parameters and the return type.
+        Self::extract_type_refs(state, node, &id);
+        Self::push_signature(state, node, &id);
+
         // Extract call sites from the function body.
         Self::extract_call_sites(state, node, &id);
 
@@ -857,9 +884,14 @@ impl SwiftExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
+        // Types named by the parameters.
+        Self::extract_type_refs(state, node, &id);
+        Self::push_signature(state, node, &id);
+
         // Extract call sites from the init body.
         Self::extract_call_sites(state, node, &id);
 
@@ -923,9 +955,22 @@ impl SwiftExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
+        if let Some(annotation) = Self::find_child_by_kind(node, "type_annotation") {
+            push_type_refs(
+                &mut state.unresolved_refs,
+                annotation,
+                &state.source,
+                &SWIFT_TYPE_REFS,
+                &id,
+                &EdgeKind::TypeOf,
+                &state.file_path,
+            );
+        }
+
         // Extract attribute annotations.
         Self::extract_annotations_from_modifiers(state, node, &id);
     }
@@ -983,6 +1028,7 @@ impl SwiftExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -1037,6 +1083,10 @@ impl SwiftExtractor {
                             line,
                             column,
                             file_path: state.file_path.clone(),
+                            receiver_type: None,
+                            abi: None,
+                            http_method: None,
+                            sql: false,
                         });
                     }
                 }
@@ -1175,6 +1225,10 @@ impl SwiftExtractor {
                                 line: child.start_position().row as u32,
                                 column: child.start_position().column as u32,
                                 file_path: state.file_path.clone(),
+                                receiver_type: None,
+                                abi: None,
+                                http_method: None,
+                                sql: false,
                             });
                         }
                         // Recurse into the call for nested calls.
@@ -1231,6 +1285,89 @@ impl SwiftExtractor {
         }
     }
 
+    /// Extract type references from parameter types and return type.
+    ///
+    /// Types named by the `parameter` children become `Receives` refs and the
+    /// `return_type` field (absent on initializers) becomes `Returns` refs.
+    fn extract_type_refs(state: &mut ExtractionState, node: TsNode<'_>, fn_node_id: &str) {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            if child.kind() == "parameter" {
+                push_type_refs(
+                    &mut state.unresolved_refs,
+                    child,
+                    &state.source,
+                    &SWIFT_TYPE_REFS,
+                    fn_node_id,
+                    &EdgeKind::Receives,
+                    &state.file_path,
+                );
+            }
+        }
+        if let Some(return_type) = node.child_by_field_name("return_type") {
+            push_type_refs(
+                &mut state.unresolved_refs,
+                return_type,
+                &state.source,
+                &SWIFT_TYPE_REFS,
+                fn_node_id,
+                &EdgeKind::Returns,
+                &state.file_path,
+            );
+        }
+    }
+
+    /// Records the declared parameters and return type of a function or
+    /// initializer. Default values follow their `parameter` as siblings; a
+    /// default or the `...` of a variadic parameter makes it optional.
+    fn push_signature(state: &mut ExtractionState, node: TsNode<'_>, fn_node_id: &str) {
+        let mut params: Vec<Param> = Vec::new();
+        let mut cursor = node.walk();
+        if cursor.goto_first_child() {
+            loop {
+                let child = cursor.node();
+                if child.kind() == "parameter" {
+                    let name = child
+                        .child_by_field_name("name")
+                        .map(|n| state.node_text(n))
+                        .unwrap_or_default();
+                    let mut inner = child.walk();
+                    let parts: Vec<TsNode<'_>> = child.children(&mut inner).collect();
+                    let variadic = parts.iter().any(|c| c.kind() == "...");
+                    let ty = parts
+                        .iter()
+                        .rfind(|c| {
+                            c.is_named()
+                                && !matches!(c.kind(), "simple_identifier" | "parameter_modifiers")
+                        })
+                        .map(|t| {
+                            let text = state.node_text(*t);
+                            if variadic {
+                                format!("{text}...")
+                            } else {
+                                text
+                            }
+                        });
+                    params.push(param(&name, ty.as_deref(), None, variadic));
+                } else if cursor.field_name() == Some("default_value") {
+                    if let Some(last) = params.last_mut() {
+                        last.default_value = Some(state.node_text(child));
+                        last.optional = true;
+                    }
+                }
+                if !cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+        }
+        let return_type = node
+            .child_by_field_name("return_type")
+            .map(|t| state.node_text(t));
+        state
+            .signatures
+            .extend(signature(fn_node_id, params, return_type.as_deref()));
+    }
+
     /// Find the first child of a node with a given kind.
     fn find_child_by_kind<'a>(node: TsNode<'a>, kind: &str) -> Option<TsNode<'a>> {
         let mut cursor = node.walk();
@@ -1344,6 +1481,10 @@ impl SwiftExtractor {
             line: start_line,
             column: start_column,
             file_path: state.file_path.clone(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
         });
 
         // Direct Annotates edge from the annotation to the target.
@@ -1352,6 +1493,7 @@ impl SwiftExtractor {
             target: target_id.to_string(),
             kind: EdgeKind::Annotates,
             line: Some(start_line),
+            provenance: None,
         });
     }
 
@@ -1384,6 +1526,7 @@ impl SwiftExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: state.signatures,
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -1402,4 +1545,12 @@ impl crate::extraction::LanguageExtractor for SwiftExtractor {
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
         Self::extract_swift(file_path, source)
     }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        Self::parse_and_extract(file_path, source)
+    }
+
+    fn extracts_signatures(&self) -> bool {
+        true
+    }
 }
@@ -0,0 +1,373 @@
+//! Generic extractor for languages declared in the project config.
+//!
+//! A `languages` entry in `.tokensave/config.json` names a tree-sitter
+//! grammar (a compiled shared library, or a bundled grammar key) and a
+//! `tags.scm`-style query, the format tree-sitter grammars ship for code
+//! navigation. Each match of the query tags one node with a
+//! `@definition.<kind>` or `@reference.<kind>` capture and its name with
+//! `@name`; an optional `@doc` capture becomes the docstring.
+//!
+//! Definitions become nodes nested under the innermost enclosing definition;
+//! references become unresolved refs from it.
+
+use std::path::Path;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use tree_sitter::{Language, Parser, Query, QueryCursor, StreamingIterator};
+
+use crate::config::TagsLanguageConfig;
+use crate::errors::{Result, TokenSaveError};
+use crate::extraction::{intern_extensions, LanguageExtractor};
+use crate::types::{
+    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, UnresolvedRef, Visibility,
+};
+
+/// A [`LanguageExtractor`] driven by a tags query over any tree-sitter
+/// grammar.
+pub struct TagsExtractor {
+    name: String,
+    extensions: Vec<&'static str>,
+    language: Language,
+    query: Query,
+    /// Keeps a loaded grammar library mapped for as long as `language` lives.
+    _library: Option<libloading::Library>,
+}
+
+/// One tagged definition or reference, copied out of a query match.
+struct Tag {
+    role: Role,
+    name: String,
+    doc: Option<String>,
+    start_byte: usize,
+    end_byte: usize,
+    start_line: u32,
+    end_line: u32,
+    start_column: u32,
+    end_column: u32,
+    signature: Option<String>,
+}
+
+enum Role {
+    Definition(NodeKind),
+    Reference(EdgeKind),
+}
+
+/// Maps the `<kind>` of a `@definition.<kind>` capture to a node kind, or
+/// `None` for kinds the graph has no equivalent for.
+fn definition_kind(kind: &str) -> Option<NodeKind> {
+    Some(match kind {
+        "function" => NodeKind::Function,
+        "method" => NodeKind::Method,
+        "class" => NodeKind::Class,
+        "interface" => NodeKind::Interface,
+        "module" => NodeKind::Module,
+        "constant" => NodeKind::Const,
+        "macro" => NodeKind::Macro,
+        "type" => NodeKind::TypeAlias,
+        "struct" => NodeKind::Struct,
+        "enum" => NodeKind::Enum,
+        "field" | "property" => NodeKind::Field,
+        _ => return None,
+    })
+}
+
+/// Maps the `<kind>` of a `@reference.<kind>` capture to an edge kind.
+fn reference_kind(kind: &str) -> EdgeKind {
+    match kind {
+        "call" | "send" => EdgeKind::Calls,
+        "implementation" => EdgeKind::Implements,
+        _ => EdgeKind::Uses,
+    }
+}
+
+impl TagsExtractor {
+    /// Creates an extractor for `language` from the text of a tags query.
+    pub fn new(
+        name: &str,
+        extensions: &[String],
+        language: Language,
+        query_source: &str,
+    ) -> Result<Self> {
+        Self::build(name, extensions, language, query_source, None)
+    }
+
+    fn build(
+        name: &str,
+        extensions: &[String],
+        language: Language,
+        query_source: &str,
+        library: Option<libloading::Library>,
+    ) -> Result<Self> {
+        let query = Query::new(&language, query_source).map_err(|e| TokenSaveError::Config {
+            message: format!("invalid tags query for language '{name}': {e}"),
+        })?;
+        if !query
+            .capture_names()
+            .iter()
+            .any(|c| c.starts_with("definition.") || c.starts_with("reference."))
+        {
+            return Err(TokenSaveError::Config {
+                message: format!(
+                    "tags query for language '{name}' has no @definition.* or @reference.* captures"
+                ),
+            });
+        }
+        Ok(Self {
+            name: name.to_string(),
+            extensions: intern_extensions(extensions.iter().map(String::as_str)),
+            language,
+            query,
+            _library: library,
+        })
+    }
+
+    /// Creates the extractor a `languages` config entry describes, loading
+    /// its grammar library and tags query relative to `project_root`.
+    ///
+    /// Loading a grammar library runs its initialisers; grammars are trusted
+    /// the way the project's own build scripts are.
+    pub fn from_config(project_root: &Path, config: &TagsLanguageConfig) -> Result<Self> {
+        let tags_path = project_root.join(&config.tags);
+        let query_source =
+            std::fs::read_to_string(&tags_path).map_err(|e| TokenSaveError::File {
+                message: format!("cannot read tags query for '{}': {e}", config.name),
+                path: tags_path.display().to_string(),
+            })?;
+
+        if let Some(key) = &config.grammar {
+            let language = crate::extraction::ts_provider::find_language(key).ok_or_else(|| {
+                TokenSaveError::Config {
+                    message: format!(
+                        "language '{}' names unknown bundled grammar '{key}'",
+                        config.name
+                    ),
+                }
+            })?;
+            return Self::new(&config.name, &config.extensions, language, &query_source);
+        }
+
+        let Some(library) = &config.library else {
+            return Err(TokenSaveError::Config {
+                message: format!(
+                    "language '{}' needs either a grammar library or a bundled grammar",
+                    config.name
+                ),
+            });
+        };
+        let library_path = project_root.join(library);
+        let symbol = config
+            .symbol
+            .clone()
+            .unwrap_or_else(|| format!("tree_sitter_{}", config.name.replace('-', "_")));
+        let load_error = |e: libloading::Error| TokenSaveError::File {
+            message: format!("cannot load grammar for '{}': {e}", config.name),
+            path: library_path.display().to_string(),
+        };
+        // SAFETY: loading a grammar runs its initialisers; the library is
+        // named by the project's own configuration.
+        let library = unsafe { libloading::Library::new(&library_path) }.map_err(load_error)?;
+        // SAFETY: tree-sitter grammars export their language function with
+        // this signature; the library stays loaded inside the extractor.
+        let language = unsafe {
+            let language_fn = *library
+                .get::<unsafe extern "C" fn() -> *const ()>(symbol.as_bytes())
+                .map_err(load_error)?;
+            Language::new(tree_sitter_language::LanguageFn::from_raw(language_fn))
+        };
+        Self::build(
+            &config.name,
+            &config.extensions,
+            language,
+            &query_source,
+            Some(library),
+        )
+    }
+
+    /// Runs the query over `source` and returns its tags in source order,
+    /// outer definitions before the ones they enclose.
+    fn collect_tags(&self, source: &str) -> std::result::Result<Vec<Tag>, String> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&self.language)
+            .map_err(|e| format!("failed to load {} grammar: {e}", self.name))?;
+        let tree = parser
+            .parse(source, None)
+            .ok_or_else(|| "tree-sitter parse returned None".to_string())?;
+
+        let names = self.query.capture_names();
+        let mut tags = Vec::new();
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(&self.query, tree.root_node(), source.as_bytes());
+        while let Some(found) = matches.next() {
+            let mut role = None;
+            let mut name = None;
+            let mut doc = None;
+            for capture in found.captures {
+                let capture_name = names[capture.index as usize];
+                let text = || capture.node.utf8_text(source.as_bytes()).unwrap_or("");
+                if capture_name == "name" {
+                    name = Some(text().to_string());
+                } else if capture_name == "doc" {
+                    doc = Some(text().trim().to_string());
+                } else if let Some(kind) = capture_name.strip_prefix("definition.") {
+                    role = definition_kind(kind).map(|k| (Role::Definition(k), capture.node));
+                } else if let Some(kind) = capture_name.strip_prefix("reference.") {
+                    role = Some((Role::Reference(reference_kind(kind)), capture.node));
+                }
+            }
+            let (Some((role, node)), Some(name)) = (role, name) else {
+                continue;
+            };
+            if name.is_empty() {
+                continue;
+            }
+            let signature = node
+                .utf8_text(source.as_bytes())
+                .ok()
+                .and_then(|text| text.lines().next())
+                .map(|line| line.trim().to_string());
+            tags.push(Tag {
+                role,
+                name,
+                doc: doc.filter(|d| !d.is_empty()),
+                start_byte: node.start_byte(),
+                end_byte: node.end_byte(),
+                start_line: node.start_position().row as u32,
+                end_line: node.end_position().row as u32,
+                start_column: node.start_position().column as u32,
+                end_column: node.end_position().column as u32,
+                signature,
+            });
+        }
+        tags.sort_by_key(|t| (t.start_byte, std::cmp::Reverse(t.end_byte)));
+        Ok(tags)
+    }
+}
+
+impl LanguageExtractor for TagsExtractor {
+    fn extensions(&self) -> &[&str] {
+        &self.extensions
+    }
+
+    fn language_name(&self) -> &str {
+        &self.name
+    }
+
+    fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
+        let start = Instant::now();
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let file_node_id = generate_node_id(file_path, &NodeKind::File, file_path, 0);
+        let node = |id: String,
+                    kind: NodeKind,
+                    name: String,
+                    qualified_name: String,
+                    tag: Option<&Tag>| Node {
+            id,
+            kind,
+            name,
+            qualified_name,
+            file_path: file_path.to_string(),
+            start_line: tag.map_or(0, |t| t.start_line),
+            end_line: tag.map_or(source.lines().count().saturating_sub(1) as u32, |t| {
+                t.end_line
+            }),
+            start_column: tag.map_or(0, |t| t.start_column),
+            end_column: tag.map_or(0, |t| t.end_column),
+            signature: tag.and_then(|t| t.signature.clone()),
+            docstring: tag.and_then(|t| t.doc.clone()),
+            visibility: Visibility::Pub,
+            is_async: false,
+            branches: 0,
+            loops: 0,
+            returns: 0,
+            max_nesting: 0,
+            unsafe_blocks: 0,
+            unchecked_calls: 0,
+            assertions: 0,
+            updated_at: timestamp,
+        };
+
+        let mut nodes = vec![node(
+            file_node_id.clone(),
+            NodeKind::File,
+            file_path.to_string(),
+            file_path.to_string(),
+            None,
+        )];
+        let mut edges = Vec::new();
+        let mut unresolved_refs = Vec::new();
+        let mut errors = Vec::new();
+
+        let tags = match self.collect_tags(source) {
+            Ok(tags) => tags,
+            Err(e) => {
+                errors.push(e);
+                Vec::new()
+            }
+        };
+
+        // Definitions enclosing the current tag, outermost first, as
+        // (end byte, node id, qualified name).
+        let mut scopes: Vec<(usize, String, String)> = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        for tag in &tags {
+            while scopes.last().is_some_and(|s| s.0 < tag.end_byte) {
+                scopes.pop();
+            }
+            let (parent_id, parent_name) = scopes
+                .last()
+                .map_or((file_node_id.as_str(), file_path), |s| {
+                    (s.1.as_str(), s.2.as_str())
+                });
+            match &tag.role {
+                Role::Definition(kind) => {
+                    // Several patterns may tag the same definition.
+                    if !seen.insert((tag.start_byte, tag.end_byte, tag.name.clone())) {
+                        continue;
+                    }
+                    let qualified_name = format!("{parent_name}::{}", tag.name);
+                    let id = generate_node_id(file_path, kind, &tag.name, tag.start_line);
+                    edges.push(Edge {
+                        source: parent_id.to_string(),
+                        target: id.clone(),
+                        kind: EdgeKind::Contains,
+                        line: Some(tag.start_line),
+                        provenance: None,
+                    });
+                    nodes.push(node(
+                        id.clone(),
+                        kind.clone(),
+                        tag.name.clone(),
+                        qualified_name.clone(),
+                        Some(tag),
+                    ));
+                    scopes.push((tag.end_byte, id, qualified_name));
+                }
+                Role::Reference(kind) => unresolved_refs.push(UnresolvedRef {
+                    from_node_id: parent_id.to_string(),
+                    reference_name: tag.name.clone(),
+                    reference_kind: kind.clone(),
+                    line: tag.start_line,
+                    column: tag.start_column,
+                    file_path: file_path.to_string(),
+                    receiver_type: None,
+                    abi: None,
+                    http_method: None,
+                    sql: false,
+                }),
+            }
+        }
+
+        ExtractionResult {
+            nodes,
+            edges,
+            unresolved_refs,
+            signatures: Vec::new(),
+            errors,
+            duration_ms: start.elapsed().as_millis() as u64,
+        }
+    }
+}
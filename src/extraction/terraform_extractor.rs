@@ -0,0 +1,570 @@
+//! Extractor for Terraform (HCL) configurations.
+//!
+//! Top-level `resource`, `data`, `module`, `variable` and `output` blocks and
+//! the entries of `locals` blocks become nodes named by the address that
+//! expressions use to reference them: `aws_s3_bucket.logs`,
+//! `data.aws_ami.ubuntu`, `module.vpc`, `var.region`, `output.bucket_arn`
+//! and `local.tags`.
+//!
+//! The references in a block's expressions, string templates and heredocs
+//! become unresolved refs to those addresses: `Uses`, or `DependsOn` for the
+//! entries of `depends_on`. References from `provider` and `terraform`
+//! blocks count as the file's. A Terraform module is a directory, so the
+//! resolver binds them within the referencing file's directory.
+
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::extraction::LanguageExtractor;
+use crate::types::{
+    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, UnresolvedRef, Visibility,
+};
+
+/// Extracts blocks and their references from Terraform files.
+pub struct TerraformExtractor;
+
+impl LanguageExtractor for TerraformExtractor {
+    fn extensions(&self) -> &[&str] {
+        &["tf", "tofu"]
+    }
+
+    fn language_name(&self) -> &'static str {
+        "Terraform"
+    }
+
+    fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
+        let start = Instant::now();
+        let mut emitter = Emitter::new(file_path, source);
+        let mut errors = Vec::new();
+        let mut parser = Parser {
+            source,
+            bytes: source.as_bytes(),
+            pos: 0,
+        };
+        match parser.body(true) {
+            Ok(body) => {
+                for block in &body.blocks {
+                    emitter.emit_block(block);
+                }
+            }
+            Err(e) => {
+                let (line, _) = emitter.position(parser.pos);
+                errors.push(format!("{file_path}:{}: {e}", line + 1));
+            }
+        }
+        ExtractionResult {
+            nodes: emitter.nodes,
+            edges: emitter.edges,
+            unresolved_refs: emitter.unresolved_refs,
+            signatures: Vec::new(),
+            errors,
+            duration_ms: start.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+/// The attributes and nested blocks of a block, or of a whole file.
+#[derive(Default)]
+struct Body<'a> {
+    attributes: Vec<Attribute<'a>>,
+    blocks: Vec<Block<'a>>,
+}
+
+struct Block<'a> {
+    kind: &'a str,
+    labels: Vec<&'a str>,
+    start: usize,
+    end: usize,
+    body: Body<'a>,
+}
+
+struct Attribute<'a> {
+    name: &'a str,
+    start: usize,
+    end: usize,
+    /// Addresses the value references, with their byte offsets.
+    refs: Vec<(String, usize)>,
+    /// The value when it is a single quoted string.
+    string: Option<&'a str>,
+}
+
+/// A scanner over HCL native syntax. It keeps the structure of bodies and
+/// only looks into expressions for the references they make.
+struct Parser<'a> {
+    source: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<u8> {
+        self.bytes.get(self.pos + offset).copied()
+    }
+
+    fn at(&self, token: &str) -> bool {
+        self.bytes[self.pos..].starts_with(token.as_bytes())
+    }
+
+    fn skip_line(&mut self) {
+        while self.peek().is_some_and(|c| c != b'\n') {
+            self.pos += 1;
+        }
+    }
+
+    /// Skips blanks and comments, and line breaks when `newlines` is set.
+    fn skip_trivia(&mut self, newlines: bool) {
+        loop {
+            match self.peek() {
+                Some(b' ' | b'\t' | b'\r') => self.pos += 1,
+                Some(b'\n') if newlines => self.pos += 1,
+                Some(b'#') => self.skip_line(),
+                Some(b'/') if self.peek_at(1) == Some(b'/') => self.skip_line(),
+                Some(b'/') if self.peek_at(1) == Some(b'*') => {
+                    self.pos = self.source[self.pos + 2..]
+                        .find("*/")
+                        .map_or(self.bytes.len(), |i| self.pos + 2 + i + 2);
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn identifier(&mut self) -> Option<&'a str> {
+        let start = self.pos;
+        if !self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == b'_')
+        {
+            return None;
+        }
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == b'_' || c == b'-')
+        {
+            self.pos += 1;
+        }
+        Some(&self.source[start..self.pos])
+    }
+
+    /// Parses the items of a body up to its closing brace, or to the end of
+    /// the file at the top level.
+    fn body(&mut self, top_level: bool) -> Result<Body<'a>, String> {
+        let mut body = Body::default();
+        loop {
+            self.skip_trivia(true);
+            match self.peek() {
+                None if top_level => return Ok(body),
+                None => return Err("unterminated block".to_string()),
+                Some(b'}') if !top_level => {
+                    self.pos += 1;
+                    return Ok(body);
+                }
+                _ => {}
+            }
+            let start = self.pos;
+            let name = self.identifier().ok_or("expected an attribute or block")?;
+            self.skip_trivia(false);
+            if self.peek() == Some(b'=') && self.peek_at(1) != Some(b'=') {
+                self.pos += 1;
+                let mut refs = Vec::new();
+                let (end, string) = self.expression(&mut refs)?;
+                body.attributes.push(Attribute {
+                    name,
+                    start,
+                    end,
+                    refs,
+                    string,
+                });
+                continue;
+            }
+            let mut labels = Vec::new();
+            loop {
+                self.skip_trivia(false);
+                match self.peek() {
+                    Some(b'{') => break,
+                    Some(b'"') => labels.push(self.string(&mut Vec::new())?),
+                    _ => labels.push(self.identifier().ok_or("expected a block label")?),
+                }
+            }
+            self.pos += 1;
+            let inner = self.body(false)?;
+            body.blocks.push(Block {
+                kind: name,
+                labels,
+                start,
+                end: self.pos,
+                body: inner,
+            });
+        }
+    }
+
+    /// Scans an attribute value up to the end of its line, or up to the
+    /// brace closing a single-line block. Returns where the value ends and
+    /// the value itself when it is a single quoted string.
+    fn expression(
+        &mut self,
+        refs: &mut Vec<(String, usize)>,
+    ) -> Result<(usize, Option<&'a str>), String> {
+        let mut depth = 0usize;
+        let mut end = self.pos;
+        let mut tokens = 0;
+        let mut string = None;
+        loop {
+            self.skip_trivia(depth > 0);
+            match self.peek() {
+                None | Some(b'\n') => break,
+                Some(b'}') if depth == 0 => break,
+                Some(b'"') => string = Some(self.string(refs)?),
+                _ => self.token(&mut depth, refs)?,
+            }
+            tokens += 1;
+            end = self.pos;
+        }
+        Ok((end, string.filter(|_| tokens == 1)))
+    }
+
+    /// Scans a `${ ... }` or `%{ ... }` template sequence after its opening
+    /// characters, including its closing brace.
+    fn template_sequence(&mut self, refs: &mut Vec<(String, usize)>) -> Result<(), String> {
+        let mut depth = 0usize;
+        loop {
+            self.skip_trivia(true);
+            match self.peek() {
+                None => return Err("unterminated template".to_string()),
+                Some(b'}') if depth == 0 => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some(b'"') => {
+                    self.string(refs)?;
+                }
+                _ => self.token(&mut depth, refs)?,
+            }
+        }
+    }
+
+    /// Consumes one token of an expression other than a quoted string.
+    fn token(&mut self, depth: &mut usize, refs: &mut Vec<(String, usize)>) -> Result<(), String> {
+        match self.peek() {
+            Some(b'(' | b'[' | b'{') => {
+                *depth += 1;
+                self.pos += 1;
+            }
+            Some(b')' | b']' | b'}') => {
+                *depth = depth.saturating_sub(1);
+                self.pos += 1;
+            }
+            Some(b'<') if self.at("<<") => self.heredoc(refs)?,
+            // An attribute access after an index (`[0].id`) is no reference.
+            Some(b'.') => {
+                self.pos += 1;
+                self.identifier();
+            }
+            Some(c) if c.is_ascii_alphabetic() || c == b'_' => self.traversal(refs),
+            Some(c) if c.is_ascii_digit() => {
+                while self
+                    .peek()
+                    .is_some_and(|c| c.is_ascii_alphanumeric() || c == b'.')
+                {
+                    self.pos += 1;
+                }
+            }
+            _ => self.pos += 1,
+        }
+        Ok(())
+    }
+
+    /// Scans a quoted string and returns its raw content.
+    fn string(&mut self, refs: &mut Vec<(String, usize)>) -> Result<&'a str, String> {
+        self.pos += 1;
+        let start = self.pos;
+        loop {
+            match self.peek() {
+                None | Some(b'\n') => return Err("unterminated string".to_string()),
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(&self.source[start..self.pos - 1]);
+                }
+                Some(b'\\') => self.pos += 2,
+                _ => self.template_text(refs)?,
+            }
+        }
+    }
+
+    /// Consumes one character of template text, or a whole template
+    /// sequence. `$${` and `%%{` are escapes.
+    fn template_text(&mut self, refs: &mut Vec<(String, usize)>) -> Result<(), String> {
+        if self.at("$${") || self.at("%%{") {
+            self.pos += 3;
+        } else if self.at("${") || self.at("%{") {
+            self.pos += 2;
+            self.template_sequence(refs)?;
+        } else {
+            self.pos += 1;
+        }
+        Ok(())
+    }
+
+    /// Scans a `<<EOT` or `<<-EOT` heredoc up to its closing marker line.
+    fn heredoc(&mut self, refs: &mut Vec<(String, usize)>) -> Result<(), String> {
+        self.pos += 2;
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        let Some(marker) = self.identifier() else {
+            return Ok(());
+        };
+        self.skip_line();
+        while self.pos < self.bytes.len() {
+            if self.peek() == Some(b'\n') {
+                self.pos += 1;
+                let line_end = self.source[self.pos..]
+                    .find('\n')
+                    .map_or(self.bytes.len(), |i| self.pos + i);
+                if self.source[self.pos..line_end].trim() == marker {
+                    self.pos = line_end;
+                    return Ok(());
+                }
+            } else {
+                self.template_text(refs)?;
+            }
+        }
+        Err(format!("unterminated heredoc {marker}"))
+    }
+
+    /// Scans a variable and its attribute accesses (`var.tags.name`), and
+    /// records the address it references, if any.
+    fn traversal(&mut self, refs: &mut Vec<(String, usize)>) {
+        let start = self.pos;
+        let mut segments = Vec::new();
+        while let Some(segment) = self.identifier() {
+            segments.push(segment);
+            // Splats and legacy numeric indexes (`aws_instance.web.*.id`).
+            while self.peek() == Some(b'.')
+                && self
+                    .peek_at(1)
+                    .is_some_and(|c| c == b'*' || c.is_ascii_digit())
+            {
+                self.pos += 1;
+                while self.peek().is_some_and(|c| c == b'*' || c.is_ascii_digit()) {
+                    self.pos += 1;
+                }
+            }
+            if self.peek() != Some(b'.') {
+                break;
+            }
+            self.pos += 1;
+        }
+        if let Some(address) = address(&segments) {
+            refs.push((address, start));
+        }
+    }
+}
+
+/// The address of the block a traversal references: a variable, local,
+/// module output, data source or resource attribute. Managed resource types
+/// always carry their provider's prefix (`aws_`), which tells them from
+/// `each`, `count`, `path` and the variables of `for` expressions.
+fn address(segments: &[&str]) -> Option<String> {
+    match segments {
+        [root @ ("var" | "local" | "module"), name, ..] => Some(format!("{root}.{name}")),
+        ["data", kind, name, ..] => Some(format!("data.{kind}.{name}")),
+        [kind, name, ..] if kind.contains('_') => Some(format!("{kind}.{name}")),
+        _ => None,
+    }
+}
+
+struct Emitter<'a> {
+    file_path: &'a str,
+    source: &'a str,
+    file_id: String,
+    timestamp: u64,
+    /// Byte offset of the start of each line.
+    line_starts: Vec<usize>,
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+    unresolved_refs: Vec<UnresolvedRef>,
+}
+
+impl<'a> Emitter<'a> {
+    fn new(file_path: &'a str, source: &'a str) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        let mut emitter = Self {
+            file_path,
+            source,
+            file_id: generate_node_id(file_path, &NodeKind::File, file_path, 0),
+            timestamp,
+            line_starts,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            unresolved_refs: Vec::new(),
+        };
+        let file_node = emitter.node(
+            emitter.file_id.clone(),
+            NodeKind::File,
+            file_path.to_string(),
+            file_path.to_string(),
+            (0, source.len()),
+        );
+        emitter.nodes.push(file_node);
+        emitter
+    }
+
+    /// 0-based (line, column) of a byte offset.
+    fn position(&self, offset: usize) -> (u32, u32) {
+        let line = self.line_starts.partition_point(|&s| s <= offset) - 1;
+        (line as u32, (offset - self.line_starts[line]) as u32)
+    }
+
+    fn node(
+        &self,
+        id: String,
+        kind: NodeKind,
+        name: String,
+        qualified_name: String,
+        span: (usize, usize),
+    ) -> Node {
+        let (start_line, start_column) = self.position(span.0);
+        let (end_line, end_column) = self.position(span.1);
+        let signature = (kind != NodeKind::File).then(|| {
+            let line = self.source[span.0..span.1]
+                .lines()
+                .next()
+                .unwrap_or("")
+                .trim();
+            match line.char_indices().nth(120) {
+                Some((cut, _)) => format!("{}...", &line[..cut]),
+                None => line.to_string(),
+            }
+        });
+        // Variables and outputs are the module's interface.
+        let visibility = match kind {
+            NodeKind::File | NodeKind::TfVariable | NodeKind::TfOutput => Visibility::Pub,
+            _ => Visibility::Private,
+        };
+        Node {
+            id,
+            kind,
+            name,
+            qualified_name,
+            file_path: self.file_path.to_string(),
+            start_line,
+            end_line,
+            start_column,
+            end_column,
+            signature,
+            docstring: None,
+            visibility,
+            is_async: false,
+            branches: 0,
+            loops: 0,
+            returns: 0,
+            max_nesting: 0,
+            unsafe_blocks: 0,
+            unchecked_calls: 0,
+            assertions: 0,
+            updated_at: self.timestamp,
+        }
+    }
+
+    /// Adds a node contained in the file and returns its id.
+    fn add(
+        &mut self,
+        kind: NodeKind,
+        name: String,
+        span: (usize, usize),
+        docstring: Option<&str>,
+    ) -> String {
+        let (line, _) = self.position(span.0);
+        let qualified_name = format!("{}::{name}", self.file_path);
+        let id = generate_node_id(self.file_path, &kind, &qualified_name, line);
+        let mut node = self.node(id.clone(), kind, name, qualified_name, span);
+        node.docstring = docstring.map(str::to_string);
+        self.nodes.push(node);
+        self.edges.push(Edge {
+            source: self.file_id.clone(),
+            target: id.clone(),
+            kind: EdgeKind::Contains,
+            line: Some(line),
+            provenance: None,
+        });
+        id
+    }
+
+    fn emit_block(&mut self, block: &Block<'_>) {
+        let (kind, name) = match (block.kind, block.labels.as_slice()) {
+            ("resource", [kind, name]) => (NodeKind::TfResource, format!("{kind}.{name}")),
+            ("data", [kind, name]) => (NodeKind::TfData, format!("data.{kind}.{name}")),
+            ("module", [name]) => (NodeKind::TfModule, format!("module.{name}")),
+            ("variable", [name]) => (NodeKind::TfVariable, format!("var.{name}")),
+            ("output", [name]) => (NodeKind::TfOutput, format!("output.{name}")),
+            ("locals", []) => {
+                for local in &block.body.attributes {
+                    let id = self.add(
+                        NodeKind::TfLocal,
+                        format!("local.{}", local.name),
+                        (local.start, local.end),
+                        None,
+                    );
+                    self.reference(&id, local);
+                }
+                return;
+            }
+            _ => {
+                let file_id = self.file_id.clone();
+                self.references(&file_id, &block.body);
+                return;
+            }
+        };
+        let description = block
+            .body
+            .attributes
+            .iter()
+            .find(|a| a.name == "description")
+            .and_then(|a| a.string);
+        let id = self.add(kind, name, (block.start, block.end), description);
+        self.references(&id, &block.body);
+    }
+
+    /// Records the references made anywhere in `body` as refs from `from`.
+    fn references(&mut self, from: &str, body: &Body<'_>) {
+        for attribute in &body.attributes {
+            self.reference(from, attribute);
+        }
+        for block in &body.blocks {
+            self.references(from, &block.body);
+        }
+    }
+
+    fn reference(&mut self, from: &str, attribute: &Attribute<'_>) {
+        let kind = if attribute.name == "depends_on" {
+            EdgeKind::DependsOn
+        } else {
+            EdgeKind::Uses
+        };
+        for (address, offset) in &attribute.refs {
+            let (line, column) = self.position(*offset);
+            self.unresolved_refs.push(UnresolvedRef {
+                from_node_id: from.to_string(),
+                reference_name: address.clone(),
+                reference_kind: kind.clone(),
+                line,
+                column,
+                file_path: self.file_path.to_string(),
+                receiver_type: None,
+                abi: None,
+                http_method: None,
+                sql: false,
+            });
+        }
+    }
+}
@@ -27,6 +27,12 @@ pub fn language(key: &str) -> Language {
         .unwrap_or_else(|| panic!("ts_provider: unknown language key '{key}'"))
 }
 
+/// Returns the `tree_sitter::Language` for `key`, or `None` when no bundled
+/// grammar has that key. For keys that come from user configuration.
+pub fn find_language(key: &str) -> Option<Language> {
+    LANGUAGES.get(key).cloned()
+}
+
 #[cfg(test)]
 mod tests {
     /// Every key that an extractor passes to `language()` must be present in the
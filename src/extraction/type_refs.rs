@@ -0,0 +1,381 @@
+// Rust guideline compliant 2025-10-17
+//! Generic type-reference collection for tree-sitter AST nodes.
+//!
+//! Walks a type expression (a parameter list, a return type, a field's
+//! declared type) and reports every user-defined type it names, so the
+//! extractors can emit `Receives`, `Returns` and `TypeOf` references. As
+//! with complexity counting, each language supplies the node type names
+//! that carry type names in its grammar.
+
+use tree_sitter::Node as TsNode;
+
+use crate::types::{EdgeKind, UnresolvedRef};
+
+/// Configuration mapping tree-sitter node type names to type references.
+pub struct TypeRefConfig {
+    /// Node types whose text is a single type name (e.g. `type_identifier`).
+    pub name_types: &'static [&'static str],
+    /// Node types whose full text is a qualified type name
+    /// (e.g. `scoped_type_identifier`). Their children are not visited.
+    pub qualified_types: &'static [&'static str],
+    /// Node types whose direct `name_types` children spell a dotted path
+    /// (e.g. Kotlin's `user_type`). Other children, such as type arguments,
+    /// are still visited.
+    pub path_types: &'static [&'static str],
+    /// Node types that never contain a type reference worth following
+    /// (default values, parameter names that share a node type with types).
+    pub skip_types: &'static [&'static str],
+    /// Names the grammar parses like any other type but that always refer to
+    /// language builtins (e.g. `String` in Kotlin, `Self` in Rust).
+    pub builtin_names: &'static [&'static str],
+}
+
+/// A type named inside a type expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeMention {
+    pub name: String,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl TypeMention {
+    /// Turns the mention into an unresolved reference of `kind` from
+    /// `from_node_id`.
+    pub fn into_ref(self, from_node_id: &str, kind: EdgeKind, file_path: &str) -> UnresolvedRef {
+        UnresolvedRef {
+            from_node_id: from_node_id.to_string(),
+            reference_name: self.name,
+            reference_kind: kind,
+            line: self.line,
+            column: self.column,
+            file_path: file_path.to_string(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
+        }
+    }
+}
+
+/// Collects the user-defined types named anywhere under `node`, in source
+/// order and without duplicates.
+///
+/// Uses an explicit stack instead of recursion (NASA Power of 10, Rule 1).
+pub fn collect_type_mentions(
+    node: TsNode<'_>,
+    source: &[u8],
+    config: &TypeRefConfig,
+) -> Vec<TypeMention> {
+    let mut mentions: Vec<TypeMention> = Vec::new();
+    let mut stack = vec![node];
+
+    while let Some(current) = stack.pop() {
+        let kind = current.kind();
+        if config.skip_types.contains(&kind) {
+            continue;
+        }
+
+        let name = if config.name_types.contains(&kind) || config.qualified_types.contains(&kind) {
+            Some(node_text(current, source))
+        } else if config.path_types.contains(&kind) {
+            let mut segments = Vec::new();
+            let mut cursor = current.walk();
+            for child in current.named_children(&mut cursor) {
+                if config.name_types.contains(&child.kind()) {
+                    segments.push(node_text(child, source));
+                } else {
+                    stack.push(child);
+                }
+            }
+            (!segments.is_empty()).then(|| segments.join("."))
+        } else {
+            // Push children in reverse so they pop in source order.
+            let count = current.named_child_count();
+            for i in (0..count).rev() {
+                if let Some(child) = current.named_child(i as u32) {
+                    stack.push(child);
+                }
+            }
+            None
+        };
+
+        if let Some(name) = name {
+            if name.is_empty()
+                || config.builtin_names.contains(&name.as_str())
+                || mentions.iter().any(|m| m.name == name)
+            {
+                continue;
+            }
+            mentions.push(TypeMention {
+                name,
+                line: current.start_position().row as u32,
+                column: current.start_position().column as u32,
+            });
+        }
+    }
+
+    mentions
+}
+
+/// Creates unresolved `kind` refs from `from_id` to every type named under
+/// `type_node` and appends them to `refs`.
+pub fn push_type_refs(
+    refs: &mut Vec<UnresolvedRef>,
+    type_node: TsNode<'_>,
+    source: &[u8],
+    config: &TypeRefConfig,
+    from_id: &str,
+    kind: &EdgeKind,
+    file_path: &str,
+) {
+    for mention in collect_type_mentions(type_node, source, config) {
+        refs.push(mention.into_ref(from_id, kind.clone(), file_path));
+    }
+}
+
+/// Returns the node's text with all whitespace removed, so qualified names
+/// spread over several lines still read `a::b::C`.
+fn node_text(node: TsNode<'_>, source: &[u8]) -> String {
+    node.utf8_text(source)
+        .unwrap_or("")
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Per-language configurations
+// ---------------------------------------------------------------------------
+
+pub static RUST_TYPE_REFS: TypeRefConfig = TypeRefConfig {
+    name_types: &["type_identifier"],
+    qualified_types: &["scoped_type_identifier"],
+    path_types: &[],
+    skip_types: &["lifetime", "attribute_item", "self_parameter"],
+    builtin_names: &["Self"],
+};
+
+pub static TYPESCRIPT_TYPE_REFS: TypeRefConfig = TypeRefConfig {
+    name_types: &["type_identifier"],
+    qualified_types: &["nested_type_identifier"],
+    path_types: &[],
+    skip_types: &["decorator"],
+    builtin_names: &[
+        "string",
+        "number",
+        "boolean",
+        "void",
+        "null",
+        "undefined",
+        "any",
+        "never",
+        "unknown",
+        "object",
+        "symbol",
+        "bigint",
+    ],
+};
+
+pub static JAVA_TYPE_REFS: TypeRefConfig = TypeRefConfig {
+    name_types: &["type_identifier"],
+    qualified_types: &["scoped_type_identifier"],
+    path_types: &[],
+    skip_types: &["modifiers", "annotation", "marker_annotation"],
+    builtin_names: &[
+        "String",
+        "Object",
+        "Integer",
+        "Long",
+        "Short",
+        "Byte",
+        "Double",
+        "Float",
+        "Boolean",
+        "Character",
+        "Void",
+    ],
+};
+
+pub static KOTLIN_TYPE_REFS: TypeRefConfig = TypeRefConfig {
+    name_types: &["type_identifier"],
+    qualified_types: &[],
+    path_types: &["user_type"],
+    skip_types: &["modifiers", "annotation"],
+    builtin_names: &[
+        "Any",
+        "Unit",
+        "Nothing",
+        "String",
+        "Int",
+        "Long",
+        "Short",
+        "Byte",
+        "Double",
+        "Float",
+        "Boolean",
+        "Char",
+        "Array",
+        "List",
+        "Map",
+        "Set",
+        "MutableList",
+        "MutableMap",
+        "MutableSet",
+    ],
+};
+
+pub static CSHARP_TYPE_REFS: TypeRefConfig = TypeRefConfig {
+    name_types: &["identifier"],
+    qualified_types: &["qualified_name"],
+    path_types: &[],
+    skip_types: &["attribute_list", "modifier"],
+    builtin_names: &["var", "dynamic"],
+};
+
+pub static PYTHON_TYPE_REFS: TypeRefConfig = TypeRefConfig {
+    // `string_content` picks up forward references written as strings.
+    name_types: &["identifier", "string_content"],
+    qualified_types: &["attribute"],
+    path_types: &[],
+    skip_types: &[],
+    builtin_names: &[
+        "int",
+        "float",
+        "complex",
+        "str",
+        "bytes",
+        "bool",
+        "object",
+        "type",
+        "list",
+        "dict",
+        "set",
+        "frozenset",
+        "tuple",
+        "None",
+        "Any",
+        "Optional",
+        "Union",
+        "List",
+        "Dict",
+        "Set",
+        "FrozenSet",
+        "Tuple",
+        "Type",
+        "Callable",
+        "Iterable",
+        "Iterator",
+        "Generator",
+        "Sequence",
+        "Mapping",
+        "Literal",
+        "Self",
+    ],
+};
+
+pub static GO_TYPE_REFS: TypeRefConfig = TypeRefConfig {
+    name_types: &["type_identifier"],
+    qualified_types: &["qualified_type"],
+    path_types: &[],
+    skip_types: &[],
+    builtin_names: &[
+        "bool", "byte", "rune", "string", "error", "int", "int8", "int16", "int32", "int64",
+        "uint", "uint8", "uint16", "uint32", "uint64", "uintptr", "float32", "float64", "any",
+    ],
+};
+
+pub static SWIFT_TYPE_REFS: TypeRefConfig = TypeRefConfig {
+    name_types: &["type_identifier"],
+    qualified_types: &[],
+    path_types: &["user_type"],
+    skip_types: &["modifiers", "attribute"],
+    builtin_names: &[
+        "Int",
+        "UInt",
+        "Int64",
+        "Int32",
+        "Double",
+        "Float",
+        "Bool",
+        "String",
+        "Character",
+        "Void",
+        "Any",
+        "AnyObject",
+        "Self",
+        "Array",
+        "Dictionary",
+        "Set",
+        "Optional",
+    ],
+};
+
+pub static SCALA_TYPE_REFS: TypeRefConfig = TypeRefConfig {
+    name_types: &["type_identifier"],
+    qualified_types: &["stable_type_identifier"],
+    path_types: &[],
+    skip_types: &["annotation"],
+    builtin_names: &[
+        "Any", "AnyRef", "AnyVal", "Nothing", "Unit", "Int", "Long", "Short", "Byte", "Double",
+        "Float", "Boolean", "Char", "String",
+    ],
+};
+
+pub static C_TYPE_REFS: TypeRefConfig = TypeRefConfig {
+    name_types: &["type_identifier"],
+    qualified_types: &[],
+    path_types: &[],
+    skip_types: &["type_qualifier", "storage_class_specifier"],
+    builtin_names: &[
+        "size_t",
+        "ssize_t",
+        "ptrdiff_t",
+        "int8_t",
+        "int16_t",
+        "int32_t",
+        "int64_t",
+        "uint8_t",
+        "uint16_t",
+        "uint32_t",
+        "uint64_t",
+        "intptr_t",
+        "uintptr_t",
+        "bool",
+        "FILE",
+    ],
+};
+
+pub static CPP_TYPE_REFS: TypeRefConfig = TypeRefConfig {
+    name_types: &["type_identifier"],
+    qualified_types: &[],
+    path_types: &[],
+    skip_types: &["type_qualifier", "storage_class_specifier"],
+    builtin_names: &[
+        "size_t",
+        "ssize_t",
+        "ptrdiff_t",
+        "int8_t",
+        "int16_t",
+        "int32_t",
+        "int64_t",
+        "uint8_t",
+        "uint16_t",
+        "uint32_t",
+        "uint64_t",
+        "intptr_t",
+        "uintptr_t",
+        "string",
+        "vector",
+        "map",
+        "unordered_map",
+        "set",
+        "unordered_set",
+        "unique_ptr",
+        "shared_ptr",
+        "weak_ptr",
+        "optional",
+        "function",
+        "pair",
+        "array",
+    ],
+};
@@ -7,8 +7,12 @@ use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tree_sitter::{Node as TsNode, Parser, Tree};
 
 use crate::extraction::complexity::{count_complexity, TYPESCRIPT_COMPLEXITY};
+use crate::extraction::receivers::{receiver_type, TypeDecls, TYPESCRIPT_RECEIVERS};
+use crate::extraction::signatures::{param, signature};
+use crate::extraction::type_refs::{push_type_refs, TYPESCRIPT_TYPE_REFS};
 use crate::types::{
-    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, UnresolvedRef, Visibility,
+    generate_node_id, Edge, EdgeKind, ExtractionResult, Node, NodeKind, Signature, UnresolvedRef,
+    Visibility,
 };
 
 /// Extracts code graph nodes and edges from TypeScript/JavaScript source files
@@ -20,11 +24,14 @@ struct ExtractionState {
     nodes: Vec<Node>,
     edges: Vec<Edge>,
     unresolved_refs: Vec<UnresolvedRef>,
+    signatures: Vec<Signature>,
     errors: Vec<String>,
     /// Stack of (name, `node_id`) for building qualified names and parent edges.
     node_stack: Vec<(String, String)>,
     file_path: String,
     source: Vec<u8>,
+    /// Type declarations of the file, for receiver inference.
+    type_decls: TypeDecls,
     timestamp: u64,
     /// Whether the current declaration is inside an `export_statement`.
     in_export: bool,
@@ -40,10 +47,12 @@ impl ExtractionState {
             nodes: Vec::new(),
             edges: Vec::new(),
             unresolved_refs: Vec::new(),
+            signatures: Vec::new(),
             errors: Vec::new(),
             node_stack: Vec::new(),
             file_path: file_path.to_string(),
             source: source.as_bytes().to_vec(),
+            type_decls: TypeDecls::default(),
             timestamp,
             in_export: false,
         }
@@ -77,6 +86,12 @@ impl TypeScriptExtractor {
     /// `file_path` is used for qualified names and node IDs (not for I/O).
     /// `source` is the source code to parse.
     pub fn extract_typescript(file_path: &str, source: &str) -> ExtractionResult {
+        Self::parse_and_extract(file_path, source).0
+    }
+
+    /// Parses `source` and extracts it, returning the tree along with the
+    /// result.
+    fn parse_and_extract(file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
         let start = Instant::now();
         let mut state = ExtractionState::new(file_path, source);
 
@@ -86,7 +101,7 @@ impl TypeScriptExtractor {
             Ok(tree) => tree,
             Err(msg) => {
                 state.errors.push(msg);
-                return Self::build_result(state, start);
+                return (Self::build_result(state, start), None);
             }
         };
 
@@ -120,11 +135,15 @@ impl TypeScriptExtractor {
 
         // Walk the AST.
         let root = tree.root_node();
+        state.type_decls = TypeDecls::new(root, &state.source, &TYPESCRIPT_RECEIVERS);
         Self::visit_children(&mut state, root);
 
         state.node_stack.pop();
 
-        Self::build_result(state, start)
+        let mut result = Self::build_result(state, start);
+        super::routes::extract_routes(file_path, source, root, &mut result);
+        super::sql::extract_embedded_sql(file_path, source, root, &mut result);
+        (result, Some(tree))
     }
 
     /// Parse source code into a tree-sitter AST, selecting grammar by file extension.
@@ -173,6 +192,10 @@ impl TypeScriptExtractor {
                 // Namespace declarations appear as expression_statement > internal_module.
                 if let Some(internal) = Self::find_child_by_kind(node, "internal_module") {
                     Self::visit_namespace(state, internal);
+                } else if let Some(call) = Self::find_child_by_kind(node, "call_expression") {
+                    if let Some((name, signature)) = Self::test_block(state, call) {
+                        Self::visit_test_block(state, call, name, signature);
+                    }
                 }
             }
             _ => {
@@ -244,6 +267,7 @@ impl TypeScriptExtractor {
                                 target: id,
                                 kind: EdgeKind::Contains,
                                 line: Some(start_line),
+                                provenance: None,
                             });
                         }
                     }
@@ -310,11 +334,13 @@ impl TypeScriptExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
         // Extract type references from parameter and return type annotations.
         Self::extract_type_refs(state, node, &id);
+        Self::push_signature(state, node, &id);
 
         // Extract call sites from the function body.
         if let Some(body) = Self::find_child_by_kind(node, "statement_block") {
@@ -415,11 +441,13 @@ impl TypeScriptExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
         // Extract type references from parameter and return type annotations.
         Self::extract_type_refs(state, arrow_node, &id);
+        Self::push_signature(state, arrow_node, &id);
 
         // Extract call sites from the arrow function body.
         if let Some(body) = Self::find_child_by_kind(arrow_node, "statement_block") {
@@ -427,6 +455,117 @@ impl TypeScriptExtractor {
         }
     }
 
+    /// The name and signature of a `describe`, `it` or `test` block declaring
+    /// a test suite or test case, in any of its `.only`, `.skip` or
+    /// `.each(table)` forms; `None` for any other call.
+    fn test_block(state: &ExtractionState, call: TsNode<'_>) -> Option<(String, String)> {
+        let callee = state.node_text(call.child_by_field_name("function")?);
+        let function = callee.split(['.', '(', '`']).next().unwrap_or("").trim();
+        if !matches!(
+            function,
+            "describe" | "fdescribe" | "xdescribe" | "it" | "fit" | "xit" | "test" | "xtest"
+        ) {
+            return None;
+        }
+        let description = call.child_by_field_name("arguments")?.named_child(0)?;
+        if !matches!(description.kind(), "string" | "template_string") {
+            return None;
+        }
+        let quoted = state.node_text(description);
+        let text = quoted.get(1..quoted.len().saturating_sub(1)).unwrap_or("");
+        let name = if text.is_empty() { function } else { text };
+        Some((name.to_string(), format!("{callee}({quoted})")))
+    }
+
+    /// Extract a `describe` or `it` block as a test case making the calls in
+    /// its callback, and the blocks nested in it as test cases of their own.
+    fn visit_test_block(
+        state: &mut ExtractionState,
+        call: TsNode<'_>,
+        name: String,
+        signature: String,
+    ) {
+        let callback = call.child_by_field_name("arguments").and_then(|args| {
+            let mut cursor = args.walk();
+            args.named_children(&mut cursor)
+                .filter(|arg| {
+                    matches!(
+                        arg.kind(),
+                        "arrow_function" | "function_expression" | "function"
+                    )
+                })
+                .last()
+        });
+        let is_async = callback.is_some_and(|c| Self::has_child_kind(c, "async"));
+        let start_line = call.start_position().row as u32;
+        let end_line = call.end_position().row as u32;
+        let start_column = call.start_position().column as u32;
+        let end_column = call.end_position().column as u32;
+        let qualified_name = format!("{}::{}", state.qualified_prefix(), name);
+        let id = generate_node_id(&state.file_path, &NodeKind::TestCase, &name, start_line);
+        let metrics = count_complexity(call, &TYPESCRIPT_COMPLEXITY, &state.source);
+
+        let graph_node = Node {
+            id: id.clone(),
+            kind: NodeKind::TestCase,
+            name: name.clone(),
+            qualified_name,
+            file_path: state.file_path.clone(),
+            start_line,
+            end_line,
+            start_column,
+            end_column,
+            signature: Some(signature),
+            docstring: None,
+            visibility: Visibility::Private,
+            is_async,
+            branches: metrics.branches,
+            loops: metrics.loops,
+            returns: metrics.returns,
+            max_nesting: metrics.max_nesting,
+            unsafe_blocks: metrics.unsafe_blocks,
+            unchecked_calls: metrics.unchecked_calls,
+            assertions: metrics.assertions,
+            updated_at: state.timestamp,
+        };
+        state.nodes.push(graph_node);
+
+        // Contains edge from parent.
+        if let Some(parent_id) = state.parent_node_id() {
+            state.edges.push(Edge {
+                source: parent_id.to_string(),
+                target: id.clone(),
+                kind: EdgeKind::Contains,
+                line: Some(start_line),
+                provenance: None,
+            });
+        }
+
+        if let Some(callback) = callback {
+            state.node_stack.push((name, id.clone()));
+            Self::extract_test_calls(state, callback, &id);
+            state.node_stack.pop();
+        }
+    }
+
+    /// Like [`Self::extract_call_sites`] for the callback of a test block,
+    /// but descending into nested callbacks, as hooks and the callbacks
+    /// passed to `expect` run as part of the test, and extracting nested
+    /// `describe` and `it` blocks as test cases of their own.
+    fn extract_test_calls(state: &mut ExtractionState, node: TsNode<'_>, test_id: &str) {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            if child.kind() == "call_expression" {
+                if let Some((name, signature)) = Self::test_block(state, child) {
+                    Self::visit_test_block(state, child, name, signature);
+                    continue;
+                }
+                Self::push_call(state, child, test_id);
+            }
+            Self::extract_test_calls(state, child, test_id);
+        }
+    }
+
     /// Extract a const variable declaration (not an arrow function).
     fn visit_const_variable(state: &mut ExtractionState, declarator: TsNode<'_>) {
         let name = Self::find_child_by_kind(declarator, "identifier")
@@ -476,6 +615,7 @@ impl TypeScriptExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -532,6 +672,7 @@ impl TypeScriptExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -623,11 +764,13 @@ impl TypeScriptExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
         // Extract type references from parameter and return type annotations.
         Self::extract_type_refs(state, node, &id);
+        Self::push_signature(state, node, &id);
 
         // Extract call sites from the method body.
         if let Some(body) = Self::find_child_by_kind(node, "statement_block") {
@@ -677,11 +820,24 @@ impl TypeScriptExtractor {
         if let Some(parent_id) = state.parent_node_id() {
             state.edges.push(Edge {
                 source: parent_id.to_string(),
-                target: id,
+                target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
+
+        if let Some(field_type) = node.child_by_field_name("type") {
+            push_type_refs(
+                &mut state.unresolved_refs,
+                field_type,
+                &state.source,
+                &TYPESCRIPT_TYPE_REFS,
+                &id,
+                &EdgeKind::TypeOf,
+                &state.file_path,
+            );
+        }
     }
 
     /// Extract an interface declaration node.
@@ -734,6 +890,7 @@ impl TypeScriptExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -802,11 +959,15 @@ impl TypeScriptExtractor {
         if let Some(parent_id) = state.parent_node_id() {
             state.edges.push(Edge {
                 source: parent_id.to_string(),
-                target: id,
+                target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
+
+        Self::extract_type_refs(state, node, &id);
+        Self::push_signature(state, node, &id);
     }
 
     /// Extract an enum declaration node.
@@ -860,6 +1021,7 @@ impl TypeScriptExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -929,6 +1091,7 @@ impl TypeScriptExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -982,6 +1145,7 @@ impl TypeScriptExtractor {
                 target: id,
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
     }
@@ -1031,6 +1195,7 @@ impl TypeScriptExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -1042,6 +1207,10 @@ impl TypeScriptExtractor {
             line: start_line,
             column: start_column,
             file_path: state.file_path.clone(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
         });
     }
 
@@ -1096,6 +1265,7 @@ impl TypeScriptExtractor {
                 target: id.clone(),
                 kind: EdgeKind::Contains,
                 line: Some(start_line),
+                provenance: None,
             });
         }
 
@@ -1166,6 +1336,7 @@ impl TypeScriptExtractor {
                         target: parent_id.to_string(),
                         kind: EdgeKind::Annotates,
                         line: Some(start_line),
+                        provenance: None,
                     });
                 }
                 if !cursor.goto_next_sibling() {
@@ -1196,6 +1367,10 @@ impl TypeScriptExtractor {
                                     line: child.start_position().row as u32,
                                     column: child.start_position().column as u32,
                                     file_path: state.file_path.clone(),
+                                    receiver_type: None,
+                                    abi: None,
+                                    http_method: None,
+                                    sql: false,
                                 });
                             }
                         }
@@ -1214,6 +1389,10 @@ impl TypeScriptExtractor {
                                             line: iface.start_position().row as u32,
                                             column: iface.start_position().column as u32,
                                             file_path: state.file_path.clone(),
+                                            receiver_type: None,
+                                            abi: None,
+                                            http_method: None,
+                                            sql: false,
                                         });
                                     }
                                     if !inner.goto_next_sibling() {
@@ -1255,19 +1434,7 @@ impl TypeScriptExtractor {
                 let child = cursor.node();
                 match child.kind() {
                     "call_expression" => {
-                        // Get the callee name.
-                        let callee = child.named_child(0);
-                        if let Some(callee) = callee {
-                            let callee_name = state.node_text(callee);
-                            state.unresolved_refs.push(UnresolvedRef {
-                                from_node_id: fn_node_id.to_string(),
-                                reference_name: callee_name,
-                                reference_kind: EdgeKind::Calls,
-                                line: child.start_position().row as u32,
-                                column: child.start_position().column as u32,
-                                file_path: state.file_path.clone(),
-                            });
-                        }
+                        Self::push_call(state, child, fn_node_id);
                         // Also recurse into the call expression for nested calls.
                         Self::extract_call_sites(state, child, fn_node_id);
                     }
@@ -1284,78 +1451,115 @@ impl TypeScriptExtractor {
         }
     }
 
+    /// Record the call expression `call` as a call reference from `fn_node_id`.
+    fn push_call(state: &mut ExtractionState, call: TsNode<'_>, fn_node_id: &str) {
+        // Get the callee name.
+        if let Some(callee) = call.named_child(0) {
+            let callee_name = state.node_text(callee);
+            let receiver = receiver_type(
+                call,
+                &callee_name,
+                &state.source,
+                &state.type_decls,
+                &TYPESCRIPT_RECEIVERS,
+            );
+            state.unresolved_refs.push(UnresolvedRef {
+                from_node_id: fn_node_id.to_string(),
+                reference_name: callee_name,
+                reference_kind: EdgeKind::Calls,
+                line: call.start_position().row as u32,
+                column: call.start_position().column as u32,
+                file_path: state.file_path.clone(),
+                receiver_type: receiver,
+                abi: None,
+                http_method: None,
+                sql: false,
+            });
+        }
+    }
+
     /// Extract type references from parameter annotations and return type.
     ///
-    /// In tree-sitter-typescript, type annotations appear as `type_annotation`
-    /// children on parameter nodes and on the function itself (return type).
-    /// Each `type_identifier` inside creates a "uses" unresolved ref.
+    /// Types named in the `parameters` become `Receives` refs and types named
+    /// in the `return_type` annotation become `Returns` refs.
     fn extract_type_refs(state: &mut ExtractionState, node: TsNode<'_>, fn_node_id: &str) {
-        let mut cursor = node.walk();
-        if !cursor.goto_first_child() {
-            return;
+        if let Some(params) = node.child_by_field_name("parameters") {
+            push_type_refs(
+                &mut state.unresolved_refs,
+                params,
+                &state.source,
+                &TYPESCRIPT_TYPE_REFS,
+                fn_node_id,
+                &EdgeKind::Receives,
+                &state.file_path,
+            );
         }
-        loop {
-            let child = cursor.node();
-            match child.kind() {
-                // Parameter nodes contain type_annotation children; also the return type annotation
-                "required_parameter" | "optional_parameter" | "rest_parameter"
-                | "type_annotation" => {
-                    Self::collect_type_identifiers(state, child, fn_node_id);
-                }
-                // Formal parameters container
-                "formal_parameters" => {
-                    Self::extract_type_refs(state, child, fn_node_id);
-                }
-                _ => {}
-            }
-            if !cursor.goto_next_sibling() {
-                break;
-            }
+        if let Some(return_type) = node.child_by_field_name("return_type") {
+            push_type_refs(
+                &mut state.unresolved_refs,
+                return_type,
+                &state.source,
+                &TYPESCRIPT_TYPE_REFS,
+                fn_node_id,
+                &EdgeKind::Returns,
+                &state.file_path,
+            );
         }
     }
 
-    /// Recursively collect `type_identifier` nodes and emit "uses" refs.
-    fn collect_type_identifiers(state: &mut ExtractionState, node: TsNode<'_>, fn_node_id: &str) {
-        let mut cursor = node.walk();
-        if !cursor.goto_first_child() {
-            return;
-        }
-        loop {
-            let child = cursor.node();
-            if child.kind() == "type_identifier" {
-                let type_name = state.node_text(child);
-                // Skip built-in types
-                if !matches!(
-                    type_name.as_str(),
-                    "string"
-                        | "number"
-                        | "boolean"
-                        | "void"
-                        | "null"
-                        | "undefined"
-                        | "any"
-                        | "never"
-                        | "unknown"
-                        | "object"
-                        | "symbol"
-                        | "bigint"
-                ) {
-                    state.unresolved_refs.push(UnresolvedRef {
-                        from_node_id: fn_node_id.to_string(),
-                        reference_name: type_name,
-                        reference_kind: EdgeKind::Uses,
-                        line: child.start_position().row as u32,
-                        column: child.start_position().column as u32,
-                        file_path: state.file_path.clone(),
-                    });
+    /// Records the declared parameters and return type of a function, method
+    /// or arrow function. An explicit `this` parameter only types the
+    /// receiver, so it is not recorded.
+    fn push_signature(state: &mut ExtractionState, node: TsNode<'_>, id: &str) {
+        let mut params = Vec::new();
+        if let Some(list) = node.child_by_field_name("parameters") {
+            let mut cursor = list.walk();
+            for child in list.named_children(&mut cursor) {
+                let kind = child.kind();
+                let (pattern, ty, default) = match kind {
+                    "required_parameter" | "optional_parameter" => (
+                        child.child_by_field_name("pattern"),
+                        child.child_by_field_name("type"),
+                        child.child_by_field_name("value"),
+                    ),
+                    // JavaScript parameters carry no annotation wrapper.
+                    "identifier" | "rest_pattern" => (Some(child), None, None),
+                    "assignment_pattern" => (
+                        child.child_by_field_name("left"),
+                        None,
+                        child.child_by_field_name("right"),
+                    ),
+                    _ => continue,
+                };
+                let pattern = pattern.map(|p| state.node_text(p)).unwrap_or_default();
+                if pattern == "this" {
+                    continue;
                 }
-            } else {
-                Self::collect_type_identifiers(state, child, fn_node_id);
-            }
-            if !cursor.goto_next_sibling() {
-                break;
+                let (name, rest) = match pattern.strip_prefix("...") {
+                    Some(name) => (name.to_string(), true),
+                    None => (pattern, false),
+                };
+                let ty = ty.map(|t| Self::annotation_text(state, t));
+                let default = default.map(|v| state.node_text(v));
+                let optional = rest || kind == "optional_parameter";
+                params.push(param(&name, ty.as_deref(), default.as_deref(), optional));
             }
+        } else if let Some(single) = node.child_by_field_name("parameter") {
+            // `x => ...` has one bare, untyped parameter.
+            params.push(param(&state.node_text(single), None, None, false));
         }
+        let return_type = node
+            .child_by_field_name("return_type")
+            .map(|t| Self::annotation_text(state, t));
+        state
+            .signatures
+            .extend(signature(id, params, return_type.as_deref()));
+    }
+
+    /// The type written in a `: T` annotation, without the colon.
+    fn annotation_text(state: &ExtractionState, annotation: TsNode<'_>) -> String {
+        let text = state.node_text(annotation);
+        text.trim_start_matches(':').trim().to_string()
     }
 
     /// Extract the function/method signature (everything up to the body `{`).
@@ -1479,6 +1683,7 @@ impl TypeScriptExtractor {
             nodes: state.nodes,
             edges: state.edges,
             unresolved_refs: state.unresolved_refs,
+            signatures: state.signatures,
             errors: state.errors,
             duration_ms: start.elapsed().as_millis() as u64,
         }
@@ -1495,6 +1700,18 @@ impl crate::extraction::LanguageExtractor for TypeScriptExtractor {
     }
 
     fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
-        TypeScriptExtractor::extract_typescript(file_path, source)
+        let mut result = TypeScriptExtractor::extract_typescript(file_path, source);
+        super::graphql_extractor::extract_embedded(file_path, source, &mut result);
+        result
+    }
+
+    fn extract_with_tree(&self, file_path: &str, source: &str) -> (ExtractionResult, Option<Tree>) {
+        let (mut result, tree) = Self::parse_and_extract(file_path, source);
+        super::graphql_extractor::extract_embedded(file_path, source, &mut result);
+        (result, tree)
+    }
+
+    fn extracts_signatures(&self) -> bool {
+        true
     }
 }
@@ -30,7 +30,8 @@ use std::sync::{Arc, Mutex};
 
 use serde::{Deserialize, Serialize};
 
-use crate::extraction::LanguageRegistry;
+use crate::extraction::domain::DomainRules;
+use crate::extraction::{finish_extraction, LanguageRegistry};
 use crate::sync;
 use crate::types::ExtractionResult;
 
@@ -41,6 +42,14 @@ const TOKEN_ENV_VAR: &str = "TOKENSAVE_WORKER_TOKEN";
 /// shared between the spawn-side and the dispatch-side.
 pub const WORKER_SUBCOMMAND: &str = "extract-worker";
 
+/// Sent once after the token: the project settings the parent has already
+/// validated, so a worker never loads them on its own.
+#[derive(Serialize, Deserialize)]
+struct WorkerSetup {
+    /// The project's domain rules file, when it has a valid one.
+    domain_rules: Option<String>,
+}
+
 #[derive(Serialize, Deserialize)]
 struct ExtractRequest {
     project_root: PathBuf,
@@ -113,20 +122,52 @@ fn worker_main() -> io::Result<()> {
         return Err(io::Error::other("worker token mismatch"));
     }
 
-    let registry = LanguageRegistry::new();
+    // The parent compiled these rules before spawning us; rules it accepted
+    // failing here means the two disagree, which must not pass silently.
+    let setup: WorkerSetup = read_message(&mut reader)?;
+    let rules = setup
+        .domain_rules
+        .as_deref()
+        .map(DomainRules::parse)
+        .transpose()
+        .map_err(|e| io::Error::other(format!("domain rules rejected: {e}")))?;
+
+    // Registry of the last project seen.
+    let mut project: Option<(PathBuf, LanguageRegistry)> = None;
     loop {
         let req: ExtractRequest = match read_message(&mut reader) {
             Ok(req) => req,
             Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
             Err(e) => return Err(e),
         };
-        let resp = process_request(&registry, &req);
+        let registry = match &project {
+            Some((root, registry)) if *root == req.project_root => registry,
+            _ => &project.insert(load_project(&req.project_root)).1,
+        };
+        let resp = process_request(registry, rules.as_ref(), &req);
         write_message(&mut writer, &resp)?;
         writer.flush()?;
     }
 }
 
-fn process_request(registry: &LanguageRegistry, req: &ExtractRequest) -> ExtractResponse {
+/// Builds the registry for the project at `project_root`. The grammar
+/// libraries the parent leaves to workers are loaded here; ones that fail to
+/// load are reported like plugins and skipped.
+fn load_project(project_root: &Path) -> (PathBuf, LanguageRegistry) {
+    let mut registry = LanguageRegistry::with_loaded_plugins();
+    if let Ok(config) = crate::config::load_config(project_root) {
+        for e in registry.add_tags_languages(project_root, &config.languages) {
+            eprintln!("[tokensave] skipping language: {e}");
+        }
+    }
+    (project_root.to_path_buf(), registry)
+}
+
+fn process_request(
+    registry: &LanguageRegistry,
+    domain_rules: Option<&DomainRules>,
+    req: &ExtractRequest,
+) -> ExtractResponse {
     let abs_path = req.project_root.join(&req.file_path);
     let Ok(source) = sync::read_source_file(&abs_path) else {
         return ExtractResponse {
@@ -141,8 +182,14 @@ fn process_request(registry: &LanguageRegistry, req: &ExtractRequest) -> Extract
         };
     };
 
-    let mut result = extractor.extract(&req.file_path, &source);
-    result.sanitize();
+    let (mut result, tree) = extractor.extract_with_tree(&req.file_path, &source);
+    finish_extraction(
+        &mut result,
+        &req.file_path,
+        &source,
+        tree.as_ref(),
+        domain_rules,
+    );
     let content_hash = sync::content_hash(&source);
     let size = source.len() as u64;
     let mtime =
@@ -172,6 +219,7 @@ pub struct WorkerPool {
     self_path: PathBuf,
     project_root: PathBuf,
     token: [u8; TOKEN_LEN],
+    setup: Arc<WorkerSetup>,
 }
 
 struct WorkerHandle {
@@ -193,19 +241,28 @@ impl Drop for WorkerHandle {
 
 impl WorkerPool {
     /// Spawn `num_workers` worker processes. Each gets the same token; the
-    /// token is generated once per pool.
-    pub fn new(num_workers: usize, project_root: PathBuf) -> io::Result<Self> {
+    /// token is generated once per pool. Every worker applies `domain_rules`,
+    /// which the caller has already loaded and reported on.
+    pub fn new(
+        num_workers: usize,
+        project_root: PathBuf,
+        domain_rules: Option<&DomainRules>,
+    ) -> io::Result<Self> {
         let self_path = std::env::current_exe()?;
         let token = generate_token()?;
+        let setup = Arc::new(WorkerSetup {
+            domain_rules: domain_rules.map(|rules| rules.source().to_string()),
+        });
         let mut workers = Vec::with_capacity(num_workers);
         for _ in 0..num_workers {
-            workers.push(spawn_worker(&self_path, &token)?);
+            workers.push(spawn_worker(&self_path, &token, &setup)?);
         }
         Ok(Self {
             workers,
             self_path,
             project_root,
             token,
+            setup,
         })
     }
 
@@ -235,6 +292,7 @@ impl WorkerPool {
                 let project_root = self.project_root.clone();
                 let self_path = self.self_path.clone();
                 let token = self.token;
+                let setup = self.setup.clone();
 
                 std::thread::spawn(move || {
                     worker_thread(
@@ -246,6 +304,7 @@ impl WorkerPool {
                         project_root,
                         self_path,
                         token,
+                        &setup,
                         total,
                     );
                 })
@@ -276,6 +335,7 @@ fn worker_thread<F>(
     project_root: PathBuf,
     self_path: PathBuf,
     token: [u8; TOKEN_LEN],
+    setup: &WorkerSetup,
     total: usize,
 ) where
     F: Fn(usize, usize, &str) + Send + Sync,
@@ -316,7 +376,7 @@ fn worker_thread<F>(
             Err(e) => {
                 eprintln!("[tokensave] extraction worker crashed on {file_path}: {e}, respawning");
                 // Old `worker` is dropped here, reaping the dead child.
-                match spawn_worker(&self_path, &token) {
+                match spawn_worker(&self_path, &token, setup) {
                     Ok(new_worker) => worker = new_worker,
                     Err(e) => {
                         eprintln!(
@@ -341,7 +401,11 @@ fn round_trip(worker: &mut WorkerHandle, req: &ExtractRequest) -> io::Result<Ext
     read_message(&mut worker.stdout)
 }
 
-fn spawn_worker(self_path: &Path, token: &[u8; TOKEN_LEN]) -> io::Result<WorkerHandle> {
+fn spawn_worker(
+    self_path: &Path,
+    token: &[u8; TOKEN_LEN],
+    setup: &WorkerSetup,
+) -> io::Result<WorkerHandle> {
     let token_hex = hex::encode(token);
     let mut child = Command::new(self_path)
         .arg(WORKER_SUBCOMMAND)
@@ -362,6 +426,7 @@ fn spawn_worker(self_path: &Path, token: &[u8; TOKEN_LEN]) -> io::Result<WorkerH
     let stdout = BufReader::new(stdout);
 
     stdin.write_all(token)?;
+    write_message(&mut stdin, setup)?;
     stdin.flush()?;
 
     Ok(WorkerHandle {
@@ -92,11 +92,27 @@ impl<'a> GraphQueryManager<'a> {
     ///
     /// Excludes:
     /// - Nodes named `"main"` (program entry points).
-    /// - Nodes whose name starts with `"test"` (likely test functions).
+    /// - Test code: test cases and the code they enclose (see
+    ///   [`TestScopes`](crate::tokensave::TestScopes)).
     /// - `pub` items at file level (they may be part of a public API).
     ///
     /// If `kinds` is non-empty, only nodes of the specified kinds are checked.
     pub async fn find_dead_code(&self, kinds: &[NodeKind]) -> Result<Vec<Node>> {
+        let dead = self.find_dead_code_with_confidence(kinds, 0.0).await?;
+        Ok(dead.into_iter().map(|(node, _)| node).collect())
+    }
+
+    /// Like [`find_dead_code`](Self::find_dead_code), but only incoming
+    /// edges with a confidence of at least `min_confidence` keep a node
+    /// alive. Edges without provenance count as fully confident.
+    ///
+    /// Each node is returned with the confidence of its strongest incoming
+    /// edge, or 0.0 when nothing references it at all.
+    pub async fn find_dead_code_with_confidence(
+        &self,
+        kinds: &[NodeKind],
+        min_confidence: f64,
+    ) -> Result<Vec<(Node, f64)>> {
         let kind_filter = if kinds.is_empty() {
             String::new()
         } else {
@@ -109,24 +125,28 @@ impl<'a> GraphQueryManager<'a> {
             "SELECT id, kind, name, qualified_name, file_path, start_line, end_line,
                     start_column, end_column, docstring, signature, visibility,
                     is_async, branches, loops, returns, max_nesting, unsafe_blocks,
-                    unchecked_calls, assertions, updated_at
+                    unchecked_calls, assertions, updated_at,
+                    (SELECT MAX(COALESCE(confidence, 1.0)) FROM edges WHERE target = nodes.id)
              FROM nodes
              WHERE name != 'main'
-             AND name NOT LIKE 'test%'
+             AND kind != 'test_case'
              AND visibility != 'public'
              {kind_filter}
-             AND NOT EXISTS (SELECT 1 FROM edges WHERE target = nodes.id)"
+             AND NOT EXISTS (
+                 SELECT 1 FROM edges
+                 WHERE target = nodes.id AND COALESCE(confidence, 1.0) >= ?1
+             )"
         );
 
-        let mut rows =
-            self.db
-                .conn()
-                .query(&sql, ())
-                .await
-                .map_err(|e| TokenSaveError::Database {
-                    message: format!("failed to find dead code: {e}"),
-                    operation: "find_dead_code".to_string(),
-                })?;
+        let mut rows = self
+            .db
+            .conn()
+            .query(&sql, libsql::params![min_confidence])
+            .await
+            .map_err(|e| TokenSaveError::Database {
+                message: format!("failed to find dead code: {e}"),
+                operation: "find_dead_code".to_string(),
+            })?;
 
         let mut dead = Vec::new();
         while let Some(row) = rows.next().await.map_err(|e| TokenSaveError::Database {
@@ -134,9 +154,13 @@ impl<'a> GraphQueryManager<'a> {
             operation: "find_dead_code".to_string(),
         })? {
             let node = row_to_node_dead_code(&row)?;
-            dead.push(node);
+            let strongest = row.get::<Option<f64>>(21)?.unwrap_or(0.0);
+            dead.push((node, strongest));
         }
 
+        let test_cases = self.db.get_nodes_by_kind(NodeKind::TestCase).await?;
+        let tests = crate::tokensave::TestScopes::new(&test_cases);
+        dead.retain(|(node, _)| !tests.contains(node));
         Ok(dead)
     }
 
@@ -336,7 +360,8 @@ impl<'a> GraphQueryManager<'a> {
     /// Builds a file-level directed adjacency map from the code graph.
     ///
     /// For each file, collects all files it depends on via `calls`, `uses`,
-    /// `extends`, or `implements` edges. Self-edges are excluded.
+    /// `extends`, or `implements` edges. Self-edges and edges into external
+    /// dependencies are excluded.
     ///
     /// When `path_prefix` is `Some`, only files under that prefix are included
     /// (both as sources and targets).
@@ -349,7 +374,8 @@ impl<'a> GraphQueryManager<'a> {
                    JOIN nodes n1 ON e.source = n1.id \
                    JOIN nodes n2 ON e.target = n2.id \
                    WHERE e.kind IN ('calls', 'uses', 'extends', 'implements') \
-                   AND n1.file_path != n2.file_path";
+                   AND n1.file_path != n2.file_path \
+                   AND n2.kind != 'external_symbol'";
 
         let mut rows =
             self.db
@@ -9,15 +9,51 @@ use crate::types::*;
 /// optional edge used to reach it (the first node has `None`).
 pub type GraphPath = Vec<(Node, Option<Edge>)>;
 
+/// Edge kinds that connect a type to the types it extends or implements.
+const HIERARCHY_EDGES: &[EdgeKind] = &[EdgeKind::Implements, EdgeKind::Extends];
+
 /// Performs graph traversal operations on the code graph.
 pub struct GraphTraverser<'a> {
     db: &'a Database,
+    /// Whether traversals follow virtual dispatch between abstract methods
+    /// and their implementations.
+    virtual_dispatch: bool,
+    /// Edges whose confidence is below this threshold are not followed.
+    min_confidence: f64,
 }
 
 impl<'a> GraphTraverser<'a> {
     /// Creates a new `GraphTraverser` backed by the given database.
     pub fn new(db: &'a Database) -> Self {
-        Self { db }
+        Self {
+            db,
+            virtual_dispatch: false,
+            min_confidence: 0.0,
+        }
+    }
+
+    /// Enables or disables virtual dispatch expansion.
+    ///
+    /// When enabled, `traverse_bfs`, `get_callers`, `get_callees` and
+    /// `get_impact_radius` treat a call to an interface, trait or abstract
+    /// method as a possible call to each of its implementations: walking
+    /// callers (incoming) from an implementation also visits the methods it
+    /// implements, and walking callees (outgoing) from an abstract method
+    /// also visits its implementations. These hops are reported with
+    /// `EdgeKind::Virtual` edges and do not count towards the depth.
+    #[must_use]
+    pub fn with_virtual_dispatch(mut self, enabled: bool) -> Self {
+        self.virtual_dispatch = enabled;
+        self
+    }
+
+    /// Skips edges whose confidence (see [`Edge::confidence`]) is below
+    /// `min_confidence` in every traversal. Defaults to 0.0, which follows
+    /// all edges.
+    #[must_use]
+    pub fn with_min_confidence(mut self, min_confidence: f64) -> Self {
+        self.min_confidence = min_confidence;
+        self
     }
 
     /// Performs a breadth-first traversal starting from `start_id`.
@@ -39,8 +75,8 @@ impl<'a> GraphTraverser<'a> {
         let mut result_edges: Vec<Edge> = Vec::new();
         let mut roots: Vec<String> = Vec::new();
 
-        // Queue holds (node_id, current_depth).
-        let mut queue: VecDeque<(String, u32)> = VecDeque::new();
+        // Queue holds (node_id, current_depth, reached_by_virtual_dispatch).
+        let mut queue: VecDeque<(String, u32, bool)> = VecDeque::new();
 
         // Optionally include the start node.
         if let Some(start_node) = self.db.get_node_by_id(start_id).await? {
@@ -49,7 +85,7 @@ impl<'a> GraphTraverser<'a> {
                 roots.push(start_id.to_string());
                 result_nodes.push(start_node);
             }
-            queue.push_back((start_id.to_string(), 0));
+            queue.push_back((start_id.to_string(), 0, false));
         } else {
             return Ok(Subgraph {
                 nodes: Vec::new(),
@@ -60,7 +96,20 @@ impl<'a> GraphTraverser<'a> {
 
         let edge_filter = opts.edge_kinds.as_deref().unwrap_or(&[]);
 
-        while let Some((current_id, depth)) = queue.pop_front() {
+        while let Some((current_id, depth, reached_virtually)) = queue.pop_front() {
+            if self.virtual_dispatch && !reached_virtually {
+                for (node, edge) in self.virtual_links(&current_id, &opts.direction).await? {
+                    if !visited.insert(node.id.clone()) {
+                        continue;
+                    }
+                    queue.push_back((node.id.clone(), depth, true));
+                    result_edges.push(edge);
+                    if Self::node_matches_filter(&node, opts) {
+                        result_nodes.push(node);
+                    }
+                }
+            }
+
             if depth >= opts.max_depth {
                 continue;
             }
@@ -122,21 +171,21 @@ impl<'a> GraphTraverser<'a> {
                             if !visited.contains(&child_id) {
                                 visited.insert(child_id.clone());
                                 result_edges.push(child_edge);
-                                queue.push_back((child_id, depth + 1));
+                                queue.push_back((child_id, depth + 1, false));
                             }
                         }
                     }
 
                     result_nodes.push(neighbor_node.clone());
                     result_edges.push(edge.clone());
-                    queue.push_back((neighbor_id, depth + 1));
+                    queue.push_back((neighbor_id, depth + 1, false));
 
                     if result_nodes.len() >= opts.limit as usize {
                         break;
                     }
                 } else {
                     result_edges.push(edge.clone());
-                    queue.push_back((neighbor_id, depth + 1));
+                    queue.push_back((neighbor_id, depth + 1, false));
                 }
             }
         }
@@ -255,7 +304,10 @@ impl<'a> GraphTraverser<'a> {
 
     /// Gets all nodes that call the given node, up to `max_depth` levels.
     ///
-    /// Follows incoming `Calls` edges to find callers transitively.
+    /// Follows incoming `Calls` edges to find callers transitively. With
+    /// virtual dispatch enabled, the interface, trait and abstract methods a
+    /// visited method implements are reported too (with an
+    /// `EdgeKind::Virtual` edge) and their callers followed.
     pub async fn get_callers(&self, node_id: &str, max_depth: usize) -> Result<Vec<(Node, Edge)>> {
         debug_assert!(!node_id.is_empty(), "get_callers called with empty node_id");
         debug_assert!(max_depth > 0, "get_callers max_depth must be positive");
@@ -263,18 +315,31 @@ impl<'a> GraphTraverser<'a> {
         let mut visited: HashSet<String> = HashSet::new();
         visited.insert(node_id.to_string());
 
-        let mut queue: VecDeque<(String, usize)> = VecDeque::new();
-        queue.push_back((node_id.to_string(), 0));
+        let mut queue: VecDeque<(String, usize, bool)> = VecDeque::new();
+        queue.push_back((node_id.to_string(), 0, false));
+
+        while let Some((current_id, depth, reached_virtually)) = queue.pop_front() {
+            if self.virtual_dispatch && !reached_virtually {
+                let links = self
+                    .virtual_links(&current_id, &TraversalDirection::Incoming)
+                    .await?;
+                for (node, edge) in links {
+                    if visited.insert(node.id.clone()) {
+                        queue.push_back((node.id.clone(), depth, true));
+                        results.push((node, edge));
+                    }
+                }
+            }
 
-        while let Some((current_id, depth)) = queue.pop_front() {
             if depth >= max_depth {
                 continue;
             }
 
-            let edges = self
+            let mut edges = self
                 .db
                 .get_incoming_edges(&current_id, &[EdgeKind::Calls])
                 .await?;
+            self.retain_confident(&mut edges);
 
             let caller_ids: Vec<String> = edges
                 .iter()
@@ -300,7 +365,7 @@ impl<'a> GraphTraverser<'a> {
 
                 if let Some(caller_node) = caller_map.get(caller_id) {
                     visited.insert(caller_id.clone());
-                    queue.push_back((caller_id.clone(), depth + 1));
+                    queue.push_back((caller_id.clone(), depth + 1, false));
                     results.push((caller_node.clone(), edge));
                 }
             }
@@ -311,7 +376,10 @@ impl<'a> GraphTraverser<'a> {
 
     /// Gets all nodes that the given node calls, up to `max_depth` levels.
     ///
-    /// Follows outgoing `Calls` edges to find callees transitively.
+    /// Follows outgoing `Calls` edges to find callees transitively. With
+    /// virtual dispatch enabled, calls to an interface, trait or abstract
+    /// method also reach each of its implementations (reported with an
+    /// `EdgeKind::Virtual` edge), whose callees are followed in turn.
     pub async fn get_callees(&self, node_id: &str, max_depth: usize) -> Result<Vec<(Node, Edge)>> {
         debug_assert!(!node_id.is_empty(), "get_callees called with empty node_id");
         debug_assert!(max_depth > 0, "get_callees max_depth must be positive");
@@ -319,18 +387,31 @@ impl<'a> GraphTraverser<'a> {
         let mut visited: HashSet<String> = HashSet::new();
         visited.insert(node_id.to_string());
 
-        let mut queue: VecDeque<(String, usize)> = VecDeque::new();
-        queue.push_back((node_id.to_string(), 0));
+        let mut queue: VecDeque<(String, usize, bool)> = VecDeque::new();
+        queue.push_back((node_id.to_string(), 0, false));
+
+        while let Some((current_id, depth, reached_virtually)) = queue.pop_front() {
+            if self.virtual_dispatch && !reached_virtually {
+                let links = self
+                    .virtual_links(&current_id, &TraversalDirection::Outgoing)
+                    .await?;
+                for (node, edge) in links {
+                    if visited.insert(node.id.clone()) {
+                        queue.push_back((node.id.clone(), depth, true));
+                        results.push((node, edge));
+                    }
+                }
+            }
 
-        while let Some((current_id, depth)) = queue.pop_front() {
             if depth >= max_depth {
                 continue;
             }
 
-            let edges = self
+            let mut edges = self
                 .db
                 .get_outgoing_edges(&current_id, &[EdgeKind::Calls])
                 .await?;
+            self.retain_confident(&mut edges);
 
             let callee_ids: Vec<String> = edges
                 .iter()
@@ -356,7 +437,7 @@ impl<'a> GraphTraverser<'a> {
 
                 if let Some(callee_node) = callee_map.get(callee_id) {
                     visited.insert(callee_id.clone());
-                    queue.push_back((callee_id.clone(), depth + 1));
+                    queue.push_back((callee_id.clone(), depth + 1, false));
                     results.push((callee_node.clone(), edge));
                 }
             }
@@ -369,6 +450,8 @@ impl<'a> GraphTraverser<'a> {
     /// indirectly reference or call this node.
     ///
     /// Performs a BFS over incoming edges of all kinds up to `max_depth`.
+    /// With virtual dispatch enabled, dependents of the interface, trait and
+    /// abstract methods the node implements are included as well.
     pub async fn get_impact_radius(&self, node_id: &str, max_depth: usize) -> Result<Subgraph> {
         debug_assert!(
             !node_id.is_empty(),
@@ -512,7 +595,8 @@ impl<'a> GraphTraverser<'a> {
 
         while let Some(current_id) = queue.pop_front() {
             // Get outgoing edges.
-            let outgoing = self.db.get_outgoing_edges(&current_id, edge_kinds).await?;
+            let mut outgoing = self.db.get_outgoing_edges(&current_id, edge_kinds).await?;
+            self.retain_confident(&mut outgoing);
             for edge in outgoing {
                 let neighbor = edge.target.clone();
                 if !visited.contains(&neighbor) {
@@ -533,7 +617,8 @@ impl<'a> GraphTraverser<'a> {
             }
 
             // Also get incoming edges (traverse bidirectionally for path finding).
-            let incoming = self.db.get_incoming_edges(&current_id, edge_kinds).await?;
+            let mut incoming = self.db.get_incoming_edges(&current_id, edge_kinds).await?;
+            self.retain_confident(&mut incoming);
             for edge in incoming {
                 let neighbor = edge.source.clone();
                 if !visited.contains(&neighbor) {
@@ -594,14 +679,23 @@ impl<'a> GraphTraverser<'a> {
         edge_kinds: &[EdgeKind],
         direction: &TraversalDirection,
     ) -> Result<Vec<Edge>> {
-        match direction {
-            TraversalDirection::Outgoing => self.db.get_outgoing_edges(node_id, edge_kinds).await,
-            TraversalDirection::Incoming => self.db.get_incoming_edges(node_id, edge_kinds).await,
+        let mut edges = match direction {
+            TraversalDirection::Outgoing => self.db.get_outgoing_edges(node_id, edge_kinds).await?,
+            TraversalDirection::Incoming => self.db.get_incoming_edges(node_id, edge_kinds).await?,
             TraversalDirection::Both => {
                 let mut edges = self.db.get_outgoing_edges(node_id, edge_kinds).await?;
                 edges.extend(self.db.get_incoming_edges(node_id, edge_kinds).await?);
-                Ok(edges)
+                edges
             }
+        };
+        self.retain_confident(&mut edges);
+        Ok(edges)
+    }
+
+    /// Drops edges below the traverser's confidence threshold.
+    fn retain_confident(&self, edges: &mut Vec<Edge>) {
+        if self.min_confidence > 0.0 {
+            edges.retain(|edge| edge.confidence() >= self.min_confidence);
         }
     }
 
@@ -624,6 +718,104 @@ impl<'a> GraphTraverser<'a> {
         }
     }
 
+    /// Returns the virtual dispatch links of a method node.
+    ///
+    /// `Incoming` yields the abstract methods the node implements (edges run
+    /// from the abstract method to the node), `Outgoing` yields the node's
+    /// implementations (edges run from the node to each implementation), and
+    /// `Both` yields both. Non-method nodes have no links.
+    async fn virtual_links(
+        &self,
+        node_id: &str,
+        direction: &TraversalDirection,
+    ) -> Result<Vec<(Node, Edge)>> {
+        let Some(method) = self.db.get_node_by_id(node_id).await? else {
+            return Ok(Vec::new());
+        };
+        if !is_dispatchable_kind(&method.kind) {
+            return Ok(Vec::new());
+        }
+
+        let mut links = Vec::new();
+        if matches!(
+            direction,
+            TraversalDirection::Incoming | TraversalDirection::Both
+        ) {
+            for base in self.related_methods(&method, true).await? {
+                let edge = virtual_edge(&base.id, &method.id);
+                links.push((base, edge));
+            }
+        }
+        if matches!(
+            direction,
+            TraversalDirection::Outgoing | TraversalDirection::Both
+        ) {
+            for implementation in self.related_methods(&method, false).await? {
+                let edge = virtual_edge(&method.id, &implementation.id);
+                links.push((implementation, edge));
+            }
+        }
+        Ok(links)
+    }
+
+    /// Finds same-named methods on the types related to `method`'s
+    /// containing type through `Implements`/`Extends` edges.
+    ///
+    /// With `up` set, the supertypes are searched (the methods `method`
+    /// overrides or implements); otherwise the subtypes are searched (the
+    /// methods overriding or implementing `method`). The hierarchy is walked
+    /// transitively.
+    async fn related_methods(&self, method: &Node, up: bool) -> Result<Vec<Node>> {
+        let mut visited: HashSet<String> = HashSet::new();
+        let mut queue: VecDeque<String> = VecDeque::new();
+        for edge in self
+            .db
+            .get_incoming_edges(&method.id, &[EdgeKind::Contains])
+            .await?
+        {
+            if visited.insert(edge.source.clone()) {
+                queue.push_back(edge.source);
+            }
+        }
+
+        let mut related: Vec<Node> = Vec::new();
+        let mut seen_methods: HashSet<String> = HashSet::new();
+        while let Some(type_id) = queue.pop_front() {
+            let mut hierarchy = if up {
+                self.db
+                    .get_outgoing_edges(&type_id, HIERARCHY_EDGES)
+                    .await?
+            } else {
+                self.db
+                    .get_incoming_edges(&type_id, HIERARCHY_EDGES)
+                    .await?
+            };
+            self.retain_confident(&mut hierarchy);
+            for edge in hierarchy {
+                let next = if up { edge.target } else { edge.source };
+                if !visited.insert(next.clone()) {
+                    continue;
+                }
+                let children = self
+                    .db
+                    .get_outgoing_edges(&next, &[EdgeKind::Contains])
+                    .await?;
+                let child_ids: Vec<String> = children.into_iter().map(|e| e.target).collect();
+                for child in self.db.get_nodes_by_ids(&child_ids).await? {
+                    if child.id != method.id
+                        && child.name == method.name
+                        && is_dispatchable_kind(&child.kind)
+                        && seen_methods.insert(child.id.clone())
+                    {
+                        related.push(child);
+                    }
+                }
+                queue.push_back(next);
+            }
+        }
+        Ok(related)
+    }
+
     /// Checks whether a node passes the optional `node_kinds` filter.
     fn node_matches_filter(node: &Node, opts: &TraversalOptions) -> bool {
         if let Some(ref kinds) = opts.node_kinds {
@@ -648,3 +840,22 @@ fn is_container_kind(kind: &NodeKind) -> bool {
             | NodeKind::Enum
     )
 }
+
+/// Returns true if a node kind is a method that calls can dispatch through.
+fn is_dispatchable_kind(kind: &NodeKind) -> bool {
+    matches!(
+        kind,
+        NodeKind::Method | NodeKind::AbstractMethod | NodeKind::StructMethod | NodeKind::Function
+    )
+}
+
+/// Builds the synthetic edge reported for a virtual dispatch hop.
+fn virtual_edge(source: &str, target: &str) -> Edge {
+    Edge {
+        source: source.to_string(),
+        target: target.to_string(),
+        kind: EdgeKind::Virtual,
+        line: None,
+        provenance: None,
+    }
+}
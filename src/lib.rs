@@ -34,6 +34,8 @@ pub mod graph;
 pub mod hooks;
 pub mod mcp;
 pub mod monitor;
+pub mod packages;
+pub mod plugins;
 pub mod project_watcher;
 pub mod resolution;
 pub mod sync;
@@ -6,6 +6,7 @@ use std::path::Path;
 use std::process;
 
 use tokensave::context::{format_context_as_json, format_context_as_markdown};
+use tokensave::resolution::UnresolvedReport;
 use tokensave::tokensave::TokenSave;
 use tokensave::types::*;
 
@@ -185,6 +186,21 @@ enum Commands {
         #[arg(short, long)]
         json: bool,
     },
+    /// Report references that could not be resolved or were resolved ambiguously
+    Unresolved {
+        /// Project path
+        #[arg(short, long)]
+        path: Option<String>,
+        /// Only report references from files under this directory
+        #[arg(long)]
+        filter: Option<String>,
+        /// Maximum groups to show per grouping
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+        /// Output as JSON
+        #[arg(short, long)]
+        json: bool,
+    },
     /// Find test files affected by changed source files
     Affected {
         /// Changed file paths
@@ -195,7 +211,7 @@ enum Commands {
         /// Read file list from stdin (one per line)
         #[arg(long)]
         stdin: bool,
-        /// Max dependency traversal depth
+        /// Max call or use hops from a changed symbol
         #[arg(short, long, default_value = "5")]
         depth: usize,
         /// Custom glob filter for test files
@@ -327,6 +343,27 @@ enum Commands {
         #[command(subcommand)]
         action: BranchAction,
     },
+    /// Manage extractor plugins
+    Plugin {
+        #[command(subcommand)]
+        action: PluginAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum PluginAction {
+    /// List installed plugins and the languages they extract
+    List,
+    /// Install a plugin from a directory holding plugin.toml and its library
+    Install {
+        /// Plugin directory
+        path: String,
+    },
+    /// Remove an installed plugin
+    Remove {
+        /// Plugin name
+        name: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -893,6 +930,53 @@ async fn run(cli: Cli) -> tokensave::errors::Result<()> {
                 }
             }
         }
+        Commands::Unresolved {
+            path,
+            filter,
+            limit,
+            json,
+        } => {
+            let project_path = tokensave::config::resolve_path(path);
+            let cg = ensure_initialized(&project_path).await?;
+            let diagnostics = cg.diagnose_references(filter.as_deref()).await?;
+            let mut report = UnresolvedReport::new(&diagnostics);
+            report.truncate(limit);
+
+            if json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&report).unwrap_or_default()
+                );
+            } else {
+                println!("{} unresolved or ambiguous references", report.total);
+                for (reason, count) in &report.by_reason {
+                    println!("  {:<24} {}", reason, count);
+                }
+                for (title, groups) in [
+                    ("By name", &report.by_name),
+                    ("By file", &report.by_file),
+                    ("By language", &report.by_language),
+                ] {
+                    if groups.is_empty() {
+                        continue;
+                    }
+                    println!("\n{}:", title);
+                    for group in groups {
+                        let reasons: Vec<String> = group
+                            .reasons
+                            .iter()
+                            .map(|(reason, count)| format!("{reason} {count}"))
+                            .collect();
+                        println!(
+                            "  {:>6}  {}  ({})",
+                            group.count,
+                            group.key,
+                            reasons.join(", ")
+                        );
+                    }
+                }
+            }
+        }
         Commands::Affected {
             files,
             path,
@@ -1421,6 +1505,9 @@ async fn run(cli: Cli) -> tokensave::errors::Result<()> {
         Commands::Branch { action } => {
             handle_branch_action(action).await?;
         }
+        Commands::Plugin { action } => {
+            handle_plugin_action(action)?;
+        }
     }
     Ok(())
 }
@@ -1652,6 +1739,54 @@ async fn handle_branch_action(action: BranchAction) -> tokensave::errors::Result
     Ok(())
 }
 
+fn handle_plugin_action(action: PluginAction) -> tokensave::errors::Result<()> {
+    use tokensave::plugins;
+
+    let plugin_dir = || {
+        plugins::default_plugin_dir().ok_or_else(|| tokensave::errors::TokenSaveError::Config {
+            message: "cannot determine home directory".to_string(),
+        })
+    };
+    match action {
+        PluginAction::List => {
+            let installed = plugins::discover_plugins();
+            if installed.is_empty() {
+                eprintln!("No plugins installed. Run `tokensave plugin install <dir>` to add one.");
+                return Ok(());
+            }
+            for plugin in &installed {
+                let extensions: Vec<&str> = plugin.manifest.extensions().collect();
+                eprintln!(
+                    "  {} {} — {} (.{})  {}",
+                    plugin.name(),
+                    plugin.manifest.plugin.version,
+                    plugin.manifest.language.name,
+                    extensions.join(", ."),
+                    plugin.dir.display()
+                );
+            }
+        }
+        PluginAction::Install { path } => {
+            let plugin = plugins::install_plugin(Path::new(&path), &plugin_dir()?)?;
+            eprintln!(
+                "Installed plugin '{}' {} into {}",
+                plugin.name(),
+                plugin.manifest.plugin.version,
+                plugin.dir.display()
+            );
+            eprintln!("Run `tokensave sync` in your projects to index its files.");
+        }
+        PluginAction::Remove { name } => {
+            if plugins::remove_plugin(&name, &plugin_dir()?)? {
+                eprintln!("Removed plugin '{name}'.");
+            } else {
+                eprintln!("Plugin '{name}' is not installed.");
+            }
+        }
+    }
+    Ok(())
+}
+
 fn format_size(bytes: u64) -> String {
     if bytes >= 1_073_741_824 {
         format!("{:.1} GB", bytes as f64 / 1_073_741_824.0)
@@ -1902,7 +2037,8 @@ fn check_for_update(
 // - src/doctor.rs (health checks)
 // - src/tokensave.rs (is_test_file)
 
-/// BFS through file dependents to find test files affected by changes.
+/// Finds the files holding the test code affected by changes to
+/// `changed_files` (see [`TokenSave::affected_tests`]).
 async fn find_affected_tests(
     cg: &TokenSave,
     changed_files: &[String],
@@ -1917,52 +2053,13 @@ async fn find_affected_tests(
         max_depth > 0,
         "find_affected_tests max_depth must be positive"
     );
-    use std::collections::{HashSet, VecDeque};
-
     let custom_glob = custom_filter.and_then(|p| glob::Pattern::new(p).ok());
+    let affected = cg
+        .affected_tests(changed_files, max_depth, custom_glob.as_ref())
+        .await?;
 
-    let matches_test = |path: &str| -> bool {
-        if let Some(ref g) = custom_glob {
-            g.matches(path)
-        } else {
-            tokensave::tokensave::is_test_file(path)
-        }
-    };
-
-    let mut affected: HashSet<String> = HashSet::new();
-    let mut visited: HashSet<String> = HashSet::new();
-
-    // Seed: changed files that are themselves tests go directly into the result
-    let mut queue: VecDeque<(String, usize)> = VecDeque::new();
-    for file in changed_files {
-        if matches_test(file) {
-            affected.insert(file.clone());
-        }
-        if visited.insert(file.clone()) {
-            queue.push_back((file.clone(), 0));
-        }
-    }
-
-    // BFS through file dependents
-    while let Some((file, depth)) = queue.pop_front() {
-        if depth >= max_depth {
-            continue;
-        }
-        let dependents = cg.get_file_dependents(&file).await?;
-        for dep in dependents {
-            if !visited.insert(dep.clone()) {
-                continue;
-            }
-            if matches_test(&dep) {
-                affected.insert(dep.clone());
-            } else {
-                queue.push_back((dep, depth + 1));
-            }
-        }
-    }
-
-    let mut result: Vec<String> = affected.into_iter().collect();
-    result.sort();
+    let mut result: Vec<String> = affected.into_iter().map(|n| n.file_path).collect();
+    result.dedup();
     Ok(result)
 }
 // direct test 1774739850
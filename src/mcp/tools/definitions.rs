@@ -130,6 +130,13 @@ pub fn get_tool_definitions() -> Vec<ToolDefinition> {
         def_session_end(),
         def_body(),
         def_todos(),
+        def_unresolved(),
+        def_external(),
+        def_stale_docs(),
+        def_build_targets(),
+        def_routes(),
+        def_sql_access(),
+        def_signature_search(),
     ];
     debug_assert!(
         !definitions.is_empty(),
@@ -148,7 +155,7 @@ fn def_search() -> ToolDefinition {
     def_always_load(
         "tokensave_search",
         "Search Symbols",
-        "Search for symbols (functions, structs, traits, etc.) in the code graph by name or keyword.",
+        "Search for symbols (functions, structs, traits, etc.) in the code graph by name or keyword. Domain symbols (routes, commands, registered primitives) include `links` to the functions that register or implement them.",
         json!({
             "type": "object",
             "properties": {
@@ -248,6 +255,14 @@ fn def_callers() -> ToolDefinition {
                 "max_depth": {
                     "type": "number",
                     "description": "Maximum traversal depth (default: 3)"
+                },
+                "virtual_dispatch": {
+                    "type": "boolean",
+                    "description": "If true, also report the interface, trait or abstract methods a method implements, and their callers (default: false)"
+                },
+                "min_confidence": {
+                    "type": "number",
+                    "description": "Only follow edges whose resolution confidence is at least this value, from 0 to 1 (default: 0)"
                 }
             },
             "required": ["node_id"]
@@ -270,6 +285,14 @@ fn def_callees() -> ToolDefinition {
                 "max_depth": {
                     "type": "number",
                     "description": "Maximum traversal depth (default: 3)"
+                },
+                "virtual_dispatch": {
+                    "type": "boolean",
+                    "description": "If true, calls to interface, trait or abstract methods also reach their implementations (default: false)"
+                },
+                "min_confidence": {
+                    "type": "number",
+                    "description": "Only follow edges whose resolution confidence is at least this value, from 0 to 1 (default: 0)"
                 }
             },
             "required": ["node_id"]
@@ -292,6 +315,14 @@ fn def_impact() -> ToolDefinition {
                 "max_depth": {
                     "type": "number",
                     "description": "Maximum traversal depth (default: 3)"
+                },
+                "virtual_dispatch": {
+                    "type": "boolean",
+                    "description": "If true, include dependents of the interface, trait or abstract methods the node implements (default: false)"
+                },
+                "min_confidence": {
+                    "type": "number",
+                    "description": "Only follow edges whose resolution confidence is at least this value, from 0 to 1 (default: 0)"
                 }
             },
             "required": ["node_id"]
@@ -347,7 +378,7 @@ fn def_affected() -> ToolDefinition {
     def(
         "tokensave_affected",
         "Affected Tests",
-        "Find test files affected by changed source files via dependency graph traversal.",
+        "Find the tests affected by changed source files: the test cases that call or use their symbols, directly or through other code, and the files holding them.",
         json!({
             "type": "object",
             "properties": {
@@ -358,11 +389,11 @@ fn def_affected() -> ToolDefinition {
                 },
                 "depth": {
                     "type": "number",
-                    "description": "Maximum dependency traversal depth (default: 5)"
+                    "description": "Maximum number of call or use hops from a changed symbol (default: 5)"
                 },
                 "filter": {
                     "type": "string",
-                    "description": "Custom glob pattern for test files (default: common test patterns)"
+                    "description": "Custom glob pattern for test files, whose code is then the test code (default: detected test cases, and common test paths for languages without test detection)"
                 }
             },
             "required": ["files"]
@@ -374,7 +405,7 @@ fn def_dead_code() -> ToolDefinition {
     def(
         "tokensave_dead_code",
         "Dead Code",
-        "Find symbols with no incoming edges (potentially unreachable code). Excludes main, test functions, and public items.",
+        "Find symbols with no incoming edges (potentially unreachable code). Excludes main, test cases and the code inside test modules, and public items.",
         json!({
             "type": "object",
             "properties": {
@@ -382,6 +413,10 @@ fn def_dead_code() -> ToolDefinition {
                     "type": "array",
                     "items": { "type": "string" },
                     "description": "Node kinds to check (default: [\"function\", \"method\"])"
+                },
+                "min_confidence": {
+                    "type": "number",
+                    "description": "Ignore incoming edges whose resolution confidence is below this value, from 0 to 1 (default: 0)"
                 }
             }
         }),
@@ -496,6 +531,10 @@ fn def_rename_preview() -> ToolDefinition {
                 "node_id": {
                     "type": "string",
                     "description": "The unique node ID to find references for"
+                },
+                "min_confidence": {
+                    "type": "number",
+                    "description": "Only list edges whose resolution confidence is at least this value, from 0 to 1 (default: 0)"
                 }
             },
             "required": ["node_id"]
@@ -580,10 +619,18 @@ fn def_coupling() -> ToolDefinition {
     def(
         "tokensave_coupling",
         "Coupling",
-        "Rank files by coupling: fan_in (most depended on) or fan_out (most dependencies).",
+        "Rank files by coupling: fan_in (most depended on) or fan_out (most dependencies). \
+         With granularity 'package', ranks the crates/packages/modules declared by Cargo.toml, \
+         package.json, go.mod, pom.xml and settings.gradle instead, alongside the dependencies \
+         their manifests declare.",
         json!({
             "type": "object",
             "properties": {
+                "granularity": {
+                    "type": "string",
+                    "enum": ["file", "package"],
+                    "description": "file: rank files. package: rank manifest packages by the code dependencies between their files (default: file)"
+                },
                 "direction": {
                     "type": "string",
                     "enum": ["fan_in", "fan_out"],
@@ -869,7 +916,7 @@ fn def_test_map() -> ToolDefinition {
     def(
         "tokensave_test_map",
         "Test Map",
-        "Map source symbols to their test functions. Shows which tests cover which source code.",
+        "Map source symbols to the test cases that call them (#[test], Go TestXxx, pytest, JUnit @Test, Jest/Mocha it/describe). Shows which tests cover which source code.",
         json!({
             "type": "object",
             "properties": {
@@ -1148,10 +1195,16 @@ fn def_dsm() -> ToolDefinition {
     def(
         "tokensave_dsm",
         "Design Structure Matrix",
-        "Get the Design Structure Matrix: file dependency summary showing clusters, density, and layering violations.",
+        "Get the Design Structure Matrix: file dependency summary showing clusters, density, and layering violations. \
+         With granularity 'package', each manifest package (crate, npm package, Go module, Maven/Gradle project) is one row.",
         json!({
             "type": "object",
             "properties": {
+                "granularity": {
+                    "type": "string",
+                    "enum": ["file", "package"],
+                    "description": "file: one row per file. package: one row per manifest package (default: file)"
+                },
                 "path": {
                     "type": "string",
                     "description": "Filter to files under this directory path"
@@ -1305,6 +1358,220 @@ fn def_todos() -> ToolDefinition {
     )
 }
 
+fn def_unresolved() -> ToolDefinition {
+    def(
+        "tokensave_unresolved",
+        "Unresolved references",
+        "Report references the graph could not resolve or resolved ambiguously, grouped by \
+         referenced name, file, and language. Each group lists why its references stayed \
+         unresolved (unknown_name, filtered_by_known_names, no_fitting_candidate, \
+         tied_candidates) so blind spots like generated code or missing include paths \
+         stand out.",
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Filter to references from files under this directory path (relative to project root)"
+                },
+                "reason": {
+                    "type": "string",
+                    "description": "Only report references with this reason (e.g. \"unknown_name\")"
+                },
+                "limit": {
+                    "type": "number",
+                    "description": "Maximum groups per grouping (default: 20)"
+                }
+            }
+        }),
+    )
+}
+
+fn def_external() -> ToolDefinition {
+    def(
+        "tokensave_external",
+        "External dependencies",
+        "List the external dependencies (crates, npm packages, Python modules, Java packages, \
+         standard libraries) the code calls into, with how many symbols, references and callers \
+         each has. With a package, list the symbols used from it and the project functions that \
+         reference each one, e.g. to answer which functions call into reqwest.",
+        json!({
+            "type": "object",
+            "properties": {
+                "package": {
+                    "type": "string",
+                    "description": "Dependency to list the used symbols and callers of (e.g. \"tokio\", \"java.util\")"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Only count references from files under this directory path (relative to project root)"
+                },
+                "limit": {
+                    "type": "number",
+                    "description": "Maximum packages, or symbols of the package, to return (default: 50)"
+                }
+            }
+        }),
+    )
+}
+
+fn def_stale_docs() -> ToolDefinition {
+    def(
+        "tokensave_stale_docs",
+        "Stale documentation",
+        "List Markdown sections whose inline code or code blocks mention symbols that no longer \
+         exist in the code (renamed or deleted functions and methods of a project type or \
+         module, such as `Graph::removed_fn`), with the stale names and their lines, so docs can \
+         be fixed alongside the code.",
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Filter to docs under this directory path (relative to project root)"
+                },
+                "limit": {
+                    "type": "number",
+                    "description": "Maximum sections to return (default: 50)"
+                }
+            }
+        }),
+    )
+}
+
+fn def_build_targets() -> ToolDefinition {
+    def(
+        "tokensave_build_targets",
+        "Build targets of a file",
+        "List the Make, CMake, Bazel and Buck build targets that include a source file, and \
+         every target that must be rebuilt if it changes: the targets including it or a file \
+         depending on it, and the targets depending on those.",
+        json!({
+            "type": "object",
+            "properties": {
+                "file": {
+                    "type": "string",
+                    "description": "Source file path (relative to project root)"
+                }
+            },
+            "required": ["file"]
+        }),
+    )
+}
+
+fn def_routes() -> ToolDefinition {
+    def(
+        "tokensave_routes",
+        "HTTP routes",
+        "List the HTTP API surface: the routes registered with web frameworks (axum, actix, \
+         Rocket, Express, Flask, FastAPI, Spring, net/http, gin, echo, chi), each with its \
+         method and path, the handler functions serving it, and the functions requesting it \
+         through fetch, axios, requests, reqwest or net/http with a literal URL.",
+        json!({
+            "type": "object",
+            "properties": {
+                "prefix": {
+                    "type": "string",
+                    "description": "Only list routes whose path starts with this prefix (e.g. \"/api/users\")"
+                },
+                "method": {
+                    "type": "string",
+                    "description": "Only list routes serving this HTTP method (e.g. \"POST\")"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Only list routes registered in files under this directory path (relative to project root)"
+                },
+                "limit": {
+                    "type": "number",
+                    "description": "Maximum routes to return (default: 100)"
+                }
+            }
+        }),
+    )
+}
+
+fn def_sql_access() -> ToolDefinition {
+    def(
+        "tokensave_sql_access",
+        "SQL table access",
+        "Find the code reading and writing a SQL table or view: the functions whose SQL \
+         string literals (or the views and SQL files whose statements) select from it, and \
+         those inserting into, updating or deleting from it, with the columns each one \
+         touches. Tables and columns come from CREATE TABLE / ALTER TABLE statements in \
+         .sql files and migrations.",
+        json!({
+            "type": "object",
+            "properties": {
+                "table": {
+                    "type": "string",
+                    "description": "Name of the table or view (e.g. \"edges\")"
+                },
+                "column": {
+                    "type": "string",
+                    "description": "Only list accesses to this column of the table"
+                },
+                "access": {
+                    "type": "string",
+                    "enum": ["read", "write"],
+                    "description": "Only list readers or only writers (default: both)"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Only list code in files under this directory path (relative to project root)"
+                },
+                "limit": {
+                    "type": "number",
+                    "description": "Maximum readers and writers to return, each (default: 100)"
+                }
+            },
+            "required": ["table"]
+        }),
+    )
+}
+
+fn def_signature_search() -> ToolDefinition {
+    def(
+        "tokensave_signature_search",
+        "Signature search",
+        "Find functions and methods by the types they take and return, e.g. everything \
+         accepting a `&Database` or returning `Result<Subgraph>`. Types are compared after \
+         normalization, which ignores references, pointers, qualifiers, nullability and \
+         module paths, so `Database` matches `&mut crate::db::Database` and `*db.Database`. \
+         Generic arguments match structurally: `Result<Subgraph>` matches \
+         `Result<Subgraph, Error>`, a bare `List` matches any `List`, and `_` or a single \
+         capital letter stands for any type (`Map<String, _>`). Signatures are extracted \
+         for Rust, Go, Python, Java, TypeScript/JavaScript, C, C++, C#, Kotlin, Swift and \
+         Scala only; the result's `note` names the other languages in scope that declare \
+         functions.",
+        json!({
+            "type": "object",
+            "properties": {
+                "param_type": {
+                    "type": "string",
+                    "description": "Type one of the parameters must have (e.g. \"Database\", \"List<User>\")"
+                },
+                "return_type": {
+                    "type": "string",
+                    "description": "Type the callable must return (e.g. \"Result<Subgraph>\")"
+                },
+                "nested": {
+                    "type": "boolean",
+                    "description": "Also match types nested in generic arguments, so \"User\" finds Promise<User> (default: false)"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Only search files under this directory path (relative to project root)"
+                },
+                "limit": {
+                    "type": "number",
+                    "description": "Maximum callables to return (default: 50)"
+                }
+            }
+        }),
+    )
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
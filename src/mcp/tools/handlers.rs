@@ -4,20 +4,25 @@
 //! the JSON arguments, calls the appropriate `TokenSave` method, and
 //! formats the result.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Write as _;
 
 use serde_json::{json, Value};
 
 use crate::context::format_context_as_markdown;
 use crate::errors::{Result, TokenSaveError};
+use crate::extraction::signatures::TypePattern;
 use crate::graph::health::{
     acyclicity_score, compute_composite_health, dependency_depth, depth_score, gini_coefficient,
     gini_label, modularity_score, HealthDimensions,
 };
 use crate::graph::queries::GraphQueryManager;
+use crate::packages::PackageMap;
+use crate::resolution::{is_doc_file, UnresolvedReport};
 use crate::tokensave::TokenSave;
-use crate::types::{BuildContextOptions, EdgeKind, NodeKind, Visibility};
+use crate::types::{
+    split_route, BuildContextOptions, EdgeKind, Node, NodeKind, TypeSpec, Visibility,
+};
 
 use super::{ToolResult, MAX_RESPONSE_CHARS};
 
@@ -33,6 +38,44 @@ fn require_node_id(args: &Value) -> Result<&str> {
         })
 }
 
+/// Extracts the optional `min_confidence` parameter, clamped to 0.0..=1.0.
+/// Defaults to 0.0, which keeps every edge.
+fn min_confidence_arg(args: &Value) -> f64 {
+    args.get("min_confidence")
+        .and_then(serde_json::Value::as_f64)
+        .map_or(0.0, |v| v.clamp(0.0, 1.0))
+}
+
+/// Extracts the optional `granularity` parameter: `true` for `"package"`,
+/// `false` for the default `"file"`.
+fn by_package_arg(args: &Value) -> Result<bool> {
+    match args
+        .get("granularity")
+        .and_then(|v| v.as_str())
+        .unwrap_or("file")
+    {
+        "file" => Ok(false),
+        "package" => Ok(true),
+        other => Err(TokenSaveError::Config {
+            message: format!("invalid granularity '{other}'. Valid values: file, package"),
+        }),
+    }
+}
+
+/// Collapses the file dependency graph under `path_prefix` into one between
+/// the manifest packages containing the files, keyed by package node ID.
+async fn package_adjacency(
+    cg: &TokenSave,
+    path_prefix: Option<&str>,
+) -> Result<(PackageMap, HashMap<String, HashSet<String>>)> {
+    let packages = PackageMap::load(cg.db()).await?;
+    let files = GraphQueryManager::new(cg.db())
+        .build_file_adjacency(path_prefix)
+        .await?;
+    let adjacency = packages.collapse(&files);
+    Ok((packages, adjacency))
+}
+
 /// Returns the user-provided `path` argument, falling back to the scope
 /// prefix when the argument is absent. This makes listing tools
 /// automatically scoped to the subdirectory the server was launched from.
@@ -136,6 +179,13 @@ pub async fn handle_tool_call(
         "tokensave_session_end" => handle_session_end(cg, args, scope_prefix).await,
         "tokensave_body" => handle_body(cg, args, scope_prefix).await,
         "tokensave_todos" => handle_todos(cg, args, scope_prefix).await,
+        "tokensave_unresolved" => handle_unresolved(cg, args, scope_prefix).await,
+        "tokensave_external" => handle_external(cg, args, scope_prefix).await,
+        "tokensave_stale_docs" => handle_stale_docs(cg, args, scope_prefix).await,
+        "tokensave_build_targets" => handle_build_targets(cg, args).await,
+        "tokensave_routes" => handle_routes(cg, args, scope_prefix).await,
+        "tokensave_sql_access" => handle_sql_access(cg, args, scope_prefix).await,
+        "tokensave_signature_search" => handle_signature_search(cg, args, scope_prefix).await,
         _ => Err(TokenSaveError::Config {
             message: format!("unknown tool: {tool_name}"),
         }),
@@ -193,20 +243,22 @@ async fn handle_search(
 
     let touched_files = unique_file_paths(results.iter().map(|r| r.node.file_path.as_str()));
 
-    let items: Vec<Value> = results
-        .iter()
-        .map(|r| {
-            json!({
-                "id": r.node.id,
-                "name": r.node.name,
-                "kind": r.node.kind.as_str(),
-                "file": r.node.file_path,
-                "line": r.node.start_line,
-                "signature": r.node.signature,
-                "score": r.score,
-            })
-        })
-        .collect();
+    let mut items: Vec<Value> = Vec::with_capacity(results.len());
+    for r in &results {
+        let mut item = json!({
+            "id": r.node.id,
+            "name": r.node.name,
+            "kind": r.node.kind.as_str(),
+            "file": r.node.file_path,
+            "line": r.node.start_line,
+            "signature": r.node.signature,
+            "score": r.score,
+        });
+        if r.node.kind == NodeKind::DomainSymbol {
+            item["links"] = Value::Array(domain_symbol_links(cg, &r.node.id).await?);
+        }
+        items.push(item);
+    }
 
     let output = serde_json::to_string_pretty(&items).unwrap_or_default();
     Ok(ToolResult {
@@ -217,6 +269,36 @@ async fn handle_search(
     })
 }
 
+/// The nodes a domain symbol is linked to: what it implements or uses, and
+/// the functions that register or dispatch on it.
+async fn domain_symbol_links(cg: &TokenSave, node_id: &str) -> Result<Vec<Value>> {
+    let outgoing = cg.get_outgoing_edges(node_id).await?;
+    let incoming = cg.get_incoming_edges(node_id).await?;
+    let edges = outgoing
+        .iter()
+        .filter(|e| e.kind != EdgeKind::Contains)
+        .map(|e| (e, &e.target))
+        .chain(
+            incoming
+                .iter()
+                .filter(|e| matches!(e.kind, EdgeKind::Registers | EdgeKind::Dispatches))
+                .map(|e| (e, &e.source)),
+        );
+    let mut links = Vec::new();
+    for (edge, other) in edges {
+        if let Some(node) = cg.get_node(other).await? {
+            links.push(json!({
+                "edge_kind": edge.kind.as_str(),
+                "name": node.name,
+                "kind": node.kind.as_str(),
+                "file": node.file_path,
+                "line": node.start_line,
+            }));
+        }
+    }
+    Ok(links)
+}
+
 /// Handles `tokensave_context` tool calls.
 async fn handle_context(
     cg: &TokenSave,
@@ -319,7 +401,10 @@ async fn handle_context(
             if matches!(node.kind, NodeKind::Trait | NodeKind::Interface)
                 && node.visibility == Visibility::Pub
             {
-                let implementors = cg.get_callers(&node.id, 1).await.unwrap_or_default();
+                let implementors = cg
+                    .get_callers(&node.id, 1, false, 0.0)
+                    .await
+                    .unwrap_or_default();
                 let impl_count = implementors
                     .iter()
                     .filter(|(_, e)| matches!(e.kind, crate::types::EdgeKind::Implements))
@@ -355,7 +440,10 @@ async fn handle_context(
             for file in &file_paths {
                 let nodes = cg.get_nodes_by_file(file).await.unwrap_or_default();
                 for node in &nodes {
-                    let callers = cg.get_callers(&node.id, 2).await.unwrap_or_default();
+                    let callers = cg
+                        .get_callers(&node.id, 2, false, 0.0)
+                        .await
+                        .unwrap_or_default();
                     for (caller, _) in &callers {
                         if crate::tokensave::is_test_file(&caller.file_path) {
                             test_files.insert(caller.file_path.clone());
@@ -400,7 +488,25 @@ async fn handle_callers(cg: &TokenSave, args: Value) -> Result<ToolResult> {
         .and_then(serde_json::Value::as_u64)
         .map_or(3, |v| v.min(10) as usize);
 
-    let results = cg.get_callers(node_id, max_depth).await?;
+    let virtual_dispatch = args
+        .get("virtual_dispatch")
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false);
+    let min_confidence = min_confidence_arg(&args);
+
+    let mut results = cg
+        .get_callers(node_id, max_depth, virtual_dispatch, min_confidence)
+        .await?;
+    // Doc sections that mention the node are listed too, as direct callers,
+    // so a change to it shows which docs go stale.
+    if max_depth > 0 {
+        let mentions = cg.get_doc_mentions(node_id).await?;
+        results.extend(
+            mentions
+                .into_iter()
+                .filter(|(_, edge)| edge.confidence() >= min_confidence),
+        );
+    }
 
     let touched_files = unique_file_paths(results.iter().map(|(n, _)| n.file_path.as_str()));
 
@@ -414,6 +520,7 @@ async fn handle_callers(cg: &TokenSave, args: Value) -> Result<ToolResult> {
                 "file": node.file_path,
                 "line": node.start_line,
                 "edge_kind": edge.kind.as_str(),
+                "confidence": edge.confidence(),
             })
         })
         .collect();
@@ -436,7 +543,15 @@ async fn handle_callees(cg: &TokenSave, args: Value) -> Result<ToolResult> {
         .and_then(serde_json::Value::as_u64)
         .map_or(3, |v| v.min(10) as usize);
 
-    let results = cg.get_callees(node_id, max_depth).await?;
+    let virtual_dispatch = args
+        .get("virtual_dispatch")
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false);
+    let min_confidence = min_confidence_arg(&args);
+
+    let results = cg
+        .get_callees(node_id, max_depth, virtual_dispatch, min_confidence)
+        .await?;
 
     let touched_files = unique_file_paths(results.iter().map(|(n, _)| n.file_path.as_str()));
 
@@ -450,6 +565,7 @@ async fn handle_callees(cg: &TokenSave, args: Value) -> Result<ToolResult> {
                 "file": node.file_path,
                 "line": node.start_line,
                 "edge_kind": edge.kind.as_str(),
+                "confidence": edge.confidence(),
             })
         })
         .collect();
@@ -472,10 +588,26 @@ async fn handle_impact(cg: &TokenSave, args: Value) -> Result<ToolResult> {
         .and_then(serde_json::Value::as_u64)
         .map_or(3, |v| v.min(10) as usize);
 
-    let subgraph = cg.get_impact_radius(node_id, max_depth).await?;
+    let virtual_dispatch = args
+        .get("virtual_dispatch")
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false);
+    let min_confidence = min_confidence_arg(&args);
+
+    let subgraph = cg
+        .get_impact_radius(node_id, max_depth, virtual_dispatch, min_confidence)
+        .await?;
 
     let touched_files = unique_file_paths(subgraph.nodes.iter().map(|n| n.file_path.as_str()));
 
+    // The traversal walks incoming edges, so each dependent is the source of
+    // the edge that reached it. The start node has no confidence.
+    let mut confidence: HashMap<&str, f64> = HashMap::new();
+    for edge in &subgraph.edges {
+        let entry = confidence.entry(edge.source.as_str()).or_insert(0.0);
+        *entry = entry.max(edge.confidence());
+    }
+
     let nodes: Vec<Value> = subgraph
         .nodes
         .iter()
@@ -486,6 +618,7 @@ async fn handle_impact(cg: &TokenSave, args: Value) -> Result<ToolResult> {
                 "kind": n.kind.as_str(),
                 "file": n.file_path,
                 "line": n.start_line,
+                "confidence": confidence.get(n.id.as_str()),
             })
         })
         .collect();
@@ -712,52 +845,29 @@ async fn handle_affected(cg: &TokenSave, args: Value) -> Result<ToolResult> {
 
     let custom_filter = args.get("filter").and_then(|v| v.as_str());
     let custom_glob = custom_filter.and_then(|p| glob::Pattern::new(p).ok());
+    let affected = cg
+        .affected_tests(&files, max_depth, custom_glob.as_ref())
+        .await?;
 
-    let matches_test = |path: &str| -> bool {
-        if let Some(ref g) = custom_glob {
-            g.matches(path)
-        } else {
-            crate::tokensave::is_test_file(path)
-        }
-    };
-
-    let mut affected: HashSet<String> = HashSet::new();
-    let mut visited: HashSet<String> = HashSet::new();
-    let mut queue: std::collections::VecDeque<(String, usize)> = std::collections::VecDeque::new();
-
-    for file in &files {
-        if matches_test(file) {
-            affected.insert(file.clone());
-        }
-        if visited.insert(file.clone()) {
-            queue.push_back((file.clone(), 0));
-        }
-    }
-
-    while let Some((file, depth)) = queue.pop_front() {
-        if depth >= max_depth {
-            continue;
-        }
-        let dependents = cg.get_file_dependents(&file).await?;
-        for dep in dependents {
-            if !visited.insert(dep.clone()) {
-                continue;
-            }
-            if matches_test(&dep) {
-                affected.insert(dep.clone());
-            } else {
-                queue.push_back((dep, depth + 1));
-            }
-        }
-    }
-
-    let mut result: Vec<String> = affected.into_iter().collect();
-    result.sort();
+    let mut result: Vec<String> = affected.iter().map(|n| n.file_path.clone()).collect();
+    result.dedup();
+    let test_cases: Vec<Value> = affected
+        .iter()
+        .filter(|n| n.kind == NodeKind::TestCase)
+        .map(|n| {
+            json!({
+                "name": n.name,
+                "file": n.file_path,
+                "line": n.start_line,
+            })
+        })
+        .collect();
 
     let touched_files = result.clone();
     let output = json!({
         "changed_files": files,
         "affected_tests": result,
+        "test_cases": test_cases,
         "count": result.len(),
     });
 
@@ -785,14 +895,18 @@ async fn handle_dead_code(
         },
     );
 
-    let dead = cg.find_dead_code(&kinds).await?;
-    let dead = filter_by_scope(dead, scope_prefix, |n| &n.file_path);
+    let min_confidence = min_confidence_arg(&args);
+
+    let dead = cg
+        .find_dead_code_with_confidence(&kinds, min_confidence)
+        .await?;
+    let dead = filter_by_scope(dead, scope_prefix, |(n, _)| &n.file_path);
 
-    let touched_files = unique_file_paths(dead.iter().map(|n| n.file_path.as_str()));
+    let touched_files = unique_file_paths(dead.iter().map(|(n, _)| n.file_path.as_str()));
 
     let items: Vec<Value> = dead
         .iter()
-        .map(|n| {
+        .map(|(n, strongest)| {
             json!({
                 "id": n.id,
                 "name": n.name,
@@ -800,6 +914,7 @@ async fn handle_dead_code(
                 "file": n.file_path,
                 "line": n.start_line,
                 "signature": n.signature,
+                "max_reference_confidence": strongest,
             })
         })
         .collect();
@@ -859,7 +974,7 @@ async fn handle_diff_context(cg: &TokenSave, args: Value) -> Result<ToolResult>
             }));
 
             // Get impact radius for each modified symbol
-            let impact = cg.get_impact_radius(&node.id, depth).await?;
+            let impact = cg.get_impact_radius(&node.id, depth, false, 0.0).await?;
             for impacted in &impact.nodes {
                 if impacted.id != node.id {
                     impacted_symbols.push(json!({
@@ -1191,8 +1306,11 @@ async fn handle_rename_preview(cg: &TokenSave, args: Value) -> Result<ToolResult
     };
 
     // Get all edges referencing this node
-    let incoming = cg.get_incoming_edges(node_id).await?;
-    let outgoing = cg.get_outgoing_edges(node_id).await?;
+    let min_confidence = min_confidence_arg(&args);
+    let mut incoming = cg.get_incoming_edges(node_id).await?;
+    let mut outgoing = cg.get_outgoing_edges(node_id).await?;
+    incoming.retain(|e| e.confidence() >= min_confidence);
+    outgoing.retain(|e| e.confidence() >= min_confidence);
 
     let mut references: Vec<Value> = Vec::new();
     let mut touched: Vec<String> = Vec::new();
@@ -1214,6 +1332,7 @@ async fn handle_rename_preview(cg: &TokenSave, args: Value) -> Result<ToolResult
                 "line": source_node.start_line,
                 "edge_kind": edge.kind.as_str(),
                 "edge_line": edge.line,
+                "confidence": edge.confidence(),
             }));
         }
     }
@@ -1231,15 +1350,21 @@ async fn handle_rename_preview(cg: &TokenSave, args: Value) -> Result<ToolResult
                 "line": target_node.start_line,
                 "edge_kind": edge.kind.as_str(),
                 "edge_line": edge.line,
+                "confidence": edge.confidence(),
             }));
         }
     }
 
     let touched_files = unique_file_paths(touched.iter().map(std::string::String::as_str));
+    let doc_reference_count = references
+        .iter()
+        .filter(|r| r["direction"] == "incoming" && r["file"].as_str().is_some_and(is_doc_file))
+        .count();
 
     let output = json!({
         "node": node_info,
         "reference_count": references.len(),
+        "doc_reference_count": doc_reference_count,
         "references": references,
     });
 
@@ -1488,21 +1613,53 @@ async fn handle_coupling(
         .map_or(10, |v| v.min(100) as usize);
 
     let path_prefix = effective_path(&args, scope_prefix);
+    let by_package = by_package_arg(&args)?;
 
-    let results = cg.get_file_coupling(fan_in, path_prefix, limit).await?;
-
-    let items: Vec<Value> = results
-        .iter()
-        .map(|(file, count)| {
-            json!({
-                "file": file,
-                "coupled_files": count,
+    let items: Vec<Value> = if by_package {
+        let (packages, adjacency) = package_adjacency(cg, path_prefix).await?;
+        let mut ranking: Vec<(&str, &str, usize)> = adjacency
+            .iter()
+            .map(|(id, deps)| {
+                let count = if fan_in {
+                    adjacency.values().filter(|d| d.contains(id)).count()
+                } else {
+                    deps.len()
+                };
+                (id.as_str(), packages.name(id).unwrap_or(id), count)
             })
-        })
-        .collect();
+            .filter(|(_, _, count)| *count > 0)
+            .collect();
+        ranking.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.1.cmp(b.1)));
+        ranking.truncate(limit);
+        ranking
+            .into_iter()
+            .map(|(id, name, count)| {
+                let (dependencies, dependents) = packages.declared_counts(id);
+                json!({
+                    "package": name,
+                    "directory": packages.dir(id).unwrap_or_default(),
+                    "coupled_packages": count,
+                    "declared_dependencies": dependencies,
+                    "declared_dependents": dependents,
+                })
+            })
+            .collect()
+    } else {
+        cg.get_file_coupling(fan_in, path_prefix, limit)
+            .await?
+            .iter()
+            .map(|(file, count)| {
+                json!({
+                    "file": file,
+                    "coupled_files": count,
+                })
+            })
+            .collect()
+    };
 
     let output = json!({
         "direction": direction,
+        "granularity": if by_package { "package" } else { "file" },
         "result_count": items.len(),
         "ranking": items,
     });
@@ -2843,7 +3000,10 @@ async fn handle_pr_context(cg: &TokenSave, args: Value) -> Result<ToolResult> {
 
             // Check if this symbol has callers outside changed files — if so, it's
             // a modification to an existing API. Otherwise it's likely new.
-            let callers = cg.get_callers(&node.id, 1).await.unwrap_or_default();
+            let callers = cg
+                .get_callers(&node.id, 1, false, 0.0)
+                .await
+                .unwrap_or_default();
             let has_external_callers = callers
                 .iter()
                 .any(|(c, _)| !changed_files.contains(&c.file_path));
@@ -2876,7 +3036,10 @@ async fn handle_pr_context(cg: &TokenSave, args: Value) -> Result<ToolResult> {
         }
         let nodes = cg.get_nodes_by_file(file).await.unwrap_or_default();
         for node in &nodes {
-            let impact = cg.get_impact_radius(&node.id, 2).await.unwrap_or_default();
+            let impact = cg
+                .get_impact_radius(&node.id, 2, false, 0.0)
+                .await
+                .unwrap_or_default();
             for impacted in &impact.nodes {
                 if crate::tokensave::is_test_file(&impacted.file_path) {
                     affected_tests.insert(impacted.file_path.clone());
@@ -3051,19 +3214,23 @@ async fn handle_test_map(
         });
     };
 
+    let tests = cg.test_scopes().await?;
     let mut coverage_map: Vec<Value> = Vec::new();
     let mut uncovered: Vec<Value> = Vec::new();
     let mut all_test_files: HashSet<String> = HashSet::new();
 
     for node in &source_nodes {
-        if !matches!(node.kind, NodeKind::Function | NodeKind::Method) {
+        if !matches!(node.kind, NodeKind::Function | NodeKind::Method) || tests.contains(node) {
             continue;
         }
 
-        let callers = cg.get_callers(&node.id, 3).await.unwrap_or_default();
+        let callers = cg
+            .get_callers(&node.id, 3, false, 0.0)
+            .await
+            .unwrap_or_default();
         let test_callers: Vec<Value> = callers
             .iter()
-            .filter(|(n, _)| crate::tokensave::is_test_file(&n.file_path))
+            .filter(|(n, _)| tests.contains(n))
             .map(|(n, _)| {
                 all_test_files.insert(n.file_path.clone());
                 json!({
@@ -3889,10 +4056,35 @@ async fn handle_dsm(cg: &TokenSave, args: Value, scope_prefix: Option<&str>) ->
         .get("max_files")
         .and_then(serde_json::Value::as_u64)
         .map_or(30, |v| v.min(200) as usize);
+    let by_package = by_package_arg(&args)?;
+    let (unit, count_key) = if by_package {
+        ("packages", "package_count")
+    } else {
+        ("files", "file_count")
+    };
 
-    let adj = GraphQueryManager::new(cg.db())
-        .build_file_adjacency(path_prefix)
-        .await?;
+    // At package granularity each package stands in for a file, named by
+    // the package and clustered by the directory above the package's own.
+    let mut package_dirs: HashMap<String, String> = HashMap::new();
+    let adj = if by_package {
+        let (packages, adjacency) = package_adjacency(cg, path_prefix).await?;
+        let label = |id: &str| packages.name(id).unwrap_or(id).to_string();
+        for id in adjacency.keys() {
+            package_dirs.insert(label(id), packages.dir(id).unwrap_or_default().to_string());
+        }
+        adjacency
+            .iter()
+            .map(|(id, deps)| (label(id), deps.iter().map(|d| label(d)).collect()))
+            .collect::<HashMap<String, HashSet<String>>>()
+    } else {
+        GraphQueryManager::new(cg.db())
+            .build_file_adjacency(path_prefix)
+            .await?
+    };
+    let parent_dir = |path: &str| {
+        path.rfind('/')
+            .map_or_else(|| ".".to_string(), |i| path[..i].to_string())
+    };
 
     let file_count = adj.len();
     let edge_count: usize = adj.values().map(std::collections::HashSet::len).sum();
@@ -3905,9 +4097,10 @@ async fn handle_dsm(cg: &TokenSave, args: Value, scope_prefix: Option<&str>) ->
     // Group files by parent directory
     let mut dir_to_files: HashMap<String, Vec<String>> = HashMap::new();
     for file in adj.keys() {
-        let dir = file
-            .rfind('/')
-            .map_or_else(|| ".".to_string(), |i| file[..i].to_string());
+        let dir = match package_dirs.get(file) {
+            Some(package_dir) => parent_dir(package_dir),
+            None => parent_dir(file),
+        };
         dir_to_files.entry(dir).or_default().push(file.clone());
     }
 
@@ -3940,14 +4133,14 @@ async fn handle_dsm(cg: &TokenSave, args: Value, scope_prefix: Option<&str>) ->
                     }
                     json!({
                         "directory": dir,
-                        "file_count": files.len(),
+                        count_key: files.len(),
                         "internal_edges": internal,
                         "outgoing_edges": outgoing,
                         "incoming_edges": incoming,
                     })
                 })
                 .collect();
-            clusters.sort_by_key(|c| std::cmp::Reverse(c["file_count"].as_u64().unwrap_or(0)));
+            clusters.sort_by_key(|c| std::cmp::Reverse(c[count_key].as_u64().unwrap_or(0)));
             json!({ "clusters": clusters })
         }
         "matrix" => {
@@ -3966,12 +4159,12 @@ async fn handle_dsm(cg: &TokenSave, args: Value, scope_prefix: Option<&str>) ->
             let selected: Vec<String> = file_edge_counts.into_iter().map(|(f, _)| f).collect();
             let _selected_set: HashSet<&str> = selected.iter().map(String::as_str).collect();
 
-            // Build short filenames (last component)
+            // Build short filenames (last component); package names stay whole
             let short_names: Vec<String> = selected
                 .iter()
-                .map(|f| {
-                    f.rfind('/')
-                        .map_or_else(|| f.clone(), |i| f[i + 1..].to_string())
+                .map(|f| match f.rfind('/') {
+                    Some(i) if !by_package => f[i + 1..].to_string(),
+                    _ => f.clone(),
                 })
                 .collect();
 
@@ -3989,16 +4182,17 @@ async fn handle_dsm(cg: &TokenSave, args: Value, scope_prefix: Option<&str>) ->
             }
 
             json!({
-                "files": short_names,
+                unit: short_names,
                 "matrix": matrix,
-                "note": format!("Top {} files by edge count shown", n),
+                "note": format!("Top {n} {unit} by edge count shown"),
             })
         }
         _ => {
             // stats (default)
             let largest_cluster = dir_to_files.values().map(Vec::len).max().unwrap_or(0);
             json!({
-                "files": file_count,
+                "granularity": if by_package { "package" } else { "file" },
+                unit: file_count,
                 "edges": edge_count,
                 "density": (density * 10000.0).round() / 10000.0,
                 "clusters": dir_to_files.len(),
@@ -4047,21 +4241,20 @@ async fn handle_test_risk(
     let all_nodes = cg.get_all_nodes().await?;
     let all_edges = cg.get_all_edges().await?;
 
-    // Build a map from node_id to file_path for fast lookup
-    let node_to_file: HashMap<String, String> = all_nodes
+    let tests = cg.test_scopes().await?;
+    // The nodes that are test code: test cases and the helpers they enclose
+    let test_code: HashSet<&str> = all_nodes
         .iter()
-        .map(|n| (n.id.clone(), n.file_path.clone()))
+        .filter(|n| tests.contains(n))
+        .map(|n| n.id.as_str())
         .collect();
 
-    // Source functions/methods (exclude test files, exclude test-named nodes)
+    // Source functions/methods (exclude test code)
     let source_fns: Vec<_> = all_nodes
         .iter()
         .filter(|n| {
             matches!(n.kind, NodeKind::Function | NodeKind::Method)
-                && !crate::tokensave::is_test_file(&n.file_path)
-                && !n.name.starts_with("test_")
-                && !n.name.starts_with("test")
-                && !n.file_path.contains("/test")
+                && !test_code.contains(n.id.as_str())
         })
         .filter(|n| {
             path_prefix.is_none_or(|pfx| {
@@ -4083,15 +4276,11 @@ async fn handle_test_risk(
         }
     }
 
-    // Determine which nodes are tested: called by nodes in test files
+    // Determine which nodes are tested: called by test code
     let mut tested: HashSet<String> = HashSet::new();
     for e in &all_edges {
-        if e.kind == EdgeKind::Calls {
-            if let Some(caller_file) = node_to_file.get(&e.source) {
-                if crate::tokensave::is_test_file(caller_file) {
-                    tested.insert(e.target.clone());
-                }
-            }
+        if e.kind == EdgeKind::Calls && test_code.contains(e.source.as_str()) {
+            tested.insert(e.target.clone());
         }
     }
 
@@ -4679,6 +4868,474 @@ async fn handle_todos(
     })
 }
 
+/// Handles `tokensave_unresolved`: groups unresolved and ambiguous
+/// references by name, file and language with the reason for each.
+async fn handle_unresolved(
+    cg: &TokenSave,
+    args: Value,
+    scope_prefix: Option<&str>,
+) -> Result<ToolResult> {
+    let path = effective_path(&args, scope_prefix);
+    let reason = args.get("reason").and_then(|v| v.as_str());
+    let limit = args
+        .get("limit")
+        .and_then(serde_json::Value::as_u64)
+        .map_or(20, |v| v.min(500) as usize);
+
+    let mut diagnostics = cg.diagnose_references(path).await?;
+    if let Some(reason) = reason {
+        diagnostics.retain(|d| d.reason.as_str() == reason);
+    }
+    let mut report = UnresolvedReport::new(&diagnostics);
+    report.truncate(limit);
+
+    let formatted = serde_json::to_string_pretty(&report).unwrap_or_default();
+    Ok(ToolResult {
+        value: json!({
+            "content": [{ "type": "text", "text": truncate_response(&formatted) }]
+        }),
+        touched_files: vec![],
+    })
+}
+
+/// Handles `tokensave_stale_docs`: lists doc sections whose code mentions
+/// did not resolve to any symbol.
+async fn handle_stale_docs(
+    cg: &TokenSave,
+    args: Value,
+    scope_prefix: Option<&str>,
+) -> Result<ToolResult> {
+    let path = effective_path(&args, scope_prefix);
+    let limit = args
+        .get("limit")
+        .and_then(serde_json::Value::as_u64)
+        .map_or(50, |v| v.min(500) as usize);
+
+    let mut sections = cg.stale_doc_references(path).await?;
+    let section_count = sections.len();
+    sections.truncate(limit);
+    let touched_files = unique_file_paths(sections.iter().map(|(n, _)| n.file_path.as_str()));
+    let sections: Vec<Value> = sections
+        .iter()
+        .map(|(section, refs)| {
+            json!({
+                "section": section.name,
+                "node_id": section.id,
+                "file": section.file_path,
+                "line": section.start_line,
+                "stale": refs
+                    .iter()
+                    .map(|r| json!({ "name": r.reference_name, "line": r.line }))
+                    .collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+
+    let output = json!({
+        "section_count": section_count,
+        "sections": sections,
+    });
+    let formatted = serde_json::to_string_pretty(&output).unwrap_or_default();
+    Ok(ToolResult {
+        value: json!({
+            "content": [{ "type": "text", "text": truncate_response(&formatted) }]
+        }),
+        touched_files,
+    })
+}
+
+/// Handles `tokensave_build_targets`: lists the build targets including a
+/// file and those to rebuild when it changes.
+async fn handle_build_targets(cg: &TokenSave, args: Value) -> Result<ToolResult> {
+    let file = args
+        .get("file")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| TokenSaveError::Config {
+            message: "missing required parameter: file".to_string(),
+        })?;
+
+    let (including, rebuilt) = cg.build_targets_for_file(file).await?;
+    let touched_files = unique_file_paths(rebuilt.iter().map(|n| n.file_path.as_str()));
+    let target = |n: &Node| {
+        json!({
+            "name": n.name,
+            "node_id": n.id,
+            "file": n.file_path,
+            "line": n.start_line,
+        })
+    };
+    let output = json!({
+        "file": file,
+        "targets": including.iter().map(target).collect::<Vec<_>>(),
+        "rebuild": rebuilt.iter().map(target).collect::<Vec<_>>(),
+    });
+    let formatted = serde_json::to_string_pretty(&output).unwrap_or_default();
+    Ok(ToolResult {
+        value: json!({
+            "content": [{ "type": "text", "text": truncate_response(&formatted) }]
+        }),
+        touched_files,
+    })
+}
+
+/// Handles `tokensave_routes`: lists the HTTP routes with their handlers
+/// and the functions requesting them.
+async fn handle_routes(
+    cg: &TokenSave,
+    args: Value,
+    scope_prefix: Option<&str>,
+) -> Result<ToolResult> {
+    let prefix = args.get("prefix").and_then(|v| v.as_str()).unwrap_or("");
+    let method = args
+        .get("method")
+        .and_then(|v| v.as_str())
+        .map(str::to_ascii_uppercase);
+    let limit = args
+        .get("limit")
+        .and_then(serde_json::Value::as_u64)
+        .map_or(100, |v| v.min(1000) as usize);
+
+    let routes = filter_by_scope(
+        cg.routes().await?,
+        effective_path(&args, scope_prefix),
+        |(route, ..)| route.file_path.as_str(),
+    );
+    let routes: Vec<_> = routes
+        .into_iter()
+        .filter(|(route, ..)| {
+            split_route(&route.name).is_some_and(|(m, path)| {
+                path.starts_with(prefix)
+                    && method
+                        .as_deref()
+                        .is_none_or(|wanted| m == wanted || m == "ANY")
+            })
+        })
+        .collect();
+    let route_count = routes.len();
+    let shown = &routes[..route_count.min(limit)];
+    let touched_files = unique_file_paths(
+        shown
+            .iter()
+            .flat_map(|(route, handlers, _)| std::iter::once(route).chain(handlers))
+            .map(|n| n.file_path.as_str()),
+    );
+    let function = |n: &Node| {
+        json!({
+            "name": n.name,
+            "kind": n.kind.as_str(),
+            "file": n.file_path,
+            "line": n.start_line,
+        })
+    };
+    let output = json!({
+        "route_count": route_count,
+        "routes": shown
+            .iter()
+            .map(|(route, handlers, callers)| {
+                json!({
+                    "route": route.signature.as_deref().unwrap_or(&route.name),
+                    "node_id": route.id,
+                    "file": route.file_path,
+                    "line": route.start_line,
+                    "handlers": handlers.iter().map(function).collect::<Vec<_>>(),
+                    "callers": callers.iter().map(function).collect::<Vec<_>>(),
+                })
+            })
+            .collect::<Vec<_>>(),
+    });
+    let formatted = serde_json::to_string_pretty(&output).unwrap_or_default();
+    Ok(ToolResult {
+        value: json!({
+            "content": [{ "type": "text", "text": truncate_response(&formatted) }]
+        }),
+        touched_files,
+    })
+}
+
+/// Handles `tokensave_sql_access`: lists the code reading and writing a
+/// SQL table, with the columns each accessor touches.
+async fn handle_sql_access(
+    cg: &TokenSave,
+    args: Value,
+    scope_prefix: Option<&str>,
+) -> Result<ToolResult> {
+    let table = args
+        .get("table")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| TokenSaveError::Config {
+            message: "missing required parameter: table".to_string(),
+        })?
+        .to_ascii_lowercase();
+    let column = args
+        .get("column")
+        .and_then(|v| v.as_str())
+        .map(|c| format!("{table}.{}", c.to_ascii_lowercase()));
+    let access = args.get("access").and_then(|v| v.as_str());
+    let limit = args
+        .get("limit")
+        .and_then(serde_json::Value::as_u64)
+        .map_or(100, |v| v.min(1000) as usize);
+
+    let (tables, accesses) = cg.sql_access(&table).await?;
+    let accesses = filter_by_scope(
+        accesses,
+        effective_path(&args, scope_prefix),
+        |(node, ..)| node.file_path.as_str(),
+    );
+    // Accessors in order, each with the tables and columns it touches.
+    let mut readers: Vec<(Node, Vec<String>)> = Vec::new();
+    let mut writers: Vec<(Node, Vec<String>)> = Vec::new();
+    for (node, object, kind) in accesses {
+        if column.as_ref().is_some_and(|c| *c != object.name) {
+            continue;
+        }
+        let (accessors, wanted) = match kind {
+            EdgeKind::Writes => (&mut writers, access != Some("read")),
+            _ => (&mut readers, access != Some("write")),
+        };
+        if !wanted {
+            continue;
+        }
+        match accessors.iter_mut().find(|(n, _)| n.id == node.id) {
+            Some((_, touched)) => {
+                if !touched.contains(&object.name) {
+                    touched.push(object.name);
+                }
+            }
+            None => accessors.push((node, vec![object.name])),
+        }
+    }
+
+    let touched_files = unique_file_paths(
+        tables.iter().map(|n| n.file_path.as_str()).chain(
+            readers
+                .iter()
+                .chain(&writers)
+                .map(|(n, _)| n.file_path.as_str()),
+        ),
+    );
+    let accessor = |(n, touched): &(Node, Vec<String>)| {
+        json!({
+            "name": n.name,
+            "kind": n.kind.as_str(),
+            "file": n.file_path,
+            "line": n.start_line,
+            "touches": touched,
+        })
+    };
+    let output = json!({
+        "table": table,
+        "definitions": tables
+            .iter()
+            .map(|n| {
+                json!({
+                    "kind": n.kind.as_str(),
+                    "node_id": n.id,
+                    "file": n.file_path,
+                    "line": n.start_line,
+                    "signature": n.signature,
+                })
+            })
+            .collect::<Vec<_>>(),
+        "reader_count": readers.len(),
+        "writer_count": writers.len(),
+        "readers": readers.iter().take(limit).map(accessor).collect::<Vec<_>>(),
+        "writers": writers.iter().take(limit).map(accessor).collect::<Vec<_>>(),
+    });
+    let formatted = serde_json::to_string_pretty(&output).unwrap_or_default();
+    Ok(ToolResult {
+        value: json!({
+            "content": [{ "type": "text", "text": truncate_response(&formatted) }]
+        }),
+        touched_files,
+    })
+}
+
+/// Handles `tokensave_signature_search`: lists the callables taking and
+/// returning the given types, with their parameters and return type.
+async fn handle_signature_search(
+    cg: &TokenSave,
+    args: Value,
+    scope_prefix: Option<&str>,
+) -> Result<ToolResult> {
+    let pattern = |key: &str| -> Result<Option<TypePattern>> {
+        let Some(text) = args.get(key).and_then(|v| v.as_str()) else {
+            return Ok(None);
+        };
+        TypePattern::parse(text)
+            .map(Some)
+            .ok_or_else(|| TokenSaveError::Config {
+                message: format!("{key} names no type: {text}"),
+            })
+    };
+    let param_type = pattern("param_type")?;
+    let return_type = pattern("return_type")?;
+    if param_type.is_none() && return_type.is_none() {
+        return Err(TokenSaveError::Config {
+            message: "provide param_type, return_type or both".to_string(),
+        });
+    }
+    let nested = args
+        .get("nested")
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false);
+    let limit = args
+        .get("limit")
+        .and_then(serde_json::Value::as_u64)
+        .map_or(50, |v| v.min(500) as usize);
+
+    let path = effective_path(&args, scope_prefix);
+
+    let results = cg
+        .signature_search(
+            param_type.as_ref(),
+            return_type.as_ref(),
+            nested,
+            path,
+            limit,
+        )
+        .await?;
+    let unsupported = cg.languages_without_signatures(path).await?;
+    let touched_files = unique_file_paths(results.iter().map(|(n, _)| n.file_path.as_str()));
+    let type_text = |ty: &TypeSpec| ty.text.clone();
+    let mut output = json!({
+        "param_type": param_type.as_ref().map(std::string::ToString::to_string),
+        "return_type": return_type.as_ref().map(std::string::ToString::to_string),
+        "result_count": results.len(),
+        "results": results
+            .iter()
+            .map(|(n, sig)| {
+                json!({
+                    "node_id": n.id,
+                    "name": n.name,
+                    "kind": n.kind.as_str(),
+                    "file": n.file_path,
+                    "line": n.start_line,
+                    "signature": n.signature,
+                    "params": sig
+                        .params
+                        .iter()
+                        .map(|p| {
+                            json!({
+                                "name": p.name,
+                                "type": p.declared_type.as_ref().map(type_text),
+                                "default": p.default_value,
+                                "optional": p.optional,
+                            })
+                        })
+                        .collect::<Vec<_>>(),
+                    "returns": sig.return_type.as_ref().map(type_text),
+                })
+            })
+            .collect::<Vec<_>>(),
+    });
+    // Callables in these languages have no signatures to match.
+    if !unsupported.is_empty() {
+        output["note"] = json!(format!(
+            "signatures not extracted for {}",
+            unsupported.join(", ")
+        ));
+    }
+    let formatted = serde_json::to_string_pretty(&output).unwrap_or_default();
+    Ok(ToolResult {
+        value: json!({
+            "content": [{ "type": "text", "text": truncate_response(&formatted) }]
+        }),
+        touched_files,
+    })
+}
+
+/// Handles `tokensave_external`: summarises the external dependencies the
+/// code references, or the used symbols and callers of one of them.
+async fn handle_external(
+    cg: &TokenSave,
+    args: Value,
+    scope_prefix: Option<&str>,
+) -> Result<ToolResult> {
+    let path = effective_path(&args, scope_prefix);
+    let package = args.get("package").and_then(|v| v.as_str());
+    let limit = args
+        .get("limit")
+        .and_then(serde_json::Value::as_u64)
+        .map_or(50, |v| v.min(500) as usize);
+
+    let usages = cg.db().get_external_usages(path).await?;
+    let output = if let Some(package) = package {
+        // Usages arrive ordered by package and symbol.
+        let mut symbols: Vec<(String, Vec<Value>)> = Vec::new();
+        for (_, symbol, caller, line) in usages.iter().filter(|u| u.0 == package) {
+            if symbols.last().is_none_or(|(s, _)| s != symbol) {
+                symbols.push((symbol.clone(), Vec::new()));
+            }
+            if let Some((_, callers)) = symbols.last_mut() {
+                callers.push(json!({
+                    "name": caller.name,
+                    "kind": caller.kind.as_str(),
+                    "file": caller.file_path,
+                    "line": line.unwrap_or(caller.start_line),
+                }));
+            }
+        }
+        let symbol_count = symbols.len();
+        symbols.truncate(limit);
+        let symbols: Vec<Value> = symbols
+            .into_iter()
+            .map(|(symbol, callers)| {
+                json!({
+                    "symbol": symbol,
+                    "reference_count": callers.len(),
+                    "callers": callers,
+                })
+            })
+            .collect();
+        json!({
+            "package": package,
+            "symbol_count": symbol_count,
+            "symbols": symbols,
+        })
+    } else {
+        let mut packages: BTreeMap<&str, (HashSet<&str>, usize, HashSet<&str>)> = BTreeMap::new();
+        for (package, symbol, caller, _) in &usages {
+            let entry = packages.entry(package.as_str()).or_default();
+            entry.0.insert(symbol.as_str());
+            entry.1 += 1;
+            entry.2.insert(caller.id.as_str());
+        }
+        let mut ranking: Vec<(&str, usize, usize, usize)> = packages
+            .into_iter()
+            .map(|(name, (symbols, references, callers))| {
+                (name, symbols.len(), references, callers.len())
+            })
+            .collect();
+        ranking.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(b.0)));
+        let package_count = ranking.len();
+        ranking.truncate(limit);
+        let packages: Vec<Value> = ranking
+            .into_iter()
+            .map(|(name, symbols, references, callers)| {
+                json!({
+                    "package": name,
+                    "symbol_count": symbols,
+                    "reference_count": references,
+                    "caller_count": callers,
+                })
+            })
+            .collect();
+        json!({
+            "package_count": package_count,
+            "packages": packages,
+        })
+    };
+
+    let formatted = serde_json::to_string_pretty(&output).unwrap_or_default();
+    Ok(ToolResult {
+        value: json!({
+            "content": [{ "type": "text", "text": truncate_response(&formatted) }]
+        }),
+        touched_files: vec![],
+    })
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
@@ -4688,7 +5345,7 @@ mod tests {
     #[test]
     fn test_tool_definitions_complete() {
         let tools = get_tool_definitions();
-        assert_eq!(tools.len(), 50);
+        assert_eq!(tools.len(), 57);
 
         let tool_names: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
         assert!(tool_names.contains(&"tokensave_search"));
@@ -4741,6 +5398,13 @@ mod tests {
         assert!(tool_names.contains(&"tokensave_session_end"));
         assert!(tool_names.contains(&"tokensave_body"));
         assert!(tool_names.contains(&"tokensave_todos"));
+        assert!(tool_names.contains(&"tokensave_unresolved"));
+        assert!(tool_names.contains(&"tokensave_external"));
+        assert!(tool_names.contains(&"tokensave_stale_docs"));
+        assert!(tool_names.contains(&"tokensave_build_targets"));
+        assert!(tool_names.contains(&"tokensave_routes"));
+        assert!(tool_names.contains(&"tokensave_sql_access"));
+        assert!(tool_names.contains(&"tokensave_signature_search"));
     }
 
     #[test]
@@ -0,0 +1,692 @@
+// Rust guideline compliant 2025-10-17
+//! Package manifests: the crates, npm packages, Go modules, Maven artifacts
+//! and Gradle projects a repository is split into.
+//!
+//! [`discover_packages`] reads the manifests found in the directories of the
+//! indexed files, [`package_graph`] turns them into
+//! [`NodeKind::ManifestPackage`] nodes that `Contains` their source files and
+//! `DependsOn` the other packages of the project, and [`PackageMap`] answers
+//! which package a file belongs to.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+use crate::db::Database;
+use crate::errors::Result;
+use crate::sync::read_source_file;
+use crate::tokensave::current_timestamp;
+use crate::types::*;
+
+/// The build tool that declares a package.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Ecosystem {
+    Cargo,
+    Npm,
+    Go,
+    Maven,
+    Gradle,
+}
+
+impl Ecosystem {
+    /// Returns the string representation of this ecosystem.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Ecosystem::Cargo => "cargo",
+            Ecosystem::Npm => "npm",
+            Ecosystem::Go => "go",
+            Ecosystem::Maven => "maven",
+            Ecosystem::Gradle => "gradle",
+        }
+    }
+}
+
+/// Manifest file names, in the order they are looked for in a directory.
+/// Only the first Gradle settings file found is read.
+const MANIFESTS: &[(&str, Ecosystem)] = &[
+    ("Cargo.toml", Ecosystem::Cargo),
+    ("package.json", Ecosystem::Npm),
+    ("go.mod", Ecosystem::Go),
+    ("pom.xml", Ecosystem::Maven),
+    ("settings.gradle.kts", Ecosystem::Gradle),
+    ("settings.gradle", Ecosystem::Gradle),
+];
+
+/// A package declared by a build manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Package {
+    pub ecosystem: Ecosystem,
+    /// Short name: the crate or npm package name, the last segment of a Go
+    /// module path, a Maven `artifactId` or a Gradle project name.
+    pub name: String,
+    /// The name other manifests depend on the package by: the Go module
+    /// path, the Gradle project path (`:lib:core`), otherwise `name`.
+    pub key: String,
+    /// Directory holding the package's sources, relative to the project
+    /// root (empty for the root itself).
+    pub dir: String,
+    /// Path of the manifest declaring the package, relative to the project
+    /// root. Its directory is `dir`.
+    pub manifest: String,
+    /// Keys of the packages of this project the manifest depends on.
+    /// Dependencies from outside the project are dropped.
+    pub dependencies: Vec<String>,
+}
+
+/// Finds the packages declared by manifests in the project root or in any
+/// directory containing one of `files`, plus the Gradle projects their
+/// settings files include. Packages are sorted by directory.
+pub fn discover_packages(project_root: &Path, files: &[String]) -> Vec<Package> {
+    let mut dirs: BTreeSet<String> = BTreeSet::new();
+    dirs.insert(String::new());
+    for file in files {
+        let mut path = file.as_str();
+        while let Some((parent, _)) = path.rsplit_once('/') {
+            if !dirs.insert(parent.to_string()) {
+                break;
+            }
+            path = parent;
+        }
+    }
+
+    let mut packages: Vec<Package> = Vec::new();
+    for dir in &dirs {
+        let mut gradle_seen = false;
+        for (file_name, ecosystem) in MANIFESTS {
+            if *ecosystem == Ecosystem::Gradle && gradle_seen {
+                continue;
+            }
+            let manifest = join(dir, file_name);
+            let Ok(source) = read_source_file(&project_root.join(&manifest)) else {
+                continue;
+            };
+            match ecosystem {
+                Ecosystem::Cargo => packages.extend(parse_cargo(dir, manifest, &source)),
+                Ecosystem::Npm => packages.extend(parse_npm(dir, manifest, &source)),
+                Ecosystem::Go => packages.extend(parse_go_mod(dir, manifest, &source)),
+                Ecosystem::Maven => packages.extend(parse_pom(dir, manifest, &source)),
+                Ecosystem::Gradle => {
+                    gradle_seen = true;
+                    packages.extend(parse_gradle_settings(project_root, dir, &manifest, &source));
+                }
+            }
+        }
+    }
+
+    packages.sort_by(|a, b| (&a.dir, a.ecosystem).cmp(&(&b.dir, b.ecosystem)));
+    packages.dedup_by(|a, b| a.dir == b.dir && a.ecosystem == b.ecosystem);
+
+    let keys: HashSet<(Ecosystem, String)> = packages
+        .iter()
+        .map(|p| (p.ecosystem, p.key.clone()))
+        .collect();
+    for package in &mut packages {
+        let ecosystem = package.ecosystem;
+        let own_key = package.key.clone();
+        package
+            .dependencies
+            .retain(|dep| *dep != own_key && keys.contains(&(ecosystem, dep.clone())));
+        package.dependencies.sort_unstable();
+        package.dependencies.dedup();
+    }
+    packages
+}
+
+/// Builds the package nodes for `packages`, with a `Contains` edge to the
+/// file node of each of `files` inside a package and a `DependsOn` edge
+/// for each dependency between them.
+///
+/// `files` must only name files that have a file node.
+pub fn package_graph<'a>(
+    packages: &[Package],
+    files: impl IntoIterator<Item = &'a str>,
+) -> (Vec<Node>, Vec<Edge>) {
+    let timestamp = current_timestamp() as u64;
+    let nodes: Vec<Node> = packages
+        .iter()
+        .map(|package| package_node(package, timestamp))
+        .collect();
+    let ids: HashMap<(Ecosystem, &str), &str> = packages
+        .iter()
+        .zip(&nodes)
+        .map(|(package, node)| ((package.ecosystem, package.key.as_str()), node.id.as_str()))
+        .collect();
+
+    let mut edges = Vec::new();
+    for (package, node) in packages.iter().zip(&nodes) {
+        for dep in &package.dependencies {
+            if let Some(target) = ids.get(&(package.ecosystem, dep.as_str())) {
+                edges.push(Edge {
+                    source: node.id.clone(),
+                    target: (*target).to_string(),
+                    kind: EdgeKind::DependsOn,
+                    line: None,
+                    provenance: None,
+                });
+            }
+        }
+    }
+    edges.extend(PackageMap::new(&nodes, &[]).contains_edges(files));
+    (nodes, edges)
+}
+
+fn package_node(package: &Package, timestamp: u64) -> Node {
+    Node {
+        id: generate_node_id(
+            &package.manifest,
+            &NodeKind::ManifestPackage,
+            &package.key,
+            1,
+        ),
+        kind: NodeKind::ManifestPackage,
+        name: package.name.clone(),
+        qualified_name: package.key.clone(),
+        file_path: package.manifest.clone(),
+        start_line: 1,
+        end_line: 1,
+        start_column: 0,
+        end_column: 0,
+        signature: Some(format!(
+            "{} package {}",
+            package.ecosystem.as_str(),
+            package.key
+        )),
+        docstring: None,
+        visibility: Visibility::Pub,
+        is_async: false,
+        branches: 0,
+        loops: 0,
+        returns: 0,
+        max_nesting: 0,
+        unsafe_blocks: 0,
+        unchecked_calls: 0,
+        assertions: 0,
+        updated_at: timestamp,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Package lookup
+// ---------------------------------------------------------------------------
+
+/// A package node with the directory it covers.
+#[derive(Debug, Clone)]
+struct PackageEntry {
+    id: String,
+    name: String,
+    key: String,
+    dir: String,
+}
+
+/// Maps files to the package nodes that contain them.
+#[derive(Debug, Clone, Default)]
+pub struct PackageMap {
+    /// Packages ordered deepest directory first, so the first one covering
+    /// a file is its innermost package.
+    packages: Vec<PackageEntry>,
+    /// `(dependent, dependency)` package ID pairs.
+    dependencies: HashSet<(String, String)>,
+}
+
+impl PackageMap {
+    /// Builds a map from package nodes and the `DependsOn` edges between
+    /// them. Nodes of other kinds and other edges are ignored.
+    pub fn new(nodes: &[Node], edges: &[Edge]) -> Self {
+        let mut packages: Vec<PackageEntry> = nodes
+            .iter()
+            .filter(|n| n.kind == NodeKind::ManifestPackage)
+            .map(|n| PackageEntry {
+                id: n.id.clone(),
+                name: n.name.clone(),
+                key: n.qualified_name.clone(),
+                dir: n
+                    .file_path
+                    .rsplit_once('/')
+                    .map_or_else(String::new, |(dir, _)| dir.to_string()),
+            })
+            .collect();
+        packages.sort_by_key(|p| std::cmp::Reverse(p.dir.len()));
+        let dependencies = edges
+            .iter()
+            .filter(|e| e.kind == EdgeKind::DependsOn)
+            .map(|e| (e.source.clone(), e.target.clone()))
+            .collect();
+        Self {
+            packages,
+            dependencies,
+        }
+    }
+
+    /// Loads the package nodes and their dependencies from the database.
+    pub async fn load(db: &Database) -> Result<Self> {
+        let nodes = db.get_nodes_by_kind(NodeKind::ManifestPackage).await?;
+        let mut edges = Vec::new();
+        for node in &nodes {
+            edges.extend(
+                db.get_outgoing_edges(&node.id, &[EdgeKind::DependsOn])
+                    .await?,
+            );
+        }
+        Ok(Self::new(&nodes, &edges))
+    }
+
+    /// Returns true when the project declares no packages.
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty()
+    }
+
+    /// Returns the ID of the innermost package containing `file_path`.
+    pub fn package_of(&self, file_path: &str) -> Option<&str> {
+        self.packages
+            .iter()
+            .find(|p| {
+                p.dir.is_empty()
+                    || file_path
+                        .strip_prefix(p.dir.as_str())
+                        .is_some_and(|rest| rest.starts_with('/'))
+            })
+            .map(|p| p.id.as_str())
+    }
+
+    /// Returns the keys of all packages: crate and npm package names, Go
+    /// module paths, Maven artifact IDs and Gradle project paths.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.packages.iter().map(|p| p.key.as_str())
+    }
+
+    /// Returns the name of the package with ID `id`.
+    pub fn name(&self, id: &str) -> Option<&str> {
+        self.entry(id).map(|p| p.name.as_str())
+    }
+
+    /// Returns the directory of the package with ID `id`.
+    pub fn dir(&self, id: &str) -> Option<&str> {
+        self.entry(id).map(|p| p.dir.as_str())
+    }
+
+    /// Returns true when package `from` declares a dependency on `to`.
+    pub fn depends_on(&self, from: &str, to: &str) -> bool {
+        self.dependencies
+            .contains(&(from.to_string(), to.to_string()))
+    }
+
+    /// Returns how many packages `id` declares as dependencies, and how many
+    /// declare it as one.
+    pub fn declared_counts(&self, id: &str) -> (usize, usize) {
+        let outgoing = self.dependencies.iter().filter(|(s, _)| s == id).count();
+        let incoming = self.dependencies.iter().filter(|(_, t)| t == id).count();
+        (outgoing, incoming)
+    }
+
+    /// Builds a `Contains` edge from its package to the file node of each of
+    /// `files` that lies inside a package.
+    pub fn contains_edges<'a>(&self, files: impl IntoIterator<Item = &'a str>) -> Vec<Edge> {
+        files
+            .into_iter()
+            .filter_map(|file| {
+                let package = self.package_of(file)?;
+                Some(Edge {
+                    source: package.to_string(),
+                    target: generate_node_id(file, &NodeKind::File, file, 0),
+                    kind: EdgeKind::Contains,
+                    line: None,
+                    provenance: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Collapses a file-level adjacency map into one between packages, keyed
+    /// by package ID. Files outside every package and dependencies within a
+    /// package are dropped.
+    pub fn collapse(
+        &self,
+        files: &HashMap<String, HashSet<String>>,
+    ) -> HashMap<String, HashSet<String>> {
+        let mut adjacency: HashMap<String, HashSet<String>> = HashMap::new();
+        for (source, targets) in files {
+            let Some(from) = self.package_of(source) else {
+                continue;
+            };
+            let deps = adjacency.entry(from.to_string()).or_default();
+            for target in targets {
+                if let Some(to) = self.package_of(target) {
+                    if to != from {
+                        deps.insert(to.to_string());
+                    }
+                }
+            }
+        }
+        adjacency
+    }
+
+    fn entry(&self, id: &str) -> Option<&PackageEntry> {
+        self.packages.iter().find(|p| p.id == id)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Manifest parsers
+// ---------------------------------------------------------------------------
+
+/// Joins a directory relative to the project root with a relative path.
+fn join(dir: &str, path: &str) -> String {
+    if dir.is_empty() {
+        path.to_string()
+    } else {
+        format!("{dir}/{path}")
+    }
+}
+
+/// Reads the `[package]` of a `Cargo.toml`. Virtual workspace manifests
+/// declare no package.
+fn parse_cargo(dir: &str, manifest: String, source: &str) -> Option<Package> {
+    let table: toml::Table = toml::from_str(source).ok()?;
+    let name = table.get("package")?.get("name")?.as_str()?.to_string();
+    let mut dependencies = Vec::new();
+    collect_cargo_dependencies(&table, &mut dependencies);
+    if let Some(targets) = table.get("target").and_then(toml::Value::as_table) {
+        for target in targets.values().filter_map(toml::Value::as_table) {
+            collect_cargo_dependencies(target, &mut dependencies);
+        }
+    }
+    Some(Package {
+        ecosystem: Ecosystem::Cargo,
+        key: name.clone(),
+        name,
+        dir: dir.to_string(),
+        manifest,
+        dependencies,
+    })
+}
+
+/// Adds the crates named in the dependency tables of `table`, following
+/// `package = "..."` renames.
+fn collect_cargo_dependencies(table: &toml::Table, dependencies: &mut Vec<String>) {
+    for section in ["dependencies", "dev-dependencies", "build-dependencies"] {
+        let Some(entries) = table.get(section).and_then(toml::Value::as_table) else {
+            continue;
+        };
+        for (name, spec) in entries {
+            let package = spec.get("package").and_then(toml::Value::as_str);
+            dependencies.push(package.unwrap_or(name).to_string());
+        }
+    }
+}
+
+/// Reads a `package.json`. Manifests without a `name` (such as private
+/// workspace roots) declare no package.
+fn parse_npm(dir: &str, manifest: String, source: &str) -> Option<Package> {
+    let json: serde_json::Value = serde_json::from_str(source).ok()?;
+    let name = json.get("name")?.as_str()?.to_string();
+    let mut dependencies = Vec::new();
+    for section in [
+        "dependencies",
+        "devDependencies",
+        "peerDependencies",
+        "optionalDependencies",
+    ] {
+        if let Some(entries) = json.get(section).and_then(serde_json::Value::as_object) {
+            dependencies.extend(entries.keys().cloned());
+        }
+    }
+    Some(Package {
+        ecosystem: Ecosystem::Npm,
+        key: name.clone(),
+        name,
+        dir: dir.to_string(),
+        manifest,
+        dependencies,
+    })
+}
+
+/// Reads the `module` and `require` directives of a `go.mod`.
+fn parse_go_mod(dir: &str, manifest: String, source: &str) -> Option<Package> {
+    let mut module = None;
+    let mut dependencies = Vec::new();
+    let mut in_require_block = false;
+    for line in source.lines() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        if in_require_block {
+            if line == ")" {
+                in_require_block = false;
+            } else if let Some(path) = line.split_whitespace().next() {
+                dependencies.push(path.to_string());
+            }
+        } else if let Some(rest) = line.strip_prefix("module ") {
+            module = Some(rest.trim().trim_matches('"').to_string());
+        } else if let Some(rest) = line.strip_prefix("require") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_require_block = true;
+            } else if let Some(path) = rest.split_whitespace().next() {
+                dependencies.push(path.to_string());
+            }
+        }
+    }
+    let key = module.filter(|m| !m.is_empty())?;
+    Some(Package {
+        ecosystem: Ecosystem::Go,
+        name: key.rsplit('/').next().unwrap_or(&key).to_string(),
+        key,
+        dir: dir.to_string(),
+        manifest,
+        dependencies,
+    })
+}
+
+/// Reads the `artifactId` and direct `<dependencies>` of a `pom.xml`.
+/// Dependency management, build plugins and profiles are ignored.
+fn parse_pom(dir: &str, manifest: String, source: &str) -> Option<Package> {
+    let xml = strip_xml_comments(source);
+    let own = remove_elements(
+        &xml,
+        &[
+            "parent",
+            "dependencyManagement",
+            "profiles",
+            "build",
+            "reporting",
+            "dependencies",
+            "properties",
+        ],
+    );
+    let name = element_text(&own, "artifactId")?;
+    let declared = remove_elements(
+        &xml,
+        &["dependencyManagement", "profiles", "build", "reporting"],
+    );
+    let dependencies = xml_elements(&declared, "dependencies")
+        .into_iter()
+        .flat_map(|section| xml_elements(section, "dependency"))
+        .filter_map(|dep| element_text(dep, "artifactId"))
+        .collect();
+    Some(Package {
+        ecosystem: Ecosystem::Maven,
+        key: name.clone(),
+        name,
+        dir: dir.to_string(),
+        manifest,
+        dependencies,
+    })
+}
+
+fn strip_xml_comments(xml: &str) -> String {
+    let mut out = String::with_capacity(xml.len());
+    let mut rest = xml;
+    while let Some(start) = rest.find("<!--") {
+        out.push_str(&rest[..start]);
+        match rest[start..].find("-->") {
+            Some(end) => rest = &rest[start + end + 3..],
+            None => return out,
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Returns the contents of each `<tag>...</tag>` element in `xml`. Elements
+/// nested in a same-named element are not returned separately.
+fn xml_elements<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let mut elements = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        let body = &rest[start + open.len()..];
+        let Some(end) = body.find(&close) else {
+            break;
+        };
+        elements.push(&body[..end]);
+        rest = &body[end + close.len()..];
+    }
+    elements
+}
+
+/// Returns the trimmed text of the first `<tag>` element in `xml`.
+fn element_text(xml: &str, tag: &str) -> Option<String> {
+    xml_elements(xml, tag)
+        .first()
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+}
+
+/// Removes every element named in `tags`, in order, from `xml`.
+fn remove_elements(xml: &str, tags: &[&str]) -> String {
+    let mut out = xml.to_string();
+    for tag in tags {
+        let open = format!("<{tag}>");
+        let close = format!("</{tag}>");
+        while let Some(start) = out.find(&open) {
+            let Some(end) = out[start..].find(&close) else {
+                break;
+            };
+            out.replace_range(start..start + end + close.len(), "");
+        }
+    }
+    out
+}
+
+/// Reads the root project and the projects a Gradle settings file includes,
+/// with the `project(":path")` dependencies of their build scripts.
+fn parse_gradle_settings(
+    project_root: &Path,
+    dir: &str,
+    settings: &str,
+    source: &str,
+) -> Vec<Package> {
+    let root_name = source
+        .lines()
+        .find(|line| line.trim_start().starts_with("rootProject.name"))
+        .and_then(|line| quoted_strings(line).into_iter().next())
+        .or_else(|| {
+            dir.rsplit('/')
+                .next()
+                .filter(|d| !d.is_empty())
+                .map(str::to_string)
+        })
+        .or_else(|| {
+            project_root
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| "root".to_string());
+
+    let mut projects = vec![(":".to_string(), root_name, dir.to_string())];
+    for path in gradle_includes(source) {
+        let key = if path.starts_with(':') {
+            path
+        } else {
+            format!(":{path}")
+        };
+        let relative = key.trim_start_matches(':').replace(':', "/");
+        let project_dir = join(dir, &relative);
+        if relative.is_empty() || !project_root.join(&project_dir).is_dir() {
+            continue;
+        }
+        let name = relative.rsplit('/').next().unwrap_or(&relative).to_string();
+        projects.push((key, name, project_dir));
+    }
+
+    let script_name = if settings == join(dir, "settings.gradle.kts") {
+        "build.gradle.kts"
+    } else {
+        "build.gradle"
+    };
+    projects
+        .into_iter()
+        .map(|(key, name, project_dir)| {
+            let script = ["build.gradle.kts", "build.gradle"]
+                .iter()
+                .map(|file| join(&project_dir, file))
+                .find(|path| project_root.join(path).is_file());
+            let dependencies = script
+                .as_ref()
+                .and_then(|path| read_source_file(&project_root.join(path)).ok())
+                .map(|script| gradle_project_dependencies(&script))
+                .unwrap_or_default();
+            // The root project is declared by the settings file; included
+            // projects by their build script, even when it is implicit.
+            let manifest = if key == ":" {
+                settings.to_string()
+            } else {
+                script.unwrap_or_else(|| join(&project_dir, script_name))
+            };
+            Package {
+                ecosystem: Ecosystem::Gradle,
+                name,
+                key,
+                dir: project_dir,
+                manifest,
+                dependencies,
+            }
+        })
+        .collect()
+}
+
+/// Returns the project paths named by `include` statements, including
+/// arguments continued on following lines.
+fn gradle_includes(source: &str) -> Vec<String> {
+    let mut includes = Vec::new();
+    let mut continued = false;
+    for line in source.lines() {
+        let line = line.trim();
+        let args = match line.strip_prefix("include") {
+            Some(rest) if rest.starts_with(['(', ' ', '\t']) => rest,
+            _ if continued => line,
+            _ => continue,
+        };
+        includes.extend(quoted_strings(args));
+        continued = args.trim_end().ends_with(',');
+    }
+    includes
+}
+
+/// Returns the project paths a build script depends on through
+/// `project(":path")` or `project(path: ":path")`.
+fn gradle_project_dependencies(script: &str) -> Vec<String> {
+    script
+        .match_indices("project(")
+        .filter_map(|(idx, call)| {
+            let args = &script[idx + call.len()..];
+            let args = &args[..args.find(')')?];
+            quoted_strings(args).into_iter().next()
+        })
+        .filter(|path| path.starts_with(':'))
+        .collect()
+}
+
+/// Returns the contents of the single- or double-quoted strings in `text`.
+fn quoted_strings(text: &str) -> Vec<String> {
+    let mut strings = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(['\'', '"']) {
+        let quote = rest[start..].chars().next().unwrap_or('"');
+        let body = &rest[start + 1..];
+        let Some(end) = body.find(quote) else {
+            break;
+        };
+        strings.push(body[..end].to_string());
+        rest = &body[end + 1..];
+    }
+    strings
+}
@@ -0,0 +1,346 @@
+//! Extractor plugins: language extractors shipped as shared libraries.
+//!
+//! A plugin is a directory holding a `plugin.toml` manifest and the shared
+//! library it names (see `docs/PLUGINS-DESIGN.md`). [`discover_plugins`]
+//! finds the installed plugins, [`install_plugin`] and [`remove_plugin`]
+//! manage `~/.tokensave/plugins`, and [`PluginExtractor`] wraps a loaded
+//! library as a [`LanguageExtractor`].
+//!
+//! Plugin code runs with full trust, so libraries are only loaded by the
+//! `extract-worker` subprocess: a plugin that crashes takes down one worker,
+//! not the indexer. The parent process reads manifests only.
+
+use std::ffi::{c_char, CStr, CString};
+use std::path::{Component, Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::errors::{Result, TokenSaveError};
+use crate::extraction::{intern_extensions, LanguageExtractor};
+use crate::types::ExtractionResult;
+
+/// Version of the C ABI between tokensave and plugin libraries. Bumped on
+/// breaking changes to the exported functions or to the JSON encoding of
+/// [`ExtractionResult`].
+///
+/// Fields added since version 1 (`Edge::provenance`,
+/// `UnresolvedRef::receiver_type`, `UnresolvedRef::abi`,
+/// `UnresolvedRef::http_method`, `UnresolvedRef::sql`,
+/// `ExtractionResult::signatures`) default when missing, so results from
+/// version 1 plugins still decode.
+pub const PLUGIN_API_VERSION: u32 = 1;
+
+/// Name of the manifest file in a plugin directory.
+pub const PLUGIN_MANIFEST: &str = "plugin.toml";
+
+/// Environment variable listing extra plugin directories, separated like
+/// `PATH`. They are searched before `~/.tokensave/plugins`.
+pub const PLUGIN_PATH_ENV: &str = "TOKENSAVE_PLUGIN_PATH";
+
+/// A plugin's `plugin.toml`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginManifest {
+    pub plugin: PluginInfo,
+    pub language: PluginLanguage,
+}
+
+/// The `[plugin]` table of a manifest.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginInfo {
+    pub name: String,
+    pub version: String,
+    pub api_version: u32,
+    /// File name of the shared library in the plugin directory. Defaults to
+    /// the platform's name for a library called after the plugin
+    /// (`libtokensave_elixir.so` for `tokensave-elixir` on Linux).
+    pub library: Option<String>,
+}
+
+/// The `[language]` table of a manifest.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginLanguage {
+    pub name: String,
+    /// File extensions the plugin extracts, with or without a leading dot.
+    pub extensions: Vec<String>,
+}
+
+/// An installed plugin: its manifest and the directory holding it.
+#[derive(Debug, Clone)]
+pub struct Plugin {
+    pub manifest: PluginManifest,
+    pub dir: PathBuf,
+}
+
+impl Plugin {
+    /// Reads and validates the plugin in `dir`.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(PLUGIN_MANIFEST);
+        let text = std::fs::read_to_string(&path).map_err(|e| TokenSaveError::File {
+            message: format!("cannot read plugin manifest: {e}"),
+            path: path.display().to_string(),
+        })?;
+        let manifest: PluginManifest =
+            toml::from_str(&text).map_err(|e| TokenSaveError::Config {
+                message: format!("invalid plugin manifest {}: {e}", path.display()),
+            })?;
+        let name = &manifest.plugin.name;
+        if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+            return Err(TokenSaveError::Config {
+                message: format!("invalid plugin name '{name}' in {}", path.display()),
+            });
+        }
+        if let Some(library) = &manifest.plugin.library {
+            let mut components = Path::new(library).components();
+            if !matches!(
+                (components.next(), components.next()),
+                (Some(Component::Normal(_)), None)
+            ) {
+                return Err(TokenSaveError::Config {
+                    message: format!(
+                        "plugin '{name}' library '{library}' must be a file name in the plugin directory"
+                    ),
+                });
+            }
+        }
+        if manifest.plugin.api_version != PLUGIN_API_VERSION {
+            return Err(TokenSaveError::Config {
+                message: format!(
+                    "plugin '{name}' targets plugin API v{}, this tokensave supports v{PLUGIN_API_VERSION}",
+                    manifest.plugin.api_version
+                ),
+            });
+        }
+        if manifest.extensions().next().is_none() {
+            return Err(TokenSaveError::Config {
+                message: format!("plugin '{name}' declares no file extensions"),
+            });
+        }
+        Ok(Self {
+            manifest,
+            dir: dir.to_path_buf(),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.manifest.plugin.name
+    }
+
+    /// Path of the plugin's shared library.
+    pub fn library_path(&self) -> PathBuf {
+        let file = self.manifest.plugin.library.clone().unwrap_or_else(|| {
+            format!(
+                "{}{}{}",
+                std::env::consts::DLL_PREFIX,
+                self.name().replace('-', "_"),
+                std::env::consts::DLL_SUFFIX
+            )
+        });
+        self.dir.join(file)
+    }
+}
+
+impl PluginManifest {
+    /// The declared extensions without their leading dots.
+    pub fn extensions(&self) -> impl Iterator<Item = &str> {
+        self.language
+            .extensions
+            .iter()
+            .map(|ext| ext.trim_start_matches('.'))
+            .filter(|ext| !ext.is_empty())
+    }
+}
+
+/// The directory `tokensave plugin install` installs into:
+/// `~/.tokensave/plugins`.
+pub fn default_plugin_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".tokensave").join("plugins"))
+}
+
+/// The directories searched for plugins, in order: the entries of
+/// `TOKENSAVE_PLUGIN_PATH`, then `~/.tokensave/plugins`.
+pub fn plugin_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::env::var_os(PLUGIN_PATH_ENV)
+        .map(|paths| std::env::split_paths(&paths).collect())
+        .unwrap_or_default();
+    dirs.extend(default_plugin_dir());
+    dirs
+}
+
+/// Finds the plugins installed in the plugin directories. The first plugin
+/// found with a given name wins. Plugins that fail validation are reported
+/// on stderr and skipped.
+pub fn discover_plugins() -> Vec<Plugin> {
+    let (plugins, errors) = discover_plugins_in(&plugin_dirs());
+    for error in errors {
+        eprintln!("[tokensave] skipping plugin: {error}");
+    }
+    plugins
+}
+
+/// Finds the plugins in the subdirectories of `dirs`, returning them sorted
+/// by name together with the errors of the plugins that failed validation.
+pub fn discover_plugins_in(dirs: &[PathBuf]) -> (Vec<Plugin>, Vec<TokenSaveError>) {
+    let mut plugins: Vec<Plugin> = Vec::new();
+    let mut errors = Vec::new();
+    for dir in dirs {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        let mut candidates: Vec<PathBuf> = entries
+            .filter_map(std::result::Result::ok)
+            .map(|e| e.path())
+            .filter(|p| p.join(PLUGIN_MANIFEST).is_file())
+            .collect();
+        candidates.sort();
+        for candidate in candidates {
+            match Plugin::load(&candidate) {
+                Ok(plugin) if plugins.iter().all(|p| p.name() != plugin.name()) => {
+                    plugins.push(plugin);
+                }
+                Ok(_) => {}
+                Err(e) => errors.push(e),
+            }
+        }
+    }
+    plugins.sort_by(|a, b| a.name().cmp(b.name()));
+    (plugins, errors)
+}
+
+/// Installs the plugin in directory `source` (built with the plugin SDK)
+/// into `plugin_dir`, replacing an installed plugin of the same name. Copies
+/// the manifest and the shared library only.
+pub fn install_plugin(source: &Path, plugin_dir: &Path) -> Result<Plugin> {
+    let plugin = Plugin::load(source)?;
+    let library = plugin.library_path();
+    if !library.is_file() {
+        return Err(TokenSaveError::File {
+            message: format!("plugin '{}' has no shared library", plugin.name()),
+            path: library.display().to_string(),
+        });
+    }
+    let dest = plugin_dir.join(plugin.name());
+    if dest.exists() {
+        std::fs::remove_dir_all(&dest)?;
+    }
+    std::fs::create_dir_all(&dest)?;
+    std::fs::copy(source.join(PLUGIN_MANIFEST), dest.join(PLUGIN_MANIFEST))?;
+    if let Some(file) = library.file_name() {
+        std::fs::copy(&library, dest.join(file))?;
+    }
+    Plugin::load(&dest)
+}
+
+/// Removes the plugin `name` from `plugin_dir`. Returns false when it is not
+/// installed there.
+pub fn remove_plugin(name: &str, plugin_dir: &Path) -> Result<bool> {
+    let dir = plugin_dir.join(name);
+    if name.is_empty() || name.contains(['/', '\\']) || !dir.join(PLUGIN_MANIFEST).is_file() {
+        return Ok(false);
+    }
+    std::fs::remove_dir_all(dir)?;
+    Ok(true)
+}
+
+type ApiVersionFn = unsafe extern "C" fn() -> u32;
+type ExtractFn = unsafe extern "C" fn(*const c_char, *const c_char, usize) -> *const c_char;
+type FreeFn = unsafe extern "C" fn(*const c_char);
+
+/// A [`LanguageExtractor`] backed by a plugin's shared library.
+pub struct PluginExtractor {
+    name: String,
+    language: String,
+    extensions: Vec<&'static str>,
+    extract: ExtractFn,
+    free: FreeFn,
+    /// Keeps the library mapped for as long as the function pointers live.
+    _library: libloading::Library,
+}
+
+impl PluginExtractor {
+    /// Loads the shared library of `plugin` and checks that it speaks this
+    /// version of the plugin API.
+    ///
+    /// Loading runs the library's initialisers with full trust; call this
+    /// from the `extract-worker` subprocess only.
+    pub fn load(plugin: &Plugin) -> Result<Self> {
+        let path = plugin.library_path();
+        let load_error = |e: libloading::Error| TokenSaveError::File {
+            message: format!("cannot load plugin '{}': {e}", plugin.name()),
+            path: path.display().to_string(),
+        };
+        // SAFETY: loading a plugin runs its initialisers; plugins are trusted
+        // code installed by the user, and this only runs in the worker.
+        let library = unsafe { libloading::Library::new(&path) }.map_err(load_error)?;
+        // SAFETY: the symbol types match the C ABI documented in
+        // PLUGINS-DESIGN.md, which the plugin SDK generates.
+        let (api_version, extract, free) = unsafe {
+            (
+                *library
+                    .get::<ApiVersionFn>(b"tokensave_plugin_api_version\0")
+                    .map_err(load_error)?,
+                *library
+                    .get::<ExtractFn>(b"tokensave_extract\0")
+                    .map_err(load_error)?,
+                *library
+                    .get::<FreeFn>(b"tokensave_free\0")
+                    .map_err(load_error)?,
+            )
+        };
+        // SAFETY: takes no arguments and returns a plain integer.
+        let version = unsafe { api_version() };
+        if version != PLUGIN_API_VERSION {
+            return Err(TokenSaveError::Config {
+                message: format!(
+                    "plugin '{}' was built for plugin API v{version}, this tokensave supports v{PLUGIN_API_VERSION}",
+                    plugin.name()
+                ),
+            });
+        }
+        Ok(Self {
+            name: plugin.name().to_string(),
+            language: plugin.manifest.language.name.clone(),
+            extensions: intern_extensions(plugin.manifest.extensions()),
+            extract,
+            free,
+            _library: library,
+        })
+    }
+
+    fn failed(&self, file_path: &str, message: &str) -> ExtractionResult {
+        ExtractionResult {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            unresolved_refs: Vec::new(),
+            signatures: Vec::new(),
+            errors: vec![format!("plugin '{}' on {file_path}: {message}", self.name)],
+            duration_ms: 0,
+        }
+    }
+}
+
+impl LanguageExtractor for PluginExtractor {
+    fn extensions(&self) -> &[&str] {
+        &self.extensions
+    }
+
+    fn language_name(&self) -> &str {
+        &self.language
+    }
+
+    fn extract(&self, file_path: &str, source: &str) -> ExtractionResult {
+        let Ok(c_path) = CString::new(file_path) else {
+            return self.failed(file_path, "path contains a NUL byte");
+        };
+        // SAFETY: both buffers outlive the call; the plugin returns either
+        // null or a NUL-terminated string that it owns until `free`.
+        let json = unsafe { (self.extract)(c_path.as_ptr(), source.as_ptr().cast(), source.len()) };
+        if json.is_null() {
+            return self.failed(file_path, "extraction returned no result");
+        }
+        // SAFETY: non-null results are NUL-terminated (see above).
+        let parsed = serde_json::from_slice(unsafe { CStr::from_ptr(json) }.to_bytes());
+        // SAFETY: `json` came from this plugin's `tokensave_extract`.
+        unsafe { (self.free)(json) };
+        parsed.unwrap_or_else(|e| self.failed(file_path, &format!("invalid result: {e}")))
+    }
+}
@@ -0,0 +1,94 @@
+//! Grouped diagnostics for references the graph cannot see.
+//!
+//! [`ReferenceResolver::diagnose`](super::ReferenceResolver::diagnose)
+//! explains individual references; [`UnresolvedReport`] rolls those
+//! explanations up by reason, referenced name, file and language so blind
+//! spots (generated code, missing include paths) stand out.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+
+use super::resolver::lang_from_path;
+use crate::types::RefDiagnostic;
+
+/// How many example locations each group keeps.
+const MAX_EXAMPLES: usize = 3;
+
+/// Diagnostics sharing one referenced name, file or language.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticGroup {
+    pub key: String,
+    pub count: usize,
+    /// Number of diagnostics per reason.
+    pub reasons: BTreeMap<&'static str, usize>,
+    /// A few `file:line` locations from the group.
+    pub examples: Vec<String>,
+}
+
+/// Diagnostics grouped by reason, referenced name, file and language. Each
+/// grouping is sorted by count, largest first.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UnresolvedReport {
+    pub total: usize,
+    pub by_reason: BTreeMap<&'static str, usize>,
+    pub by_name: Vec<DiagnosticGroup>,
+    pub by_file: Vec<DiagnosticGroup>,
+    pub by_language: Vec<DiagnosticGroup>,
+}
+
+impl UnresolvedReport {
+    /// Groups `diagnostics` into a report.
+    pub fn new(diagnostics: &[RefDiagnostic]) -> Self {
+        let mut by_reason: BTreeMap<&'static str, usize> = BTreeMap::new();
+        for diagnostic in diagnostics {
+            *by_reason.entry(diagnostic.reason.as_str()).or_default() += 1;
+        }
+        Self {
+            total: diagnostics.len(),
+            by_reason,
+            by_name: group_by(diagnostics, |d| d.reference.reference_name.clone()),
+            by_file: group_by(diagnostics, |d| d.reference.file_path.clone()),
+            by_language: group_by(diagnostics, |d| {
+                lang_from_path(&d.reference.file_path).to_string()
+            }),
+        }
+    }
+
+    /// Keeps only the `limit` largest groups of each grouping.
+    pub fn truncate(&mut self, limit: usize) {
+        self.by_name.truncate(limit);
+        self.by_file.truncate(limit);
+        self.by_language.truncate(limit);
+    }
+}
+
+/// Groups diagnostics under the key `key_of` returns for each of them.
+fn group_by(
+    diagnostics: &[RefDiagnostic],
+    key_of: impl Fn(&RefDiagnostic) -> String,
+) -> Vec<DiagnosticGroup> {
+    let mut groups: HashMap<String, DiagnosticGroup> = HashMap::new();
+    for diagnostic in diagnostics {
+        let key = key_of(diagnostic);
+        let group = groups
+            .entry(key.clone())
+            .or_insert_with(|| DiagnosticGroup {
+                key,
+                count: 0,
+                reasons: BTreeMap::new(),
+                examples: Vec::new(),
+            });
+        group.count += 1;
+        *group.reasons.entry(diagnostic.reason.as_str()).or_default() += 1;
+        if group.examples.len() < MAX_EXAMPLES {
+            let reference = &diagnostic.reference;
+            group
+                .examples
+                .push(format!("{}:{}", reference.file_path, reference.line));
+        }
+    }
+    let mut groups: Vec<DiagnosticGroup> = groups.into_values().collect();
+    groups.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.key.cmp(&b.key)));
+    groups
+}
@@ -0,0 +1,319 @@
+//! Synthetic nodes for the symbols of dependencies outside the project.
+//!
+//! A reference bound to an import of an external package (`use tokio::spawn`,
+//! `import { debounce } from 'lodash'`, `import requests`) resolves to a
+//! [`NodeKind::ExternalSymbol`] node instead of staying unresolved or binding
+//! to a local symbol that happens to share its name. The symbols of one
+//! dependency are grouped under a [`NodeKind::ExternalPackage`] node that
+//! `Contains` them.
+//!
+//! [`ProjectModules`] decides which imports are external: an import is part
+//! of the project when its root names a directory, file or manifest package
+//! of the project.
+
+use std::collections::{BTreeMap, HashSet};
+
+use super::imports::{norm, ImportBinding};
+use crate::tokensave::current_timestamp;
+use crate::types::*;
+
+/// The `file_path` of every external node. It is not a project path, so
+/// file-scoped queries and path filters never pick external nodes up.
+pub const EXTERNAL_FILE_PATH: &str = "<external>";
+
+/// Rust prelude types whose associated functions are called without an
+/// import (`Vec::new`), with the path they are defined at.
+const RUST_PRELUDE: &[(&str, &str)] = &[
+    ("Vec", "std::vec::Vec"),
+    ("String", "std::string::String"),
+    ("Box", "std::boxed::Box"),
+    ("Option", "std::option::Option"),
+    ("Result", "std::result::Result"),
+];
+
+/// Rust primitive types, whose associated functions (`u32::from_str_radix`)
+/// belong to the standard library.
+const RUST_PRIMITIVES: &[&str] = &[
+    "bool", "char", "str", "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64",
+    "u128", "usize", "f32", "f64",
+];
+
+/// The external symbol a reference resolves to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ExternalTarget {
+    /// Coarse language tag of the referencing file.
+    pub lang: &'static str,
+    /// The dependency: `tokio`, `@scope/pkg`, `os`, `java.util`.
+    pub package: String,
+    /// The symbol's path, starting with the package: `tokio::spawn`,
+    /// `lodash.debounce`, `java.util.List`.
+    pub symbol: String,
+}
+
+impl ExternalTarget {
+    /// Builds the target of a reference whose leading name is bound by the
+    /// external import `binding`; `members` are the reference's remaining
+    /// segments (`parse` in `serde_json::parse` with `serde_json` imported).
+    pub fn from_binding(
+        lang: &'static str,
+        binding: &ImportBinding,
+        members: &[&str],
+    ) -> Option<Self> {
+        let package = binding.package.as_ref()?;
+        let sep = if lang == "rust" { "::" } else { "." };
+        let mut symbol = package.name.clone();
+        for member in binding
+            .path
+            .iter()
+            .skip(package.segments)
+            .map(String::as_str)
+            .chain(members.iter().copied())
+        {
+            symbol.push_str(sep);
+            symbol.push_str(member);
+        }
+        Some(Self {
+            lang,
+            package: package.name.clone(),
+            symbol,
+        })
+    }
+
+    /// The ID of the symbol's node.
+    pub fn symbol_id(&self) -> String {
+        generate_node_id(
+            EXTERNAL_FILE_PATH,
+            &NodeKind::ExternalSymbol,
+            &format!("{}:{}", self.lang, self.symbol),
+            0,
+        )
+    }
+
+    /// The ID of the node of the symbol's package.
+    fn package_id(&self) -> String {
+        generate_node_id(
+            EXTERNAL_FILE_PATH,
+            &NodeKind::ExternalPackage,
+            &format!("{}:{}", self.lang, self.package),
+            0,
+        )
+    }
+
+    /// The symbol's short name: its last path segment.
+    fn name(&self) -> &str {
+        self.symbol
+            .rsplit(['.', ':', '/'])
+            .find(|s| !s.is_empty())
+            .unwrap_or(&self.symbol)
+    }
+}
+
+/// Builds the nodes for `targets` and their packages, plus the `Contains`
+/// edge from each package to each of its symbols. Duplicate targets yield a
+/// single node.
+pub(crate) fn external_graph<'a>(
+    targets: impl IntoIterator<Item = &'a ExternalTarget>,
+) -> (Vec<Node>, Vec<Edge>) {
+    let timestamp = current_timestamp() as u64;
+    let mut packages: BTreeMap<String, Node> = BTreeMap::new();
+    let mut symbols: BTreeMap<String, (String, Node)> = BTreeMap::new();
+    for target in targets {
+        let symbol_id = target.symbol_id();
+        if symbols.contains_key(&symbol_id) {
+            continue;
+        }
+        let package_id = target.package_id();
+        packages.entry(package_id.clone()).or_insert_with(|| {
+            external_node(
+                package_id.clone(),
+                NodeKind::ExternalPackage,
+                &target.package,
+                &target.package,
+                Some(format!("{} dependency {}", target.lang, target.package)),
+                timestamp,
+            )
+        });
+        let node = external_node(
+            symbol_id.clone(),
+            NodeKind::ExternalSymbol,
+            target.name(),
+            &target.symbol,
+            None,
+            timestamp,
+        );
+        symbols.insert(symbol_id, (package_id, node));
+    }
+
+    let mut edges = Vec::with_capacity(symbols.len());
+    let mut nodes: Vec<Node> = packages.into_values().collect();
+    for (package_id, node) in symbols.into_values() {
+        edges.push(Edge {
+            source: package_id,
+            target: node.id.clone(),
+            kind: EdgeKind::Contains,
+            line: None,
+            provenance: None,
+        });
+        nodes.push(node);
+    }
+    (nodes, edges)
+}
+
+fn external_node(
+    id: String,
+    kind: NodeKind,
+    name: &str,
+    qualified_name: &str,
+    signature: Option<String>,
+    timestamp: u64,
+) -> Node {
+    Node {
+        id,
+        kind,
+        name: name.to_string(),
+        qualified_name: qualified_name.to_string(),
+        file_path: EXTERNAL_FILE_PATH.to_string(),
+        start_line: 0,
+        end_line: 0,
+        start_column: 0,
+        end_column: 0,
+        signature,
+        docstring: None,
+        visibility: Visibility::Pub,
+        is_async: false,
+        branches: 0,
+        loops: 0,
+        returns: 0,
+        max_nesting: 0,
+        unsafe_blocks: 0,
+        unchecked_calls: 0,
+        assertions: 0,
+        updated_at: timestamp,
+    }
+}
+
+/// The module names and directories of the project, for telling imports of
+/// project code from imports of dependencies.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ProjectModules {
+    /// Normalised directory names, file stems and package keys.
+    names: HashSet<String>,
+    /// Every trailing run of a file's directory names, joined with `/`
+    /// (`com/acme` and `acme` for `src/com/acme/App.java`).
+    dirs: HashSet<String>,
+    /// Manifest package keys split into normalised path segments.
+    packages: Vec<Vec<String>>,
+}
+
+impl ProjectModules {
+    /// Records the directories and stem of the project file `path`.
+    pub fn add_file(&mut self, path: &str) {
+        let mut parts: Vec<String> = path
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(norm)
+            .collect();
+        let Some(file) = parts.pop() else {
+            return;
+        };
+        let stem = file.split('.').next().unwrap_or(&file);
+        if !matches!(stem, "mod" | "index" | "__init__" | "lib") {
+            self.names.insert(stem.to_string());
+        }
+        for start in 0..parts.len() {
+            if !self.dirs.insert(parts[start..].join("/")) {
+                // Shorter runs were recorded with an earlier file.
+                break;
+            }
+        }
+        self.names.extend(parts);
+    }
+
+    /// Records the key of a manifest package of the project.
+    pub fn add_package(&mut self, key: &str) {
+        self.names.insert(norm(key));
+        let segments: Vec<String> = key
+            .trim_start_matches(['@', ':'])
+            .split(['/', ':'])
+            .filter(|s| !s.is_empty())
+            .map(norm)
+            .collect();
+        if !segments.is_empty() {
+            self.packages.push(segments);
+        }
+    }
+
+    /// Returns true when `binding`, declared in a file of language `lang`,
+    /// imports from a dependency rather than from the project.
+    ///
+    /// Imports flagged external by their syntax (standard library roots,
+    /// bare npm specifiers) always are. Rust and Python imports are external
+    /// when their root is no directory, file or package of the project; JVM
+    /// and Go imports when their package path is neither under a project
+    /// package nor a trailing run of project directories.
+    pub fn is_external(&self, lang: &str, binding: &ImportBinding) -> bool {
+        if binding.external {
+            return true;
+        }
+        let Some(package) = &binding.package else {
+            return false;
+        };
+        match lang {
+            "rust" => {
+                package.name.starts_with(|c: char| c.is_ascii_lowercase())
+                    && !self.names.contains(&norm(&package.name))
+            }
+            "python" => !self.names.contains(&norm(&package.name)),
+            "java" | "kotlin" | "scala" | "go" => !self.is_project_path(&binding.path),
+            _ => false,
+        }
+    }
+
+    /// Returns the target of a Rust path that starts at a dependency without
+    /// importing it: `serde_json::to_string`, `std::mem::swap`, and the
+    /// associated functions of prelude and primitive types (`Vec::new`,
+    /// `u32::from_str_radix`).
+    pub fn rust_path_target(&self, segments: &[&str]) -> Option<ExternalTarget> {
+        let segments: Vec<&str> = segments
+            .iter()
+            .copied()
+            .filter(|s| !s.is_empty() && !s.starts_with('<'))
+            .collect();
+        let (head, rest) = segments.split_first()?;
+        if rest.is_empty() {
+            return None;
+        }
+        let root = if let Some((_, path)) = RUST_PRELUDE.iter().find(|(name, _)| name == head) {
+            (*path).to_string()
+        } else if RUST_PRIMITIVES.contains(head) {
+            format!("std::primitive::{head}")
+        } else if head.starts_with(|c: char| c.is_ascii_lowercase())
+            && head.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && !matches!(*head, "crate" | "self" | "super")
+            && !self.names.contains(&norm(head))
+        {
+            (*head).to_string()
+        } else {
+            return None;
+        };
+        let package = root.split("::").next().unwrap_or(&root).to_string();
+        Some(ExternalTarget {
+            lang: "rust",
+            package,
+            symbol: format!("{root}::{}", rest.join("::")),
+        })
+    }
+
+    /// Returns true when `path` lies under a project package key, or when
+    /// one of its leading runs of at least two segments (one for a single
+    /// segment path) is a trailing run of project directories.
+    fn is_project_path(&self, path: &[String]) -> bool {
+        let path: Vec<String> = path.iter().map(|s| norm(s)).collect();
+        if self.packages.iter().any(|key| path.starts_with(key)) {
+            return true;
+        }
+        (path.len().min(2)..=path.len())
+            .filter(|len| *len > 0)
+            .any(|len| self.dirs.contains(&path[..len].join("/")))
+    }
+}
@@ -0,0 +1,124 @@
+//! GraphQL schema lookups: the types fields return, for following the
+//! selection paths of operations to the fields they select, and the naming
+//! conventions that link schema fields to the functions resolving them.
+
+use std::collections::HashMap;
+
+use crate::types::{Edge, EdgeKind, Node, NodeKind};
+
+/// Resolver naming conventions used when the project config declares none:
+/// Graphene's `resolve_` methods, `NestJS` and `TypeGraphQL` resolver classes,
+/// and gqlgen's `queryResolver` methods.
+pub const DEFAULT_RESOLVER_CONVENTIONS: &[&str] = &[
+    "{Type}.resolve_{field}",
+    "{Type}Resolver.{field}",
+    "{type}Resolver.{Field}",
+];
+
+/// The named type a field signature (`posts(first: Int): [Post!]!`)
+/// returns.
+pub(super) fn field_type(signature: &str) -> Option<&str> {
+    let after_arguments = signature
+        .rfind(')')
+        .map_or(signature, |i| &signature[i + 1..]);
+    let (_, field_type) = after_arguments.split_once(':')?;
+    let field_type = field_type.trim_matches(|c| matches!(c, '[' | ']' | '!' | ' '));
+    (!field_type.is_empty()).then_some(field_type)
+}
+
+/// The function names `conventions` give the resolvers of `fields`, for
+/// loading the candidate functions.
+pub fn resolver_names(fields: &[Node], conventions: &[String]) -> Vec<String> {
+    let mut names: Vec<String> = fields
+        .iter()
+        .filter_map(|f| f.name.split_once('.'))
+        .flat_map(|(type_name, field)| {
+            conventions
+                .iter()
+                .map(move |c| expand(c, type_name, field).1)
+        })
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    names
+}
+
+/// Links schema fields to the functions resolving them. Each convention
+/// names a resolver `Container.function`, or just `function` for a free
+/// function, using the placeholders `{Type}`, `{type}` (first letter
+/// lowered), `{field}` and `{Field}` (first letter raised). The container
+/// is the class a method is declared in, or the receiver type of a Go
+/// method.
+///
+/// Returns an `Implements` edge from each resolver to its field.
+pub fn resolver_edges(fields: &[Node], functions: &[Node], conventions: &[String]) -> Vec<Edge> {
+    let mut by_name: HashMap<&str, Vec<&Node>> = HashMap::new();
+    for function in functions {
+        if matches!(
+            function.kind,
+            NodeKind::Function | NodeKind::Method | NodeKind::StructMethod
+        ) {
+            by_name.entry(&function.name).or_default().push(function);
+        }
+    }
+    let mut edges = Vec::new();
+    for field in fields.iter().filter(|f| f.kind == NodeKind::GqlField) {
+        let Some((type_name, field_name)) = field.name.split_once('.') else {
+            continue;
+        };
+        for convention in conventions {
+            let (container_name, function) = expand(convention, type_name, field_name);
+            let resolvers = by_name.get(function.as_str()).into_iter().flatten();
+            for resolver in resolvers.filter(|r| container(r) == container_name.as_deref()) {
+                edges.push(Edge {
+                    source: resolver.id.clone(),
+                    target: field.id.clone(),
+                    kind: EdgeKind::Implements,
+                    line: Some(resolver.start_line),
+                    provenance: None,
+                });
+            }
+        }
+    }
+    edges
+}
+
+/// Fills in a convention for a field, returning the container and the
+/// function name it gives the resolver.
+fn expand(convention: &str, type_name: &str, field: &str) -> (Option<String>, String) {
+    let expanded = convention
+        .replace("{Type}", type_name)
+        .replace("{type}", &with_first(type_name, char::to_ascii_lowercase))
+        .replace("{field}", field)
+        .replace("{Field}", &with_first(field, char::to_ascii_uppercase));
+    match expanded.rsplit_once('.') {
+        Some((container, function)) => (Some(container.to_string()), function.to_string()),
+        None => (None, expanded),
+    }
+}
+
+fn with_first(name: &str, change: fn(&char) -> char) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| change(&first).to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+/// The class a function is declared in, or the receiver type of a Go
+/// method (`func (r *queryResolver) User(...)`); `None` for free functions.
+fn container(function: &Node) -> Option<&str> {
+    if let Some(receiver) = function
+        .signature
+        .as_deref()
+        .and_then(|s| s.strip_prefix("func ("))
+    {
+        let receiver = receiver.split(')').next()?.split_whitespace().last()?;
+        return Some(receiver.trim_start_matches('*'));
+    }
+    function
+        .qualified_name
+        .rsplit("::")
+        .nth(1)
+        .filter(|parent| *parent != function.file_path)
+}
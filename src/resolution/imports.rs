@@ -0,0 +1,502 @@
+//! Per-file import bindings used to steer reference resolution.
+//!
+//! Import statements are recovered from the `Use` / `Include` nodes emitted by
+//! the extractors (their `signature` holds the full statement text) and
+//! normalised into [`ImportBinding`]s: the name a file sees locally plus the
+//! module path it was brought in from.
+
+use crate::types::{Node, NodeKind};
+
+/// A single name brought into scope by an import statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ImportBinding {
+    /// Name visible inside the importing file (the alias when present).
+    /// Empty for glob imports.
+    pub local: String,
+    /// Path segments of the imported item, with `crate`/`self`/`super`,
+    /// relative dots and file extensions stripped. For symbol imports the
+    /// last segment is the symbol's name in its defining module; for glob and
+    /// namespace imports the whole path is the module.
+    pub path: Vec<String>,
+    /// True for `use a::*`, `from a import *`, `import a.*` and `#include`.
+    pub glob: bool,
+    /// True when the import clearly points outside the project (standard
+    /// library, bare package specifiers). Such bindings shadow local symbols
+    /// of the same name instead of merely ranking them.
+    pub external: bool,
+    /// The dependency the import comes from if it is not part of the
+    /// project. `None` for imports anchored in the project (`crate::`,
+    /// relative paths) and for `#include`.
+    pub package: Option<ImportPackage>,
+}
+
+/// The leading segments of an import path that name a dependency: a crate,
+/// an npm package, a top-level Python module, or a JVM or Go package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ImportPackage {
+    /// The dependency as spelled in manifests: `serde_json`, `@scope/pkg`,
+    /// `java.util`, `github.com/pkg/errors`.
+    pub name: String,
+    /// How many leading segments of the binding's `path` it covers.
+    pub segments: usize,
+}
+
+impl ImportPackage {
+    fn new(name: &str, segments: usize) -> Option<Self> {
+        (!name.is_empty() && segments > 0).then(|| Self {
+            name: name.to_string(),
+            segments,
+        })
+    }
+}
+
+impl ImportBinding {
+    fn symbol(
+        local: &str,
+        path: Vec<String>,
+        external: bool,
+        package: Option<ImportPackage>,
+    ) -> Option<Self> {
+        if local.is_empty() || path.is_empty() {
+            return None;
+        }
+        Some(Self {
+            local: local.to_string(),
+            path,
+            glob: false,
+            external,
+            package,
+        })
+    }
+
+    fn glob(path: Vec<String>, external: bool, package: Option<ImportPackage>) -> Option<Self> {
+        if path.is_empty() {
+            return None;
+        }
+        Some(Self {
+            local: String::new(),
+            path,
+            glob: true,
+            external,
+            package,
+        })
+    }
+}
+
+/// Parses the import bindings declared by a `Use` or `Include` node.
+///
+/// `lang` is the coarse language tag of the node's file. Unknown languages and
+/// unparseable statements yield no bindings.
+pub(crate) fn bindings_for_node(node: &Node, lang: &str) -> Vec<ImportBinding> {
+    if !matches!(node.kind, NodeKind::Use | NodeKind::Include) {
+        return Vec::new();
+    }
+    let text = node.signature.as_deref().unwrap_or(&node.name).trim();
+    let mut out = Vec::new();
+    match lang {
+        "rust" => parse_rust(text, &mut out),
+        "typescript" | "javascript" => parse_ecmascript(text, &mut out),
+        "python" => parse_python(text, &mut out),
+        "java" | "kotlin" | "scala" => parse_jvm(text, &mut out),
+        "go" => parse_go(text, &mut out),
+        "c" | "cpp" => parse_include(text, &mut out),
+        _ => {}
+    }
+    out
+}
+
+/// Normalises a path segment for comparison: lowercase, `-` folded to `_`.
+pub(super) fn norm(seg: &str) -> String {
+    seg.trim().to_lowercase().replace('-', "_")
+}
+
+/// Returns the module segments a candidate node lives in: its directory
+/// components, its file stem (skipping `mod`/`index`/`__init__`/`lib`), and
+/// any enclosing containers from its qualified name.
+pub(crate) fn node_module_segments(node: &Node) -> Vec<String> {
+    let mut segs: Vec<String> = Vec::new();
+    let mut parts: Vec<&str> = node
+        .file_path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect();
+    if let Some(last) = parts.pop() {
+        let stem = last.split('.').next().unwrap_or(last);
+        segs.extend(parts.iter().map(|s| norm(s)));
+        if !matches!(stem, "mod" | "index" | "__init__" | "lib") {
+            segs.push(norm(stem));
+        }
+    }
+    let inner: Vec<&str> = node.qualified_name.split("::").skip(1).collect();
+    if let Some((_, containers)) = inner.split_last() {
+        segs.extend(
+            containers
+                .iter()
+                .filter(|s| **s != node.file_path)
+                .map(|s| norm(s)),
+        );
+    }
+    segs
+}
+
+/// Counts how many trailing segments of `module` appear, in order, among the
+/// candidate's module segments. Zero means the candidate is not reachable
+/// through the import.
+pub(crate) fn module_affinity(module: &[String], candidate: &[String]) -> usize {
+    let mut count = 0;
+    let mut upper = candidate.len();
+    for seg in module.iter().rev() {
+        let seg = norm(seg);
+        match candidate[..upper].iter().rposition(|c| *c == seg) {
+            Some(pos) => {
+                count += 1;
+                upper = pos;
+            }
+            None => break,
+        }
+    }
+    count
+}
+
+// ------------------------------------------------------------------
+// Language parsers
+// ------------------------------------------------------------------
+
+/// Splits `s` on `sep` at brace depth zero.
+fn split_top_level(s: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0_i32;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '{' | '(' | '[' => depth += 1,
+            '}' | ')' | ']' => depth -= 1,
+            c if c == sep && depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+/// Splits `name as alias` into its two halves.
+fn split_alias(s: &str) -> (&str, Option<&str>) {
+    match s.split_once(" as ") {
+        Some((name, alias)) => (name.trim(), Some(alias.trim())),
+        None => (s.trim(), None),
+    }
+}
+
+/// Rust: `[pub] use a::b::{c, d as e, f::*};`
+fn parse_rust(text: &str, out: &mut Vec<ImportBinding>) {
+    let mut body = text.trim();
+    if let Some(rest) = body.strip_prefix("pub") {
+        body = rest.trim_start();
+        if body.starts_with('(') {
+            body = body.find(')').map_or(body, |i| body[i + 1..].trim_start());
+        }
+    }
+    let Some(body) = body.strip_prefix("use ") else {
+        return;
+    };
+    let body = body.trim().trim_end_matches(';').trim();
+    let root = body
+        .trim_start_matches("::")
+        .split("::")
+        .next()
+        .unwrap_or("")
+        .trim();
+    let external = matches!(root, "std" | "core" | "alloc");
+    // Paths rooted at `crate`, `self` or `super` stay inside the crate; any
+    // other root may name a dependency.
+    let package = if matches!(root, "crate" | "self" | "super") || root.contains('{') {
+        None
+    } else {
+        ImportPackage::new(root, 1)
+    };
+    expand_rust_tree(&[], body, external, package.as_ref(), out);
+}
+
+fn expand_rust_tree(
+    prefix: &[String],
+    tree: &str,
+    external: bool,
+    package: Option<&ImportPackage>,
+    out: &mut Vec<ImportBinding>,
+) {
+    let tree = tree.trim();
+    if tree.is_empty() {
+        return;
+    }
+    if let Some(open) = tree.find('{') {
+        let head = tree[..open].trim_end_matches("::");
+        let inner = tree[open + 1..].trim_end();
+        let inner = inner.strip_suffix('}').unwrap_or(inner);
+        let mut base = prefix.to_vec();
+        base.extend(rust_segments(head));
+        for item in split_top_level(inner, ',') {
+            expand_rust_tree(&base, item, external, package, out);
+        }
+        return;
+    }
+    let (path, alias) = split_alias(tree);
+    let mut segs = prefix.to_vec();
+    if let Some(stripped) = path
+        .strip_suffix("::*")
+        .or_else(|| (path == "*").then_some(""))
+    {
+        segs.extend(rust_segments(stripped));
+        out.extend(ImportBinding::glob(segs, external, package.cloned()));
+        return;
+    }
+    let raw_last = path.rsplit("::").next().unwrap_or(path);
+    segs.extend(rust_segments(path));
+    // `use a::{self}` binds the module `a` itself.
+    let local = alias.unwrap_or(if raw_last == "self" {
+        prefix.last().map_or("", String::as_str)
+    } else {
+        raw_last
+    });
+    out.extend(ImportBinding::symbol(
+        local,
+        segs,
+        external,
+        package.cloned(),
+    ));
+}
+
+/// Splits a Rust path into segments, dropping `crate`, `self`,
+/// `super` and leading `::`.
+fn rust_segments(path: &str) -> Vec<String> {
+    path.split("::")
+        .map(str::trim)
+        .filter(|s| !s.is_empty() && !matches!(*s, "crate" | "self" | "super"))
+        .map(str::to_string)
+        .collect()
+}
+
+/// TypeScript / JavaScript: `import d, { a as b, c } from './x'`,
+/// `import * as ns from 'y'`.
+fn parse_ecmascript(text: &str, out: &mut Vec<ImportBinding>) {
+    let Some(body) = text.trim().strip_prefix("import") else {
+        return;
+    };
+    let body = body.trim().trim_end_matches(';').trim();
+    let Some(from_idx) = body.rfind(" from ") else {
+        return;
+    };
+    let clause = body[..from_idx].trim();
+    let clause = clause.strip_prefix("type ").unwrap_or(clause).trim();
+    let spec = body[from_idx + 6..]
+        .trim()
+        .trim_matches(|c| c == '\'' || c == '"' || c == '`');
+    let external = !(spec.starts_with('.')
+        || spec.starts_with('/')
+        || spec.starts_with("@/")
+        || spec.starts_with("~/"));
+    let module = ecmascript_segments(spec);
+    // `@scope/pkg/sub` comes from `@scope/pkg`, `pkg/sub` from `pkg`.
+    let package = if !external {
+        None
+    } else if spec.starts_with('@') {
+        let name: Vec<&str> = spec.splitn(3, '/').take(2).collect();
+        ImportPackage::new(&name.join("/"), 2)
+    } else {
+        ImportPackage::new(spec.split('/').next().unwrap_or(spec), 1)
+    };
+
+    for part in split_top_level(clause, ',') {
+        let part = part.trim();
+        if let Some(named) = part.strip_prefix('{') {
+            let named = named.trim_end_matches('}');
+            for item in named.split(',') {
+                let item = item.trim();
+                let item = item.strip_prefix("type ").unwrap_or(item);
+                let (name, alias) = split_alias(item);
+                if name.is_empty() {
+                    continue;
+                }
+                let mut path = module.clone();
+                path.push(name.to_string());
+                out.extend(ImportBinding::symbol(
+                    alias.unwrap_or(name),
+                    path,
+                    external,
+                    package.clone(),
+                ));
+            }
+        } else if let Some(ns) = part.strip_prefix('*') {
+            if let Some(alias) = ns.trim().strip_prefix("as ") {
+                out.extend(ImportBinding::symbol(
+                    alias.trim(),
+                    module.clone(),
+                    external,
+                    package.clone(),
+                ));
+            }
+        } else if !part.is_empty() {
+            // Default import: the local name is the best guess for the export.
+            let mut path = module.clone();
+            path.push(part.to_string());
+            out.extend(ImportBinding::symbol(part, path, external, package.clone()));
+        }
+    }
+}
+
+fn ecmascript_segments(spec: &str) -> Vec<String> {
+    let mut segs: Vec<String> = spec
+        .split('/')
+        .map(|s| s.trim_start_matches('@'))
+        .filter(|s| !s.is_empty() && *s != "." && *s != ".." && *s != "~")
+        .map(str::to_string)
+        .collect();
+    if let Some(last) = segs.last_mut() {
+        for ext in [".js", ".jsx", ".ts", ".tsx", ".mjs", ".cjs"] {
+            if let Some(stripped) = last.strip_suffix(ext) {
+                *last = stripped.to_string();
+                break;
+            }
+        }
+    }
+    if segs.last().is_some_and(|s| s == "index") {
+        segs.pop();
+    }
+    segs
+}
+
+/// Python: `import a.b [as c]`, `from .a import b as c, d`.
+fn parse_python(text: &str, out: &mut Vec<ImportBinding>) {
+    let text = text.trim();
+    let dotted = |s: &str| -> Vec<String> {
+        s.split('.')
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+    if let Some(rest) = text.strip_prefix("from ") {
+        let Some((module, names)) = rest.split_once(" import ") else {
+            return;
+        };
+        let module = module.trim();
+        // Relative imports (`from .a import b`) stay inside the project.
+        let package = if module.starts_with('.') {
+            None
+        } else {
+            ImportPackage::new(module.split('.').next().unwrap_or(module), 1)
+        };
+        let module = dotted(module);
+        let names = names.trim().trim_matches(|c| c == '(' || c == ')');
+        for item in names.split(',') {
+            let (name, alias) = split_alias(item.trim());
+            if name == "*" {
+                out.extend(ImportBinding::glob(module.clone(), false, package.clone()));
+            } else if !name.is_empty() {
+                let mut path = module.clone();
+                path.push(name.to_string());
+                out.extend(ImportBinding::symbol(
+                    alias.unwrap_or(name),
+                    path,
+                    false,
+                    package.clone(),
+                ));
+            }
+        }
+    } else if let Some(rest) = text.strip_prefix("import ") {
+        for item in rest.split(',') {
+            let (name, alias) = split_alias(item.trim());
+            let head = name.split('.').next().unwrap_or(name);
+            let package = ImportPackage::new(head, 1);
+            if let Some(alias) = alias {
+                out.extend(ImportBinding::symbol(alias, dotted(name), false, package));
+            } else {
+                // `import a.b` binds `a`; refs look like `a.b.func`.
+                out.extend(ImportBinding::symbol(head, dotted(head), false, package));
+            }
+        }
+    }
+}
+
+/// Java / Kotlin / Scala: `import [static] a.b.C [as D];`, `import a.b.*`.
+fn parse_jvm(text: &str, out: &mut Vec<ImportBinding>) {
+    let Some(body) = text.trim().strip_prefix("import ") else {
+        return;
+    };
+    let body = body.trim().trim_end_matches(';').trim();
+    let body = body.strip_prefix("static ").unwrap_or(body).trim();
+    let external = ["java.", "javax.", "kotlin.", "scala."]
+        .iter()
+        .any(|p| body.starts_with(p));
+    let (path, alias) = split_alias(body);
+    let segs: Vec<String> = path
+        .split('.')
+        .filter(|s| !s.is_empty() && *s != "*" && *s != "_")
+        .map(str::to_string)
+        .collect();
+    if path.ends_with(".*") || path.ends_with("._") {
+        let package = ImportPackage::new(&segs.join("."), segs.len());
+        out.extend(ImportBinding::glob(segs, external, package));
+        return;
+    }
+    // The package is everything before the imported class or member.
+    let package = ImportPackage::new(
+        &segs[..segs.len().saturating_sub(1)].join("."),
+        segs.len().saturating_sub(1),
+    );
+    let last = path.rsplit('.').next().unwrap_or(path);
+    out.extend(ImportBinding::symbol(
+        alias.unwrap_or(last),
+        segs,
+        external,
+        package,
+    ));
+}
+
+/// Go: `"path/to/pkg"` or `alias "path/to/pkg"`. The package is bound under
+/// its alias or the last path element.
+fn parse_go(text: &str, out: &mut Vec<ImportBinding>) {
+    let text = text.trim();
+    let (alias, path) = match text.split_once(char::is_whitespace) {
+        Some((alias, path)) if !alias.starts_with('"') => (Some(alias.trim()), path.trim()),
+        _ => (None, text),
+    };
+    let path = path.trim_matches(|c| c == '"' || c == '`');
+    // Standard library import paths have no dot in their first element.
+    let external = !path.split('/').next().unwrap_or("").contains('.');
+    let segs: Vec<String> = path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect();
+    let package = ImportPackage::new(path, segs.len());
+    match alias {
+        Some(".") => out.extend(ImportBinding::glob(segs, external, package)),
+        Some("_") => {}
+        Some(alias) => out.extend(ImportBinding::symbol(alias, segs, external, package)),
+        None => {
+            let last = path.rsplit('/').next().unwrap_or(path);
+            out.extend(ImportBinding::symbol(last, segs, external, package));
+        }
+    }
+}
+
+/// C / C++: `#include "dir/file.h"` behaves like a glob import of the header.
+fn parse_include(text: &str, out: &mut Vec<ImportBinding>) {
+    let body = text.trim().trim_start_matches('#').trim_start();
+    let body = body.strip_prefix("include").unwrap_or(body).trim();
+    let external = body.starts_with('<');
+    let path = body.trim_matches(|c| c == '"' || c == '<' || c == '>');
+    let mut segs: Vec<String> = path
+        .split('/')
+        .filter(|s| !s.is_empty() && *s != "." && *s != "..")
+        .map(str::to_string)
+        .collect();
+    if let Some(last) = segs.last_mut() {
+        if let Some((stem, _)) = last.split_once('.') {
+            *last = stem.to_string();
+        }
+    }
+    out.extend(ImportBinding::glob(segs, external, None));
+}
@@ -0,0 +1,199 @@
+// Rust guideline compliant 2025-10-17
+use std::collections::{HashMap, HashSet};
+
+use super::ReferenceResolver;
+use crate::db::Database;
+use crate::errors::Result;
+use crate::types::*;
+
+/// Re-resolves only the references an incremental sync can affect.
+///
+/// Every reference is stored with the node it resolved to, so when a set of
+/// files changes only three groups need another look:
+///
+/// - references extracted from the changed files themselves;
+/// - references in unchanged files that pointed into a changed file, whose
+///   edges were dropped together with the old nodes;
+/// - references in unchanged files whose name matches a symbol the changed
+///   files newly declare, which may now bind to it (or to it instead of
+///   their previous target).
+///
+/// References that reach their target only through an import alias are not
+/// found by the last rule; they are picked up when their own file changes.
+///
+/// Usage: call [`before_file_change`](Self::before_file_change) for every
+/// changed or removed file before its nodes are deleted,
+/// [`after_file_indexed`](Self::after_file_indexed) once its new extraction
+/// is stored, then [`finish`](Self::finish).
+pub struct IncrementalResolver<'a> {
+    db: &'a Database,
+    /// Files that were re-indexed or removed in this sync.
+    changed_files: HashSet<String>,
+    /// Node names each changed file declared before the change.
+    old_names: HashMap<String, HashSet<String>>,
+    /// References extracted from the re-indexed files.
+    new_refs: Vec<UnresolvedRef>,
+    /// References from other files that resolved into a changed file.
+    dangling: Vec<UnresolvedRef>,
+    /// Names declared by changed files that they did not declare before.
+    added_names: HashSet<String>,
+}
+
+impl<'a> IncrementalResolver<'a> {
+    /// Creates an empty incremental resolver over `db`.
+    pub fn new(db: &'a Database) -> Self {
+        Self {
+            db,
+            changed_files: HashSet::new(),
+            old_names: HashMap::new(),
+            new_refs: Vec::new(),
+            dangling: Vec::new(),
+            added_names: HashSet::new(),
+        }
+    }
+
+    /// Records the state of `file_path` before its nodes are deleted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database queries fail.
+    pub async fn before_file_change(&mut self, file_path: &str) -> Result<()> {
+        if !self.changed_files.insert(file_path.to_string()) {
+            return Ok(());
+        }
+        self.dangling
+            .extend(self.db.get_refs_targeting_file(file_path).await?);
+        let names = self
+            .db
+            .get_nodes_by_file(file_path)
+            .await?
+            .into_iter()
+            .map(|n| n.name)
+            .collect();
+        self.old_names.insert(file_path.to_string(), names);
+        Ok(())
+    }
+
+    /// Records the freshly stored extraction of `file_path`.
+    pub fn after_file_indexed(&mut self, file_path: &str, result: &ExtractionResult) {
+        self.changed_files.insert(file_path.to_string());
+        self.new_refs.extend(result.unresolved_refs.iter().cloned());
+        let old = self.old_names.get(file_path);
+        for node in &result.nodes {
+            if !old.is_some_and(|names| names.contains(&node.name)) {
+                // References naming a dotted name in full (`var.region`) are
+                // keyed by its last segment, and requests by their path.
+                let key = match split_route(&node.name) {
+                    Some((_, path)) if node.kind == NodeKind::Route => path,
+                    _ => node.name.rsplit('.').next().unwrap_or(&node.name),
+                };
+                self.added_names.insert(key.to_string());
+            }
+        }
+    }
+
+    /// Resolves the affected references, replaces their edges and stores
+    /// their new targets.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any database operation fails.
+    pub async fn finish(self) -> Result<ResolutionResult> {
+        let Self {
+            db,
+            changed_files,
+            new_refs,
+            dangling,
+            added_names,
+            ..
+        } = self;
+
+        let mut refs = new_refs;
+        let mut stale_edges = Vec::new();
+
+        // References from other changed files were deleted and re-extracted.
+        refs.extend(
+            dangling
+                .into_iter()
+                .filter(|r| !changed_files.contains(&r.file_path)),
+        );
+
+        if !added_names.is_empty() {
+            let keys: Vec<String> = added_names.into_iter().collect();
+            for (uref, target) in db.get_refs_by_name_keys(&keys).await? {
+                if changed_files.contains(&uref.file_path) {
+                    continue;
+                }
+                if let Some(target) = target {
+                    stale_edges.push(Edge {
+                        source: uref.from_node_id.clone(),
+                        target,
+                        kind: uref.reference_kind.clone(),
+                        line: Some(uref.line),
+                        provenance: None,
+                    });
+                }
+                refs.push(uref);
+            }
+        }
+
+        let mut seen = HashSet::new();
+        refs.retain(|r| {
+            seen.insert((
+                r.from_node_id.clone(),
+                r.reference_name.clone(),
+                r.reference_kind.clone(),
+                r.line,
+                r.column,
+            ))
+        });
+
+        if refs.is_empty() {
+            db.delete_unreferenced_external_nodes().await?;
+            return Ok(ResolutionResult {
+                resolved: Vec::new(),
+                unresolved: Vec::new(),
+                total: 0,
+                resolved_count: 0,
+            });
+        }
+
+        db.delete_edges(&stale_edges).await?;
+        let resolver = ReferenceResolver::for_refs(db, &refs).await?;
+        let resolution = resolver.resolve_all(&refs);
+        let edges = resolver.create_edges(&resolution.resolved);
+        let (external_nodes, external_edges) = resolver.external_nodes(&resolution.resolved);
+        insert_new_external_nodes(db, external_nodes, external_edges).await?;
+        if !edges.is_empty() {
+            db.insert_edges(&edges).await?;
+        }
+        db.update_ref_targets(&resolution).await?;
+        // Edges into external symbols went away with the changed files.
+        db.delete_unreferenced_external_nodes().await?;
+        Ok(resolution)
+    }
+}
+
+/// Stores the external nodes that are not in the graph yet, with the
+/// `Contains` edges into the new symbols. Nodes already stored are left
+/// alone so the edges into them stay as they are.
+async fn insert_new_external_nodes(
+    db: &Database,
+    mut nodes: Vec<Node>,
+    mut edges: Vec<Edge>,
+) -> Result<()> {
+    if nodes.is_empty() {
+        return Ok(());
+    }
+    let ids: Vec<String> = nodes.iter().map(|n| n.id.clone()).collect();
+    let stored: HashSet<String> = db
+        .get_nodes_by_ids(&ids)
+        .await?
+        .into_iter()
+        .map(|n| n.id)
+        .collect();
+    nodes.retain(|n| !stored.contains(&n.id));
+    edges.retain(|e| !stored.contains(&e.target));
+    db.insert_nodes(&nodes).await?;
+    db.insert_edges(&edges).await
+}
@@ -2,6 +2,19 @@
 ///
 /// Resolves unresolved references (from tree-sitter extraction) into concrete
 /// edges by matching them against known nodes in the database.
+mod diagnostics;
+mod external;
+mod graphql;
+mod imports;
+mod incremental;
+#[cfg(feature = "lang-protobuf")]
+mod proto;
 mod resolver;
 
-pub use resolver::ReferenceResolver;
+pub use diagnostics::{DiagnosticGroup, UnresolvedReport};
+pub use external::EXTERNAL_FILE_PATH;
+pub use graphql::{resolver_edges, resolver_names, DEFAULT_RESOLVER_CONVENTIONS};
+pub use incremental::IncrementalResolver;
+#[cfg(feature = "lang-protobuf")]
+pub use proto::{generated_names, proto_edges};
+pub use resolver::{is_doc_file, ReferenceResolver};
@@ -0,0 +1,237 @@
+//! Protobuf links across languages: the types protoc generates for
+//! messages, the methods serving a service's RPCs and the client calls
+//! invoking them, found by the names protoc's plugins give them in Go,
+//! Rust, Java and TypeScript / JavaScript.
+
+use std::collections::HashMap;
+
+use super::resolver::lang_from_path;
+use crate::types::{Edge, EdgeKind, Node, NodeKind, UnresolvedRef};
+
+/// The languages whose generated code is linked to Protobuf schemas.
+const LANGUAGES: &[&str] = &["go", "rust", "java", "typescript", "javascript"];
+
+/// Suffixes protoc plugins append to the stem of the schema a file is
+/// generated from (`user_grpc.pb.go`, `user_pb.d.ts`, `UserOuterClass.java`).
+const GENERATED_SUFFIXES: &[&str] = &["_grpc_pb", "_grpc", "_pb", "OuterClass", "Grpc"];
+
+/// The names the code generated from `protos` gives messages and RPC
+/// methods in every linked language, for loading the candidate nodes and
+/// call references.
+pub fn generated_names(protos: &[Node]) -> Vec<String> {
+    let mut names: Vec<String> = protos
+        .iter()
+        .flat_map(|proto| match proto.kind {
+            NodeKind::ProtoMessage => vec![proto.name.clone()],
+            NodeKind::ProtoRpc => LANGUAGES
+                .iter()
+                .filter_map(|lang| rpc_method(&proto.name, lang))
+                .collect(),
+            _ => Vec::new(),
+        })
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    names
+}
+
+/// Links Protobuf schemas to the code of other languages:
+///
+/// - a type named after a message, in a file generated from the message's
+///   schema (`user.pb.go`, `user_pb.d.ts`, `UserOuterClass.java` for
+///   `user.proto`) or, in Rust, from its package (`user.v1.rs` for
+///   `package user.v1`), `Implements` the message;
+/// - a method named after an RPC in its language's casing (`GetUser` in Go,
+///   `get_user` in Rust, `getUser` in Java and TypeScript) whose signature
+///   takes the RPC's request message `Implements` the RPC;
+/// - a function calling such a method on a client or stub (a receiver
+///   named `client` / `stub`, or typed after the service) `Calls` the RPC.
+pub fn proto_edges(protos: &[Node], code: &[Node], refs: &[UnresolvedRef]) -> Vec<Edge> {
+    let mut by_name: HashMap<&str, Vec<&Node>> = HashMap::new();
+    for node in code {
+        if LANGUAGES.contains(&lang_from_path(&node.file_path)) {
+            by_name.entry(&node.name).or_default().push(node);
+        }
+    }
+    let mut calls: HashMap<&str, Vec<&UnresolvedRef>> = HashMap::new();
+    for uref in refs {
+        if uref.reference_kind == EdgeKind::Calls {
+            calls.entry(uref.name_key()).or_default().push(uref);
+        }
+    }
+
+    // `package users.v1;` is recorded as a `Package` node signed with it.
+    let packages: HashMap<&str, &str> = protos
+        .iter()
+        .filter(|n| n.kind == NodeKind::Package && n.file_path.rsplit('.').next() == Some("proto"))
+        .filter_map(|n| {
+            let package = n.signature.as_deref()?.strip_prefix("package")?.trim();
+            Some((n.file_path.as_str(), package))
+        })
+        .collect();
+
+    let mut edges = Vec::new();
+    for proto in protos {
+        match proto.kind {
+            NodeKind::ProtoMessage => {
+                let package = packages.get(proto.file_path.as_str()).copied();
+                let types = by_name.get(proto.name.as_str()).into_iter().flatten();
+                for generated in types.filter(|n| {
+                    n.kind.is_type_definition()
+                        && generated_from(&n.file_path, &proto.file_path, package)
+                }) {
+                    edges.push(edge(
+                        &generated.id,
+                        proto,
+                        EdgeKind::Implements,
+                        generated.start_line,
+                    ));
+                }
+            }
+            NodeKind::ProtoRpc => {
+                let request = proto.signature.as_deref().and_then(request_type);
+                let service = proto.qualified_name.rsplit("::").nth(1).unwrap_or("");
+                for lang in LANGUAGES {
+                    let Some(method) = rpc_method(&proto.name, lang) else {
+                        continue;
+                    };
+                    let handlers = by_name.get(method.as_str()).into_iter().flatten();
+                    for handler in handlers.filter(|n| {
+                        matches!(
+                            n.kind,
+                            NodeKind::Function | NodeKind::Method | NodeKind::StructMethod
+                        ) && lang_from_path(&n.file_path) == *lang
+                            && request.is_some_and(|r| takes(n, r))
+                    }) {
+                        edges.push(edge(
+                            &handler.id,
+                            proto,
+                            EdgeKind::Implements,
+                            handler.start_line,
+                        ));
+                    }
+                    let sites = calls.get(method.as_str()).into_iter().flatten();
+                    for call in sites.filter(|r| {
+                        lang_from_path(&r.file_path) == *lang && is_client_call(r, service)
+                    }) {
+                        edges.push(edge(&call.from_node_id, proto, EdgeKind::Calls, call.line));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    edges
+}
+
+fn edge(source: &str, proto: &Node, kind: EdgeKind, line: u32) -> Edge {
+    Edge {
+        source: source.to_string(),
+        target: proto.id.clone(),
+        kind,
+        line: Some(line),
+        provenance: None,
+    }
+}
+
+/// The name protoc's plugins give the method of an RPC in `lang`.
+fn rpc_method(rpc: &str, lang: &str) -> Option<String> {
+    match lang {
+        "go" => Some(rpc.to_string()),
+        "rust" => Some(snake_case(rpc)),
+        "java" | "typescript" | "javascript" => {
+            let mut chars = rpc.chars();
+            let first = chars.next()?;
+            Some(first.to_ascii_lowercase().to_string() + chars.as_str())
+        }
+        _ => None,
+    }
+}
+
+/// `GetUserByID` → `get_user_by_id`, as prost and tonic name methods.
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::with_capacity(name.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            let previous = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(char::is_ascii_lowercase);
+            if previous.is_ascii_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_ascii_uppercase() && next_lower)
+            {
+                snake.push('_');
+            }
+        }
+        snake.push(c.to_ascii_lowercase());
+    }
+    snake
+}
+
+/// The request message of an RPC signature
+/// (`rpc Watch(stream pkg.WatchRequest) returns (...)` → `WatchRequest`).
+fn request_type(signature: &str) -> Option<&str> {
+    let (_, rest) = signature.split_once('(')?;
+    let (request, _) = rest.split_once(')')?;
+    let request = request.trim();
+    let request = request.strip_prefix("stream ").unwrap_or(request).trim();
+    let request = request.rsplit('.').next()?;
+    (!request.is_empty()).then_some(request)
+}
+
+/// True when a function's signature names `message` as a whole word.
+fn takes(function: &Node, message: &str) -> bool {
+    let Some(signature) = function.signature.as_deref() else {
+        return false;
+    };
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    signature.match_indices(message).any(|(start, _)| {
+        let end = start + message.len();
+        !signature[..start].ends_with(is_ident) && !signature[end..].starts_with(is_ident)
+    })
+}
+
+/// True when a call goes through a generated client: its receiver is named
+/// like a client or stub, or its type is named after the service.
+fn is_client_call(call: &UnresolvedRef, service: &str) -> bool {
+    let name = call.reference_name.as_str();
+    let receiver = name
+        .rsplit_once("::")
+        .or_else(|| name.rsplit_once('.'))
+        .map_or("", |(receiver, _)| receiver)
+        .to_ascii_lowercase();
+    receiver.contains("client")
+        || receiver.contains("stub")
+        || call
+            .receiver_type
+            .as_deref()
+            .is_some_and(|t| !service.is_empty() && t.contains(service))
+}
+
+/// True when `code_path` is named like a file protoc generates from the
+/// schema at `proto_path`: the same stem, ignoring case, separators and the
+/// plugins' suffixes. prost instead writes one Rust file per proto
+/// `package`, named after it (`user.v1.rs`, or `_.rs` without a package),
+/// or a module per segment (`user/v1.rs`).
+fn generated_from(code_path: &str, proto_path: &str, package: Option<&str>) -> bool {
+    fn stem(path: &str) -> String {
+        let name = path.rsplit('/').next().unwrap_or(path);
+        let name = name.split('.').next().unwrap_or(name);
+        let name = GENERATED_SUFFIXES
+            .iter()
+            .find_map(|suffix| name.strip_suffix(suffix))
+            .unwrap_or(name);
+        name.chars()
+            .filter(char::is_ascii_alphanumeric)
+            .map(|c| c.to_ascii_lowercase())
+            .collect()
+    }
+    if let Some(module) = code_path.strip_suffix(".rs") {
+        let package = package.unwrap_or("_");
+        let module = module.strip_suffix("/mod").unwrap_or(module);
+        let file = module.rsplit('/').next().unwrap_or(module);
+        let nested = package.replace('.', "/");
+        return file == package || module == nested || module.ends_with(&format!("/{nested}"));
+    }
+    stem(code_path) == stem(proto_path)
+}
@@ -1,19 +1,123 @@
 // Rust guideline compliant 2025-10-17
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use rayon::prelude::*;
 
+use super::external::{self, ExternalTarget, ProjectModules};
+use super::graphql::field_type;
+use super::imports::{
+    bindings_for_node, module_affinity, node_module_segments, norm, ImportBinding,
+};
 use crate::db::Database;
+use crate::packages::PackageMap;
 use crate::types::*;
 
+/// True for documentation files, whose references link prose to code.
+pub fn is_doc_file(path: &str) -> bool {
+    matches!(path.rsplit('.').next().unwrap_or(""), "md" | "markdown")
+}
+
+/// True for the configuration files whose keys the config extractors index.
+pub fn is_config_file(path: &str) -> bool {
+    matches!(
+        path.rsplit('.').next().unwrap_or(""),
+        "yaml" | "yml" | "toml" | "json"
+    )
+}
+
+/// True for Terraform files, whose blocks form one module per directory.
+pub fn is_terraform_file(path: &str) -> bool {
+    matches!(path.rsplit('.').next().unwrap_or(""), "tf" | "tofu")
+}
+
+/// True for GraphQL documents, whose refs only bind to schema nodes.
+pub fn is_graphql_file(path: &str) -> bool {
+    matches!(path.rsplit('.').next().unwrap_or(""), "graphql" | "gql")
+}
+
+/// True for the Makefiles, `CMake` files and Bazel / Buck `BUILD` files whose
+/// refs name build targets and source files.
+pub fn is_build_file(path: &str) -> bool {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    matches!(
+        file_name,
+        "Makefile"
+            | "makefile"
+            | "GNUmakefile"
+            | "CMakeLists.txt"
+            | "BUILD"
+            | "BUILD.bazel"
+            | "BUCK"
+    ) || matches!(file_name.rsplit('.').next().unwrap_or(""), "mk" | "cmake")
+}
+
+/// True for C and C++ headers, whose functions are prototypes of functions
+/// defined elsewhere.
+fn is_header(path: &str) -> bool {
+    matches!(
+        path.rsplit('.').next().unwrap_or(""),
+        "h" | "hh" | "hpp" | "hxx"
+    )
+}
+
+/// True for a function other languages can call by its name as a C symbol:
+/// a C or C++ function that is not `static`, or a Rust one `exports` lists
+/// as exported to C (`#[no_mangle]`).
+fn is_c_callable(node: &Node, exports: &HashMap<String, Vec<String>>) -> bool {
+    node.kind == NodeKind::Function
+        && match lang_from_path(&node.file_path) {
+            "c" | "cpp" => node.visibility != Visibility::Private,
+            "rust" => is_exported_to(node, "C", exports),
+            _ => false,
+        }
+}
+
+/// True if `exports` lists `node` as exported to `language`.
+fn is_exported_to(node: &Node, language: &str, exports: &HashMap<String, Vec<String>>) -> bool {
+    exports
+        .get(&node.id)
+        .is_some_and(|languages| languages.iter().any(|l| l == language))
+}
+
+/// Records the export `uref` declares, if any, in `exports`.
+fn add_export(exports: &mut HashMap<String, Vec<String>>, uref: &UnresolvedRef) {
+    let Some(language) = uref.export_language() else {
+        return;
+    };
+    let languages = exports.entry(uref.from_node_id.clone()).or_default();
+    if !languages.iter().any(|l| l == language) {
+        languages.push(language.to_string());
+    }
+}
+
+/// The language a Rust function must be exported to for `uref` to call it:
+/// `Python` for a call from Python, `JavaScript` for one from JavaScript or
+/// TypeScript.
+fn extension_language(uref: &UnresolvedRef) -> Option<&'static str> {
+    if uref.reference_kind != EdgeKind::Calls {
+        return None;
+    }
+    match lang_from_path(&uref.file_path) {
+        "python" => Some("Python"),
+        "javascript" | "typescript" => Some("JavaScript"),
+        _ => None,
+    }
+}
+
+/// The directory of a file path, empty for files at the project root.
+fn parent_dir(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
 /// Infer a coarse language tag from a file path extension.
-fn lang_from_path(path: &str) -> &'static str {
+pub(super) fn lang_from_path(path: &str) -> &'static str {
     match path.rsplit('.').next().unwrap_or("") {
         "rs" => "rust",
         "go" => "go",
         "py" | "pyi" => "python",
         "js" | "jsx" | "mjs" | "cjs" => "javascript",
-        "ts" | "tsx" | "mts" | "cts" => "typescript",
+        // Component scripts are extracted as TypeScript.
+        "ts" | "tsx" | "mts" | "cts" | "vue" | "svelte" | "astro" => "typescript",
         "java" => "java",
         "kt" | "kts" => "kotlin",
         "swift" => "swift",
@@ -30,6 +134,11 @@ fn lang_from_path(path: &str) -> &'static str {
         "nix" => "nix",
         "zig" => "zig",
         "proto" => "proto",
+        "yaml" | "yml" => "yaml",
+        "toml" => "toml",
+        "json" => "json",
+        "tf" | "tofu" => "terraform",
+        "graphql" | "gql" => "graphql",
         _ => "unknown",
     }
 }
@@ -47,6 +156,158 @@ fn path_proximity(a: &str, b: &str) -> i64 {
     (shared as i64 * 5).min(40)
 }
 
+/// Splits a reference name into path segments on `::` or `.`, dropping a
+/// leading `new ` (Java/C#/Kotlin constructor calls).
+fn reference_segments(name: &str) -> Vec<&str> {
+    let name = name.strip_prefix("new ").unwrap_or(name).trim();
+    if name.contains("::") {
+        name.split("::").collect()
+    } else {
+        name.split('.').collect()
+    }
+}
+
+/// Reduces a type as spelled in a reference or a node name to its simple
+/// name: `crate::db::Database<T>` → `Database`.
+fn simple_type_name(name: &str) -> &str {
+    let name = name.split(['<', '[']).next().unwrap_or(name);
+    let name = name.rsplit("::").next().unwrap_or(name);
+    name.rsplit('.').next().unwrap_or(name).trim()
+}
+
+/// The type a method node belongs to: its parent in the qualified name, or
+/// the receiver of a Go method signature (`func (s *Server) Run()`).
+fn method_owner(node: &Node) -> Option<&str> {
+    if let Some(receiver) = node
+        .signature
+        .as_deref()
+        .and_then(|sig| sig.strip_prefix("func ("))
+    {
+        let receiver = receiver.split(')').next()?.split_whitespace().last()?;
+        return Some(simple_type_name(receiver.trim_start_matches('*')));
+    }
+    let mut segments = node.qualified_name.rsplit("::");
+    segments.next();
+    segments.next().map(simple_type_name)
+}
+
+/// Records that `type_name` extends or implements `supertype`.
+fn add_supertype(supertypes: &mut HashMap<String, Vec<String>>, type_name: &str, supertype: &str) {
+    let parents = supertypes
+        .entry(simple_type_name(type_name).to_string())
+        .or_default();
+    let supertype = simple_type_name(supertype);
+    if !parents.iter().any(|p| p == supertype) {
+        parents.push(supertype.to_string());
+    }
+}
+
+/// True for the files GraphQL selections come from: GraphQL documents and
+/// the scripts whose `gql` tagged templates are extracted with them.
+fn has_graphql(path: &str) -> bool {
+    is_graphql_file(path)
+        || matches!(
+            path.rsplit('.').next().unwrap_or(""),
+            "ts" | "tsx" | "js" | "jsx"
+        )
+}
+
+/// Loads the schema fields along the GraphQL selection paths among `refs`,
+/// one hop at a time: `Query.user.posts` needs `Query.user`, then the
+/// `posts` field of the type it returns.
+async fn selected_fields(
+    db: &Database,
+    refs: &[UnresolvedRef],
+) -> crate::errors::Result<Vec<Node>> {
+    let mut paths: Vec<(String, std::str::Split<'_, char>)> = refs
+        .iter()
+        .filter(|r| has_graphql(&r.file_path) && r.reference_name.matches('.').count() > 1)
+        .filter_map(|r| {
+            let mut segments = r.reference_name.split('.');
+            let field = format!("{}.{}", segments.next()?, segments.next()?);
+            Some((field, segments))
+        })
+        .collect();
+    let mut fields = Vec::new();
+    while !paths.is_empty() {
+        let names: Vec<String> = paths
+            .iter()
+            .map(|(field, _)| field.clone())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let mut returns: HashMap<String, String> = HashMap::new();
+        for node in db.get_nodes_by_names(&names).await? {
+            if node.kind != NodeKind::GqlField {
+                continue;
+            }
+            if let Some(ty) = node.signature.as_deref().and_then(field_type) {
+                returns.insert(node.name.clone(), ty.to_string());
+            }
+            fields.push(node);
+        }
+        paths = paths
+            .into_iter()
+            .filter_map(|(field, mut segments)| {
+                let segment = segments.next()?;
+                Some((format!("{}.{segment}", returns.get(&field)?), segments))
+            })
+            .collect();
+    }
+    Ok(fields)
+}
+
+/// The normalised path fragments a project file must contain to tell the
+/// imports `refs` may go through, or the Rust paths they spell, from
+/// dependencies: the package root for Rust and Python, the leading package
+/// segments for JVM languages and Go.
+fn module_probes(refs: &[UnresolvedRef], nodes: &[Node]) -> Vec<String> {
+    let mut probes: HashSet<String> = HashSet::new();
+    for node in nodes {
+        if !matches!(node.kind, NodeKind::Use | NodeKind::Include) {
+            continue;
+        }
+        let lang = lang_from_path(&node.file_path);
+        for binding in bindings_for_node(node, lang) {
+            let Some(package) = binding.package.as_ref().filter(|_| !binding.external) else {
+                continue;
+            };
+            match lang {
+                "rust" | "python" => {
+                    probes.insert(norm(&package.name));
+                }
+                "java" | "kotlin" | "scala" | "go" => {
+                    let leading: Vec<String> =
+                        binding.path.iter().take(2).map(|s| norm(s)).collect();
+                    probes.insert(leading.join("/"));
+                }
+                _ => {}
+            }
+        }
+    }
+    for uref in refs {
+        if lang_from_path(&uref.file_path) == "rust" && uref.reference_name.contains("::") {
+            if let Some(head) = reference_segments(&uref.reference_name).first() {
+                probes.insert(norm(head));
+            }
+        }
+    }
+    probes.retain(|p| !p.is_empty());
+    probes.into_iter().collect()
+}
+
+/// What the calling file's imports say about a reference.
+enum ImportOutcome {
+    /// The imports pin the reference to a concrete node.
+    Resolved(Box<ResolvedRef>),
+    /// The name is bound to an import from outside the project, so any local
+    /// symbol with the same name is a false match; it resolves to an
+    /// external node instead.
+    External,
+    /// The imports do not mention the name.
+    NoOpinion,
+}
+
 /// Resolves unresolved references into concrete edges by matching them against
 /// known nodes loaded from the database.
 ///
@@ -65,6 +326,27 @@ pub struct ReferenceResolver<'a> {
     suffix_cache: HashMap<String, Vec<String>>,
     /// All known symbol names (short + qualified + suffixes) for pre-filtering.
     known_names: HashSet<String>,
+    /// Import bindings declared by each file, keyed by file path.
+    imports: HashMap<String, Vec<ImportBinding>>,
+    /// Simple names of type definitions and `impl` blocks, keyed by node ID,
+    /// for reading the supertypes declared by `extends` / `implements`
+    /// references.
+    type_names: HashMap<String, String>,
+    /// Direct supertypes (extended classes, implemented interfaces and
+    /// traits) of each type, keyed by simple type name.
+    supertypes: HashMap<String, Vec<String>>,
+    /// The languages each function is exported to by an attribute (`C`,
+    /// `Python`, `JavaScript`), keyed by node ID.
+    exports: HashMap<String, Vec<String>>,
+    /// The manifest packages of the project, for preferring candidates in
+    /// the referencing file's package or in packages it depends on.
+    packages: PackageMap,
+    /// The project's directories, files and packages, for telling imports of
+    /// dependencies from imports of project code.
+    modules: ProjectModules,
+    /// The type each GraphQL field returns, keyed by `Type.field`, for
+    /// following selection paths.
+    graphql_fields: HashMap<String, String>,
 }
 
 impl<'a> ReferenceResolver<'a> {
@@ -85,8 +367,46 @@ impl<'a> ReferenceResolver<'a> {
         let mut name_cache: HashMap<String, Vec<Node>> = HashMap::new();
         let mut qualified_name_cache: HashMap<String, Vec<Node>> = HashMap::new();
         let mut suffix_cache: HashMap<String, Vec<String>> = HashMap::new();
+        let mut imports: HashMap<String, Vec<ImportBinding>> = HashMap::new();
+        let mut type_names: HashMap<String, String> = HashMap::new();
+        let mut graphql_fields: HashMap<String, String> = HashMap::new();
+        let mut modules = ProjectModules::default();
+        let mut files: HashSet<&str> = HashSet::new();
 
         for node in all_nodes {
+            // Packages are declared by manifests, never referenced by code,
+            // external nodes are only reached through imports, and domain
+            // symbols are named by string literals rather than identifiers.
+            if matches!(
+                node.kind,
+                NodeKind::ManifestPackage
+                    | NodeKind::ExternalPackage
+                    | NodeKind::ExternalSymbol
+                    | NodeKind::DomainSymbol
+            ) {
+                continue;
+            }
+            if files.insert(node.file_path.as_str()) {
+                modules.add_file(&node.file_path);
+            }
+            if node.kind.is_type_definition() || node.kind == NodeKind::Impl {
+                type_names.insert(node.id.clone(), simple_type_name(&node.name).to_string());
+            }
+            if node.kind == NodeKind::GqlField {
+                if let Some(returns) = node.signature.as_deref().and_then(field_type) {
+                    graphql_fields.insert(node.name.clone(), returns.to_string());
+                }
+            }
+            if matches!(node.kind, NodeKind::Use | NodeKind::Include) {
+                let bindings = imports.entry(node.file_path.clone()).or_default();
+                for binding in bindings_for_node(node, lang_from_path(&node.file_path)) {
+                    // Python emits one Use node per imported name, each
+                    // carrying the whole statement; keep the first copy.
+                    if !bindings.contains(&binding) {
+                        bindings.push(binding);
+                    }
+                }
+            }
             name_cache
                 .entry(node.name.clone())
                 .or_default()
@@ -135,25 +455,205 @@ impl<'a> ReferenceResolver<'a> {
             qualified_name_cache,
             suffix_cache,
             known_names,
+            imports,
+            type_names,
+            supertypes: HashMap::new(),
+            exports: HashMap::new(),
+            packages: PackageMap::default(),
+            modules,
+            graphql_fields,
+        }
+    }
+
+    /// Scores candidates using the package boundaries in `packages`, and
+    /// treats imports of those packages as imports of project code.
+    #[must_use]
+    pub fn with_packages(mut self, packages: PackageMap) -> Self {
+        for key in packages.keys() {
+            self.modules.add_package(key);
+        }
+        self.packages = packages;
+        self
+    }
+
+    /// Creates a resolver that loads only the nodes `refs` can resolve to:
+    /// the import nodes of the files they come from, plus every node whose
+    /// name is a reference's name key or an imported name.
+    ///
+    /// GraphQL selection paths load the schema fields along them, method
+    /// calls with a known receiver type the supertypes recorded in the
+    /// database, and imports the project files that could tell them apart
+    /// from dependencies, and calls that may cross a language boundary the
+    /// exports recorded in the database. The project's packages always are.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if loading the nodes fails.
+    pub async fn for_refs(db: &'a Database, refs: &[UnresolvedRef]) -> crate::errors::Result<Self> {
+        let files: Vec<String> = refs
+            .iter()
+            .map(|r| r.file_path.clone())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let import_nodes = db.get_import_nodes_by_files(&files).await?;
+
+        // A dotted name (`var.region`, or `nodes.kind` in SQL) may also name
+        // a node in full, and a request names the routes for its method and
+        // for any method.
+        let mut names: HashSet<String> = refs
+            .iter()
+            .flat_map(|r| [r.name_key().to_string(), r.reference_name.clone()])
+            .collect();
+        names.extend(
+            refs.iter()
+                .filter_map(UnresolvedRef::route)
+                .flat_map(|(method, path)| {
+                    [
+                        UnresolvedRef::route_name(method, path),
+                        UnresolvedRef::route_name("ANY", path),
+                    ]
+                }),
+        );
+        for node in &import_nodes {
+            for binding in bindings_for_node(node, lang_from_path(&node.file_path)) {
+                if let Some(last) = binding.path.last() {
+                    names.insert(last.clone());
+                }
+            }
+        }
+        let names: Vec<String> = names.into_iter().collect();
+
+        let mut nodes = import_nodes;
+        let mut seen: HashSet<String> = nodes.iter().map(|n| n.id.clone()).collect();
+        let mut loaded = db.get_nodes_by_names(&names).await?;
+        loaded.extend(selected_fields(db, refs).await?);
+        for node in loaded {
+            if seen.insert(node.id.clone()) {
+                nodes.push(node);
+            }
+        }
+        let probes = module_probes(refs, &nodes);
+        let mut resolver = Self::from_nodes(db, &nodes).with_packages(PackageMap::load(db).await?);
+        for path in db.get_file_paths_containing(&probes).await? {
+            resolver.modules.add_file(&path);
+        }
+        if refs.iter().any(|r| r.receiver_type.is_some()) {
+            for (type_name, supertype) in db.get_supertype_refs().await? {
+                add_supertype(&mut resolver.supertypes, &type_name, &supertype);
+            }
+        }
+        if refs
+            .iter()
+            .any(|r| r.c_symbol().is_some() || extension_language(r).is_some())
+        {
+            for uref in db.get_export_refs().await? {
+                add_export(&mut resolver.exports, &uref);
+            }
         }
+        Ok(resolver)
     }
 
     /// Attempts to resolve a single unresolved reference.
     ///
     /// Resolution strategies are tried in order:
-    /// 1. **Qualified name match** -- if the reference contains `::`, try
+    /// 0. **Receiver type** -- a method call whose receiver has a declared
+    ///    type binds to the method of that name on the type, or else on its
+    ///    nearest supertype (confidence 0.9).
+    /// 1. **Import match** -- if the calling file imports the reference's
+    ///    leading name (directly, via an alias, or through a glob import),
+    ///    only candidates reachable through that import are considered
+    ///    (confidence 0.95, or 0.9 for glob imports). A name bound to an
+    ///    import of a dependency outside the project resolves to an
+    ///    [`NodeKind::ExternalSymbol`] node (confidence 0.9) when no project
+    ///    symbol is reachable through the import.
+    /// 2. **Qualified name match** -- if the reference contains `::`, try
     ///    matching against qualified names of known nodes (confidence 0.95).
-    /// 2. **Exact name match** -- look up the reference name in the name cache.
+    /// 3. **External symbol** -- a Rust path rooted at a crate that is not
+    ///    part of the project (`serde_json::to_string`, `Vec::new`), or a
+    ///    method call on a receiver whose type was imported from a
+    ///    dependency, resolves to an external symbol (confidence 0.9). Use
+    ///    [`external_nodes`](Self::external_nodes) to build those nodes.
+    /// 4. **Exact name match** -- look up the reference name in the name cache.
     ///    A single match yields confidence 0.9; multiple matches are scored via
     ///    `find_best_match` and the winner gets confidence 0.7.
     ///
+    /// Between strategies 0 and 1, a GraphQL selection path
+    /// (`Query.user.posts`) binds to the schema field it ends at
+    /// (confidence 0.9), and a Python or JavaScript call to a Rust function
+    /// exported to that language (confidence 0.8). A reference to a C symbol
+    /// (one with the `C` [`abi`](UnresolvedRef::abi)) skips the strategies
+    /// and binds only to a function other languages can call by that symbol
+    /// (confidence 0.8), an HTTP request (`/users/{}` with method `GET`)
+    /// only to a route serving it, and a reference from a SQL statement
+    /// (`nodes.kind`) only to the table, view or column of that name (both confidence 0.8).
+    ///
+    /// The result's `strategy` classifies the match (see
+    /// [`ResolutionStrategy`]); `create_edges` stores it, with the
+    /// confidence, as the edge's provenance.
+    ///
+    /// Type references (`TypeOf`, `Returns`, `Receives`) only ever resolve to
+    /// type definitions, so a function that shares a type's name is skipped.
+    ///
     /// Returns `None` if no strategy can resolve the reference.
     pub fn resolve_one(&self, uref: &UnresolvedRef) -> Option<ResolvedRef> {
-        // Strategy 1: qualified name match
+        self.resolve_with(uref, &self.supertypes, &self.exports)
+    }
+
+    /// Resolves `uref` following the type hierarchy in `supertypes`.
+    fn resolve_with(
+        &self,
+        uref: &UnresolvedRef,
+        supertypes: &HashMap<String, Vec<String>>,
+        exports: &HashMap<String, Vec<String>>,
+    ) -> Option<ResolvedRef> {
+        // References across a language boundary bind by C symbol alone.
+        if let Some(symbol) = uref.c_symbol() {
+            return self.try_c_symbol_match(uref, symbol, exports);
+        }
+        // Other exports declare a binding rather than look one up.
+        if uref.export_language().is_some() {
+            return None;
+        }
+        // HTTP requests bind only to the routes serving them.
+        if let Some((method, path)) = uref.route() {
+            return self.try_route_match(uref, method, path);
+        }
+        // SQL statements reference schema objects only.
+        if let Some(object) = uref.sql_object() {
+            return self.try_sql_match(uref, object);
+        }
+
+        // Strategy 0: the declared type of a method call's receiver
+        if let Some(resolved) = self.try_receiver_match(uref, supertypes) {
+            return Some(resolved);
+        }
+
+        if let Some(resolved) = self.try_selection_match(uref) {
+            return Some(resolved);
+        }
+
+        if let Some(resolved) = self.try_extension_match(uref, exports) {
+            return Some(resolved);
+        }
+
+        // Strategy 1: the calling file's imports
+        match self.try_import_match(uref) {
+            ImportOutcome::Resolved(resolved) => return Some(*resolved),
+            ImportOutcome::External => return self.try_external_match(uref),
+            ImportOutcome::NoOpinion => {}
+        }
+
+        // Strategy 2: qualified name match
         if uref.reference_name.contains("::") {
             if let Some(resolved) = self.try_qualified_match(uref) {
                 return Some(resolved);
             }
+            // Strategy 3: a path no project symbol matches may start at a
+            // dependency
+            if let Some(resolved) = self.try_external_match(uref) {
+                return Some(resolved);
+            }
             // Fall through to try exact name match with the simple name
             let simple_name = uref
                 .reference_name
@@ -166,31 +666,127 @@ impl<'a> ReferenceResolver<'a> {
             return None;
         }
 
-        // Strategy 2: exact name match
-        self.try_exact_name_match(uref)
+        // Strategy 3: a method of an external type
+        if let Some(resolved) = self.try_external_match(uref) {
+            return Some(resolved);
+        }
+
+        // Strategy 4: exact name match
+        if let Some(resolved) = self.try_exact_name_match(uref) {
+            return Some(resolved);
+        }
+
+        // A dotted type name (`models.User`) that the imports did not place
+        // falls back to its last segment, as `::` paths do above.
+        if uref.reference_kind.is_type_reference() {
+            if let Some((_, simple_name)) = uref.reference_name.rsplit_once('.') {
+                return self.try_exact_name_match_simple(uref, simple_name);
+            }
+        }
+        None
     }
 
-    /// Returns true if a reference name could plausibly resolve to a known symbol.
-    fn is_known_name(&self, name: &str) -> bool {
-        self.known_names.contains(name)
+    /// Returns true if a reference could plausibly resolve to a known symbol,
+    /// either by name or through an import binding of its leading segment,
+    /// or to an external symbol.
+    fn is_known_name(&self, uref: &UnresolvedRef) -> bool {
+        if let Some(symbol) = uref.c_symbol() {
+            return self.name_cache.contains_key(symbol);
+        }
+        if let Some(object) = uref.sql_object() {
+            return self.name_cache.contains_key(object);
+        }
+        if let Some((method, path)) = uref.route() {
+            return [method, "ANY"].iter().any(|m| {
+                self.known_names
+                    .contains(&UnresolvedRef::route_name(m, path))
+            });
+        }
+        if self.known_names.contains(&uref.reference_name) {
+            return true;
+        }
+        if uref.receiver_type.is_some() && self.name_cache.contains_key(uref.name_key()) {
+            return true;
+        }
+        if self.selected_field(uref).is_some() {
+            return true;
+        }
+        if uref.reference_kind.is_type_reference()
+            && uref
+                .reference_name
+                .rsplit_once('.')
+                .is_some_and(|(_, simple)| self.known_names.contains(simple))
+        {
+            return true;
+        }
+        let Some(bindings) = self.imports.get(&uref.file_path) else {
+            return self.external_target(uref).is_some();
+        };
+        let segments = reference_segments(&uref.reference_name);
+        segments
+            .first()
+            .is_some_and(|head| bindings.iter().any(|b| !b.glob && b.local == *head))
+            || self.external_target(uref).is_some()
+    }
+
+    /// Explains a reference that resolution leaves out of the graph or binds
+    /// arbitrarily. Returns `None` when it resolves to a single best target.
+    ///
+    /// Refs `resolve_all` skips up front are reported as
+    /// [`UnresolvedReason::UnknownName`] when no node shares even their last
+    /// segment, and as [`UnresolvedReason::FilteredByKnownNames`] otherwise.
+    /// A resolved ref is reported only when its target was picked among
+    /// candidates that tied for the best score. Exports to Python and
+    /// JavaScript, which never resolve, are not reported.
+    pub fn diagnose(&self, uref: &UnresolvedRef) -> Option<UnresolvedReason> {
+        if uref.export_language().is_some() && uref.c_symbol().is_none() {
+            return None;
+        }
+        if !self.is_known_name(uref) {
+            return Some(if self.name_cache.contains_key(uref.name_key()) {
+                UnresolvedReason::FilteredByKnownNames
+            } else {
+                UnresolvedReason::UnknownName
+            });
+        }
+        let Some(resolved) = self.resolve_one(uref) else {
+            return Some(UnresolvedReason::NoFittingCandidate);
+        };
+        if !matches!(resolved.strategy, ResolutionStrategy::Ambiguous { .. }) {
+            return None;
+        }
+        // Ambiguous matches choose among the nodes named like the target,
+        // which is the reference's last segment unless an import renamed it.
+        let candidates = [uref.name_key(), uref.reference_name.as_str()]
+            .into_iter()
+            .filter_map(|name| self.name_cache.get(name))
+            .chain(self.name_cache.values())
+            .find(|nodes| nodes.iter().any(|n| n.id == resolved.target_node_id))?;
+        let tied = self.best_score_count(uref, candidates);
+        (tied > 1).then(|| UnresolvedReason::TiedCandidates {
+            candidates: u32::try_from(tied).unwrap_or(u32::MAX),
+        })
     }
 
     /// Resolves a batch of unresolved references in parallel, returning a
     /// summary of the results.
     ///
     /// Pre-filters references whose name doesn't exist in the graph at all,
-    /// turning hopeless lookups into O(1) hash checks.
+    /// turning hopeless lookups into O(1) hash checks. Supertypes declared
+    /// by `extends` / `implements` references in the batch are followed
+    /// when resolving method calls by receiver type.
     pub fn resolve_all(&self, refs: &[UnresolvedRef]) -> ResolutionResult {
         let total = refs.len();
+        let supertypes = self.supertypes_with(refs);
+        let exports = self.exports_with(refs);
 
         // Partition into resolvable (name exists in graph) and hopeless.
-        let (candidates, hopeless): (Vec<_>, Vec<_>) = refs
-            .iter()
-            .partition(|uref| self.is_known_name(&uref.reference_name));
+        let (candidates, hopeless): (Vec<_>, Vec<_>) =
+            refs.iter().partition(|uref| self.is_known_name(uref));
 
         let results: Vec<_> = candidates
             .par_iter()
-            .map(|uref| (*uref, self.resolve_one(uref)))
+            .map(|uref| (*uref, self.resolve_with(uref, &supertypes, &exports)))
             .collect();
 
         let mut resolved = Vec::new();
@@ -221,24 +817,269 @@ impl<'a> ReferenceResolver<'a> {
                 target: r.target_node_id.clone(),
                 kind: r.original.reference_kind.clone(),
                 line: Some(r.original.line),
+                provenance: Some(EdgeProvenance {
+                    strategy: r.strategy,
+                    confidence: r.confidence,
+                }),
             })
             .collect()
     }
 
+    /// Builds the external nodes the references in `resolved` point at:
+    /// one [`NodeKind::ExternalSymbol`] per referenced symbol, one
+    /// [`NodeKind::ExternalPackage`] per dependency, and the `Contains`
+    /// edges between them.
+    pub fn external_nodes(&self, resolved: &[ResolvedRef]) -> (Vec<Node>, Vec<Edge>) {
+        let targets: Vec<ExternalTarget> = resolved
+            .iter()
+            .filter(|r| r.strategy == ResolutionStrategy::External)
+            .filter_map(|r| self.external_target(&r.original))
+            .collect();
+        external::external_graph(&targets)
+    }
+
     // ------------------------------------------------------------------
     // Private helpers
     // ------------------------------------------------------------------
 
-    /// Strategy 1: try matching the reference name against qualified names.
+    /// Returns the known supertypes extended with those declared by the
+    /// `extends` / `implements` references in `refs`.
+    fn supertypes_with(&self, refs: &[UnresolvedRef]) -> HashMap<String, Vec<String>> {
+        let mut supertypes = self.supertypes.clone();
+        for uref in refs {
+            if !matches!(
+                uref.reference_kind,
+                EdgeKind::Extends | EdgeKind::Implements
+            ) {
+                continue;
+            }
+            if let Some(type_name) = self.type_names.get(&uref.from_node_id) {
+                add_supertype(&mut supertypes, type_name, &uref.reference_name);
+            }
+        }
+        supertypes
+    }
+
+    /// Returns the known exports extended with those declared by the
+    /// references in `refs`.
+    fn exports_with(&self, refs: &[UnresolvedRef]) -> HashMap<String, Vec<String>> {
+        let mut exports = self.exports.clone();
+        for uref in refs {
+            add_export(&mut exports, uref);
+        }
+        exports
+    }
+
+    /// Strategy 0: look the method up on the receiver's declared type, then
+    /// on its supertypes, nearest first.
+    fn try_receiver_match(
+        &self,
+        uref: &UnresolvedRef,
+        supertypes: &HashMap<String, Vec<String>>,
+    ) -> Option<ResolvedRef> {
+        let receiver = uref.receiver_type.as_deref()?;
+        let candidates = self.name_cache.get(uref.name_key())?;
+
+        let mut queue = VecDeque::from([receiver]);
+        let mut seen = HashSet::new();
+        while let Some(type_name) = queue.pop_front() {
+            if !seen.insert(type_name) {
+                continue;
+            }
+            let owned: Vec<Node> = candidates
+                .iter()
+                .filter(|n| method_owner(n) == Some(type_name))
+                .cloned()
+                .collect();
+            if let Some(best) = self.find_best_match(uref, &owned) {
+                return Some(ResolvedRef {
+                    original: uref.clone(),
+                    target_node_id: best.id,
+                    confidence: 0.9,
+                    resolved_by: "receiver-type".to_string(),
+                    strategy: ResolutionStrategy::ExactQualified,
+                });
+            }
+            if let Some(parents) = supertypes.get(type_name) {
+                queue.extend(parents.iter().map(String::as_str));
+            }
+        }
+        None
+    }
+
+    /// Strategy 1: consult the import bindings of the calling file.
+    fn try_import_match(&self, uref: &UnresolvedRef) -> ImportOutcome {
+        let Some(bindings) = self.imports.get(&uref.file_path) else {
+            return ImportOutcome::NoOpinion;
+        };
+        let segments = reference_segments(&uref.reference_name);
+        let Some((head, rest)) = segments.split_first() else {
+            return ImportOutcome::NoOpinion;
+        };
+
+        if let Some(binding) = bindings.iter().find(|b| !b.glob && b.local == *head) {
+            // `parse` imported as `a::b::parse` looks for `parse` in `a::b`;
+            // `cfg::parse` with `cfg` bound to `a::cfg` looks in `a::cfg`.
+            let (name, module): (&str, Vec<String>) = match rest.split_last() {
+                None => match binding.path.split_last() {
+                    Some((name, module)) => (name.as_str(), module.to_vec()),
+                    None => return ImportOutcome::NoOpinion,
+                },
+                Some((name, middle)) => (
+                    name,
+                    binding
+                        .path
+                        .iter()
+                        .cloned()
+                        .chain(middle.iter().map(|s| (*s).to_string()))
+                        .collect(),
+                ),
+            };
+            if let Some(node) = self.best_in_modules(uref, name, &[module.as_slice()]) {
+                return ImportOutcome::Resolved(Box::new(ResolvedRef {
+                    original: uref.clone(),
+                    target_node_id: node.id,
+                    confidence: 0.95,
+                    resolved_by: "import-match".to_string(),
+                    strategy: ResolutionStrategy::ImportScoped,
+                }));
+            }
+            if self
+                .modules
+                .is_external(lang_from_path(&uref.file_path), binding)
+            {
+                return ImportOutcome::External;
+            }
+            // An alias we could not place: still follow the rename so
+            // `use a::parse as load; load()` looks for `parse`.
+            if rest.is_empty() && name != *head {
+                if let Some(candidates) = self.name_cache.get(name) {
+                    if let Some(best) = self.find_best_match(uref, candidates) {
+                        return ImportOutcome::Resolved(Box::new(ResolvedRef {
+                            original: uref.clone(),
+                            strategy: Self::name_match_strategy(uref, candidates, &best),
+                            target_node_id: best.id,
+                            confidence: 0.7,
+                            resolved_by: "import-alias".to_string(),
+                        }));
+                    }
+                }
+            }
+            return ImportOutcome::NoOpinion;
+        }
+
+        if rest.is_empty() {
+            let globs: Vec<&[String]> = bindings
+                .iter()
+                .filter(|b| b.glob)
+                .map(|b| b.path.as_slice())
+                .collect();
+            if let Some(node) = self.best_in_modules(uref, head, &globs) {
+                return ImportOutcome::Resolved(Box::new(ResolvedRef {
+                    original: uref.clone(),
+                    target_node_id: node.id,
+                    confidence: 0.9,
+                    resolved_by: "import-glob".to_string(),
+                    strategy: ResolutionStrategy::ImportScoped,
+                }));
+            }
+        }
+
+        ImportOutcome::NoOpinion
+    }
+
+    /// Strategies 1 and 3: bind the reference to the external symbol it
+    /// names.
+    fn try_external_match(&self, uref: &UnresolvedRef) -> Option<ResolvedRef> {
+        let target = self.external_target(uref)?;
+        Some(ResolvedRef {
+            original: uref.clone(),
+            target_node_id: target.symbol_id(),
+            confidence: 0.9,
+            resolved_by: "external".to_string(),
+            strategy: ResolutionStrategy::External,
+        })
+    }
+
+    /// Returns the external symbol `uref` names: through the external import
+    /// its leading name is bound to, through the external import of its
+    /// receiver's type, or as a Rust path rooted at a dependency. A leading
+    /// name bound to a project import rules the others out.
+    fn external_target(&self, uref: &UnresolvedRef) -> Option<ExternalTarget> {
+        let lang = lang_from_path(&uref.file_path);
+        let segments = reference_segments(&uref.reference_name);
+        let (head, rest) = segments.split_first()?;
+        let bindings = self
+            .imports
+            .get(&uref.file_path)
+            .map_or(&[][..], Vec::as_slice);
+        let bound = |name: &str| bindings.iter().find(|b| !b.glob && b.local == name);
+
+        if let Some(binding) = bound(head) {
+            return if self.modules.is_external(lang, binding) {
+                ExternalTarget::from_binding(lang, binding, rest)
+            } else {
+                None
+            };
+        }
+        if let Some(receiver) = uref.receiver_type.as_deref() {
+            let binding = bound(simple_type_name(receiver))?;
+            return if self.modules.is_external(lang, binding) {
+                ExternalTarget::from_binding(lang, binding, &[uref.name_key()])
+            } else {
+                None
+            };
+        }
+        if lang == "rust" && uref.reference_name.contains("::") {
+            return self.modules.rust_path_target(&segments);
+        }
+        None
+    }
+
+    /// Picks the best node named `name` that lives in one of `modules`.
+    ///
+    /// Candidates are ranked by how many trailing module segments they match,
+    /// then by the usual `find_best_match` scoring. Returns `None` when no
+    /// candidate is reachable through any module.
+    fn best_in_modules(
+        &self,
+        uref: &UnresolvedRef,
+        name: &str,
+        modules: &[&[String]],
+    ) -> Option<Node> {
+        let candidates = self.name_cache.get(name)?;
+        let mut best_affinity = 0;
+        let mut best: Vec<Node> = Vec::new();
+        for node in candidates {
+            let segments = node_module_segments(node);
+            let affinity = modules
+                .iter()
+                .map(|module| module_affinity(module, &segments))
+                .max()
+                .unwrap_or(0);
+            if affinity == 0 || affinity < best_affinity {
+                continue;
+            }
+            if affinity > best_affinity {
+                best_affinity = affinity;
+                best.clear();
+            }
+            best.push(node.clone());
+        }
+        self.find_best_match(uref, &best)
+    }
+
+    /// Strategy 3: try matching the reference name against qualified names.
     fn try_qualified_match(&self, uref: &UnresolvedRef) -> Option<ResolvedRef> {
         // Direct lookup first
         if let Some(candidates) = self.qualified_name_cache.get(&uref.reference_name) {
-            if let Some(node) = candidates.first() {
+            if let Some(node) = candidates.iter().find(|n| Self::kind_fits(uref, n)) {
                 return Some(ResolvedRef {
                     original: uref.clone(),
                     target_node_id: node.id.clone(),
                     confidence: 0.95,
                     resolved_by: "qualified-match".to_string(),
+                    strategy: ResolutionStrategy::ExactQualified,
                 });
             }
         }
@@ -248,12 +1089,13 @@ impl<'a> ReferenceResolver<'a> {
         if let Some(full_names) = self.suffix_cache.get(&uref.reference_name) {
             for full_name in full_names {
                 if let Some(candidates) = self.qualified_name_cache.get(full_name) {
-                    if let Some(node) = candidates.first() {
+                    if let Some(node) = candidates.iter().find(|n| Self::kind_fits(uref, n)) {
                         return Some(ResolvedRef {
                             original: uref.clone(),
                             target_node_id: node.id.clone(),
                             confidence: 0.95,
                             resolved_by: "qualified-match".to_string(),
+                            strategy: ResolutionStrategy::ExactQualified,
                         });
                     }
                 }
@@ -263,11 +1105,193 @@ impl<'a> ReferenceResolver<'a> {
         None
     }
 
-    /// Strategy 2: exact name match using the name cache.
+    /// The `Type.field` a GraphQL selection path (`Query.user.posts`) ends
+    /// at, following the type each field along the path returns.
+    fn selected_field(&self, uref: &UnresolvedRef) -> Option<String> {
+        let mut segments = uref.reference_name.split('.');
+        let mut field = format!("{}.{}", segments.next()?, segments.next()?);
+        for segment in segments {
+            field = format!("{}.{segment}", self.graphql_fields.get(&field)?);
+        }
+        self.graphql_fields.contains_key(&field).then_some(field)
+    }
+
+    /// Binds a field an operation or fragment selects to the schema field,
+    /// reached along its selection path (confidence 0.9).
+    fn try_selection_match(&self, uref: &UnresolvedRef) -> Option<ResolvedRef> {
+        if uref.reference_name.matches('.').count() < 2 {
+            return None;
+        }
+        let field = self.selected_field(uref)?;
+        let candidates = self.name_cache.get(&field)?;
+        let best = self.find_best_match(uref, candidates)?;
+        Some(ResolvedRef {
+            original: uref.clone(),
+            target_node_id: best.id.clone(),
+            confidence: 0.9,
+            resolved_by: "graphql-selection".to_string(),
+            strategy: Self::name_match_strategy(uref, candidates, &best),
+        })
+    }
+
+    /// Binds a reference to a C symbol (`compress` with the `C` ABI) to the
+    /// functions other languages can call by that name, preferring
+    /// definitions to header prototypes, or, for the `Implements` reference
+    /// of a Rust `#[no_mangle]` function, to the C prototypes declaring it
+    /// (confidence 0.8).
+    fn try_c_symbol_match(
+        &self,
+        uref: &UnresolvedRef,
+        symbol: &str,
+        exports: &HashMap<String, Vec<String>>,
+    ) -> Option<ResolvedRef> {
+        let candidates: Vec<&Node> = self
+            .name_cache
+            .get(symbol)?
+            .iter()
+            .filter(|n| n.id != uref.from_node_id && n.file_path != uref.file_path)
+            .filter(|n| {
+                if uref.reference_kind == EdgeKind::Implements {
+                    n.kind == NodeKind::Function
+                        && is_header(&n.file_path)
+                        && matches!(lang_from_path(&n.file_path), "c" | "cpp")
+                } else {
+                    is_c_callable(n, exports)
+                }
+            })
+            .collect();
+        let best = candidates.iter().max_by_key(|n| {
+            (
+                !is_header(&n.file_path),
+                path_proximity(&uref.file_path, &n.file_path),
+            )
+        })?;
+        Some(ResolvedRef {
+            original: uref.clone(),
+            target_node_id: best.id.clone(),
+            confidence: 0.8,
+            resolved_by: "c-symbol".to_string(),
+            strategy: Self::foreign_strategy(&candidates, best),
+        })
+    }
+
+    /// Binds an HTTP request to the route serving its path, preferring a
+    /// route for its method to one for any method, then the nearest
+    /// (confidence 0.8).
+    fn try_route_match(
+        &self,
+        uref: &UnresolvedRef,
+        method: &str,
+        path: &str,
+    ) -> Option<ResolvedRef> {
+        let candidates: Vec<&Node> = [method, "ANY"]
+            .iter()
+            .filter_map(|m| self.name_cache.get(&UnresolvedRef::route_name(m, path)))
+            .flatten()
+            .filter(|n| n.kind == NodeKind::Route)
+            .collect();
+        let best = candidates.iter().max_by_key(|n| {
+            (
+                n.name.starts_with(method),
+                path_proximity(&uref.file_path, &n.file_path),
+            )
+        })?;
+        let fitting = candidates.iter().filter(|n| n.name == best.name).count();
+        Some(ResolvedRef {
+            original: uref.clone(),
+            target_node_id: best.id.clone(),
+            confidence: 0.8,
+            resolved_by: "route".to_string(),
+            strategy: if fitting > 1 {
+                ResolutionStrategy::Ambiguous {
+                    candidates: u32::try_from(fitting).unwrap_or(u32::MAX),
+                }
+            } else {
+                ResolutionStrategy::Heuristic
+            },
+        })
+    }
+
+    /// Binds a reference from a SQL statement to the table, view or column
+    /// it names, preferring the nearest definition (confidence 0.8).
+    fn try_sql_match(&self, uref: &UnresolvedRef, object: &str) -> Option<ResolvedRef> {
+        let candidates: Vec<&Node> = self
+            .name_cache
+            .get(object)?
+            .iter()
+            .filter(|n| n.kind.is_sql())
+            .collect();
+        let best = candidates
+            .iter()
+            .max_by_key(|n| path_proximity(&uref.file_path, &n.file_path))?;
+        Some(ResolvedRef {
+            original: uref.clone(),
+            target_node_id: best.id.clone(),
+            confidence: 0.8,
+            resolved_by: "sql".to_string(),
+            strategy: if candidates.len() > 1 {
+                ResolutionStrategy::Ambiguous {
+                    candidates: u32::try_from(candidates.len()).unwrap_or(u32::MAX),
+                }
+            } else if best.file_path == uref.file_path {
+                ResolutionStrategy::SameFile
+            } else {
+                ResolutionStrategy::Heuristic
+            },
+        })
+    }
+
+    /// Binds a Python call to a Rust `#[pyfunction]`, or a JavaScript or
+    /// TypeScript call to a Rust `#[wasm_bindgen]` function, when no function
+    /// of the calling language has its name (confidence 0.8).
+    fn try_extension_match(
+        &self,
+        uref: &UnresolvedRef,
+        exports: &HashMap<String, Vec<String>>,
+    ) -> Option<ResolvedRef> {
+        let language = extension_language(uref)?;
+        let lang = lang_from_path(&uref.file_path);
+        let nodes = self.name_cache.get(uref.name_key())?;
+        if nodes.iter().any(|n| lang_from_path(&n.file_path) == lang) {
+            return None;
+        }
+        let candidates: Vec<&Node> = nodes
+            .iter()
+            .filter(|n| n.kind == NodeKind::Function && is_exported_to(n, language, exports))
+            .collect();
+        let best = candidates
+            .iter()
+            .max_by_key(|n| path_proximity(&uref.file_path, &n.file_path))?;
+        Some(ResolvedRef {
+            original: uref.clone(),
+            target_node_id: best.id.clone(),
+            confidence: 0.8,
+            resolved_by: "extension-binding".to_string(),
+            strategy: Self::foreign_strategy(&candidates, best),
+        })
+    }
+
+    /// Classifies a match across a language boundary: ambiguous when other
+    /// candidates were as good a fit as `chosen`.
+    fn foreign_strategy(candidates: &[&Node], chosen: &Node) -> ResolutionStrategy {
+        let fitting = candidates
+            .iter()
+            .filter(|n| is_header(&n.file_path) == is_header(&chosen.file_path))
+            .count();
+        if fitting > 1 {
+            ResolutionStrategy::Ambiguous {
+                candidates: u32::try_from(fitting).unwrap_or(u32::MAX),
+            }
+        } else {
+            ResolutionStrategy::Heuristic
+        }
+    }
+
+    /// Strategy 4: exact name match using the name cache.
     fn try_exact_name_match(&self, uref: &UnresolvedRef) -> Option<ResolvedRef> {
         let candidates = self.name_cache.get(&uref.reference_name)?;
 
-        if candidates.len() == 1 {
+        if candidates.len() == 1 && Self::kind_fits(uref, &candidates[0]) {
             let ref_lang = lang_from_path(&uref.file_path);
             let candidate_lang = lang_from_path(&candidates[0].file_path);
             let confidence = if ref_lang != "unknown"
@@ -283,17 +1307,19 @@ impl<'a> ReferenceResolver<'a> {
                 target_node_id: candidates[0].id.clone(),
                 confidence,
                 resolved_by: "exact-match".to_string(),
+                strategy: Self::name_match_strategy(uref, candidates, &candidates[0]),
             });
         }
 
         // Multiple candidates -- score them and pick the best.
-        let best = Self::find_best_match(uref, candidates)?;
+        let best = self.find_best_match(uref, candidates)?;
 
         Some(ResolvedRef {
             original: uref.clone(),
             target_node_id: best.id.clone(),
             confidence: 0.7,
             resolved_by: "exact-match".to_string(),
+            strategy: Self::name_match_strategy(uref, candidates, &best),
         })
     }
 
@@ -304,7 +1330,7 @@ impl<'a> ReferenceResolver<'a> {
     ) -> Option<ResolvedRef> {
         let candidates = self.name_cache.get(simple_name)?;
 
-        if candidates.len() == 1 {
+        if candidates.len() == 1 && Self::kind_fits(uref, &candidates[0]) {
             let ref_lang = lang_from_path(&uref.file_path);
             let candidate_lang = lang_from_path(&candidates[0].file_path);
             let confidence = if ref_lang != "unknown"
@@ -320,19 +1346,81 @@ impl<'a> ReferenceResolver<'a> {
                 target_node_id: candidates[0].id.clone(),
                 confidence,
                 resolved_by: "simple-name-match".to_string(),
+                strategy: Self::name_match_strategy(uref, candidates, &candidates[0]),
             });
         }
 
-        let best = Self::find_best_match(uref, candidates)?;
+        let best = self.find_best_match(uref, candidates)?;
 
         Some(ResolvedRef {
             original: uref.clone(),
             target_node_id: best.id.clone(),
             confidence: 0.7,
             resolved_by: "simple-name-match".to_string(),
+            strategy: Self::name_match_strategy(uref, candidates, &best),
         })
     }
 
+    /// Classifies a match made by name alone: ambiguous when several
+    /// candidates could have been the target, otherwise same-file or
+    /// heuristic depending on where `chosen` lives.
+    fn name_match_strategy(
+        uref: &UnresolvedRef,
+        candidates: &[Node],
+        chosen: &Node,
+    ) -> ResolutionStrategy {
+        let fitting = candidates
+            .iter()
+            .filter(|n| Self::kind_fits(uref, n))
+            .count();
+        if fitting > 1 {
+            ResolutionStrategy::Ambiguous {
+                candidates: u32::try_from(fitting).unwrap_or(u32::MAX),
+            }
+        } else if chosen.file_path == uref.file_path {
+            ResolutionStrategy::SameFile
+        } else {
+            ResolutionStrategy::Heuristic
+        }
+    }
+
+    /// Returns false when `node` cannot be the target of `uref`: type
+    /// references only bind to type definitions.
+    /// Doc sections mention code, never other doc sections, so headings
+    /// that repeat a symbol's name are not candidates for them. Config keys
+    /// and code reference only their own kind. Terraform blocks are only
+    /// visible to the `.tf` files of their own module directory. GraphQL
+    /// documents reference only schema nodes, and code embedding them only
+    /// their fields and fragments. Build files list source files and other
+    /// build targets, which nothing else references. SQL schema objects are
+    /// only referenced from SQL statements, which bypass this check.
+    fn kind_fits(uref: &UnresolvedRef, node: &Node) -> bool {
+        (!uref.reference_kind.is_type_reference() || node.kind.is_type_definition())
+            && !(is_doc_file(&uref.file_path) && is_doc_file(&node.file_path))
+            && is_config_file(&uref.file_path) == node.kind.is_config()
+            && is_terraform_file(&uref.file_path) == node.kind.is_terraform()
+            && (!node.kind.is_terraform()
+                || parent_dir(&uref.file_path) == parent_dir(&node.file_path))
+            && if node.kind.is_graphql() {
+                is_graphql_file(&uref.file_path)
+                    || node.kind == NodeKind::GqlFragment
+                    || (node.kind == NodeKind::GqlField && uref.reference_name.contains('.'))
+            } else {
+                !is_graphql_file(&uref.file_path)
+            }
+            && if is_build_file(&uref.file_path) {
+                node.kind
+                    == if uref.reference_kind == EdgeKind::Contains {
+                        NodeKind::File
+                    } else {
+                        NodeKind::BuildTarget
+                    }
+            } else {
+                node.kind != NodeKind::BuildTarget
+            }
+            && !node.kind.is_sql()
+    }
+
     /// Scores candidate nodes for a reference and returns the best match.
     ///
     /// Scoring heuristics:
@@ -342,66 +1430,102 @@ impl<'a> ReferenceResolver<'a> {
     /// - Exported / pub visibility: +10
     /// - Callable kind (function/method) when the ref kind is `Calls`: +25
     /// - Line proximity (same file only): +20 - (`line_distance` / 10)
-    fn find_best_match(uref: &UnresolvedRef, candidates: &[Node]) -> Option<Node> {
-        if candidates.is_empty() {
-            return None;
-        }
-
-        let ref_lang = lang_from_path(&uref.file_path);
+    /// - Same manifest package: +30, a package the referencing package
+    ///   depends on: +10, any other package: -40
+    fn find_best_match(&self, uref: &UnresolvedRef, candidates: &[Node]) -> Option<Node> {
         let mut best_score = i64::MIN;
         let mut best_node: Option<&Node> = None;
 
         for node in candidates {
-            let mut score: i64 = 0;
+            let Some(score) = self.match_score(uref, node) else {
+                continue;
+            };
+            if score > best_score {
+                best_score = score;
+                best_node = Some(node);
+            }
+        }
 
-            // Same file bonus
-            if node.file_path == uref.file_path {
-                score += 100;
+        best_node.cloned()
+    }
 
-                // Line proximity bonus (same file only)
-                let distance = node.start_line.abs_diff(uref.line);
-                let proximity = 20_i64.saturating_sub(i64::from(distance) / 10);
-                score += proximity.max(0);
-            } else {
-                // Directory proximity bonus (different files only)
-                score += path_proximity(&uref.file_path, &node.file_path);
-            }
+    /// Returns how many candidates share the best `find_best_match` score.
+    fn best_score_count(&self, uref: &UnresolvedRef, candidates: &[Node]) -> usize {
+        let scores: Vec<i64> = candidates
+            .iter()
+            .filter_map(|node| self.match_score(uref, node))
+            .collect();
+        let Some(best) = scores.iter().max() else {
+            return 0;
+        };
+        scores.iter().filter(|score| *score == best).count()
+    }
 
-            // Language matching
-            let candidate_lang = lang_from_path(&node.file_path);
-            if ref_lang != "unknown" && candidate_lang != "unknown" {
-                if ref_lang == candidate_lang {
-                    score += 50;
-                } else {
-                    score -= 80;
-                }
+    /// Scores one candidate for `find_best_match`, or `None` when it cannot
+    /// be the target of `uref` at all.
+    fn match_score(&self, uref: &UnresolvedRef, node: &Node) -> Option<i64> {
+        if !Self::kind_fits(uref, node) {
+            return None;
+        }
+        let mut score: i64 = 0;
+
+        // Same file bonus
+        if node.file_path == uref.file_path {
+            score += 100;
+
+            // Line proximity bonus (same file only)
+            let distance = node.start_line.abs_diff(uref.line);
+            let proximity = 20_i64.saturating_sub(i64::from(distance) / 10);
+            score += proximity.max(0);
+        } else {
+            // Directory proximity bonus (different files only)
+            score += path_proximity(&uref.file_path, &node.file_path);
+        }
+
+        // Language matching
+        let ref_lang = lang_from_path(&uref.file_path);
+        let candidate_lang = lang_from_path(&node.file_path);
+        if ref_lang != "unknown" && candidate_lang != "unknown" {
+            if ref_lang == candidate_lang {
+                score += 50;
+            } else {
+                score -= 80;
             }
+        }
 
-            // Exported / pub bonus
-            if node.visibility == Visibility::Pub {
+        // Package boundaries
+        if let (Some(from), Some(to)) = (
+            self.packages.package_of(&uref.file_path),
+            self.packages.package_of(&node.file_path),
+        ) {
+            if from == to {
+                score += 30;
+            } else if self.packages.depends_on(from, to) {
                 score += 10;
+            } else {
+                score -= 40;
             }
+        }
 
-            // Callable kind bonus for Calls references
-            if uref.reference_kind == EdgeKind::Calls
-                && matches!(
-                    node.kind,
-                    NodeKind::Function
-                        | NodeKind::Method
-                        | NodeKind::StructMethod
-                        | NodeKind::Constructor
-                        | NodeKind::AbstractMethod
-                )
-            {
-                score += 25;
-            }
+        // Exported / pub bonus
+        if node.visibility == Visibility::Pub {
+            score += 10;
+        }
 
-            if score > best_score {
-                best_score = score;
-                best_node = Some(node);
-            }
+        // Callable kind bonus for Calls references
+        if uref.reference_kind == EdgeKind::Calls
+            && matches!(
+                node.kind,
+                NodeKind::Function
+                    | NodeKind::Method
+                    | NodeKind::StructMethod
+                    | NodeKind::Constructor
+                    | NodeKind::AbstractMethod
+            )
+        {
+            score += 25;
         }
 
-        best_node.cloned()
+        Some(score)
     }
 }
@@ -1,5 +1,5 @@
 // Rust guideline compliant 2025-10-17
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::time::Instant;
 
@@ -14,9 +14,14 @@ use crate::config::{
 use crate::context::ContextBuilder;
 use crate::db::Database;
 use crate::errors::{Result, TokenSaveError};
-use crate::extraction::LanguageRegistry;
+use crate::extraction::domain::DomainRules;
+use crate::extraction::signatures::TypePattern;
+use crate::extraction::{finish_extraction, LanguageRegistry};
 use crate::graph::{GraphQueryManager, GraphTraverser};
-use crate::resolution::ReferenceResolver;
+use crate::packages::{self, PackageMap};
+use crate::resolution::{
+    is_doc_file, resolver_edges, resolver_names, IncrementalResolver, ReferenceResolver,
+};
 use crate::sync;
 use crate::types::*;
 
@@ -26,9 +31,9 @@ fn safe_extract(
     extractor: &dyn crate::extraction::LanguageExtractor,
     file_path: &str,
     source: &str,
-) -> Option<ExtractionResult> {
+) -> Option<(ExtractionResult, Option<tree_sitter::Tree>)> {
     std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-        extractor.extract(file_path, source)
+        extractor.extract_with_tree(file_path, source)
     }))
     .map_err(|_| {
         eprintln!("[tokensave] extraction panicked for {file_path}, skipping");
@@ -39,6 +44,19 @@ fn safe_extract(
 /// Tuple shape produced per file by both extraction paths.
 type ExtractTuple = (String, ExtractionResult, String, u64, i64);
 
+/// Metadata key holding a hash of the discovered packages, so syncs rebuild
+/// the package nodes only when a manifest changed.
+const PACKAGES_FINGERPRINT_KEY: &str = "packages_fingerprint";
+
+/// Returns the paths of the extracted files that produced a file node.
+fn files_with_file_node(extractions: &[ExtractTuple]) -> Vec<String> {
+    extractions
+        .iter()
+        .filter(|(_, result, ..)| result.nodes.iter().any(|n| n.kind == NodeKind::File))
+        .map(|(path, ..)| path.clone())
+        .collect()
+}
+
 /// Extract every file in `files`, isolating each extraction in a subprocess
 /// when possible. Subprocess isolation contains C/C++ grammar aborts that
 /// `catch_unwind` cannot intercept; it is the primary defense against
@@ -48,30 +66,93 @@ type ExtractTuple = (String, ExtractionResult, String, u64, i64);
 /// pool cannot start (e.g. when running under `cargo test`, where
 /// `current_exe()` points at the test harness rather than the tokensave
 /// binary). Either way, returns one tuple per successfully-processed file;
-/// crashed and unreadable files are skipped.
+/// crashed and unreadable files are skipped. The fallback fails when
+/// `files` holds files of plugins or grammar libraries, which only the
+/// workers load, rather than indexing the project without them.
+///
+/// Both paths run the project's `domain_rules` over each extraction;
+/// workers get the rules the project was opened with.
 fn extract_files_isolated(
     project_root: &Path,
     registry: &crate::extraction::LanguageRegistry,
+    domain_rules: Option<&DomainRules>,
     files: Vec<String>,
-) -> Vec<ExtractTuple> {
+) -> Result<Vec<ExtractTuple>> {
     if should_use_subprocess() {
         let workers = std::thread::available_parallelism()
             .map(std::num::NonZeroUsize::get)
             .unwrap_or(4);
-        match crate::extraction_worker::WorkerPool::new(workers, project_root.to_path_buf()) {
-            Ok(pool) => return pool.extract_files(files, |_, _, _| {}),
+        match crate::extraction_worker::WorkerPool::new(
+            workers,
+            project_root.to_path_buf(),
+            domain_rules,
+        ) {
+            Ok(pool) => return Ok(pool.extract_files(files, |_, _, _| {})),
             Err(e) => eprintln!(
                 "[tokensave] could not spawn extraction worker pool ({e}), \
                  falling back to in-process extraction"
             ),
         }
     }
-    extract_files_in_process(project_root, registry, &files)
+    check_no_worker_files(registry, &files)?;
+    Ok(extract_files_in_process(
+        project_root,
+        registry,
+        domain_rules,
+        &files,
+    ))
+}
+
+/// Fails when `files` holds files that only the `extract-worker`
+/// subprocess can extract, naming their extensions.
+fn check_no_worker_files(
+    registry: &crate::extraction::LanguageRegistry,
+    files: &[String],
+) -> Result<()> {
+    let extensions: BTreeSet<&str> = files
+        .iter()
+        .filter(|f| registry.is_worker_file(f))
+        .filter_map(|f| f.rsplit('.').next())
+        .collect();
+    if extensions.is_empty() {
+        return Ok(());
+    }
+    let extensions: Vec<String> = extensions.iter().map(|ext| format!(".{ext}")).collect();
+    Err(TokenSaveError::Config {
+        message: format!(
+            "cannot extract {} files: their plugins or grammar libraries only run in the \
+             extraction worker subprocess, which is unavailable",
+            extensions.join(", ")
+        ),
+    })
+}
+
+/// Returns the registry for a project: the built-in extractors, the
+/// installed plugins, and the languages declared in its config. Plugins and
+/// grammar libraries are left to the extraction workers; languages that fail
+/// to load are reported on stderr and skipped.
+fn project_registry(project_root: &Path, config: &TokenSaveConfig) -> LanguageRegistry {
+    let mut registry = LanguageRegistry::with_installed_plugins();
+    for e in registry.declare_tags_languages(project_root, &config.languages) {
+        eprintln!("[tokensave] skipping language: {e}");
+    }
+    registry
+}
+
+/// Loads the project's domain rules, warning about and ignoring a rules file
+/// that cannot be read or compiled. Projects load them once, when opened, so
+/// an invalid file is reported a single time.
+fn load_domain_rules(project_root: &Path) -> Option<DomainRules> {
+    DomainRules::load(project_root).unwrap_or_else(|e| {
+        eprintln!("[tokensave] ignoring domain rules: {e}");
+        None
+    })
 }
 
 fn extract_files_in_process(
     project_root: &Path,
     registry: &crate::extraction::LanguageRegistry,
+    domain_rules: Option<&DomainRules>,
     files: &[String],
 ) -> Vec<ExtractTuple> {
     files
@@ -80,8 +161,8 @@ fn extract_files_in_process(
             let abs_path = project_root.join(file_path);
             let source = sync::read_source_file(&abs_path).ok()?;
             let extractor = registry.extractor_for_file(file_path)?;
-            let mut result = safe_extract(extractor, file_path, &source)?;
-            result.sanitize();
+            let (mut result, tree) = safe_extract(extractor, file_path, &source)?;
+            finish_extraction(&mut result, file_path, &source, tree.as_ref(), domain_rules);
             let hash = sync::content_hash(&source);
             let size = source.len() as u64;
             let mtime = sync::file_stat(&abs_path).map_or_else(current_timestamp, |(m, _)| m);
@@ -112,6 +193,8 @@ pub struct TokenSave {
     config: TokenSaveConfig,
     project_root: PathBuf,
     registry: LanguageRegistry,
+    /// The project's domain rules, loaded when it was opened.
+    domain_rules: Option<DomainRules>,
     /// The active git branch (None if detached HEAD or not a git repo).
     active_branch: Option<String>,
     /// The branch whose DB is actually being served (may differ from `active_branch` on fallback).
@@ -189,11 +272,14 @@ impl TokenSave {
             let _ = branch_meta::save_branch_meta(&get_tokensave_dir(project_root), &meta);
         }
 
+        let registry = project_registry(project_root, &config);
+        let domain_rules = load_domain_rules(project_root);
         Ok(Self {
             db,
             config,
             project_root: project_root.to_path_buf(),
-            registry: LanguageRegistry::new(),
+            registry,
+            domain_rules,
             active_branch,
             serving_branch: None,
             fallback_warning: None,
@@ -248,11 +334,14 @@ impl TokenSave {
                 delete_db_files(&db_path);
                 clear_dirty_sentinel(project_root);
                 let (db, _) = Database::initialize(&db_path).await?;
+                let registry = project_registry(project_root, &config);
+                let domain_rules = load_domain_rules(project_root);
                 let ts = Self {
                     db,
                     config,
                     project_root: project_root.to_path_buf(),
-                    registry: LanguageRegistry::new(),
+                    registry,
+                    domain_rules,
                     active_branch: active_branch.clone(),
                     serving_branch: serving_branch.clone(),
                     fallback_warning: fallback_warning.clone(),
@@ -277,11 +366,14 @@ impl TokenSave {
                 delete_db_files(&db_path);
                 clear_dirty_sentinel(project_root);
                 let (new_db, _) = Database::initialize(&db_path).await?;
+                let registry = project_registry(project_root, &config);
+                let domain_rules = load_domain_rules(project_root);
                 let ts = Self {
                     db: new_db,
                     config,
                     project_root: project_root.to_path_buf(),
-                    registry: LanguageRegistry::new(),
+                    registry,
+                    domain_rules,
                     active_branch: active_branch.clone(),
                     serving_branch: serving_branch.clone(),
                     fallback_warning: fallback_warning.clone(),
@@ -297,11 +389,14 @@ impl TokenSave {
             clear_dirty_sentinel(project_root);
         }
 
+        let registry = project_registry(project_root, &config);
+        let domain_rules = load_domain_rules(project_root);
         let ts = Self {
             db,
             config,
             project_root: project_root.to_path_buf(),
-            registry: LanguageRegistry::new(),
+            registry,
+            domain_rules,
             active_branch,
             serving_branch,
             fallback_warning,
@@ -410,11 +505,14 @@ impl TokenSave {
         }
 
         let (db, _) = Database::open(&db_path).await?;
+        let registry = project_registry(project_root, &config);
+        let domain_rules = load_domain_rules(project_root);
         Ok(Self {
             db,
             config,
             project_root: project_root.to_path_buf(),
-            registry: LanguageRegistry::new(),
+            registry,
+            domain_rules,
             active_branch: Some(branch_name.to_string()),
             serving_branch: Some(branch_name.to_string()),
             fallback_warning: None,
@@ -671,12 +769,18 @@ impl TokenSave {
         let registry = &self.registry;
 
         let phase_start = Instant::now();
-        let extractions: Vec<_> = extract_files_isolated(&project_root, registry, files.clone());
+        let extractions: Vec<_> = extract_files_isolated(
+            &project_root,
+            registry,
+            self.domain_rules.as_ref(),
+            files.clone(),
+        )?;
 
         // 4. Collect all data
         let mut all_nodes = Vec::new();
         let mut all_edges = Vec::new();
         let mut all_unresolved = Vec::new();
+        let mut all_signatures = Vec::new();
         let mut file_records = Vec::new();
         let mut total_nodes = 0;
 
@@ -686,6 +790,7 @@ impl TokenSave {
             all_nodes.extend_from_slice(&result.nodes);
             all_edges.extend_from_slice(&result.edges);
             all_unresolved.extend_from_slice(&result.unresolved_refs);
+            all_signatures.extend_from_slice(&result.signatures);
             file_records.push(FileRecord {
                 path: file_path.clone(),
                 content_hash: hash.clone(),
@@ -704,20 +809,56 @@ impl TokenSave {
             phase_start.elapsed().as_secs_f64()
         ));
 
-        // 5. Resolve references in-memory (parallel) before DB insert
+        // 5. Read package manifests so resolution can respect package
+        //    boundaries
+        let phase_start = Instant::now();
+        let packages = packages::discover_packages(&self.project_root, &files);
+        let package_fingerprint = sync::content_hash(&format!("{packages:?}"));
+        let indexed = files_with_file_node(&extractions);
+        let (package_nodes, package_edges) =
+            packages::package_graph(&packages, indexed.iter().map(String::as_str));
+        let package_map = PackageMap::new(&package_nodes, &package_edges);
+        on_verbose(&format!(
+            "found {} packages in {:.1}s",
+            packages.len(),
+            phase_start.elapsed().as_secs_f64()
+        ));
+
+        // 6. Resolve references in-memory (parallel) before DB insert
         let phase_start = Instant::now();
-        if !all_unresolved.is_empty() {
-            let resolver = ReferenceResolver::from_nodes(&self.db, &all_nodes);
+        let resolution = if all_unresolved.is_empty() {
+            None
+        } else {
+            let resolver =
+                ReferenceResolver::from_nodes(&self.db, &all_nodes).with_packages(package_map);
             let resolution = resolver.resolve_all(&all_unresolved);
             all_edges.extend(resolver.create_edges(&resolution.resolved));
-        }
+            let (external_nodes, external_edges) = resolver.external_nodes(&resolution.resolved);
+            all_nodes.extend(external_nodes);
+            all_edges.extend(external_edges);
+            Some(resolution)
+        };
         on_verbose(&format!(
             "resolved {} references in {:.1}s",
             all_unresolved.len(),
             phase_start.elapsed().as_secs_f64()
         ));
+        all_edges.extend(resolver_edges(
+            &all_nodes,
+            &all_nodes,
+            &self.config.graphql_resolvers,
+        ));
+        #[cfg(feature = "lang-protobuf")]
+        all_edges.extend(crate::resolution::proto_edges(
+            &all_nodes,
+            &all_nodes,
+            &all_unresolved,
+        ));
+
+        all_nodes.extend(package_nodes);
+        all_edges.extend(package_edges);
 
-        // 6. Sort by PK order + dedup edges
+        // 7. Sort by PK order + dedup edges
         all_nodes.sort_unstable_by(|a, b| a.id.cmp(&b.id));
         all_edges.sort_unstable_by(|a, b| {
             (&a.source, &a.target, a.kind.as_str(), &a.line).cmp(&(
@@ -730,16 +871,26 @@ impl TokenSave {
         all_edges.dedup_by(|a, b| {
             a.source == b.source && a.target == b.target && a.kind == b.kind && a.line == b.line
         });
+        all_signatures.sort_unstable_by(|a, b| a.node_id.cmp(&b.node_id));
         file_records.sort_unstable_by(|a, b| a.path.cmp(&b.path));
         let total_edges = all_edges.len();
 
-        // 7. Bulk-insert via prepared statements (zero SQL re-parsing)
+        // 8. Bulk-insert via prepared statements (zero SQL re-parsing)
         let phase_start = Instant::now();
         self.db.insert_nodes(&all_nodes).await?;
         self.db.insert_edges(&all_edges).await?;
+        self.db.insert_signatures(&all_signatures).await?;
+        if let Some(resolution) = &resolution {
+            // Keep every reference with its target so incremental syncs can
+            // re-resolve just the ones a change affects.
+            self.db.insert_resolution(resolution).await?;
+        }
         self.db.upsert_files(&file_records).await?;
+        self.db
+            .set_metadata(PACKAGES_FINGERPRINT_KEY, &package_fingerprint)
+            .await?;
 
-        // 8. Restore indexes and normal durability
+        // 9. Restore indexes and normal durability
         self.db.end_bulk_load().await?;
         on_verbose(&format!(
             "wrote to database in {:.1}s",
@@ -853,19 +1004,27 @@ impl TokenSave {
 
         // Extract graph data from the files in parallel (subprocess-isolated)
         let _ = stat_map; // worker re-stats internally; map kept for potential future use
-        let sync_extractions: Vec<_> =
-            extract_files_isolated(project_root, registry, file_paths.to_vec());
+        let sync_extractions: Vec<_> = extract_files_isolated(
+            project_root,
+            registry,
+            self.domain_rules.as_ref(),
+            file_paths.to_vec(),
+        )?;
 
         // Insert into database
+        let mut incremental = IncrementalResolver::new(&self.db);
         for (file_path, result, hash, size, mtime) in &sync_extractions {
+            incremental.before_file_change(file_path).await?;
             self.db.delete_nodes_by_file(file_path).await?;
             self.db.insert_nodes(&result.nodes).await?;
             self.db.insert_edges(&result.edges).await?;
+            self.db.insert_signatures(&result.signatures).await?;
             if !result.unresolved_refs.is_empty() {
                 self.db
                     .insert_unresolved_refs(&result.unresolved_refs)
                     .await?;
             }
+            incremental.after_file_indexed(file_path, result);
 
             let file_record = FileRecord {
                 path: (*file_path).clone(),
@@ -878,18 +1037,15 @@ impl TokenSave {
             self.db.upsert_file(&file_record).await?;
         }
 
-        // Resolve references for any new/changed unresolved refs
-        if !file_paths.is_empty() {
-            let resolver = ReferenceResolver::new(&self.db).await;
-            let unresolved = self.db.get_unresolved_refs().await?;
-            if !unresolved.is_empty() {
-                let resolution = resolver.resolve_all(&unresolved);
-                let edges = resolver.create_edges(&resolution.resolved);
-                if !edges.is_empty() {
-                    self.db.insert_edges(&edges).await?;
-                }
-            }
-        }
+        // Re-indexing dropped the package edges of the changed files
+        self.link_package_files(&files_with_file_node(&sync_extractions))
+            .await?;
+
+        // Resolve the references affected by the changed files
+        incremental.finish().await?;
+        self.link_graphql_resolvers(file_paths).await?;
+        #[cfg(feature = "lang-protobuf")]
+        self.link_proto_code(file_paths).await?;
 
         self.db
             .set_metadata("last_sync_at", &current_timestamp().to_string())
@@ -1071,8 +1227,10 @@ impl TokenSave {
         }
 
         // Remove deleted files
+        let mut incremental = IncrementalResolver::new(&self.db);
         for path in &removed {
             on_progress(0, 0, &format!("removing {path}"));
+            incremental.before_file_change(path).await?;
             self.db.delete_file(path).await?;
         }
 
@@ -1082,8 +1240,12 @@ impl TokenSave {
 
         let phase_start = Instant::now();
         let _ = stat_map; // worker re-stats internally
-        let sync_extractions: Vec<_> =
-            extract_files_isolated(project_root, registry, to_index.clone());
+        let sync_extractions: Vec<_> = extract_files_isolated(
+            project_root,
+            registry,
+            self.domain_rules.as_ref(),
+            to_index.clone(),
+        )?;
 
         let total = sync_extractions.len();
         let mut total_nodes = 0usize;
@@ -1094,14 +1256,17 @@ impl TokenSave {
             total_nodes += result.nodes.len();
             total_edges += result.edges.len();
 
+            incremental.before_file_change(file_path).await?;
             self.db.delete_nodes_by_file(file_path).await?;
             self.db.insert_nodes(&result.nodes).await?;
             self.db.insert_edges(&result.edges).await?;
+            self.db.insert_signatures(&result.signatures).await?;
             if !result.unresolved_refs.is_empty() {
                 self.db
                     .insert_unresolved_refs(&result.unresolved_refs)
                     .await?;
             }
+            incremental.after_file_indexed(file_path, result);
 
             let file_record = FileRecord {
                 path: file_path.clone(),
@@ -1123,24 +1288,24 @@ impl TokenSave {
             ));
         }
 
-        // Resolve references (call edges, uses, etc.) across all files.
+        // Rebuild the package nodes if a manifest changed, and relink the
+        // re-indexed files to their packages either way.
+        self.refresh_packages(&current_files, &files_with_file_node(&sync_extractions))
+            .await?;
+
+        // Resolve references (call edges, uses, etc.) affected by the change.
         // This must run after all files are indexed so cross-file references
         // can find their targets.
-        if !to_index.is_empty() {
+        if !to_index.is_empty() || !removed.is_empty() {
             on_progress(0, 0, "resolving references");
             let phase_start = Instant::now();
-            let unresolved = self.db.get_unresolved_refs().await?;
-            if !unresolved.is_empty() {
-                let resolver = ReferenceResolver::new(&self.db).await;
-                let resolution = resolver.resolve_all(&unresolved);
-                let edges = resolver.create_edges(&resolution.resolved);
-                if !edges.is_empty() {
-                    self.db.insert_edges(&edges).await?;
-                }
-            }
+            let resolution = incremental.finish().await?;
+            self.link_graphql_resolvers(&to_index).await?;
+            #[cfg(feature = "lang-protobuf")]
+            self.link_proto_code(&to_index).await?;
             on_verbose(&format!(
                 "resolved {} references in {:.1}s",
-                unresolved.len(),
+                resolution.total,
                 phase_start.elapsed().as_secs_f64()
             ));
         }
@@ -1166,6 +1331,126 @@ impl TokenSave {
         })
     }
 
+    /// Rebuilds the package nodes from the manifests next to `files` when
+    /// they changed since the last sync, or when a manifest was re-indexed
+    /// (which drops the package nodes stored under its path). Otherwise only
+    /// links `reindexed`, whose `Contains` edges from their package were
+    /// dropped with their old file node, back to their packages.
+    async fn refresh_packages(&self, files: &[String], reindexed: &[String]) -> Result<()> {
+        let packages = packages::discover_packages(&self.project_root, files);
+        let fingerprint = sync::content_hash(&format!("{packages:?}"));
+        let stored = self.db.get_metadata(PACKAGES_FINGERPRINT_KEY).await?;
+        let manifest_reindexed = packages.iter().any(|p| reindexed.contains(&p.manifest));
+        if !manifest_reindexed && stored.as_deref() == Some(fingerprint.as_str()) {
+            return self.link_package_files(reindexed).await;
+        }
+
+        let file_nodes = self.db.get_nodes_by_kind(NodeKind::File).await?;
+        let (nodes, edges) =
+            packages::package_graph(&packages, file_nodes.iter().map(|n| n.file_path.as_str()));
+        self.db
+            .delete_nodes_by_kind(NodeKind::ManifestPackage)
+            .await?;
+        self.db.insert_nodes(&nodes).await?;
+        self.db.insert_edges(&edges).await?;
+        self.db
+            .set_metadata(PACKAGES_FINGERPRINT_KEY, &fingerprint)
+            .await
+    }
+
+    /// Adds the `Contains` edges from the stored packages to `files`.
+    async fn link_package_files(&self, files: &[String]) -> Result<()> {
+        if files.is_empty() {
+            return Ok(());
+        }
+        let packages = PackageMap::load(&self.db).await?;
+        if packages.is_empty() {
+            return Ok(());
+        }
+        let edges = packages.contains_edges(files.iter().map(String::as_str));
+        self.db.insert_edges(&edges).await
+    }
+
+    /// Links GraphQL schema fields to their resolvers by the configured
+    /// naming conventions, adding the links that re-indexing `files` dropped:
+    /// those from or to a node in one of them.
+    async fn link_graphql_resolvers(&self, files: &[String]) -> Result<()> {
+        if files.is_empty() {
+            return Ok(());
+        }
+        let fields = self.db.get_nodes_by_kind(NodeKind::GqlField).await?;
+        if fields.is_empty() {
+            return Ok(());
+        }
+        let conventions = &self.config.graphql_resolvers;
+        let functions = self
+            .db
+            .get_nodes_by_names(&resolver_names(&fields, conventions))
+            .await?;
+        let in_files: HashSet<&str> = fields
+            .iter()
+            .chain(&functions)
+            .filter(|n| files.contains(&n.file_path))
+            .map(|n| n.id.as_str())
+            .collect();
+        let edges: Vec<Edge> = resolver_edges(&fields, &functions, conventions)
+            .into_iter()
+            .filter(|e| {
+                in_files.contains(e.source.as_str()) || in_files.contains(e.target.as_str())
+            })
+            .collect();
+        self.db.insert_edges(&edges).await
+    }
+
+    /// Links Protobuf messages and RPCs to the code generated from, serving
+    /// and calling them, adding the links that re-indexing `files` dropped.
+    #[cfg(feature = "lang-protobuf")]
+    async fn link_proto_code(&self, files: &[String]) -> Result<()> {
+        if files.is_empty() {
+            return Ok(());
+        }
+        let mut protos = self.db.get_nodes_by_kind(NodeKind::ProtoMessage).await?;
+        protos.extend(self.db.get_nodes_by_kind(NodeKind::ProtoRpc).await?);
+        if protos.is_empty() {
+            return Ok(());
+        }
+        // The packages name the Rust files prost generates.
+        protos.extend(
+            self.db
+                .get_nodes_by_kind(NodeKind::Package)
+                .await?
+                .into_iter()
+                .filter(|n| n.file_path.rsplit('.').next() == Some("proto")),
+        );
+        let names = crate::resolution::generated_names(&protos);
+        let code = self.db.get_nodes_by_names(&names).await?;
+        let refs: Vec<UnresolvedRef> = self
+            .db
+            .get_refs_by_name_keys(&names)
+            .await?
+            .into_iter()
+            .map(|(uref, _)| uref)
+            .collect();
+        let in_files: HashSet<&str> = protos
+            .iter()
+            .chain(&code)
+            .filter(|n| files.contains(&n.file_path))
+            .map(|n| n.id.as_str())
+            .chain(
+                refs.iter()
+                    .filter(|r| files.contains(&r.file_path))
+                    .map(|r| r.from_node_id.as_str()),
+            )
+            .collect();
+        let edges: Vec<Edge> = crate::resolution::proto_edges(&protos, &code, &refs)
+            .into_iter()
+            .filter(|e| {
+                in_files.contains(e.source.as_str()) || in_files.contains(e.target.as_str())
+            })
+            .collect();
+        self.db.insert_edges(&edges).await
+    }
+
     /// Scans the project root for source files in all supported languages,
     /// respecting the configured exclude patterns and max file size.
     ///
@@ -1322,11 +1607,12 @@ impl TokenSave {
         files
     }
 
-    /// Checks whether a file should be included: correct extension, not
-    /// excluded by config globs, and within the max file size.
+    /// Checks whether a file should be included: correct extension or file
+    /// name, not excluded by config globs, and within the max file size.
     fn accept_file(&self, path: &Path, supported_exts: &[&str]) -> Option<String> {
         let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-        if !supported_exts.contains(&ext) {
+        let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        if !supported_exts.contains(&ext) && !self.registry.handles_file_name(file_name) {
             return None;
         }
         let relative = path.strip_prefix(&self.project_root).ok()?;
@@ -1363,33 +1649,67 @@ impl TokenSave {
 
     /// Re-indexes a single file after an edit.
     async fn reindex_file(&self, file_path: &str) -> Result<()> {
-        let abs_path = self.absolute_path(file_path);
-        let source = std::fs::read_to_string(&abs_path).map_err(|e| TokenSaveError::Config {
-            message: format!("failed to read file {file_path}: {e}"),
-        })?;
-
-        let Some(extractor) = self.registry.extractor_for_file(file_path) else {
-            return Ok(());
-        };
+        let (result, hash, size, mtime) = if self.registry.is_worker_file(file_path) {
+            // Plugins and grammar libraries only run in the extract-worker
+            // subprocess.
+            let extracted = extract_files_isolated(
+                &self.project_root,
+                &self.registry,
+                self.domain_rules.as_ref(),
+                vec![file_path.into()],
+            )?;
+            let Some((_, result, hash, size, mtime)) = extracted.into_iter().next() else {
+                return Ok(());
+            };
+            (result, hash, size, mtime)
+        } else {
+            let abs_path = self.absolute_path(file_path);
+            let source =
+                std::fs::read_to_string(&abs_path).map_err(|e| TokenSaveError::Config {
+                    message: format!("failed to read file {file_path}: {e}"),
+                })?;
+
+            let Some(extractor) = self.registry.extractor_for_file(file_path) else {
+                return Ok(());
+            };
 
-        let mut result =
-            safe_extract(extractor, file_path, &source).ok_or_else(|| TokenSaveError::Config {
-                message: format!("extraction panicked for {file_path}"),
-            })?;
-        result.sanitize();
+            let (mut result, tree) =
+                safe_extract(extractor, file_path, &source).ok_or_else(|| {
+                    TokenSaveError::Config {
+                        message: format!("extraction panicked for {file_path}"),
+                    }
+                })?;
+            finish_extraction(
+                &mut result,
+                file_path,
+                &source,
+                tree.as_ref(),
+                self.domain_rules.as_ref(),
+            );
 
-        let hash = sync::content_hash(&source);
-        let size = source.len() as u64;
-        let mtime = sync::file_stat(&abs_path).map_or_else(current_timestamp, |(m, _)| m);
+            let hash = sync::content_hash(&source);
+            let size = source.len() as u64;
+            let mtime = sync::file_stat(&abs_path).map_or_else(current_timestamp, |(m, _)| m);
+            (result, hash, size, mtime)
+        };
 
+        let mut incremental = IncrementalResolver::new(&self.db);
+        incremental.before_file_change(file_path).await?;
         self.db.delete_nodes_by_file(file_path).await?;
         self.db.insert_nodes(&result.nodes).await?;
         self.db.insert_edges(&result.edges).await?;
+        self.db.insert_signatures(&result.signatures).await?;
         if !result.unresolved_refs.is_empty() {
             self.db
                 .insert_unresolved_refs(&result.unresolved_refs)
                 .await?;
         }
+        incremental.after_file_indexed(file_path, &result);
+        incremental.finish().await?;
+        self.link_graphql_resolvers(&[file_path.to_string()])
+            .await?;
+        #[cfg(feature = "lang-protobuf")]
+        self.link_proto_code(&[file_path.to_string()]).await?;
 
         let file_record = FileRecord {
             path: file_path.to_string(),
@@ -1401,6 +1721,20 @@ impl TokenSave {
         };
         self.db.upsert_file(&file_record).await?;
 
+        // Re-indexing dropped the file's package edges, and its package
+        // nodes when it is a manifest
+        if result.nodes.iter().any(|n| n.kind == NodeKind::File) {
+            let files: Vec<String> = self
+                .db
+                .get_all_files()
+                .await?
+                .into_iter()
+                .map(|f| f.path)
+                .collect();
+            self.refresh_packages(&files, &[file_path.to_string()])
+                .await?;
+        }
+
         Ok(())
     }
 
@@ -1707,21 +2041,56 @@ impl TokenSave {
     }
 
     /// Returns all nodes that transitively call the given node, up to `max_depth`.
-    pub async fn get_callers(&self, node_id: &str, max_depth: usize) -> Result<Vec<(Node, Edge)>> {
-        let traverser = GraphTraverser::new(&self.db);
+    ///
+    /// With `virtual_dispatch`, callers of the interface, trait or abstract
+    /// methods the node implements are included (see
+    /// [`GraphTraverser::with_virtual_dispatch`]). Call edges with a
+    /// confidence below `min_confidence` are not followed.
+    pub async fn get_callers(
+        &self,
+        node_id: &str,
+        max_depth: usize,
+        virtual_dispatch: bool,
+        min_confidence: f64,
+    ) -> Result<Vec<(Node, Edge)>> {
+        let traverser = GraphTraverser::new(&self.db)
+            .with_virtual_dispatch(virtual_dispatch)
+            .with_min_confidence(min_confidence);
         traverser.get_callers(node_id, max_depth).await
     }
 
     /// Returns all nodes that the given node transitively calls, up to `max_depth`.
-    pub async fn get_callees(&self, node_id: &str, max_depth: usize) -> Result<Vec<(Node, Edge)>> {
-        let traverser = GraphTraverser::new(&self.db);
+    ///
+    /// With `virtual_dispatch`, calls to interface, trait or abstract methods
+    /// also reach their implementations. Call edges with a confidence below
+    /// `min_confidence` are not followed.
+    pub async fn get_callees(
+        &self,
+        node_id: &str,
+        max_depth: usize,
+        virtual_dispatch: bool,
+        min_confidence: f64,
+    ) -> Result<Vec<(Node, Edge)>> {
+        let traverser = GraphTraverser::new(&self.db)
+            .with_virtual_dispatch(virtual_dispatch)
+            .with_min_confidence(min_confidence);
         traverser.get_callees(node_id, max_depth).await
     }
 
     /// Computes the impact radius: all nodes that directly or indirectly
-    /// depend on the given node, up to `max_depth`.
-    pub async fn get_impact_radius(&self, node_id: &str, max_depth: usize) -> Result<Subgraph> {
-        let traverser = GraphTraverser::new(&self.db);
+    /// depend on the given node, up to `max_depth`. With `virtual_dispatch`,
+    /// dependents reached through the methods the node implements count too.
+    /// Edges with a confidence below `min_confidence` are not followed.
+    pub async fn get_impact_radius(
+        &self,
+        node_id: &str,
+        max_depth: usize,
+        virtual_dispatch: bool,
+        min_confidence: f64,
+    ) -> Result<Subgraph> {
+        let traverser = GraphTraverser::new(&self.db)
+            .with_virtual_dispatch(virtual_dispatch)
+            .with_min_confidence(min_confidence);
         traverser.get_impact_radius(node_id, max_depth).await
     }
 
@@ -1737,6 +2106,438 @@ impl TokenSave {
         qm.find_dead_code(kinds).await
     }
 
+    /// Finds potentially dead code, ignoring incoming edges with a confidence
+    /// below `min_confidence`. Each node comes with the confidence of its
+    /// strongest incoming edge (0.0 when it has none).
+    pub async fn find_dead_code_with_confidence(
+        &self,
+        kinds: &[NodeKind],
+        min_confidence: f64,
+    ) -> Result<Vec<(Node, f64)>> {
+        let qm = GraphQueryManager::new(&self.db);
+        qm.find_dead_code_with_confidence(kinds, min_confidence)
+            .await
+    }
+
+    /// Explains the references that are missing from the graph or were bound
+    /// by an arbitrary pick among tied candidates, optionally limited to
+    /// files under `path_prefix`.
+    pub async fn diagnose_references(
+        &self,
+        path_prefix: Option<&str>,
+    ) -> Result<Vec<RefDiagnostic>> {
+        let mut refs = self.db.get_unresolved_refs().await?;
+        refs.extend(self.db.get_ambiguous_refs().await?);
+        if let Some(prefix) = path_prefix {
+            refs.retain(|r| r.file_path.starts_with(prefix));
+        }
+        let resolver = ReferenceResolver::for_refs(&self.db, &refs).await?;
+        Ok(refs
+            .into_iter()
+            .filter_map(|reference| {
+                resolver
+                    .diagnose(&reference)
+                    .map(|reason| RefDiagnostic { reference, reason })
+            })
+            .collect())
+    }
+
+    /// Returns the documentation sections that mention `node_id` in code
+    /// spans or code blocks, with the linking edges.
+    pub async fn get_doc_mentions(&self, node_id: &str) -> Result<Vec<(Node, Edge)>> {
+        let edges = self
+            .db
+            .get_incoming_edges(node_id, &[EdgeKind::Uses])
+            .await?;
+        let mut mentions = Vec::new();
+        for edge in edges {
+            if let Some(section) = self.db.get_node_by_id(&edge.source).await? {
+                if is_doc_file(&section.file_path) {
+                    mentions.push((section, edge));
+                }
+            }
+        }
+        Ok(mentions)
+    }
+
+    /// Returns the documentation sections whose code mentions no longer
+    /// name any symbol in the graph, each with its stale mentions,
+    /// optionally limited to files under `path_prefix`. Sections are ordered
+    /// by file and line.
+    ///
+    /// Only qualified mentions whose scope is a type or module of the project
+    /// count (`Graph::removed_fn`): unqualified names and paths through std
+    /// or third-party types never resolve, stale or not.
+    pub async fn stale_doc_references(
+        &self,
+        path_prefix: Option<&str>,
+    ) -> Result<Vec<(Node, Vec<UnresolvedRef>)>> {
+        let mut refs = self.db.get_unresolved_refs().await?;
+        refs.retain(|r| {
+            is_doc_file(&r.file_path) && path_prefix.is_none_or(|p| r.file_path.starts_with(p))
+        });
+        let scope = |name: &str| -> Option<String> {
+            let name = ["crate::", "self::", "super::"]
+                .iter()
+                .find_map(|prefix| name.strip_prefix(prefix))
+                .unwrap_or(name);
+            let (head, _) = name.split_once("::").or_else(|| name.split_once('.'))?;
+            Some(head.to_string())
+        };
+        let scopes: Vec<String> = refs
+            .iter()
+            .filter_map(|r| scope(&r.reference_name))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let project_scopes: HashSet<String> = self
+            .db
+            .get_nodes_by_names(&scopes)
+            .await?
+            .into_iter()
+            .filter(|n| {
+                n.kind.is_type_definition()
+                    || matches!(
+                        n.kind,
+                        NodeKind::Module
+                            | NodeKind::Namespace
+                            | NodeKind::Package
+                            | NodeKind::GoPackage
+                            | NodeKind::ScalaPackage
+                            | NodeKind::KotlinPackage
+                            | NodeKind::PascalUnit
+                    )
+            })
+            .map(|n| n.name)
+            .collect();
+        refs.retain(|r| scope(&r.reference_name).is_some_and(|s| project_scopes.contains(&s)));
+        let mut by_section: HashMap<String, Vec<UnresolvedRef>> = HashMap::new();
+        for r in refs {
+            by_section
+                .entry(r.from_node_id.clone())
+                .or_default()
+                .push(r);
+        }
+        let ids: Vec<String> = by_section.keys().cloned().collect();
+        let mut sections: Vec<(Node, Vec<UnresolvedRef>)> = self
+            .db
+            .get_nodes_by_ids(&ids)
+            .await?
+            .into_iter()
+            .filter_map(|node| {
+                let mut refs = by_section.remove(&node.id)?;
+                refs.sort_by_key(|r| (r.line, r.column));
+                Some((node, refs))
+            })
+            .collect();
+        sections.sort_by(|a, b| {
+            (&a.0.file_path, a.0.start_line).cmp(&(&b.0.file_path, b.0.start_line))
+        });
+        Ok(sections)
+    }
+
+    /// Returns the build targets listing `file_path` among their sources,
+    /// and the build targets to rebuild when it changes: those listing it or
+    /// a file depending on it, plus the targets depending on any of these,
+    /// transitively. Both are ordered by file and line.
+    pub async fn build_targets_for_file(&self, file_path: &str) -> Result<(Vec<Node>, Vec<Node>)> {
+        let qm = GraphQueryManager::new(&self.db);
+        let mut files = vec![file_path.to_string()];
+        let mut seen_files: HashSet<String> = files.iter().cloned().collect();
+        let mut index = 0;
+        while index < files.len() {
+            for dependent in qm.get_file_dependents(&files[index]).await? {
+                if seen_files.insert(dependent.clone()) {
+                    files.push(dependent);
+                }
+            }
+            index += 1;
+        }
+
+        let mut including = Vec::new();
+        let mut rebuilt: Vec<Node> = Vec::new();
+        for file in &files {
+            let file_id = generate_node_id(file, &NodeKind::File, file, 0);
+            let sources: Vec<String> = self
+                .db
+                .get_incoming_edges(&file_id, &[EdgeKind::Contains])
+                .await?
+                .into_iter()
+                .map(|e| e.source)
+                .collect();
+            for node in self.db.get_nodes_by_ids(&sources).await? {
+                if node.kind != NodeKind::BuildTarget {
+                    continue;
+                }
+                if file == file_path {
+                    including.push(node.clone());
+                }
+                if rebuilt.iter().all(|n| n.id != node.id) {
+                    rebuilt.push(node);
+                }
+            }
+        }
+        let mut index = 0;
+        while index < rebuilt.len() {
+            let dependents: Vec<String> = self
+                .db
+                .get_incoming_edges(&rebuilt[index].id, &[EdgeKind::DependsOn])
+                .await?
+                .into_iter()
+                .map(|e| e.source)
+                .collect();
+            for node in self.db.get_nodes_by_ids(&dependents).await? {
+                if node.kind == NodeKind::BuildTarget && rebuilt.iter().all(|n| n.id != node.id) {
+                    rebuilt.push(node);
+                }
+            }
+            index += 1;
+        }
+        for targets in [&mut including, &mut rebuilt] {
+            targets.sort_by(|a, b| (&a.file_path, a.start_line).cmp(&(&b.file_path, b.start_line)));
+        }
+        Ok((including, rebuilt))
+    }
+
+    /// Returns every HTTP route in the graph, ordered by file and line, with
+    /// the handlers it dispatches to and the functions requesting it.
+    pub async fn routes(&self) -> Result<Vec<(Node, Vec<Node>, Vec<Node>)>> {
+        let mut routes = self.db.get_nodes_by_kind(NodeKind::Route).await?;
+        routes.sort_by(|a, b| (&a.file_path, a.start_line).cmp(&(&b.file_path, b.start_line)));
+        let mut listed = Vec::with_capacity(routes.len());
+        for route in routes {
+            let handlers: Vec<String> = self
+                .db
+                .get_outgoing_edges(&route.id, &[EdgeKind::Implements])
+                .await?
+                .into_iter()
+                .map(|e| e.target)
+                .collect();
+            let callers: Vec<String> = self
+                .db
+                .get_incoming_edges(&route.id, &[EdgeKind::Calls])
+                .await?
+                .into_iter()
+                .map(|e| e.source)
+                .collect();
+            let mut handlers = self.db.get_nodes_by_ids(&handlers).await?;
+            let mut callers = self.db.get_nodes_by_ids(&callers).await?;
+            for nodes in [&mut handlers, &mut callers] {
+                nodes.sort_by(|a, b| {
+                    (&a.file_path, a.start_line).cmp(&(&b.file_path, b.start_line))
+                });
+            }
+            listed.push((route, handlers, callers));
+        }
+        Ok(listed)
+    }
+
+    /// Returns the SQL tables and views named `table`, and every access to
+    /// them or their columns as (accessing node, table or column, edge
+    /// kind): `Uses` for reads, `Writes` for inserts, updates and deletes.
+    /// Both are ordered by file and line.
+    pub async fn sql_access(
+        &self,
+        table: &str,
+    ) -> Result<(Vec<Node>, Vec<(Node, Node, EdgeKind)>)> {
+        let table = table.to_ascii_lowercase();
+        let mut tables: Vec<Node> = self
+            .db
+            .get_nodes_by_names(std::slice::from_ref(&table))
+            .await?
+            .into_iter()
+            .filter(|n| matches!(n.kind, NodeKind::SqlTable | NodeKind::SqlView))
+            .collect();
+        tables.sort_by(|a, b| (&a.file_path, a.start_line).cmp(&(&b.file_path, b.start_line)));
+        let prefix = format!("{table}.");
+        let columns: Vec<Node> = self
+            .db
+            .get_nodes_by_kind(NodeKind::SqlColumn)
+            .await?
+            .into_iter()
+            .filter(|n| n.name.starts_with(&prefix))
+            .collect();
+
+        let mut accesses = Vec::new();
+        for object in tables.iter().chain(&columns) {
+            let edges = self
+                .db
+                .get_incoming_edges(&object.id, &[EdgeKind::Uses, EdgeKind::Writes])
+                .await?;
+            let sources: Vec<String> = edges.iter().map(|e| e.source.clone()).collect();
+            let nodes = self.db.get_nodes_by_ids(&sources).await?;
+            for edge in edges {
+                if let Some(node) = nodes.iter().find(|n| n.id == edge.source) {
+                    accesses.push((node.clone(), object.clone(), edge.kind));
+                }
+            }
+        }
+        accesses.sort_by(|(a, a_object, _), (b, b_object, _)| {
+            (&a.file_path, a.start_line, &a_object.name).cmp(&(
+                &b.file_path,
+                b.start_line,
+                &b_object.name,
+            ))
+        });
+        Ok((tables, accesses))
+    }
+
+    /// Returns the test cases in the graph, which tell test code from
+    /// production code.
+    pub async fn test_scopes(&self) -> Result<TestScopes> {
+        let test_cases = self.db.get_nodes_by_kind(NodeKind::TestCase).await?;
+        Ok(TestScopes::new(&test_cases))
+    }
+
+    /// Returns the test code a change to `files` can affect: the test code
+    /// in those files, and the test code that calls or uses one of their
+    /// symbols through at most `max_depth` hops. The walk goes through
+    /// production code and test helpers and stops at test cases, so a file
+    /// with inline tests contributes only the tests that reach the change.
+    /// With `filter`, test code is instead everything in the files it
+    /// matches.
+    pub async fn affected_tests(
+        &self,
+        files: &[String],
+        max_depth: usize,
+        filter: Option<&glob::Pattern>,
+    ) -> Result<Vec<Node>> {
+        let scopes = self.test_scopes().await?;
+        let is_test = |node: &Node| match filter {
+            Some(pattern) => pattern.matches(&node.file_path),
+            None => scopes.contains(node),
+        };
+
+        let mut affected: Vec<Node> = Vec::new();
+        let mut visited: HashSet<String> = HashSet::new();
+        let mut frontier: Vec<Node> = Vec::new();
+        for file in files {
+            frontier.extend(self.db.get_nodes_by_file(file).await?);
+        }
+        frontier.retain(|n| visited.insert(n.id.clone()));
+
+        let mut depth = 0;
+        loop {
+            let mut walk_from: Vec<String> = Vec::new();
+            for node in frontier {
+                if is_test(&node) {
+                    if node.kind == NodeKind::TestCase {
+                        affected.push(node);
+                        continue;
+                    }
+                    walk_from.push(node.id.clone());
+                    affected.push(node);
+                } else {
+                    walk_from.push(node.id);
+                }
+            }
+            if depth >= max_depth || walk_from.is_empty() {
+                break;
+            }
+            let mut dependents: Vec<String> = Vec::new();
+            for id in &walk_from {
+                for edge in self
+                    .db
+                    .get_incoming_edges(id, &[EdgeKind::Calls, EdgeKind::Uses])
+                    .await?
+                {
+                    if visited.insert(edge.source.clone()) {
+                        dependents.push(edge.source);
+                    }
+                }
+            }
+            frontier = self.db.get_nodes_by_ids(&dependents).await?;
+            depth += 1;
+        }
+
+        affected.sort_by(|a, b| (&a.file_path, a.start_line).cmp(&(&b.file_path, b.start_line)));
+        Ok(affected)
+    }
+
+    /// Finds callables with a parameter whose type matches `param_type`
+    /// and a return type matching `return_type`; an absent pattern matches
+    /// anything. With `nested`, a pattern also matches types nested in
+    /// generic arguments, so `Database` finds `Arc<Database>` parameters.
+    /// Results are ordered by file and line.
+    pub async fn signature_search(
+        &self,
+        param_type: Option<&TypePattern>,
+        return_type: Option<&TypePattern>,
+        nested: bool,
+        path_prefix: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<(Node, Signature)>> {
+        let matches = |pattern: &TypePattern, ty: &TypeSpec| {
+            if nested {
+                pattern.matches_within(&ty.name)
+            } else {
+                pattern.matches(&ty.name)
+            }
+        };
+        let candidates = self
+            .db
+            .get_signature_candidates(
+                param_type.map(|p| p.key().unwrap_or_default()),
+                return_type.map(|p| p.key().unwrap_or_default()),
+                path_prefix,
+            )
+            .await?;
+        let mut signatures: HashMap<String, Signature> = self
+            .db
+            .get_signatures(&candidates)
+            .await?
+            .into_iter()
+            .filter(|sig| {
+                param_type.is_none_or(|pattern| {
+                    sig.params
+                        .iter()
+                        .filter_map(|p| p.declared_type.as_ref())
+                        .any(|ty| matches(pattern, ty))
+                }) && return_type.is_none_or(|pattern| {
+                    sig.return_type
+                        .as_ref()
+                        .is_some_and(|ty| matches(pattern, ty))
+                })
+            })
+            .map(|sig| (sig.node_id.clone(), sig))
+            .collect();
+
+        let ids: Vec<String> = candidates
+            .into_iter()
+            .filter(|id| signatures.contains_key(id))
+            .take(limit)
+            .collect();
+        let mut nodes: HashMap<String, Node> = self
+            .db
+            .get_nodes_by_ids(&ids)
+            .await?
+            .into_iter()
+            .map(|n| (n.id.clone(), n))
+            .collect();
+        Ok(ids
+            .iter()
+            .filter_map(|id| Some((nodes.remove(id)?, signatures.remove(id)?)))
+            .collect())
+    }
+
+    /// Returns the names of the languages under `path_prefix` whose
+    /// callables [`signature_search`](Self::signature_search) cannot find,
+    /// because their extractor records no signatures.
+    pub async fn languages_without_signatures(
+        &self,
+        path_prefix: Option<&str>,
+    ) -> Result<Vec<String>> {
+        let mut languages = BTreeSet::new();
+        for path in self.db.get_callable_files(path_prefix).await? {
+            if let Some(extractor) = self.registry.extractor_for_file(&path) {
+                if !extractor.extracts_signatures() {
+                    languages.insert(extractor.language_name().to_string());
+                }
+            }
+        }
+        Ok(languages.into_iter().collect())
+    }
+
     /// Returns all nodes for a given file, ordered by start line.
     pub async fn get_nodes_by_file(&self, file_path: &str) -> Result<Vec<Node>> {
         self.db.get_nodes_by_file(file_path).await
@@ -2033,6 +2834,101 @@ impl TokenSave {
 // Shared utilities
 // ---------------------------------------------------------------------------
 
+/// The test cases the extractors found, by file.
+///
+/// Test code is a [`NodeKind::TestCase`] and whatever it encloses, such as
+/// the helpers of a `#[cfg(test)]` module, plus every file that is test code
+/// as a whole: one its language's tooling only builds as tests, such as a Go
+/// `_test.go` file, or, in languages whose test framework constructs are not
+/// detected, one whose path passes [`is_test_file`].
+#[derive(Debug, Default)]
+pub struct TestScopes {
+    /// The line spans of the test cases in each file.
+    spans: HashMap<String, Vec<(u32, u32)>>,
+}
+
+impl TestScopes {
+    /// Collects the spans of the given test cases.
+    pub fn new(test_cases: &[Node]) -> Self {
+        let mut spans: HashMap<String, Vec<(u32, u32)>> = HashMap::new();
+        for test in test_cases.iter().filter(|n| n.kind == NodeKind::TestCase) {
+            spans
+                .entry(test.file_path.clone())
+                .or_default()
+                .push((test.start_line, test.end_line));
+        }
+        Self { spans }
+    }
+
+    /// Returns `true` if the file holds test cases or is test code as a
+    /// whole.
+    pub fn is_test_file(&self, path: &str) -> bool {
+        self.spans.contains_key(path) || is_test_by_path(path)
+    }
+
+    /// Returns `true` for a test case, for the code it encloses, and for
+    /// everything in a file that is test code as a whole.
+    pub fn contains(&self, node: &Node) -> bool {
+        if node.kind == NodeKind::TestCase || is_test_by_path(&node.file_path) {
+            return true;
+        }
+        self.spans.get(&node.file_path).is_some_and(|spans| {
+            spans
+                .iter()
+                .any(|&(start, end)| start <= node.start_line && node.end_line <= end)
+        })
+    }
+}
+
+/// True for a file that is test code as a whole, judged by its path alone.
+fn is_test_by_path(path: &str) -> bool {
+    if detects_tests(path) {
+        is_test_only_file(path)
+    } else {
+        is_test_file(path)
+    }
+}
+
+/// True for the files whose extractors mark test framework constructs as
+/// [`NodeKind::TestCase`].
+fn detects_tests(path: &str) -> bool {
+    matches!(
+        path.rsplit('.').next().unwrap_or(""),
+        "rs" | "go" | "py" | "java" | "ts" | "tsx" | "js" | "jsx"
+    )
+}
+
+/// True for the files a language's tooling only builds or loads for tests:
+/// Rust integration tests under `tests/`, Go `_test.go` files, Java sources
+/// under Maven and Gradle's `src/test/`, JavaScript and TypeScript `.test.`
+/// and `.spec.` modules, pytest's `conftest.py`, and Python, JavaScript and
+/// TypeScript files under a `tests/` or `test/` directory.
+fn is_test_only_file(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    let (stem, ext) = name.rsplit_once('.').unwrap_or((name, ""));
+    let in_test_dir = in_dir(path, "tests/") || in_dir(path, "test/");
+    match ext {
+        "rs" => in_dir(path, "tests/"),
+        "go" => stem.ends_with("_test"),
+        "java" => in_dir(path, "src/test/"),
+        "ts" | "tsx" | "js" | "jsx" => {
+            in_test_dir
+                || in_dir(path, "__tests__/")
+                || stem
+                    .rsplit_once('.')
+                    .is_some_and(|(_, suffix)| matches!(suffix, "test" | "spec"))
+        }
+        "py" => in_test_dir || name == "conftest.py",
+        _ => false,
+    }
+}
+
+/// True when `path` lies under a directory ending in `dir` (e.g. `tests/`),
+/// at the project root or deeper.
+fn in_dir(path: &str, dir: &str) -> bool {
+    path.starts_with(dir) || path.contains(&format!("/{dir}"))
+}
+
 /// Returns `true` if the file path looks like a test file.
 pub fn is_test_file(path: &str) -> bool {
     let test_segments = [
@@ -75,6 +75,67 @@ pub enum NodeKind {
     PascalUnit,
     PascalProgram,
     PascalRecord,
+    /// A crate, npm package, Go module, Maven artifact or Gradle project
+    /// declared by a build manifest rather than by source code.
+    ManifestPackage,
+    /// A dependency outside the project (crate, npm package, Python module,
+    /// JVM or Go package) that the code imports symbols from.
+    ExternalPackage,
+    /// A symbol of an external dependency that project code references.
+    ExternalSymbol,
+    /// A name the program registers as data (a route, command, interpreter
+    /// primitive or event), found by the project's domain rules.
+    DomainSymbol,
+    /// A Vue, Svelte or Astro single-file component.
+    Component,
+    /// A key of a YAML, TOML or JSON file, named by its key path.
+    ConfigKey,
+    /// A job of a GitHub Actions workflow.
+    WorkflowJob,
+    /// A step of a GitHub Actions workflow job.
+    WorkflowStep,
+    /// A service of a docker-compose file.
+    ComposeService,
+    /// A Kubernetes resource, named `Kind/name`.
+    K8sResource,
+    /// A Terraform `resource` block, named by its address (`aws_s3_bucket.logs`).
+    TfResource,
+    /// A Terraform `data` block (`data.aws_ami.ubuntu`).
+    TfData,
+    /// A Terraform `module` call (`module.vpc`).
+    TfModule,
+    /// A Terraform input variable (`var.region`).
+    TfVariable,
+    /// A Terraform output value (`output.bucket_arn`).
+    TfOutput,
+    /// A Terraform local value (`local.tags`).
+    TfLocal,
+    /// A GraphQL object, interface, input, enum, union or scalar type.
+    GqlType,
+    /// A field of a GraphQL type, named `Type.field`.
+    GqlField,
+    /// A named GraphQL query, mutation or subscription.
+    GqlOperation,
+    /// A GraphQL fragment.
+    GqlFragment,
+    /// A build target: a Make rule target, a `CMake` target, or a Bazel or
+    /// Buck rule named by its label (`//src/net:http`).
+    BuildTarget,
+    /// An HTTP route a web framework dispatches to a handler, named after
+    /// its method and normalized path (`GET /users/{}`).
+    Route,
+    /// A table of a SQL schema, created by `CREATE TABLE`.
+    SqlTable,
+    /// A SQL view.
+    SqlView,
+    /// A column of a SQL table, named `table.column`.
+    SqlColumn,
+    /// A SQL index.
+    SqlIndex,
+    /// A test a test framework runs (a `#[test]` function, Go `TestXxx`,
+    /// pytest `test_*`, `JUnit` `@Test`, a Jest `it`), or a suite grouping
+    /// tests: a `#[cfg(test)]` module or a `describe` block.
+    TestCase,
     // Protobuf-specific
     #[cfg(feature = "lang-protobuf")]
     ProtoMessage,
@@ -149,6 +210,33 @@ impl NodeKind {
             NodeKind::PascalUnit => "pascal_unit",
             NodeKind::PascalProgram => "pascal_program",
             NodeKind::PascalRecord => "pascal_record",
+            NodeKind::ManifestPackage => "manifest_package",
+            NodeKind::ExternalPackage => "external_package",
+            NodeKind::ExternalSymbol => "external_symbol",
+            NodeKind::DomainSymbol => "domain_symbol",
+            NodeKind::Component => "component",
+            NodeKind::ConfigKey => "config_key",
+            NodeKind::WorkflowJob => "workflow_job",
+            NodeKind::WorkflowStep => "workflow_step",
+            NodeKind::ComposeService => "compose_service",
+            NodeKind::K8sResource => "k8s_resource",
+            NodeKind::TfResource => "tf_resource",
+            NodeKind::TfData => "tf_data",
+            NodeKind::TfModule => "tf_module",
+            NodeKind::TfVariable => "tf_variable",
+            NodeKind::TfOutput => "tf_output",
+            NodeKind::TfLocal => "tf_local",
+            NodeKind::GqlType => "gql_type",
+            NodeKind::GqlField => "gql_field",
+            NodeKind::GqlOperation => "gql_operation",
+            NodeKind::GqlFragment => "gql_fragment",
+            NodeKind::BuildTarget => "build_target",
+            NodeKind::Route => "route",
+            NodeKind::SqlTable => "sql_table",
+            NodeKind::SqlView => "sql_view",
+            NodeKind::SqlColumn => "sql_column",
+            NodeKind::SqlIndex => "sql_index",
+            NodeKind::TestCase => "test_case",
             #[cfg(feature = "lang-protobuf")]
             NodeKind::ProtoMessage => "proto_message",
             #[cfg(feature = "lang-protobuf")]
@@ -221,6 +309,33 @@ impl NodeKind {
             "pascal_unit" => Some(NodeKind::PascalUnit),
             "pascal_program" => Some(NodeKind::PascalProgram),
             "pascal_record" => Some(NodeKind::PascalRecord),
+            "manifest_package" => Some(NodeKind::ManifestPackage),
+            "external_package" => Some(NodeKind::ExternalPackage),
+            "external_symbol" => Some(NodeKind::ExternalSymbol),
+            "domain_symbol" => Some(NodeKind::DomainSymbol),
+            "component" => Some(NodeKind::Component),
+            "config_key" => Some(NodeKind::ConfigKey),
+            "workflow_job" => Some(NodeKind::WorkflowJob),
+            "workflow_step" => Some(NodeKind::WorkflowStep),
+            "compose_service" => Some(NodeKind::ComposeService),
+            "k8s_resource" => Some(NodeKind::K8sResource),
+            "tf_resource" => Some(NodeKind::TfResource),
+            "tf_data" => Some(NodeKind::TfData),
+            "tf_module" => Some(NodeKind::TfModule),
+            "tf_variable" => Some(NodeKind::TfVariable),
+            "tf_output" => Some(NodeKind::TfOutput),
+            "tf_local" => Some(NodeKind::TfLocal),
+            "gql_type" => Some(NodeKind::GqlType),
+            "gql_field" => Some(NodeKind::GqlField),
+            "gql_operation" => Some(NodeKind::GqlOperation),
+            "gql_fragment" => Some(NodeKind::GqlFragment),
+            "build_target" => Some(NodeKind::BuildTarget),
+            "route" => Some(NodeKind::Route),
+            "sql_table" => Some(NodeKind::SqlTable),
+            "sql_view" => Some(NodeKind::SqlView),
+            "sql_column" => Some(NodeKind::SqlColumn),
+            "sql_index" => Some(NodeKind::SqlIndex),
+            "test_case" => Some(NodeKind::TestCase),
             #[cfg(feature = "lang-protobuf")]
             "proto_message" => Some(NodeKind::ProtoMessage),
             #[cfg(feature = "lang-protobuf")]
@@ -230,6 +345,79 @@ impl NodeKind {
             _ => None,
         }
     }
+
+    /// Returns true for kinds that declare a type a parameter, field or
+    /// return value can name (structs, classes, interfaces, aliases, ...).
+    pub fn is_type_definition(&self) -> bool {
+        match self {
+            NodeKind::Struct
+            | NodeKind::Enum
+            | NodeKind::Trait
+            | NodeKind::TypeAlias
+            | NodeKind::Class
+            | NodeKind::Interface
+            | NodeKind::InnerClass
+            | NodeKind::InterfaceType
+            | NodeKind::ScalaObject
+            | NodeKind::CaseClass
+            | NodeKind::Union
+            | NodeKind::Typedef
+            | NodeKind::DataClass
+            | NodeKind::SealedClass
+            | NodeKind::KotlinObject
+            | NodeKind::Mixin
+            | NodeKind::Delegate
+            | NodeKind::Record
+            | NodeKind::PascalRecord => true,
+            #[cfg(feature = "lang-protobuf")]
+            NodeKind::ProtoMessage => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true for the kinds extracted from configuration files, which
+    /// only references from configuration files can target.
+    pub fn is_config(&self) -> bool {
+        matches!(
+            self,
+            NodeKind::ConfigKey
+                | NodeKind::WorkflowJob
+                | NodeKind::WorkflowStep
+                | NodeKind::ComposeService
+                | NodeKind::K8sResource
+        )
+    }
+
+    /// Returns true for the kinds extracted from Terraform files, which only
+    /// references from the same Terraform module can target.
+    pub fn is_terraform(&self) -> bool {
+        matches!(
+            self,
+            NodeKind::TfResource
+                | NodeKind::TfData
+                | NodeKind::TfModule
+                | NodeKind::TfVariable
+                | NodeKind::TfOutput
+                | NodeKind::TfLocal
+        )
+    }
+
+    /// Returns true for the kinds extracted from GraphQL documents.
+    pub fn is_graphql(&self) -> bool {
+        matches!(
+            self,
+            NodeKind::GqlType | NodeKind::GqlField | NodeKind::GqlOperation | NodeKind::GqlFragment
+        )
+    }
+
+    /// Returns true for the kinds of SQL schema objects, which only
+    /// references from SQL statements can target.
+    pub fn is_sql(&self) -> bool {
+        matches!(
+            self,
+            NodeKind::SqlTable | NodeKind::SqlView | NodeKind::SqlColumn | NodeKind::SqlIndex
+        )
+    }
 }
 
 /// Kinds of edges in the code graph.
@@ -245,6 +433,21 @@ pub enum EdgeKind {
     Extends,
     Annotates,
     Receives,
+    /// Links a manifest package to a package of the same project that it
+    /// declares as a dependency, or a workflow job or compose service to one
+    /// it needs to run first.
+    DependsOn,
+    /// Links a function to a domain symbol that it registers.
+    Registers,
+    /// Links a function to a domain symbol that it dispatches on or looks up.
+    Dispatches,
+    /// Links a function or SQL statement to a table or column that it
+    /// inserts into, updates or deletes from. Reads are `Uses` edges.
+    Writes,
+    /// Links an abstract, interface or trait method to an implementation
+    /// that a call to it may dispatch to. Never stored: produced by graph
+    /// traversals that expand virtual dispatch.
+    Virtual,
 }
 
 #[allow(clippy::should_implement_trait)]
@@ -262,6 +465,11 @@ impl EdgeKind {
             EdgeKind::Extends => "extends",
             EdgeKind::Annotates => "annotates",
             EdgeKind::Receives => "receives",
+            EdgeKind::DependsOn => "depends_on",
+            EdgeKind::Registers => "registers",
+            EdgeKind::Dispatches => "dispatches",
+            EdgeKind::Writes => "writes",
+            EdgeKind::Virtual => "virtual",
         }
     }
 
@@ -278,9 +486,24 @@ impl EdgeKind {
             "extends" => Some(EdgeKind::Extends),
             "annotates" => Some(EdgeKind::Annotates),
             "receives" => Some(EdgeKind::Receives),
+            "depends_on" => Some(EdgeKind::DependsOn),
+            "registers" => Some(EdgeKind::Registers),
+            "dispatches" => Some(EdgeKind::Dispatches),
+            "writes" => Some(EdgeKind::Writes),
+            "virtual" => Some(EdgeKind::Virtual),
             _ => None,
         }
     }
+
+    /// Returns true for the edges that point at a type a node mentions:
+    /// `TypeOf` (a field's or property's declared type), `Returns` (a
+    /// function's return type) and `Receives` (a parameter or receiver type).
+    pub fn is_type_reference(&self) -> bool {
+        matches!(
+            self,
+            EdgeKind::TypeOf | EdgeKind::Returns | EdgeKind::Receives
+        )
+    }
 }
 
 /// Visibility of a code item.
@@ -356,6 +579,80 @@ pub struct Edge {
     pub target: String,
     pub kind: EdgeKind,
     pub line: Option<u32>,
+    /// How the edge's target was found, for edges produced by reference
+    /// resolution. `None` for edges the extractors emit directly (such as
+    /// `Contains`), which are exact.
+    #[serde(default)]
+    pub provenance: Option<EdgeProvenance>,
+}
+
+impl Edge {
+    /// Returns how confident the graph is in this edge, from 0.0 to 1.0.
+    /// Edges without provenance are exact and score 1.0.
+    pub fn confidence(&self) -> f64 {
+        self.provenance.as_ref().map_or(1.0, |p| p.confidence)
+    }
+}
+
+/// The resolution strategy and confidence behind a resolved edge.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EdgeProvenance {
+    pub strategy: ResolutionStrategy,
+    pub confidence: f64,
+}
+
+/// How a reference was matched to the node it points at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ResolutionStrategy {
+    /// Matched a qualified name, or a method on the receiver's declared type.
+    ExactQualified,
+    /// Bound through an import of the referencing file.
+    ImportScoped,
+    /// The only node with that name, defined in the referencing file.
+    SameFile,
+    /// The only node with that name, defined in another file.
+    Heuristic,
+    /// The best-scoring of several nodes sharing the name.
+    Ambiguous { candidates: u32 },
+    /// A symbol of a dependency outside the project, bound through an
+    /// external import or a dependency-rooted path.
+    External,
+}
+
+impl ResolutionStrategy {
+    /// Returns the string representation of this strategy.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ExactQualified => "exact_qualified",
+            Self::ImportScoped => "import_scoped",
+            Self::SameFile => "same_file",
+            Self::Heuristic => "heuristic",
+            Self::Ambiguous { .. } => "ambiguous",
+            Self::External => "external",
+        }
+    }
+
+    /// Parses a strategy from its string representation. `candidates` is
+    /// only used by `ambiguous`.
+    pub fn parse(s: &str, candidates: u32) -> Option<Self> {
+        match s {
+            "exact_qualified" => Some(Self::ExactQualified),
+            "import_scoped" => Some(Self::ImportScoped),
+            "same_file" => Some(Self::SameFile),
+            "heuristic" => Some(Self::Heuristic),
+            "ambiguous" => Some(Self::Ambiguous { candidates }),
+            "external" => Some(Self::External),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of candidates an ambiguous match chose from, or 1.
+    pub fn candidates(&self) -> u32 {
+        match self {
+            Self::Ambiguous { candidates } => *candidates,
+            _ => 1,
+        }
+    }
 }
 
 /// Record tracking an indexed file.
@@ -378,6 +675,129 @@ pub struct UnresolvedRef {
     pub line: u32,
     pub column: u32,
     pub file_path: String,
+    /// For method calls, the declared type of the receiver expression
+    /// (`self.db.get_node` inside `impl Server` with `db: Database` gives
+    /// `Database`), when the extractor could determine it.
+    #[serde(default)]
+    pub receiver_type: Option<String>,
+    /// For a reference across a language boundary, the ABI it crosses by.
+    /// `C` for a call to a C symbol (a cgo `C.compress()` call, a Rust
+    /// `extern "C"` declaration, a JNI `native` method), which binds only to
+    /// functions another language can call by that symbol. On an
+    /// `Implements` reference, the language a Rust function is exported to:
+    /// `Python` for a `#[pyfunction]`, `JavaScript` for a `#[wasm_bindgen]`
+    /// function and `C` for a `#[no_mangle]` one.
+    #[serde(default)]
+    pub abi: Option<String>,
+    /// For an HTTP request, its method (`GET`). The reference's name is then
+    /// the normalized path requested, whose parameters are all `{}`
+    /// (`/users/{}`), and it binds only to the routes serving that path.
+    #[serde(default)]
+    pub http_method: Option<String>,
+    /// Whether this reference comes from a SQL statement. Its name is then
+    /// the table, view or column it reads or writes (`nodes`, `nodes.kind`),
+    /// and it binds only to SQL schema objects.
+    #[serde(default)]
+    pub sql: bool,
+}
+
+impl UnresolvedRef {
+    /// The key this reference is indexed under in the database: the last
+    /// segment of its name, ignoring `::` / `.` qualifiers and a leading
+    /// `new ` (`self.db.get_node` → `get_node`, `Foo::new` → `new`).
+    ///
+    /// Apart from import aliases, references resolve to nodes whose `name`
+    /// equals this key, which lets incremental sync find the references a
+    /// newly added symbol may bind.
+    pub fn name_key(&self) -> &str {
+        if self.http_method.is_some() {
+            return &self.reference_name;
+        }
+        let name = self.reference_name.trim();
+        let name = name.strip_prefix("new ").unwrap_or(name).trim();
+        let name = name.rsplit("::").next().unwrap_or(name);
+        name.rsplit('.').next().unwrap_or(name)
+    }
+
+    /// The C symbol this reference names, if it crosses a language boundary
+    /// by the C ABI (see [`abi`](Self::abi)).
+    pub fn c_symbol(&self) -> Option<&str> {
+        (self.abi.as_deref() == Some("C")).then_some(self.reference_name.as_str())
+    }
+
+    /// The language this reference exports its function to, if it records
+    /// an export (see [`abi`](Self::abi)).
+    pub fn export_language(&self) -> Option<&str> {
+        if self.reference_kind != EdgeKind::Implements {
+            return None;
+        }
+        self.abi.as_deref()
+    }
+
+    /// The name of a [`NodeKind::Route`]: its method and normalized path,
+    /// whose parameters are all `{}` (`GET /users/{}`). A request binds to
+    /// the routes named after its method or `ANY` and its path, so requests
+    /// are keyed by their path alone.
+    pub fn route_name(method: &str, path: &str) -> String {
+        format!("{method} {path}")
+    }
+
+    /// The method and path of the route this reference requests, if it is
+    /// an HTTP request (see [`http_method`](Self::http_method)).
+    pub fn route(&self) -> Option<(&str, &str)> {
+        let method = self.http_method.as_deref()?;
+        Some((method, &self.reference_name))
+    }
+
+    /// The table, view or column this reference names, if it comes from a
+    /// SQL statement (see [`sql`](Self::sql)).
+    pub fn sql_object(&self) -> Option<&str> {
+        self.sql.then_some(self.reference_name.as_str())
+    }
+}
+
+/// Splits the name of a route into its method and path
+/// (`GET /users/{}` → `("GET", "/users/{}")`).
+pub fn split_route(name: &str) -> Option<(&str, &str)> {
+    let (method, path) = name.split_once(' ')?;
+    (ROUTE_METHODS.contains(&method) && path.starts_with('/')).then_some((method, path))
+}
+
+/// The methods of route names; `ANY` matches requests of every method.
+const ROUTE_METHODS: &[&str] = &[
+    "GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS", "ANY",
+];
+
+/// The declared parameters and return type of a callable node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Signature {
+    pub node_id: String,
+    /// The parameters in declaration order. Receivers (Rust's `self`,
+    /// Python's `self` / `cls`, TypeScript's `this`) are not parameters.
+    pub params: Vec<Param>,
+    /// The declared return type, if any.
+    pub return_type: Option<TypeSpec>,
+}
+
+/// A declared parameter of a callable.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Param {
+    pub name: String,
+    /// The declared type, if any.
+    pub declared_type: Option<TypeSpec>,
+    /// The default value, as written.
+    pub default_value: Option<String>,
+    /// Whether callers may leave the argument out: it has a default, is
+    /// marked optional (`x?: T`), or collects the remaining arguments.
+    pub optional: bool,
+}
+
+/// A type as written in a signature, and its normalized name (see
+/// [`crate::extraction::signatures`]).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeSpec {
+    pub text: String,
+    pub name: String,
 }
 
 /// Result of extracting code entities from a file.
@@ -386,6 +806,9 @@ pub struct ExtractionResult {
     pub nodes: Vec<Node>,
     pub edges: Vec<Edge>,
     pub unresolved_refs: Vec<UnresolvedRef>,
+    /// The parameters and return types of the callables in `nodes`.
+    #[serde(default)]
+    pub signatures: Vec<Signature>,
     pub errors: Vec<String>,
     pub duration_ms: u64,
 }
@@ -413,6 +836,7 @@ impl ExtractionResult {
             .retain(|e| !bad_ids.contains(&e.source) && !bad_ids.contains(&e.target));
         self.unresolved_refs
             .retain(|r| !bad_ids.contains(&r.from_node_id));
+        self.signatures.retain(|s| !bad_ids.contains(&s.node_id));
 
         let removed = before - self.nodes.len();
         if removed > 0 {
@@ -420,6 +844,53 @@ impl ExtractionResult {
                 .push(format!("stripped {removed} node(s) with empty names"));
         }
     }
+
+    /// Replaces the IDs extractors derive from start lines with ones derived
+    /// from each node's qualified name, so a node keeps its ID when edits
+    /// elsewhere in the file move it. Nodes of one kind sharing a qualified
+    /// name (overloads, redeclarations) are numbered in source order. Edges,
+    /// unresolved refs and signatures are rewritten to the new IDs.
+    pub fn assign_stable_ids(&mut self) {
+        let mut order: Vec<usize> = (0..self.nodes.len()).collect();
+        order.sort_by_key(|&i| (self.nodes[i].start_line, self.nodes[i].start_column));
+
+        let mut overloads: HashMap<(&str, &str), u32> = HashMap::new();
+        let mut new_ids: HashMap<String, String> = HashMap::with_capacity(self.nodes.len());
+        for &i in &order {
+            let node = &self.nodes[i];
+            if new_ids.contains_key(&node.id) {
+                continue;
+            }
+            let name = if node.qualified_name.is_empty() {
+                &node.name
+            } else {
+                &node.qualified_name
+            };
+            let index = overloads.entry((node.kind.as_str(), name)).or_insert(0);
+            let id = generate_node_id(&node.file_path, &node.kind, name, *index);
+            *index += 1;
+            new_ids.insert(node.id.clone(), id);
+        }
+
+        let remap = |id: &mut String| {
+            if let Some(new_id) = new_ids.get(id.as_str()) {
+                id.clone_from(new_id);
+            }
+        };
+        for node in &mut self.nodes {
+            remap(&mut node.id);
+        }
+        for edge in &mut self.edges {
+            remap(&mut edge.source);
+            remap(&mut edge.target);
+        }
+        for uref in &mut self.unresolved_refs {
+            remap(&mut uref.from_node_id);
+        }
+        for signature in &mut self.signatures {
+            remap(&mut signature.node_id);
+        }
+    }
 }
 
 /// A subgraph containing a subset of nodes and edges.
@@ -570,16 +1041,25 @@ pub struct CodeBlock {
     pub node_id: Option<String>,
 }
 
-/// Generates a deterministic node ID from file path, kind, name, and line number.
+/// Generates a deterministic node ID from file path, kind, name, and a
+/// disambiguator telling apart nodes that share the other three.
 ///
 /// The ID format is `"kind:32hexchars"` where the hex portion is the first 32
-/// characters of the SHA-256 hash of the input components.
-pub fn generate_node_id(file_path: &str, kind: &NodeKind, name: &str, line: u32) -> String {
+/// characters of the SHA-256 hash of the input components. Extractors pass
+/// the start line as the disambiguator;
+/// [`ExtractionResult::assign_stable_ids`] then swaps in IDs keyed by
+/// qualified name and overload index.
+pub fn generate_node_id(
+    file_path: &str,
+    kind: &NodeKind,
+    name: &str,
+    disambiguator: u32,
+) -> String {
     debug_assert!(
         !name.is_empty(),
-        "generate_node_id called with empty name for {file_path}:{line}"
+        "generate_node_id called with empty name for {file_path}:{disambiguator}"
     );
-    let input = format!("{}:{}:{}:{}", file_path, kind.as_str(), name, line);
+    let input = format!("{}:{}:{}:{}", file_path, kind.as_str(), name, disambiguator);
     let mut hasher = Sha256::new();
     hasher.update(input.as_bytes());
     let hash = hasher.finalize();
@@ -603,6 +1083,43 @@ pub struct ResolvedRef {
     pub target_node_id: String,
     pub confidence: f64,
     pub resolved_by: String,
+    pub strategy: ResolutionStrategy,
+}
+
+/// Why a reference is missing from the graph, or bound only by an arbitrary
+/// pick among candidates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum UnresolvedReason {
+    /// No node in the graph has the referenced name.
+    UnknownName,
+    /// Nodes share the reference's last segment, but the name as written is
+    /// unknown, so resolution skipped it up front.
+    FilteredByKnownNames,
+    /// Nodes with the name exist, but none can be the target (for example a
+    /// type reference whose namesakes are all functions).
+    NoFittingCandidate,
+    /// Resolved, but several candidates tied for the best score and the
+    /// first one was taken.
+    TiedCandidates { candidates: u32 },
+}
+
+impl UnresolvedReason {
+    /// Returns the string representation of this reason.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::UnknownName => "unknown_name",
+            Self::FilteredByKnownNames => "filtered_by_known_names",
+            Self::NoFittingCandidate => "no_fitting_candidate",
+            Self::TiedCandidates { .. } => "tied_candidates",
+        }
+    }
+}
+
+/// A reference that did not resolve, or resolved ambiguously, and why.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefDiagnostic {
+    pub reference: UnresolvedRef,
+    pub reason: UnresolvedReason,
 }
 
 /// Result of a single string replacement edit.
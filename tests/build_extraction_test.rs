@@ -0,0 +1,233 @@
+mod common;
+
+use std::fs;
+
+use common::{named, refs};
+use tempfile::TempDir;
+use tokensave::extraction::{
+    CMakeExtractor, LanguageExtractor, LanguageRegistry, MakeExtractor, StarlarkBuildExtractor,
+};
+use tokensave::tokensave::TokenSave;
+use tokensave::types::*;
+
+const MAKEFILE: &str = "CC := gcc
+SRCS = main.c \\
+       util.c
+OBJS = $(SRCS:.c=.o)
+
+.PHONY: all clean
+
+all: app
+
+app: $(OBJS) | build
+\t$(CC) -o $@ $^
+
+%.o: %.c config.h
+\t$(CC) -c $< -o $@
+
+build:
+\tmkdir -p build
+
+clean:
+\trm -f app $(OBJS)
+";
+
+const CMAKE: &str = r#"project(server C)
+set(SOURCES main.c net/http.c)
+
+add_library(net STATIC net/http.c)
+target_sources(net PRIVATE ${CMAKE_CURRENT_SOURCE_DIR}/net/tls.c)
+add_library(Server::net ALIAS net)
+
+# The server binary
+add_executable(${PROJECT_NAME} ${SOURCES})
+target_link_libraries(${PROJECT_NAME} PRIVATE Server::net pthread)
+"#;
+
+const BUILD: &str = r#"load("@rules_cc//cc:defs.bzl", "cc_binary", "cc_library")
+
+COMMON = ["util.h"]
+
+cc_library(
+    name = "util",
+    srcs = ["util.cc"] + glob(["*.inc"]),
+    hdrs = COMMON,
+    deps = ["//third_party/zlib", "@abseil//absl/strings"],
+)
+
+genrule(
+    name = "version",
+    outs = ["version.h"],
+)
+
+cc_binary(
+    name = "tool",
+    srcs = ["main.cc", ":version", "//lib:shared.h"],
+    deps = [":util"],
+)
+"#;
+
+fn targets(result: &ExtractionResult) -> Vec<&str> {
+    result
+        .nodes
+        .iter()
+        .filter(|n| n.kind == NodeKind::BuildTarget)
+        .map(|n| n.name.as_str())
+        .collect()
+}
+
+#[test]
+fn test_build_files_are_matched_by_name() {
+    let registry = LanguageRegistry::new();
+    let language = |path: &str| registry.extractor_for_file(path).map(|e| e.language_name());
+    assert_eq!(language("src/Makefile"), Some("Make"));
+    assert_eq!(language("rules.mk"), Some("Make"));
+    assert_eq!(language("src/CMakeLists.txt"), Some("CMake"));
+    assert_eq!(language("notes.txt"), None);
+    assert_eq!(language("lib/BUILD.bazel"), Some("Starlark"));
+    assert_eq!(language("BUCK"), Some("Starlark"));
+}
+
+#[test]
+fn test_make_targets_prerequisites_and_pattern_rules() {
+    let result = MakeExtractor.extract("src/Makefile", MAKEFILE);
+    assert_eq!(targets(&result), vec!["all", "app", "build", "clean"]);
+    let app = result.nodes.iter().find(|n| n.name == "app").unwrap();
+    assert_eq!((app.start_line, app.end_line), (9, 10));
+    assert_eq!(app.signature.as_deref(), Some("app: $(OBJS) | build"));
+
+    assert_eq!(
+        refs(&result, "all"),
+        vec![("app".to_string(), EdgeKind::DependsOn)]
+    );
+    // Objects without a rule map to their sources through `%.o: %.c`.
+    let mut expected = named(
+        EdgeKind::Contains,
+        &["src/main.c", "src/config.h", "src/util.c", "src/config.h"],
+    );
+    expected.push(("build".to_string(), EdgeKind::DependsOn));
+    assert_eq!(refs(&result, "app"), expected);
+    let main = result
+        .unresolved_refs
+        .iter()
+        .find(|r| r.reference_name == "src/main.c")
+        .unwrap();
+    assert_eq!((main.line, main.column), (9, 5));
+}
+
+#[test]
+fn test_cmake_targets_sources_and_links() {
+    let result = CMakeExtractor.extract("server/CMakeLists.txt", CMAKE);
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(targets(&result), vec!["net", "Server::net", "server"]);
+    assert_eq!(
+        refs(&result, "net"),
+        named(
+            EdgeKind::Contains,
+            &["server/net/http.c", "server/net/tls.c"]
+        )
+    );
+    assert_eq!(
+        refs(&result, "Server::net"),
+        vec![("net".to_string(), EdgeKind::DependsOn)]
+    );
+    let mut expected = named(EdgeKind::Contains, &["server/main.c", "server/net/http.c"]);
+    expected.push(("Server::net".to_string(), EdgeKind::DependsOn));
+    expected.push(("pthread".to_string(), EdgeKind::DependsOn));
+    assert_eq!(refs(&result, "server"), expected);
+
+    let result = CMakeExtractor.extract("CMakeLists.txt", "add_executable(app\n  main.c\n");
+    assert_eq!(result.errors, vec!["CMakeLists.txt:1: unclosed command"]);
+}
+
+#[test]
+fn test_bazel_rules_are_named_by_label() {
+    let result = StarlarkBuildExtractor.extract("tools/BUILD", BUILD);
+    assert_eq!(
+        targets(&result),
+        vec!["//tools:util", "//tools:version", "//tools:tool"]
+    );
+    let util = &result.nodes[1];
+    assert_eq!(
+        util.signature.as_deref(),
+        Some(r#"cc_library(name = "util")"#)
+    );
+    assert_eq!((util.start_line, util.end_line), (4, 9));
+
+    let mut expected = named(EdgeKind::Contains, &["tools/util.cc", "tools/util.h"]);
+    // External repositories are not part of the graph.
+    expected.push(("//third_party/zlib:zlib".to_string(), EdgeKind::DependsOn));
+    assert_eq!(refs(&result, "//tools:util"), expected);
+    assert_eq!(
+        refs(&result, "//tools:tool"),
+        vec![
+            ("tools/main.cc".to_string(), EdgeKind::Contains),
+            ("//tools:version".to_string(), EdgeKind::DependsOn),
+            ("lib/shared.h".to_string(), EdgeKind::Contains),
+            ("//tools:util".to_string(), EdgeKind::DependsOn),
+        ]
+    );
+}
+
+#[test]
+fn test_non_ascii_and_truncated_build_files_do_not_panic() {
+    let make = MakeExtractor.extract("Makefile", "é日\"all: app\n.É: x\nçà: é.c\n");
+    assert_eq!(targets(&make), vec!["é日\"all", ".É", "çà"]);
+    MakeExtractor.extract("Makefile", "app: $(OBJS");
+
+    let cmake = CMakeExtractor.extract("CMakeLists.txt", "(é)\nadd_library(日 é.c)\n");
+    assert_eq!(targets(&cmake), vec!["日"]);
+    let truncated = CMakeExtractor.extract("CMakeLists.txt", "add_library(net \"é");
+    assert_eq!(truncated.errors, vec!["CMakeLists.txt:1: unclosed command"]);
+
+    let build = StarlarkBuildExtractor.extract("BUILD", "cc_library(\n  name = \"");
+    assert_eq!(targets(&build), vec!["//:"]);
+    let build = StarlarkBuildExtractor.extract("BUILD", "cc_library(name = \"é日\", srcs = [\"");
+    assert_eq!(targets(&build), vec!["//:é日"]);
+}
+
+#[tokio::test]
+async fn test_build_targets_to_rebuild_for_a_file() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+    fs::create_dir_all(project.join("tools")).unwrap();
+    fs::write(project.join("tools/BUILD"), BUILD).unwrap();
+    fs::write(
+        project.join("tools/util.cc"),
+        "int helper() { return 1; }\n",
+    )
+    .unwrap();
+    fs::write(
+        project.join("tools/main.cc"),
+        "int main() { return helper(); }\n",
+    )
+    .unwrap();
+    fs::write(project.join("tools/util.h"), "int helper();\n").unwrap();
+
+    let ts = TokenSave::init(project).await.unwrap();
+    ts.index_all().await.unwrap();
+
+    let names = |nodes: &[Node]| -> Vec<String> { nodes.iter().map(|n| n.name.clone()).collect() };
+    let (including, rebuilt) = ts.build_targets_for_file("tools/util.cc").await.unwrap();
+    assert_eq!(names(&including), vec!["//tools:util"]);
+    // `tool` depends on `util`, and its `main.cc` calls into `util.cc`.
+    assert_eq!(names(&rebuilt), vec!["//tools:util", "//tools:tool"]);
+
+    let (including, _) = ts.build_targets_for_file("tools/main.cc").await.unwrap();
+    assert_eq!(names(&including), vec!["//tools:tool"]);
+
+    // Code never binds to a build target sharing its name.
+    let nodes = ts.get_nodes_by_file("tools/BUILD").await.unwrap();
+    let util = nodes.iter().find(|n| n.name == "//tools:util").unwrap();
+    let incoming = ts.get_incoming_edges(&util.id).await.unwrap();
+    assert!(incoming
+        .iter()
+        .all(|e| e.kind != EdgeKind::DependsOn || nodes.iter().any(|n| n.id == e.source)));
+
+    // A source added later is linked on sync.
+    fs::create_dir_all(project.join("lib")).unwrap();
+    fs::write(project.join("lib/shared.h"), "int shared();\n").unwrap();
+    ts.sync().await.unwrap();
+    let (including, _) = ts.build_targets_for_file("lib/shared.h").await.unwrap();
+    assert_eq!(names(&including), vec!["//tools:tool"]);
+}
@@ -555,3 +555,33 @@ fn test_c_language_name() {
     let extractor = CExtractor;
     assert_eq!(extractor.language_name(), "C");
 }
+
+#[test]
+fn test_c_type_reference_refs() {
+    let source = r#"struct Service {
+    Repo *repo;
+    int count;
+};
+
+Account *find(UserId id, size_t limit) {
+    return 0;
+}
+"#;
+    let extractor = CExtractor;
+    let result = extractor.extract("service.c", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    let names = |kind: EdgeKind| -> Vec<String> {
+        result
+            .unresolved_refs
+            .iter()
+            .filter(|r| r.reference_kind == kind)
+            .map(|r| r.reference_name.clone())
+            .collect()
+    };
+    let receives = names(EdgeKind::Receives);
+    let returns = names(EdgeKind::Returns);
+    let type_of = names(EdgeKind::TypeOf);
+    assert_eq!(receives, vec!["UserId"]);
+    assert_eq!(returns, vec!["Account"]);
+    assert_eq!(type_of, vec!["Repo"]);
+}
@@ -0,0 +1,45 @@
+//! Lookups shared by the extractor tests.
+//!
+//! Each test binary uses only some of these.
+#![allow(dead_code)]
+
+use tokensave::types::*;
+
+/// Returns the first node named `name`.
+pub fn node<'a>(result: &'a ExtractionResult, name: &str) -> &'a Node {
+    result
+        .nodes
+        .iter()
+        .find(|n| n.name == name)
+        .unwrap_or_else(|| panic!("no node {name}"))
+}
+
+/// Returns the name and kind of every unresolved ref from the node named
+/// `from`, in source order.
+pub fn refs(result: &ExtractionResult, from: &str) -> Vec<(String, EdgeKind)> {
+    let id = &node(result, from).id;
+    result
+        .unresolved_refs
+        .iter()
+        .filter(|r| &r.from_node_id == id)
+        .map(|r| (r.reference_name.clone(), r.reference_kind.clone()))
+        .collect()
+}
+
+/// Pairs each of `names` with `kind`, to compare against [`refs`].
+pub fn named(kind: EdgeKind, names: &[&str]) -> Vec<(String, EdgeKind)> {
+    names
+        .iter()
+        .map(|n| (n.to_string(), kind.clone()))
+        .collect()
+}
+
+/// Returns the unresolved refs of `kind` as `(name, line)`, in source order.
+pub fn refs_of_kind(result: &ExtractionResult, kind: EdgeKind) -> Vec<(&str, u32)> {
+    result
+        .unresolved_refs
+        .iter()
+        .filter(|r| r.reference_kind == kind)
+        .map(|r| (r.reference_name.as_str(), r.line))
+        .collect()
+}
@@ -0,0 +1,229 @@
+mod common;
+
+use common::refs_of_kind;
+use tempfile::TempDir;
+use tokensave::extraction::{AstroExtractor, LanguageExtractor, SvelteExtractor, VueExtractor};
+use tokensave::tokensave::TokenSave;
+use tokensave::types::*;
+
+const VUE: &str = r#"<template>
+  <div class="list">
+    <!-- <LegacyRow @click="old" /> -->
+    <user-card v-for="u in users" :user="u" @select="pick(u)" />
+    <button @click.prevent="save">Save</button>
+    <button @click="count++">Bump</button>
+    <template v-if="open"><Modal /></template>
+  </div>
+</template>
+
+<script lang="ts">
+import UserCard from './UserCard.vue'
+
+export default {
+  components: { UserCard },
+  methods: {
+    pick(u) { this.selected = u },
+    save() { persist(this.selected) },
+  },
+}
+</script>
+
+<style scoped>
+.list > div { color: red }
+</style>
+"#;
+
+const SVELTE: &str = r#"<script context="module">
+  export const prerender = true;
+</script>
+
+<script>
+  import Counter from './Counter.svelte';
+  let count = 0;
+  function increment() {
+    count += 1;
+  }
+</script>
+
+<svelte:head><title>Home</title></svelte:head>
+<Counter on:change={increment} />
+<button onclick={() => increment()}>+</button>
+<button on:click={increment}>{count > 1 ? 'many' : 'one'}</button>
+"#;
+
+const ASTRO: &str = r#"---
+import Layout from '../layouts/Layout.astro';
+const posts = await fetchPosts();
+function title(post: Post): string {
+  return post.title;
+}
+---
+<Layout title="Blog">
+  {posts.map((p) => <Card heading={title(p)} />)}
+</Layout>
+<script>
+  document.querySelector('h1')?.addEventListener('click', () => {});
+</script>
+"#;
+
+fn component(result: &ExtractionResult) -> &Node {
+    result
+        .nodes
+        .iter()
+        .find(|n| n.kind == NodeKind::Component)
+        .unwrap()
+}
+
+#[test]
+fn test_vue_component_contains_script_symbols() {
+    let result = VueExtractor.extract("src/components/UserList.vue", VUE);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+
+    let file = result
+        .nodes
+        .iter()
+        .find(|n| n.kind == NodeKind::File)
+        .unwrap();
+    let component = component(&result);
+    assert_eq!(component.name, "UserList");
+    assert_eq!(
+        component.qualified_name,
+        "src/components/UserList.vue::UserList"
+    );
+    assert!(result
+        .edges
+        .iter()
+        .any(|e| e.kind == EdgeKind::Contains && e.source == file.id && e.target == component.id));
+
+    // The import keeps its line in the file, and moved under the component.
+    let import = result
+        .nodes
+        .iter()
+        .find(|n| n.kind == NodeKind::Use)
+        .unwrap();
+    assert_eq!(import.start_line, 11);
+    assert!(result.edges.iter().any(|e| e.kind == EdgeKind::Contains
+        && e.source == component.id
+        && e.target == import.id));
+
+    // Options API methods become methods of the component.
+    let save = result
+        .nodes
+        .iter()
+        .find(|n| n.kind == NodeKind::Method && n.name == "save")
+        .unwrap();
+    assert_eq!(save.start_line, 17);
+    assert_eq!(
+        save.qualified_name,
+        "src/components/UserList.vue::UserList::save"
+    );
+    assert!(result
+        .nodes
+        .iter()
+        .any(|n| n.kind == NodeKind::Method && n.name == "pick"));
+}
+
+#[test]
+fn test_vue_template_components_and_handlers() {
+    let result = VueExtractor.extract("UserList.vue", VUE);
+    let component_id = component(&result).id.clone();
+
+    // The script's import is a use too, from line 11 on.
+    assert_eq!(
+        refs_of_kind(&result, EdgeKind::Uses)
+            .into_iter()
+            .filter(|(_, line)| *line < 9)
+            .collect::<Vec<_>>(),
+        vec![("UserCard", 3), ("Modal", 6)]
+    );
+    assert_eq!(
+        refs_of_kind(&result, EdgeKind::Calls),
+        vec![("pick", 3), ("save", 4)]
+    );
+    assert!(result
+        .unresolved_refs
+        .iter()
+        .filter(|r| r.line < 9)
+        .all(|r| r.from_node_id == component_id));
+}
+
+#[test]
+fn test_svelte_scripts_and_markup() {
+    let result = SvelteExtractor.extract("src/routes/Home.svelte", SVELTE);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    let component = component(&result);
+    assert_eq!(component.name, "Home");
+
+    let increment = result.nodes.iter().find(|n| n.name == "increment").unwrap();
+    assert_eq!(increment.kind, NodeKind::Function);
+    assert_eq!((increment.start_line, increment.start_column), (7, 2));
+    assert!(result
+        .nodes
+        .iter()
+        .any(|n| n.name == "prerender" && n.start_line == 1));
+
+    let uses: Vec<_> = refs_of_kind(&result, EdgeKind::Uses)
+        .into_iter()
+        .filter(|(name, _)| *name == "Counter")
+        .collect();
+    assert_eq!(uses, vec![("Counter", 13)]);
+    assert_eq!(
+        refs_of_kind(&result, EdgeKind::Calls)
+            .into_iter()
+            .filter(|(_, line)| *line > 11)
+            .collect::<Vec<_>>(),
+        vec![("increment", 13), ("increment", 15)]
+    );
+}
+
+#[test]
+fn test_astro_frontmatter_and_markup() {
+    let result = AstroExtractor.extract("src/pages/blog.astro", ASTRO);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    assert_eq!(component(&result).name, "blog");
+
+    let title = result.nodes.iter().find(|n| n.name == "title").unwrap();
+    assert_eq!((title.start_line, title.end_line), (3, 5));
+    let uses: Vec<_> = refs_of_kind(&result, EdgeKind::Uses)
+        .into_iter()
+        .filter(|(_, line)| *line > 6)
+        .collect();
+    assert_eq!(uses, vec![("Layout", 7), ("Card", 8)]);
+}
+
+#[tokio::test]
+async fn test_template_usages_resolve_to_components() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+    std::fs::create_dir_all(project.join("src")).unwrap();
+    std::fs::write(project.join("src/UserList.vue"), VUE).unwrap();
+    std::fs::write(
+        project.join("src/UserCard.vue"),
+        "<template><div>{{ user.name }}</div></template>\n<script setup lang=\"ts\">\ndefineProps(['user'])\n</script>\n",
+    )
+    .unwrap();
+
+    let ts = TokenSave::init(project).await.unwrap();
+    ts.index_all().await.unwrap();
+    let nodes = ts.get_nodes_by_file("src/UserCard.vue").await.unwrap();
+    let user_card = nodes
+        .iter()
+        .find(|n| n.kind == NodeKind::Component)
+        .unwrap();
+    let list = ts.get_nodes_by_file("src/UserList.vue").await.unwrap();
+    let save = list.iter().find(|n| n.name == "save").unwrap();
+    let user_list = list.iter().find(|n| n.kind == NodeKind::Component).unwrap();
+
+    let incoming = ts.get_incoming_edges(&user_card.id).await.unwrap();
+    assert!(
+        incoming
+            .iter()
+            .any(|e| e.kind == EdgeKind::Uses && e.source == user_list.id),
+        "{incoming:?}"
+    );
+    let callers = ts.get_callers(&save.id, 1, false, 0.0).await.unwrap();
+    assert!(
+        callers.iter().any(|(n, _)| n.id == user_list.id),
+        "{callers:?}"
+    );
+}
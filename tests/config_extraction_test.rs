@@ -0,0 +1,313 @@
+mod common;
+
+use std::fs;
+
+use common::node;
+use tempfile::TempDir;
+use tokensave::extraction::{JsonExtractor, LanguageExtractor, TomlExtractor, YamlExtractor};
+use tokensave::tokensave::TokenSave;
+use tokensave::types::*;
+
+const WORKFLOW: &str = r#"name: CI
+on:
+  push:
+    branches: [main]   # default branch
+
+jobs:
+  build:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+      - name: Build
+        run: |
+          cargo build --release
+          echo done # comment
+      - id: test
+        run: cargo test
+  lint:
+    needs: build
+    steps:
+    - run: cargo clippy
+  deploy:
+    needs: [build, lint]
+    uses: ./.github/workflows/deploy.yml@main
+"#;
+
+const COMPOSE: &str = r#"services:
+  web:
+    build: .
+    depends_on:
+      db:
+        condition: service_healthy
+      cache:
+        condition: service_started
+  worker:
+    image: "app:latest"
+    links: ["db:database"]
+  db:
+    image: postgres:16
+  cache:
+    image: redis
+"#;
+
+const DEPLOYMENT: &str = r#"apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+spec:
+  template:
+    spec:
+      serviceAccountName: web-sa
+      containers:
+        - name: web
+          image: nginx:1.25
+          envFrom:
+            - configMapRef:
+                name: app-config
+---
+apiVersion: v1
+kind: ServiceAccount
+metadata:
+  name: web-sa
+"#;
+
+const CARGO: &str = r#"[package]
+name = "demo"
+version = "0.1.0"
+
+[dependencies]
+tokio = { version = "1", features = ["full"] }
+serde = "1"
+
+[[bin]]
+name = "demo"
+path = "src/main.rs"
+"#;
+
+fn depends_on(result: &ExtractionResult, from: &str) -> Vec<String> {
+    let id = &node(result, from).id;
+    let mut targets: Vec<String> = result
+        .edges
+        .iter()
+        .filter(|e| e.kind == EdgeKind::DependsOn && &e.source == id)
+        .map(|e| {
+            let target = result.nodes.iter().find(|n| n.id == e.target).unwrap();
+            target.name.clone()
+        })
+        .collect();
+    targets.sort();
+    targets
+}
+
+#[test]
+fn test_yaml_key_paths_with_line_ranges() {
+    let result = YamlExtractor.extract("config/ci.yml", WORKFLOW);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+
+    let branches = node(&result, "on.push.branches");
+    assert_eq!(branches.kind, NodeKind::ConfigKey);
+    assert_eq!(branches.qualified_name, "config/ci.yml::on.push.branches");
+    assert_eq!((branches.start_line, branches.end_line), (3, 3));
+    assert_eq!(branches.signature.as_deref(), Some("branches: [main]"));
+    let run = node(&result, "jobs.build.steps[1].run");
+    assert_eq!((run.start_line, run.end_line), (11, 13));
+    let push = node(&result, "on.push");
+    assert!(result
+        .edges
+        .iter()
+        .any(|e| e.kind == EdgeKind::Contains && e.source == push.id && e.target == branches.id));
+    // Outside a workflow directory, jobs are plain keys.
+    assert!(result
+        .nodes
+        .iter()
+        .all(|n| n.kind == NodeKind::ConfigKey || n.kind == NodeKind::File));
+}
+
+#[test]
+fn test_github_workflow_jobs_and_steps() {
+    let result = YamlExtractor.extract(".github/workflows/ci.yml", WORKFLOW);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+
+    let build = node(&result, "build");
+    assert_eq!(build.kind, NodeKind::WorkflowJob);
+    assert_eq!(build.qualified_name, ".github/workflows/ci.yml::jobs.build");
+    let steps: Vec<(&str, &str)> = result
+        .nodes
+        .iter()
+        .filter(|n| n.kind == NodeKind::WorkflowStep)
+        .map(|n| {
+            (
+                n.name.as_str(),
+                n.qualified_name.rsplit("::").next().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        steps,
+        vec![
+            ("actions/checkout@v4", "jobs.build.steps[0]"),
+            ("Build", "jobs.build.steps[1]"),
+            ("test", "jobs.build.steps[2]"),
+            ("steps[0]", "jobs.lint.steps[0]"),
+        ]
+    );
+
+    assert_eq!(depends_on(&result, "lint"), vec!["build"]);
+    assert_eq!(depends_on(&result, "deploy"), vec!["build", "lint"]);
+    let reusable = generate_node_id(
+        ".github/workflows/deploy.yml",
+        &NodeKind::File,
+        ".github/workflows/deploy.yml",
+        0,
+    );
+    assert!(result.edges.iter().any(|e| e.kind == EdgeKind::Uses
+        && e.source == node(&result, "deploy").id
+        && e.target == reusable));
+}
+
+#[test]
+fn test_compose_services_depend_on_each_other() {
+    let result = YamlExtractor.extract("deploy/stack.yaml", COMPOSE);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    let services: Vec<&str> = result
+        .nodes
+        .iter()
+        .filter(|n| n.kind == NodeKind::ComposeService)
+        .map(|n| n.name.as_str())
+        .collect();
+    assert_eq!(services, vec!["web", "worker", "db", "cache"]);
+    assert_eq!(depends_on(&result, "web"), vec!["cache", "db"]);
+    assert_eq!(depends_on(&result, "worker"), vec!["db"]);
+    assert!(result
+        .nodes
+        .iter()
+        .any(|n| n.name == "services.web.depends_on.db.condition"));
+}
+
+#[test]
+fn test_kubernetes_resources_reference_by_kind_and_name() {
+    let result = YamlExtractor.extract("k8s/web.yaml", DEPLOYMENT);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    let deployment = node(&result, "Deployment/web");
+    assert_eq!(deployment.kind, NodeKind::K8sResource);
+    assert_eq!(deployment.qualified_name, "k8s/web.yaml::Deployment/web");
+    assert_eq!((deployment.start_line, deployment.end_line), (0, 13));
+    assert_eq!(node(&result, "ServiceAccount/web-sa").start_line, 15);
+    // Keys of a multi-document stream start with the document index.
+    assert!(result.nodes.iter().any(|n| n.name == "[1].metadata.name"));
+
+    let refs: Vec<(&str, u32)> = result
+        .unresolved_refs
+        .iter()
+        .map(|r| {
+            assert_eq!(r.from_node_id, deployment.id);
+            (r.reference_name.as_str(), r.line)
+        })
+        .collect();
+    assert_eq!(
+        refs,
+        vec![("ServiceAccount/web-sa", 7), ("ConfigMap/app-config", 13)]
+    );
+}
+
+#[test]
+fn test_toml_tables_and_arrays_of_tables() {
+    let result = TomlExtractor.extract("Cargo.toml", CARGO);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    let dependencies = node(&result, "dependencies");
+    assert_eq!((dependencies.start_line, dependencies.end_line), (4, 6));
+    let tokio = node(&result, "dependencies.tokio");
+    assert_eq!(tokio.start_line, 5);
+    assert!(result.edges.iter().any(|e| e.kind == EdgeKind::Contains
+        && e.source == dependencies.id
+        && e.target == tokio.id));
+    assert!(result
+        .nodes
+        .iter()
+        .any(|n| n.name == "dependencies.tokio.features"));
+    let bin = node(&result, "bin[0].path");
+    assert_eq!(bin.start_line, 10);
+
+    let result = TomlExtractor.extract("bad.toml", "[package\nname = 1\n");
+    assert_eq!(result.errors.len(), 1);
+    assert_eq!(result.nodes.len(), 1, "only the file node");
+}
+
+#[test]
+fn test_json_with_comments_and_lock_files() {
+    let source = "{\n  // compiler settings\n  \"compilerOptions\": {\n    \"paths\": { \"@/*\": [\"src/*\"] },\n  },\n  \"references\": [{ \"path\": \"./core\" }],\n}\n";
+    let result = JsonExtractor.extract("tsconfig.json", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    let paths = node(&result, "compilerOptions.paths");
+    assert_eq!((paths.start_line, paths.end_line), (3, 3));
+    assert_eq!(node(&result, "references[0].path").start_line, 5);
+    assert!(result
+        .nodes
+        .iter()
+        .all(|n| n.name != "references[0].path[0]"));
+
+    let result = JsonExtractor.extract("web/package-lock.json", "{\"lockfileVersion\": 3}");
+    assert_eq!(result.nodes.len(), 1);
+    let result = JsonExtractor.extract("broken.json", "{\"a\": [1, 2}");
+    assert_eq!(result.errors.len(), 1);
+}
+
+#[tokio::test]
+async fn test_config_keys_are_searchable_and_resolve_only_among_configs() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+    fs::create_dir_all(project.join("src")).unwrap();
+    fs::create_dir_all(project.join("k8s")).unwrap();
+    fs::write(project.join("Cargo.toml"), CARGO).unwrap();
+    fs::write(
+        project.join("src/main.rs"),
+        "fn main() {\n    let version = env!(\"CARGO_PKG_VERSION\");\n    println!(\"{}\", version);\n}\n",
+    )
+    .unwrap();
+    fs::write(project.join("k8s/web.yaml"), DEPLOYMENT).unwrap();
+    fs::write(
+        project.join("k8s/config.json"),
+        r#"{"apiVersion": "v1", "kind": "ConfigMap", "metadata": {"name": "app-config"}, "data": {}}"#,
+    )
+    .unwrap();
+
+    let ts = TokenSave::init(project).await.unwrap();
+    ts.index_all().await.unwrap();
+
+    let results = ts.search("dependencies.tokio", 10).await.unwrap();
+    assert!(
+        results
+            .iter()
+            .any(|r| r.node.qualified_name == "Cargo.toml::dependencies.tokio"),
+        "{:?}",
+        results.iter().map(|r| &r.node.name).collect::<Vec<_>>()
+    );
+
+    // The Kubernetes references link across files and formats.
+    let config_map = ts.get_nodes_by_file("k8s/config.json").await.unwrap();
+    let config_map = config_map
+        .iter()
+        .find(|n| n.kind == NodeKind::K8sResource)
+        .unwrap();
+    let incoming = ts.get_incoming_edges(&config_map.id).await.unwrap();
+    assert!(
+        incoming.iter().any(|e| e.kind == EdgeKind::Uses),
+        "{incoming:?}"
+    );
+
+    // Re-indexing the manifest keeps its package.
+    fs::write(project.join("Cargo.toml"), format!("# edited\n{CARGO}")).unwrap();
+    ts.sync().await.unwrap();
+    let packages = ts
+        .db()
+        .get_nodes_by_kind(NodeKind::ManifestPackage)
+        .await
+        .unwrap();
+    assert_eq!(packages.len(), 1);
+    assert!(ts
+        .get_nodes_by_file("Cargo.toml")
+        .await
+        .unwrap()
+        .iter()
+        .any(|n| n.name == "package.version" && n.start_line == 3));
+}
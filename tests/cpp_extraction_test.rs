@@ -813,3 +813,30 @@ class [[nodiscard]] Result {
         .collect();
     assert!(annot_refs.len() >= 3, "expected at least 3 Annotates refs");
 }
+
+#[test]
+fn test_cpp_type_reference_refs() {
+    let source = r#"class Service {
+    ns::Repo* repo;
+public:
+    std::vector<Account> find(const UserId& id, int limit);
+};
+"#;
+    let extractor = CppExtractor;
+    let result = extractor.extract("service.cpp", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    let names = |kind: EdgeKind| -> Vec<String> {
+        result
+            .unresolved_refs
+            .iter()
+            .filter(|r| r.reference_kind == kind)
+            .map(|r| r.reference_name.clone())
+            .collect()
+    };
+    let receives = names(EdgeKind::Receives);
+    let returns = names(EdgeKind::Returns);
+    let type_of = names(EdgeKind::TypeOf);
+    assert_eq!(receives, vec!["UserId"]);
+    assert_eq!(returns, vec!["Account"]);
+    assert_eq!(type_of, vec!["Repo"]);
+}
@@ -603,3 +603,65 @@ namespace MyApp
         methods[0].qualified_name
     );
 }
+
+#[test]
+fn test_cs_type_reference_refs() {
+    let source = r#"public class Service
+{
+    private Repo<User> repo;
+    public Models.Account Owner { get; set; }
+
+    public Account Find(UserId id, int limit)
+    {
+        return null;
+    }
+}
+"#;
+    let extractor = CSharpExtractor;
+    let result = extractor.extract("Service.cs", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    let names = |kind: EdgeKind| -> Vec<String> {
+        result
+            .unresolved_refs
+            .iter()
+            .filter(|r| r.reference_kind == kind)
+            .map(|r| r.reference_name.clone())
+            .collect()
+    };
+    let receives = names(EdgeKind::Receives);
+    let returns = names(EdgeKind::Returns);
+    let type_of = names(EdgeKind::TypeOf);
+    assert_eq!(receives, vec!["UserId"]);
+    assert_eq!(returns, vec!["Account"]);
+    assert_eq!(type_of, vec!["Repo", "User", "Models.Account"]);
+}
+
+#[test]
+fn test_cs_call_receiver_types() {
+    let source = r#"class Server {
+  private Database db;
+  public Store Store { get; set; }
+  void Run(Client client) {
+    Conn conn = Open();
+    db.GetNode(1);
+    Store.Put(1);
+    client.Send(2);
+    conn.Close();
+  }
+}
+"#;
+    let extractor = CSharpExtractor;
+    let result = extractor.extract("Server.cs", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    let receiver = |name: &str| -> Option<String> {
+        result
+            .unresolved_refs
+            .iter()
+            .find(|r| r.reference_kind == EdgeKind::Calls && r.reference_name == name)
+            .and_then(|r| r.receiver_type.clone())
+    };
+    assert_eq!(receiver("db.GetNode").as_deref(), Some("Database"));
+    assert_eq!(receiver("Store.Put").as_deref(), Some("Store"));
+    assert_eq!(receiver("client.Send").as_deref(), Some("Client"));
+    assert_eq!(receiver("conn.Close").as_deref(), Some("Conn"));
+}
@@ -46,6 +46,7 @@ fn sample_edge(source: &str, target: &str, kind: EdgeKind) -> Edge {
         target: target.to_string(),
         kind,
         line: Some(5),
+        provenance: None,
     }
 }
 
@@ -223,6 +224,51 @@ async fn test_insert_all_bulk() {
     assert_eq!(all_files.len(), 2);
 }
 
+// -------------------------------------------------------------------------
+// edge provenance
+// -------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_edge_provenance_roundtrip() {
+    let (db, _dir) = setup_db().await;
+
+    let nodes: Vec<Node> = (0..4)
+        .map(|i| sample_node(&format!("pv-{i}"), &format!("f{i}"), "src/lib.rs"))
+        .collect();
+    db.insert_nodes(&nodes).await.expect("insert_nodes failed");
+
+    let with_provenance = |source: &str, target: &str, strategy, confidence| Edge {
+        provenance: Some(EdgeProvenance {
+            strategy,
+            confidence,
+        }),
+        ..sample_edge(source, target, EdgeKind::Calls)
+    };
+    let single = with_provenance("pv-0", "pv-1", ResolutionStrategy::SameFile, 0.9);
+    let batch = with_provenance(
+        "pv-1",
+        "pv-2",
+        ResolutionStrategy::Ambiguous { candidates: 3 },
+        0.7,
+    );
+    let bulk = with_provenance("pv-2", "pv-3", ResolutionStrategy::ImportScoped, 0.95);
+    let exact = sample_edge("pv-3", "pv-0", EdgeKind::Uses);
+
+    db.insert_edge(&single).await.expect("insert_edge failed");
+    db.insert_edges(std::slice::from_ref(&batch))
+        .await
+        .expect("insert_edges failed");
+    db.insert_all(&[], &[bulk.clone(), exact.clone()], &[])
+        .await
+        .expect("insert_all failed");
+
+    let mut all = db.get_all_edges().await.expect("get_all_edges failed");
+    all.sort_by(|a, b| a.source.cmp(&b.source));
+    assert_eq!(all, vec![single, batch, bulk, exact]);
+    assert!((all[1].confidence() - 0.7).abs() < f64::EPSILON);
+    assert!((all[3].confidence() - 1.0).abs() < f64::EPSILON);
+}
+
 // -------------------------------------------------------------------------
 // delete_edges_by_source
 // -------------------------------------------------------------------------
@@ -533,12 +579,14 @@ async fn test_get_inheritance_depth() {
             target: "ih-p".to_string(),
             kind: EdgeKind::Extends,
             line: None,
+            provenance: None,
         },
         Edge {
             source: "ih-p".to_string(),
             target: "ih-gp".to_string(),
             kind: EdgeKind::Extends,
             line: None,
+            provenance: None,
         },
     ];
     db.insert_edges(&edges).await.expect("insert_edges failed");
@@ -838,24 +886,28 @@ async fn test_get_god_classes() {
             target: "gc-m1".to_string(),
             kind: EdgeKind::Contains,
             line: None,
+            provenance: None,
         },
         Edge {
             source: "gc-class".to_string(),
             target: "gc-m2".to_string(),
             kind: EdgeKind::Contains,
             line: None,
+            provenance: None,
         },
         Edge {
             source: "gc-class".to_string(),
             target: "gc-f1".to_string(),
             kind: EdgeKind::Contains,
             line: None,
+            provenance: None,
         },
         Edge {
             source: "gc-class".to_string(),
             target: "gc-ctor".to_string(),
             kind: EdgeKind::Contains,
             line: None,
+            provenance: None,
         },
     ];
     db.insert_edges(&edges).await.expect("insert_edges failed");
@@ -1224,6 +1276,10 @@ async fn test_insert_unresolved_refs_batch() {
             line: 10,
             column: 5,
             file_path: "src/lib.rs".to_string(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
         },
         UnresolvedRef {
             from_node_id: "ur-node".to_string(),
@@ -1232,6 +1288,10 @@ async fn test_insert_unresolved_refs_batch() {
             line: 15,
             column: 10,
             file_path: "src/lib.rs".to_string(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
         },
         UnresolvedRef {
             from_node_id: "ur-node".to_string(),
@@ -1240,6 +1300,10 @@ async fn test_insert_unresolved_refs_batch() {
             line: 20,
             column: 0,
             file_path: "src/lib.rs".to_string(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
         },
     ];
 
@@ -1505,36 +1569,42 @@ async fn test_get_god_classes_multiple_classes() {
             target: "gcm-m1".into(),
             kind: EdgeKind::Contains,
             line: None,
+            provenance: None,
         },
         Edge {
             source: "gcm-big".into(),
             target: "gcm-m2".into(),
             kind: EdgeKind::Contains,
             line: None,
+            provenance: None,
         },
         Edge {
             source: "gcm-big".into(),
             target: "gcm-m3".into(),
             kind: EdgeKind::Contains,
             line: None,
+            provenance: None,
         },
         Edge {
             source: "gcm-big".into(),
             target: "gcm-f1".into(),
             kind: EdgeKind::Contains,
             line: None,
+            provenance: None,
         },
         Edge {
             source: "gcm-big".into(),
             target: "gcm-f2".into(),
             kind: EdgeKind::Contains,
             line: None,
+            provenance: None,
         },
         Edge {
             source: "gcm-small".into(),
             target: "gcm-sm1".into(),
             kind: EdgeKind::Contains,
             line: None,
+            provenance: None,
         },
     ];
     db.insert_edges(&edges).await.expect("insert_edges failed");
@@ -1578,6 +1648,7 @@ async fn test_edge_line_none_and_some() {
         target: "eln-2".to_string(),
         kind: EdgeKind::Calls,
         line: None,
+        provenance: None,
     };
     db.insert_edge(&edge_no_line)
         .await
@@ -1589,6 +1660,7 @@ async fn test_edge_line_none_and_some() {
         target: "eln-2".to_string(),
         kind: EdgeKind::Calls,
         line: Some(42),
+        provenance: None,
     };
     db.insert_edge(&edge_with_line)
         .await
@@ -1619,6 +1691,7 @@ async fn test_edge_unique_constraint_dedup() {
         target: "euc-2".to_string(),
         kind: EdgeKind::Calls,
         line: Some(10),
+        provenance: None,
     };
     db.insert_edge(&edge).await.expect("insert_edge failed");
     db.insert_edge(&edge)
@@ -1779,6 +1852,7 @@ async fn test_batch_incoming_call_counts() {
             target: tgt.to_string(),
             kind: EdgeKind::Calls,
             line: None,
+            provenance: None,
         })
         .await
         .unwrap();
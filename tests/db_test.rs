@@ -101,6 +101,7 @@ async fn test_insert_and_get_edge() {
         target: "node-b".to_string(),
         kind: EdgeKind::Calls,
         line: Some(5),
+        provenance: None,
     };
     db.insert_edge(&edge).await.expect("failed to insert edge");
 
@@ -244,6 +245,7 @@ async fn test_delete_nodes_by_file() {
         target: "del-2".to_string(),
         kind: EdgeKind::Calls,
         line: None,
+        provenance: None,
     };
     db.insert_edge(&edge).await.expect("failed to insert edge");
 
@@ -283,6 +285,10 @@ async fn test_unresolved_refs() {
         line: 10,
         column: 5,
         file_path: "src/lib.rs".to_string(),
+        receiver_type: None,
+        abi: None,
+        http_method: None,
+        sql: false,
     };
 
     db.insert_unresolved_ref(&uref)
@@ -0,0 +1,238 @@
+use std::fs;
+
+use tempfile::TempDir;
+use tokensave::extraction::domain::DomainRules;
+use tokensave::extraction::{LanguageExtractor, PythonExtractor, RustExtractor};
+use tokensave::types::*;
+
+const RULES: &str = r#"
+[layer]
+name = "elisp-primitive"
+files = ["src/**/*.rs"]
+language = "rust"
+
+[[rule]]
+name = "interp-define"
+pattern = '''
+(call_expression
+  function: (field_expression field: (field_identifier) @method (#eq? @method "define"))
+  arguments: (arguments . (string_literal) @NAME . (_) @VALUE))
+'''
+name_arg = "$NAME"
+target_arg = "$VALUE"
+
+[[rule]]
+name = "match-dispatch"
+pattern = '(match_arm pattern: (match_pattern (string_literal) @NAME))'
+name_arg = "$NAME"
+emit_edges = [{ kind = "dispatches", from = "enclosing_function", to = "$NAME" }]
+
+[[rule]]
+name = "stub-array"
+pattern = '''
+(const_item
+  name: (identifier) @VAR
+  value: (reference_expression value: (array_expression (string_literal) @NAME)))
+'''
+name_arg = "$NAME"
+var_filter = ["STUBS"]
+emit_edges = [{ kind = "registers", from = "enclosing_file", to = "$NAME" }]
+"#;
+
+const SOURCE: &str = r#"const STUBS: &[&str] = &["car", "cdr"];
+const OTHER: &[&str] = &["ignored"];
+
+fn install(interp: &mut Interp) {
+    interp.define("transpose-regions", transpose_regions);
+    interp.define("car", builtins::car);
+}
+
+fn dispatch(name: &str) {
+    match name {
+        "car" => {}
+        _ => {}
+    }
+}
+
+fn transpose_regions() {}
+"#;
+
+fn extract(rules: &DomainRules, file_path: &str, source: &str) -> ExtractionResult {
+    let (mut result, tree) = RustExtractor.extract_with_tree(file_path, source);
+    rules.apply(file_path, source, tree.as_ref(), &mut result);
+    result
+}
+
+fn node<'a>(result: &'a ExtractionResult, kind: NodeKind, name: &str) -> &'a Node {
+    result
+        .nodes
+        .iter()
+        .find(|n| n.kind == kind && n.name == name)
+        .unwrap_or_else(|| panic!("no {} named {name}", kind.as_str()))
+}
+
+fn has_edge(result: &ExtractionResult, source: &Node, target: &Node, kind: EdgeKind) -> bool {
+    result
+        .edges
+        .iter()
+        .any(|e| e.source == source.id && e.target == target.id && e.kind == kind)
+}
+
+#[test]
+fn test_call_rule_registers_symbol_and_targets_handler() {
+    let rules = DomainRules::parse(RULES).unwrap();
+    let result = extract(&rules, "src/interp.rs", SOURCE);
+
+    let symbol = node(&result, NodeKind::DomainSymbol, "transpose-regions");
+    assert_eq!(symbol.qualified_name, "elisp-primitive::transpose-regions");
+    assert_eq!(symbol.start_line, 4);
+    assert_eq!(
+        symbol.signature.as_deref(),
+        Some("interp.define(\"transpose-regions\", transpose_regions);")
+    );
+
+    let install = node(&result, NodeKind::Function, "install");
+    assert!(has_edge(&result, install, symbol, EdgeKind::Registers));
+    let file = node(&result, NodeKind::File, "src/interp.rs");
+    assert!(has_edge(&result, file, symbol, EdgeKind::Contains));
+
+    // The handler is resolved later, like any other reference.
+    let targets: Vec<&str> = result
+        .unresolved_refs
+        .iter()
+        .filter(|r| r.from_node_id == symbol.id && r.reference_kind == EdgeKind::Implements)
+        .map(|r| r.reference_name.as_str())
+        .collect();
+    assert_eq!(targets, vec!["transpose_regions"]);
+    assert!(result
+        .unresolved_refs
+        .iter()
+        .any(|r| r.reference_kind == EdgeKind::Implements && r.reference_name == "builtins::car"));
+}
+
+#[test]
+fn test_symbol_registered_twice_in_a_file_is_one_node() {
+    let rules = DomainRules::parse(RULES).unwrap();
+    let result = extract(&rules, "src/interp.rs", SOURCE);
+
+    let cars: Vec<&Node> = result
+        .nodes
+        .iter()
+        .filter(|n| n.kind == NodeKind::DomainSymbol && n.name == "car")
+        .collect();
+    assert_eq!(cars.len(), 1);
+    let car = cars[0];
+    assert_eq!(car.start_line, 0, "positioned at its first registration");
+
+    let install = node(&result, NodeKind::Function, "install");
+    let dispatch = node(&result, NodeKind::Function, "dispatch");
+    let file = node(&result, NodeKind::File, "src/interp.rs");
+    assert!(has_edge(&result, install, car, EdgeKind::Registers));
+    assert!(has_edge(&result, dispatch, car, EdgeKind::Dispatches));
+    assert!(has_edge(&result, file, car, EdgeKind::Registers));
+}
+
+#[test]
+fn test_test_cases_are_enclosing_functions() {
+    let rules = DomainRules::parse(RULES).unwrap();
+    let source = "#[test]\nfn dispatches_car() {\n    match \"car\" {\n        \"car\" => {}\n        _ => {}\n    }\n}\n";
+    let result = extract(&rules, "src/interp.rs", source);
+
+    let test = node(&result, NodeKind::TestCase, "dispatches_car");
+    let car = node(&result, NodeKind::DomainSymbol, "car");
+    assert!(has_edge(&result, test, car, EdgeKind::Dispatches));
+}
+
+#[test]
+fn test_var_filter_limits_array_rule() {
+    let rules = DomainRules::parse(RULES).unwrap();
+    let result = extract(&rules, "src/interp.rs", SOURCE);
+
+    assert!(result.nodes.iter().any(|n| n.name == "cdr"));
+    assert!(!result.nodes.iter().any(|n| n.name == "ignored"));
+}
+
+#[test]
+fn test_rules_skip_files_outside_layer() {
+    let rules = DomainRules::parse(RULES).unwrap();
+    assert!(rules.applies_to("src/interp.rs"));
+    assert!(!rules.applies_to("benches/interp.rs"));
+
+    let result = extract(&rules, "benches/interp.rs", SOURCE);
+    assert!(!result
+        .nodes
+        .iter()
+        .any(|n| n.kind == NodeKind::DomainSymbol));
+}
+
+#[test]
+fn test_rules_skip_files_in_other_languages() {
+    let rules = DomainRules::parse(
+        "[layer]\nname = \"l\"\nlanguage = \"rust\"\n\n[[rule]]\nname = \"r\"\npattern = '(string_literal) @NAME'\nname_arg = \"$NAME\"\n",
+    )
+    .unwrap();
+    let source = "NAMES = [\"car\"]\n";
+    let (mut result, tree) = PythonExtractor.extract_with_tree("src/names.py", source);
+    rules.apply("src/names.py", source, tree.as_ref(), &mut result);
+    assert!(!result
+        .nodes
+        .iter()
+        .any(|n| n.kind == NodeKind::DomainSymbol));
+}
+
+#[test]
+fn test_invalid_rules_are_rejected() {
+    let base = "[layer]\nname = \"l\"\nlanguage = \"rust\"\n\n[[rule]]\nname = \"r\"\n";
+    let cases = [
+        (
+            "pattern = '(string_literal) @NAME'\nname_arg = \"$OTHER\"\n",
+            "no capture @OTHER",
+        ),
+        (
+            "pattern = '(not_a_node) @NAME'\nname_arg = \"$NAME\"\n",
+            "invalid pattern",
+        ),
+        (
+            "pattern = '(string_literal) @NAME'\nname_arg = \"$NAME\"\npattern_lang = \"ast-grep\"\n",
+            "unsupported pattern_lang",
+        ),
+        (
+            "pattern = '(string_literal) @NAME'\nname_arg = \"$NAME\"\nemit_edges = [{ kind = \"owns\", from = \"enclosing_function\", to = \"$NAME\" }]\n",
+            "unknown edge kind",
+        ),
+        (
+            "pattern = '(string_literal) @NAME'\nname_arg = \"$NAME\"\nvar_filter = [\"X\"]\n",
+            "no capture @VAR",
+        ),
+    ];
+    for (rule, expected) in cases {
+        let Err(err) = DomainRules::parse(&format!("{base}{rule}")) else {
+            panic!("rule accepted: {rule}");
+        };
+        assert!(
+            err.to_string().contains(expected),
+            "expected '{expected}' in '{err}'"
+        );
+    }
+}
+
+#[test]
+fn test_load_reads_project_rules_file() {
+    let dir = TempDir::new().unwrap();
+    assert!(DomainRules::load(dir.path()).unwrap().is_none());
+
+    fs::create_dir_all(dir.path().join(".tokensave")).unwrap();
+    fs::write(dir.path().join(".tokensave/domain-symbols.toml"), RULES).unwrap();
+    let rules = DomainRules::load(dir.path()).unwrap().unwrap();
+    assert!(rules.applies_to("src/main.rs"));
+}
+
+#[test]
+fn test_rules_compile_again_from_their_source() {
+    let rules = DomainRules::parse(RULES).unwrap();
+    let copy = DomainRules::parse(rules.source()).unwrap();
+    let source = extract(&rules, "src/interp.rs", SOURCE);
+    let copied = extract(&copy, "src/interp.rs", SOURCE);
+    assert_eq!(source.nodes.len(), copied.nodes.len());
+    assert_eq!(source.edges.len(), copied.edges.len());
+}
@@ -0,0 +1,220 @@
+mod common;
+
+use std::fs;
+
+use common::refs_of_kind;
+use tempfile::TempDir;
+use tokensave::extraction::{
+    GoExtractor, JavaExtractor, LanguageExtractor, PythonExtractor, RustExtractor,
+};
+use tokensave::tokensave::TokenSave;
+use tokensave::types::*;
+
+/// The `(abi, name, line)` of each reference of `kind` that crosses a
+/// language boundary.
+fn abi_refs(result: &ExtractionResult, kind: EdgeKind) -> Vec<(&str, &str, u32)> {
+    result
+        .unresolved_refs
+        .iter()
+        .filter(|r| r.reference_kind == kind)
+        .filter_map(|r| Some((r.abi.as_deref()?, r.reference_name.as_str(), r.line)))
+        .collect()
+}
+
+#[test]
+fn test_rust_extern_blocks_and_exports() {
+    let source = r#"extern "C" {
+    fn compress(buf: *const u8, len: usize) -> i32;
+    #[link_name = "zlib_version"]
+    fn version() -> *const c_char;
+}
+
+/// Checksum for C callers.
+#[no_mangle]
+pub extern "C" fn checksum(buf: *const u8, len: usize) -> u32 {
+    0
+}
+
+#[pyfunction]
+fn encode(data: &[u8]) -> Vec<u8> {
+    unsafe { compress(data.as_ptr(), data.len()) };
+    Vec::new()
+}
+"#;
+    let result = RustExtractor.extract("src/lib.rs", source);
+    assert_eq!(
+        abi_refs(&result, EdgeKind::Calls),
+        vec![("C", "compress", 1), ("C", "zlib_version", 3)]
+    );
+    assert_eq!(
+        abi_refs(&result, EdgeKind::Implements),
+        vec![("C", "checksum", 8), ("Python", "encode", 13)]
+    );
+    assert_eq!(
+        refs_of_kind(&result, EdgeKind::Calls)[2..],
+        [
+            ("compress", 14),
+            ("data.as_ptr", 14),
+            ("data.len", 14),
+            ("Vec::new", 15)
+        ]
+    );
+    let signature = |name: &str| {
+        let node = result.nodes.iter().find(|n| n.name == name).unwrap();
+        node.signature.clone().unwrap()
+    };
+    assert_eq!(
+        signature("checksum"),
+        r#"pub extern "C" fn checksum(buf: *const u8, len: usize) -> u32"#
+    );
+    assert_eq!(signature("encode"), "fn encode(data: &[u8]) -> Vec<u8>");
+}
+
+#[test]
+fn test_cgo_jni_and_ctypes_calls_name_c_symbols() {
+    let go = GoExtractor.extract(
+        "codec/codec.go",
+        "package codec\n\n// #include \"codec.h\"\nimport \"C\"\n\nfunc Pack(b []byte) int {\n\treturn int(C.compress(nil, 0))\n}\n",
+    );
+    assert_eq!(
+        refs_of_kind(&go, EdgeKind::Calls),
+        vec![("int", 6), ("compress", 6)]
+    );
+    assert_eq!(abi_refs(&go, EdgeKind::Calls), vec![("C", "compress", 6)]);
+
+    let java = JavaExtractor.extract(
+        "src/com/example/Codec.java",
+        "package com.example;\n\nclass Codec {\n  static class Raw_Stream {\n    native int compress(byte[] buf);\n  }\n}\n",
+    );
+    assert_eq!(
+        abi_refs(&java, EdgeKind::Calls),
+        vec![("C", "Java_com_example_Codec_00024Raw_1Stream_compress", 4)]
+    );
+
+    let python = PythonExtractor.extract(
+        "codec.py",
+        "import ctypes\n\n_lib = ctypes.CDLL(\"libcodec.so\")\n\ndef pack(data):\n    _lib.compress(data, len(data))\n    return data.upper()\n",
+    );
+    assert_eq!(
+        refs_of_kind(&python, EdgeKind::Calls),
+        vec![("compress", 5), ("len", 5), ("data.upper", 6)]
+    );
+    assert_eq!(
+        abi_refs(&python, EdgeKind::Calls),
+        vec![("C", "compress", 5)]
+    );
+}
+
+#[tokio::test]
+async fn test_callers_of_a_c_function_cross_languages() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+    fs::create_dir_all(project.join("native")).unwrap();
+    fs::write(
+        project.join("native/codec.h"),
+        "int compress(const char *buf, int len);\nunsigned checksum(const char *buf, int len);\n",
+    )
+    .unwrap();
+    fs::write(
+        project.join("native/codec.c"),
+        "#include \"codec.h\"\n\nint compress(const char *buf, int len) {\n    return checksum(buf, len);\n}\n\nJNIEXPORT jint JNICALL Java_com_example_Codec_compress(JNIEnv *env, jobject self) {\n    return compress(0, 0);\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        project.join("lib.rs"),
+        "extern \"C\" {\n    fn compress(buf: *const u8, len: i32) -> i32;\n}\n\npub fn pack() -> i32 {\n    unsafe { compress(std::ptr::null(), 0) }\n}\n\n#[no_mangle]\npub extern \"C\" fn checksum(buf: *const u8, len: i32) -> u32 {\n    0\n}\n\n#[pyfunction]\nfn encode(level: i32) -> i32 {\n    pack()\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        project.join("codec.py"),
+        "import ctypes\nimport rustcodec\n\nlib = ctypes.CDLL(\"libcodec.so\")\n\ndef shrink(data):\n    return lib.compress(data, len(data))\n\ndef encode_all(level):\n    return rustcodec.encode(level)\n",
+    )
+    .unwrap();
+    fs::write(
+        project.join("Codec.java"),
+        "package com.example;\n\nclass Codec {\n  native int compress();\n}\n",
+    )
+    .unwrap();
+
+    let ts = TokenSave::init(project).await.unwrap();
+    ts.index_all().await.unwrap();
+
+    let c = ts.get_nodes_by_file("native/codec.c").await.unwrap();
+    let compress = c.iter().find(|n| n.name == "compress").unwrap();
+    let callers = ts.get_callers(&compress.id, 2, false, 0.0).await.unwrap();
+    let mut names: Vec<(&str, &str)> = callers
+        .iter()
+        .map(|(n, _)| (n.file_path.as_str(), n.name.as_str()))
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    assert_eq!(
+        names,
+        vec![
+            ("Codec.java", "compress"),
+            ("codec.py", "shrink"),
+            ("lib.rs", "compress"),
+            ("lib.rs", "pack"),
+            ("native/codec.c", "Java_com_example_Codec_compress"),
+        ]
+    );
+
+    // The Rust export implements the C prototype, and Python reaches the
+    // extension function.
+    let rust = ts.get_nodes_by_file("lib.rs").await.unwrap();
+    let checksum = rust.iter().find(|n| n.name == "checksum").unwrap();
+    let header = ts.get_nodes_by_file("native/codec.h").await.unwrap();
+    let prototype = header.iter().find(|n| n.name == "checksum").unwrap();
+    let outgoing = ts.get_outgoing_edges(&checksum.id).await.unwrap();
+    assert!(outgoing
+        .iter()
+        .any(|e| e.kind == EdgeKind::Implements && e.target == prototype.id));
+    let encode = rust.iter().find(|n| n.name == "encode").unwrap();
+    let python = ts.get_nodes_by_file("codec.py").await.unwrap();
+    let encode_all = python.iter().find(|n| n.name == "encode_all").unwrap();
+    assert!(ts
+        .get_outgoing_edges(&encode_all.id)
+        .await
+        .unwrap()
+        .iter()
+        .any(|e| e.kind == EdgeKind::Calls && e.target == encode.id));
+
+    // The JNI implementation is reached from its native method.
+    let java = ts.get_nodes_by_file("Codec.java").await.unwrap();
+    let native = java.iter().find(|n| n.name == "compress").unwrap();
+    let jni = c
+        .iter()
+        .find(|n| n.name == "Java_com_example_Codec_compress")
+        .unwrap();
+    assert!(ts
+        .get_outgoing_edges(&native.id)
+        .await
+        .unwrap()
+        .iter()
+        .any(|e| e.kind == EdgeKind::Calls && e.target == jni.id));
+
+    // A cgo caller added later is linked on sync.
+    fs::write(
+        project.join("pack.go"),
+        "package main\n\n// #include \"native/codec.h\"\nimport \"C\"\n\nfunc Pack() int {\n\treturn int(C.compress(nil, 0))\n}\n",
+    )
+    .unwrap();
+    ts.sync().await.unwrap();
+    let callers = ts.get_callers(&compress.id, 1, false, 0.0).await.unwrap();
+    assert!(callers.iter().any(|(n, _)| n.file_path == "pack.go"));
+
+    // So is a Python caller of the extension function, whose export is
+    // read back from the database.
+    fs::write(
+        project.join("bulk.py"),
+        "import rustcodec
+
+def encode_many(level):
+    return rustcodec.encode(level)
+",
+    )
+    .unwrap();
+    ts.sync().await.unwrap();
+    let callers = ts.get_callers(&encode.id, 1, false, 0.0).await.unwrap();
+    assert!(callers.iter().any(|(n, _)| n.file_path == "bulk.py"));
+}
@@ -378,3 +378,35 @@ func HandleRequest() {}
     assert!(fns[0].qualified_name.contains("HandleRequest"));
     assert!(fns[0].qualified_name.contains("handler.go"));
 }
+
+#[test]
+fn test_go_call_receiver_types() {
+    let source = r#"package main
+
+type Server struct {
+	db *Database
+}
+
+func (s *Server) Run(client Client) {
+	var conn Conn
+	s.db.GetNode(1)
+	client.Send(2)
+	conn.Close()
+	fmt.Println(3)
+}
+"#;
+    let extractor = GoExtractor;
+    let result = extractor.extract("server.go", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    let receiver = |name: &str| -> Option<String> {
+        result
+            .unresolved_refs
+            .iter()
+            .find(|r| r.reference_kind == EdgeKind::Calls && r.reference_name == name)
+            .and_then(|r| r.receiver_type.clone())
+    };
+    assert_eq!(receiver("s.db.GetNode").as_deref(), Some("Database"));
+    assert_eq!(receiver("client.Send").as_deref(), Some("Client"));
+    assert_eq!(receiver("conn.Close").as_deref(), Some("Conn"));
+    assert_eq!(receiver("fmt.Println").as_deref(), None);
+}
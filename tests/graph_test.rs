@@ -65,18 +65,21 @@ async fn setup_call_chain() -> (Database, TempDir) {
             target: "n-process".to_string(),
             kind: EdgeKind::Calls,
             line: Some(5),
+            provenance: None,
         },
         Edge {
             source: "n-process".to_string(),
             target: "n-validate".to_string(),
             kind: EdgeKind::Calls,
             line: Some(10),
+            provenance: None,
         },
         Edge {
             source: "n-validate".to_string(),
             target: "n-check".to_string(),
             kind: EdgeKind::Calls,
             line: Some(15),
+            provenance: None,
         },
     ];
     db.insert_edges(&edges)
@@ -200,6 +203,177 @@ async fn test_call_graph_bidirectional() {
     );
 }
 
+/// Sets up a trait method with one implementation:
+/// `Shape::area` (abstract) <- `impl Shape for Circle { fn area }`,
+/// `render` calls `Shape::area`, and `Circle::area` calls `pi`.
+async fn setup_virtual_dispatch() -> (Database, TempDir) {
+    let (db, dir) = setup_db().await;
+
+    let mut shape = make_node("n-shape", "Shape", "src/shape.rs", Visibility::Pub);
+    shape.kind = NodeKind::Trait;
+    let mut shape_area = make_node("n-shape-area", "area", "src/shape.rs", Visibility::Pub);
+    shape_area.kind = NodeKind::AbstractMethod;
+    let mut circle = make_node("n-circle", "Circle", "src/circle.rs", Visibility::Pub);
+    circle.kind = NodeKind::Impl;
+    let mut circle_area = make_node("n-circle-area", "area", "src/circle.rs", Visibility::Pub);
+    circle_area.kind = NodeKind::Method;
+    let render = make_node("n-render", "render", "src/main.rs", Visibility::Pub);
+    let pi = make_node("n-pi", "pi", "src/circle.rs", Visibility::Pub);
+
+    db.insert_nodes(&[shape, shape_area, circle, circle_area, render, pi])
+        .await
+        .expect("failed to insert nodes");
+
+    let edge = |source: &str, target: &str, kind: EdgeKind| Edge {
+        source: source.to_string(),
+        target: target.to_string(),
+        kind,
+        line: Some(1),
+        provenance: None,
+    };
+    let edges = vec![
+        edge("n-shape", "n-shape-area", EdgeKind::Contains),
+        edge("n-circle", "n-circle-area", EdgeKind::Contains),
+        edge("n-circle", "n-shape", EdgeKind::Implements),
+        edge("n-render", "n-shape-area", EdgeKind::Calls),
+        edge("n-circle-area", "n-pi", EdgeKind::Calls),
+    ];
+    db.insert_edges(&edges)
+        .await
+        .expect("failed to insert edges");
+
+    (db, dir)
+}
+
+#[tokio::test]
+async fn test_get_callers_virtual_dispatch() {
+    let (db, _dir) = setup_virtual_dispatch().await;
+
+    let plain = GraphTraverser::new(&db)
+        .get_callers("n-circle-area", 3)
+        .await
+        .expect("get_callers failed");
+    assert!(
+        plain.is_empty(),
+        "without virtual dispatch the impl has no callers"
+    );
+
+    let results = GraphTraverser::new(&db)
+        .with_virtual_dispatch(true)
+        .get_callers("n-circle-area", 3)
+        .await
+        .expect("get_callers failed");
+    let found: Vec<(&str, EdgeKind)> = results
+        .iter()
+        .map(|(n, e)| (n.id.as_str(), e.kind.clone()))
+        .collect();
+    assert!(
+        found.contains(&("n-shape-area", EdgeKind::Virtual)),
+        "trait method should be reported as a virtual link, got: {found:?}"
+    );
+    assert!(
+        found.contains(&("n-render", EdgeKind::Calls)),
+        "caller of the trait method should be found, got: {found:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_get_callees_virtual_dispatch() {
+    let (db, _dir) = setup_virtual_dispatch().await;
+
+    let results = GraphTraverser::new(&db)
+        .with_virtual_dispatch(true)
+        .get_callees("n-render", 2)
+        .await
+        .expect("get_callees failed");
+    let found: Vec<(&str, EdgeKind)> = results
+        .iter()
+        .map(|(n, e)| (n.id.as_str(), e.kind.clone()))
+        .collect();
+    assert!(
+        found.contains(&("n-shape-area", EdgeKind::Calls)),
+        "got: {found:?}"
+    );
+    assert!(
+        found.contains(&("n-circle-area", EdgeKind::Virtual)),
+        "call to the trait method should fan out to the impl, got: {found:?}"
+    );
+    assert!(
+        found.contains(&("n-pi", EdgeKind::Calls)),
+        "callees of the impl should be followed, got: {found:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_impact_radius_virtual_dispatch() {
+    let (db, _dir) = setup_virtual_dispatch().await;
+
+    let plain = GraphTraverser::new(&db)
+        .get_impact_radius("n-circle-area", 3)
+        .await
+        .expect("get_impact_radius failed");
+    assert!(!plain.nodes.iter().any(|n| n.id == "n-render"));
+
+    let subgraph = GraphTraverser::new(&db)
+        .with_virtual_dispatch(true)
+        .get_impact_radius("n-circle-area", 3)
+        .await
+        .expect("get_impact_radius failed");
+    let ids: Vec<&str> = subgraph.nodes.iter().map(|n| n.id.as_str()).collect();
+    assert!(
+        ids.contains(&"n-render"),
+        "impact of the impl should reach callers of the trait method, got: {ids:?}"
+    );
+    assert!(subgraph.edges.iter().any(|e| e.kind == EdgeKind::Virtual));
+}
+
+#[tokio::test]
+async fn test_min_confidence_skips_weak_edges() {
+    let (db, _dir) = setup_call_chain().await;
+    let weak = Edge {
+        source: "n-process".to_string(),
+        target: "n-validate".to_string(),
+        kind: EdgeKind::Calls,
+        line: Some(10),
+        provenance: None,
+    };
+    db.delete_edges(std::slice::from_ref(&weak))
+        .await
+        .expect("delete_edges failed");
+    db.insert_edge(&Edge {
+        provenance: Some(EdgeProvenance {
+            strategy: ResolutionStrategy::Heuristic,
+            confidence: 0.5,
+        }),
+        ..weak
+    })
+    .await
+    .expect("insert_edge failed");
+
+    let all = GraphTraverser::new(&db)
+        .get_callees("n-main", 5)
+        .await
+        .expect("get_callees failed");
+    assert_eq!(all.len(), 3);
+
+    let confident = GraphTraverser::new(&db)
+        .with_min_confidence(0.8)
+        .get_callees("n-main", 5)
+        .await
+        .expect("get_callees failed");
+    let names: Vec<&str> = confident.iter().map(|(n, _)| n.name.as_str()).collect();
+    assert_eq!(names, vec!["process"]);
+
+    let impact = GraphTraverser::new(&db)
+        .with_min_confidence(0.8)
+        .get_impact_radius("n-check", 5)
+        .await
+        .expect("get_impact_radius failed");
+    let names: Vec<&str> = impact.nodes.iter().map(|n| n.name.as_str()).collect();
+    assert!(names.contains(&"validate"));
+    assert!(!names.contains(&"process"), "got: {names:?}");
+}
+
 #[tokio::test]
 async fn test_bfs_traversal_with_depth_limit() {
     let (db, _dir) = setup_call_chain().await;
@@ -432,6 +606,84 @@ async fn test_find_dead_code_with_kind_filter() {
     );
 }
 
+#[tokio::test]
+async fn test_find_dead_code_with_confidence() {
+    let (db, _dir) = setup_db().await;
+
+    let caller = make_node("n-caller", "caller", "src/lib.rs", Visibility::Private);
+    let guessed = make_node("n-guessed", "guessed", "src/lib.rs", Visibility::Private);
+    db.insert_nodes(&[caller, guessed])
+        .await
+        .expect("insert nodes failed");
+    db.insert_edge(&Edge {
+        source: "n-caller".to_string(),
+        target: "n-guessed".to_string(),
+        kind: EdgeKind::Calls,
+        line: Some(3),
+        provenance: Some(EdgeProvenance {
+            strategy: ResolutionStrategy::Ambiguous { candidates: 2 },
+            confidence: 0.7,
+        }),
+    })
+    .await
+    .expect("insert edge failed");
+
+    let qm = GraphQueryManager::new(&db);
+
+    let dead = qm
+        .find_dead_code_with_confidence(&[], 0.0)
+        .await
+        .expect("find_dead_code_with_confidence failed");
+    assert!(!dead.iter().any(|(n, _)| n.id == "n-guessed"));
+
+    let dead = qm
+        .find_dead_code_with_confidence(&[], 0.8)
+        .await
+        .expect("find_dead_code_with_confidence failed");
+    let guessed = dead
+        .iter()
+        .find(|(n, _)| n.id == "n-guessed")
+        .expect("a node only referenced below the threshold is dead");
+    assert!((guessed.1 - 0.7).abs() < f64::EPSILON);
+    let caller = dead
+        .iter()
+        .find(|(n, _)| n.id == "n-caller")
+        .expect("an unreferenced node is dead");
+    assert!(caller.1.abs() < f64::EPSILON);
+}
+
+#[tokio::test]
+async fn test_find_dead_code_excludes_test_code_not_test_names() {
+    let (db, _dir) = setup_db().await;
+
+    // A `#[cfg(test)]` module spanning lines 20-40, with a test and a helper.
+    let mut module = make_node("n-tests", "tests", "src/db.rs", Visibility::Private);
+    module.kind = NodeKind::TestCase;
+    (module.start_line, module.end_line) = (20, 40);
+    let mut test = make_node("n-test", "opens", "src/db.rs", Visibility::Private);
+    test.kind = NodeKind::TestCase;
+    (test.start_line, test.end_line) = (30, 35);
+    let mut helper = make_node("n-helper", "fixture", "src/db.rs", Visibility::Private);
+    (helper.start_line, helper.end_line) = (22, 25);
+    // Production code whose name merely starts with "test".
+    let probe = make_node(
+        "n-probe",
+        "test_connection",
+        "src/db.rs",
+        Visibility::Private,
+    );
+
+    db.insert_nodes(&[module, test, helper, probe])
+        .await
+        .expect("insert nodes failed");
+
+    let qm = GraphQueryManager::new(&db);
+    let dead = qm.find_dead_code(&[]).await.expect("find_dead_code failed");
+
+    let dead_names: Vec<&str> = dead.iter().map(|n| n.name.as_str()).collect();
+    assert_eq!(dead_names, vec!["test_connection"]);
+}
+
 #[tokio::test]
 async fn test_get_node_metrics() {
     let (db, _dir) = setup_call_chain().await;
@@ -508,12 +760,14 @@ async fn test_find_circular_dependencies() {
             target: "n-b".to_string(),
             kind: EdgeKind::Calls,
             line: Some(1),
+            provenance: None,
         },
         Edge {
             source: "n-b".to_string(),
             target: "n-a".to_string(),
             kind: EdgeKind::Calls,
             line: Some(1),
+            provenance: None,
         },
     ];
     db.insert_edges(&edges).await.expect("insert edges failed");
@@ -577,6 +831,7 @@ async fn test_type_hierarchy() {
         target: "n-trait".to_string(),
         kind: EdgeKind::Implements,
         line: None,
+        provenance: None,
     };
     db.insert_edge(&edge).await.expect("insert edge failed");
 
@@ -663,12 +918,14 @@ async fn test_node_metrics_depth() {
             target: "n-module".to_string(),
             kind: EdgeKind::Contains,
             line: None,
+            provenance: None,
         },
         Edge {
             source: "n-module".to_string(),
             target: "n-func".to_string(),
             kind: EdgeKind::Contains,
             line: None,
+            provenance: None,
         },
     ];
     db.insert_edges(&edges).await.expect("insert edges failed");
@@ -0,0 +1,226 @@
+mod common;
+
+use std::fs;
+
+use common::{named, refs};
+use tempfile::TempDir;
+use tokensave::extraction::{GraphqlExtractor, LanguageExtractor, TypeScriptExtractor};
+use tokensave::tokensave::TokenSave;
+use tokensave::types::*;
+
+const SCHEMA: &str = r#"interface Node {
+  id: ID!
+}
+
+"A registered user."
+type User implements Node {
+  id: ID!
+  """
+  Posts written by the user, newest first.
+  """
+  posts(first: Int = 10,
+        after: String): [Post!]!
+}
+
+type Post implements Node {
+  id: ID!
+  title: String
+  author: User!
+}
+
+union SearchResult = User | Post
+
+type Query {
+  user(id: ID!): User
+  search(term: String!): [SearchResult!]!
+}
+"#;
+
+const OPERATIONS: &str = r#"query UserPosts($id: ID!) {
+  user(id: $id) {
+    __typename
+    latest: posts(first: 1) {
+      ...PostFields
+    }
+  }
+}
+
+fragment PostFields on Post {
+  title
+  author { id }
+}
+
+{
+  search(term: "x") {
+    ... on User { id }
+  }
+}
+"#;
+
+#[test]
+fn test_graphql_schema_types_and_fields() {
+    let result = GraphqlExtractor.extract("schema.graphql", SCHEMA);
+    let nodes: Vec<(&str, &NodeKind)> = result
+        .nodes
+        .iter()
+        .skip(1)
+        .map(|n| (n.name.as_str(), &n.kind))
+        .collect();
+    assert_eq!(
+        nodes,
+        vec![
+            ("Node", &NodeKind::GqlType),
+            ("Node.id", &NodeKind::GqlField),
+            ("User", &NodeKind::GqlType),
+            ("User.id", &NodeKind::GqlField),
+            ("User.posts", &NodeKind::GqlField),
+            ("Post", &NodeKind::GqlType),
+            ("Post.id", &NodeKind::GqlField),
+            ("Post.title", &NodeKind::GqlField),
+            ("Post.author", &NodeKind::GqlField),
+            ("SearchResult", &NodeKind::GqlType),
+            ("Query", &NodeKind::GqlType),
+            ("Query.user", &NodeKind::GqlField),
+            ("Query.search", &NodeKind::GqlField),
+        ]
+    );
+
+    let user = &result.nodes[3];
+    assert_eq!(user.docstring.as_deref(), Some("A registered user."));
+    let posts = &result.nodes[5];
+    assert_eq!(
+        posts.signature.as_deref(),
+        Some("posts(first: Int = 10, after: String): [Post!]!")
+    );
+    assert_eq!(
+        posts.docstring.as_deref(),
+        Some("Posts written by the user, newest first.")
+    );
+    assert_eq!((posts.start_line, posts.end_line), (10, 11));
+
+    assert_eq!(
+        refs(&result, "User"),
+        named(EdgeKind::Implements, &["Node"])
+    );
+    assert_eq!(
+        refs(&result, "User.posts"),
+        named(EdgeKind::Uses, &["Int", "String", "Post"])
+    );
+    assert_eq!(
+        refs(&result, "SearchResult"),
+        named(EdgeKind::Uses, &["User", "Post"])
+    );
+}
+
+#[test]
+fn test_graphql_operations_reference_selection_paths() {
+    let result = GraphqlExtractor.extract("queries.graphql", OPERATIONS);
+    let operation = result.nodes.iter().find(|n| n.name == "UserPosts").unwrap();
+    assert_eq!(operation.kind, NodeKind::GqlOperation);
+    assert_eq!((operation.start_line, operation.end_line), (0, 7));
+    assert_eq!(
+        refs(&result, "UserPosts"),
+        named(
+            EdgeKind::Uses,
+            &["ID", "PostFields", "Query.user.posts", "Query.user"]
+        )
+    );
+    let fragment = result
+        .nodes
+        .iter()
+        .find(|n| n.name == "PostFields")
+        .unwrap();
+    assert_eq!(fragment.kind, NodeKind::GqlFragment);
+    assert_eq!(
+        refs(&result, "PostFields"),
+        named(
+            EdgeKind::Uses,
+            &["Post.author.id", "Post.title", "Post.author"]
+        )
+    );
+    // Anonymous operations belong to the file.
+    assert_eq!(
+        refs(&result, "queries.graphql"),
+        named(EdgeKind::Uses, &["User.id", "Query.search"])
+    );
+}
+
+#[test]
+fn test_graphql_tagged_templates_in_typescript() {
+    let source = r#"import { gql } from "@apollo/client";
+
+export const USER_QUERY = gql`
+  query User($id: ID!) {
+    user(id: $id) { ...${USER_FIELDS} id }
+  }
+`;
+"#;
+    let result = TypeScriptExtractor.extract("src/queries.ts", source);
+    let operation = result.nodes.iter().find(|n| n.name == "User").unwrap();
+    assert_eq!(operation.kind, NodeKind::GqlOperation);
+    assert_eq!(operation.start_line, 3);
+    let container = result
+        .edges
+        .iter()
+        .find(|e| e.target == operation.id && e.kind == EdgeKind::Contains)
+        .unwrap();
+    let constant = result.nodes.iter().find(|n| n.id == container.source);
+    assert_eq!(constant.unwrap().name, "USER_QUERY");
+    let field = result
+        .unresolved_refs
+        .iter()
+        .find(|r| r.reference_name == "Query.user.id")
+        .unwrap();
+    assert_eq!((field.line, field.column), (4, 38));
+}
+
+#[tokio::test]
+async fn test_graphql_field_impact_reaches_clients_and_resolvers() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+    fs::write(project.join("schema.graphql"), SCHEMA).unwrap();
+    fs::write(project.join("queries.graphql"), OPERATIONS).unwrap();
+    fs::write(
+        project.join("resolvers.py"),
+        "class User:\n    def resolve_posts(self, info, first=10):\n        return []\n",
+    )
+    .unwrap();
+
+    let ts = TokenSave::init(project).await.unwrap();
+    ts.index_all().await.unwrap();
+
+    let schema = ts.get_nodes_by_file("schema.graphql").await.unwrap();
+    let posts = schema.iter().find(|n| n.name == "User.posts").unwrap();
+    let impact = ts
+        .get_impact_radius(&posts.id, 2, false, 0.0)
+        .await
+        .unwrap();
+    let names: Vec<&str> = impact.nodes.iter().map(|n| n.name.as_str()).collect();
+    assert!(names.contains(&"UserPosts"), "{names:?}");
+    assert!(names.contains(&"resolve_posts"), "{names:?}");
+
+    // Code sharing a type's name is not a schema reference.
+    let user = schema.iter().find(|n| n.name == "User").unwrap();
+    let incoming = ts.get_incoming_edges(&user.id).await.unwrap();
+    let resolvers = ts.get_nodes_by_file("resolvers.py").await.unwrap();
+    assert!(incoming
+        .iter()
+        .all(|e| resolvers.iter().all(|n| n.id != e.source)));
+
+    // A resolver added later is linked on sync.
+    fs::write(
+        project.join("resolvers.go"),
+        "package graph\n\ntype queryResolver struct{}\n\nfunc (r *queryResolver) User(id string) error {\n\treturn nil\n}\n",
+    )
+    .unwrap();
+    ts.sync().await.unwrap();
+    let go = ts.get_nodes_by_file("resolvers.go").await.unwrap();
+    let resolver = go.iter().find(|n| n.name == "User").unwrap();
+    let query_user = schema.iter().find(|n| n.name == "Query.user").unwrap();
+    assert!(ts
+        .get_outgoing_edges(&resolver.id)
+        .await
+        .unwrap()
+        .iter()
+        .any(|e| e.kind == EdgeKind::Implements && e.target == query_user.id));
+}
@@ -4,7 +4,7 @@ use std::os::unix::fs::symlink;
 use tempfile::TempDir;
 use tokensave::config::{load_config, save_config};
 use tokensave::tokensave::TokenSave;
-use tokensave::types::EdgeKind;
+use tokensave::types::{EdgeKind, NodeKind};
 
 /// Directly test that the ignore crate with add_custom_ignore_filename reads
 /// nested .gitignore files, regardless of git repo presence.
@@ -142,6 +142,89 @@ async fn test_incremental_sync() {
     assert!(!results.is_empty(), "should find 'modified' after sync");
 }
 
+#[tokio::test]
+async fn test_external_calls_create_dependency_nodes() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+
+    fs::create_dir_all(project.join("src")).unwrap();
+    fs::write(
+        project.join("src/main.rs"),
+        "use tokio::spawn;\n\nfn run() {\n    spawn(async {});\n}\n\nfn spawn_local() {}\n",
+    )
+    .unwrap();
+
+    let cg = TokenSave::init(project).await.unwrap();
+    cg.index_all().await.unwrap();
+
+    let packages = cg
+        .db()
+        .get_nodes_by_kind(NodeKind::ExternalPackage)
+        .await
+        .unwrap();
+    assert_eq!(packages.len(), 1, "{packages:?}");
+    assert_eq!(packages[0].name, "tokio");
+    let symbols = cg
+        .db()
+        .get_nodes_by_kind(NodeKind::ExternalSymbol)
+        .await
+        .unwrap();
+    let spawn = symbols
+        .iter()
+        .find(|n| n.qualified_name == "tokio::spawn")
+        .expect("tokio::spawn symbol");
+    let callers = cg.get_callers(&spawn.id, 1, false, 0.0).await.unwrap();
+    assert_eq!(callers.len(), 1);
+    assert_eq!(callers[0].0.name, "run");
+
+    // Once nothing calls into the dependency, its nodes are dropped.
+    fs::write(project.join("src/main.rs"), "fn run() {}\n").unwrap();
+    cg.sync().await.unwrap();
+    for kind in [NodeKind::ExternalPackage, NodeKind::ExternalSymbol] {
+        assert!(cg.db().get_nodes_by_kind(kind).await.unwrap().is_empty());
+    }
+}
+
+#[tokio::test]
+async fn test_node_ids_survive_edits_that_move_nodes() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+
+    fs::create_dir_all(project.join("src")).unwrap();
+    fs::write(
+        project.join("src/lib.rs"),
+        "pub fn first() {}\n\npub fn second() {\n    first();\n}\n",
+    )
+    .unwrap();
+
+    let cg = TokenSave::init(project).await.unwrap();
+    cg.index_all().await.unwrap();
+    let id_of = |results: &[tokensave::types::SearchResult], name: &str| {
+        results
+            .iter()
+            .find(|r| r.node.name == name)
+            .map(|r| r.node.id.clone())
+            .unwrap()
+    };
+    let before = cg.search("second", 10).await.unwrap();
+    let second = id_of(&before, "second");
+
+    // Shift every node down and add a node above them.
+    fs::write(
+        project.join("src/lib.rs"),
+        "\n\npub fn zeroth() {}\n\npub fn first() {}\n\npub fn second() {\n    first();\n}\n",
+    )
+    .unwrap();
+    cg.sync().await.unwrap();
+
+    let after = cg.search("second", 10).await.unwrap();
+    assert_eq!(id_of(&after, "second"), second);
+    let node = cg.get_node(&second).await.unwrap().expect("node by old ID");
+    assert_eq!(node.start_line, 6);
+    let callees = cg.get_callees(&second, 1, false, 0.0).await.unwrap();
+    assert!(callees.iter().any(|(n, _)| n.name == "first"));
+}
+
 #[tokio::test]
 async fn test_init_and_open() {
     let dir = TempDir::new().unwrap();
@@ -698,7 +781,7 @@ async fn test_index_all_produces_call_edges() {
 
     let target_id = find_node_id(&cg, "target_fn").await;
 
-    let callers = cg.get_callers(&target_id, 3).await.unwrap();
+    let callers = cg.get_callers(&target_id, 3, false, 0.0).await.unwrap();
     assert!(
         callers
             .iter()
@@ -717,7 +800,7 @@ async fn test_sync_produces_call_edges() {
 
     let target_id = find_node_id(&cg, "target_fn").await;
 
-    let callers = cg.get_callers(&target_id, 3).await.unwrap();
+    let callers = cg.get_callers(&target_id, 3, false, 0.0).await.unwrap();
     assert!(
         callers
             .iter()
@@ -763,7 +846,7 @@ pub fn top_fn() -> u32 { middle_fn() }
     let middle_id = find_node_id(&cg, "middle_fn").await;
 
     // middle_fn -> base_fn
-    let base_callers = cg.get_callers(&base_id, 1).await.unwrap();
+    let base_callers = cg.get_callers(&base_id, 1, false, 0.0).await.unwrap();
     assert!(
         base_callers
             .iter()
@@ -772,14 +855,14 @@ pub fn top_fn() -> u32 { middle_fn() }
     );
 
     // top_fn -> middle_fn
-    let middle_callers = cg.get_callers(&middle_id, 1).await.unwrap();
+    let middle_callers = cg.get_callers(&middle_id, 1, false, 0.0).await.unwrap();
     assert!(
         middle_callers.iter().any(|(node, _)| node.name == "top_fn"),
         "sync should resolve top_fn -> middle_fn call edge after modification"
     );
 
     // Transitive: top_fn should appear as a depth-2 caller of base_fn
-    let transitive_callers = cg.get_callers(&base_id, 3).await.unwrap();
+    let transitive_callers = cg.get_callers(&base_id, 3, false, 0.0).await.unwrap();
     assert!(
         transitive_callers
             .iter()
@@ -825,7 +908,7 @@ pub fn run_engine() -> u32 {
 
     let entry_id = find_node_id(&cg, "entry_point").await;
 
-    let callers = cg.get_callers(&entry_id, 3).await.unwrap();
+    let callers = cg.get_callers(&entry_id, 3, false, 0.0).await.unwrap();
     assert!(
         callers.iter().any(|(node, _)| node.name == "run_engine"),
         "sync should resolve cross-file call edges when a new file is added"
@@ -894,6 +977,71 @@ async fn test_sync_does_not_duplicate_edges() {
     );
 }
 
+#[tokio::test]
+async fn test_sync_keeps_callers_in_unchanged_files_when_callee_changes() {
+    let (dir, cg) = setup_call_edge_project().await;
+    cg.index_all().await.unwrap();
+
+    // Shift target_fn down so its node is replaced; caller_mod is untouched.
+    fs::write(
+        dir.path().join("src/callee_mod.rs"),
+        r#"
+/// A new helper above the target.
+pub fn helper() -> u32 {
+    7
+}
+
+/// The target function that should be found via call edges.
+pub fn target_fn() -> u32 {
+    42
+}
+"#,
+    )
+    .unwrap();
+
+    cg.sync().await.unwrap();
+
+    let target_id = find_node_id(&cg, "target_fn").await;
+    let callers = cg.get_callers(&target_id, 1, false, 0.0).await.unwrap();
+    assert!(
+        callers.iter().any(|(node, _)| node.name == "caller_fn"),
+        "sync should re-resolve calls from unchanged files into a modified file"
+    );
+}
+
+#[tokio::test]
+async fn test_sync_binds_unchanged_refs_to_newly_added_symbol() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+
+    fs::create_dir_all(project.join("src")).unwrap();
+
+    // The call cannot resolve yet: nothing declares `late_fn`.
+    fs::write(
+        project.join("src/caller.rs"),
+        "pub fn early_caller() -> u32 { late_fn() }\n",
+    )
+    .unwrap();
+
+    let cg = TokenSave::init(project).await.unwrap();
+    cg.index_all().await.unwrap();
+
+    fs::write(
+        project.join("src/provider.rs"),
+        "pub fn late_fn() -> u32 { 1 }\n",
+    )
+    .unwrap();
+
+    cg.sync().await.unwrap();
+
+    let late_id = find_node_id(&cg, "late_fn").await;
+    let callers = cg.get_callers(&late_id, 1, false, 0.0).await.unwrap();
+    assert!(
+        callers.iter().any(|(node, _)| node.name == "early_caller"),
+        "sync should bind references in unchanged files to newly added symbols"
+    );
+}
+
 #[tokio::test]
 async fn test_concurrent_sync_is_rejected() {
     let dir = TempDir::new().unwrap();
@@ -457,3 +457,63 @@ public class App {
     assert!(methods[0].qualified_name.contains("App"));
     assert!(methods[0].qualified_name.contains("run"));
 }
+
+#[test]
+fn test_java_type_reference_refs() {
+    let source = r#"public class Service {
+    private Repo<User> repo;
+
+    public Account find(UserId id, int limit) {
+        return null;
+    }
+}
+"#;
+    let extractor = JavaExtractor;
+    let result = extractor.extract("Service.java", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    let names = |kind: EdgeKind| -> Vec<String> {
+        result
+            .unresolved_refs
+            .iter()
+            .filter(|r| r.reference_kind == kind)
+            .map(|r| r.reference_name.clone())
+            .collect()
+    };
+    let receives = names(EdgeKind::Receives);
+    let returns = names(EdgeKind::Returns);
+    let type_of = names(EdgeKind::TypeOf);
+    assert_eq!(receives, vec!["UserId"]);
+    assert_eq!(returns, vec!["Account"]);
+    assert_eq!(type_of, vec!["Repo", "User"]);
+}
+
+#[test]
+fn test_java_call_receiver_types() {
+    let source = r#"class Server {
+  private Database db;
+  void run(Client client) {
+    Conn conn = open();
+    this.db.getNode(1);
+    db.getNode(2);
+    client.send(2);
+    conn.close();
+    unknown.call();
+  }
+}
+"#;
+    let extractor = JavaExtractor;
+    let result = extractor.extract("Server.java", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    let receiver = |name: &str| -> Option<String> {
+        result
+            .unresolved_refs
+            .iter()
+            .find(|r| r.reference_kind == EdgeKind::Calls && r.reference_name == name)
+            .and_then(|r| r.receiver_type.clone())
+    };
+    assert_eq!(receiver("this.db.getNode").as_deref(), Some("Database"));
+    assert_eq!(receiver("db.getNode").as_deref(), Some("Database"));
+    assert_eq!(receiver("client.send").as_deref(), Some("Client"));
+    assert_eq!(receiver("conn.close").as_deref(), Some("Conn"));
+    assert_eq!(receiver("unknown.call").as_deref(), None);
+}
@@ -555,3 +555,58 @@ fn test_kt_language_name() {
     let extractor = KotlinExtractor;
     assert_eq!(extractor.language_name(), "Kotlin");
 }
+
+#[test]
+fn test_kotlin_type_reference_refs() {
+    let result = extract(
+        r#"class Service {
+    val repo: Repo<User> = Repo()
+
+    fun UserId.find(limit: Int, filter: Filter): Account? {
+        return null
+    }
+}
+"#,
+    );
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    let names = |kind: EdgeKind| -> Vec<String> {
+        result
+            .unresolved_refs
+            .iter()
+            .filter(|r| r.reference_kind == kind)
+            .map(|r| r.reference_name.clone())
+            .collect()
+    };
+    assert_eq!(names(EdgeKind::Receives), vec!["UserId", "Filter"]);
+    assert_eq!(names(EdgeKind::Returns), vec!["Account"]);
+    assert_eq!(names(EdgeKind::TypeOf), vec!["Repo", "User"]);
+}
+
+#[test]
+fn test_kotlin_call_receiver_types() {
+    let source = r#"class Server(private val db: Database) {
+    val store: Store = Store()
+    fun run(client: Client) {
+        val conn: Conn = open()
+        this.db.getNode(1)
+        store.put(2)
+        client.send(2)
+        conn.close()
+    }
+}
+"#;
+    let extractor = KotlinExtractor;
+    let result = extractor.extract("Server.kt", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    let receiver = |name: &str| -> Option<String> {
+        result
+            .unresolved_refs
+            .iter()
+            .find(|r| r.reference_kind == EdgeKind::Calls && r.reference_name == name)
+            .and_then(|r| r.receiver_type.clone())
+    };
+    assert_eq!(receiver("this.db.getNode").as_deref(), Some("Database"));
+    assert_eq!(receiver("store.put").as_deref(), Some("Store"));
+    assert_eq!(receiver("client.send").as_deref(), Some("Client"));
+    assert_eq!(receiver("conn.close").as_deref(), Some("Conn"));
+}
@@ -215,3 +215,83 @@ fn test_markdown_handles_header_with_punctuation() {
     assert_eq!(modules.len(), 1);
     assert_eq!(modules[0].name, "Hello, World! (2024)");
 }
+
+const GUIDE: &str = r#"# Resolving `ReferenceResolver`
+
+Call `ReferenceResolver::resolve_all()` after indexing, or `resolve_one(r)`
+for a single reference. Run `cargo test` and edit `Cargo.toml` as usual;
+`main.rs` is the entry point.
+
+## Example
+
+```rust
+let resolver = ReferenceResolver::new(&db);
+let edges = resolver.resolve_all(&refs);
+let again = resolver.resolve_all(&refs);
+```
+
+```console
+$ tokensave sync --force_full
+```
+"#;
+
+fn refs_from<'a>(result: &'a ExtractionResult, section: &str) -> Vec<(&'a str, u32)> {
+    let id = &result.nodes.iter().find(|n| n.name == section).unwrap().id;
+    result
+        .unresolved_refs
+        .iter()
+        .filter(|r| &r.from_node_id == id)
+        .map(|r| {
+            assert_eq!(r.reference_kind, EdgeKind::Uses);
+            (r.reference_name.as_str(), r.line)
+        })
+        .collect()
+}
+
+#[test]
+fn test_markdown_code_spans_reference_symbols() {
+    let result = MarkdownExtractor.extract("docs/guide.md", GUIDE);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    // Commands, file names and plain words stay prose.
+    assert_eq!(
+        refs_from(&result, "Resolving `ReferenceResolver`"),
+        vec![
+            ("ReferenceResolver", 0),
+            ("ReferenceResolver::resolve_all", 2),
+            ("resolve_one", 2),
+        ]
+    );
+}
+
+#[test]
+fn test_markdown_code_blocks_reference_symbols() {
+    let result = MarkdownExtractor.extract("docs/guide.md", GUIDE);
+    // Each symbol once per block; shell blocks are skipped, and calls
+    // through the local `resolver` name no symbol.
+    assert_eq!(
+        refs_from(&result, "Example"),
+        vec![("ReferenceResolver::new", 9)]
+    );
+    let column = result
+        .unresolved_refs
+        .iter()
+        .find(|r| r.reference_name == "ReferenceResolver::new")
+        .unwrap()
+        .column;
+    assert_eq!(column, 15);
+}
+
+#[test]
+fn test_markdown_code_blocks_skip_local_bindings() {
+    let source = "# Loading\n\n```rust\nfn collect(node_ids: &[NodeId], graph_store: &Graph) {\n    let mut by_id = HashMap::new();\n    for node_id in node_ids {\n        by_id.insert(node_id, graph_store.load_nodes());\n    }\n    Graph::load_nodes(graph_store);\n}\n```\n";
+    let result = MarkdownExtractor.extract("README.md", source);
+    assert_eq!(
+        refs_from(&result, "Loading"),
+        vec![
+            ("NodeId", 3),
+            ("Graph", 3),
+            ("HashMap::new", 4),
+            ("Graph::load_nodes", 8)
+        ]
+    );
+}
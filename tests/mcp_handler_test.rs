@@ -1528,6 +1528,59 @@ async fn test_str_replace_success() {
     assert!(!content.contains("fn hello() {}"));
 }
 
+#[tokio::test]
+async fn test_str_replace_keeps_file_in_its_package() {
+    use tokensave::types::{generate_node_id, EdgeKind, NodeKind};
+
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+    fs::create_dir_all(project.join("src")).unwrap();
+    fs::write(project.join("Cargo.toml"), "[package]\nname = \"app\"\n").unwrap();
+    fs::write(project.join("src/main.rs"), "fn hello() {}\n").unwrap();
+
+    let cg = TokenSave::init(project).await.unwrap();
+    cg.index_all().await.unwrap();
+    let main_file = generate_node_id("src/main.rs", &NodeKind::File, "src/main.rs", 0);
+
+    for (path, old_str, new_str) in [
+        ("src/main.rs", "fn hello() {}", "fn hello_updated() {}"),
+        (
+            "Cargo.toml",
+            "name = \"app\"",
+            "name = \"app\"\nversion = \"0.2.0\"",
+        ),
+    ] {
+        let result = handle_tool_call(
+            &cg,
+            "tokensave_str_replace",
+            json!({ "path": path, "old_str": old_str, "new_str": new_str }),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        let parsed: Value = serde_json::from_str(extract_text(&result.value)).unwrap();
+        assert_eq!(parsed["success"], true, "{path}");
+
+        let packages = cg
+            .db()
+            .get_nodes_by_kind(NodeKind::ManifestPackage)
+            .await
+            .unwrap();
+        let app = packages
+            .iter()
+            .find(|n| n.name == "app")
+            .unwrap_or_else(|| panic!("editing {path} deleted the app package"));
+        let outgoing = cg.get_outgoing_edges(&app.id).await.unwrap();
+        assert!(
+            outgoing
+                .iter()
+                .any(|e| e.kind == EdgeKind::Contains && e.target == main_file),
+            "editing {path} dropped src/main.rs from its package"
+        );
+    }
+}
+
 #[tokio::test]
 async fn test_str_replace_not_found() {
     let dir = TempDir::new().unwrap();
@@ -2082,6 +2135,89 @@ async fn test_dsm_clusters() {
     );
 }
 
+/// A Cargo workspace where `app` calls into `core` and declares the
+/// dependency.
+async fn setup_workspace() -> (TokenSave, TempDir) {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+    for (path, contents) in [
+        ("Cargo.toml", "[workspace]\nmembers = [\"core\", \"app\"]\n"),
+        ("core/Cargo.toml", "[package]\nname = \"core\"\n"),
+        ("core/src/lib.rs", "pub fn helper() -> u32 {\n    1\n}\n"),
+        (
+            "app/Cargo.toml",
+            "[package]\nname = \"app\"\n\n[dependencies]\ncore = { path = \"../core\" }\n",
+        ),
+        ("app/src/main.rs", "fn main() {\n    helper();\n}\n"),
+    ] {
+        let path = project.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+    let cg = TokenSave::init(project).await.unwrap();
+    cg.index_all().await.unwrap();
+    (cg, dir)
+}
+
+#[tokio::test]
+async fn test_coupling_by_package() {
+    let (cg, _dir) = setup_workspace().await;
+    let result = handle_tool_call(
+        &cg,
+        "tokensave_coupling",
+        json!({ "granularity": "package", "direction": "fan_in" }),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let text = extract_text(&result.value);
+    let parsed: Value = serde_json::from_str(text).unwrap();
+    let ranking = parsed["ranking"].as_array().unwrap();
+    assert_eq!(ranking.len(), 1, "only core is depended on, got: {text}");
+    assert_eq!(ranking[0]["package"], "core");
+    assert_eq!(ranking[0]["directory"], "core");
+    assert_eq!(ranking[0]["coupled_packages"], 1);
+    assert_eq!(ranking[0]["declared_dependents"], 1);
+}
+
+#[tokio::test]
+async fn test_dsm_by_package() {
+    let (cg, _dir) = setup_workspace().await;
+    let result = handle_tool_call(
+        &cg,
+        "tokensave_dsm",
+        json!({ "granularity": "package", "format": "matrix" }),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let text = extract_text(&result.value);
+    let parsed: Value = serde_json::from_str(text).unwrap();
+    let names: Vec<&str> = parsed["packages"]
+        .as_array()
+        .unwrap_or_else(|| panic!("packages field should exist, got: {text}"))
+        .iter()
+        .map(|v| v.as_str().unwrap())
+        .collect();
+    assert_eq!(names.len(), 2);
+    let app = names.iter().position(|n| *n == "app").unwrap();
+    let core = names.iter().position(|n| *n == "core").unwrap();
+    assert_eq!(parsed["matrix"][app][core], 1);
+    assert_eq!(parsed["matrix"][core][app], 0);
+
+    let err = handle_tool_call(
+        &cg,
+        "tokensave_dsm",
+        json!({ "granularity": "module" }),
+        None,
+        None,
+    )
+    .await;
+    assert!(err.is_err(), "unknown granularity should be rejected");
+}
+
 // ---------------------------------------------------------------------------
 // tokensave_test_risk
 // ---------------------------------------------------------------------------
@@ -2320,3 +2456,754 @@ async fn test_todos_empty_when_clean() {
     let output: Value = serde_json::from_str(text).unwrap();
     assert_eq!(output["match_count"].as_u64().unwrap(), 0);
 }
+
+// ---------------------------------------------------------------------------
+// tokensave_unresolved
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_unresolved_groups_by_name_and_reason() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+    fs::create_dir_all(project.join("src")).unwrap();
+    fs::write(
+        project.join("src/main.rs"),
+        r#"
+fn main() {
+    not_defined_anywhere();
+    not_defined_anywhere();
+    helper();
+}
+
+fn helper() {}
+"#,
+    )
+    .unwrap();
+    let cg = TokenSave::init(project).await.unwrap();
+    cg.index_all().await.unwrap();
+
+    let result = handle_tool_call(&cg, "tokensave_unresolved", json!({}), None, None)
+        .await
+        .unwrap();
+    let text = extract_text(&result.value);
+    let output: Value = serde_json::from_str(text).unwrap();
+    let group = output["by_name"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|g| g["key"] == "not_defined_anywhere")
+        .unwrap_or_else(|| panic!("missing group for unknown call: {text}"));
+    assert_eq!(group["count"].as_u64().unwrap(), 2);
+    assert_eq!(group["reasons"]["unknown_name"].as_u64().unwrap(), 2);
+    assert!(
+        output["by_name"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|g| g["key"] != "helper"),
+        "resolved calls must not be reported: {text}"
+    );
+
+    let result = handle_tool_call(
+        &cg,
+        "tokensave_unresolved",
+        json!({"reason": "no_fitting_candidate"}),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let output: Value = serde_json::from_str(extract_text(&result.value)).unwrap();
+    assert_eq!(output["total"].as_u64().unwrap(), 0);
+}
+
+// ---------------------------------------------------------------------------
+// tokensave_external
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_external_lists_packages_and_callers() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+    fs::create_dir_all(project.join("src")).unwrap();
+    fs::write(
+        project.join("src/main.rs"),
+        r#"
+use tokio::spawn;
+
+fn start() {
+    spawn(work());
+}
+
+fn stop() {
+    spawn(work());
+    serde_json::to_string(&1);
+}
+
+fn work() {}
+"#,
+    )
+    .unwrap();
+    let cg = TokenSave::init(project).await.unwrap();
+    cg.index_all().await.unwrap();
+
+    let result = handle_tool_call(&cg, "tokensave_external", json!({}), None, None)
+        .await
+        .unwrap();
+    let text = extract_text(&result.value);
+    let output: Value = serde_json::from_str(text).unwrap();
+    assert_eq!(output["package_count"].as_u64().unwrap(), 2, "{text}");
+    let tokio = &output["packages"][0];
+    assert_eq!(tokio["package"], "tokio", "most referenced first: {text}");
+    assert_eq!(tokio["symbol_count"].as_u64().unwrap(), 1);
+    assert_eq!(tokio["reference_count"].as_u64().unwrap(), 2);
+    assert_eq!(tokio["caller_count"].as_u64().unwrap(), 2);
+
+    let result = handle_tool_call(
+        &cg,
+        "tokensave_external",
+        json!({"package": "tokio"}),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let text = extract_text(&result.value);
+    let output: Value = serde_json::from_str(text).unwrap();
+    assert_eq!(output["symbol_count"].as_u64().unwrap(), 1, "{text}");
+    let symbol = &output["symbols"][0];
+    assert_eq!(symbol["symbol"], "tokio::spawn");
+    let callers: Vec<&str> = symbol["callers"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(callers, vec!["start", "stop"]);
+}
+
+#[tokio::test]
+async fn test_search_links_domain_symbols_to_handlers() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+    fs::create_dir_all(project.join("src")).unwrap();
+    fs::write(
+        project.join("src/main.rs"),
+        r#"
+mod primitives;
+use crate::primitives::transpose_regions;
+
+fn install(interp: &mut Interp) {
+    interp.define("transpose-regions", transpose_regions);
+}
+"#,
+    )
+    .unwrap();
+    fs::write(
+        project.join("src/primitives.rs"),
+        "pub fn transpose_regions() {}\n",
+    )
+    .unwrap();
+    TokenSave::init(project).await.unwrap();
+    fs::write(
+        project.join(".tokensave/domain-symbols.toml"),
+        r#"
+[layer]
+name = "elisp-primitive"
+files = ["src/**/*.rs"]
+language = "rust"
+
+[[rule]]
+name = "interp-define"
+form = "call"
+pattern = '(call_expression function: (field_expression field: (field_identifier) @m (#eq? @m "define")) arguments: (arguments . (string_literal) @NAME . (_) @VALUE))'
+name_arg = "$NAME"
+target_arg = "$VALUE"
+"#,
+    )
+    .unwrap();
+    // Domain rules are loaded when the project is opened.
+    let cg = TokenSave::open(project).await.unwrap();
+    cg.index_all().await.unwrap();
+
+    let result = handle_tool_call(
+        &cg,
+        "tokensave_search",
+        json!({"query": "transpose-regions"}),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let text = extract_text(&result.value);
+    let items: Value = serde_json::from_str(text).unwrap();
+    let symbol = items
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|item| item["kind"] == "domain_symbol")
+        .unwrap_or_else(|| panic!("no domain symbol in {text}"));
+    assert_eq!(symbol["name"], "transpose-regions");
+    assert_eq!(symbol["file"], "src/main.rs");
+
+    let links = symbol["links"].as_array().unwrap();
+    let link = |edge: &str| {
+        links
+            .iter()
+            .find(|l| l["edge_kind"] == edge)
+            .unwrap_or_else(|| panic!("no {edge} link in {text}"))
+    };
+    assert_eq!(link("implements")["name"], "transpose_regions");
+    assert_eq!(link("implements")["file"], "src/primitives.rs");
+    assert_eq!(link("registers")["name"], "install");
+}
+
+// ---------------------------------------------------------------------------
+// tokensave_stale_docs
+// ---------------------------------------------------------------------------
+
+#[cfg(feature = "lang-markdown")]
+#[tokio::test]
+async fn test_docs_link_to_code_and_report_stale_mentions() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+    fs::create_dir_all(project.join("src")).unwrap();
+    fs::write(
+        project.join("src/lib.rs"),
+        "pub struct Resolver;\n\nimpl Resolver {\n    pub fn resolve_all(&self) {}\n}\n\npub fn run() {\n    Resolver::resolve_all(&Resolver);\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        project.join("README.md"),
+        "# Usage\n\nCall `Resolver::resolve_all()` from `run`.\n\n## Legacy\n\nThe old `Resolver::resolve_everything()` entry point.\n",
+    )
+    .unwrap();
+    let cg = TokenSave::init(project).await.unwrap();
+    cg.index_all().await.unwrap();
+    let resolve_all = find_node_id(&cg, "resolve_all").await;
+
+    let result = handle_tool_call(
+        &cg,
+        "tokensave_callers",
+        json!({"node_id": resolve_all}),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let text = extract_text(&result.value);
+    let callers: Value = serde_json::from_str(text).unwrap();
+    let callers = callers.as_array().unwrap();
+    assert!(callers.iter().any(|c| c["name"] == "run"), "{text}");
+    assert!(
+        callers
+            .iter()
+            .any(|c| c["name"] == "Usage" && c["file"] == "README.md" && c["edge_kind"] == "uses"),
+        "{text}"
+    );
+
+    // Doc mentions honor min_confidence like every other caller edge.
+    let result = handle_tool_call(
+        &cg,
+        "tokensave_callers",
+        json!({"node_id": resolve_all, "min_confidence": 1.0}),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let text = extract_text(&result.value);
+    let callers: Value = serde_json::from_str(text).unwrap();
+    assert!(
+        callers
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|c| c["file"] != "README.md"),
+        "{text}"
+    );
+
+    let result = handle_tool_call(
+        &cg,
+        "tokensave_rename_preview",
+        json!({"node_id": resolve_all}),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let output: Value = serde_json::from_str(extract_text(&result.value)).unwrap();
+    assert_eq!(output["doc_reference_count"].as_u64().unwrap(), 1);
+
+    let result = handle_tool_call(&cg, "tokensave_stale_docs", json!({}), None, None)
+        .await
+        .unwrap();
+    let text = extract_text(&result.value);
+    let output: Value = serde_json::from_str(text).unwrap();
+    assert_eq!(output["section_count"].as_u64().unwrap(), 1, "{text}");
+    let section = &output["sections"][0];
+    assert_eq!(section["section"], "Legacy");
+    assert_eq!(section["file"], "README.md");
+    assert_eq!(section["stale"][0]["name"], "Resolver::resolve_everything");
+    assert_eq!(section["stale"][0]["line"].as_u64().unwrap(), 6);
+}
+
+#[cfg(feature = "lang-markdown")]
+#[tokio::test]
+async fn test_stale_docs_skip_locals_and_std_types() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+    fs::create_dir_all(project.join("src")).unwrap();
+    fs::write(
+        project.join("src/lib.rs"),
+        "pub struct Graph;\n\nimpl Graph {\n    pub fn load_nodes(&self) {}\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        project.join("README.md"),
+        "# Loading\n\n```rust\nlet graph = Graph;\nlet mut node_ids: HashMap<String, u32> = HashMap::new();\nfor node_id in node_ids.keys() {}\ngraph.load_nodes();\nGraph::removed_fn();\n```\n",
+    )
+    .unwrap();
+    let cg = TokenSave::init(project).await.unwrap();
+    cg.index_all().await.unwrap();
+
+    let result = handle_tool_call(&cg, "tokensave_stale_docs", json!({}), None, None)
+        .await
+        .unwrap();
+    let text = extract_text(&result.value);
+    let output: Value = serde_json::from_str(text).unwrap();
+    assert_eq!(output["section_count"].as_u64().unwrap(), 1, "{text}");
+    let stale: Vec<&str> = output["sections"][0]["stale"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(stale, vec!["Graph::removed_fn"], "{text}");
+}
+
+// ---------------------------------------------------------------------------
+// tokensave_build_targets
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_build_targets_lists_including_and_dependent_targets() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+    fs::write(
+        project.join("CMakeLists.txt"),
+        "add_library(core core.c)\nadd_executable(app main.c)\ntarget_link_libraries(app core)\n",
+    )
+    .unwrap();
+    fs::write(project.join("core.c"), "int core(void) { return 0; }\n").unwrap();
+    fs::write(project.join("main.c"), "int main(void) { return 0; }\n").unwrap();
+    let cg = TokenSave::init(project).await.unwrap();
+    cg.index_all().await.unwrap();
+
+    let result = handle_tool_call(
+        &cg,
+        "tokensave_build_targets",
+        json!({"file": "core.c"}),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let text = extract_text(&result.value);
+    let output: Value = serde_json::from_str(text).unwrap();
+    assert_eq!(output["targets"].as_array().unwrap().len(), 1, "{text}");
+    assert_eq!(output["targets"][0]["name"], "core");
+    assert_eq!(output["targets"][0]["file"], "CMakeLists.txt");
+    let rebuild: Vec<&str> = output["rebuild"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(rebuild, vec!["core", "app"]);
+
+    let missing = handle_tool_call(&cg, "tokensave_build_targets", json!({}), None, None).await;
+    assert!(missing.is_err());
+}
+
+// ---------------------------------------------------------------------------
+// tokensave_routes
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_routes_lists_handlers_and_callers() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+    fs::write(
+        project.join("server.js"),
+        "app.get(\"/api/users/:id\", showUser);\napp.post(\"/api/users\", createUser);\n\nfunction showUser(req, res) {}\nfunction createUser(req, res) {}\n",
+    )
+    .unwrap();
+    fs::write(
+        project.join("client.js"),
+        "export function loadUser(id) {\n  return fetch(`/api/users/${id}`);\n}\n",
+    )
+    .unwrap();
+    let cg = TokenSave::init(project).await.unwrap();
+    cg.index_all().await.unwrap();
+
+    let result = handle_tool_call(&cg, "tokensave_routes", json!({}), None, None)
+        .await
+        .unwrap();
+    let text = extract_text(&result.value);
+    let output: Value = serde_json::from_str(text).unwrap();
+    assert_eq!(output["route_count"].as_u64().unwrap(), 2, "{text}");
+    let show = &output["routes"][0];
+    assert_eq!(show["route"], "GET /api/users/:id");
+    assert_eq!(show["handlers"][0]["name"], "showUser");
+    assert_eq!(show["callers"][0]["name"], "loadUser");
+    assert_eq!(show["callers"][0]["file"], "client.js");
+
+    let result = handle_tool_call(
+        &cg,
+        "tokensave_routes",
+        json!({"method": "post"}),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let output: Value = serde_json::from_str(extract_text(&result.value)).unwrap();
+    assert_eq!(output["route_count"].as_u64().unwrap(), 1);
+    assert_eq!(output["routes"][0]["handlers"][0]["name"], "createUser");
+}
+
+// ---------------------------------------------------------------------------
+// tokensave_sql_access
+// ---------------------------------------------------------------------------
+
+#[cfg(feature = "lang-sql")]
+#[tokio::test]
+async fn test_sql_access_lists_readers_and_writers() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+    fs::write(
+        project.join("schema.sql"),
+        "CREATE TABLE edges (source TEXT, target TEXT, kind TEXT);\n",
+    )
+    .unwrap();
+    fs::write(
+        project.join("store.py"),
+        "def link(db, a, b):\n    db.execute(\"INSERT INTO edges (source, target) VALUES (?, ?)\", (a, b))\n\ndef callers(db, b):\n    return db.execute(\"SELECT source FROM edges WHERE target = ?\", (b,))\n",
+    )
+    .unwrap();
+    let cg = TokenSave::init(project).await.unwrap();
+    cg.index_all().await.unwrap();
+
+    let result = handle_tool_call(
+        &cg,
+        "tokensave_sql_access",
+        json!({"table": "edges"}),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let text = extract_text(&result.value);
+    let output: Value = serde_json::from_str(text).unwrap();
+    assert_eq!(output["definitions"][0]["file"], "schema.sql", "{text}");
+    assert_eq!(output["writer_count"].as_u64().unwrap(), 1);
+    assert_eq!(output["writers"][0]["name"], "link");
+    assert_eq!(
+        output["writers"][0]["touches"],
+        json!(["edges", "edges.source", "edges.target"])
+    );
+    assert_eq!(output["reader_count"].as_u64().unwrap(), 1);
+    assert_eq!(output["readers"][0]["name"], "callers");
+
+    let result = handle_tool_call(
+        &cg,
+        "tokensave_sql_access",
+        json!({"table": "edges", "column": "target", "access": "read"}),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let output: Value = serde_json::from_str(extract_text(&result.value)).unwrap();
+    assert_eq!(output["writer_count"].as_u64().unwrap(), 0);
+    assert_eq!(output["readers"][0]["touches"], json!(["edges.target"]));
+
+    let missing = handle_tool_call(&cg, "tokensave_sql_access", json!({}), None, None).await;
+    assert!(missing.is_err());
+}
+
+// ---------------------------------------------------------------------------
+// tokensave_signature_search
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_signature_search_matches_normalized_types() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+    fs::create_dir_all(project.join("src")).unwrap();
+    fs::write(
+        project.join("src/lib.rs"),
+        r#"
+pub struct Database;
+pub struct Subgraph;
+pub struct Error;
+
+pub fn build(db: &Database, depth: usize) -> Result<Subgraph, Error> { todo!() }
+
+pub fn open(path: &str) -> Result<Database, Error> { todo!() }
+
+pub fn shared(db: std::sync::Arc<Database>) {}
+
+pub fn count(db: &mut crate::Database) -> usize { 0 }
+"#,
+    )
+    .unwrap();
+    let cg = TokenSave::init(project).await.unwrap();
+    cg.index_all().await.unwrap();
+
+    let search = |args: Value| {
+        let cg = &cg;
+        async move {
+            let result = handle_tool_call(cg, "tokensave_signature_search", args, None, None)
+                .await
+                .unwrap();
+            let output: Value = serde_json::from_str(extract_text(&result.value)).unwrap();
+            output["results"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|r| r["name"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        }
+    };
+
+    assert_eq!(
+        search(json!({"param_type": "&Database"})).await,
+        vec!["build", "count"]
+    );
+    assert_eq!(
+        search(json!({"param_type": "Database", "nested": true})).await,
+        vec!["build", "shared", "count"]
+    );
+    assert_eq!(
+        search(json!({"return_type": "Result<Subgraph>"})).await,
+        vec!["build"]
+    );
+    assert_eq!(
+        search(json!({"return_type": "Result<_, Error>"})).await,
+        vec!["build", "open"]
+    );
+    assert_eq!(
+        search(json!({"param_type": "usize", "return_type": "Result"})).await,
+        vec!["build"]
+    );
+
+    let result = handle_tool_call(
+        &cg,
+        "tokensave_signature_search",
+        json!({"return_type": "Result<Subgraph>"}),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let output: Value = serde_json::from_str(extract_text(&result.value)).unwrap();
+    let build = &output["results"][0];
+    assert_eq!(build["params"][0]["name"], "db");
+    assert_eq!(build["params"][0]["type"], "&Database");
+    assert_eq!(build["returns"], "Result<Subgraph, Error>");
+
+    let missing = handle_tool_call(&cg, "tokensave_signature_search", json!({}), None, None).await;
+    assert!(missing.is_err());
+}
+
+#[cfg(feature = "lang-ruby")]
+#[tokio::test]
+async fn test_signature_search_notes_languages_without_signatures() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+    fs::create_dir_all(project.join("src")).unwrap();
+    fs::create_dir_all(project.join("native")).unwrap();
+    fs::write(
+        project.join("src/lib.rs"),
+        "pub fn open(path: &str) -> usize { 0 }\n",
+    )
+    .unwrap();
+    fs::write(
+        project.join("native/codec.c"),
+        "int compress(const char *buf, int len) { return len; }\n",
+    )
+    .unwrap();
+    fs::write(
+        project.join("native/codec.rb"),
+        "def decompress(buf, len)\n  buf\nend\n",
+    )
+    .unwrap();
+    let cg = TokenSave::init(project).await.unwrap();
+    cg.index_all().await.unwrap();
+
+    let search = |args: Value| {
+        let cg = &cg;
+        async move {
+            let result = handle_tool_call(cg, "tokensave_signature_search", args, None, None)
+                .await
+                .unwrap();
+            serde_json::from_str::<Value>(extract_text(&result.value)).unwrap()
+        }
+    };
+    let note = |args: Value| {
+        let search = &search;
+        async move { search(args).await["note"].as_str().map(str::to_string) }
+    };
+
+    let output = search(json!({"param_type": "int"})).await;
+    assert_eq!(output["results"][0]["name"], "compress", "{output}");
+    assert_eq!(
+        output["note"].as_str(),
+        Some("signatures not extracted for Ruby")
+    );
+    assert_eq!(
+        note(json!({"param_type": "str", "path": "src"})).await,
+        None
+    );
+}
+
+// ---------------------------------------------------------------------------
+// tokensave_test_map
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_test_map_uses_detected_tests_not_paths() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+    fs::create_dir_all(project.join("src")).unwrap();
+    fs::write(
+        project.join("src/lib.rs"),
+        r#"
+pub fn parse(s: &str) -> usize { s.len() }
+
+pub fn test_connection() -> bool { true }
+
+pub fn run() -> bool { test_connection() }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> usize { parse("ab") }
+
+    #[test]
+    fn parses() { assert_eq!(fixture(), 2); }
+}
+"#,
+    )
+    .unwrap();
+    let cg = TokenSave::init(project).await.unwrap();
+    cg.index_all().await.unwrap();
+
+    let result = handle_tool_call(
+        &cg,
+        "tokensave_test_map",
+        json!({"file": "src/lib.rs"}),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let text = extract_text(&result.value);
+    let output: Value = serde_json::from_str(text).unwrap();
+    assert_eq!(output["covered_symbols"].as_u64().unwrap(), 1, "{text}");
+    assert_eq!(output["coverage"][0]["source_name"], "parse");
+    assert_eq!(output["test_files"], json!(["src/lib.rs"]));
+    let mut uncovered: Vec<&str> = output["uncovered"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|u| u["name"].as_str())
+        .collect();
+    uncovered.sort_unstable();
+    assert_eq!(uncovered, vec!["run", "test_connection"]);
+}
+
+#[tokio::test]
+async fn test_affected_reports_only_inline_tests_that_reach_the_change() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+    fs::create_dir_all(project.join("src")).unwrap();
+    fs::write(
+        project.join("src/lexer.rs"),
+        "pub fn tokenize(s: &str) -> usize { s.len() }\n",
+    )
+    .unwrap();
+    fs::write(
+        project.join("src/parser.rs"),
+        r#"
+use crate::lexer::tokenize;
+
+pub fn parse(s: &str) -> usize { tokenize(s) }
+
+pub fn precedence() -> u8 { 1 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses() {
+        let n = parse("ab");
+        assert_eq!(n, 2);
+    }
+
+    #[test]
+    fn ranks() {
+        let p = precedence();
+        assert_eq!(p, 1);
+    }
+}
+"#,
+    )
+    .unwrap();
+    fs::write(
+        project.join("src/render.rs"),
+        r#"
+use crate::parser::parse;
+
+pub fn draw(s: &str) -> usize { parse(s) }
+
+pub fn render() -> bool { true }
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn renders() {
+        let ok = super::render();
+        assert!(ok);
+    }
+}
+"#,
+    )
+    .unwrap();
+    let cg = TokenSave::init(project).await.unwrap();
+    cg.index_all().await.unwrap();
+
+    let result = handle_tool_call(
+        &cg,
+        "tokensave_affected",
+        json!({"files": ["src/lexer.rs"]}),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let text = extract_text(&result.value);
+    let output: Value = serde_json::from_str(text).unwrap();
+    assert_eq!(output["affected_tests"], json!(["src/parser.rs"]), "{text}");
+    let cases: Vec<&str> = output["test_cases"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|t| t["name"].as_str())
+        .collect();
+    assert_eq!(cases, vec!["parses"]);
+}
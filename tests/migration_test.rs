@@ -247,7 +247,7 @@ async fn test_create_schema_fresh_db() {
         .await
         .expect("create_schema should succeed");
 
-    assert_eq!(get_user_version(&conn).await, 6);
+    assert_eq!(get_user_version(&conn).await, 14);
     assert!(table_exists(&conn, "nodes").await);
     assert!(table_exists(&conn, "edges").await);
     assert!(table_exists(&conn, "files").await);
@@ -255,6 +255,8 @@ async fn test_create_schema_fresh_db() {
     assert!(table_exists(&conn, "vectors").await);
     assert!(table_exists(&conn, "metadata").await);
     assert!(table_exists(&conn, "nodes_fts").await);
+    assert!(table_exists(&conn, "params").await);
+    assert!(table_exists(&conn, "return_types").await);
 }
 
 /// create_schema is idempotent — calling it twice does not error.
@@ -269,7 +271,7 @@ async fn test_create_schema_idempotent() {
         .await
         .expect("second create_schema should succeed");
 
-    assert_eq!(get_user_version(&conn).await, 6);
+    assert_eq!(get_user_version(&conn).await, 14);
 }
 
 /// migrate returns false when already at the latest version.
@@ -283,11 +285,11 @@ async fn test_migrate_already_latest_returns_false() {
 
     let migrated = migrate(&conn).await.expect("migrate should succeed");
 
-    assert!(!migrated, "migrate should return false when already at v6");
-    assert_eq!(get_user_version(&conn).await, 6);
+    assert!(!migrated, "migrate should return false when already at v14");
+    assert_eq!(get_user_version(&conn).await, 14);
 }
 
-/// migrate from v0 (completely empty database) applies all migrations to v6.
+/// migrate from v0 (completely empty database) applies all migrations to v14.
 #[tokio::test]
 async fn test_migrate_from_v0() {
     let (conn, _db, _dir) = create_raw_db().await;
@@ -303,7 +305,7 @@ async fn test_migrate_from_v0() {
         migrated,
         "migrate should return true when migrations were applied"
     );
-    assert_eq!(get_user_version(&conn).await, 6);
+    assert_eq!(get_user_version(&conn).await, 14);
 
     // All expected tables should exist
     assert!(table_exists(&conn, "nodes").await);
@@ -313,6 +315,8 @@ async fn test_migrate_from_v0() {
     assert!(table_exists(&conn, "vectors").await);
     assert!(table_exists(&conn, "metadata").await);
     assert!(table_exists(&conn, "nodes_fts").await);
+    assert!(table_exists(&conn, "params").await);
+    assert!(table_exists(&conn, "return_types").await);
 
     // V3 complexity columns should exist
     assert!(column_exists(&conn, "nodes", "branches").await);
@@ -327,6 +331,28 @@ async fn test_migrate_from_v0() {
 
     // V5 unique index should exist
     assert!(index_exists(&conn, "idx_edges_unique").await);
+
+    // V7 reference resolution columns should exist
+    assert!(column_exists(&conn, "unresolved_refs", "name_key").await);
+    assert!(column_exists(&conn, "unresolved_refs", "target_node_id").await);
+    assert!(index_exists(&conn, "idx_unresolved_refs_name_key").await);
+
+    // V8 receiver type column should exist
+    assert!(column_exists(&conn, "unresolved_refs", "receiver_type").await);
+
+    // V9 edge provenance columns should exist
+    assert!(column_exists(&conn, "edges", "strategy").await);
+    assert!(column_exists(&conn, "edges", "confidence").await);
+    assert!(column_exists(&conn, "edges", "candidates").await);
+
+    // V12 abi column should exist
+    assert!(column_exists(&conn, "unresolved_refs", "abi").await);
+
+    // V13 http_method column should exist
+    assert!(column_exists(&conn, "unresolved_refs", "http_method").await);
+
+    // V14 sql column should exist
+    assert!(column_exists(&conn, "unresolved_refs", "sql").await);
 }
 
 /// migrate from v1 (tables exist, no metadata, no complexity columns) to v5.
@@ -344,7 +370,7 @@ async fn test_migrate_from_v1() {
         .expect("migrate from v1 should succeed");
 
     assert!(migrated);
-    assert_eq!(get_user_version(&conn).await, 6);
+    assert_eq!(get_user_version(&conn).await, 14);
 
     // V2: metadata table
     assert!(table_exists(&conn, "metadata").await);
@@ -380,7 +406,7 @@ async fn test_migrate_from_v2() {
         .expect("migrate from v2 should succeed");
 
     assert!(migrated);
-    assert_eq!(get_user_version(&conn).await, 6);
+    assert_eq!(get_user_version(&conn).await, 14);
 
     // V3 columns
     assert!(column_exists(&conn, "nodes", "branches").await);
@@ -410,7 +436,7 @@ async fn test_migrate_from_v3() {
         .expect("migrate from v3 should succeed");
 
     assert!(migrated);
-    assert_eq!(get_user_version(&conn).await, 6);
+    assert_eq!(get_user_version(&conn).await, 14);
 
     // V4 columns
     assert!(column_exists(&conn, "nodes", "unsafe_blocks").await);
@@ -438,9 +464,14 @@ async fn test_migrate_from_v4() {
         .expect("migrate from v4 should succeed");
 
     assert!(migrated);
-    assert_eq!(get_user_version(&conn).await, 6);
+    assert_eq!(get_user_version(&conn).await, 14);
 
     assert!(index_exists(&conn, "idx_edges_unique").await);
+    assert!(column_exists(&conn, "unresolved_refs", "name_key").await);
+    assert!(column_exists(&conn, "unresolved_refs", "target_node_id").await);
+    assert!(index_exists(&conn, "idx_unresolved_refs_target_node_id").await);
+    assert!(column_exists(&conn, "unresolved_refs", "receiver_type").await);
+    assert!(column_exists(&conn, "edges", "confidence").await);
 }
 
 /// V5 migration actually deduplicates edge rows.
@@ -528,6 +559,141 @@ async fn test_v5_deduplicates_edges() {
     );
 }
 
+/// V10 migration forgets indexed files so they are re-extracted with
+/// stable node IDs.
+#[tokio::test]
+async fn test_v10_forgets_indexed_files() {
+    let (conn, _db, _dir) = create_raw_db().await;
+    create_schema(&conn)
+        .await
+        .expect("create_schema should succeed");
+    conn.execute(
+        "INSERT INTO files (path, content_hash, size, modified_at, indexed_at) VALUES ('src/lib.rs', 'abc', 10, 1000, 1000)",
+        (),
+    )
+    .await
+    .expect("failed to insert file");
+    conn.execute_batch(
+        "ALTER TABLE unresolved_refs DROP COLUMN abi;
+         ALTER TABLE unresolved_refs DROP COLUMN http_method;
+         ALTER TABLE unresolved_refs DROP COLUMN sql;",
+    )
+    .await
+    .expect("failed to drop reference columns");
+    set_user_version(&conn, 9).await;
+
+    let migrated = migrate(&conn)
+        .await
+        .expect("migrate from v9 should succeed");
+    assert!(migrated);
+
+    let mut rows = conn
+        .query("SELECT COUNT(*) FROM files", ())
+        .await
+        .expect("failed to count files");
+    let row = rows
+        .next()
+        .await
+        .expect("failed to read row")
+        .expect("should have row");
+    let count: i64 = row.get(0).expect("failed to read count");
+    assert_eq!(count, 0, "v10 migration should forget indexed files");
+}
+
+/// V11 migration adds the signature tables to a v10 database.
+#[tokio::test]
+async fn test_v11_adds_signature_tables() {
+    let (conn, _db, _dir) = create_raw_db().await;
+    create_schema(&conn)
+        .await
+        .expect("create_schema should succeed");
+    conn.execute_batch(
+        "DROP TABLE params; DROP TABLE return_types;
+         ALTER TABLE unresolved_refs DROP COLUMN abi;
+         ALTER TABLE unresolved_refs DROP COLUMN http_method;
+         ALTER TABLE unresolved_refs DROP COLUMN sql;",
+    )
+    .await
+    .expect("failed to drop signature tables");
+    set_user_version(&conn, 10).await;
+
+    let migrated = migrate(&conn)
+        .await
+        .expect("migrate from v10 should succeed");
+    assert!(migrated);
+
+    assert!(table_exists(&conn, "params").await);
+    assert!(table_exists(&conn, "return_types").await);
+    assert!(column_exists(&conn, "params", "type_name").await);
+    assert!(column_exists(&conn, "params", "optional").await);
+    assert!(index_exists(&conn, "idx_params_type_name").await);
+    assert!(index_exists(&conn, "idx_return_types_type_name").await);
+}
+
+/// V12 migration adds the abi column to a v11 database.
+#[tokio::test]
+async fn test_v12_adds_abi_column() {
+    let (conn, _db, _dir) = create_raw_db().await;
+    create_schema(&conn)
+        .await
+        .expect("create_schema should succeed");
+    conn.execute_batch(
+        "ALTER TABLE unresolved_refs DROP COLUMN abi;
+         ALTER TABLE unresolved_refs DROP COLUMN http_method;
+         ALTER TABLE unresolved_refs DROP COLUMN sql;",
+    )
+    .await
+    .expect("failed to drop reference columns");
+    set_user_version(&conn, 11).await;
+
+    let migrated = migrate(&conn)
+        .await
+        .expect("migrate from v11 should succeed");
+    assert!(migrated);
+    assert!(column_exists(&conn, "unresolved_refs", "abi").await);
+}
+
+/// V13 migration adds the http_method column to a v12 database.
+#[tokio::test]
+async fn test_v13_adds_http_method_column() {
+    let (conn, _db, _dir) = create_raw_db().await;
+    create_schema(&conn)
+        .await
+        .expect("create_schema should succeed");
+    conn.execute_batch(
+        "ALTER TABLE unresolved_refs DROP COLUMN http_method;
+         ALTER TABLE unresolved_refs DROP COLUMN sql;",
+    )
+    .await
+    .expect("failed to drop reference columns");
+    set_user_version(&conn, 12).await;
+
+    let migrated = migrate(&conn)
+        .await
+        .expect("migrate from v12 should succeed");
+    assert!(migrated);
+    assert!(column_exists(&conn, "unresolved_refs", "http_method").await);
+}
+
+/// V14 migration adds the sql column to a v13 database.
+#[tokio::test]
+async fn test_v14_adds_sql_column() {
+    let (conn, _db, _dir) = create_raw_db().await;
+    create_schema(&conn)
+        .await
+        .expect("create_schema should succeed");
+    conn.execute("ALTER TABLE unresolved_refs DROP COLUMN sql", ())
+        .await
+        .expect("failed to drop sql column");
+    set_user_version(&conn, 13).await;
+
+    let migrated = migrate(&conn)
+        .await
+        .expect("migrate from v13 should succeed");
+    assert!(migrated);
+    assert!(column_exists(&conn, "unresolved_refs", "sql").await);
+}
+
 /// After full migration from v0, all expected indexes exist.
 #[tokio::test]
 async fn test_indexes_exist_after_full_migration() {
@@ -558,9 +724,9 @@ async fn test_indexes_exist_after_full_migration() {
     assert!(index_exists(&conn, "idx_unresolved_refs_file_path").await);
 }
 
-/// Database::initialize creates a v6 database.
+/// Database::initialize creates a v14 database.
 #[tokio::test]
-async fn test_database_initialize_creates_v6() {
+async fn test_database_initialize_creates_v14() {
     let dir = TempDir::new().expect("failed to create temp dir");
     let db_path = dir.path().join("init_test.db");
 
@@ -580,7 +746,7 @@ async fn test_database_initialize_creates_v6() {
         .expect("failed to read row")
         .expect("should have row");
     let version: i64 = row.get(0).expect("failed to read version");
-    assert_eq!(version, 6);
+    assert_eq!(version, 14);
 }
 
 /// Database::open on an already-current database does not re-migrate.
@@ -589,7 +755,7 @@ async fn test_database_open_no_migration_needed() {
     let dir = TempDir::new().expect("failed to create temp dir");
     let db_path = dir.path().join("open_test.db");
 
-    // Initialize creates a v6 database
+    // Initialize creates a v14 database
     let (db, _) = Database::initialize(&db_path)
         .await
         .expect("Database::initialize should succeed");
@@ -606,9 +772,9 @@ async fn test_database_open_no_migration_needed() {
     );
 }
 
-/// Database::open on a v1 database migrates to v6.
+/// Database::open on a v1 database migrates to v14.
 #[tokio::test]
-async fn test_database_open_migrates_v1_to_v6() {
+async fn test_database_open_migrates_v1_to_v14() {
     let dir = TempDir::new().expect("failed to create temp dir");
     let db_path = dir.path().join("open_v1_test.db");
 
@@ -628,14 +794,14 @@ async fn test_database_open_migrates_v1_to_v6() {
         create_v1_schema(&conn).await;
     }
 
-    // Open via Database::open — should detect v1 and migrate to v6
+    // Open via Database::open — should detect v1 and migrate to v14
     let (db, migrated) = Database::open(&db_path)
         .await
         .expect("Database::open should succeed");
 
     assert!(migrated, "opening a v1 database should trigger migration");
 
-    // Verify the schema is now v6
+    // Verify the schema is now v14
     let mut rows = db
         .conn()
         .query("PRAGMA user_version", ())
@@ -647,7 +813,7 @@ async fn test_database_open_migrates_v1_to_v6() {
         .expect("failed to read row")
         .expect("should have row");
     let version: i64 = row.get(0).expect("failed to read version");
-    assert_eq!(version, 6);
+    assert_eq!(version, 14);
 }
 
 /// After create_schema, all v5 columns on nodes exist.
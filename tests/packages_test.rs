@@ -0,0 +1,235 @@
+use std::fs;
+use std::path::Path;
+
+use tempfile::TempDir;
+use tokensave::packages::*;
+use tokensave::tokensave::TokenSave;
+use tokensave::types::*;
+
+fn write(root: &Path, path: &str, contents: &str) {
+    let path = root.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+}
+
+fn find<'a>(packages: &'a [Package], name: &str) -> &'a Package {
+    packages
+        .iter()
+        .find(|p| p.name == name)
+        .unwrap_or_else(|| panic!("no package named {name} in {packages:?}"))
+}
+
+fn files(paths: &[&str]) -> Vec<String> {
+    paths.iter().map(|p| (*p).to_string()).collect()
+}
+
+#[test]
+fn test_cargo_workspace_members_and_path_dependencies() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        "Cargo.toml",
+        "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.dependencies]\ncore = { path = \"crates/core\" }\n",
+    );
+    write(
+        root,
+        "crates/core/Cargo.toml",
+        "[package]\nname = \"core\"\nversion = \"0.1.0\"\n\n[dependencies]\nserde = \"1\"\n",
+    );
+    write(
+        root,
+        "crates/app/Cargo.toml",
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\ncore.workspace = true\n\n[dev-dependencies]\nhelpers = { package = \"test-helpers\", path = \"../helpers\" }\n",
+    );
+    write(
+        root,
+        "crates/helpers/Cargo.toml",
+        "[package]\nname = \"test-helpers\"\n",
+    );
+
+    let packages = discover_packages(
+        root,
+        &files(&[
+            "crates/core/src/lib.rs",
+            "crates/app/src/main.rs",
+            "crates/helpers/src/lib.rs",
+        ]),
+    );
+    assert_eq!(
+        packages.len(),
+        3,
+        "the virtual workspace root is not a package"
+    );
+    let app = find(&packages, "app");
+    assert_eq!(app.ecosystem, Ecosystem::Cargo);
+    assert_eq!(app.dir, "crates/app");
+    assert_eq!(app.manifest, "crates/app/Cargo.toml");
+    assert_eq!(app.dependencies, vec!["core", "test-helpers"]);
+    assert!(
+        find(&packages, "core").dependencies.is_empty(),
+        "external crates are dropped"
+    );
+}
+
+#[test]
+fn test_npm_go_and_maven_manifests() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        "web/package.json",
+        r#"{"name": "@acme/web", "dependencies": {"@acme/ui": "*", "react": "^18"}}"#,
+    );
+    write(root, "ui/package.json", r#"{"name": "@acme/ui"}"#);
+    write(
+        root,
+        "svc/go.mod",
+        "module example.com/svc\n\ngo 1.22\n\nrequire (\n\texample.com/lib v0.0.0 // local\n\tgithub.com/pkg/errors v0.9.1\n)\n",
+    );
+    write(root, "lib/go.mod", "module example.com/lib\n");
+    write(
+        root,
+        "api/pom.xml",
+        "<project>\n  <parent><artifactId>parent</artifactId></parent>\n  <artifactId>api</artifactId>\n  <dependencies>\n    <dependency><groupId>com.acme</groupId><artifactId>model</artifactId></dependency>\n    <!-- <dependency><artifactId>web</artifactId></dependency> -->\n  </dependencies>\n</project>\n",
+    );
+    write(
+        root,
+        "model/pom.xml",
+        "<project><artifactId>model</artifactId></project>",
+    );
+
+    let packages = discover_packages(
+        root,
+        &files(&[
+            "web/src/index.ts",
+            "ui/src/button.ts",
+            "svc/main.go",
+            "lib/lib.go",
+            "api/src/main/java/Api.java",
+            "model/src/main/java/Model.java",
+        ]),
+    );
+    assert_eq!(packages.len(), 6);
+    assert_eq!(find(&packages, "@acme/web").dependencies, vec!["@acme/ui"]);
+    let svc = find(&packages, "svc");
+    assert_eq!(svc.key, "example.com/svc");
+    assert_eq!(svc.dependencies, vec!["example.com/lib"]);
+    assert_eq!(find(&packages, "api").dependencies, vec!["model"]);
+}
+
+#[test]
+fn test_gradle_settings_include_projects() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        "settings.gradle.kts",
+        "rootProject.name = \"shop\"\ninclude(\":app\",\n    \":lib:core\")\n",
+    );
+    write(
+        root,
+        "app/build.gradle.kts",
+        "dependencies {\n    implementation(project(\":lib:core\"))\n}\n",
+    );
+    fs::create_dir_all(root.join("lib/core/src")).unwrap();
+
+    let packages = discover_packages(root, &files(&["app/src/Main.kt"]));
+    assert_eq!(packages.len(), 3);
+    assert_eq!(find(&packages, "shop").manifest, "settings.gradle.kts");
+    let app = find(&packages, "app");
+    assert_eq!(app.key, ":app");
+    assert_eq!(app.dependencies, vec![":lib:core"]);
+    let core = find(&packages, "core");
+    assert_eq!(core.dir, "lib/core");
+    assert_eq!(core.manifest, "lib/core/build.gradle.kts");
+}
+
+#[test]
+fn test_package_map_picks_innermost_package() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    write(root, "package.json", r#"{"name": "root"}"#);
+    write(root, "packages/ui/package.json", r#"{"name": "ui"}"#);
+
+    let packages = discover_packages(root, &files(&["packages/ui/index.ts", "index.ts"]));
+    let (nodes, edges) = package_graph(&packages, ["packages/ui/index.ts", "index.ts"]);
+    let map = PackageMap::new(&nodes, &edges);
+    let ui = map.package_of("packages/ui/index.ts").unwrap();
+    assert_eq!(map.name(ui), Some("ui"));
+    assert_eq!(map.name(map.package_of("index.ts").unwrap()), Some("root"));
+    assert_eq!(
+        map.name(map.package_of("packages/uikit/index.ts").unwrap()),
+        Some("root"),
+        "a directory prefix that is not a path segment does not match"
+    );
+
+    let contains: Vec<&Edge> = edges
+        .iter()
+        .filter(|e| e.kind == EdgeKind::Contains)
+        .collect();
+    assert_eq!(contains.len(), 2);
+    assert!(contains.iter().any(|e| e.source == ui
+        && e.target
+            == generate_node_id(
+                "packages/ui/index.ts",
+                &NodeKind::File,
+                "packages/ui/index.ts",
+                0
+            )));
+}
+
+#[tokio::test]
+async fn test_index_and_sync_build_package_nodes() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        "Cargo.toml",
+        "[workspace]\nmembers = [\"core\", \"app\"]\n",
+    );
+    write(root, "core/Cargo.toml", "[package]\nname = \"core\"\n");
+    write(root, "core/src/lib.rs", "pub fn helper() {}\n");
+    write(
+        root,
+        "app/Cargo.toml",
+        "[package]\nname = \"app\"\n\n[dependencies]\ncore = { path = \"../core\" }\n",
+    );
+    write(root, "app/src/main.rs", "fn main() { helper(); }\n");
+
+    let cg = TokenSave::init(root).await.unwrap();
+    cg.index_all().await.unwrap();
+
+    let packages = cg
+        .db()
+        .get_nodes_by_kind(NodeKind::ManifestPackage)
+        .await
+        .unwrap();
+    assert_eq!(packages.len(), 2);
+    let app = packages.iter().find(|n| n.name == "app").unwrap();
+    let core = packages.iter().find(|n| n.name == "core").unwrap();
+    let outgoing = cg.get_outgoing_edges(&app.id).await.unwrap();
+    assert!(outgoing
+        .iter()
+        .any(|e| e.kind == EdgeKind::DependsOn && e.target == core.id));
+    let main_file = generate_node_id("app/src/main.rs", &NodeKind::File, "app/src/main.rs", 0);
+    assert!(outgoing
+        .iter()
+        .any(|e| e.kind == EdgeKind::Contains && e.target == main_file));
+
+    // Re-indexing a file during sync keeps it linked to its package.
+    write(root, "app/src/main.rs", "fn main() {\n    helper();\n}\n");
+    cg.sync().await.unwrap();
+    let outgoing = cg.get_outgoing_edges(&app.id).await.unwrap();
+    assert!(outgoing
+        .iter()
+        .any(|e| e.kind == EdgeKind::Contains && e.target == main_file));
+
+    // A manifest edit rebuilds the packages.
+    write(root, "app/Cargo.toml", "[package]\nname = \"app\"\n");
+    cg.sync().await.unwrap();
+    let map = PackageMap::load(cg.db()).await.unwrap();
+    let app_id = map.package_of("app/src/main.rs").unwrap();
+    let core_id = map.package_of("core/src/lib.rs").unwrap();
+    assert!(!map.depends_on(app_id, core_id));
+}
@@ -0,0 +1,200 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tempfile::TempDir;
+use tokensave::extraction::LanguageRegistry;
+use tokensave::plugins::*;
+
+fn write_plugin(dir: &Path, name: &str, api_version: u32, extensions: &str) -> PathBuf {
+    let plugin_dir = dir.join(name);
+    fs::create_dir_all(&plugin_dir).unwrap();
+    fs::write(
+        plugin_dir.join(PLUGIN_MANIFEST),
+        format!(
+            "[plugin]\nname = \"{name}\"\nversion = \"1.0.0\"\napi_version = {api_version}\nlibrary = \"lib{name}.so\"\n\n[language]\nname = \"Lang\"\nextensions = {extensions}\n"
+        ),
+    )
+    .unwrap();
+    plugin_dir
+}
+
+#[test]
+fn test_manifest_extensions_and_default_library() {
+    let dir = TempDir::new().unwrap();
+    let plugin_dir = dir.path().join("tokensave-elixir");
+    fs::create_dir_all(&plugin_dir).unwrap();
+    fs::write(
+        plugin_dir.join(PLUGIN_MANIFEST),
+        "[plugin]\nname = \"tokensave-elixir\"\nversion = \"1.2.0\"\napi_version = 1\n\n[language]\nname = \"Elixir\"\nextensions = [\".ex\", \"exs\"]\n",
+    )
+    .unwrap();
+
+    let plugin = Plugin::load(&plugin_dir).unwrap();
+    assert_eq!(plugin.name(), "tokensave-elixir");
+    assert_eq!(
+        plugin.manifest.extensions().collect::<Vec<_>>(),
+        vec!["ex", "exs"]
+    );
+    let library = plugin.library_path();
+    let file = library.file_name().unwrap().to_str().unwrap();
+    assert!(file.contains("tokensave_elixir"), "{file}");
+    assert!(file.ends_with(std::env::consts::DLL_SUFFIX), "{file}");
+}
+
+#[test]
+fn test_discovery_skips_invalid_plugins_and_keeps_first_name() {
+    let first = TempDir::new().unwrap();
+    let second = TempDir::new().unwrap();
+    write_plugin(first.path(), "alpha", PLUGIN_API_VERSION, "[\"al\"]");
+    write_plugin(second.path(), "alpha", PLUGIN_API_VERSION, "[\"other\"]");
+    write_plugin(second.path(), "beta", PLUGIN_API_VERSION, "[\"be\"]");
+    write_plugin(second.path(), "future", PLUGIN_API_VERSION + 1, "[\"fu\"]");
+    write_plugin(second.path(), "empty", PLUGIN_API_VERSION, "[]");
+    fs::create_dir_all(second.path().join("not-a-plugin")).unwrap();
+
+    let (plugins, errors) =
+        discover_plugins_in(&[first.path().to_path_buf(), second.path().to_path_buf()]);
+    let names: Vec<&str> = plugins.iter().map(Plugin::name).collect();
+    assert_eq!(names, vec!["alpha", "beta"]);
+    assert_eq!(
+        plugins[0].manifest.extensions().collect::<Vec<_>>(),
+        vec!["al"]
+    );
+    assert_eq!(errors.len(), 2, "{errors:?}");
+    let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+    assert!(
+        messages.iter().any(|m| m.contains("plugin API")),
+        "{messages:?}"
+    );
+    assert!(
+        messages.iter().any(|m| m.contains("no file extensions")),
+        "{messages:?}"
+    );
+}
+
+#[test]
+fn test_install_copies_manifest_and_library_and_remove_deletes() {
+    let source = TempDir::new().unwrap();
+    let installed = TempDir::new().unwrap();
+    let plugin_dir = write_plugin(source.path(), "gamma", PLUGIN_API_VERSION, "[\"ga\"]");
+
+    let err = install_plugin(&plugin_dir, installed.path()).unwrap_err();
+    assert!(err.to_string().contains("no shared library"), "{err}");
+
+    fs::write(plugin_dir.join("libgamma.so"), b"not really a library").unwrap();
+    fs::write(plugin_dir.join("Cargo.toml"), "[package]").unwrap();
+    let plugin = install_plugin(&plugin_dir, installed.path()).unwrap();
+    assert_eq!(plugin.dir, installed.path().join("gamma"));
+    assert!(plugin.library_path().is_file());
+    assert!(!plugin.dir.join("Cargo.toml").exists());
+
+    assert!(remove_plugin("gamma", installed.path()).unwrap());
+    assert!(!installed.path().join("gamma").exists());
+    assert!(!remove_plugin("gamma", installed.path()).unwrap());
+    assert!(!remove_plugin("../gamma", installed.path()).unwrap());
+}
+
+#[test]
+fn test_install_rejects_library_outside_plugin_dir() {
+    let source = TempDir::new().unwrap();
+    let installed = TempDir::new().unwrap();
+    let plugin_dir = write_plugin(source.path(), "eta", PLUGIN_API_VERSION, "[\"et\"]");
+    let manifest = fs::read_to_string(plugin_dir.join(PLUGIN_MANIFEST)).unwrap();
+    fs::create_dir_all(plugin_dir.join("target/release")).unwrap();
+    fs::write(
+        plugin_dir.join("target/release/libeta.so"),
+        b"not really a library",
+    )
+    .unwrap();
+    let absolute = plugin_dir.join("target/release/libeta.so");
+
+    for library in [
+        "target/release/libeta.so",
+        absolute.to_str().unwrap(),
+        "../libeta.so",
+    ] {
+        fs::write(
+            plugin_dir.join(PLUGIN_MANIFEST),
+            manifest.replace("libeta.so", library),
+        )
+        .unwrap();
+        let err = install_plugin(&plugin_dir, installed.path()).unwrap_err();
+        assert!(
+            err.to_string().contains("must be a file name"),
+            "{library}: {err}"
+        );
+        assert!(!installed.path().join("eta").exists(), "{library}");
+    }
+
+    fs::write(plugin_dir.join(PLUGIN_MANIFEST), &manifest).unwrap();
+    fs::write(plugin_dir.join("libeta.so"), b"not really a library").unwrap();
+    install_plugin(&plugin_dir, installed.path()).unwrap();
+    let plugin = Plugin::load(&installed.path().join("eta")).unwrap();
+    assert_eq!(
+        plugin.library_path(),
+        installed.path().join("eta/libeta.so")
+    );
+    assert!(plugin.library_path().is_file());
+}
+
+#[test]
+fn test_registry_leaves_plugin_files_to_workers() {
+    let dir = TempDir::new().unwrap();
+    let plugin_dir = write_plugin(dir.path(), "delta", PLUGIN_API_VERSION, "[\"dl\", \"go\"]");
+    fs::write(plugin_dir.join("libdelta.so"), b"not really a library").unwrap();
+    std::env::set_var(PLUGIN_PATH_ENV, dir.path());
+
+    // The indexing process claims plugin extensions, even over built-ins,
+    // without loading anything.
+    let registry = LanguageRegistry::with_installed_plugins();
+    assert!(registry.supported_extensions().contains(&"dl"));
+    assert!(registry.is_worker_file("src/a.dl"));
+    assert!(registry.extractor_for_file("src/a.dl").is_none());
+    assert!(registry.extractor_for_file("main.go").is_none());
+    assert!(registry.extractor_for_file("main.rs").is_some());
+
+    // Workers load plugins; one that fails to load leaves its extensions to
+    // the built-in extractors.
+    let registry = LanguageRegistry::with_loaded_plugins();
+    assert!(!registry.is_worker_file("src/a.dl"));
+    assert!(registry.extractor_for_file("src/a.dl").is_none());
+    assert_eq!(
+        registry
+            .extractor_for_file("main.go")
+            .unwrap()
+            .language_name(),
+        "Go"
+    );
+
+    let plugin = Plugin::load(&plugin_dir).unwrap();
+    let Err(err) = PluginExtractor::load(&plugin) else {
+        panic!("loaded a library that is not one");
+    };
+    assert!(
+        err.to_string().contains("cannot load plugin 'delta'"),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_version_1_results_still_decode() {
+    // A result as a plugin built against the first API version encodes it:
+    // no edge provenance, receiver types or signatures.
+    let json = r#"{
+        "nodes": [],
+        "edges": [{"source": "function:a", "target": "function:b", "kind": "Calls", "line": 3}],
+        "unresolved_refs": [{
+            "from_node_id": "function:a", "reference_name": "helper",
+            "reference_kind": "Calls", "line": 4, "column": 2, "file_path": "a.dl"
+        }],
+        "errors": [],
+        "duration_ms": 0
+    }"#;
+    let result: tokensave::types::ExtractionResult = serde_json::from_str(json).unwrap();
+    assert!(result.edges[0].provenance.is_none());
+    assert!(result.unresolved_refs[0].receiver_type.is_none());
+    assert!(result.unresolved_refs[0].abi.is_none());
+    assert!(result.unresolved_refs[0].http_method.is_none());
+    assert!(!result.unresolved_refs[0].sql);
+    assert!(result.signatures.is_empty());
+}
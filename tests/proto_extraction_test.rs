@@ -318,3 +318,173 @@ fn test_proto_message_docstring_connection_config() {
         conn.docstring
     );
 }
+
+#[tokio::test]
+async fn test_proto_rpc_impact_reaches_servers_and_clients() {
+    use std::fs;
+    use tokensave::tokensave::TokenSave;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let project = dir.path();
+    fs::create_dir_all(project.join("api")).unwrap();
+    fs::write(
+        project.join("api/user.proto"),
+        r#"syntax = "proto3";
+package users.v1;
+
+message GetUserByIDRequest {
+  string id = 1;
+}
+
+message User {
+  string name = 1;
+}
+
+service UserService {
+  rpc GetUserByID(GetUserByIDRequest) returns (User);
+}
+"#,
+    )
+    .unwrap();
+    fs::write(
+        project.join("api/user.pb.go"),
+        "package api\n\ntype GetUserByIDRequest struct {\n\tId string\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        project.join("server.go"),
+        "package main\n\ntype server struct{}\n\nfunc (s *server) GetUserByID(ctx context.Context, req *api.GetUserByIDRequest) (*api.User, error) {\n\treturn nil, nil\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        project.join("service.rs"),
+        "pub struct Users;\n\nimpl UserService for Users {\n    async fn get_user_by_id(&self, request: Request<GetUserByIDRequest>) -> Result<Response<User>, Status> {\n        todo!()\n    }\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        project.join("client.ts"),
+        "export async function loadUser(client: UserServiceClient, id: string) {\n  return client.getUserByID({ id });\n}\n",
+    )
+    .unwrap();
+    // A hand-written type sharing a message's name is not generated code.
+    fs::write(
+        project.join("model.go"),
+        "package main\n\ntype User struct {\n\tName string\n}\n",
+    )
+    .unwrap();
+
+    let ts = TokenSave::init(project).await.unwrap();
+    ts.index_all().await.unwrap();
+
+    let schema = ts.get_nodes_by_file("api/user.proto").await.unwrap();
+    let rpc = schema.iter().find(|n| n.name == "GetUserByID").unwrap();
+    let impact = ts.get_impact_radius(&rpc.id, 1, false, 0.0).await.unwrap();
+    let mut names: Vec<(&str, &str)> = impact
+        .nodes
+        .iter()
+        .filter(|n| n.file_path != rpc.file_path)
+        .map(|n| (n.file_path.as_str(), n.name.as_str()))
+        .collect();
+    names.sort_unstable();
+    assert_eq!(
+        names,
+        vec![
+            ("client.ts", "loadUser"),
+            ("server.go", "GetUserByID"),
+            ("service.rs", "get_user_by_id"),
+        ]
+    );
+
+    let request = schema
+        .iter()
+        .find(|n| n.name == "GetUserByIDRequest")
+        .unwrap();
+    let generated = ts.get_incoming_edges(&request.id).await.unwrap();
+    let go = ts.get_nodes_by_file("api/user.pb.go").await.unwrap();
+    assert!(generated
+        .iter()
+        .any(|e| e.kind == EdgeKind::Implements && go.iter().any(|n| n.id == e.source)));
+    let user = schema.iter().find(|n| n.name == "User").unwrap();
+    let model = ts.get_nodes_by_file("model.go").await.unwrap();
+    assert!(ts
+        .get_incoming_edges(&user.id)
+        .await
+        .unwrap()
+        .iter()
+        .all(|e| model.iter().all(|n| n.id != e.source)));
+
+    // A client added later is linked on sync.
+    fs::write(
+        project.join("Client.java"),
+        "class Client {\n  User fetch(UserServiceGrpc.UserServiceBlockingStub stub) {\n    return stub.getUserByID(GetUserByIDRequest.newBuilder().build());\n  }\n}\n",
+    )
+    .unwrap();
+    ts.sync().await.unwrap();
+    let java = ts.get_nodes_by_file("Client.java").await.unwrap();
+    let fetch = java.iter().find(|n| n.name == "fetch").unwrap();
+    assert!(ts
+        .get_outgoing_edges(&fetch.id)
+        .await
+        .unwrap()
+        .iter()
+        .any(|e| e.kind == EdgeKind::Calls && e.target == rpc.id));
+}
+
+#[tokio::test]
+async fn test_proto_messages_link_to_prost_generated_rust_types() {
+    use std::fs;
+    use tokensave::tokensave::TokenSave;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let project = dir.path();
+    fs::create_dir_all(project.join("api")).unwrap();
+    fs::create_dir_all(project.join("src/pb")).unwrap();
+    fs::write(
+        project.join("api/user.proto"),
+        "syntax = \"proto3\";\npackage users.v1;\n\nmessage User {\n  string name = 1;\n}\n\nmessage Team {\n  string name = 1;\n}\n",
+    )
+    .unwrap();
+    // prost names the file after the package, not the schema.
+    fs::write(
+        project.join("src/pb/users.v1.rs"),
+        "pub struct User {\n    pub name: String,\n}\n",
+    )
+    .unwrap();
+    // A hand-written type in a file sharing the schema's stem is not.
+    fs::write(
+        project.join("src/user.rs"),
+        "pub struct User {\n    pub name: String,\n}\n",
+    )
+    .unwrap();
+
+    let ts = TokenSave::init(project).await.unwrap();
+    ts.index_all().await.unwrap();
+
+    let schema = ts.get_nodes_by_file("api/user.proto").await.unwrap();
+    let implementers = |name: &str| {
+        let message = schema.iter().find(|n| n.name == name).unwrap().id.clone();
+        let ts = &ts;
+        async move {
+            let mut files = Vec::new();
+            for edge in ts.get_incoming_edges(&message).await.unwrap() {
+                if edge.kind == EdgeKind::Implements {
+                    let node = ts.get_node(&edge.source).await.unwrap().unwrap();
+                    files.push(node.file_path);
+                }
+            }
+            files.sort_unstable();
+            files
+        }
+    };
+    assert_eq!(implementers("User").await, vec!["src/pb/users.v1.rs"]);
+
+    // A module per package segment, added later, is linked on sync.
+    fs::create_dir_all(project.join("src/gen/users")).unwrap();
+    fs::write(
+        project.join("src/gen/users/v1.rs"),
+        "pub struct Team {\n    pub name: String,\n}\n",
+    )
+    .unwrap();
+    ts.sync().await.unwrap();
+    assert_eq!(implementers("Team").await, vec!["src/gen/users/v1.rs"]);
+}
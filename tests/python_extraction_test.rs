@@ -570,3 +570,54 @@ fn test_py_extensions() {
     assert_eq!(extractor.extensions(), &["py"]);
     assert_eq!(extractor.language_name(), "Python");
 }
+
+#[test]
+fn test_python_type_reference_refs() {
+    let source = r#"def find(repo, uid: UserId, limit: int = 10) -> Optional[models.Account]:
+    pass
+"#;
+    let extractor = PythonExtractor;
+    let result = extractor.extract("service.py", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    let names = |kind: EdgeKind| -> Vec<String> {
+        result
+            .unresolved_refs
+            .iter()
+            .filter(|r| r.reference_kind == kind)
+            .map(|r| r.reference_name.clone())
+            .collect()
+    };
+    let receives = names(EdgeKind::Receives);
+    let returns = names(EdgeKind::Returns);
+    assert_eq!(receives, vec!["UserId"]);
+    assert_eq!(returns, vec!["models.Account"]);
+}
+
+#[test]
+fn test_python_call_receiver_types() {
+    let source = r#"class Server:
+    db: Database
+
+    def run(self, client: Client, retries: int = 3):
+        conn: Conn = open()
+        self.db.get_node(1)
+        client.send(2)
+        conn.close()
+        self.flush()
+"#;
+    let extractor = PythonExtractor;
+    let result = extractor.extract("server.py", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    let receiver = |name: &str| -> Option<String> {
+        result
+            .unresolved_refs
+            .iter()
+            .find(|r| r.reference_kind == EdgeKind::Calls && r.reference_name == name)
+            .and_then(|r| r.receiver_type.clone())
+    };
+    assert_eq!(receiver("self.db.get_node").as_deref(), Some("Database"));
+    assert_eq!(receiver("client.send").as_deref(), Some("Client"));
+    assert_eq!(receiver("conn.close").as_deref(), Some("Conn"));
+    assert_eq!(receiver("self.flush").as_deref(), Some("Server"));
+    assert_eq!(receiver("open").as_deref(), None);
+}
@@ -1,5 +1,6 @@
 use tempfile::TempDir;
 use tokensave::db::Database;
+use tokensave::packages::{package_graph, Ecosystem, Package, PackageMap};
 use tokensave::resolution::ReferenceResolver;
 use tokensave::types::*;
 
@@ -80,6 +81,10 @@ async fn test_resolve_exact_name_match() {
         line: 3,
         column: 12,
         file_path: "src/main.rs".to_string(),
+        receiver_type: None,
+        abi: None,
+        http_method: None,
+        sql: false,
     };
 
     let result = resolver.resolve_one(&uref);
@@ -108,6 +113,10 @@ async fn test_resolve_qualified_name_match() {
         line: 3,
         column: 12,
         file_path: "src/main.rs".to_string(),
+        receiver_type: None,
+        abi: None,
+        http_method: None,
+        sql: false,
     };
 
     let result = resolver.resolve_one(&uref);
@@ -119,6 +128,7 @@ async fn test_resolve_qualified_name_match() {
         resolved.confidence
     );
     assert_eq!(resolved.resolved_by, "qualified-match");
+    assert_eq!(resolved.strategy, ResolutionStrategy::ExactQualified);
 }
 
 #[tokio::test]
@@ -133,6 +143,10 @@ async fn test_resolve_all() {
         line: 3,
         column: 12,
         file_path: "src/main.rs".to_string(),
+        receiver_type: None,
+        abi: None,
+        http_method: None,
+        sql: false,
     }];
 
     let result = resolver.resolve_all(&refs);
@@ -154,6 +168,10 @@ async fn test_unresolvable_reference() {
         line: 5,
         column: 8,
         file_path: "src/main.rs".to_string(),
+        receiver_type: None,
+        abi: None,
+        http_method: None,
+        sql: false,
     };
 
     assert!(
@@ -175,6 +193,10 @@ async fn test_unresolvable_in_resolve_all() {
             line: 3,
             column: 12,
             file_path: "src/main.rs".to_string(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
         },
         UnresolvedRef {
             from_node_id: "function:caller".to_string(),
@@ -183,6 +205,10 @@ async fn test_unresolvable_in_resolve_all() {
             line: 5,
             column: 8,
             file_path: "src/main.rs".to_string(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
         },
     ];
 
@@ -206,10 +232,15 @@ async fn test_creates_edges_from_resolved() {
             line: 3,
             column: 12,
             file_path: "src/main.rs".to_string(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
         },
         target_node_id: generate_node_id("src/utils.rs", &NodeKind::Function, "helper", 1),
         confidence: 0.9,
         resolved_by: "exact-match".to_string(),
+        strategy: ResolutionStrategy::Heuristic,
     };
 
     let edges = resolver.create_edges(&[resolved]);
@@ -224,6 +255,13 @@ async fn test_creates_edges_from_resolved() {
         edges[0].target,
         generate_node_id("src/utils.rs", &NodeKind::Function, "helper", 1)
     );
+    assert_eq!(
+        edges[0].provenance,
+        Some(EdgeProvenance {
+            strategy: ResolutionStrategy::Heuristic,
+            confidence: 0.9,
+        })
+    );
 }
 
 #[tokio::test]
@@ -326,6 +364,10 @@ async fn test_multiple_candidates_best_match_scoring() {
         line: 3,
         column: 4,
         file_path: "src/main.rs".to_string(),
+        receiver_type: None,
+        abi: None,
+        http_method: None,
+        sql: false,
     };
 
     let result = resolver.resolve_one(&uref);
@@ -340,6 +382,10 @@ async fn test_multiple_candidates_best_match_scoring() {
         "multiple-match confidence should be 0.7, got {}",
         resolved.confidence
     );
+    assert_eq!(
+        resolved.strategy,
+        ResolutionStrategy::Ambiguous { candidates: 2 }
+    );
 }
 
 #[tokio::test]
@@ -362,3 +408,844 @@ async fn test_resolve_all_empty_input() {
     assert!(result.resolved.is_empty());
     assert!(result.unresolved.is_empty());
 }
+
+// ---------------------------------------------------------------------------
+// Import-aware resolution
+// ---------------------------------------------------------------------------
+
+/// Builds a node whose qualified name is `file::name`.
+fn make_node(kind: NodeKind, name: &str, file: &str, line: u32, signature: &str) -> Node {
+    Node {
+        id: generate_node_id(file, &kind, name, line),
+        kind,
+        name: name.to_string(),
+        qualified_name: format!("{file}::{name}"),
+        file_path: file.to_string(),
+        start_line: line,
+        end_line: line + 3,
+        start_column: 0,
+        end_column: 1,
+        signature: Some(signature.to_string()),
+        docstring: None,
+        visibility: Visibility::Pub,
+        is_async: false,
+        branches: 0,
+        loops: 0,
+        returns: 0,
+        max_nesting: 0,
+        unsafe_blocks: 0,
+        unchecked_calls: 0,
+        assertions: 0,
+        updated_at: 0,
+    }
+}
+
+fn call_ref(file: &str, name: &str) -> UnresolvedRef {
+    UnresolvedRef {
+        from_node_id: generate_node_id(file, &NodeKind::Function, "caller", 10),
+        reference_name: name.to_string(),
+        reference_kind: EdgeKind::Calls,
+        line: 12,
+        column: 4,
+        file_path: file.to_string(),
+        receiver_type: None,
+        abi: None,
+        http_method: None,
+        sql: false,
+    }
+}
+
+async fn empty_db() -> (TempDir, Database) {
+    let dir = TempDir::new().expect("failed to create temp dir");
+    let (db, _) = Database::initialize(&dir.path().join("test.db"))
+        .await
+        .expect("failed to init db");
+    (dir, db)
+}
+
+#[tokio::test]
+async fn test_import_prefers_imported_module_over_nearby_candidate() {
+    let (_dir, db) = empty_db().await;
+    let imported = make_node(
+        NodeKind::Function,
+        "parse",
+        "src/config.rs",
+        1,
+        "fn parse()",
+    );
+    // Closer by path proximity, but not the one the caller imports.
+    let nearby = make_node(
+        NodeKind::Function,
+        "parse",
+        "src/app/cli/args.rs",
+        1,
+        "fn parse()",
+    );
+    let use_node = make_node(
+        NodeKind::Use,
+        "crate::config::parse",
+        "src/app/cli/main.rs",
+        1,
+        "use crate::config::parse;",
+    );
+    let resolver = ReferenceResolver::from_nodes(&db, &[imported.clone(), nearby, use_node]);
+
+    let resolved = resolver
+        .resolve_one(&call_ref("src/app/cli/main.rs", "parse"))
+        .expect("should resolve through the import");
+    assert_eq!(resolved.target_node_id, imported.id);
+    assert_eq!(resolved.resolved_by, "import-match");
+    assert_eq!(resolved.strategy, ResolutionStrategy::ImportScoped);
+    assert!((resolved.confidence - 0.95).abs() < f64::EPSILON);
+}
+
+#[tokio::test]
+async fn test_import_follows_rust_alias_in_use_group() {
+    let (_dir, db) = empty_db().await;
+    let imported = make_node(
+        NodeKind::Function,
+        "parse",
+        "src/config/mod.rs",
+        1,
+        "fn parse()",
+    );
+    let other = make_node(NodeKind::Function, "load", "src/main.rs", 40, "fn load()");
+    let use_node = make_node(
+        NodeKind::Use,
+        "crate::config::{parse as load, Config}",
+        "src/main.rs",
+        1,
+        "use crate::config::{parse as load, Config};",
+    );
+    let resolver = ReferenceResolver::from_nodes(&db, &[imported.clone(), other, use_node]);
+
+    let resolved = resolver
+        .resolve_one(&call_ref("src/main.rs", "load"))
+        .expect("alias should resolve to the imported function");
+    assert_eq!(resolved.target_node_id, imported.id);
+}
+
+#[tokio::test]
+async fn test_import_follows_typescript_named_alias() {
+    let (_dir, db) = empty_db().await;
+    let imported = make_node(
+        NodeKind::Function,
+        "format",
+        "web/src/util/index.ts",
+        1,
+        "function format()",
+    );
+    let decoy = make_node(
+        NodeKind::Function,
+        "format",
+        "web/src/views/format.ts",
+        1,
+        "function format()",
+    );
+    let use_node = make_node(
+        NodeKind::Use,
+        "../util",
+        "web/src/views/page.ts",
+        1,
+        "import { format as fmt } from '../util';",
+    );
+    let resolver = ReferenceResolver::from_nodes(&db, &[imported.clone(), decoy, use_node]);
+
+    let result = resolver.resolve_all(&[call_ref("web/src/views/page.ts", "fmt")]);
+    assert_eq!(result.resolved_count, 1);
+    assert_eq!(result.resolved[0].target_node_id, imported.id);
+}
+
+#[tokio::test]
+async fn test_import_resolves_python_module_alias_attribute() {
+    let (_dir, db) = empty_db().await;
+    let imported = make_node(
+        NodeKind::Function,
+        "load",
+        "pkg/storage/disk.py",
+        1,
+        "def load()",
+    );
+    let decoy = make_node(
+        NodeKind::Function,
+        "load",
+        "pkg/net/http.py",
+        1,
+        "def load()",
+    );
+    let use_node = make_node(
+        NodeKind::Use,
+        "pkg.storage.disk",
+        "pkg/app.py",
+        1,
+        "import pkg.storage.disk as disk",
+    );
+    let resolver = ReferenceResolver::from_nodes(&db, &[imported.clone(), decoy, use_node]);
+
+    let result = resolver.resolve_all(&[call_ref("pkg/app.py", "disk.load")]);
+    assert_eq!(result.resolved_count, 1);
+    assert_eq!(result.resolved[0].target_node_id, imported.id);
+}
+
+#[tokio::test]
+async fn test_external_import_shadows_local_symbol() {
+    let (_dir, db) = empty_db().await;
+    let local = make_node(
+        NodeKind::Function,
+        "debounce",
+        "src/timing.ts",
+        1,
+        "function debounce()",
+    );
+    let use_node = make_node(
+        NodeKind::Use,
+        "lodash",
+        "src/search.ts",
+        1,
+        "import { debounce } from 'lodash';",
+    );
+    let resolver = ReferenceResolver::from_nodes(&db, &[local.clone(), use_node]);
+
+    let resolved = resolver
+        .resolve_one(&call_ref("src/search.ts", "debounce"))
+        .expect("an external import resolves to an external symbol");
+    assert_eq!(resolved.strategy, ResolutionStrategy::External);
+    assert_ne!(
+        resolved.target_node_id, local.id,
+        "a name imported from an external package must not bind to a local symbol"
+    );
+    // Files without that import still resolve normally.
+    assert_eq!(
+        resolver
+            .resolve_one(&call_ref("src/other.ts", "debounce"))
+            .map(|r| r.target_node_id),
+        Some(local.id)
+    );
+}
+
+#[tokio::test]
+async fn test_glob_import_prefers_matching_module() {
+    let (_dir, db) = empty_db().await;
+    let wanted = make_node(
+        NodeKind::Function,
+        "helper",
+        "src/b/util.rs",
+        1,
+        "fn helper()",
+    );
+    let decoy = make_node(
+        NodeKind::Function,
+        "helper",
+        "src/a/mod.rs",
+        1,
+        "fn helper()",
+    );
+    let use_node = make_node(
+        NodeKind::Use,
+        "crate::b::util::*",
+        "src/a/run.rs",
+        1,
+        "use crate::b::util::*;",
+    );
+    let resolver = ReferenceResolver::from_nodes(&db, &[wanted.clone(), decoy, use_node]);
+
+    let resolved = resolver
+        .resolve_one(&call_ref("src/a/run.rs", "helper"))
+        .expect("should resolve through the glob import");
+    assert_eq!(resolved.target_node_id, wanted.id);
+    assert_eq!(resolved.resolved_by, "import-glob");
+}
+
+// ---------------------------------------------------------------------------
+// Type references
+// ---------------------------------------------------------------------------
+
+fn type_ref(file: &str, name: &str, kind: EdgeKind) -> UnresolvedRef {
+    UnresolvedRef {
+        reference_kind: kind,
+        ..call_ref(file, name)
+    }
+}
+
+#[tokio::test]
+async fn test_type_ref_skips_function_with_same_name() {
+    let (_dir, db) = empty_db().await;
+    let ctor_fn = make_node(NodeKind::Function, "UserId", "src/ids.rs", 1, "fn UserId()");
+    let strukt = make_node(
+        NodeKind::Struct,
+        "UserId",
+        "src/model.rs",
+        1,
+        "struct UserId",
+    );
+    let resolver = ReferenceResolver::from_nodes(&db, &[ctor_fn, strukt.clone()]);
+
+    let resolved = resolver
+        .resolve_one(&type_ref("src/api.rs", "UserId", EdgeKind::Receives))
+        .expect("parameter type should resolve to the struct");
+    assert_eq!(resolved.target_node_id, strukt.id);
+}
+
+#[tokio::test]
+async fn test_type_ref_does_not_bind_to_lone_function() {
+    let (_dir, db) = empty_db().await;
+    let func = make_node(NodeKind::Function, "Config", "src/a.rs", 1, "fn Config()");
+    let resolver = ReferenceResolver::from_nodes(&db, &[func]);
+
+    assert!(resolver
+        .resolve_one(&type_ref("src/b.rs", "Config", EdgeKind::TypeOf))
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_dotted_type_ref_falls_back_to_simple_name() {
+    let (_dir, db) = empty_db().await;
+    let class = make_node(NodeKind::Class, "User", "app/models.py", 1, "class User:");
+    let resolver = ReferenceResolver::from_nodes(&db, std::slice::from_ref(&class));
+
+    let resolved = resolver
+        .resolve_one(&type_ref("app/views.py", "models.User", EdgeKind::Returns))
+        .expect("dotted return type should resolve by its last segment");
+    assert_eq!(resolved.target_node_id, class.id);
+}
+
+// ---------------------------------------------------------------------------
+// Receiver types
+// ---------------------------------------------------------------------------
+
+fn method_node(owner: &str, name: &str, file: &str, line: u32, signature: &str) -> Node {
+    Node {
+        qualified_name: format!("{file}::{owner}::{name}"),
+        ..make_node(NodeKind::Method, name, file, line, signature)
+    }
+}
+
+fn method_call(file: &str, name: &str, receiver_type: &str) -> UnresolvedRef {
+    UnresolvedRef {
+        receiver_type: Some(receiver_type.to_string()),
+        abi: None,
+        http_method: None,
+        sql: false,
+        ..call_ref(file, name)
+    }
+}
+
+#[tokio::test]
+async fn test_receiver_type_picks_method_on_declared_type() {
+    let (_dir, db) = empty_db().await;
+    let wanted = method_node("Database", "get_node", "src/db.rs", 1, "fn get_node(&self)");
+    // Same file as the caller, so it would win on name alone.
+    let decoy = method_node(
+        "Cache",
+        "get_node",
+        "src/server.rs",
+        1,
+        "fn get_node(&self)",
+    );
+    let resolver = ReferenceResolver::from_nodes(&db, &[wanted.clone(), decoy]);
+
+    let resolved = resolver
+        .resolve_one(&method_call(
+            "src/server.rs",
+            "self.db.get_node",
+            "Database",
+        ))
+        .expect("call should resolve through its receiver type");
+    assert_eq!(resolved.target_node_id, wanted.id);
+    assert_eq!(resolved.resolved_by, "receiver-type");
+}
+
+#[tokio::test]
+async fn test_receiver_type_reads_go_method_receiver() {
+    let (_dir, db) = empty_db().await;
+    let mut wanted = make_node(
+        NodeKind::Method,
+        "Close",
+        "net/conn.go",
+        1,
+        "func (c *Conn) Close() error",
+    );
+    wanted.qualified_name = "net/conn.go::Close".to_string();
+    let decoy = make_node(
+        NodeKind::Method,
+        "Close",
+        "net/server.go",
+        1,
+        "func (f *File) Close() error",
+    );
+    let resolver = ReferenceResolver::from_nodes(&db, &[wanted.clone(), decoy]);
+
+    let resolved = resolver
+        .resolve_one(&method_call("net/server.go", "conn.Close", "Conn"))
+        .expect("call should resolve to the Conn method");
+    assert_eq!(resolved.target_node_id, wanted.id);
+}
+
+#[tokio::test]
+async fn test_receiver_type_follows_implemented_trait() {
+    let (_dir, db) = empty_db().await;
+    let trait_method = method_node("Store", "put", "src/store.rs", 3, "fn put(&self)");
+    let server_impl = make_node(
+        NodeKind::Impl,
+        "Server",
+        "src/server.rs",
+        1,
+        "impl Store for Server",
+    );
+    let decoy = make_node(NodeKind::Function, "put", "src/server.rs", 20, "fn put()");
+    let implements = UnresolvedRef {
+        from_node_id: server_impl.id.clone(),
+        reference_kind: EdgeKind::Implements,
+        ..call_ref("src/server.rs", "Store")
+    };
+    let resolver = ReferenceResolver::from_nodes(&db, &[trait_method.clone(), server_impl, decoy]);
+
+    let result = resolver.resolve_all(&[
+        implements,
+        method_call("src/server.rs", "self.put", "Server"),
+    ]);
+    let call = result
+        .resolved
+        .iter()
+        .find(|r| r.original.reference_kind == EdgeKind::Calls)
+        .expect("call should resolve through the implemented trait");
+    assert_eq!(call.target_node_id, trait_method.id);
+}
+
+#[tokio::test]
+async fn test_for_refs_loads_stored_supertypes() {
+    let (_dir, db) = empty_db().await;
+    let base_method = method_node("Base", "save", "src/Base.java", 3, "void save()");
+    let class = make_node(
+        NodeKind::Class,
+        "Account",
+        "src/Account.java",
+        1,
+        "class Account extends Base",
+    );
+    let decoy = method_node("Other", "save", "src/Account.java", 9, "void save()");
+    db.insert_nodes(&[base_method.clone(), class.clone(), decoy])
+        .await
+        .expect("failed to insert nodes");
+    db.insert_unresolved_refs(&[UnresolvedRef {
+        from_node_id: class.id.clone(),
+        reference_kind: EdgeKind::Extends,
+        ..call_ref("src/Account.java", "Base")
+    }])
+    .await
+    .expect("failed to insert refs");
+
+    let call = method_call("src/Main.java", "account.save", "Account");
+    let resolver = ReferenceResolver::for_refs(&db, std::slice::from_ref(&call))
+        .await
+        .expect("failed to build resolver");
+    let resolved = resolver
+        .resolve_one(&call)
+        .expect("call should resolve to the inherited method");
+    assert_eq!(resolved.target_node_id, base_method.id);
+}
+
+#[tokio::test]
+async fn test_for_refs_loads_fields_along_graphql_selection() {
+    let (_dir, db) = empty_db().await;
+    let user = make_node(
+        NodeKind::GqlField,
+        "Query.user",
+        "schema.graphql",
+        2,
+        "user(id: ID!): User",
+    );
+    let posts = make_node(
+        NodeKind::GqlField,
+        "User.posts",
+        "schema.graphql",
+        6,
+        "posts: [Post!]!",
+    );
+    let title = make_node(
+        NodeKind::GqlField,
+        "Post.title",
+        "schema.graphql",
+        10,
+        "title: String",
+    );
+    db.insert_nodes(&[user, posts, title.clone()])
+        .await
+        .expect("failed to insert nodes");
+
+    let selection = UnresolvedRef {
+        reference_kind: EdgeKind::Uses,
+        ..call_ref("src/queries.graphql", "Query.user.posts.title")
+    };
+    let resolver = ReferenceResolver::for_refs(&db, std::slice::from_ref(&selection))
+        .await
+        .expect("failed to build resolver");
+    let resolved = resolver
+        .resolve_one(&selection)
+        .expect("selection should resolve to the schema field");
+    assert_eq!(resolved.target_node_id, title.id);
+}
+
+// ---------------------------------------------------------------------------
+// Diagnostics
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_diagnose_unknown_and_filtered_names() {
+    let (_dir, db) = empty_db().await;
+    let helper = make_node(
+        NodeKind::Function,
+        "helper",
+        "src/util.rs",
+        1,
+        "fn helper()",
+    );
+    let resolver = ReferenceResolver::from_nodes(&db, &[helper]);
+
+    assert_eq!(
+        resolver.diagnose(&call_ref("src/main.rs", "missing")),
+        Some(UnresolvedReason::UnknownName)
+    );
+    assert_eq!(
+        resolver.diagnose(&call_ref("src/main.rs", "client.helper")),
+        Some(UnresolvedReason::FilteredByKnownNames),
+        "a dotted call whose last segment exists is filtered, not unknown"
+    );
+    assert_eq!(resolver.diagnose(&call_ref("src/main.rs", "helper")), None);
+}
+
+#[tokio::test]
+async fn test_diagnose_external_symbol_and_no_fitting_candidate() {
+    let (_dir, db) = empty_db().await;
+    let local = make_node(
+        NodeKind::Function,
+        "debounce",
+        "src/timing.ts",
+        1,
+        "function debounce()",
+    );
+    let use_node = make_node(
+        NodeKind::Use,
+        "lodash",
+        "src/search.ts",
+        1,
+        "import { debounce } from 'lodash';",
+    );
+    let resolver = ReferenceResolver::from_nodes(&db, &[local, use_node]);
+
+    assert_eq!(
+        resolver.diagnose(&call_ref("src/search.ts", "debounce")),
+        None,
+        "a name bound to an external import resolves to an external symbol"
+    );
+    assert_eq!(
+        resolver.diagnose(&type_ref("src/other.ts", "debounce", EdgeKind::TypeOf)),
+        Some(UnresolvedReason::NoFittingCandidate),
+        "a type reference never binds to a function"
+    );
+}
+
+#[tokio::test]
+async fn test_diagnose_tied_candidates() {
+    let (_dir, db) = empty_db().await;
+    let a = make_node(
+        NodeKind::Function,
+        "render",
+        "src/a/view.rs",
+        1,
+        "fn render()",
+    );
+    let b = make_node(
+        NodeKind::Function,
+        "render",
+        "src/b/view.rs",
+        1,
+        "fn render()",
+    );
+    let resolver = ReferenceResolver::from_nodes(&db, &[a, b]);
+
+    assert_eq!(
+        resolver.diagnose(&call_ref("src/main.rs", "render")),
+        Some(UnresolvedReason::TiedCandidates { candidates: 2 })
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Package boundaries
+// ---------------------------------------------------------------------------
+
+fn cargo_package(name: &str, dependencies: &[&str]) -> Package {
+    Package {
+        ecosystem: Ecosystem::Cargo,
+        name: name.to_string(),
+        key: name.to_string(),
+        dir: format!("crates/{name}"),
+        manifest: format!("crates/{name}/Cargo.toml"),
+        dependencies: dependencies.iter().map(|d| (*d).to_string()).collect(),
+    }
+}
+
+#[tokio::test]
+async fn test_candidate_in_dependency_package_wins() {
+    let (_dir, db) = empty_db().await;
+    let unrelated = make_node(
+        NodeKind::Function,
+        "render",
+        "crates/legacy/src/lib.rs",
+        1,
+        "pub fn render()",
+    );
+    let dependency = make_node(
+        NodeKind::Function,
+        "render",
+        "crates/ui/src/lib.rs",
+        1,
+        "pub fn render()",
+    );
+    let packages = [
+        cargo_package("app", &["ui"]),
+        cargo_package("legacy", &[]),
+        cargo_package("ui", &[]),
+    ];
+    let (nodes, edges) = package_graph(&packages, []);
+    let resolver = ReferenceResolver::from_nodes(&db, &[unrelated, dependency.clone()])
+        .with_packages(PackageMap::new(&nodes, &edges));
+
+    let resolved = resolver
+        .resolve_one(&call_ref("crates/app/src/main.rs", "render"))
+        .expect("call should resolve");
+    assert_eq!(resolved.target_node_id, dependency.id);
+    assert_eq!(
+        resolver.diagnose(&call_ref("crates/app/src/main.rs", "render")),
+        None,
+        "package boundaries break the tie"
+    );
+}
+
+#[tokio::test]
+async fn test_external_symbols_group_by_dependency() {
+    let (_dir, db) = empty_db().await;
+    let local_spawn = make_node(NodeKind::Function, "spawn", "src/task.rs", 1, "fn spawn()");
+    let nodes = [
+        local_spawn.clone(),
+        make_node(
+            NodeKind::Use,
+            "tokio",
+            "src/main.rs",
+            1,
+            "use tokio::{spawn, sync::Mutex};",
+        ),
+        make_node(
+            NodeKind::Use,
+            "requests",
+            "app/client.py",
+            1,
+            "import requests",
+        ),
+        make_node(
+            NodeKind::Use,
+            "List",
+            "src/main/java/com/acme/App.java",
+            1,
+            "import java.util.List;",
+        ),
+    ];
+    let resolver = ReferenceResolver::from_nodes(&db, &nodes);
+
+    let refs = [
+        call_ref("src/main.rs", "spawn"),
+        call_ref("src/main.rs", "Mutex::new"),
+        call_ref("src/main.rs", "serde_json::to_string"),
+        call_ref("src/main.rs", "Vec::new"),
+        call_ref("app/client.py", "requests.get"),
+        call_ref("src/main/java/com/acme/App.java", "List.of"),
+    ];
+    let result = resolver.resolve_all(&refs);
+    assert_eq!(result.resolved_count, refs.len(), "{:?}", result.unresolved);
+    assert!(result
+        .resolved
+        .iter()
+        .all(|r| r.strategy == ResolutionStrategy::External && r.target_node_id != local_spawn.id));
+
+    let (nodes, edges) = resolver.external_nodes(&result.resolved);
+    let names_of = |kind: NodeKind| {
+        let mut names: Vec<&str> = nodes
+            .iter()
+            .filter(|n| n.kind == kind)
+            .map(|n| n.qualified_name.as_str())
+            .collect();
+        names.sort_unstable();
+        names
+    };
+    assert_eq!(
+        names_of(NodeKind::ExternalSymbol),
+        vec![
+            "java.util.List.of",
+            "requests.get",
+            "serde_json::to_string",
+            "std::vec::Vec::new",
+            "tokio::spawn",
+            "tokio::sync::Mutex::new",
+        ]
+    );
+    assert_eq!(
+        names_of(NodeKind::ExternalPackage),
+        vec!["java.util", "requests", "serde_json", "std", "tokio"]
+    );
+    let tokio = nodes
+        .iter()
+        .find(|n| n.kind == NodeKind::ExternalPackage && n.name == "tokio")
+        .unwrap();
+    assert_eq!(
+        edges
+            .iter()
+            .filter(|e| e.kind == EdgeKind::Contains && e.source == tokio.id)
+            .count(),
+        2
+    );
+    assert!(
+        nodes
+            .iter()
+            .any(|n| n.id == result.resolved[0].target_node_id && n.name == "spawn"),
+        "edges point at the built symbol nodes"
+    );
+}
+
+#[tokio::test]
+async fn test_project_imports_are_not_external() {
+    let (_dir, db) = empty_db().await;
+    let nodes = [
+        make_node(
+            NodeKind::Struct,
+            "Account",
+            "src/models/account.rs",
+            1,
+            "struct Account",
+        ),
+        make_node(
+            NodeKind::Function,
+            "helper",
+            "app/utils.py",
+            1,
+            "def helper()",
+        ),
+        make_node(
+            NodeKind::Class,
+            "Item",
+            "src/main/java/com/acme/model/Item.java",
+            1,
+            "class Item",
+        ),
+        make_node(
+            NodeKind::Function,
+            "Run",
+            "internal/db/db.go",
+            1,
+            "func Run()",
+        ),
+        make_node(NodeKind::Use, "User", "src/main.rs", 1, "use models::User;"),
+        make_node(
+            NodeKind::Use,
+            "Thing",
+            "src/main.rs",
+            2,
+            "use crate::missing::Thing;",
+        ),
+        make_node(
+            NodeKind::Use,
+            "utils",
+            "app/main.py",
+            1,
+            "from utils import missing",
+        ),
+        make_node(
+            NodeKind::Use,
+            "Order",
+            "src/main/java/com/acme/App.java",
+            1,
+            "import com.acme.model.Order;",
+        ),
+        make_node(
+            NodeKind::Use,
+            "Lists",
+            "src/main/java/com/acme/App.java",
+            2,
+            "import com.google.common.collect.Lists;",
+        ),
+        make_node(
+            NodeKind::Use,
+            "db",
+            "cmd/main.go",
+            1,
+            "\"example.com/shop/internal/db\"",
+        ),
+    ];
+    let go_module = Package {
+        ecosystem: Ecosystem::Go,
+        name: "shop".to_string(),
+        key: "example.com/shop".to_string(),
+        dir: String::new(),
+        manifest: "go.mod".to_string(),
+        dependencies: Vec::new(),
+    };
+    let (package_nodes, package_edges) = package_graph(&[go_module], []);
+    let resolver = ReferenceResolver::from_nodes(&db, &nodes)
+        .with_packages(PackageMap::new(&package_nodes, &package_edges));
+
+    for (file, name) in [
+        ("src/main.rs", "User"),
+        ("src/main.rs", "Thing"),
+        ("app/main.py", "missing"),
+        ("src/main/java/com/acme/App.java", "Order"),
+        ("cmd/main.go", "db.Open"),
+    ] {
+        let resolved = resolver.resolve_one(&call_ref(file, name));
+        assert!(
+            resolved
+                .as_ref()
+                .is_none_or(|r| r.strategy != ResolutionStrategy::External),
+            "{name} in {file} is imported from the project: {resolved:?}"
+        );
+    }
+    let resolved = resolver
+        .resolve_one(&call_ref(
+            "src/main/java/com/acme/App.java",
+            "Lists.newArrayList",
+        ))
+        .expect("a third-party package resolves externally");
+    assert_eq!(resolved.strategy, ResolutionStrategy::External);
+    let (nodes, _) = resolver.external_nodes(&[resolved]);
+    assert!(nodes
+        .iter()
+        .any(|n| n.kind == NodeKind::ExternalPackage && n.name == "com.google.common.collect"));
+}
+
+#[tokio::test]
+async fn test_method_on_external_receiver_type_is_external() {
+    let (_dir, db) = empty_db().await;
+    let local_get = make_node(NodeKind::Function, "get", "src/cache.rs", 1, "fn get()");
+    let use_node = make_node(
+        NodeKind::Use,
+        "Client",
+        "src/http.rs",
+        1,
+        "use reqwest::Client;",
+    );
+    let resolver = ReferenceResolver::from_nodes(&db, &[local_get.clone(), use_node]);
+
+    let uref = UnresolvedRef {
+        receiver_type: Some("Client".to_string()),
+        abi: None,
+        http_method: None,
+        sql: false,
+        ..call_ref("src/http.rs", "client.get")
+    };
+    let resolved = resolver
+        .resolve_one(&uref)
+        .expect("a method on an external type resolves externally");
+    assert_ne!(resolved.target_node_id, local_get.id);
+    let (nodes, _) = resolver.external_nodes(&[resolved]);
+    assert!(nodes
+        .iter()
+        .any(|n| n.kind == NodeKind::ExternalSymbol && n.qualified_name == "reqwest::Client::get"));
+}
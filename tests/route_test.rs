@@ -0,0 +1,313 @@
+use std::fs;
+
+use tempfile::TempDir;
+use tokensave::extraction::{
+    GoExtractor, JavaExtractor, LanguageExtractor, PythonExtractor, RustExtractor,
+    TypeScriptExtractor,
+};
+use tokensave::tokensave::TokenSave;
+use tokensave::types::*;
+
+const AXUM: &str = r#"use axum::{routing::{get, post}, Router};
+
+pub fn app() -> Router {
+    Router::new()
+        .route("/users", get(list_users).post(create_user))
+        .route("/users/:id", get(handlers::show_user))
+}
+
+#[get("/health")]
+async fn health() -> &'static str {
+    "ok"
+}
+
+async fn sync(client: &reqwest::Client, base: &str, id: u32) {
+    client.get(format!("{}/users/{}", base, id)).send().await;
+    client.post("https://api.example.com/users?notify=1").send().await;
+}
+"#;
+
+const EXPRESS: &str = r#"const app = express();
+
+app.get("/users/:id", users.show);
+app.post("/users", (req, res) => res.send(201));
+router.route("/users/:id/posts").get(listPosts).delete(deletePosts);
+
+export async function loadUser(id) {
+  const res = await fetch(`${API_BASE}/users/${id}`);
+  await axios.get("/users/42/posts");
+  return fetch("/users", { method: "POST", body: "{}" });
+}
+"#;
+
+const FLASK: &str = r#"from flask import Flask
+
+app = Flask(__name__)
+
+@app.route("/users/<int:user_id>", methods=["GET", "PUT"])
+def user(user_id):
+    return {}
+
+@router.delete("/users/{user_id}")
+async def delete_user(user_id: int):
+    return None
+
+def sync(base):
+    requests.get(f"{base}/users/{7}")
+    requests.request("DELETE", "http://localhost:8000/users/7")
+"#;
+
+const SPRING: &str = r#"package com.example;
+
+@RestController
+@RequestMapping("/api/users")
+public class UserController {
+    @GetMapping("/{id}")
+    public User get(@PathVariable long id) { return null; }
+
+    @RequestMapping(value = {"", "/"}, method = RequestMethod.POST)
+    public User create(@RequestBody User user) { return user; }
+}
+"#;
+
+const GO: &str = r#"package main
+
+import "net/http"
+
+func routes(mux *http.ServeMux, r *mux.Router, g *gin.Engine) {
+	mux.HandleFunc("GET /users/{id}", showUser)
+	r.HandleFunc("/users", h.CreateUser).Methods("POST")
+	g.DELETE("/users/:id", deleteUser)
+	http.HandleFunc("/health", func(w http.ResponseWriter, r *http.Request) {})
+}
+
+func fetchUser(base string, id int) {
+	http.Get(fmt.Sprintf("%s/users/%d", base, id))
+	http.NewRequest(http.MethodPost, base+"/users", nil)
+}
+"#;
+
+/// The routes of `result` with their handlers: the names of the functions
+/// the route implements directly and of the references it makes.
+fn routes(result: &ExtractionResult) -> Vec<(String, Vec<String>)> {
+    result
+        .nodes
+        .iter()
+        .filter(|n| n.kind == NodeKind::Route)
+        .map(|route| {
+            let mut handlers: Vec<String> = result
+                .edges
+                .iter()
+                .filter(|e| e.source == route.id && e.kind == EdgeKind::Implements)
+                .filter_map(|e| result.nodes.iter().find(|n| n.id == e.target))
+                .map(|n| n.name.clone())
+                .collect();
+            handlers.extend(
+                result
+                    .unresolved_refs
+                    .iter()
+                    .filter(|r| r.from_node_id == route.id)
+                    .map(|r| r.reference_name.clone()),
+            );
+            (route.name.clone(), handlers)
+        })
+        .collect()
+}
+
+fn requests(result: &ExtractionResult) -> Vec<(String, String)> {
+    result
+        .unresolved_refs
+        .iter()
+        .filter_map(|r| {
+            let (method, path) = r.route()?;
+            let from = result.nodes.iter().find(|n| n.id == r.from_node_id);
+            Some((format!("{method} {path}"), from.unwrap().name.clone()))
+        })
+        .collect()
+}
+
+fn route(name: &str, handlers: &[&str]) -> (String, Vec<String>) {
+    (
+        name.to_string(),
+        handlers.iter().map(|h| h.to_string()).collect(),
+    )
+}
+
+fn request(name: &str, from: &str) -> (String, String) {
+    (name.to_string(), from.to_string())
+}
+
+#[test]
+fn test_rust_routes_and_reqwest_requests() {
+    let result = RustExtractor.extract("src/app.rs", AXUM);
+    assert_eq!(
+        routes(&result),
+        vec![
+            route("GET /users", &["list_users"]),
+            route("POST /users", &["create_user"]),
+            route("GET /users/{}", &["handlers::show_user"]),
+            route("GET /health", &["health"]),
+        ]
+    );
+    let list = result
+        .nodes
+        .iter()
+        .find(|n| n.name == "GET /users/{}")
+        .unwrap();
+    assert_eq!(list.signature.as_deref(), Some("GET /users/:id"));
+    assert_eq!((list.start_line, list.start_column), (5, 15));
+    let app = result.nodes.iter().find(|n| n.name == "app").unwrap();
+    assert!(result
+        .edges
+        .iter()
+        .any(|e| e.source == app.id && e.target == list.id && e.kind == EdgeKind::Registers));
+
+    // Paths alone are map lookups; reqwest takes absolute URLs.
+    assert_eq!(
+        requests(&result),
+        vec![
+            request("GET /users/{}", "sync"),
+            request("POST /users", "sync"),
+        ]
+    );
+}
+
+#[test]
+fn test_express_routes_and_fetch_requests() {
+    let result = TypeScriptExtractor.extract("src/server.js", EXPRESS);
+    assert_eq!(
+        routes(&result),
+        vec![
+            route("GET /users/{}", &["users.show"]),
+            route("POST /users", &[]),
+            route("GET /users/{}/posts", &["listPosts"]),
+            route("DELETE /users/{}/posts", &["deletePosts"]),
+        ]
+    );
+    assert_eq!(
+        requests(&result),
+        vec![
+            request("GET /users/{}", "loadUser"),
+            request("GET /users/{}/posts", "loadUser"),
+            request("POST /users", "loadUser"),
+        ]
+    );
+}
+
+#[test]
+fn test_python_decorated_routes_and_requests() {
+    let result = PythonExtractor.extract("app.py", FLASK);
+    assert_eq!(
+        routes(&result),
+        vec![
+            route("GET /users/{}", &["user"]),
+            route("PUT /users/{}", &["user"]),
+            route("DELETE /users/{}", &["delete_user"]),
+        ]
+    );
+    let put = result
+        .nodes
+        .iter()
+        .find(|n| n.name == "PUT /users/{}")
+        .unwrap();
+    assert_eq!(put.signature.as_deref(), Some("PUT /users/<int:user_id>"));
+    assert_eq!(
+        requests(&result),
+        vec![
+            request("GET /users/{}", "sync"),
+            request("DELETE /users/{}", "sync"),
+        ]
+    );
+}
+
+#[test]
+fn test_spring_mappings_join_the_controller_path() {
+    let result = JavaExtractor.extract("src/UserController.java", SPRING);
+    assert_eq!(
+        routes(&result),
+        vec![
+            route("GET /api/users/{}", &["get"]),
+            route("POST /api/users", &["create"]),
+        ]
+    );
+}
+
+#[test]
+fn test_go_routes_and_requests() {
+    let result = GoExtractor.extract("main.go", GO);
+    assert_eq!(
+        routes(&result),
+        vec![
+            route("GET /users/{}", &["showUser"]),
+            route("POST /users", &["h.CreateUser"]),
+            route("DELETE /users/{}", &["deleteUser"]),
+            route("ANY /health", &[]),
+        ]
+    );
+    assert_eq!(
+        requests(&result),
+        vec![
+            request("GET /users/{}", "fetchUser"),
+            request("POST /users", "fetchUser"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_requests_link_to_routes_across_languages() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+    fs::write(project.join("app.py"), FLASK).unwrap();
+    fs::write(
+        project.join("client.ts"),
+        "export async function fetchUser(id: number) {\n  return fetch(`/users/${id}`);\n}\n\nexport function ping() {\n  return fetch(\"https://example.com/health\");\n}\n",
+    )
+    .unwrap();
+
+    let ts = TokenSave::init(project).await.unwrap();
+    ts.index_all().await.unwrap();
+
+    let names = |nodes: &[Node]| -> Vec<String> { nodes.iter().map(|n| n.name.clone()).collect() };
+    let routes = ts.routes().await.unwrap();
+    let listed: Vec<(&str, Vec<String>, Vec<String>)> = routes
+        .iter()
+        .map(|(route, handlers, callers)| (route.name.as_str(), names(handlers), names(callers)))
+        .collect();
+    assert_eq!(
+        listed,
+        vec![
+            (
+                "GET /users/{}",
+                vec!["user".to_string()],
+                vec!["sync".to_string(), "fetchUser".to_string()]
+            ),
+            ("PUT /users/{}", vec!["user".to_string()], vec![]),
+            (
+                "DELETE /users/{}",
+                vec!["delete_user".to_string()],
+                vec!["sync".to_string()]
+            ),
+        ]
+    );
+
+    // Changing a handler reaches the clients requesting its route.
+    let app = ts.get_nodes_by_file("app.py").await.unwrap();
+    let user = app.iter().find(|n| n.name == "user").unwrap();
+    let impact = ts.get_impact_radius(&user.id, 2, false, 0.0).await.unwrap();
+    assert!(names(&impact.nodes).contains(&"fetchUser".to_string()));
+
+    // A route registered later, for any method, is linked on sync.
+    fs::write(
+        project.join("health.go"),
+        "package main\n\nimport \"net/http\"\n\nfunc main() {\n\thttp.HandleFunc(\"/health\", health)\n}\n\nfunc health(w http.ResponseWriter, r *http.Request) {}\n",
+    )
+    .unwrap();
+    ts.sync().await.unwrap();
+    let routes = ts.routes().await.unwrap();
+    let (_, handlers, callers) = routes
+        .iter()
+        .find(|(route, ..)| route.name == "ANY /health")
+        .unwrap();
+    assert_eq!(names(handlers), vec!["health"]);
+    assert_eq!(names(callers), vec!["ping"]);
+}
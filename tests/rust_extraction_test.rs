@@ -569,3 +569,68 @@ pub struct Config {
         "each AnnotationUsage should have an Annotates unresolved ref"
     );
 }
+
+#[test]
+fn test_rust_type_reference_refs() {
+    let source = r#"struct Service {
+    repo: Repo<User>,
+}
+
+fn find(&self, id: UserId, limit: usize) -> Option<crate::model::Account> {
+    None
+}
+"#;
+    let extractor = RustExtractor;
+    let result = extractor.extract("test.rs", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    let names = |kind: EdgeKind| -> Vec<String> {
+        result
+            .unresolved_refs
+            .iter()
+            .filter(|r| r.reference_kind == kind)
+            .map(|r| r.reference_name.clone())
+            .collect()
+    };
+    let receives = names(EdgeKind::Receives);
+    let returns = names(EdgeKind::Returns);
+    let type_of = names(EdgeKind::TypeOf);
+    assert_eq!(receives, vec!["UserId"]);
+    assert_eq!(returns, vec!["Option", "crate::model::Account"]);
+    assert_eq!(type_of, vec!["Repo", "User"]);
+}
+
+#[test]
+fn test_rust_call_receiver_types() {
+    let source = r#"struct Server {
+    db: Arc<Database>,
+}
+
+impl Server {
+    fn run(&self, client: &Client, id: u64) {
+        let conn: Conn = open();
+        let other = open();
+        self.db.get_node(id);
+        client.send(id);
+        conn.close();
+        other.close();
+        self.flush();
+    }
+}
+"#;
+    let extractor = RustExtractor;
+    let result = extractor.extract("test.rs", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    let receiver = |name: &str| -> Option<String> {
+        result
+            .unresolved_refs
+            .iter()
+            .find(|r| r.reference_kind == EdgeKind::Calls && r.reference_name == name)
+            .and_then(|r| r.receiver_type.clone())
+    };
+    assert_eq!(receiver("self.db.get_node").as_deref(), Some("Database"));
+    assert_eq!(receiver("client.send").as_deref(), Some("Client"));
+    assert_eq!(receiver("conn.close").as_deref(), Some("Conn"));
+    assert_eq!(receiver("other.close").as_deref(), None);
+    assert_eq!(receiver("self.flush").as_deref(), Some("Server"));
+    assert_eq!(receiver("open").as_deref(), None);
+}
@@ -296,3 +296,54 @@ fn test_scala_scaladoc() {
         .unwrap();
     assert!(obj.docstring.as_ref().unwrap().contains("greeting"));
 }
+
+#[test]
+fn test_scala_type_reference_refs() {
+    let result = extract(
+        r#"class Service(val repo: Repo) {
+  val owner: Account = null
+  def find(id: UserId)(implicit ctx: Context): List[User] = Nil
+}
+"#,
+    );
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    let names = |kind: EdgeKind| -> Vec<String> {
+        result
+            .unresolved_refs
+            .iter()
+            .filter(|r| r.reference_kind == kind)
+            .map(|r| r.reference_name.clone())
+            .collect()
+    };
+    assert_eq!(names(EdgeKind::Receives), vec!["UserId", "Context"]);
+    assert_eq!(names(EdgeKind::Returns), vec!["List", "User"]);
+    assert_eq!(names(EdgeKind::TypeOf), vec!["Repo", "Account"]);
+}
+
+#[test]
+fn test_scala_call_receiver_types() {
+    let source = r#"class Server(db: Database) {
+  val store: Store = new Store()
+  def run(client: Client): Unit = {
+    val conn: Conn = open()
+    this.db.getNode(1)
+    store.put(2)
+    client.send(2)
+    conn.close()
+  }
+}
+"#;
+    let result = extract(source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    let receiver = |name: &str| -> Option<String> {
+        result
+            .unresolved_refs
+            .iter()
+            .find(|r| r.reference_kind == EdgeKind::Calls && r.reference_name == name)
+            .and_then(|r| r.receiver_type.clone())
+    };
+    assert_eq!(receiver("this.db.getNode").as_deref(), Some("Database"));
+    assert_eq!(receiver("store.put").as_deref(), Some("Store"));
+    assert_eq!(receiver("client.send").as_deref(), Some("Client"));
+    assert_eq!(receiver("conn.close").as_deref(), Some("Conn"));
+}
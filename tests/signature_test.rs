@@ -0,0 +1,395 @@
+use tokensave::extraction::signatures::{normalize_type, TypePattern};
+use tokensave::extraction::{
+    CExtractor, CSharpExtractor, CppExtractor, GoExtractor, JavaExtractor, KotlinExtractor,
+    LanguageExtractor, PythonExtractor, RustExtractor, ScalaExtractor, SwiftExtractor,
+    TypeScriptExtractor,
+};
+use tokensave::types::*;
+
+/// The signature recorded for the callable named `name`.
+fn signature_of<'a>(result: &'a ExtractionResult, name: &str) -> &'a Signature {
+    let id = &result
+        .nodes
+        .iter()
+        .find(|n| n.name == name)
+        .expect("node not found")
+        .id;
+    result
+        .signatures
+        .iter()
+        .find(|s| &s.node_id == id)
+        .expect("signature not found")
+}
+
+/// `name: type_name` for every parameter, untyped ones as just the name.
+fn params(signature: &Signature) -> Vec<String> {
+    signature
+        .params
+        .iter()
+        .map(|p| match &p.declared_type {
+            Some(ty) => format!("{}: {}", p.name, ty.name),
+            None => p.name.clone(),
+        })
+        .collect()
+}
+
+fn return_name(signature: &Signature) -> Option<&str> {
+    signature.return_type.as_ref().map(|t| t.name.as_str())
+}
+
+#[test]
+fn test_rust_signatures() {
+    let source = r#"pub struct Db;
+impl Db {
+    pub fn query(&self, sql: &str, mut limit: Option<usize>) -> Result<Vec<Row>, Error> { todo!() }
+}
+pub fn open(path: &std::path::Path) -> std::io::Result<Db> { todo!() }
+fn noop() {}
+"#;
+    let result = RustExtractor.extract("src/db.rs", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+
+    let query = signature_of(&result, "query");
+    assert_eq!(params(query), vec!["sql: str", "limit: Option<usize>"]);
+    assert_eq!(return_name(query), Some("Result<Vec<Row>,Error>"));
+    let sql = &query.params[0];
+    assert_eq!(sql.declared_type.as_ref().unwrap().text, "&str");
+
+    let open = signature_of(&result, "open");
+    assert_eq!(params(open), vec!["path: Path"]);
+    assert_eq!(return_name(open), Some("Result<Db>"));
+
+    let noop = result.nodes.iter().find(|n| n.name == "noop").unwrap();
+    assert!(result.signatures.iter().all(|s| s.node_id != noop.id));
+}
+
+#[test]
+fn test_go_signatures() {
+    let source = r#"package store
+
+func Get(ctx context.Context, a, b int, keys ...string) (map[string][]byte, error) {
+	return nil, nil
+}
+
+func (s *Store) Put(key string, value []byte) error { return nil }
+"#;
+    let result = GoExtractor.extract("store.go", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+
+    let get = signature_of(&result, "Get");
+    assert_eq!(
+        params(get),
+        vec!["ctx: Context", "a: int", "b: int", "keys: string[]"]
+    );
+    assert!(get.params[3].optional);
+    assert_eq!(return_name(get), Some("(map<string,byte[]>,error)"));
+
+    let put = signature_of(&result, "Put");
+    assert_eq!(params(put), vec!["key: string", "value: byte[]"]);
+    assert_eq!(return_name(put), Some("error"));
+}
+
+#[test]
+fn test_python_signatures() {
+    let source = r#"class Repo:
+    def find(self, key: str, limit: int = 10, *args, **kwargs) -> Optional[List["User"]]:
+        pass
+
+    @classmethod
+    def create(cls, name):
+        pass
+
+def load(path, strict=False) -> None:
+    pass
+"#;
+    let result = PythonExtractor.extract("repo.py", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+
+    let find = signature_of(&result, "find");
+    assert_eq!(
+        params(find),
+        vec!["key: str", "limit: int", "*args", "**kwargs"]
+    );
+    assert_eq!(find.params[1].default_value.as_deref(), Some("10"));
+    assert!(find.params[1].optional);
+    assert!(find.params[2].optional && find.params[3].optional);
+    assert_eq!(return_name(find), Some("Optional<List<User>>"));
+
+    let create = signature_of(&result, "create");
+    assert_eq!(params(create), vec!["name"]);
+
+    let load = signature_of(&result, "load");
+    assert_eq!(params(load), vec!["path", "strict"]);
+    assert_eq!(load.params[1].default_value.as_deref(), Some("False"));
+    assert_eq!(return_name(load), Some("None"));
+}
+
+#[test]
+fn test_java_signatures() {
+    let source = r#"class UserService {
+  UserService(Repository<User> repo) {}
+  public Map<String, List<User>> group(final Collection<? extends User> users, String... keys) { return null; }
+  void resize(int sizes[]) {}
+}
+"#;
+    let result = JavaExtractor.extract("UserService.java", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+
+    let group = signature_of(&result, "group");
+    assert_eq!(
+        params(group),
+        vec!["users: Collection<User>", "keys: String[]"]
+    );
+    assert!(group.params[1].optional);
+    assert_eq!(return_name(group), Some("Map<String,List<User>>"));
+
+    let resize = signature_of(&result, "resize");
+    assert_eq!(params(resize), vec!["sizes: int[]"]);
+    assert_eq!(return_name(resize), Some("void"));
+
+    let ctor = result
+        .nodes
+        .iter()
+        .find(|n| n.name == "UserService" && n.kind == NodeKind::Constructor)
+        .unwrap();
+    let ctor = result
+        .signatures
+        .iter()
+        .find(|s| s.node_id == ctor.id)
+        .unwrap();
+    assert_eq!(params(ctor), vec!["repo: Repository<User>"]);
+    assert!(ctor.return_type.is_none());
+}
+
+#[test]
+fn test_typescript_signatures() {
+    let source = r#"export function fetchAll(this: Window, url: string, opts?: RequestInit, ...ids: number[]): Promise<Array<User>> {
+  return fetch(url);
+}
+
+class Cache {
+  get(key: string, fallback: User | null = null): User | undefined { return undefined; }
+}
+
+const double = (n: number): number => n * 2;
+"#;
+    let result = TypeScriptExtractor.extract("api.ts", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+
+    let fetch_all = signature_of(&result, "fetchAll");
+    assert_eq!(
+        params(fetch_all),
+        vec!["url: string", "opts: RequestInit", "ids: number[]"]
+    );
+    assert!(!fetch_all.params[0].optional);
+    assert!(fetch_all.params[1].optional && fetch_all.params[2].optional);
+    assert_eq!(return_name(fetch_all), Some("Promise<Array<User>>"));
+
+    let get = signature_of(&result, "get");
+    assert_eq!(params(get), vec!["key: string", "fallback: User"]);
+    assert_eq!(get.params[1].default_value.as_deref(), Some("null"));
+    assert_eq!(return_name(get), Some("User"));
+
+    let double = signature_of(&result, "double");
+    assert_eq!(params(double), vec!["n: number"]);
+    assert_eq!(return_name(double), Some("number"));
+
+    let js = TypeScriptExtractor.extract(
+        "merge.js",
+        "function merge(target, source = {}, ...rest) {}\n",
+    );
+    let merge = signature_of(&js, "merge");
+    assert_eq!(params(merge), vec!["target", "source", "rest"]);
+    assert_eq!(merge.params[1].default_value.as_deref(), Some("{}"));
+    assert!(!merge.params[0].optional && merge.params[1].optional && merge.params[2].optional);
+    assert!(merge.return_type.is_none());
+}
+
+#[test]
+fn test_c_signatures() {
+    let source = r#"struct buffer;
+const char *buffer_find(const struct buffer *buf, char *needles[], size_t count);
+int log_message(int level, const char *fmt, ...) { return 0; }
+void reset(void) {}
+"#;
+    let result = CExtractor.extract("buffer.c", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+
+    let find = signature_of(&result, "buffer_find");
+    assert_eq!(
+        params(find),
+        vec!["buf: buffer", "needles: char[]", "count: size_t"]
+    );
+    assert_eq!(
+        find.params[0].declared_type.as_ref().unwrap().text,
+        "struct buffer*"
+    );
+    assert_eq!(return_name(find), Some("char"));
+
+    let log = signature_of(&result, "log_message");
+    assert_eq!(params(log), vec!["level: int", "fmt: char", ""]);
+    assert!(log.params[2].optional);
+    assert_eq!(return_name(log), Some("int"));
+
+    let reset = signature_of(&result, "reset");
+    assert!(reset.params.is_empty());
+    assert_eq!(return_name(reset), Some("void"));
+}
+
+#[test]
+fn test_cpp_signatures() {
+    let source = r#"namespace store {
+class Cache {
+public:
+    std::optional<User> get(const std::string &key, int ttl = 60) const;
+    auto size() const -> std::size_t;
+};
+
+template <typename... Args>
+void log(const char *fmt, Args &&...args) {}
+}
+"#;
+    let result = CppExtractor.extract("cache.hpp", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+
+    let get = signature_of(&result, "get");
+    assert_eq!(params(get), vec!["key: string", "ttl: int"]);
+    assert_eq!(
+        get.params[0].declared_type.as_ref().unwrap().text,
+        "std::string&"
+    );
+    assert_eq!(get.params[1].default_value.as_deref(), Some("60"));
+    assert!(get.params[1].optional);
+    assert_eq!(return_name(get), Some("optional<User>"));
+
+    let size = signature_of(&result, "size");
+    assert!(size.params.is_empty());
+    assert_eq!(return_name(size), Some("size_t"));
+
+    let log = signature_of(&result, "log");
+    assert_eq!(params(log), vec!["fmt: char", "args: Args[]"]);
+    assert!(log.params[1].optional);
+}
+
+#[test]
+fn test_csharp_signatures() {
+    let source = r#"class UserService {
+  UserService(ref Repository<User> repo) {}
+  public Task<List<User>?> Find(string name, int limit = 10, params string[] tags) { return null; }
+}
+"#;
+    let result = CSharpExtractor.extract("UserService.cs", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+
+    let find = signature_of(&result, "Find");
+    assert_eq!(
+        params(find),
+        vec!["name: string", "limit: int", "tags: string[]"]
+    );
+    assert_eq!(find.params[1].default_value.as_deref(), Some("10"));
+    assert!(!find.params[0].optional && find.params[1].optional && find.params[2].optional);
+    assert_eq!(return_name(find), Some("Task<List<User>>"));
+
+    let ctor = result
+        .nodes
+        .iter()
+        .find(|n| n.kind == NodeKind::Constructor)
+        .unwrap();
+    let ctor = result
+        .signatures
+        .iter()
+        .find(|s| s.node_id == ctor.id)
+        .unwrap();
+    assert_eq!(params(ctor), vec!["repo: Repository<User>"]);
+    assert!(ctor.return_type.is_none());
+}
+
+#[test]
+fn test_kotlin_signatures() {
+    let source = r#"class Users {
+  suspend fun find(name: String, limit: Int = 10, vararg tags: String): List<User>? = null
+}
+fun String.shout(times: Int) {}
+"#;
+    let result = KotlinExtractor.extract("Users.kt", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+
+    let find = signature_of(&result, "find");
+    assert_eq!(
+        params(find),
+        vec!["name: String", "limit: Int", "tags: String[]"]
+    );
+    assert_eq!(find.params[1].default_value.as_deref(), Some("10"));
+    assert!(find.params[2].optional);
+    assert_eq!(return_name(find), Some("List<User>"));
+
+    // The extension receiver is not a parameter.
+    let shout = signature_of(&result, "shout");
+    assert_eq!(params(shout), vec!["times: Int"]);
+    assert!(shout.return_type.is_none());
+}
+
+#[test]
+fn test_swift_signatures() {
+    let source = r#"class Users {
+  func find(_ name: String, limit: Int = 10, tags: String...) -> [User]? { return nil }
+  init(count: inout Int) {}
+}
+"#;
+    let result = SwiftExtractor.extract("Users.swift", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+
+    let find = signature_of(&result, "find");
+    assert_eq!(
+        params(find),
+        vec!["name: String", "limit: Int", "tags: String[]"]
+    );
+    assert_eq!(find.params[1].default_value.as_deref(), Some("10"));
+    assert!(!find.params[0].optional && find.params[1].optional && find.params[2].optional);
+    assert_eq!(return_name(find), Some("User[]"));
+
+    let init = signature_of(&result, "init");
+    assert_eq!(params(init), vec!["count: Int"]);
+    assert!(init.return_type.is_none());
+}
+
+#[test]
+fn test_scala_signatures() {
+    let source = r#"class Users {
+  def find(name: String, limit: Int = 10)(implicit ctx: Context, tags: String*): Option[List[User]] = None
+}
+"#;
+    let result = ScalaExtractor.extract("Users.scala", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+
+    // Every clause of a curried function, in order.
+    let find = signature_of(&result, "find");
+    assert_eq!(
+        params(find),
+        vec![
+            "name: String",
+            "limit: Int",
+            "ctx: Context",
+            "tags: String[]"
+        ]
+    );
+    assert_eq!(find.params[1].default_value.as_deref(), Some("10"));
+    assert!(find.params[3].optional);
+    assert_eq!(return_name(find), Some("Option<List<User>>"));
+}
+
+#[test]
+fn test_patterns_match_across_languages() {
+    let pattern = TypePattern::parse("List<User>").unwrap();
+    assert!(pattern.matches(&normalize_type("java.util.List<User>")));
+    assert!(pattern.matches(&normalize_type("List[User]")));
+    assert!(!pattern.matches(&normalize_type("List<Order>")));
+
+    let any_map = TypePattern::parse("Map<String, _>").unwrap();
+    assert!(!any_map.matches(&normalize_type("HashMap<String, u8>")));
+    assert!(any_map.matches(&normalize_type("Map<String, List<User>>")));
+
+    let user = TypePattern::parse("User").unwrap();
+    assert!(!user.matches(&normalize_type("Promise<User>")));
+    assert!(user.matches_within(&normalize_type("Promise<User>")));
+}
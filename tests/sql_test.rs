@@ -0,0 +1,307 @@
+#![cfg(feature = "lang-sql")]
+
+use std::fs;
+
+use tempfile::TempDir;
+use tokensave::extraction::{LanguageExtractor, PythonExtractor, RustExtractor, SqlExtractor};
+use tokensave::tokensave::TokenSave;
+use tokensave::types::*;
+
+const SCHEMA: &str = r#"-- Code graph schema
+CREATE TABLE IF NOT EXISTS nodes (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL,
+    name TEXT NOT NULL
+);
+
+CREATE TABLE edges (
+    source TEXT NOT NULL REFERENCES nodes(id),
+    target TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    FOREIGN KEY (target) REFERENCES nodes (id)
+);
+
+CREATE INDEX idx_edges_source ON edges(source);
+
+ALTER TABLE nodes ADD COLUMN docstring TEXT;
+
+CREATE VIEW callers AS
+    SELECT e.source, n.name
+    FROM edges e JOIN nodes n ON n.id = e.target
+    WHERE e.kind = 'calls';
+"#;
+
+const STORE: &str = r#"const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS files (path TEXT PRIMARY KEY, size INTEGER)";
+
+pub fn record(conn: &Connection, path: &str) {
+    conn.execute(
+        "INSERT INTO files (path, size) VALUES (?1, ?2) \
+         ON CONFLICT(path) DO UPDATE SET size = excluded.size",
+        (path, 0),
+    ).unwrap();
+}
+
+pub fn largest(conn: &Connection) -> Vec<String> {
+    query(conn, r"SELECT f.path FROM files AS f WHERE f.size > ?1 ORDER BY f.size DESC")
+}
+
+pub fn forget(conn: &Connection, path: &str) {
+    conn.execute("DELETE FROM files WHERE path = ?1", [path]).unwrap();
+    println!("Select a file from the list");
+}
+"#;
+
+const USERS: &str = r#"def active_users(db):
+    return db.execute("""
+        SELECT u.id, u.email
+        FROM users u
+        JOIN orgs o ON o.id = u.org_id
+        WHERE o.active = 1
+    """)
+
+def rename(db, uid, name):
+    db.execute(f"UPDATE users SET name = '{name}', updated_at = now() WHERE id = {uid}")
+"#;
+
+fn names_of_kind(result: &ExtractionResult, kind: NodeKind) -> Vec<String> {
+    result
+        .nodes
+        .iter()
+        .filter(|n| n.kind == kind)
+        .map(|n| n.name.clone())
+        .collect()
+}
+
+/// The SQL references made by the node named `from`, as (table or column,
+/// edge kind), sorted.
+fn sql_refs(result: &ExtractionResult, from: &str) -> Vec<(String, &'static str)> {
+    let from = result.nodes.iter().find(|n| n.name == from).unwrap();
+    let mut refs: Vec<(String, &'static str)> = result
+        .unresolved_refs
+        .iter()
+        .filter(|r| r.from_node_id == from.id)
+        .filter_map(|r| Some((r.sql_object()?.to_string(), r.reference_kind.as_str())))
+        .collect();
+    refs.sort();
+    refs
+}
+
+fn access(object: &str, kind: &'static str) -> (String, &'static str) {
+    (object.to_string(), kind)
+}
+
+#[test]
+fn test_sql_file_tables_columns_and_foreign_keys() {
+    let result = SqlExtractor.extract("db/schema.sql", SCHEMA);
+    assert_eq!(
+        names_of_kind(&result, NodeKind::SqlTable),
+        vec!["nodes", "edges"]
+    );
+    assert_eq!(
+        names_of_kind(&result, NodeKind::SqlColumn),
+        vec![
+            "nodes.id",
+            "nodes.kind",
+            "nodes.name",
+            "edges.source",
+            "edges.target",
+            "edges.kind",
+            "nodes.docstring",
+        ]
+    );
+    assert_eq!(
+        names_of_kind(&result, NodeKind::SqlIndex),
+        vec!["idx_edges_source"]
+    );
+    assert_eq!(names_of_kind(&result, NodeKind::SqlView), vec!["callers"]);
+
+    let nodes = result.nodes.iter().find(|n| n.name == "nodes").unwrap();
+    assert_eq!(
+        nodes.signature.as_deref(),
+        Some("CREATE TABLE IF NOT EXISTS nodes")
+    );
+    assert_eq!((nodes.start_line, nodes.end_line), (1, 5));
+    let kind = result
+        .nodes
+        .iter()
+        .find(|n| n.name == "nodes.kind")
+        .unwrap();
+    assert_eq!(kind.signature.as_deref(), Some("kind TEXT NOT NULL"));
+    // A column added later belongs to the table it alters.
+    let docstring = result
+        .nodes
+        .iter()
+        .find(|n| n.name == "nodes.docstring")
+        .unwrap();
+    assert!(result
+        .edges
+        .iter()
+        .any(|e| e.source == nodes.id && e.target == docstring.id && e.kind == EdgeKind::Contains));
+
+    assert_eq!(
+        sql_refs(&result, "edges.source"),
+        vec![access("nodes.id", "depends_on")]
+    );
+    assert_eq!(
+        sql_refs(&result, "edges.target"),
+        vec![access("nodes.id", "depends_on")]
+    );
+    assert_eq!(
+        sql_refs(&result, "idx_edges_source"),
+        vec![access("edges", "uses"), access("edges.source", "uses")]
+    );
+    assert_eq!(
+        sql_refs(&result, "callers"),
+        vec![
+            access("edges", "uses"),
+            access("edges.kind", "uses"),
+            access("edges.source", "uses"),
+            access("edges.target", "uses"),
+            access("nodes", "uses"),
+            access("nodes.id", "uses"),
+            access("nodes.name", "uses"),
+        ]
+    );
+}
+
+#[test]
+fn test_rust_string_literals_read_and_write_tables() {
+    let result = RustExtractor.extract("src/store.rs", STORE);
+    assert_eq!(names_of_kind(&result, NodeKind::SqlTable), vec!["files"]);
+    assert_eq!(
+        names_of_kind(&result, NodeKind::SqlColumn),
+        vec!["files.path", "files.size"]
+    );
+    assert_eq!(
+        sql_refs(&result, "record"),
+        vec![
+            access("files", "writes"),
+            access("files.path", "uses"),
+            access("files.path", "writes"),
+            access("files.size", "writes"),
+        ]
+    );
+    assert_eq!(
+        sql_refs(&result, "largest"),
+        vec![
+            access("files", "uses"),
+            access("files.path", "uses"),
+            access("files.size", "uses"),
+        ]
+    );
+    // Prose that starts with a SQL keyword is not a query.
+    assert_eq!(
+        sql_refs(&result, "forget"),
+        vec![access("files", "writes"), access("files.path", "uses")]
+    );
+    let delete = result
+        .unresolved_refs
+        .iter()
+        .find(|r| r.sql && r.reference_name == "files" && r.line == 15)
+        .unwrap();
+    assert_eq!(delete.column, 30);
+}
+
+#[test]
+fn test_python_queries_follow_aliases_and_interpolations() {
+    let result = PythonExtractor.extract("app/users.py", USERS);
+    assert_eq!(
+        sql_refs(&result, "active_users"),
+        vec![
+            access("orgs", "uses"),
+            access("orgs.active", "uses"),
+            access("orgs.id", "uses"),
+            access("users", "uses"),
+            access("users.email", "uses"),
+            access("users.id", "uses"),
+            access("users.org_id", "uses"),
+        ]
+    );
+    assert_eq!(
+        sql_refs(&result, "rename"),
+        vec![
+            access("users", "writes"),
+            access("users.id", "uses"),
+            access("users.name", "writes"),
+            access("users.updated_at", "writes"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_sql_access_links_code_to_schema_across_files() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+    fs::write(
+        project.join("schema.sql"),
+        "CREATE TABLE files (\n    path TEXT PRIMARY KEY,\n    size INTEGER\n);\n",
+    )
+    .unwrap();
+    fs::write(
+        project.join("store.rs"),
+        STORE.replace("f.path FROM", "f.path, f.mtime FROM").replacen(
+            "const SCHEMA: &str = \"CREATE TABLE IF NOT EXISTS files (path TEXT PRIMARY KEY, size INTEGER)\";\n",
+            "",
+            1,
+        ),
+    )
+    .unwrap();
+    fs::write(
+        project.join("files.rs"),
+        "pub fn files() -> u32 {\n    0\n}\n\npub fn count() -> u32 {\n    files()\n}\n",
+    )
+    .unwrap();
+
+    let ts = TokenSave::init(project).await.unwrap();
+    ts.index_all().await.unwrap();
+
+    let (tables, accesses) = ts.sql_access("FILES").await.unwrap();
+    assert_eq!(tables.len(), 1);
+    assert_eq!(tables[0].file_path, "schema.sql");
+    let accessors = |kind: EdgeKind, accesses: &[(Node, Node, EdgeKind)]| -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for (node, _, k) in accesses {
+            if *k == kind && !names.contains(&node.name) {
+                names.push(node.name.clone());
+            }
+        }
+        names
+    };
+    assert_eq!(
+        accessors(EdgeKind::Writes, &accesses),
+        vec!["record", "forget"]
+    );
+    assert_eq!(
+        accessors(EdgeKind::Uses, &accesses),
+        vec!["record", "largest", "forget"]
+    );
+    // The column a migration has yet to add is not linked.
+    assert!(accesses
+        .iter()
+        .all(|(_, object, _)| object.name != "files.mtime"));
+
+    // Code calling a function named like a table still calls the function.
+    let count = ts.get_nodes_by_file("files.rs").await.unwrap();
+    let files = count.iter().find(|n| n.name == "files").unwrap();
+    let impact = ts
+        .get_impact_radius(&files.id, 1, false, 0.0)
+        .await
+        .unwrap();
+    assert!(impact.nodes.iter().any(|n| n.name == "count"));
+    assert!(impact.nodes.iter().all(|n| n.kind != NodeKind::SqlTable));
+
+    // A migration adding the column links it on sync.
+    fs::write(
+        project.join("migrate.sql"),
+        "ALTER TABLE files ADD COLUMN mtime INTEGER;\n",
+    )
+    .unwrap();
+    ts.sync().await.unwrap();
+    let (_, accesses) = ts.sql_access("files").await.unwrap();
+    let mtime: Vec<(&str, &str)> = accesses
+        .iter()
+        .filter(|(_, object, _)| object.name == "files.mtime")
+        .map(|(node, object, _)| (node.name.as_str(), object.file_path.as_str()))
+        .collect();
+    assert_eq!(mtime, vec![("largest", "migrate.sql")]);
+}
@@ -515,3 +515,31 @@ fn test_swift_annotation_extraction() {
         "each AnnotationUsage should have an Annotates unresolved ref"
     );
 }
+
+#[test]
+fn test_swift_type_reference_refs() {
+    let source = r#"class Service {
+    var repo: Repo
+    func find(id: UserId, limit: Int) -> [Account] {
+        return []
+    }
+}
+"#;
+    let extractor = SwiftExtractor;
+    let result = extractor.extract("Service.swift", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    let names = |kind: EdgeKind| -> Vec<String> {
+        result
+            .unresolved_refs
+            .iter()
+            .filter(|r| r.reference_kind == kind)
+            .map(|r| r.reference_name.clone())
+            .collect()
+    };
+    let receives = names(EdgeKind::Receives);
+    let returns = names(EdgeKind::Returns);
+    let type_of = names(EdgeKind::TypeOf);
+    assert_eq!(receives, vec!["UserId"]);
+    assert_eq!(returns, vec!["Account"]);
+    assert_eq!(type_of, vec!["Repo"]);
+}
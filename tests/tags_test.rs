@@ -0,0 +1,244 @@
+use std::fs;
+
+use tempfile::TempDir;
+use tokensave::config::{get_config_path, load_config, save_config, TagsLanguageConfig};
+use tokensave::extraction::ts_provider::find_language;
+use tokensave::extraction::{LanguageExtractor, LanguageRegistry, TagsExtractor};
+use tokensave::tokensave::TokenSave;
+use tokensave::types::*;
+
+/// Starlark parses as Python; this is a trimmed `tags.scm` of the kind
+/// grammars ship.
+const TAGS: &str = r#"
+(class_definition
+  name: (identifier) @name) @definition.class
+
+(function_definition
+  name: (identifier) @name
+  body: (block . (expression_statement (string) @doc)?)) @definition.function
+
+(call
+  function: [
+    (identifier) @name
+    (attribute attribute: (identifier) @name)
+  ]) @reference.call
+"#;
+
+const BUILD: &str = r#"def cc_rule(name, srcs):
+    """Declares a C library."""
+    native.cc_library(name = name, srcs = srcs)
+
+class Toolchain:
+    def resolve(self):
+        return cc_rule("tc", [])
+
+cc_rule("main", glob(["*.c"]))
+"#;
+
+fn starlark() -> TagsExtractor {
+    TagsExtractor::new(
+        "Starlark",
+        &[".star".to_string(), "bzl".to_string()],
+        find_language("python").unwrap(),
+        TAGS,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_definitions_nest_under_enclosing_definitions() {
+    let extractor = starlark();
+    assert_eq!(extractor.extensions(), &["star", "bzl"]);
+    assert_eq!(extractor.language_name(), "Starlark");
+
+    let result = extractor.extract("rules/defs.star", BUILD);
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    let find = |name: &str| result.nodes.iter().find(|n| n.name == name).unwrap();
+
+    let cc_rule = find("cc_rule");
+    assert_eq!(cc_rule.kind, NodeKind::Function);
+    assert_eq!(cc_rule.qualified_name, "rules/defs.star::cc_rule");
+    assert_eq!(cc_rule.start_line, 0);
+    assert_eq!(cc_rule.end_line, 2);
+    assert_eq!(
+        cc_rule.signature.as_deref(),
+        Some("def cc_rule(name, srcs):")
+    );
+    assert_eq!(
+        cc_rule.docstring.as_deref(),
+        Some(r#""""Declares a C library.""""#)
+    );
+
+    let toolchain = find("Toolchain");
+    assert_eq!(toolchain.kind, NodeKind::Class);
+    let resolve = find("resolve");
+    assert_eq!(
+        resolve.qualified_name,
+        "rules/defs.star::Toolchain::resolve"
+    );
+    assert!(result.edges.iter().any(|e| e.kind == EdgeKind::Contains
+        && e.source == toolchain.id
+        && e.target == resolve.id));
+    assert_eq!(result.nodes.len(), 4, "file plus three definitions");
+}
+
+#[test]
+fn test_references_come_from_innermost_definition() {
+    let result = starlark().extract("BUILD.star", BUILD);
+    let id_of = |name: &str| {
+        result
+            .nodes
+            .iter()
+            .find(|n| n.name == name)
+            .unwrap()
+            .id
+            .clone()
+    };
+    let refs: Vec<(&str, &str, u32)> = result
+        .unresolved_refs
+        .iter()
+        .map(|r| {
+            assert_eq!(r.reference_kind, EdgeKind::Calls);
+            (r.from_node_id.as_str(), r.reference_name.as_str(), r.line)
+        })
+        .collect();
+    let cc_rule = id_of("cc_rule");
+    let resolve = id_of("resolve");
+    let file = id_of("BUILD.star");
+    assert_eq!(
+        refs,
+        vec![
+            (cc_rule.as_str(), "cc_library", 2),
+            (resolve.as_str(), "cc_rule", 6),
+            (file.as_str(), "cc_rule", 8),
+            (file.as_str(), "glob", 8),
+        ]
+    );
+}
+
+#[test]
+fn test_invalid_languages_are_rejected() {
+    let python = || find_language("python").unwrap();
+    let error = |query: &str| match TagsExtractor::new("Bad", &[], python(), query) {
+        Ok(_) => panic!("accepted query {query}"),
+        Err(e) => e,
+    };
+    let err = error("(no_such_node) @name");
+    assert!(err.to_string().contains("invalid tags query"), "{err}");
+    let err = error("(identifier) @name");
+    assert!(err.to_string().contains("no @definition"), "{err}");
+
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("tags.scm"), TAGS).unwrap();
+    let config = TagsLanguageConfig {
+        name: "jsonnet".to_string(),
+        extensions: vec!["jsonnet".to_string()],
+        library: Some("grammars/jsonnet.so".to_string()),
+        symbol: None,
+        grammar: None,
+        tags: "tags.scm".to_string(),
+    };
+    let Err(err) = TagsExtractor::from_config(dir.path(), &config) else {
+        panic!("loaded a grammar library that does not exist");
+    };
+    assert!(
+        err.to_string()
+            .contains("cannot load grammar for 'jsonnet'"),
+        "{err}"
+    );
+
+    let mut registry = LanguageRegistry::new();
+    let errors = registry.add_tags_languages(dir.path(), &[config]);
+    assert_eq!(errors.len(), 1);
+    assert!(registry.extractor_for_file("lib.jsonnet").is_none());
+}
+
+#[test]
+fn test_grammar_libraries_are_left_to_workers() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("tags.scm"), TAGS).unwrap();
+    let language =
+        |name: &str, ext: &str, library: Option<&str>, grammar: Option<&str>| TagsLanguageConfig {
+            name: name.to_string(),
+            extensions: vec![ext.to_string()],
+            library: library.map(str::to_string),
+            symbol: None,
+            grammar: grammar.map(str::to_string),
+            tags: "tags.scm".to_string(),
+        };
+    let languages = [
+        language("jsonnet", ".jsonnet", Some("grammars/jsonnet.so"), None),
+        language("Starlark", "star", None, Some("python")),
+    ];
+
+    // The library does not exist, but only a worker would notice.
+    let mut registry = LanguageRegistry::new();
+    let errors = registry.declare_tags_languages(dir.path(), &languages);
+    assert!(errors.is_empty(), "{errors:?}");
+    assert!(registry.is_worker_file("lib.jsonnet"));
+    assert!(registry.extractor_for_file("lib.jsonnet").is_none());
+    assert!(!registry.is_worker_file("defs.star"));
+    assert!(registry.extractor_for_file("defs.star").is_some());
+}
+
+#[tokio::test]
+async fn test_project_config_languages_are_indexed() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+    fs::write(project.join("defs.star"), BUILD).unwrap();
+    fs::write(project.join("starlark-tags.scm"), TAGS).unwrap();
+    drop(TokenSave::init(project).await.unwrap());
+
+    let mut config = load_config(project).unwrap();
+    config.languages.push(TagsLanguageConfig {
+        name: "Starlark".to_string(),
+        extensions: vec!["star".to_string()],
+        library: None,
+        symbol: None,
+        grammar: Some("python".to_string()),
+        tags: "starlark-tags.scm".to_string(),
+    });
+    save_config(project, &config).unwrap();
+    assert!(fs::read_to_string(get_config_path(project))
+        .unwrap()
+        .contains("starlark-tags.scm"));
+
+    let ts = TokenSave::open(project).await.unwrap();
+    ts.index_all().await.unwrap();
+    let nodes = ts.get_nodes_by_file("defs.star").await.unwrap();
+    let cc_rule = nodes.iter().find(|n| n.name == "cc_rule").unwrap();
+    let resolve = nodes.iter().find(|n| n.name == "resolve").unwrap();
+    let callers = ts.get_callers(&cc_rule.id, 1, false, 0.0).await.unwrap();
+    assert!(
+        callers.iter().any(|(n, _)| n.id == resolve.id),
+        "{callers:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_grammar_library_files_fail_without_workers() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+    fs::write(project.join("lib.jsonnet"), "{ a: 1 }\n").unwrap();
+    fs::write(project.join("tags.scm"), TAGS).unwrap();
+    drop(TokenSave::init(project).await.unwrap());
+
+    let mut config = load_config(project).unwrap();
+    config.languages.push(TagsLanguageConfig {
+        name: "jsonnet".to_string(),
+        extensions: vec!["jsonnet".to_string()],
+        library: Some("grammars/jsonnet.so".to_string()),
+        symbol: None,
+        grammar: None,
+        tags: "tags.scm".to_string(),
+    });
+    save_config(project, &config).unwrap();
+
+    // Tests extract in-process, where grammar libraries are never loaded:
+    // indexing fails instead of leaving the files out.
+    let ts = TokenSave::open(project).await.unwrap();
+    let Err(error) = ts.index_all().await else {
+        panic!("indexing without the jsonnet grammar succeeded");
+    };
+    assert!(error.to_string().contains(".jsonnet"), "{error}");
+}
@@ -0,0 +1,223 @@
+mod common;
+
+use std::fs;
+
+use common::{named, refs};
+use tempfile::TempDir;
+use tokensave::extraction::{LanguageExtractor, TerraformExtractor};
+use tokensave::tokensave::TokenSave;
+use tokensave::types::*;
+
+const MAIN: &str = r#"terraform {
+  required_version = ">= 1.5"
+}
+
+provider "aws" {
+  region = var.region
+}
+
+/* Buckets */
+resource "aws_s3_bucket" "logs" {
+  bucket = "${local.prefix}-logs"
+  tags   = merge(local.tags, { Name = "logs" })
+}
+
+resource "aws_s3_bucket_policy" "logs" {
+  bucket = aws_s3_bucket.logs.id
+  policy = <<-EOT
+    {"Resource": "${aws_s3_bucket.logs.arn}/*"}
+  EOT
+  depends_on = [module.vpc]
+}
+
+data "aws_ami" "ubuntu" {
+  most_recent = true
+  filter {
+    name   = "name"
+    values = [var.ami_pattern]
+  }
+}
+
+module "vpc" {
+  source = "./modules/vpc"
+  cidr   = var.cidr
+}
+
+locals {
+  prefix = "app-${var.env}"
+  tags = {
+    Env = var.env
+  }
+  subnet_ids = [for s in module.vpc.subnets : s.id]
+}
+"#;
+
+const VARIABLES: &str = r#"variable "region" {
+  description = "AWS region to deploy to"
+  default     = "eu-west-1"
+}
+
+variable "env" { type = string }
+
+output "bucket_arn" {
+  value = aws_s3_bucket.logs.arn
+}
+"#;
+
+#[test]
+fn test_terraform_blocks_are_named_by_address() {
+    let result = TerraformExtractor.extract("infra/main.tf", MAIN);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    let nodes: Vec<(&str, &NodeKind)> = result
+        .nodes
+        .iter()
+        .skip(1)
+        .map(|n| (n.name.as_str(), &n.kind))
+        .collect();
+    assert_eq!(
+        nodes,
+        vec![
+            ("aws_s3_bucket.logs", &NodeKind::TfResource),
+            ("aws_s3_bucket_policy.logs", &NodeKind::TfResource),
+            ("data.aws_ami.ubuntu", &NodeKind::TfData),
+            ("module.vpc", &NodeKind::TfModule),
+            ("local.prefix", &NodeKind::TfLocal),
+            ("local.tags", &NodeKind::TfLocal),
+            ("local.subnet_ids", &NodeKind::TfLocal),
+        ]
+    );
+    let bucket = &result.nodes[1];
+    assert_eq!(bucket.qualified_name, "infra/main.tf::aws_s3_bucket.logs");
+    assert_eq!((bucket.start_line, bucket.end_line), (9, 12));
+    assert_eq!(
+        bucket.signature.as_deref(),
+        Some(r#"resource "aws_s3_bucket" "logs" {"#)
+    );
+    let tags = result
+        .nodes
+        .iter()
+        .find(|n| n.name == "local.tags")
+        .unwrap();
+    assert_eq!((tags.start_line, tags.end_line), (37, 39));
+
+    let result = TerraformExtractor.extract("infra/variables.tf", VARIABLES);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    let region = &result.nodes[1];
+    assert_eq!(region.name, "var.region");
+    assert_eq!(region.docstring.as_deref(), Some("AWS region to deploy to"));
+    assert_eq!(result.nodes[2].name, "var.env");
+    assert_eq!(result.nodes[3].kind, NodeKind::TfOutput);
+}
+
+#[test]
+fn test_terraform_references_in_expressions_and_templates() {
+    let result = TerraformExtractor.extract("infra/main.tf", MAIN);
+    assert_eq!(
+        refs(&result, "aws_s3_bucket.logs"),
+        named(EdgeKind::Uses, &["local.prefix", "local.tags"])
+    );
+    assert_eq!(
+        refs(&result, "aws_s3_bucket_policy.logs"),
+        vec![
+            ("aws_s3_bucket.logs".to_string(), EdgeKind::Uses),
+            ("aws_s3_bucket.logs".to_string(), EdgeKind::Uses),
+            ("module.vpc".to_string(), EdgeKind::DependsOn),
+        ]
+    );
+    // References in nested blocks belong to the enclosing block.
+    assert_eq!(
+        refs(&result, "data.aws_ami.ubuntu"),
+        named(EdgeKind::Uses, &["var.ami_pattern"])
+    );
+    assert_eq!(
+        refs(&result, "local.subnet_ids"),
+        named(EdgeKind::Uses, &["module.vpc"])
+    );
+    // The provider's references are the file's.
+    assert_eq!(
+        refs(&result, "infra/main.tf"),
+        named(EdgeKind::Uses, &["var.region"])
+    );
+
+    let template = result
+        .unresolved_refs
+        .iter()
+        .find(|r| r.reference_name == "aws_s3_bucket.logs" && r.line == 17)
+        .unwrap();
+    assert_eq!(template.column, 20);
+}
+
+#[test]
+fn test_terraform_syntax_error_is_reported() {
+    let result =
+        TerraformExtractor.extract("broken.tf", "resource \"a_b\" \"c\" {\n  x = \"open\n}\n");
+    assert_eq!(result.errors.len(), 1, "{:?}", result.errors);
+    assert!(result.errors[0].starts_with("broken.tf:2:"));
+    assert_eq!(result.nodes.len(), 1, "only the file node");
+}
+
+#[tokio::test]
+async fn test_terraform_references_resolve_within_their_module() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path();
+    fs::create_dir_all(project.join("infra")).unwrap();
+    fs::create_dir_all(project.join("staging")).unwrap();
+    fs::write(project.join("infra/main.tf"), MAIN).unwrap();
+    fs::write(project.join("infra/variables.tf"), VARIABLES).unwrap();
+    // Another module declaring the same variable.
+    fs::write(project.join("staging/variables.tf"), VARIABLES).unwrap();
+
+    let ts = TokenSave::init(project).await.unwrap();
+    ts.index_all().await.unwrap();
+
+    let variables = ts.get_nodes_by_file("infra/variables.tf").await.unwrap();
+    let region = variables.iter().find(|n| n.name == "var.region").unwrap();
+    let main = ts.get_nodes_by_file("infra/main.tf").await.unwrap();
+    let main_file = main.iter().find(|n| n.kind == NodeKind::File).unwrap();
+    let uses: Vec<Edge> = ts
+        .get_incoming_edges(&region.id)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|e| e.kind == EdgeKind::Uses)
+        .collect();
+    assert_eq!(uses.len(), 1, "{uses:?}");
+    assert_eq!(uses[0].source, main_file.id);
+
+    // Changing the bucket impacts the policy and the output that use it.
+    let bucket = main
+        .iter()
+        .find(|n| n.name == "aws_s3_bucket.logs")
+        .unwrap();
+    let impact = ts
+        .get_impact_radius(&bucket.id, 3, false, 0.0)
+        .await
+        .unwrap();
+    let names: Vec<&str> = impact.nodes.iter().map(|n| n.name.as_str()).collect();
+    assert!(names.contains(&"aws_s3_bucket_policy.logs"), "{names:?}");
+    assert!(names.contains(&"output.bucket_arn"), "{names:?}");
+    let staging = ts.get_nodes_by_file("staging/variables.tf").await.unwrap();
+    let staging_output = staging
+        .iter()
+        .find(|n| n.kind == NodeKind::TfOutput)
+        .unwrap();
+    assert!(ts
+        .get_outgoing_edges(&staging_output.id)
+        .await
+        .unwrap()
+        .iter()
+        .all(|e| e.kind == EdgeKind::Contains));
+
+    // A variable declared later binds the references that named it.
+    fs::write(project.join("infra/network.tf"), "variable \"cidr\" {}\n").unwrap();
+    ts.sync().await.unwrap();
+    let network = ts.get_nodes_by_file("infra/network.tf").await.unwrap();
+    let cidr = network.iter().find(|n| n.name == "var.cidr").unwrap();
+    let module = main.iter().find(|n| n.name == "module.vpc").unwrap();
+    assert!(ts
+        .get_incoming_edges(&cidr.id)
+        .await
+        .unwrap()
+        .iter()
+        .any(|e| e.kind == EdgeKind::Uses && e.source == module.id));
+}
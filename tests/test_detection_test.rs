@@ -0,0 +1,241 @@
+use tokensave::extraction::{
+    GoExtractor, JavaExtractor, LanguageExtractor, PythonExtractor, RustExtractor,
+    TypeScriptExtractor,
+};
+use tokensave::tokensave::TestScopes;
+use tokensave::types::*;
+
+/// Names of the test cases the extractor marked, in source order.
+fn test_cases(result: &ExtractionResult) -> Vec<&str> {
+    result
+        .nodes
+        .iter()
+        .filter(|n| n.kind == NodeKind::TestCase)
+        .map(|n| n.name.as_str())
+        .collect()
+}
+
+/// Names of the calls made directly from the node named `name`.
+fn calls_from<'a>(result: &'a ExtractionResult, name: &str) -> Vec<&'a str> {
+    let id = &result
+        .nodes
+        .iter()
+        .find(|n| n.name == name)
+        .expect("node not found")
+        .id;
+    result
+        .unresolved_refs
+        .iter()
+        .filter(|r| &r.from_node_id == id && r.reference_kind == EdgeKind::Calls)
+        .map(|r| r.reference_name.as_str())
+        .collect()
+}
+
+/// Extracts each `(path, source)` file and collects the test scopes of the
+/// results, returning them with all the extracted nodes.
+fn setup(files: &[(&str, &str)]) -> (TestScopes, Vec<Node>) {
+    let mut nodes = Vec::new();
+    for (path, source) in files {
+        let result = match path.rsplit('.').next() {
+            Some("rs") => RustExtractor.extract(path, source),
+            Some("go") => GoExtractor.extract(path, source),
+            Some("py") => PythonExtractor.extract(path, source),
+            Some("java") => JavaExtractor.extract(path, source),
+            _ => TypeScriptExtractor.extract(path, source),
+        };
+        assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+        nodes.extend(result.nodes);
+    }
+    (TestScopes::new(&nodes), nodes)
+}
+
+#[test]
+fn test_rust_test_attributes_and_cfg_test_module() {
+    let source = r#"pub fn add(a: i32) -> i32 { a }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn helper() -> i32 { add(1) }
+
+    #[test]
+    fn adds() {
+        assert_eq!(helper(), 1);
+    }
+
+    #[tokio::test]
+    async fn adds_async() { add(2); }
+}
+
+#[cfg(not(test))]
+mod prod {}
+"#;
+    let result = RustExtractor.extract("src/lib.rs", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    assert_eq!(test_cases(&result), vec!["tests", "adds", "adds_async"]);
+    let helper = result.nodes.iter().find(|n| n.name == "helper").unwrap();
+    assert_eq!(helper.kind, NodeKind::Function);
+    let prod = result.nodes.iter().find(|n| n.name == "prod").unwrap();
+    assert_eq!(prod.kind, NodeKind::Module);
+}
+
+#[test]
+fn test_go_testing_signatures() {
+    let source = r#"package a
+
+import "testing"
+
+func TestAdd(t *testing.T) {
+	Add(1)
+}
+
+func Testify(t *testing.T) {}
+
+func BenchmarkAdd(b *testing.B) {}
+
+func TestHelper(x int) {}
+"#;
+    let result = GoExtractor.extract("a_test.go", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    assert_eq!(test_cases(&result), vec!["TestAdd", "BenchmarkAdd"]);
+    assert!(calls_from(&result, "TestAdd").contains(&"Add"));
+}
+
+#[test]
+fn test_python_pytest_and_unittest() {
+    let source = r#"import unittest
+
+def test_add():
+    add(1)
+
+def testament():
+    pass
+
+class TestMath:
+    def setup_method(self):
+        pass
+    def test_sub(self):
+        sub(1)
+
+class Checks(unittest.TestCase):
+    def testMul(self):
+        mul(1)
+
+class Service:
+    def test_connection(self):
+        pass
+
+@pytest.mark.slow
+def test_slow():
+    pass
+"#;
+    let result = PythonExtractor.extract("app/checks.py", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    assert_eq!(
+        test_cases(&result),
+        vec!["test_add", "test_sub", "testMul", "test_slow"]
+    );
+    let conn = result
+        .nodes
+        .iter()
+        .find(|n| n.name == "test_connection")
+        .unwrap();
+    assert_eq!(conn.kind, NodeKind::Method);
+}
+
+#[test]
+fn test_java_junit_annotations() {
+    let source = r#"class MathTest {
+  @Test
+  void adds() { Math.add(1); }
+  @org.junit.jupiter.params.ParameterizedTest
+  void many(int x) {}
+  @Override
+  public String toString() { return ""; }
+}
+"#;
+    let result = JavaExtractor.extract("src/MathTest.java", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    assert_eq!(test_cases(&result), vec!["adds", "many"]);
+}
+
+#[test]
+fn test_typescript_describe_and_it_blocks() {
+    let source = r#"import { add } from './math';
+
+describe('math', () => {
+  beforeEach(() => { reset(); });
+  it('adds', async () => {
+    expect(add(1, 2)).toBe(3);
+  });
+  it.each([[1, 2]])('adds %i', (a, b) => { add(a, b); });
+  describe.skip(`nested`, function () {
+    test('inner', () => inner());
+  });
+});
+
+setup('x');
+"#;
+    let result = TypeScriptExtractor.extract("src/math.spec.ts", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    assert_eq!(
+        test_cases(&result),
+        vec!["math", "adds", "adds %i", "nested", "inner"]
+    );
+    assert!(calls_from(&result, "math").contains(&"reset"));
+    assert!(calls_from(&result, "adds").contains(&"add"));
+    assert!(calls_from(&result, "inner").contains(&"inner"));
+}
+
+#[test]
+fn test_test_only_files_are_test_code_throughout() {
+    let (scopes, nodes) = setup(&[
+        ("tests/common/mod.rs", "pub fn start_server() {}\n"),
+        (
+            "src/lib.rs",
+            "pub fn serve() {}\n\n#[cfg(test)]\nmod tests {\n    fn fixture() {}\n}\n",
+        ),
+        ("server_test.go", "package server\n\nfunc newClient() {}\n"),
+        ("server.go", "package server\n\nfunc Listen() {}\n"),
+        ("api.test.ts", "function renderPage() {}\n"),
+        ("api.spec.js", "function mockFetch() {}\n"),
+        ("api.ts", "export function fetchPage() {}\n"),
+        ("conftest.py", "def client():\n    pass\n"),
+        ("app.py", "def create_app():\n    pass\n"),
+        ("tests/factories.py", "def make_user():\n    pass\n"),
+        ("test/fixtures.js", "function loadFixture() {}\n"),
+        (
+            "app/src/test/java/AppTest.java",
+            "class AppTest {\n    void stubRepo() {}\n}\n",
+        ),
+        (
+            "app/src/main/java/App.java",
+            "class App {\n    void run() {}\n}\n",
+        ),
+    ]);
+    let in_tests = |name: &str| {
+        let node = nodes.iter().find(|n| n.name == name).unwrap();
+        scopes.contains(node)
+    };
+
+    for helper in [
+        "start_server",
+        "fixture",
+        "newClient",
+        "renderPage",
+        "mockFetch",
+        "client",
+        "make_user",
+        "loadFixture",
+        "stubRepo",
+    ] {
+        assert!(in_tests(helper), "{helper} is test code");
+    }
+    for code in ["serve", "Listen", "fetchPage", "create_app", "run"] {
+        assert!(!in_tests(code), "{code} is not test code");
+    }
+    assert!(scopes.is_test_file("server_test.go"));
+    assert!(scopes.is_test_file("src/lib.rs"));
+    assert!(!scopes.is_test_file("api.ts"));
+}
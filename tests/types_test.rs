@@ -185,6 +185,7 @@ fn edge_serde_roundtrip() {
         target: "function:bbbb".to_string(),
         kind: EdgeKind::Calls,
         line: Some(15),
+        provenance: None,
     };
 
     let json = serde_json::to_string(&edge).expect("failed to serialize Edge");
@@ -273,6 +274,33 @@ fn test_c_cpp_csharp_pascal_kotlin_dart_node_kinds_roundtrip() {
         (NodeKind::PascalProgram, "pascal_program"),
         (NodeKind::PascalUnit, "pascal_unit"),
         (NodeKind::PascalRecord, "pascal_record"),
+        (NodeKind::ManifestPackage, "manifest_package"),
+        (NodeKind::ExternalPackage, "external_package"),
+        (NodeKind::ExternalSymbol, "external_symbol"),
+        (NodeKind::DomainSymbol, "domain_symbol"),
+        (NodeKind::Component, "component"),
+        (NodeKind::ConfigKey, "config_key"),
+        (NodeKind::WorkflowJob, "workflow_job"),
+        (NodeKind::WorkflowStep, "workflow_step"),
+        (NodeKind::ComposeService, "compose_service"),
+        (NodeKind::K8sResource, "k8s_resource"),
+        (NodeKind::TfResource, "tf_resource"),
+        (NodeKind::TfData, "tf_data"),
+        (NodeKind::TfModule, "tf_module"),
+        (NodeKind::TfVariable, "tf_variable"),
+        (NodeKind::TfOutput, "tf_output"),
+        (NodeKind::TfLocal, "tf_local"),
+        (NodeKind::GqlType, "gql_type"),
+        (NodeKind::GqlField, "gql_field"),
+        (NodeKind::GqlOperation, "gql_operation"),
+        (NodeKind::GqlFragment, "gql_fragment"),
+        (NodeKind::BuildTarget, "build_target"),
+        (NodeKind::Route, "route"),
+        (NodeKind::SqlTable, "sql_table"),
+        (NodeKind::SqlView, "sql_view"),
+        (NodeKind::SqlColumn, "sql_column"),
+        (NodeKind::SqlIndex, "sql_index"),
+        (NodeKind::TestCase, "test_case"),
         (NodeKind::Property, "property"),
         (NodeKind::DataClass, "data_class"),
         (NodeKind::SealedClass, "sealed_class"),
@@ -296,6 +324,11 @@ fn test_new_edge_kinds_roundtrip() {
         (EdgeKind::Extends, "extends"),
         (EdgeKind::Annotates, "annotates"),
         (EdgeKind::Receives, "receives"),
+        (EdgeKind::DependsOn, "depends_on"),
+        (EdgeKind::Registers, "registers"),
+        (EdgeKind::Dispatches, "dispatches"),
+        (EdgeKind::Writes, "writes"),
+        (EdgeKind::Virtual, "virtual"),
     ];
     for (kind, expected_str) in kinds {
         assert_eq!(kind.as_str(), expected_str);
@@ -330,12 +363,14 @@ fn extraction_result_sanitize_no_empty_names() {
         target: "function:aaa".to_string(),
         kind: EdgeKind::Calls,
         line: None,
+        provenance: None,
     };
     let edge_involving_bad = Edge {
         source: "function:bbb".to_string(),
         target: "function:aaa".to_string(),
         kind: EdgeKind::Calls,
         line: None,
+        provenance: None,
     };
     let unresolved_bad = UnresolvedRef {
         from_node_id: "function:bbb".to_string(),
@@ -344,12 +379,17 @@ fn extraction_result_sanitize_no_empty_names() {
         line: 1,
         column: 0,
         file_path: "src/lib.rs".to_string(),
+        receiver_type: None,
+        abi: None,
+        http_method: None,
+        sql: false,
     };
 
     let mut result = ExtractionResult {
         nodes: vec![good, bad],
         edges: vec![edge_good_to_good.clone(), edge_involving_bad],
         unresolved_refs: vec![unresolved_bad],
+        signatures: Vec::new(),
         errors: vec![],
         duration_ms: 0,
     };
@@ -381,6 +421,7 @@ fn extraction_result_sanitize_noop_when_clean() {
         nodes: vec![node],
         edges: vec![],
         unresolved_refs: vec![],
+        signatures: Vec::new(),
         errors: vec![],
         duration_ms: 0,
     };
@@ -389,6 +430,58 @@ fn extraction_result_sanitize_noop_when_clean() {
     assert!(result.errors.is_empty());
 }
 
+#[test]
+fn extraction_result_stable_ids_ignore_lines_and_number_overloads() {
+    let node_at = |id: &str, name: &str, line: u32| Node {
+        start_line: line,
+        ..make_node(id, name)
+    };
+    // `parse` is overloaded; the second overload is listed first.
+    let mut result = ExtractionResult {
+        nodes: vec![
+            node_at("function:l20", "parse", 20),
+            node_at("function:l3", "main", 3),
+            node_at("function:l10", "parse", 10),
+        ],
+        edges: vec![Edge {
+            source: "function:l3".to_string(),
+            target: "function:l10".to_string(),
+            kind: EdgeKind::Calls,
+            line: Some(4),
+            provenance: None,
+        }],
+        unresolved_refs: vec![UnresolvedRef {
+            from_node_id: "function:l20".to_string(),
+            reference_name: "helper".to_string(),
+            reference_kind: EdgeKind::Calls,
+            line: 21,
+            column: 0,
+            file_path: "src/lib.rs".to_string(),
+            receiver_type: None,
+            abi: None,
+            http_method: None,
+            sql: false,
+        }],
+        signatures: vec![Signature {
+            node_id: "function:l10".to_string(),
+            params: vec![],
+            return_type: None,
+        }],
+        errors: vec![],
+        duration_ms: 0,
+    };
+    result.assign_stable_ids();
+
+    let id =
+        |name: &str, index: u32| generate_node_id("src/lib.rs", &NodeKind::Function, name, index);
+    let ids: Vec<&str> = result.nodes.iter().map(|n| n.id.as_str()).collect();
+    assert_eq!(ids, vec![id("parse", 1), id("main", 0), id("parse", 0)]);
+    assert_eq!(result.edges[0].source, id("main", 0));
+    assert_eq!(result.edges[0].target, id("parse", 0));
+    assert_eq!(result.unresolved_refs[0].from_node_id, id("parse", 1));
+    assert_eq!(result.signatures[0].node_id, id("parse", 0));
+}
+
 #[test]
 fn traversal_direction_serde_roundtrip() {
     let cases = [
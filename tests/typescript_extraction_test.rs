@@ -719,3 +719,61 @@ enum Direction {
     assert_eq!(enums.len(), 1);
     assert_eq!(enums[0].visibility, Visibility::Private); // not exported
 }
+
+#[test]
+fn test_typescript_type_reference_refs() {
+    let source = r#"class Service {
+  repo: Repo<User>;
+  find(id: UserId, limit: number): Promise<Account> {
+    return this.repo.get(id);
+  }
+}
+"#;
+    let extractor = TypeScriptExtractor;
+    let result = extractor.extract("test.ts", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    let names = |kind: EdgeKind| -> Vec<String> {
+        result
+            .unresolved_refs
+            .iter()
+            .filter(|r| r.reference_kind == kind)
+            .map(|r| r.reference_name.clone())
+            .collect()
+    };
+    let receives = names(EdgeKind::Receives);
+    let returns = names(EdgeKind::Returns);
+    let type_of = names(EdgeKind::TypeOf);
+    assert_eq!(receives, vec!["UserId"]);
+    assert_eq!(returns, vec!["Promise", "Account"]);
+    assert_eq!(type_of, vec!["Repo", "User"]);
+}
+
+#[test]
+fn test_typescript_call_receiver_types() {
+    let source = r#"class Server {
+  private db: Database;
+  run(client: Client) {
+    const conn: Conn = open();
+    this.db.getNode(1);
+    client.send(2);
+    conn.close();
+    this.flush();
+  }
+}
+"#;
+    let extractor = TypeScriptExtractor;
+    let result = extractor.extract("test.ts", source);
+    assert!(result.errors.is_empty(), "errors: {:?}", result.errors);
+    let receiver = |name: &str| -> Option<String> {
+        result
+            .unresolved_refs
+            .iter()
+            .find(|r| r.reference_kind == EdgeKind::Calls && r.reference_name == name)
+            .and_then(|r| r.receiver_type.clone())
+    };
+    assert_eq!(receiver("this.db.getNode").as_deref(), Some("Database"));
+    assert_eq!(receiver("client.send").as_deref(), Some("Client"));
+    assert_eq!(receiver("conn.close").as_deref(), Some("Conn"));
+    assert_eq!(receiver("this.flush").as_deref(), Some("Server"));
+    assert_eq!(receiver("open").as_deref(), None);
+}